@@ -1,29 +1,883 @@
 use std::env;
+use std::iter::Peekable;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum Command {
+    Serve(ServeArgs),
+    Daemon(DaemonCommand),
+    Export(ExportArgs),
+    /// Checks an `--format audit` export's hash chain; `-` reads stdin.
+    ExportVerify {
+        path: PathBuf,
+    },
+    Ccusage(CcusageArgs),
+    Report(ReportArgs),
+    Summary(SummaryArgs),
+    Compare(CompareArgs),
+    Sessions(SessionsArgs),
+    Session(SessionArgs),
+    Top(TopArgs),
+    Tui(TuiArgs),
+    Budget(BudgetArgs),
+    Pricing(PricingCommand),
+    Settings(SettingsCommand),
+    Homes(HomesCommand),
+    Ingest(IngestArgs),
+    Statusline(StatuslineArgs),
+    Token(TokenCommand),
+    Doctor,
+    Prune(PruneArgs),
+    Compact,
+    Db(DbCommand),
+    Import(ImportArgs),
+}
 
 #[derive(Debug, Default)]
-pub struct CliArgs {
+pub struct ServeArgs {
     pub port: Option<u16>,
+    pub bind: Option<String>,
+    pub tls_cert: Option<PathBuf>,
+    pub tls_key: Option<PathBuf>,
     pub no_open: bool,
+    /// Serve only `/api/*`, without the dashboard.
+    pub no_ui: bool,
+    pub require_token: bool,
+    pub statsd: Option<String>,
+    /// Start even if another instance already serves the configured port.
+    pub force: bool,
+    /// Names other devices open the dashboard under on a non-loopback bind.
+    pub allowed_hosts: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum TokenCommand {
+    Create { label: Option<String> },
+    List,
+    Revoke { id: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Parquet,
+    Csv,
+    Json,
+    Jsonl,
+    /// Hash-chained JSON Lines of events.
+    Audit,
+}
+
+#[derive(Debug)]
+pub enum DaemonCommand {
+    /// Serves the API without opening a browser and ingests on a schedule.
+    Run(ServeArgs),
+    /// Writes a launchd agent (macOS) or systemd user unit (Linux) that runs
+    /// `codex-tracker daemon` at login.
+    Install {
+        /// Prints the service file instead of writing it.
+        print: bool,
+    },
+}
+
+#[derive(Debug)]
+pub struct ExportArgs {
+    pub format: ExportFormat,
+    pub range: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    /// Per-day totals instead of individual events.
+    pub daily: bool,
+    /// `-` writes to stdout.
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+pub struct CcusageArgs {
+    pub report: String,
+    pub range: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub home: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct ReportArgs {
+    pub period: Option<String>,
+    pub format: Option<String>,
+    pub home: Option<String>,
+    /// Writes a self-contained HTML snapshot here instead of printing.
+    pub html: Option<PathBuf>,
+}
+
+#[derive(Debug, Default)]
+pub struct SummaryArgs {
+    pub range: Option<String>,
+    pub home: Option<String>,
+    /// Per-user totals across every home instead of one summary.
+    pub by_user: bool,
+    /// Ingest the active home before reading totals.
+    pub refresh: bool,
+}
+
+/// Without `to`, compares with the window of the same length just before
+/// `range`.
+#[derive(Debug, Default)]
+pub struct CompareArgs {
+    pub range: Option<String>,
+    pub to: Option<String>,
+    pub home: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct SessionsArgs {
+    pub range: Option<String>,
+    pub home: Option<String>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug)]
+pub struct SessionArgs {
+    pub session_id: String,
+    pub home: Option<i64>,
+}
+
+#[derive(Debug)]
+pub enum HomesCommand {
+    List,
+    Add {
+        path: String,
+        label: Option<String>,
+    },
+    /// Deletes the home's ingested data too, so it needs `--yes`.
+    Remove {
+        id: i64,
+        yes: bool,
+    },
+    Activate {
+        id: i64,
+    },
+    Rename {
+        id: i64,
+        label: String,
+    },
+    Archive {
+        id: i64,
+    },
+    Unarchive {
+        id: i64,
+    },
+    /// Sets the user a home's usage belongs to; `None` clears it.
+    User {
+        id: i64,
+        user: Option<String>,
+    },
+}
+
+/// `--home` and `--path` are mutually exclusive; neither means the active home.
+#[derive(Debug, Default)]
+pub struct IngestArgs {
+    pub home: Option<i64>,
+    pub path: Option<PathBuf>,
+    /// Ingests into a scratch copy of the database and discards it.
+    pub dry_run: bool,
+}
+
+/// `before` is a `YYYY-MM-DD` date or an RFC 3339 timestamp.
+#[derive(Debug, Default)]
+pub struct PruneArgs {
+    pub before: String,
+    pub home: Option<i64>,
+}
+
+/// `path` is `-` for stdin.
+#[derive(Debug, Default)]
+pub struct ImportArgs {
+    pub from: String,
+    pub path: PathBuf,
+    pub home: Option<i64>,
+}
+
+#[derive(Debug)]
+pub enum DbCommand {
+    /// Imports another tracker database's usage into this one, optionally
+    /// as a teammate's.
+    Merge { path: PathBuf, user: Option<String> },
+    /// Re-reads a range from the session logs and diffs it with the database.
+    Audit(AuditArgs),
+}
+
+#[derive(Debug, Default)]
+pub struct AuditArgs {
+    pub range: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub home: Option<i64>,
+}
+
+#[derive(Debug)]
+pub enum PricingCommand {
+    List,
+    Set(PricingSetArgs),
+    /// `-` reads from stdin.
+    Import {
+        path: PathBuf,
+    },
+    /// Writes to stdout without `--out` (or with `--out -`).
+    Export {
+        out: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug)]
+pub enum SettingsCommand {
+    /// Writes to stdout without `--out` (or with `--out -`).
+    Export { out: Option<PathBuf> },
+    /// `-` reads from stdin.
+    Import { path: PathBuf },
+}
+
+/// Prices are USD per 1M tokens; omitted ones keep the current rule's value.
+#[derive(Debug)]
+pub struct PricingSetArgs {
+    pub model_pattern: String,
+    pub input: Option<f64>,
+    pub cached: Option<f64>,
+    pub output: Option<f64>,
+    pub from: Option<String>,
+    /// Limits the rule to one provider; rules without one price any.
+    pub provider: Option<String>,
+    /// Makes the rule the default pricing for models no other rule matches.
+    pub fallback: bool,
+}
+
+/// `budget status` options.
+#[derive(Debug, Default)]
+pub struct BudgetArgs {
+    /// Overrides the configured `monthly_budget_usd`.
+    pub budget: Option<f64>,
+    /// Percent of the budget that counts as exceeded (default 100).
+    pub threshold: Option<f64>,
+    pub home: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatuslineFormat {
+    #[default]
+    Plain,
+    /// ANSI colours for a starship `custom` module.
+    Starship,
+    /// `#[fg=...]` styles for `status-right`.
+    Tmux,
+    /// JSON for a waybar `custom` module with `return-type: json`.
+    Waybar,
+}
+
+#[derive(Debug, Default)]
+pub struct StatuslineArgs {
+    pub format: StatuslineFormat,
+}
+
+#[derive(Debug, Default)]
+pub struct TopArgs {
+    pub interval: Option<u64>,
+    pub once: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct TuiArgs {
+    pub refresh: Option<u64>,
+}
+
+/// A parsed command line: the subcommand plus the global `--json` flag, which
+/// may appear anywhere.
+#[derive(Debug)]
+pub struct Cli {
+    pub command: Command,
+    /// Print machine-readable JSON instead of text meant for people.
+    pub json: bool,
+}
+
+pub fn parse_args() -> Result<Cli, String> {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let json = args.iter().any(|arg| arg == "--json");
+    args.retain(|arg| arg != "--json");
+    let command = parse_command(args.into_iter().peekable())?;
+    Ok(Cli { command, json })
+}
+
+fn parse_command(mut args: Peekable<impl Iterator<Item = String>>) -> Result<Command, String> {
+    match args.peek().map(String::as_str) {
+        Some("daemon") => {
+            args.next();
+            parse_daemon_args(args).map(Command::Daemon)
+        }
+        Some("export") => {
+            args.next();
+            if args.peek().map(String::as_str) == Some("verify") {
+                args.next();
+                return parse_export_verify_args(args);
+            }
+            parse_export_args(args).map(Command::Export)
+        }
+        Some("ccusage") => {
+            args.next();
+            parse_ccusage_args(args).map(Command::Ccusage)
+        }
+        Some("report") => {
+            args.next();
+            parse_report_args(args).map(Command::Report)
+        }
+        Some("summary") => {
+            args.next();
+            parse_summary_args(args).map(Command::Summary)
+        }
+        Some("compare") => {
+            args.next();
+            parse_compare_args(args).map(Command::Compare)
+        }
+        Some("sessions") => {
+            args.next();
+            parse_sessions_args(args).map(Command::Sessions)
+        }
+        Some("session") => {
+            args.next();
+            parse_session_args(args).map(Command::Session)
+        }
+        Some("top") => {
+            args.next();
+            parse_top_args(args).map(Command::Top)
+        }
+        Some("tui") => {
+            args.next();
+            parse_tui_args(args).map(Command::Tui)
+        }
+        Some("budget") => {
+            args.next();
+            parse_budget_args(args).map(Command::Budget)
+        }
+        Some("homes") => {
+            args.next();
+            parse_homes_args(args).map(Command::Homes)
+        }
+        Some("ingest") => {
+            args.next();
+            parse_ingest_args(args).map(Command::Ingest)
+        }
+        Some("statusline") => {
+            args.next();
+            parse_statusline_args(args).map(Command::Statusline)
+        }
+        Some("pricing") => {
+            args.next();
+            parse_pricing_args(args).map(Command::Pricing)
+        }
+        Some("settings") => {
+            args.next();
+            parse_settings_args(args).map(Command::Settings)
+        }
+        Some("token") => {
+            args.next();
+            parse_token_args(args).map(Command::Token)
+        }
+        Some("doctor") => {
+            args.next();
+            parse_no_args(args, Command::Doctor)
+        }
+        Some("prune") => {
+            args.next();
+            parse_prune_args(args).map(Command::Prune)
+        }
+        Some("compact") => {
+            args.next();
+            parse_no_args(args, Command::Compact)
+        }
+        Some("import") => {
+            args.next();
+            parse_import_args(args).map(Command::Import)
+        }
+        Some("db") => {
+            args.next();
+            parse_db_args(args).map(Command::Db)
+        }
+        _ => parse_serve_args(args).map(Command::Serve),
+    }
 }
 
-pub fn parse_args() -> Result<CliArgs, String> {
-    let mut args = env::args().skip(1);
-    let mut parsed = CliArgs::default();
+fn parse_serve_args(mut args: impl Iterator<Item = String>) -> Result<ServeArgs, String> {
+    let mut parsed = ServeArgs::default();
 
     while let Some(arg) = args.next() {
         match arg.as_str() {
             "--port" => {
-                let value = args
-                    .next()
-                    .ok_or_else(|| "missing value for --port".to_string())?;
+                let value = next_value(&mut args, "--port")?;
                 let port = value
                     .parse::<u16>()
                     .map_err(|_| format!("invalid port value: {value}"))?;
                 parsed.port = Some(port);
             }
+            "--bind" => parsed.bind = Some(next_value(&mut args, "--bind")?),
+            "--allowed-host" => parsed
+                .allowed_hosts
+                .push(next_value(&mut args, "--allowed-host")?),
+            "--tls-cert" => {
+                parsed.tls_cert = Some(PathBuf::from(next_value(&mut args, "--tls-cert")?));
+            }
+            "--tls-key" => {
+                parsed.tls_key = Some(PathBuf::from(next_value(&mut args, "--tls-key")?));
+            }
             "--no-open" => {
                 parsed.no_open = true;
             }
+            "--no-ui" | "--headless" => parsed.no_ui = true,
+            "--require-token" => {
+                parsed.require_token = true;
+            }
+            "--statsd" => parsed.statsd = Some(next_value(&mut args, "--statsd")?),
+            "--force" => parsed.force = true,
+            "--help" | "-h" => {
+                print_help();
+                std::process::exit(0);
+            }
+            _ => {
+                return Err(format!("unknown argument: {arg}"));
+            }
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn parse_daemon_args(
+    mut args: Peekable<impl Iterator<Item = String>>,
+) -> Result<DaemonCommand, String> {
+    if args.peek().map(String::as_str) != Some("install") {
+        return parse_serve_args(args).map(DaemonCommand::Run);
+    }
+    args.next();
+    let mut print = false;
+    for arg in args {
+        match arg.as_str() {
+            "--print" => print = true,
+            "--help" | "-h" => {
+                print_help();
+                std::process::exit(0);
+            }
+            _ => return Err(format!("unknown argument: {arg}")),
+        }
+    }
+    Ok(DaemonCommand::Install { print })
+}
+
+fn parse_export_args(mut args: impl Iterator<Item = String>) -> Result<ExportArgs, String> {
+    let mut format = None;
+    let mut parsed = ExportArgs {
+        format: ExportFormat::Parquet,
+        range: None,
+        start: None,
+        end: None,
+        daily: false,
+        out: None,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                let value = next_value(&mut args, "--format")?;
+                format = Some(match value.as_str() {
+                    "parquet" => ExportFormat::Parquet,
+                    "csv" => ExportFormat::Csv,
+                    "json" => ExportFormat::Json,
+                    "jsonl" => ExportFormat::Jsonl,
+                    "audit" => ExportFormat::Audit,
+                    _ => return Err(format!("unsupported export format: {value}")),
+                });
+            }
+            "--range" => parsed.range = Some(next_value(&mut args, "--range")?),
+            "--start" => parsed.start = Some(next_value(&mut args, "--start")?),
+            "--end" => parsed.end = Some(next_value(&mut args, "--end")?),
+            "--daily" => parsed.daily = true,
+            "--out" => parsed.out = Some(PathBuf::from(next_value(&mut args, "--out")?)),
+            "--help" | "-h" => {
+                print_help();
+                std::process::exit(0);
+            }
+            _ => {
+                return Err(format!("unknown argument: {arg}"));
+            }
+        }
+    }
+
+    parsed.format = format.ok_or_else(|| "missing --format for export".to_string())?;
+    if parsed.daily && matches!(parsed.format, ExportFormat::Parquet | ExportFormat::Audit) {
+        return Err("--daily is only available for csv, json, and jsonl exports".to_string());
+    }
+    Ok(parsed)
+}
+
+fn parse_export_verify_args(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let path = match args.next() {
+        Some(arg) if arg == "--help" || arg == "-h" => {
+            print_help();
+            std::process::exit(0);
+        }
+        Some(arg) if arg == "-" || !arg.starts_with('-') => PathBuf::from(arg),
+        _ => return Err("missing file for export verify (or - for stdin)".to_string()),
+    };
+    if let Some(extra) = args.next() {
+        return Err(format!("unknown argument: {extra}"));
+    }
+    Ok(Command::ExportVerify { path })
+}
+
+fn parse_ccusage_args(mut args: impl Iterator<Item = String>) -> Result<CcusageArgs, String> {
+    let report = args
+        .next()
+        .ok_or_else(|| "missing ccusage report (daily, session, blocks)".to_string())?;
+    if report == "--help" || report == "-h" {
+        print_help();
+        std::process::exit(0);
+    }
+    let mut parsed = CcusageArgs {
+        report,
+        range: None,
+        start: None,
+        end: None,
+        home: None,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--range" => parsed.range = Some(next_value(&mut args, "--range")?),
+            "--start" => parsed.start = Some(next_value(&mut args, "--start")?),
+            "--end" => parsed.end = Some(next_value(&mut args, "--end")?),
+            "--home" => parsed.home = Some(next_value(&mut args, "--home")?),
+            // Output is always JSON; accepted (like the global `--json`) so
+            // `ccusage <report> -j` invocations work unchanged.
+            "-j" => {}
+            _ => {
+                return Err(format!("unknown argument: {arg}"));
+            }
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn parse_report_args(mut args: impl Iterator<Item = String>) -> Result<ReportArgs, String> {
+    let mut parsed = ReportArgs::default();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--period" => parsed.period = Some(next_value(&mut args, "--period")?),
+            "--format" => parsed.format = Some(next_value(&mut args, "--format")?),
+            "--home" => parsed.home = Some(next_value(&mut args, "--home")?),
+            "--html" => parsed.html = Some(PathBuf::from(next_value(&mut args, "--html")?)),
+            "--help" | "-h" => {
+                print_help();
+                std::process::exit(0);
+            }
+            _ => {
+                return Err(format!("unknown argument: {arg}"));
+            }
+        }
+    }
+    if parsed.html.is_some() && parsed.format.is_some() {
+        return Err("--html and --format cannot be combined".to_string());
+    }
+
+    Ok(parsed)
+}
+
+fn parse_summary_args(mut args: impl Iterator<Item = String>) -> Result<SummaryArgs, String> {
+    let mut parsed = SummaryArgs::default();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--range" => parsed.range = Some(expand_range(next_value(&mut args, "--range")?)),
+            "--home" => parsed.home = Some(next_value(&mut args, "--home")?),
+            "--by-user" => parsed.by_user = true,
+            "--refresh" => parsed.refresh = true,
+            "--help" | "-h" => {
+                print_help();
+                std::process::exit(0);
+            }
+            _ => {
+                return Err(format!("unknown argument: {arg}"));
+            }
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn parse_compare_args(mut args: impl Iterator<Item = String>) -> Result<CompareArgs, String> {
+    let mut parsed = CompareArgs::default();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--range" => parsed.range = Some(expand_range(next_value(&mut args, "--range")?)),
+            "--to" => parsed.to = Some(expand_range(next_value(&mut args, "--to")?)),
+            "--home" => parsed.home = Some(next_value(&mut args, "--home")?),
+            "--help" | "-h" => {
+                print_help();
+                std::process::exit(0);
+            }
+            _ => {
+                return Err(format!("unknown argument: {arg}"));
+            }
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn parse_sessions_args(mut args: impl Iterator<Item = String>) -> Result<SessionsArgs, String> {
+    let mut parsed = SessionsArgs::default();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--range" => parsed.range = Some(expand_range(next_value(&mut args, "--range")?)),
+            "--home" => parsed.home = Some(next_value(&mut args, "--home")?),
+            "--limit" => {
+                let value = next_value(&mut args, "--limit")?;
+                let limit = value
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|limit| *limit > 0)
+                    .ok_or_else(|| format!("invalid limit: {value}"))?;
+                parsed.limit = Some(limit);
+            }
+            "--help" | "-h" => {
+                print_help();
+                std::process::exit(0);
+            }
+            _ => {
+                return Err(format!("unknown argument: {arg}"));
+            }
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn parse_session_args(mut args: impl Iterator<Item = String>) -> Result<SessionArgs, String> {
+    let mut session_id = None;
+    let mut home = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--home" => {
+                let value = next_value(&mut args, "--home")?;
+                let id = value
+                    .parse::<i64>()
+                    .map_err(|_| format!("invalid home id: {value}"))?;
+                home = Some(id);
+            }
+            "--help" | "-h" => {
+                print_help();
+                std::process::exit(0);
+            }
+            _ if session_id.is_none() && !arg.starts_with('-') => session_id = Some(arg),
+            _ => {
+                return Err(format!("unknown argument: {arg}"));
+            }
+        }
+    }
+
+    Ok(SessionArgs {
+        session_id: session_id.ok_or_else(|| "missing session id".to_string())?,
+        home,
+    })
+}
+
+fn parse_budget_args(mut args: impl Iterator<Item = String>) -> Result<BudgetArgs, String> {
+    match args.next().as_deref() {
+        Some("status") => {}
+        Some("--help" | "-h") => {
+            print_help();
+            std::process::exit(0);
+        }
+        Some(action) => return Err(format!("unknown budget action: {action}")),
+        None => return Err("missing budget action (status)".to_string()),
+    }
+    let mut parsed = BudgetArgs::default();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--budget" => {
+                let value = next_value(&mut args, "--budget")?;
+                let budget = value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|budget| *budget > 0.0)
+                    .ok_or_else(|| format!("invalid budget: {value}"))?;
+                parsed.budget = Some(budget);
+            }
+            "--threshold" => {
+                let value = next_value(&mut args, "--threshold")?;
+                let threshold = value
+                    .trim_end_matches('%')
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|threshold| *threshold > 0.0)
+                    .ok_or_else(|| format!("invalid threshold: {value}"))?;
+                parsed.threshold = Some(threshold);
+            }
+            "--home" => parsed.home = Some(next_value(&mut args, "--home")?),
+            "--help" | "-h" => {
+                print_help();
+                std::process::exit(0);
+            }
+            _ => {
+                return Err(format!("unknown argument: {arg}"));
+            }
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn parse_homes_args(mut args: impl Iterator<Item = String>) -> Result<HomesCommand, String> {
+    let action = args.next().ok_or_else(|| {
+        "missing homes action (list, add, remove, activate, rename, archive, unarchive, user)"
+            .to_string()
+    })?;
+    let command = match action.as_str() {
+        "list" => HomesCommand::List,
+        "add" => {
+            let path = args
+                .next()
+                .filter(|value| !value.starts_with('-'))
+                .ok_or_else(|| "missing path for homes add".to_string())?;
+            let mut label = None;
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--label" => label = Some(next_value(&mut args, "--label")?),
+                    _ => return Err(format!("unknown argument: {arg}")),
+                }
+            }
+            HomesCommand::Add { path, label }
+        }
+        "remove" => {
+            let id = parse_home_id(args.next())?;
+            let mut yes = false;
+            for arg in args.by_ref() {
+                match arg.as_str() {
+                    "--yes" | "-y" => yes = true,
+                    _ => return Err(format!("unknown argument: {arg}")),
+                }
+            }
+            HomesCommand::Remove { id, yes }
+        }
+        "activate" => HomesCommand::Activate {
+            id: parse_home_id(args.next())?,
+        },
+        "rename" => {
+            let id = parse_home_id(args.next())?;
+            let label = args
+                .next()
+                .ok_or_else(|| "missing label for homes rename".to_string())?;
+            HomesCommand::Rename { id, label }
+        }
+        "archive" => HomesCommand::Archive {
+            id: parse_home_id(args.next())?,
+        },
+        "unarchive" => HomesCommand::Unarchive {
+            id: parse_home_id(args.next())?,
+        },
+        "user" => HomesCommand::User {
+            id: parse_home_id(args.next())?,
+            user: args.next(),
+        },
+        "--help" | "-h" => {
+            print_help();
+            std::process::exit(0);
+        }
+        _ => return Err(format!("unknown homes action: {action}")),
+    };
+    if let Some(extra) = args.next() {
+        return Err(format!("unknown argument: {extra}"));
+    }
+    Ok(command)
+}
+
+fn parse_ingest_args(mut args: impl Iterator<Item = String>) -> Result<IngestArgs, String> {
+    let mut parsed = IngestArgs::default();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--home" => parsed.home = Some(parse_home_id(args.next())?),
+            "--path" => parsed.path = Some(PathBuf::from(next_value(&mut args, "--path")?)),
+            "--dry-run" => parsed.dry_run = true,
+            "--help" | "-h" => {
+                print_help();
+                std::process::exit(0);
+            }
+            _ => {
+                return Err(format!("unknown argument: {arg}"));
+            }
+        }
+    }
+
+    if parsed.home.is_some() && parsed.path.is_some() {
+        return Err("--home and --path cannot be combined".to_string());
+    }
+    Ok(parsed)
+}
+
+fn parse_prune_args(mut args: impl Iterator<Item = String>) -> Result<PruneArgs, String> {
+    let mut parsed = PruneArgs::default();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--before" => parsed.before = next_value(&mut args, "--before")?,
+            "--home" => parsed.home = Some(parse_home_id(args.next())?),
+            "--help" | "-h" => {
+                print_help();
+                std::process::exit(0);
+            }
+            _ => {
+                return Err(format!("unknown argument: {arg}"));
+            }
+        }
+    }
+
+    if parsed.before.is_empty() {
+        return Err("prune needs --before <date>".to_string());
+    }
+    Ok(parsed)
+}
+
+/// For commands without options.
+fn parse_no_args(
+    mut args: impl Iterator<Item = String>,
+    command: Command,
+) -> Result<Command, String> {
+    match args.next().as_deref() {
+        None => Ok(command),
+        Some("--help" | "-h") => {
+            print_help();
+            std::process::exit(0);
+        }
+        Some(arg) => Err(format!("unknown argument: {arg}")),
+    }
+}
+
+fn parse_statusline_args(mut args: impl Iterator<Item = String>) -> Result<StatuslineArgs, String> {
+    let mut parsed = StatuslineArgs::default();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                let value = next_value(&mut args, "--format")?;
+                parsed.format = match value.as_str() {
+                    "plain" => StatuslineFormat::Plain,
+                    "starship" => StatuslineFormat::Starship,
+                    "tmux" => StatuslineFormat::Tmux,
+                    "waybar" => StatuslineFormat::Waybar,
+                    _ => return Err(format!("unsupported statusline format: {value}")),
+                };
+            }
             "--help" | "-h" => {
                 print_help();
                 std::process::exit(0);
@@ -37,10 +891,334 @@ pub fn parse_args() -> Result<CliArgs, String> {
     Ok(parsed)
 }
 
+fn parse_home_id(value: Option<String>) -> Result<i64, String> {
+    let value = value.ok_or_else(|| "missing home id".to_string())?;
+    value
+        .parse::<i64>()
+        .map_err(|_| format!("invalid home id: {value}"))
+}
+
+fn parse_pricing_args(mut args: impl Iterator<Item = String>) -> Result<PricingCommand, String> {
+    let action = args
+        .next()
+        .ok_or_else(|| "missing pricing action (list, set, import, export)".to_string())?;
+    let command = match action.as_str() {
+        "list" => PricingCommand::List,
+        "set" => {
+            let model_pattern = args
+                .next()
+                .filter(|value| !value.starts_with('-'))
+                .ok_or_else(|| "missing model pattern for pricing set".to_string())?;
+            let mut parsed = PricingSetArgs {
+                model_pattern,
+                input: None,
+                cached: None,
+                output: None,
+                from: None,
+                provider: None,
+                fallback: false,
+            };
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--input" => parsed.input = Some(parse_price(&mut args, "--input")?),
+                    "--cached" => parsed.cached = Some(parse_price(&mut args, "--cached")?),
+                    "--output" => parsed.output = Some(parse_price(&mut args, "--output")?),
+                    "--from" => parsed.from = Some(next_value(&mut args, "--from")?),
+                    "--provider" => parsed.provider = Some(next_value(&mut args, "--provider")?),
+                    "--fallback" => parsed.fallback = true,
+                    _ => return Err(format!("unknown argument: {arg}")),
+                }
+            }
+            PricingCommand::Set(parsed)
+        }
+        "import" => {
+            let path = args
+                .next()
+                .ok_or_else(|| "missing file for pricing import".to_string())?;
+            PricingCommand::Import {
+                path: PathBuf::from(path),
+            }
+        }
+        "export" => {
+            let mut out = None;
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--out" => out = Some(PathBuf::from(next_value(&mut args, "--out")?)),
+                    _ => return Err(format!("unknown argument: {arg}")),
+                }
+            }
+            PricingCommand::Export { out }
+        }
+        "--help" | "-h" => {
+            print_help();
+            std::process::exit(0);
+        }
+        _ => return Err(format!("unknown pricing action: {action}")),
+    };
+    if let Some(extra) = args.next() {
+        return Err(format!("unknown argument: {extra}"));
+    }
+    Ok(command)
+}
+
+fn parse_settings_args(mut args: impl Iterator<Item = String>) -> Result<SettingsCommand, String> {
+    let action = args
+        .next()
+        .ok_or_else(|| "missing settings action (export, import)".to_string())?;
+    let command = match action.as_str() {
+        "export" => {
+            let mut out = None;
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--out" => out = Some(PathBuf::from(next_value(&mut args, "--out")?)),
+                    _ => return Err(format!("unknown argument: {arg}")),
+                }
+            }
+            SettingsCommand::Export { out }
+        }
+        "import" => {
+            let path = args
+                .next()
+                .ok_or_else(|| "missing file for settings import".to_string())?;
+            SettingsCommand::Import {
+                path: PathBuf::from(path),
+            }
+        }
+        "--help" | "-h" => {
+            print_help();
+            std::process::exit(0);
+        }
+        _ => return Err(format!("unknown settings action: {action}")),
+    };
+    if let Some(extra) = args.next() {
+        return Err(format!("unknown argument: {extra}"));
+    }
+    Ok(command)
+}
+
+fn parse_import_args(mut args: impl Iterator<Item = String>) -> Result<ImportArgs, String> {
+    let mut parsed = ImportArgs::default();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--from" => parsed.from = next_value(&mut args, "--from")?,
+            "--home" => parsed.home = Some(parse_home_id(args.next())?),
+            "--help" | "-h" => {
+                print_help();
+                std::process::exit(0);
+            }
+            "-" => parsed.path = PathBuf::from(arg),
+            _ if arg.starts_with('-') => {
+                return Err(format!("unknown argument: {arg}"));
+            }
+            _ if parsed.path.as_os_str().is_empty() => parsed.path = PathBuf::from(arg),
+            _ => return Err(format!("unknown argument: {arg}")),
+        }
+    }
+
+    if parsed.from.is_empty() {
+        return Err("import needs --from <tool> (ccusage)".to_string());
+    }
+    if parsed.path.as_os_str().is_empty() {
+        return Err("missing file for import (or - for stdin)".to_string());
+    }
+    Ok(parsed)
+}
+
+fn parse_db_args(mut args: impl Iterator<Item = String>) -> Result<DbCommand, String> {
+    let action = args
+        .next()
+        .ok_or_else(|| "missing db action (merge, audit)".to_string())?;
+    let command = match action.as_str() {
+        "audit" => return parse_audit_args(args).map(DbCommand::Audit),
+        "merge" => {
+            let path = args
+                .next()
+                .filter(|value| !value.starts_with('-'))
+                .ok_or_else(|| "missing database file for db merge".to_string())?;
+            let mut user = None;
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--user" => user = Some(next_value(&mut args, "--user")?),
+                    _ => return Err(format!("unknown argument: {arg}")),
+                }
+            }
+            DbCommand::Merge {
+                path: PathBuf::from(path),
+                user,
+            }
+        }
+        "--help" | "-h" => {
+            print_help();
+            std::process::exit(0);
+        }
+        _ => return Err(format!("unknown db action: {action}")),
+    };
+    if let Some(extra) = args.next() {
+        return Err(format!("unknown argument: {extra}"));
+    }
+    Ok(command)
+}
+
+fn parse_audit_args(mut args: impl Iterator<Item = String>) -> Result<AuditArgs, String> {
+    let mut parsed = AuditArgs::default();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--range" => parsed.range = Some(expand_range(next_value(&mut args, "--range")?)),
+            "--start" => parsed.start = Some(next_value(&mut args, "--start")?),
+            "--end" => parsed.end = Some(next_value(&mut args, "--end")?),
+            "--home" => parsed.home = Some(parse_home_id(args.next())?),
+            "--help" | "-h" => {
+                print_help();
+                std::process::exit(0);
+            }
+            _ => {
+                return Err(format!("unknown argument: {arg}"));
+            }
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn parse_price(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<f64, String> {
+    let value = next_value(args, flag)?;
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|price| price.is_finite() && *price >= 0.0)
+        .ok_or_else(|| format!("invalid price for {flag}: {value}"))
+}
+
+fn parse_top_args(mut args: impl Iterator<Item = String>) -> Result<TopArgs, String> {
+    let mut parsed = TopArgs::default();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--interval" => {
+                let value = next_value(&mut args, "--interval")?;
+                let secs = value
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|secs| *secs > 0)
+                    .ok_or_else(|| format!("invalid interval: {value}"))?;
+                parsed.interval = Some(secs);
+            }
+            "--once" => parsed.once = true,
+            "--help" | "-h" => {
+                print_help();
+                std::process::exit(0);
+            }
+            _ => {
+                return Err(format!("unknown argument: {arg}"));
+            }
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn parse_tui_args(mut args: impl Iterator<Item = String>) -> Result<TuiArgs, String> {
+    let mut parsed = TuiArgs::default();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--refresh" => {
+                let value = next_value(&mut args, "--refresh")?;
+                let secs = value
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|secs| *secs > 0)
+                    .ok_or_else(|| format!("invalid refresh interval: {value}"))?;
+                parsed.refresh = Some(secs);
+            }
+            "--help" | "-h" => {
+                print_help();
+                std::process::exit(0);
+            }
+            _ => {
+                return Err(format!("unknown argument: {arg}"));
+            }
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Accepts the short forms (`7d`, `14d`, `month`, `all`) alongside the API's
+/// range names.
+pub fn expand_range(value: String) -> String {
+    match value.as_str() {
+        "7d" => "last7days".to_string(),
+        "14d" => "last14days".to_string(),
+        "month" => "thismonth".to_string(),
+        "all" => "alltime".to_string(),
+        _ => value,
+    }
+}
+
+fn parse_token_args(mut args: impl Iterator<Item = String>) -> Result<TokenCommand, String> {
+    let action = args
+        .next()
+        .ok_or_else(|| "missing token action (create, list, revoke)".to_string())?;
+    let command = match action.as_str() {
+        "create" => {
+            let mut label = None;
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--label" => label = Some(next_value(&mut args, "--label")?),
+                    _ => return Err(format!("unknown argument: {arg}")),
+                }
+            }
+            TokenCommand::Create { label }
+        }
+        "list" => TokenCommand::List,
+        "revoke" => {
+            let id = args
+                .next()
+                .ok_or_else(|| "missing token id for revoke".to_string())?;
+            TokenCommand::Revoke { id }
+        }
+        "--help" | "-h" => {
+            print_help();
+            std::process::exit(0);
+        }
+        _ => return Err(format!("unknown token action: {action}")),
+    };
+    if let Some(extra) = args.next() {
+        return Err(format!("unknown argument: {extra}"));
+    }
+    Ok(command)
+}
+
+fn next_value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("missing value for {flag}"))
+}
+
 pub fn print_help() {
     println!(
         "Codex Tracker CLI\n\n\
-Usage:\n  codex-tracker [--port <port>] [--no-open]\n\n\
-Options:\n  --port <port>  Override the configured port for this run only\n  --no-open      Do not open the browser automatically\n  -h, --help     Show this help message\n"
+Usage:\n  codex-tracker [--port <port>] [--bind <addr>] [--allowed-host <name>]... [--tls-cert <pem> --tls-key <pem>] [--no-open] [--no-ui] [--require-token] [--statsd <host:port>] [--force]\n  codex-tracker daemon [--port <port>] [--bind <addr>] [--allowed-host <name>]... [--tls-cert <pem> --tls-key <pem>] [--no-ui] [--require-token] [--statsd <host:port>] [--force]\n  codex-tracker daemon install [--print]\n  codex-tracker export --format parquet|csv|json|jsonl|audit [--daily] [--range <range>] [--start <rfc3339>] [--end <rfc3339>] [--out <file|->]\n  codex-tracker export verify <file|->\n  codex-tracker ccusage daily|session|blocks [--range <range>] [--start <rfc3339>] [--end <rfc3339>] [--home <id|all>]\n  codex-tracker report [--period day|week|month] [--format markdown|html|json] [--home <id|all>]\n  codex-tracker report --html <file> [--period day|week|month] [--home <id|all>]\n  codex-tracker summary [--range today|7d|14d|month|all] [--home <id|all>] [--by-user] [--refresh]\n  codex-tracker compare [--range <range>] [--to <range>] [--home <id|all>]\n  codex-tracker sessions [--range today|7d|14d|month|all] [--home <id|all>] [--limit <n>]\n  codex-tracker session <id> [--home <id>]\n  codex-tracker top [--interval <secs>] [--once]\n  codex-tracker statusline [--format plain|starship|tmux|waybar]\n  codex-tracker tui [--refresh <secs>]\n  codex-tracker budget status [--threshold <pct>] [--budget <usd>] [--home <id|all>]\n  codex-tracker homes list | homes add <path> [--label <label>] | homes remove <id> --yes\n  codex-tracker homes activate <id> | homes rename <id> <label>\n  codex-tracker homes archive <id> | homes unarchive <id> | homes user <id> [<name>]\n  codex-tracker ingest [--home <id> | --path <dir>] [--dry-run]\n  codex-tracker pricing list | pricing set <model> [--input <usd>] [--cached <usd>] [--output <usd>] [--from <rfc3339>] [--provider <name>] [--fallback]\n  codex-tracker pricing import <file|-> | pricing export [--out <file>]\n  codex-tracker settings export [--out <file>] | settings import <file|->\n  codex-tracker token create [--label <label>] | token list | token revoke <id>\n  codex-tracker doctor\n  codex-tracker prune --before <date> [--home <id>] | compact\n  codex-tracker db merge <other.sqlite> [--user <name>]\n  codex-tracker db audit [--range <range>] [--start <rfc3339>] [--end <rfc3339>] [--home <id>]\n  codex-tracker import --from ccusage <file|-> [--home <id>]\n\n\
+Options:\n  --port <port>    Override the configured port for this run only\n  --bind <addr>    Address to listen on (default 127.0.0.1; 0.0.0.0 for all interfaces)\n  --allowed-host <name>\n                   Host name or IP other devices open the dashboard under\n                   (repeatable; the bind address is allowed already; ignored\n                   on a loopback bind)\n  --tls-cert <pem> Serve HTTPS with this certificate chain (needs --tls-key)\n  --tls-key <pem>  Private key for --tls-cert\n  --no-open        Do not open the browser automatically\n  --no-ui          Serve only /api/* (headless); other paths return 404, so POST\n                   requests need an API token (alias: --headless)\n  --require-token  Require an API token for every request\n  --statsd <addr>  Push usage gauges to a StatsD agent (host:port)\n  --force          Start another instance even if one already serves the port\n  --json           Print machine-readable JSON instead of text (any command)\n  -h, --help       Show this help message\n\n\
+Daemon options:\n  Takes the server options above, never opens a browser, and re-ingests every\n  ingest_interval_secs (config, default: 60).\n  install          Write a launchd agent (macOS) or systemd user unit (Linux) that\n                   starts the daemon at login; --print writes it to stdout instead\n\n\
+Export options:\n  --format <fmt>   Output format (parquet, csv, json, jsonl, audit)\n  --daily          Export per-day totals instead of events (not for parquet or audit)\n  --range <range>  today, last7days, last14days, thismonth, alltime (default: alltime)\n  --start <time>   Range start (overrides --range)\n  --end <time>     Range end (defaults to now)\n  --out <file>     Output path, or - for stdout (default: codex-tracker-events.<fmt>\n                   or codex-tracker-daily.<fmt>)\n  audit writes events as hash-chained JSON Lines and prints the head hash;\n  export verify re-hashes such a file and exits 1 at the first broken record.\n\n\
+Report options:\n  --period <p>     day (last 24 hours, default), week (last 7 days), or month (last 30 days)\n  --format <fmt>   markdown (default), html, or json\n  --home <id|all>  Report on another home, or all homes (default: active home)\n  --html <file>    Write a self-contained HTML page with charts (period defaults to month)\n\n\
+Summary options:\n  --range <range>  today (default), 7d, 14d, month, or all\n  --home <id|all>  Summarize another home, or all homes (default: active home)\n  --by-user        Totals per user (see homes user) across all homes\n  --refresh        Ingest the active home's new log lines first (reads only by default)\n\n\
+Compare options:\n  --range <range>  today (default), 7d, 14d, month, prevmonth, or all\n  --to <range>     Range to compare with (default: the window of the same length\n                   just before --range)\n  --home <id|all>  Compare another home, or all homes (default: active home)\n\n\
+Sessions options:\n  --range <range>  today (default), 7d, 14d, month, or all\n  --home <id|all>  List another home's sessions, or all homes (default: active home)\n  --limit <n>      Number of sessions, by total tokens (default: 20)\n\n\
+Top options:\n  --interval <s>   Seconds between refreshes (default: 2)\n  --once           Print a single frame and exit\n  With --json, prints one JSON object per refresh (JSON Lines).\n\n\
+Statusline options:\n  --format <fmt>   plain (default), starship (ANSI colours), tmux (#[fg=] styles),\n                   or waybar (JSON); reads the database without ingesting\n\n\
+TUI options:\n  --refresh <s>    Seconds between data refreshes (default: 10)\n\n\
+Budget options:\n  --threshold <p>  Percent of the budget that counts as exceeded (default: 100)\n  --budget <usd>   Use this monthly budget instead of the configured one\n  --home <id|all>  Check another home, or all homes (default: active home)\n  Exits 1 when month-to-date spend reaches the threshold, 2 when no budget is set.\n\n\
+Ingest options:\n  --home <id>      Ingest another home (default: active home)\n  --path <dir>     Ingest this codex home directory, registering it if new\n  --dry-run        Report what would be ingested without saving anything\n\n\
+Pricing options (prices are USD per 1M tokens):\n  --input <usd>    Input price (required for a new model)\n  --cached <usd>   Cached input price (default: a tenth of --input)\n  --output <usd>   Output price (required for a new model)\n  --from <time>    Start a new rule at this time instead of editing the current one\n  --provider <name> Only price events from this provider (openai, azure, openrouter, ...)\n  --fallback       Default pricing: also price models no other rule matches; their\n                   cost is reported as estimated\n  set and import save to the database and the pricing defaults file, then recompute costs.\n\n\
+Settings:\n  export writes pricing, the budget, notification channels, webhooks, homes, and\n  desktop preferences as one JSON bundle; import applies it on another machine,\n  merging homes and webhooks by path and URL. Bundles include webhook secrets.\n\n\
+Doctor:\n  Checks the database, migrations, codex homes, session logs, ingest cursors,\n  pricing coverage, and the configured port without changing anything.\n  Exits 1 when a check fails.\n\n\
+Prune and compact:\n  --before <date>  Delete sessions that ended before this date (YYYY-MM-DD, local\n                   midnight) or RFC 3339 time, plus older limit snapshots\n  --home <id>      Prune one home (default: every home)\n  compact          Reclaim the freed space (VACUUM) and refresh query statistics (ANALYZE)\n\n\
+Import options:\n  --from <tool>    Where the file comes from; ccusage reads `ccusage daily --json`\n                   or `ccusage session --json` output\n  --home <id>      Import into another home (default: active home)\n  Days that already have usage from the Codex logs are skipped.\n\n\
+Db merge:\n  Imports usage, messages, and limit snapshots from another codex-tracker database\n  (e.g. copied from another machine). Homes are matched by path and added when\n  missing; rows already present are skipped, so merging twice is harmless.\n  --user <name>    The database is a teammate's: label its homes with <name>\n                   (tracked as <name>:<path>) for summary --by-user\n\n\
+Db audit options:\n  --range <range>  today (default), 7d, 14d, month, or all\n  --start <time>   Range start (overrides --range)\n  --end <time>     Range end (defaults to now)\n  --home <id>      Audit another home (default: active home)\n  Re-reads the range's usage from the session logs and compares events and tokens\n  per log with the database, without changing anything. Exits 1 on divergence.\n"
     );
 }
@@ -0,0 +1,157 @@
+use std::io;
+
+use app_api::{AppContext, RangeRequest};
+use chrono::{DateTime, Datelike, Local, Months, TimeZone};
+use serde::Serialize;
+use tracker_core::HomeScope;
+
+use crate::args::BudgetArgs;
+use crate::format::{print_json, usd};
+
+/// Exit code when spend is at or over the threshold.
+pub const EXIT_EXCEEDED: i32 = 1;
+/// Exit code when no budget is configured or passed with `--budget`.
+pub const EXIT_NO_BUDGET: i32 = 2;
+
+const DEFAULT_THRESHOLD_PCT: f64 = 100.0;
+
+#[derive(Debug, PartialEq, Serialize)]
+struct BudgetStatus {
+    monthly_budget_usd: f64,
+    month_cost_usd: f64,
+    used_percent: f64,
+    remaining_usd: f64,
+    /// Month-to-date spend extrapolated to the end of the month.
+    projected_usd: f64,
+    threshold_percent: f64,
+    exceeded: bool,
+}
+
+impl BudgetStatus {
+    fn new(budget: f64, spent: f64, threshold: f64, now: DateTime<Local>) -> Self {
+        let used_percent = if budget > 0.0 {
+            spent / budget * 100.0
+        } else {
+            0.0
+        };
+        Self {
+            monthly_budget_usd: budget,
+            month_cost_usd: spent,
+            used_percent,
+            remaining_usd: (budget - spent).max(0.0),
+            projected_usd: spent / month_elapsed_fraction(now),
+            threshold_percent: threshold,
+            exceeded: used_percent >= threshold,
+        }
+    }
+}
+
+/// Returns the process exit code: 0 under the threshold, [`EXIT_EXCEEDED`]
+/// at or over it, [`EXIT_NO_BUDGET`] without a budget.
+pub fn status(context: &AppContext, args: BudgetArgs, json: bool) -> Result<i32, io::Error> {
+    if let Err(err) = context.app_state.services.ingest.run() {
+        tracing::warn!(error = %err, "failed to refresh data before budget status");
+    }
+
+    let budget = match args.budget {
+        Some(budget) => Some(budget),
+        None => {
+            app_api::settings_get(context)
+                .map_err(io::Error::other)?
+                .monthly_budget_usd
+        }
+    };
+    let Some(budget) = budget else {
+        eprintln!(
+            "No monthly budget configured; set monthly_budget_usd in the settings or pass --budget <usd>."
+        );
+        return Ok(EXIT_NO_BUDGET);
+    };
+
+    let home_id = args
+        .home
+        .map(|value| value.parse::<HomeScope>())
+        .transpose()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err.to_string()))?;
+    let summary = app_api::summary(
+        context,
+        RangeRequest {
+            range: Some("thismonth".to_string()),
+            start: None,
+            end: None,
+            home_id,
+        },
+    )
+    .map_err(io::Error::other)?;
+    let status = BudgetStatus::new(
+        budget,
+        summary.total_cost_usd.unwrap_or(0.0),
+        args.threshold.unwrap_or(DEFAULT_THRESHOLD_PCT),
+        Local::now(),
+    );
+
+    if json {
+        print_json(&status)?;
+    } else {
+        println!(
+            "Monthly budget   {:>10}",
+            usd(Some(status.monthly_budget_usd))
+        );
+        println!(
+            "Spent            {:>10}  ({:.1}%)",
+            usd(Some(status.month_cost_usd)),
+            status.used_percent
+        );
+        println!("Remaining        {:>10}", usd(Some(status.remaining_usd)));
+        println!("Projected        {:>10}", usd(Some(status.projected_usd)));
+        if status.exceeded {
+            println!("Over the {:.0}% threshold.", status.threshold_percent);
+        }
+    }
+    Ok(if status.exceeded { EXIT_EXCEEDED } else { 0 })
+}
+
+/// Share of the current month that has passed, never zero.
+fn month_elapsed_fraction(now: DateTime<Local>) -> f64 {
+    let first = now.date_naive().with_day(1).unwrap_or(now.date_naive());
+    let next = first.checked_add_months(Months::new(1)).unwrap_or(first);
+    let start = Local
+        .from_local_datetime(&first.and_hms_opt(0, 0, 0).unwrap_or_default())
+        .earliest();
+    let end = Local
+        .from_local_datetime(&next.and_hms_opt(0, 0, 0).unwrap_or_default())
+        .earliest();
+    match (start, end) {
+        (Some(start), Some(end)) if end > start => {
+            let elapsed = (now - start).num_seconds() as f64;
+            let total = (end - start).num_seconds() as f64;
+            (elapsed / total).clamp(1.0 / total, 1.0)
+        }
+        _ => 1.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_compares_spend_with_threshold() {
+        let now = Local
+            .with_ymd_and_hms(2026, 4, 16, 0, 0, 0)
+            .earliest()
+            .expect("now");
+
+        let under = BudgetStatus::new(100.0, 40.0, 100.0, now);
+        assert!(!under.exceeded);
+        assert_eq!(under.remaining_usd, 60.0);
+        assert!((under.projected_usd - 80.0).abs() < 1.0);
+
+        let warned = BudgetStatus::new(100.0, 85.0, 80.0, now);
+        assert!(warned.exceeded);
+
+        let over = BudgetStatus::new(100.0, 120.0, 100.0, now);
+        assert!(over.exceeded);
+        assert_eq!(over.remaining_usd, 0.0);
+    }
+}
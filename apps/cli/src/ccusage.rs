@@ -0,0 +1,32 @@
+use std::io;
+
+use app_api::{AppContext, CcusageRequest};
+use tracker_core::HomeScope;
+
+use crate::args::CcusageArgs;
+use crate::format::print_json;
+
+/// Always prints JSON, so it needs no `--json` switch.
+pub fn run(context: &AppContext, args: CcusageArgs) -> Result<(), io::Error> {
+    if let Err(err) = context.app_state.services.ingest.run() {
+        tracing::warn!(error = %err, "failed to refresh data before ccusage report");
+    }
+
+    let home_id = args
+        .home
+        .map(|value| value.parse::<HomeScope>())
+        .transpose()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err.to_string()))?;
+    let report = app_api::ccusage(
+        context,
+        CcusageRequest {
+            report: args.report,
+            range: args.range,
+            start: args.start,
+            end: args.end,
+            home_id,
+        },
+    )
+    .map_err(io::Error::other)?;
+    print_json(&report)
+}
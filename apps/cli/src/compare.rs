@@ -0,0 +1,146 @@
+use std::fmt::Write as _;
+use std::io;
+
+use app_api::{AppContext, CompareRequest};
+use tracker_app::report::format_delta;
+use tracker_core::{HomeScope, RangeComparison};
+
+use crate::args::CompareArgs;
+use crate::format::{print_json, thousands, usd};
+
+pub fn run(context: &AppContext, args: CompareArgs, json: bool) -> Result<(), io::Error> {
+    if let Err(err) = context.app_state.services.ingest.run() {
+        tracing::warn!(error = %err, "failed to refresh data before compare");
+    }
+
+    let home_id = args
+        .home
+        .map(|value| value.parse::<HomeScope>())
+        .transpose()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err.to_string()))?;
+    let range = args.range.unwrap_or_else(|| "today".to_string());
+    let comparison = app_api::compare(
+        context,
+        CompareRequest {
+            range: Some(range.clone()),
+            start: None,
+            end: None,
+            to_range: args.to.clone(),
+            to_start: None,
+            to_end: None,
+            home_id,
+        },
+    )
+    .map_err(io::Error::other)?;
+
+    if json {
+        return print_json(&comparison);
+    }
+    let baseline = args.to.unwrap_or_else(|| "the window before".to_string());
+    print!("{}", render(&comparison, &range, &baseline));
+    Ok(())
+}
+
+fn render(comparison: &RangeComparison, range: &str, baseline: &str) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "Codex usage: {range} vs {baseline}");
+    let _ = writeln!(
+        out,
+        "{:<24}  {:>15}  {:>15}  {:>6}  {:>10}  {:>10}  {:>6}",
+        "MODEL", "TOKENS", "BEFORE", "CHANGE", "COST", "BEFORE", "CHANGE"
+    );
+    let mut row = |model: &str, tokens: u64, before: u64, cost: Option<f64>, cost_before| {
+        let _ = writeln!(
+            out,
+            "{:<24}  {:>15}  {:>15}  {:>6}  {:>10}  {:>10}  {:>6}",
+            model,
+            thousands(tokens),
+            thousands(before),
+            format_delta(tokens as f64, before as f64),
+            usd(cost),
+            usd(cost_before),
+            cost_delta(cost, cost_before)
+        );
+    };
+    for model in &comparison.models {
+        row(
+            &model.model,
+            model.total_tokens,
+            model.baseline_total_tokens,
+            model.total_cost_usd,
+            model.baseline_total_cost_usd,
+        );
+    }
+    row(
+        "Total",
+        comparison.current.total_tokens,
+        comparison.baseline.total_tokens,
+        comparison.current.total_cost_usd,
+        comparison.baseline.total_cost_usd,
+    );
+    out
+}
+
+/// A model missing from one range has no cost there; treat that as zero,
+/// but show `-` when a side used the model without a pricing rule.
+fn cost_delta(cost: Option<f64>, before: Option<f64>) -> String {
+    match (cost, before) {
+        (Some(cost), Some(before)) => format_delta(cost, before),
+        (Some(cost), None) => format_delta(cost, 0.0),
+        (None, Some(before)) => format_delta(0.0, before),
+        (None, None) => "-".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracker_core::{ModelComparison, TimeRange, UsageSummary};
+
+    #[test]
+    fn render_lines_up_both_ranges_with_deltas() {
+        let range = || TimeRange {
+            start: "2026-01-01T00:00:00.000Z".to_string(),
+            end: "2026-02-01T00:00:00.000Z".to_string(),
+        };
+        let comparison = RangeComparison {
+            range: range(),
+            baseline_range: range(),
+            current: UsageSummary {
+                total_tokens: 1_500_000,
+                total_cost_usd: Some(3.0),
+                ..UsageSummary::default()
+            },
+            baseline: UsageSummary {
+                total_tokens: 1_000_000,
+                total_cost_usd: Some(2.0),
+                ..UsageSummary::default()
+            },
+            models: vec![
+                ModelComparison {
+                    model: "gpt-5".to_string(),
+                    total_tokens: 1_500_000,
+                    baseline_total_tokens: 500_000,
+                    total_cost_usd: Some(3.0),
+                    baseline_total_cost_usd: Some(1.0),
+                },
+                ModelComparison {
+                    model: "gpt-4.1".to_string(),
+                    total_tokens: 0,
+                    baseline_total_tokens: 500_000,
+                    total_cost_usd: None,
+                    baseline_total_cost_usd: Some(1.0),
+                },
+            ],
+        };
+        let out = render(&comparison, "thismonth", "prevmonth");
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "Codex usage: thismonth vs prevmonth");
+        assert!(lines[2].starts_with("gpt-5 "));
+        assert!(lines[2].contains("1,500,000"));
+        assert!(lines[2].contains("+200%"));
+        assert!(lines[3].contains("-100%"));
+        assert!(lines[4].starts_with("Total"));
+        assert!(lines[4].contains("+50%"));
+    }
+}
@@ -1,23 +1,194 @@
 use std::fs;
+use std::net::IpAddr;
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
+use tracker_app::statsd::{DEFAULT_STATSD_INTERVAL_SECS, DEFAULT_STATSD_PREFIX};
+
+use crate::args::expand_range;
 
 const CONFIG_DIR_NAME: &str = "codex-tracker";
 const CONFIG_FILE_NAME: &str = "config.toml";
 const DEFAULT_PORT: u16 = 3845;
+const DEFAULT_BIND_ADDRESS: &str = "127.0.0.1";
+const DEFAULT_INGEST_INTERVAL_SECS: u64 = 60;
+const DEFAULT_RANGE: &str = "today";
+const RANGES: [&str; 5] = ["today", "last7days", "last14days", "thismonth", "alltime"];
 
+/// Unknown keys are rejected so a typo does not silently fall back to the
+/// default.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct CliConfig {
+    #[serde(default = "default_port")]
     pub port: u16,
+    /// IP address to listen on; use 0.0.0.0 to serve other devices.
+    #[serde(default = "default_bind_address")]
+    pub bind_address: String,
+    /// Host names or IPs other devices open the dashboard under when
+    /// `bind_address` is not loopback; the bind address itself is allowed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_hosts: Vec<String>,
+    /// PEM certificate chain; serving over HTTPS requires `tls_key` too.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_cert: Option<PathBuf>,
+    /// PEM private key matching `tls_cert`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_key: Option<PathBuf>,
+    /// Require an API token (see `codex-tracker token`) for every request.
+    #[serde(default)]
+    pub require_token: bool,
+    /// `host:port` of a StatsD / DogStatsD agent to push usage gauges to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub statsd_address: Option<String>,
+    #[serde(default = "default_statsd_prefix")]
+    pub statsd_prefix: String,
+    #[serde(default = "default_statsd_interval_secs")]
+    pub statsd_interval_secs: u64,
+    /// DogStatsD tags (`key:value`) added to every gauge.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub statsd_tags: Vec<String>,
+    /// Origins allowed to embed the dashboard in a frame, e.g.
+    /// `https://grafana.example.com`; framing is refused when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub frame_ancestors: Vec<String>,
+    /// Seconds between background ingests in `codex-tracker daemon`.
+    #[serde(default = "default_ingest_interval_secs")]
+    pub ingest_interval_secs: u64,
+    /// Directory holding the database and pricing defaults, instead of the
+    /// desktop app's or `~/Library/Application Support/codex-tracker`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_dir: Option<PathBuf>,
+    /// Range `summary` and `sessions` use without `--range`.
+    #[serde(default = "default_range")]
+    pub default_range: String,
+    /// IANA time zone (e.g. `Europe/Berlin`) for day boundaries and printed
+    /// times; the system zone when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Open the dashboard in the browser when the server starts.
+    #[serde(default = "default_auto_open")]
+    pub auto_open: bool,
 }
 
 impl Default for CliConfig {
     fn default() -> Self {
-        Self { port: DEFAULT_PORT }
+        Self {
+            port: DEFAULT_PORT,
+            bind_address: default_bind_address(),
+            allowed_hosts: Vec::new(),
+            tls_cert: None,
+            tls_key: None,
+            require_token: false,
+            statsd_address: None,
+            statsd_prefix: default_statsd_prefix(),
+            statsd_interval_secs: default_statsd_interval_secs(),
+            statsd_tags: Vec::new(),
+            frame_ancestors: Vec::new(),
+            ingest_interval_secs: default_ingest_interval_secs(),
+            data_dir: None,
+            default_range: default_range(),
+            timezone: None,
+            auto_open: default_auto_open(),
+        }
     }
 }
 
+impl CliConfig {
+    /// Checks values serde accepts but the CLI cannot use; errors name the
+    /// offending key.
+    fn validate(&self) -> Result<(), String> {
+        if self.bind_address.parse::<IpAddr>().is_err() {
+            return Err(invalid_key(
+                "bind_address",
+                "an IP address such as 127.0.0.1 or 0.0.0.0",
+                &self.bind_address,
+            ));
+        }
+        if self.tls_cert.is_some() != self.tls_key.is_some() {
+            let missing = if self.tls_cert.is_some() {
+                "tls_key"
+            } else {
+                "tls_cert"
+            };
+            return Err(format!(
+                "invalid config key `{missing}`: tls_cert and tls_key must be set together"
+            ));
+        }
+        for (key, value) in [
+            ("statsd_interval_secs", self.statsd_interval_secs),
+            ("ingest_interval_secs", self.ingest_interval_secs),
+        ] {
+            if value == 0 {
+                return Err(invalid_key(key, "at least 1", "0"));
+            }
+        }
+        if let Some(dir) = &self.data_dir
+            && !dir.is_absolute()
+        {
+            return Err(invalid_key(
+                "data_dir",
+                "an absolute path",
+                &dir.to_string_lossy(),
+            ));
+        }
+        if !RANGES.contains(&self.range().as_str()) {
+            return Err(invalid_key(
+                "default_range",
+                "today, 7d, 14d, month, or all",
+                &self.default_range,
+            ));
+        }
+        if let Some(timezone) = &self.timezone
+            && timezone.parse::<chrono_tz::Tz>().is_err()
+        {
+            return Err(invalid_key(
+                "timezone",
+                "an IANA time zone such as Europe/Berlin",
+                timezone,
+            ));
+        }
+        Ok(())
+    }
+
+    /// `default_range` with the short forms expanded to API range names.
+    pub fn range(&self) -> String {
+        expand_range(self.default_range.clone())
+    }
+}
+
+fn invalid_key(key: &str, expected: &str, value: &str) -> String {
+    format!("invalid config key `{key}`: expected {expected}, got {value:?}")
+}
+
+fn default_port() -> u16 {
+    DEFAULT_PORT
+}
+
+fn default_bind_address() -> String {
+    DEFAULT_BIND_ADDRESS.to_string()
+}
+
+fn default_statsd_prefix() -> String {
+    DEFAULT_STATSD_PREFIX.to_string()
+}
+
+fn default_statsd_interval_secs() -> u64 {
+    DEFAULT_STATSD_INTERVAL_SECS
+}
+
+fn default_ingest_interval_secs() -> u64 {
+    DEFAULT_INGEST_INTERVAL_SECS
+}
+
+fn default_range() -> String {
+    DEFAULT_RANGE.to_string()
+}
+
+fn default_auto_open() -> bool {
+    true
+}
+
 #[derive(Debug, Clone)]
 pub struct ConfigPaths {
     pub file: PathBuf,
@@ -30,6 +201,16 @@ pub struct ConfigLoad {
     pub created: bool,
 }
 
+/// Reads the config without creating it: defaults when the file is missing.
+pub fn load() -> Result<CliConfig, String> {
+    let file = config_dir()?.join(CONFIG_FILE_NAME);
+    if file.exists() {
+        read(&file)
+    } else {
+        Ok(CliConfig::default())
+    }
+}
+
 pub fn load_or_create() -> Result<ConfigLoad, String> {
     let dir = config_dir()?;
     fs::create_dir_all(&dir)
@@ -38,10 +219,7 @@ pub fn load_or_create() -> Result<ConfigLoad, String> {
     let paths = ConfigPaths { file };
 
     if paths.file.exists() {
-        let contents = fs::read_to_string(&paths.file)
-            .map_err(|err| format!("read config {}: {}", paths.file.display(), err))?;
-        let config: CliConfig = toml::from_str(&contents)
-            .map_err(|err| format!("parse config {}: {}", paths.file.display(), err))?;
+        let config = read(&paths.file)?;
         return Ok(ConfigLoad {
             config,
             paths,
@@ -62,6 +240,18 @@ pub fn load_or_create() -> Result<ConfigLoad, String> {
     })
 }
 
+fn read(file: &std::path::Path) -> Result<CliConfig, String> {
+    let contents = fs::read_to_string(file)
+        .map_err(|err| format!("read config {}: {}", file.display(), err))?;
+    parse(&contents).map_err(|err| format!("config {}: {}", file.display(), err))
+}
+
+fn parse(contents: &str) -> Result<CliConfig, String> {
+    let config: CliConfig = toml::from_str(contents).map_err(|err| err.to_string())?;
+    config.validate()?;
+    Ok(config)
+}
+
 fn config_dir() -> Result<PathBuf, String> {
     let home = std::env::var("HOME").map_err(|err| format!("resolve HOME: {}", err))?;
     Ok(PathBuf::from(home)
@@ -69,3 +259,38 @@ fn config_dir() -> Result<PathBuf, String> {
         .join("Application Support")
         .join(CONFIG_DIR_NAME))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_validates_and_names_bad_keys() {
+        let config = parse(
+            "port = 4000\n\
+             default_range = \"7d\"\n\
+             timezone = \"Europe/Berlin\"\n\
+             auto_open = false\n\
+             data_dir = \"/srv/codex-tracker\"\n",
+        )
+        .expect("valid config");
+        assert_eq!(config.range(), "last7days");
+        assert!(!config.auto_open);
+        assert_eq!(config.bind_address, DEFAULT_BIND_ADDRESS);
+
+        let cases = [
+            ("default_range = \"fortnight\"\n", "`default_range`"),
+            ("timezone = \"Mars/Olympus\"\n", "`timezone`"),
+            ("bind_address = \"localhost\"\n", "`bind_address`"),
+            ("data_dir = \"relative/dir\"\n", "`data_dir`"),
+            ("ingest_interval_secs = 0\n", "`ingest_interval_secs`"),
+            ("tls_cert = \"/etc/cert.pem\"\n", "`tls_key`"),
+            ("prot = 4000\n", "`prot`"),
+            ("port = \"4000\"\n", "port = \"4000\""),
+        ];
+        for (contents, expected) in cases {
+            let err = parse(contents).expect_err(contents);
+            assert!(err.contains(expected), "{contents}: {err}");
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use tracker_app::AppServices;
+
+use crate::format::print_json;
+
+const LAUNCHD_LABEL: &str = "com.codex.tracker";
+const SYSTEMD_UNIT_NAME: &str = "codex-tracker.service";
+
+/// Re-ingests the active home every `interval` on a background thread for the
+/// life of the process, so limit, budget, and webhook alerts keep firing
+/// without a browser polling the API.
+pub fn spawn_scheduler(services: AppServices, interval: Duration) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        loop {
+            thread::sleep(interval);
+            if let Err(err) = services.ingest.run() {
+                tracing::warn!(error = %err, "scheduled ingest failed");
+            }
+        }
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ServiceKind {
+    Launchd,
+    Systemd,
+}
+
+impl ServiceKind {
+    fn current() -> Result<Self, io::Error> {
+        if cfg!(target_os = "macos") {
+            Ok(Self::Launchd)
+        } else if cfg!(target_os = "linux") {
+            Ok(Self::Systemd)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "daemon install supports launchd (macOS) and systemd (Linux) only",
+            ))
+        }
+    }
+
+    fn path(self, home: &Path) -> PathBuf {
+        match self {
+            Self::Launchd => home
+                .join("Library")
+                .join("LaunchAgents")
+                .join(format!("{LAUNCHD_LABEL}.plist")),
+            Self::Systemd => {
+                let config = std::env::var_os("XDG_CONFIG_HOME")
+                    .map(PathBuf::from)
+                    .filter(|dir| dir.is_absolute())
+                    .unwrap_or_else(|| home.join(".config"));
+                config.join("systemd").join("user").join(SYSTEMD_UNIT_NAME)
+            }
+        }
+    }
+
+    fn contents(self, exe: &Path, home: &Path) -> String {
+        match self {
+            Self::Launchd => launchd_plist(exe, &home.join("Library").join("Logs")),
+            Self::Systemd => systemd_unit(exe),
+        }
+    }
+
+    fn enable_hint(self, path: &Path) -> String {
+        match self {
+            Self::Launchd => format!("launchctl load -w {}", path.display()),
+            Self::Systemd => format!("systemctl --user enable --now {SYSTEMD_UNIT_NAME}"),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Installed {
+    kind: ServiceKind,
+    path: PathBuf,
+    /// `false` with `--print`.
+    written: bool,
+    enable_command: String,
+    contents: String,
+}
+
+/// Writes (or prints) a service file that runs this binary's `daemon`
+/// command at login and restarts it if it fails.
+pub fn install(print: bool, json: bool) -> Result<(), io::Error> {
+    let kind = ServiceKind::current()?;
+    let home = PathBuf::from(std::env::var("HOME").map_err(io::Error::other)?);
+    let exe = std::env::current_exe()?;
+    let contents = kind.contents(&exe, &home);
+    let path = kind.path(&home);
+    if print && !json {
+        print!("{contents}");
+        return Ok(());
+    }
+
+    if !print {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, &contents)?;
+    }
+    if json {
+        return print_json(&Installed {
+            kind,
+            enable_command: kind.enable_hint(&path),
+            path,
+            written: !print,
+            contents,
+        });
+    }
+    println!("Wrote {}", path.display());
+    println!("Start it now and at every login with:");
+    println!("  {}", kind.enable_hint(&path));
+    Ok(())
+}
+
+fn launchd_plist(exe: &Path, log_dir: &Path) -> String {
+    let exe = xml_escape(&exe.to_string_lossy());
+    let log = xml_escape(&log_dir.join("codex-tracker.log").to_string_lossy());
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>Label</key>
+  <string>{LAUNCHD_LABEL}</string>
+  <key>ProgramArguments</key>
+  <array>
+    <string>{exe}</string>
+    <string>daemon</string>
+  </array>
+  <key>RunAtLoad</key>
+  <true/>
+  <key>KeepAlive</key>
+  <dict>
+    <key>SuccessfulExit</key>
+    <false/>
+  </dict>
+  <key>StandardOutPath</key>
+  <string>{log}</string>
+  <key>StandardErrorPath</key>
+  <string>{log}</string>
+</dict>
+</plist>
+"#
+    )
+}
+
+fn systemd_unit(exe: &Path) -> String {
+    let exe = exe.to_string_lossy();
+    let exe = if exe.contains(char::is_whitespace) {
+        format!("\"{}\"", exe.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        exe.to_string()
+    };
+    format!(
+        "[Unit]\n\
+Description=Codex Tracker\n\
+After=network.target\n\
+\n\
+[Service]\n\
+ExecStart={exe} daemon\n\
+Restart=on-failure\n\
+RestartSec=10\n\
+\n\
+[Install]\n\
+WantedBy=default.target\n"
+    )
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn service_files_run_the_daemon() {
+        let plist = launchd_plist(
+            Path::new("/Applications/A & B/codex-tracker"),
+            Path::new("/Users/me/Library/Logs"),
+        );
+        assert!(plist.contains("<string>/Applications/A &amp; B/codex-tracker</string>"));
+        assert!(plist.contains("<string>daemon</string>"));
+        assert!(plist.contains("/Users/me/Library/Logs/codex-tracker.log"));
+
+        let unit = systemd_unit(Path::new("/opt/codex tracker/codex-tracker"));
+        assert!(unit.contains("ExecStart=\"/opt/codex tracker/codex-tracker\" daemon\n"));
+        assert!(
+            systemd_unit(Path::new("/usr/bin/codex-tracker"))
+                .contains("ExecStart=/usr/bin/codex-tracker daemon\n")
+        );
+    }
+}
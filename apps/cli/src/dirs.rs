@@ -8,7 +8,16 @@ pub struct DataDirResolution {
     pub matched_existing: bool,
 }
 
-pub fn resolve_data_dir() -> Result<DataDirResolution, String> {
+/// `configured` (the config's `data_dir`) wins; otherwise the first
+/// existing desktop or CLI data dir, or the CLI default.
+pub fn resolve_data_dir(configured: Option<PathBuf>) -> Result<DataDirResolution, String> {
+    if let Some(dir) = configured {
+        let matched_existing = dir.join(DB_FILE_NAME).exists();
+        return Ok(DataDirResolution {
+            dir,
+            matched_existing,
+        });
+    }
     let home = std::env::var("HOME").map_err(|err| format!("resolve HOME: {}", err))?;
     let base = PathBuf::from(home)
         .join("Library")
@@ -0,0 +1,128 @@
+use std::io;
+use std::net::{IpAddr, TcpListener};
+use tracker_app::AppPaths;
+use tracker_app::doctor::{Check, CheckStatus, run_checks};
+
+use crate::config::CliConfig;
+use crate::format::print_json;
+use crate::{dirs, instance};
+
+/// Exit code when at least one check fails.
+pub const EXIT_FAILED: i32 = 1;
+
+/// Runs every check without migrating, ingesting, or binding for longer
+/// than the port probe. A broken config is reported as a finding, and the
+/// other checks fall back to the default data dir. Returns the process exit
+/// code.
+pub fn run(config: Result<CliConfig, String>, json: bool) -> Result<i32, io::Error> {
+    let data_dir = config
+        .as_ref()
+        .ok()
+        .and_then(|config| config.data_dir.clone());
+    let data_dir = dirs::resolve_data_dir(data_dir).map_err(io::Error::other)?;
+    let paths = AppPaths::new(data_dir.dir);
+    let mut checks = Vec::new();
+    if let Err(err) = &config {
+        checks.push(Check::error(
+            "config",
+            err.clone(),
+            "fix the key, or delete the file to start from the defaults",
+        ));
+    }
+    checks.extend(run_checks(&tracker_app::AppConfig {
+        db_path: paths.db_path,
+        pricing_defaults_path: paths.pricing_defaults_path,
+    }));
+    if let Ok(config) = &config {
+        checks.push(check_port(
+            &config.bind_address,
+            config.port,
+            config.tls_cert.is_some(),
+        ));
+    }
+
+    let failed = checks
+        .iter()
+        .any(|check| check.status == CheckStatus::Error);
+    if json {
+        print_json(&checks)?;
+    } else {
+        print_checks(&checks);
+    }
+    Ok(if failed { EXIT_FAILED } else { 0 })
+}
+
+fn print_checks(checks: &[Check]) {
+    for check in checks {
+        let status = match check.status {
+            CheckStatus::Ok => "ok",
+            CheckStatus::Warn => "warn",
+            CheckStatus::Error => "error",
+        };
+        println!("{status:<5}  {:<10}  {}", check.name, check.message);
+        if let Some(hint) = &check.hint {
+            println!("{:<5}  {:<10}  -> {hint}", "", "");
+        }
+    }
+    let count = |status| checks.iter().filter(|check| check.status == status).count();
+    println!();
+    println!(
+        "{} ok, {} warnings, {} errors.",
+        count(CheckStatus::Ok),
+        count(CheckStatus::Warn),
+        count(CheckStatus::Error)
+    );
+}
+
+/// A taken port is fine when codex-tracker itself holds it; anything else
+/// makes `serve` fall back to a random port.
+fn check_port(bind: &str, port: u16, tls: bool) -> Check {
+    let Ok(bind) = bind.parse::<IpAddr>() else {
+        return Check::error(
+            "port",
+            format!("bind_address {bind} is not an IP address"),
+            "set bind_address in config.toml to an address such as 127.0.0.1",
+        );
+    };
+    if port == 0 || TcpListener::bind((bind, port)).is_ok() {
+        return Check::ok("port", format!("{bind}:{port} is available"));
+    }
+    let host = match bind {
+        IpAddr::V4(addr) if addr.is_unspecified() => "127.0.0.1".to_string(),
+        IpAddr::V4(addr) => addr.to_string(),
+        IpAddr::V6(addr) if addr.is_unspecified() => "[::1]".to_string(),
+        IpAddr::V6(addr) => format!("[{addr}]"),
+    };
+    let scheme = if tls { "https" } else { "http" };
+    match instance::running_version(&format!("{scheme}://{host}:{port}")) {
+        Some(version) => Check::ok(
+            "port",
+            format!("codex-tracker {version} is already serving {bind}:{port}"),
+        ),
+        None => Check::warn(
+            "port",
+            format!("{bind}:{port} is in use by another program"),
+            "stop it, or set port in config.toml (or pass --port) to use another one",
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn port_check_flags_taken_ports_and_bad_addresses() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let port = listener.local_addr().expect("addr").port();
+        let check = check_port("127.0.0.1", port, false);
+        assert_eq!(check.status, CheckStatus::Warn);
+
+        drop(listener);
+        assert_eq!(check_port("127.0.0.1", port, false).status, CheckStatus::Ok);
+        assert_eq!(
+            check_port("localhost", port, false).status,
+            CheckStatus::Error
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use app_api::{AppContext, RangeRequest};
+use tracker_app::export::{self as records, RecordFormat};
+
+use crate::args::{ExportArgs, ExportFormat};
+use crate::format::print_json;
+
+/// Exit code when `export verify` finds a broken chain.
+pub const EXIT_BROKEN: i32 = 1;
+
+/// With `json`, a file export reports what it wrote as JSON; exports to
+/// stdout print only the data.
+pub fn run(context: &AppContext, args: ExportArgs, json: bool) -> Result<(), io::Error> {
+    if let Err(err) = context.app_state.services.ingest.run() {
+        tracing::warn!(error = %err, "failed to refresh data before export");
+    }
+
+    let req = RangeRequest {
+        range: Some(args.range.unwrap_or_else(|| "alltime".to_string())),
+        start: args.start,
+        end: args.end,
+        home_id: None,
+    };
+    let out = args
+        .out
+        .unwrap_or_else(|| PathBuf::from(default_file_name(args.format, args.daily)));
+    let to_stdout = out == Path::new("-");
+
+    let format = match args.format {
+        ExportFormat::Parquet => {
+            let bytes = export_parquet(context, req)?;
+            if to_stdout {
+                io::stdout().lock().write_all(&bytes)?;
+                return Ok(());
+            }
+            std::fs::write(&out, bytes)?;
+            return report_written(&out, args.format, args.daily, json);
+        }
+        ExportFormat::Csv => RecordFormat::Csv,
+        ExportFormat::Json => RecordFormat::Json,
+        ExportFormat::Jsonl => RecordFormat::Jsonl,
+        ExportFormat::Audit => {
+            let writer = output(&out, to_stdout)?;
+            let head =
+                app_api::export_events_chained(context, req, writer).map_err(io::Error::other)?;
+            if to_stdout {
+                eprintln!("Head hash: {head}");
+                return Ok(());
+            }
+            if json {
+                return print_json(&serde_json::json!({
+                    "path": out,
+                    "format": format_name(args.format),
+                    "daily": false,
+                    "head": head,
+                }));
+            }
+            println!("Wrote {}", out.display());
+            println!("Head hash: {head}");
+            return Ok(());
+        }
+    };
+    let writer = output(&out, to_stdout)?;
+    let result = if args.daily {
+        app_api::export_daily(context, req, format, writer)
+    } else {
+        app_api::export_events(context, req, format, writer)
+    };
+    result.map_err(io::Error::other)?;
+    if to_stdout {
+        return Ok(());
+    }
+    report_written(&out, args.format, args.daily, json)
+}
+
+/// Returns the process exit code.
+pub fn verify(path: &Path, json: bool) -> Result<i32, io::Error> {
+    let verification = if path == Path::new("-") {
+        records::verify_chain(io::stdin().lock())
+    } else {
+        records::verify_chain(BufReader::new(File::open(path)?))
+    }
+    .map_err(io::Error::other)?;
+    let code = if verification.broken_at.is_some() {
+        EXIT_BROKEN
+    } else {
+        0
+    };
+    if json {
+        print_json(&verification)?;
+        return Ok(code);
+    }
+    match verification.broken_at {
+        Some(line) => println!(
+            "Chain broken at record {line}; the last intact hash is {}.",
+            verification.head
+        ),
+        None => println!(
+            "{} records verified. Head hash: {}",
+            verification.records, verification.head
+        ),
+    }
+    Ok(code)
+}
+
+fn output(out: &Path, to_stdout: bool) -> Result<Box<dyn Write>, io::Error> {
+    Ok(if to_stdout {
+        Box::new(io::stdout().lock())
+    } else {
+        Box::new(BufWriter::new(File::create(out)?))
+    })
+}
+
+fn report_written(
+    out: &Path,
+    format: ExportFormat,
+    daily: bool,
+    json: bool,
+) -> Result<(), io::Error> {
+    if json {
+        return print_json(&serde_json::json!({
+            "path": out,
+            "format": format_name(format),
+            "daily": daily,
+        }));
+    }
+    println!("Wrote {}", out.display());
+    Ok(())
+}
+
+fn format_name(format: ExportFormat) -> &'static str {
+    match format {
+        ExportFormat::Parquet => "parquet",
+        ExportFormat::Csv => "csv",
+        ExportFormat::Json => "json",
+        ExportFormat::Jsonl => "jsonl",
+        ExportFormat::Audit => "audit",
+    }
+}
+
+fn default_file_name(format: ExportFormat, daily: bool) -> String {
+    let contents = if daily { "daily" } else { "events" };
+    let extension = match format {
+        ExportFormat::Audit => "audit.jsonl",
+        format => format_name(format),
+    };
+    format!("codex-tracker-{contents}.{extension}")
+}
+
+#[cfg(feature = "parquet")]
+fn export_parquet(context: &AppContext, req: RangeRequest) -> Result<Vec<u8>, io::Error> {
+    app_api::export_events_parquet(context, req).map_err(io::Error::other)
+}
+
+#[cfg(not(feature = "parquet"))]
+fn export_parquet(_context: &AppContext, _req: RangeRequest) -> Result<Vec<u8>, io::Error> {
+    Err(io::Error::other(
+        "this build of codex-tracker was compiled without parquet support",
+    ))
+}
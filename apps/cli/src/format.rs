@@ -0,0 +1,69 @@
+//! Formatting shared by the CLI outputs.
+
+use std::io::{self, Write};
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+
+/// Prints `value` as pretty JSON, for the global `--json` mode.
+pub fn print_json(value: &impl Serialize) -> Result<(), io::Error> {
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, value).map_err(io::Error::other)?;
+    writeln!(stdout)
+}
+
+/// `1234567` -> `1,234,567`.
+pub fn thousands(value: u64) -> String {
+    let digits = value.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, ch) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+/// Dollar amount, or `n/a` when no pricing rule matched.
+pub fn usd(value: Option<f64>) -> String {
+    match value {
+        Some(value) => format!("${value:.2}"),
+        None => "n/a".to_string(),
+    }
+}
+
+/// `8100` seconds -> `2h 15m`; under a minute reads `<1m`.
+pub fn duration(seconds: i64) -> String {
+    tracker_core::format_duration(seconds)
+}
+
+/// RFC 3339 timestamp -> `2026-01-02 09:30` in local time; unparseable
+/// values are returned as-is.
+pub fn local_time(value: &str) -> String {
+    DateTime::parse_from_rfc3339(value)
+        .map(|ts| {
+            ts.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|_| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_numbers() {
+        assert_eq!(thousands(0), "0");
+        assert_eq!(thousands(999), "999");
+        assert_eq!(thousands(1_000), "1,000");
+        assert_eq!(thousands(1_234_567), "1,234,567");
+        assert_eq!(usd(Some(3.456)), "$3.46");
+        assert_eq!(usd(None), "n/a");
+        assert_eq!(duration(30), "<1m");
+        assert_eq!(duration(8_100), "2h 15m");
+        assert_eq!(duration(3 * 86_400 + 7_200), "3d 2h");
+    }
+}
@@ -0,0 +1,123 @@
+use std::io;
+
+use app_api::{
+    AppContext, HomesArchiveRequest, HomesCreateRequest, HomesDeleteRequest, HomesRenameRequest,
+    HomesSetActiveRequest, HomesUpdateRequest,
+};
+
+use crate::args::HomesCommand;
+use crate::format::print_json;
+
+pub fn run(context: &AppContext, command: HomesCommand, json: bool) -> Result<(), io::Error> {
+    match command {
+        HomesCommand::List => {
+            let homes = app_api::homes_list(context).map_err(to_io)?;
+            if json {
+                return print_json(&homes);
+            }
+            for home in homes.homes {
+                let marker = if Some(home.id) == homes.active_home_id {
+                    "*"
+                } else {
+                    " "
+                };
+                let archived = if home.archived { "  (archived)" } else { "" };
+                let user = home
+                    .user
+                    .map(|user| format!("  [{user}]"))
+                    .unwrap_or_default();
+                println!(
+                    "{marker} {:>3}  {:<20}  {}{user}{archived}",
+                    home.id, home.label, home.path
+                );
+            }
+        }
+        HomesCommand::Add { path, label } => {
+            let path = std::path::absolute(app_api::expand_home_path(&path))?
+                .to_string_lossy()
+                .to_string();
+            let home = app_api::homes_create(context, HomesCreateRequest { path, label })
+                .map_err(to_io)?;
+            if json {
+                return print_json(&home);
+            }
+            println!(
+                "Added home {} ({}) at {} and made it active.",
+                home.id, home.label, home.path
+            );
+        }
+        HomesCommand::Remove { id, yes } => {
+            if !yes {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("removing home {id} deletes its ingested usage; pass --yes to confirm"),
+                ));
+            }
+            let deleted =
+                app_api::homes_delete(context, HomesDeleteRequest { id }).map_err(to_io)?;
+            if json {
+                return print_json(&deleted);
+            }
+            println!("Removed home {}.", deleted.deleted);
+        }
+        HomesCommand::Activate { id } => {
+            let home =
+                app_api::homes_set_active(context, HomesSetActiveRequest { id }).map_err(to_io)?;
+            if json {
+                return print_json(&home);
+            }
+            println!("Active home is now {} ({}).", home.id, home.label);
+        }
+        HomesCommand::Rename { id, label } => {
+            let home =
+                app_api::homes_rename(context, HomesRenameRequest { id, label }).map_err(to_io)?;
+            if json {
+                return print_json(&home);
+            }
+            println!("Renamed home {} to {}.", home.id, home.label);
+        }
+        HomesCommand::Archive { id } => {
+            let home =
+                app_api::homes_archive(context, HomesArchiveRequest { id }).map_err(to_io)?;
+            if json {
+                return print_json(&home);
+            }
+            println!(
+                "Archived home {} ({}); its usage stays in --home all reports.",
+                home.id, home.label
+            );
+        }
+        HomesCommand::Unarchive { id } => {
+            let home =
+                app_api::homes_unarchive(context, HomesArchiveRequest { id }).map_err(to_io)?;
+            if json {
+                return print_json(&home);
+            }
+            println!("Unarchived home {} ({}).", home.id, home.label);
+        }
+        HomesCommand::User { id, user } => {
+            let home = app_api::homes_update(
+                context,
+                id,
+                HomesUpdateRequest {
+                    label: None,
+                    path: None,
+                    user: Some(user.unwrap_or_default()),
+                },
+            )
+            .map_err(to_io)?;
+            if json {
+                return print_json(&home);
+            }
+            match home.user {
+                Some(user) => println!("Home {} ({}) now belongs to {user}.", home.id, home.label),
+                None => println!("Cleared the user of home {} ({}).", home.id, home.label),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn to_io(err: tracker_app::AppError) -> io::Error {
+    io::Error::other(err)
+}
@@ -0,0 +1,48 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use app_api::{AppContext, ImportRequest};
+
+use crate::args::ImportArgs;
+use crate::format::{print_json, thousands};
+
+pub fn run(context: &AppContext, args: ImportArgs, json: bool) -> Result<(), io::Error> {
+    let contents = if args.path == Path::new("-") {
+        let mut contents = String::new();
+        io::stdin().read_to_string(&mut contents)?;
+        contents
+    } else {
+        fs::read_to_string(&args.path)?
+    };
+    let imported = app_api::usage_import(
+        context,
+        ImportRequest {
+            from: args.from,
+            contents,
+            home_id: args.home,
+        },
+    )
+    .map_err(io::Error::other)?;
+    if json {
+        return print_json(&imported);
+    }
+    println!(
+        "Imported {} usage events from {}.",
+        thousands(imported.imported as u64),
+        imported.from
+    );
+    if imported.duplicates > 0 {
+        println!(
+            "Skipped {} already imported.",
+            thousands(imported.duplicates as u64)
+        );
+    }
+    if imported.overlapping > 0 {
+        println!(
+            "Skipped {} on days already ingested from Codex logs.",
+            thousands(imported.overlapping as u64)
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use std::io;
+
+use app_api::AppContext;
+use tracker_app::services::IngestTarget;
+
+use crate::args::IngestArgs;
+use crate::format::{print_json, thousands};
+
+pub fn run(context: &AppContext, args: IngestArgs, json: bool) -> Result<(), io::Error> {
+    let target = match (args.home, args.path) {
+        (Some(id), _) => IngestTarget::Home(id),
+        (None, Some(path)) => IngestTarget::Path(std::path::absolute(app_api::expand_home_path(
+            &path.to_string_lossy(),
+        ))?),
+        (None, None) => IngestTarget::Active,
+    };
+    let stats = app_api::ingest_once(context, target, args.dry_run).map_err(io::Error::other)?;
+
+    if json {
+        return print_json(&stats);
+    }
+
+    if args.dry_run {
+        println!("Dry run; nothing was saved.");
+    }
+    println!(
+        "Files scanned    {:>15}",
+        thousands(stats.files_scanned as u64)
+    );
+    println!(
+        "Files skipped    {:>15}",
+        thousands(stats.files_skipped as u64)
+    );
+    println!(
+        "Events inserted  {:>15}",
+        thousands(stats.events_inserted as u64)
+    );
+    println!("Bytes read       {:>15}", thousands(stats.bytes_read));
+    println!("Issues           {:>15}", stats.issues.len());
+    for issue in &stats.issues {
+        println!("  {}: {}", issue.file_path, issue.message);
+    }
+    Ok(())
+}
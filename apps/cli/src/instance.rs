@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Deserialize)]
+struct Health {
+    status: String,
+    version: HealthVersion,
+}
+
+#[derive(Deserialize)]
+struct HealthVersion {
+    version: String,
+}
+
+/// Asks `base_url` for `/api/health` and returns the version of the
+/// codex-tracker serving it, or `None` when nothing (or something else) is
+/// listening there. Certificates are not verified: the probe only targets
+/// this machine, which may serve a self-signed certificate.
+pub fn running_version(base_url: &str) -> Option<String> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(PROBE_TIMEOUT))
+        .tls_config(
+            ureq::tls::TlsConfig::builder()
+                .disable_verification(true)
+                .build(),
+        )
+        .build()
+        .into();
+    let body = agent
+        .get(format!("{base_url}/api/health"))
+        .call()
+        .ok()?
+        .body_mut()
+        .read_to_string()
+        .ok()?;
+    parse_health(&body)
+}
+
+fn parse_health(body: &str) -> Option<String> {
+    let health: Health = serde_json::from_str(body).ok()?;
+    (health.status == "ok").then_some(health.version.version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_codex_tracker_health() {
+        assert_eq!(
+            parse_health(r#"{"status":"ok","version":{"version":"0.3.0","git_sha":"abc"}}"#),
+            Some("0.3.0".to_string())
+        );
+        assert_eq!(parse_health(r#"{"status":"ok"}"#), None);
+        assert_eq!(parse_health("<html></html>"), None);
+    }
+}
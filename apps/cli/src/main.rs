@@ -1,111 +1,565 @@
 mod args;
+mod budget;
+mod ccusage;
+mod compare;
 mod config;
+mod daemon;
 mod dirs;
+mod doctor;
+mod export;
+mod format;
+mod homes;
+mod import;
+mod ingest;
+mod instance;
+mod maintenance;
+mod pricing;
+mod report;
+mod sessions;
+mod settings;
+mod setup;
+mod statusline;
+mod summary;
+mod tls;
+mod token;
+mod top;
+#[cfg(feature = "tui")]
+mod tui;
 
 use std::io;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::process::Command;
+use std::time::Duration;
 
-use app_api::AppContext;
+use args::{
+    AuditArgs, Command as CliCommand, CompareArgs, DaemonCommand, DbCommand, ServeArgs,
+    SessionsArgs, SummaryArgs,
+};
+use config::CliConfig;
 use http_api::{HttpState, generate_csrf_token};
-use tracker_app::{AppPaths, AppState, ensure_app_data_dir, migrate_legacy_storage};
+use tracker_app::AppError;
+use tracker_app::statsd::{StatsdConfig, StatsdExporter};
+use tracker_error::ErrorCode;
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args = args::parse_args().map_err(|err| {
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = args::parse_args().map_err(|err| {
         eprintln!("{err}");
         args::print_help();
         io::Error::new(io::ErrorKind::InvalidInput, "invalid arguments")
     })?;
+    // Loaded before any thread starts so the configured time zone can be
+    // applied to the process environment. Errors surface in the command.
+    let config = config::load();
+    if let Ok(CliConfig {
+        timezone: Some(timezone),
+        ..
+    }) = &config
+    {
+        // SAFETY: single-threaded here; the runtime and logging are set up
+        // afterwards, so nothing reads the environment concurrently.
+        unsafe { std::env::set_var("TZ", timezone) };
+    }
+    // The TUI owns the terminal; log lines on stderr would draw over it.
+    if !matches!(cli.command, CliCommand::Tui(_)) {
+        tracker_app::init_tracing();
+    }
+
+    let json = cli.json;
+    let runtime = tokio::runtime::Runtime::new()?;
+    match runtime.block_on(run(cli.command, config, json)) {
+        // Scripts get the failure in the same shape as the output.
+        Err(err) if json => {
+            eprintln!(
+                "{}",
+                serde_json::json!({ "error": err.to_string(), "code": error_code(err.as_ref()) })
+            );
+            std::process::exit(1);
+        }
+        result => result,
+    }
+}
+
+/// The stable code for a failed command. Commands wrap app errors in
+/// `io::Error`, so this looks through those; anything else is `internal`.
+fn error_code(err: &(dyn std::error::Error + 'static)) -> ErrorCode {
+    if let Some(err) = err.downcast_ref::<AppError>() {
+        return err.code();
+    }
+    match err
+        .downcast_ref::<io::Error>()
+        .and_then(|err| err.get_ref())
+    {
+        Some(inner) => error_code(inner),
+        None => ErrorCode::Internal,
+    }
+}
 
+async fn run(
+    command: CliCommand,
+    config: Result<CliConfig, String>,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        CliCommand::Serve(args) => serve(args, false, json).await,
+        CliCommand::Daemon(DaemonCommand::Run(args)) => serve(args, true, json).await,
+        CliCommand::Daemon(DaemonCommand::Install { print }) => {
+            daemon::install(print, json)?;
+            Ok(())
+        }
+        CliCommand::Export(args) => {
+            let data_dir = data_dir(&config)?;
+            let context = setup::open_context(data_dir.dir)?;
+            export::run(&context, args, json)?;
+            Ok(())
+        }
+        CliCommand::ExportVerify { path } => {
+            let code = export::verify(&path, json)?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+            Ok(())
+        }
+        CliCommand::Ccusage(args) => {
+            let data_dir = data_dir(&config)?;
+            let context = setup::open_context(data_dir.dir)?;
+            ccusage::run(&context, args)?;
+            Ok(())
+        }
+        CliCommand::Report(args) => {
+            let data_dir = data_dir(&config)?;
+            let context = setup::open_context(data_dir.dir)?;
+            report::run(&context, args, json)?;
+            Ok(())
+        }
+        CliCommand::Summary(args) => {
+            let data_dir = data_dir(&config)?;
+            let context = setup::open_context(data_dir.dir)?;
+            let args = SummaryArgs {
+                range: args.range.or_else(|| Some(config_range(&config))),
+                ..args
+            };
+            summary::run(&context, args, json)?;
+            Ok(())
+        }
+        CliCommand::Compare(args) => {
+            let data_dir = data_dir(&config)?;
+            let context = setup::open_context(data_dir.dir)?;
+            let args = CompareArgs {
+                range: args.range.or_else(|| Some(config_range(&config))),
+                ..args
+            };
+            compare::run(&context, args, json)?;
+            Ok(())
+        }
+        CliCommand::Sessions(args) => {
+            let data_dir = data_dir(&config)?;
+            let context = setup::open_context(data_dir.dir)?;
+            let args = SessionsArgs {
+                range: args.range.or_else(|| Some(config_range(&config))),
+                ..args
+            };
+            sessions::list(&context, args, json)?;
+            Ok(())
+        }
+        CliCommand::Session(args) => {
+            let data_dir = data_dir(&config)?;
+            let context = setup::open_context(data_dir.dir)?;
+            sessions::show(&context, args, json)?;
+            Ok(())
+        }
+        CliCommand::Top(args) => {
+            let data_dir = data_dir(&config)?;
+            let context = setup::open_context(data_dir.dir)?;
+            top::run(&context, args, json)?;
+            Ok(())
+        }
+        CliCommand::Tui(_) if json => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the TUI is interactive; use `codex-tracker top --json` for machine-readable output",
+        )
+        .into()),
+        CliCommand::Tui(args) => {
+            let data_dir = data_dir(&config)?;
+            let context = setup::open_context(data_dir.dir)?;
+            run_tui(&context, args)?;
+            Ok(())
+        }
+        CliCommand::Budget(args) => {
+            let data_dir = data_dir(&config)?;
+            let context = setup::open_context(data_dir.dir)?;
+            let code = budget::status(&context, args, json)?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+            Ok(())
+        }
+        CliCommand::Settings(command) => {
+            let data_dir = data_dir(&config)?;
+            let context = setup::open_context(data_dir.dir)?;
+            settings::run(&context, command, json)?;
+            Ok(())
+        }
+        CliCommand::Homes(command) => {
+            let data_dir = data_dir(&config)?;
+            let context = setup::open_context(data_dir.dir)?;
+            homes::run(&context, command, json)?;
+            Ok(())
+        }
+        CliCommand::Ingest(args) => {
+            let data_dir = data_dir(&config)?;
+            let context = setup::open_context(data_dir.dir)?;
+            ingest::run(&context, args, json)?;
+            Ok(())
+        }
+        CliCommand::Pricing(command) => {
+            let data_dir = data_dir(&config)?;
+            let context = setup::open_context(data_dir.dir)?;
+            pricing::run(&context, command, json)?;
+            Ok(())
+        }
+        CliCommand::Statusline(args) => {
+            let data_dir = data_dir(&config)?;
+            statusline::run(data_dir.dir, args, json)?;
+            Ok(())
+        }
+        CliCommand::Token(command) => {
+            let data_dir = data_dir(&config)?;
+            let context = setup::open_context(data_dir.dir)?;
+            token::run(&context, command, json)?;
+            Ok(())
+        }
+        CliCommand::Doctor => {
+            let code = doctor::run(config, json)?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+            Ok(())
+        }
+        CliCommand::Prune(args) => {
+            let data_dir = data_dir(&config)?;
+            let context = setup::open_context(data_dir.dir)?;
+            maintenance::prune(&context, args, json)?;
+            Ok(())
+        }
+        CliCommand::Compact => {
+            let data_dir = data_dir(&config)?;
+            let context = setup::open_context(data_dir.dir)?;
+            maintenance::compact(&context, json)?;
+            Ok(())
+        }
+        CliCommand::Import(args) => {
+            let data_dir = data_dir(&config)?;
+            let context = setup::open_context(data_dir.dir)?;
+            import::run(&context, args, json)?;
+            Ok(())
+        }
+        CliCommand::Db(command) => {
+            let data_dir = data_dir(&config)?;
+            let context = setup::open_context(data_dir.dir)?;
+            let command = match command {
+                DbCommand::Audit(args) => DbCommand::Audit(AuditArgs {
+                    range: args
+                        .range
+                        .or_else(|| args.start.is_none().then(|| config_range(&config))),
+                    ..args
+                }),
+                command => command,
+            };
+            let code = maintenance::db(&context, command, json)?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+            Ok(())
+        }
+    }
+}
+
+fn data_dir(config: &Result<CliConfig, String>) -> Result<dirs::DataDirResolution, io::Error> {
+    let config = config
+        .as_ref()
+        .map_err(|err| io::Error::other(err.clone()))?;
+    dirs::resolve_data_dir(config.data_dir.clone()).map_err(io::Error::other)
+}
+
+/// The config's `default_range`, or the built-in default when the config
+/// failed to load.
+fn config_range(config: &Result<CliConfig, String>) -> String {
+    config
+        .as_ref()
+        .map(CliConfig::range)
+        .unwrap_or_else(|_| CliConfig::default().range())
+}
+
+#[cfg(feature = "tui")]
+fn run_tui(context: &app_api::AppContext, args: args::TuiArgs) -> Result<(), io::Error> {
+    tui::run(context, args)
+}
+
+#[cfg(not(feature = "tui"))]
+fn run_tui(_context: &app_api::AppContext, _args: args::TuiArgs) -> Result<(), io::Error> {
+    Err(io::Error::other(
+        "this build of codex-tracker was compiled without TUI support",
+    ))
+}
+
+/// Runs the HTTP API. As a daemon it never opens a browser and re-ingests on
+/// a schedule instead of relying on the dashboard to trigger ingests.
+async fn serve(
+    args: ServeArgs,
+    daemon: bool,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // In JSON mode the only stdout line is the final status object.
+    let say = |message: String| {
+        if !json {
+            println!("{message}");
+        }
+    };
     let config = config::load_or_create().map_err(io::Error::other)?;
     if config.created {
-        println!(
+        say(format!(
             "Created config at {} (default port {}).",
             config.paths.file.display(),
             config.config.port
-        );
+        ));
     }
 
-    let data_dir = dirs::resolve_data_dir().map_err(io::Error::other)?;
+    let data_dir =
+        dirs::resolve_data_dir(config.config.data_dir.clone()).map_err(io::Error::other)?;
     if data_dir.matched_existing {
-        println!("Using existing data dir: {}", data_dir.dir.display());
+        say(format!(
+            "Using existing data dir: {}",
+            data_dir.dir.display()
+        ));
     } else {
-        println!("Using data dir: {}", data_dir.dir.display());
+        say(format!("Using data dir: {}", data_dir.dir.display()));
     }
 
     let port = args.port.unwrap_or(config.config.port);
+    let require_token = args.require_token || config.config.require_token;
+    let bind = args
+        .bind
+        .as_deref()
+        .unwrap_or(&config.config.bind_address)
+        .parse::<IpAddr>()
+        .map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "bind address must be an IP address such as 127.0.0.1 or 0.0.0.0",
+            )
+        })?;
+    let tls_paths = match (
+        args.tls_cert.or(config.config.tls_cert),
+        args.tls_key.or(config.config.tls_key),
+    ) {
+        (Some(cert), Some(key)) => Some((cert, key)),
+        (None, None) => None,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "TLS needs both a certificate and a private key",
+            )
+            .into());
+        }
+    };
 
-    let paths = AppPaths::new(data_dir.dir.clone());
-    ensure_app_data_dir(&paths).map_err(|err| io::Error::other(err.to_string()))?;
-    let legacy_backup_dir =
-        migrate_legacy_storage(&paths).map_err(|err| io::Error::other(err.to_string()))?;
-
-    let app_state = AppState::new(paths.db_path, paths.pricing_defaults_path);
-    let is_fresh_db = app_state.is_fresh_db();
-    if let Err(err) = app_state.setup_db() {
-        return Err(io::Error::other(format!("failed to initialize database: {}", err)).into());
-    }
-    if is_fresh_db && let Err(err) = app_state.apply_pricing_defaults() {
-        eprintln!("failed to apply pricing defaults: {}", err);
+    // Loopback binds serve loopback names only, so a DNS name rebound to
+    // 127.0.0.1 never gets the dashboard or its CSRF token.
+    let mut allowed_hosts = args.allowed_hosts;
+    allowed_hosts.extend(config.config.allowed_hosts);
+    if bind.is_loopback() {
+        if !allowed_hosts.is_empty() {
+            tracing::warn!("ignoring allowed hosts on a loopback bind");
+        }
+        allowed_hosts.clear();
+    } else if !bind.is_unspecified() {
+        allowed_hosts.push(bind.to_string());
     }
-    if let Err(err) = app_state.sync_pricing_defaults() {
-        eprintln!("failed to sync pricing defaults: {}", err);
+
+    let scheme = if tls_paths.is_some() { "https" } else { "http" };
+    let local_host = if bind.is_unspecified() {
+        "127.0.0.1".to_string()
+    } else {
+        match bind {
+            IpAddr::V4(addr) => addr.to_string(),
+            IpAddr::V6(addr) => format!("[{addr}]"),
+        }
+    };
+
+    if !args.force && port != 0 {
+        let url = format!("{scheme}://{local_host}:{port}");
+        let probe_url = url.clone();
+        let running = tokio::task::spawn_blocking(move || instance::running_version(&probe_url))
+            .await
+            .unwrap_or(None);
+        if let Some(version) = running {
+            if json {
+                format::print_json(&serde_json::json!({
+                    "status": "already_running",
+                    "url": url,
+                    "version": version,
+                }))?;
+            }
+            say(format!(
+                "Codex Tracker {version} is already running at {url}"
+            ));
+            if daemon {
+                say("Not starting another instance; pass --force to run one anyway.".to_string());
+            } else if !args.no_open
+                && !args.no_ui
+                && config.config.auto_open
+                && let Err(err) = open_url(&url)
+            {
+                tracing::warn!(error = %err, "failed to open browser");
+            }
+            return Ok(());
+        }
     }
 
-    let ingest_state = app_state.clone();
+    let context = setup::open_context(data_dir.dir)?;
+
+    let ingest_state = context.app_state.clone();
     tokio::task::spawn_blocking(move || {
         if let Err(err) = ingest_state.services.ingest.run() {
-            eprintln!("failed to refresh data on startup: {}", err);
+            tracing::error!(error = %err, "failed to refresh data on startup");
         }
     });
 
-    let context = AppContext {
-        app_state,
-        app_data_dir: data_dir.dir,
-        legacy_backup_dir,
-    };
+    if daemon {
+        let interval = Duration::from_secs(config.config.ingest_interval_secs.max(1));
+        say(format!("Ingesting every {}s.", interval.as_secs()));
+        daemon::spawn_scheduler(context.app_state.services.clone(), interval);
+    }
+
+    context.app_state.services.schedules.clone().spawn();
+
+    if let Some(address) = args.statsd.or(config.config.statsd_address) {
+        let statsd = StatsdConfig {
+            address,
+            prefix: config.config.statsd_prefix,
+            interval: Duration::from_secs(config.config.statsd_interval_secs.max(1)),
+            tags: config.config.statsd_tags,
+        };
+        match StatsdExporter::connect(statsd.clone()) {
+            Ok(exporter) => {
+                say(format!(
+                    "Sending metrics to StatsD at {} every {}s.",
+                    statsd.address,
+                    statsd.interval.as_secs()
+                ));
+                exporter.spawn(context.app_state.services.clone());
+            }
+            Err(err) => {
+                tracing::error!(error = %err, address = %statsd.address, "failed to start StatsD exporter")
+            }
+        }
+    }
+
+    if require_token {
+        match context.app_state.services.api_tokens.has_tokens() {
+            Ok(true) => {}
+            Ok(false) => tracing::warn!(
+                "API token auth is enabled but no tokens exist; create one with `codex-tracker token create`."
+            ),
+            Err(err) => tracing::error!(error = %err, "failed to read API tokens"),
+        }
+    }
 
     let csrf_token = generate_csrf_token();
-    let state = HttpState::new(context, csrf_token);
+    let allowed_host_count = allowed_hosts.len();
+    let state = HttpState::new(context, csrf_token)
+        .with_api_token_required(require_token)
+        .with_frame_ancestors(config.config.frame_ancestors)
+        .with_headless(args.no_ui)
+        .with_tls(tls_paths.is_some())
+        .with_allowed_hosts(allowed_hosts);
     let router = http_api::router(state);
 
-    let (listener, actual_port, used_fallback) = bind_port(port).await?;
-    let url = format!("http://127.0.0.1:{actual_port}");
+    let tls_config = match &tls_paths {
+        Some((cert, key)) => Some(tls::load_config(cert, key).await?),
+        None => None,
+    };
+
+    let (listener, actual_port, used_fallback) = bind_port(bind, port).await?;
+    let url = format!("{scheme}://{local_host}:{actual_port}");
 
     if used_fallback {
-        eprintln!("Configured port {port} was unavailable; using {actual_port} for this run.");
+        tracing::warn!("Configured port {port} was unavailable; using {actual_port} for this run.");
+    }
+    if !bind.is_loopback() && !require_token {
+        tracing::warn!(
+            "Listening on {bind} without API token auth; anyone on the network can read your usage data. Consider --require-token."
+        );
     }
 
-    println!("Codex Tracker is running at {url}");
-    println!("Press Ctrl+C to stop.");
+    if json {
+        format::print_json(&serde_json::json!({
+            "status": "running",
+            "url": url,
+            "port": actual_port,
+            "daemon": daemon,
+            "headless": args.no_ui,
+        }))?;
+    }
+    say(format!("Codex Tracker is running at {url}"));
+    if bind.is_unspecified() {
+        say(format!(
+            "Listening on all interfaces ({bind}:{actual_port})."
+        ));
+        if allowed_host_count == 0 && !args.no_ui {
+            say(
+                "Other devices need --allowed-host <name> for each name or IP they open the dashboard under."
+                    .to_string(),
+            );
+        }
+    }
+    if args.no_ui {
+        say("Serving the API only (--no-ui); the dashboard is disabled.".to_string());
+    } else if require_token {
+        say(format!(
+            "API token required; open {url}/?token=<token> to sign in."
+        ));
+    }
+    if !daemon {
+        say("Press Ctrl+C to stop.".to_string());
+    }
 
-    if !args.no_open
+    if !daemon
+        && !args.no_open
+        && !args.no_ui
+        && config.config.auto_open
         && let Err(err) = open_url(&url)
     {
-        eprintln!("failed to open browser: {}", err);
+        tracing::warn!(error = %err, "failed to open browser");
     }
 
-    axum::serve(listener, router)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    match tls_config {
+        Some(tls_config) => tls::serve(listener, tls_config, router, shutdown_signal()).await?,
+        None => {
+            axum::serve(listener, router)
+                .with_graceful_shutdown(shutdown_signal())
+                .await?
+        }
+    }
 
     Ok(())
 }
 
-async fn bind_port(port: u16) -> Result<(tokio::net::TcpListener, u16, bool), io::Error> {
+async fn bind_port(
+    bind: IpAddr,
+    port: u16,
+) -> Result<(tokio::net::TcpListener, u16, bool), io::Error> {
     if port == 0 {
-        let listener = tokio::net::TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0))).await?;
+        let listener = tokio::net::TcpListener::bind(SocketAddr::new(bind, 0)).await?;
         let actual_port = listener.local_addr()?.port();
         return Ok((listener, actual_port, false));
     }
 
-    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let addr = SocketAddr::new(bind, port);
     match tokio::net::TcpListener::bind(addr).await {
         Ok(listener) => Ok((listener, port, false)),
         Err(_) => {
-            let listener =
-                tokio::net::TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0))).await?;
+            let listener = tokio::net::TcpListener::bind(SocketAddr::new(bind, 0)).await?;
             let actual_port = listener.local_addr()?.port();
             Ok((listener, actual_port, true))
         }
@@ -121,6 +575,19 @@ fn open_url(url: &str) -> Result<(), io::Error> {
     }
 }
 
+/// Ctrl+C, or SIGTERM from launchd / systemd stopping the daemon.
 async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+
+        if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = terminate.recv() => {}
+            }
+            return;
+        }
+    }
     let _ = tokio::signal::ctrl_c().await;
 }
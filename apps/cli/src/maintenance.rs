@@ -0,0 +1,150 @@
+use std::io;
+
+use app_api::{AppContext, AuditRequest, MergeRequest, PruneRequest};
+use tracker_app::audit::AuditReport;
+use tracker_core::HomeScope;
+
+use crate::args::{AuditArgs, DbCommand, PruneArgs};
+use crate::format::{print_json, thousands};
+
+/// Exit code when `db audit` finds a divergence.
+pub const EXIT_DIVERGED: i32 = 1;
+
+pub fn prune(context: &AppContext, args: PruneArgs, json: bool) -> Result<(), io::Error> {
+    let pruned = app_api::maintenance_prune(
+        context,
+        PruneRequest {
+            before: args.before,
+            home_id: args.home.map(HomeScope::Home),
+        },
+    )
+    .map_err(io::Error::other)?;
+    if json {
+        return print_json(&pruned);
+    }
+    println!(
+        "Deleted {} usage events, {} messages, and {} limit snapshots before {}.",
+        thousands(pruned.usage_events),
+        thousands(pruned.message_events),
+        thousands(pruned.limit_snapshots),
+        pruned.before
+    );
+    if pruned.usage_events > 0 {
+        println!("Run `codex-tracker compact` to shrink the database file.");
+    }
+    Ok(())
+}
+
+pub fn compact(context: &AppContext, json: bool) -> Result<(), io::Error> {
+    let compacted = app_api::maintenance_compact(context).map_err(io::Error::other)?;
+    if json {
+        return print_json(&compacted);
+    }
+    println!(
+        "Compacted the database: {} -> {}.",
+        megabytes(compacted.bytes_before),
+        megabytes(compacted.bytes_after)
+    );
+    Ok(())
+}
+
+/// Returns the process exit code.
+pub fn db(context: &AppContext, command: DbCommand, json: bool) -> Result<i32, io::Error> {
+    match command {
+        DbCommand::Audit(args) => return audit(context, args, json),
+        DbCommand::Merge { path, user } => {
+            let merged = app_api::maintenance_merge(
+                context,
+                MergeRequest {
+                    path: path.to_string_lossy().into_owned(),
+                    user,
+                },
+            )
+            .map_err(io::Error::other)?;
+            if json {
+                print_json(&merged)?;
+                return Ok(0);
+            }
+            println!(
+                "Merged {} usage events, {} messages, and {} limit snapshots from {}.",
+                thousands(merged.usage_events),
+                thousands(merged.message_events),
+                thousands(merged.limit_snapshots),
+                merged.path
+            );
+            if merged.homes_added > 0 {
+                println!("Added {} codex homes.", merged.homes_added);
+            }
+        }
+    }
+    Ok(0)
+}
+
+fn audit(context: &AppContext, args: AuditArgs, json: bool) -> Result<i32, io::Error> {
+    let report = app_api::maintenance_audit(
+        context,
+        AuditRequest {
+            range: args.range,
+            start: args.start,
+            end: args.end,
+            home_id: args.home,
+        },
+    )
+    .map_err(io::Error::other)?;
+    let code = if report.divergences.is_empty() {
+        0
+    } else {
+        EXIT_DIVERGED
+    };
+    if json {
+        print_json(&report)?;
+    } else {
+        print_audit(&report);
+    }
+    Ok(code)
+}
+
+fn print_audit(report: &AuditReport) {
+    println!(
+        "Audited {} to {}: {} log files, {} sessions.",
+        report.start,
+        report.end,
+        thousands(report.files_scanned as u64),
+        thousands(report.sources_checked as u64)
+    );
+    println!(
+        "  logs      {} events, {} tokens",
+        thousands(report.log.events),
+        thousands(report.log.total_tokens)
+    );
+    println!(
+        "  database  {} events, {} tokens",
+        thousands(report.db.events),
+        thousands(report.db.total_tokens)
+    );
+    if report.divergences.is_empty() {
+        println!("No divergence.");
+        return;
+    }
+    println!();
+    for divergence in &report.divergences {
+        println!("{}", divergence.source);
+        println!(
+            "  logs {} events / {} tokens, database {} events / {} tokens",
+            thousands(divergence.log.events),
+            thousands(divergence.log.total_tokens),
+            thousands(divergence.db.events),
+            thousands(divergence.db.total_tokens)
+        );
+    }
+    println!();
+    println!(
+        "{} of {} sessions diverge.",
+        report.divergences.len(),
+        report.sources_checked
+    );
+}
+
+fn megabytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / 1_000_000.0)
+}
@@ -0,0 +1,286 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use app_api::{AppContext, PricingReplaceRequest};
+use chrono::DateTime;
+use serde::Serialize;
+use tracker_core::PricingRuleInput;
+
+use crate::args::{PricingCommand, PricingSetArgs};
+use crate::format::print_json;
+
+/// `effective_from` for a new model without `--from`, matching the bundled
+/// defaults.
+const DEFAULT_EFFECTIVE_FROM: &str = "2023-01-01T00:00:00.000Z";
+
+/// What `set` and `import` saved, for `--json`.
+#[derive(Serialize)]
+struct Saved {
+    rules: usize,
+    recomputed_events: i64,
+}
+
+pub fn run(context: &AppContext, command: PricingCommand, json: bool) -> Result<(), io::Error> {
+    match command {
+        PricingCommand::List => {
+            let rules = app_api::pricing_list(context).map_err(to_io)?;
+            if json {
+                return print_json(&rules);
+            }
+            if rules.is_empty() {
+                println!("No pricing rules.");
+                return Ok(());
+            }
+            println!(
+                "{:<24}  {:<10}  {:>9}  {:>9}  {:>9}  {:<24}  TO",
+                "MODEL", "PROVIDER", "INPUT", "CACHED", "OUTPUT", "FROM"
+            );
+            for rule in rules {
+                let model = if rule.fallback {
+                    format!("{} (fallback)", rule.model_pattern)
+                } else {
+                    rule.model_pattern
+                };
+                println!(
+                    "{:<24}  {:<10}  {:>9.3}  {:>9.3}  {:>9.3}  {:<24}  {}",
+                    model,
+                    rule.provider.as_deref().unwrap_or("*"),
+                    rule.input_per_1m,
+                    rule.cached_input_per_1m,
+                    rule.output_per_1m,
+                    rule.effective_from,
+                    rule.effective_to.as_deref().unwrap_or("-")
+                );
+            }
+            println!("Prices are USD per 1M tokens.");
+        }
+        PricingCommand::Set(args) => {
+            let mut rules = current_rules(context)?;
+            let model_pattern = args.model_pattern.clone();
+            apply_set(&mut rules, args)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+            let saved = replace(context, rules)?;
+            if json {
+                return print_json(&saved);
+            }
+            println!("Updated pricing for {model_pattern}.");
+            println!("Recomputed costs for {} events.", saved.recomputed_events);
+        }
+        PricingCommand::Import { path } => {
+            let contents = if path == Path::new("-") {
+                let mut contents = String::new();
+                io::stdin().read_to_string(&mut contents)?;
+                contents
+            } else {
+                fs::read_to_string(&path)?
+            };
+            let rules: Vec<PricingRuleInput> = serde_json::from_str(&contents).map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid pricing JSON: {err}"),
+                )
+            })?;
+            validate(&rules).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            let saved = replace(context, rules)?;
+            if json {
+                return print_json(&saved);
+            }
+            println!("Imported {} pricing rules.", saved.rules);
+            println!("Recomputed costs for {} events.", saved.recomputed_events);
+        }
+        PricingCommand::Export { out } => {
+            let rules = current_rules(context)?;
+            match out {
+                Some(out) if out != Path::new("-") => {
+                    let contents =
+                        serde_json::to_string_pretty(&rules).map_err(io::Error::other)?;
+                    fs::write(&out, format!("{contents}\n"))?;
+                    if json {
+                        return print_json(&serde_json::json!({ "path": out }));
+                    }
+                    println!("Wrote {}", out.display());
+                }
+                _ => print_json(&rules)?,
+            }
+        }
+    }
+    Ok(())
+}
+
+fn current_rules(context: &AppContext) -> Result<Vec<PricingRuleInput>, io::Error> {
+    let rules = context
+        .app_state
+        .services
+        .pricing
+        .list_rules()
+        .map_err(to_io)?;
+    Ok(rules.into_iter().map(PricingRuleInput::from).collect())
+}
+
+/// Saves the rules (database and defaults JSON); saving recomputes the
+/// stored costs so reports pick up the new prices.
+fn replace(context: &AppContext, rules: Vec<PricingRuleInput>) -> Result<Saved, io::Error> {
+    let count = rules.len();
+    app_api::pricing_replace(context, PricingReplaceRequest { rules }).map_err(to_io)?;
+    let recomputed = app_api::pricing_recompute_status(context).map_err(to_io)?;
+    Ok(Saved {
+        rules: count,
+        recomputed_events: recomputed.map_or(0, |progress| progress.processed as i64),
+    })
+}
+
+/// Without `--from`, updates the open-ended rule for the pattern and
+/// provider in place (or adds one). With `--from`, closes that rule at the given time and starts a new
+/// one, keeping history for older events.
+fn apply_set(rules: &mut Vec<PricingRuleInput>, args: PricingSetArgs) -> Result<(), String> {
+    let current = rules.iter().position(|rule| {
+        rule.model_pattern == args.model_pattern
+            && rule.provider == args.provider
+            && rule.effective_to.is_none()
+    });
+    let base = current.map(|index| rules[index].clone());
+    let price = |value: Option<f64>, existing: Option<f64>, flag: &str| {
+        value
+            .or(existing)
+            .ok_or_else(|| format!("{flag} is required for a new model"))
+    };
+    let input = price(
+        args.input,
+        base.as_ref().map(|rule| rule.input_per_1m),
+        "--input",
+    )?;
+    let output = price(
+        args.output,
+        base.as_ref().map(|rule| rule.output_per_1m),
+        "--output",
+    )?;
+    // Cached input defaults to a tenth of the input price, like most models.
+    let cached = args
+        .cached
+        .or(base.as_ref().map(|rule| rule.cached_input_per_1m))
+        .unwrap_or(input / 10.0);
+
+    match (current, args.from) {
+        (Some(index), None) => {
+            let rule = &mut rules[index];
+            rule.input_per_1m = input;
+            rule.cached_input_per_1m = cached;
+            rule.output_per_1m = output;
+            rule.fallback |= args.fallback;
+        }
+        (current, from) => {
+            let effective_from = match from {
+                Some(from) => {
+                    DateTime::parse_from_rfc3339(&from)
+                        .map_err(|_| format!("invalid --from timestamp: {from}"))?;
+                    from
+                }
+                None => DEFAULT_EFFECTIVE_FROM.to_string(),
+            };
+            if let Some(index) = current {
+                if rules[index].effective_from >= effective_from {
+                    return Err(format!(
+                        "--from must be after the current rule's start ({})",
+                        rules[index].effective_from
+                    ));
+                }
+                rules[index].effective_to = Some(effective_from.clone());
+            }
+            rules.push(PricingRuleInput {
+                model_pattern: args.model_pattern,
+                input_per_1m: input,
+                cached_input_per_1m: cached,
+                output_per_1m: output,
+                effective_from,
+                effective_to: None,
+                provider: args.provider,
+                fallback: args.fallback || base.is_some_and(|rule| rule.fallback),
+            });
+        }
+    }
+    validate(rules)
+}
+
+fn validate(rules: &[PricingRuleInput]) -> Result<(), String> {
+    for rule in rules {
+        if rule.model_pattern.trim().is_empty() {
+            return Err("pricing rule with an empty model pattern".to_string());
+        }
+        let prices = [
+            rule.input_per_1m,
+            rule.cached_input_per_1m,
+            rule.output_per_1m,
+        ];
+        if prices
+            .iter()
+            .any(|price| !price.is_finite() || *price < 0.0)
+        {
+            return Err(format!(
+                "negative or invalid price for {}",
+                rule.model_pattern
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn to_io(err: tracker_app::AppError) -> io::Error {
+    io::Error::other(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(model_pattern: &str, input: f64, effective_from: &str) -> PricingRuleInput {
+        PricingRuleInput {
+            model_pattern: model_pattern.to_string(),
+            input_per_1m: input,
+            cached_input_per_1m: input / 10.0,
+            output_per_1m: input * 8.0,
+            effective_from: effective_from.to_string(),
+            effective_to: None,
+            provider: None,
+            fallback: false,
+        }
+    }
+
+    fn set(model_pattern: &str, input: Option<f64>, from: Option<&str>) -> PricingSetArgs {
+        PricingSetArgs {
+            model_pattern: model_pattern.to_string(),
+            input,
+            cached: None,
+            output: None,
+            from: from.map(str::to_string),
+            provider: None,
+            fallback: false,
+        }
+    }
+
+    #[test]
+    fn set_updates_or_versions_rules() {
+        let mut rules = vec![rule("gpt-5.1", 1.25, DEFAULT_EFFECTIVE_FROM)];
+
+        apply_set(&mut rules, set("gpt-5.1", Some(2.0), None)).expect("update");
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].input_per_1m, 2.0);
+        assert_eq!(rules[0].output_per_1m, 10.0);
+
+        apply_set(
+            &mut rules,
+            set("gpt-5.1", Some(3.0), Some("2026-02-01T00:00:00Z")),
+        )
+        .expect("version");
+        assert_eq!(rules.len(), 2);
+        assert_eq!(
+            rules[0].effective_to.as_deref(),
+            Some("2026-02-01T00:00:00Z")
+        );
+        assert_eq!(rules[1].input_per_1m, 3.0);
+        assert_eq!(rules[1].effective_to, None);
+
+        let err = apply_set(&mut rules, set("gpt-6", Some(1.0), None)).expect_err("output");
+        assert!(err.contains("--output"));
+    }
+}
@@ -0,0 +1,62 @@
+use std::fs;
+use std::io::{self, Write};
+
+use app_api::{AppContext, ReportRequest};
+use tracker_core::HomeScope;
+
+use crate::args::ReportArgs;
+use crate::format::print_json;
+
+pub fn run(context: &AppContext, args: ReportArgs, json: bool) -> Result<(), io::Error> {
+    if let Err(err) = context.app_state.services.ingest.run() {
+        tracing::warn!(error = %err, "failed to refresh data before report");
+    }
+
+    let home_id = args
+        .home
+        .map(|value| value.parse::<HomeScope>())
+        .transpose()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err.to_string()))?;
+    if let Some(out) = args.html {
+        // Snapshots are meant for sharing a month of usage.
+        let period = args.period.unwrap_or_else(|| "month".to_string());
+        let (_, body) = app_api::report(
+            context,
+            ReportRequest {
+                period: Some(period.clone()),
+                format: Some("snapshot".to_string()),
+                home_id,
+            },
+        )
+        .map_err(io::Error::other)?;
+        fs::write(&out, body)?;
+        if json {
+            return print_json(&serde_json::json!({
+                "path": out,
+                "period": period,
+            }));
+        }
+        println!("Wrote {}", out.display());
+        return Ok(());
+    }
+
+    let (_, body) = app_api::report(
+        context,
+        ReportRequest {
+            period: args.period,
+            format: if json {
+                Some("json".to_string())
+            } else {
+                args.format
+            },
+            home_id,
+        },
+    )
+    .map_err(io::Error::other)?;
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(body.as_bytes())?;
+    if !body.ends_with('\n') {
+        stdout.write_all(b"\n")?;
+    }
+    Ok(())
+}
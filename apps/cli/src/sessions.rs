@@ -0,0 +1,213 @@
+use std::collections::BTreeSet;
+use std::io;
+
+use app_api::{AppContext, SessionTimelineRequest, SessionsRequest};
+use tracker_core::{HomeScope, SessionTimeline, SessionTimelineEntry};
+
+use crate::args::{SessionArgs, SessionsArgs};
+use crate::format::{local_time, print_json, thousands, usd};
+
+const DEFAULT_LIMIT: u32 = 20;
+
+pub fn list(context: &AppContext, args: SessionsArgs, json: bool) -> Result<(), io::Error> {
+    refresh(context);
+
+    let home_id = args
+        .home
+        .map(|value| value.parse::<HomeScope>())
+        .transpose()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err.to_string()))?;
+    let range = args.range.unwrap_or_else(|| "today".to_string());
+    let sessions = app_api::sessions(
+        context,
+        SessionsRequest {
+            range: Some(range.clone()),
+            limit: Some(args.limit.unwrap_or(DEFAULT_LIMIT)),
+            home_id,
+            ..SessionsRequest::default()
+        },
+    )
+    .map_err(io::Error::other)?;
+
+    if json {
+        return print_json(&sessions);
+    }
+
+    if sessions.is_empty() {
+        println!("No sessions ({range}).");
+        return Ok(());
+    }
+    println!(
+        "{:<36}  {:<16}  {:<16}  {:>8}  {:>15}  {:>10}",
+        "SESSION", "MODEL", "LAST SEEN", "MESSAGES", "TOKENS", "COST"
+    );
+    for session in &sessions {
+        println!(
+            "{:<36}  {:<16}  {:<16}  {:>8}  {:>15}  {:>10}",
+            session.session_id,
+            session.model,
+            local_time(&session.last_seen),
+            session.message_count,
+            thousands(session.total_tokens),
+            usd(session.total_cost_usd)
+        );
+    }
+    Ok(())
+}
+
+pub fn show(context: &AppContext, args: SessionArgs, json: bool) -> Result<(), io::Error> {
+    refresh(context);
+
+    let timeline = app_api::session_timeline(
+        context,
+        SessionTimelineRequest {
+            session_id: args.session_id,
+            home_id: args.home,
+        },
+    )
+    .map_err(io::Error::other)?;
+
+    if json {
+        return print_json(&timeline);
+    }
+
+    let totals = SessionTotals::from_timeline(&timeline);
+    println!("Session {}", timeline.session_id);
+    println!(
+        "  Active           {} -> {}",
+        local_time(&totals.first_ts),
+        local_time(&totals.last_ts)
+    );
+    println!("  Models           {}", totals.models.join(", "));
+    println!("  Messages         {:>15}", totals.messages);
+    println!("  Total tokens     {:>15}", thousands(totals.total_tokens));
+    println!("  Cost             {:>15}", usd(totals.cost_usd));
+    if let Some((used, window)) = totals.context {
+        println!(
+            "  Context          {:>15}",
+            format!("{} / {}", thousands(used), thousands(window))
+        );
+    }
+    println!();
+    for entry in &timeline.entries {
+        println!("{}  {}", local_time(entry.ts()), describe(entry));
+    }
+    Ok(())
+}
+
+fn refresh(context: &AppContext) {
+    if let Err(err) = context.app_state.services.ingest.run() {
+        tracing::warn!(error = %err, "failed to refresh data before listing sessions");
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct SessionTotals {
+    first_ts: String,
+    last_ts: String,
+    models: Vec<String>,
+    messages: u64,
+    total_tokens: u64,
+    cost_usd: Option<f64>,
+    /// Context used and window size at the last token event.
+    context: Option<(u64, u64)>,
+}
+
+impl SessionTotals {
+    fn from_timeline(timeline: &SessionTimeline) -> Self {
+        let mut totals = SessionTotals::default();
+        let mut models = BTreeSet::new();
+        for entry in &timeline.entries {
+            if totals.first_ts.is_empty() {
+                totals.first_ts = entry.ts().to_string();
+            }
+            totals.last_ts = entry.ts().to_string();
+            match entry {
+                SessionTimelineEntry::Message { .. } => totals.messages += 1,
+                SessionTimelineEntry::TokenCount {
+                    model,
+                    usage,
+                    context_used,
+                    context_window,
+                    cost_usd,
+                    ..
+                } => {
+                    models.insert(model.clone());
+                    totals.total_tokens += usage.total_tokens;
+                    if let Some(cost) = cost_usd {
+                        *totals.cost_usd.get_or_insert(0.0) += cost;
+                    }
+                    totals.context = Some((*context_used, *context_window));
+                }
+                SessionTimelineEntry::EffortChange { .. } => {}
+            }
+        }
+        totals.models = models.into_iter().collect();
+        totals
+    }
+}
+
+fn describe(entry: &SessionTimelineEntry) -> String {
+    match entry {
+        SessionTimelineEntry::Message { role, .. } => format!("message    {role}"),
+        SessionTimelineEntry::TokenCount {
+            model,
+            usage,
+            cost_usd,
+            ..
+        } => format!(
+            "tokens     {} ({model}, {})",
+            thousands(usage.total_tokens),
+            usd(*cost_usd)
+        ),
+        SessionTimelineEntry::EffortChange { from, to, .. } => format!(
+            "effort     {} -> {}",
+            from.as_deref().unwrap_or("default"),
+            to.as_deref().unwrap_or("default")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracker_core::UsageTotals;
+
+    fn tokens(ts: &str, total_tokens: u64, cost_usd: Option<f64>) -> SessionTimelineEntry {
+        SessionTimelineEntry::TokenCount {
+            ts: ts.to_string(),
+            model: "gpt-5.1".to_string(),
+            usage: UsageTotals {
+                total_tokens,
+                ..UsageTotals::default()
+            },
+            context_used: total_tokens,
+            context_window: 1_000,
+            cost_usd,
+        }
+    }
+
+    #[test]
+    fn totals_sum_token_events() {
+        let timeline = SessionTimeline {
+            session_id: "s1".to_string(),
+            entries: vec![
+                SessionTimelineEntry::Message {
+                    ts: "2026-01-01T10:00:00Z".to_string(),
+                    role: "user".to_string(),
+                },
+                tokens("2026-01-01T10:01:00Z", 100, Some(0.5)),
+                tokens("2026-01-01T10:02:00Z", 50, None),
+            ],
+        };
+        let totals = SessionTotals::from_timeline(&timeline);
+
+        assert_eq!(totals.first_ts, "2026-01-01T10:00:00Z");
+        assert_eq!(totals.last_ts, "2026-01-01T10:02:00Z");
+        assert_eq!(totals.models, vec!["gpt-5.1".to_string()]);
+        assert_eq!(totals.messages, 1);
+        assert_eq!(totals.total_tokens, 150);
+        assert_eq!(totals.cost_usd, Some(0.5));
+        assert_eq!(totals.context, Some((50, 1_000)));
+    }
+}
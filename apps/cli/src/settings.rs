@@ -0,0 +1,65 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use app_api::AppContext;
+use tracker_app::bundle::SettingsBundle;
+
+use crate::args::SettingsCommand;
+use crate::format::print_json;
+
+pub fn run(context: &AppContext, command: SettingsCommand, json: bool) -> Result<(), io::Error> {
+    match command {
+        SettingsCommand::Export { out } => {
+            let bundle = app_api::settings_export(context).map_err(to_io)?;
+            match out {
+                Some(out) if out != Path::new("-") => {
+                    let contents =
+                        serde_json::to_string_pretty(&bundle).map_err(io::Error::other)?;
+                    fs::write(&out, format!("{contents}\n"))?;
+                    if json {
+                        return print_json(&serde_json::json!({ "path": out }));
+                    }
+                    println!("Wrote {}", out.display());
+                    println!("The bundle includes webhook secrets; keep it private.");
+                }
+                _ => print_json(&bundle)?,
+            }
+        }
+        SettingsCommand::Import { path } => {
+            let contents = if path == Path::new("-") {
+                let mut contents = String::new();
+                io::stdin().read_to_string(&mut contents)?;
+                contents
+            } else {
+                fs::read_to_string(&path)?
+            };
+            let bundle: SettingsBundle = serde_json::from_str(&contents).map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid settings bundle: {err}"),
+                )
+            })?;
+            let imported = app_api::settings_import(context, bundle).map_err(to_io)?;
+            if json {
+                return print_json(&imported);
+            }
+            println!("Imported settings.");
+            if imported.pricing_rules > 0 {
+                println!("Replaced pricing with {} rules.", imported.pricing_rules);
+            }
+            if imported.model_aliases > 0 {
+                println!("Replaced model aliases with {}.", imported.model_aliases);
+            }
+            println!(
+                "Added {} homes and {} webhooks.",
+                imported.homes_created, imported.webhooks_created
+            );
+        }
+    }
+    Ok(())
+}
+
+fn to_io(err: tracker_app::AppError) -> io::Error {
+    io::Error::other(err)
+}
@@ -0,0 +1,33 @@
+use std::io;
+use std::path::PathBuf;
+
+use app_api::AppContext;
+use tracker_app::{AppPaths, AppState, ensure_app_data_dir, migrate_legacy_storage};
+
+/// Prepares the data dir and database the same way for every CLI command.
+pub fn open_context(data_dir: PathBuf) -> Result<AppContext, io::Error> {
+    let paths = AppPaths::new(data_dir.clone());
+    ensure_app_data_dir(&paths).map_err(io::Error::other)?;
+    let legacy_backup_dir = migrate_legacy_storage(&paths).map_err(io::Error::other)?;
+
+    let app_state = AppState::new(paths.db_path, paths.pricing_defaults_path);
+    let is_fresh_db = app_state.is_fresh_db();
+    if let Err(err) = app_state.setup_db() {
+        return Err(io::Error::other(format!(
+            "failed to initialize database: {}",
+            err
+        )));
+    }
+    if is_fresh_db && let Err(err) = app_state.apply_pricing_defaults() {
+        tracing::warn!(error = %err, "failed to apply pricing defaults");
+    }
+    if let Err(err) = app_state.sync_pricing_defaults() {
+        tracing::warn!(error = %err, "failed to sync pricing defaults");
+    }
+
+    Ok(AppContext {
+        app_state,
+        app_data_dir: data_dir,
+        legacy_backup_dir,
+    })
+}
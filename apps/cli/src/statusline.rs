@@ -0,0 +1,200 @@
+use std::io;
+use std::path::PathBuf;
+
+use app_api::{AppContext, LimitStatus, RangeRequest};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracker_app::alerts::LIMIT_ALERT_THRESHOLDS;
+use tracker_app::{AppPaths, AppState};
+
+use crate::args::{StatuslineArgs, StatuslineFormat};
+use crate::format::{duration, print_json};
+
+/// tmux colours for each limit band (below 50%, 75%, 90% used, and above).
+const TMUX_COLORS: [&str; 4] = ["green", "yellow", "colour208", "red"];
+const ANSI_COLORS: [&str; 4] = ["32", "33", "38;5;208", "31"];
+const WAYBAR_CLASSES: [&str; 4] = ["ok", "notice", "warning", "critical"];
+const SEPARATOR: &str = " • ";
+
+#[derive(Serialize)]
+struct Limit {
+    window: &'static str,
+    used_percent: f64,
+    reset_at: String,
+}
+
+impl Limit {
+    fn new(window: &'static str, status: Option<LimitStatus>) -> Option<Self> {
+        let status = status?;
+        Some(Self {
+            window,
+            used_percent: status.used_percent,
+            reset_at: status.snapshot.reset_at.to_string(),
+        })
+    }
+
+    fn band(&self) -> usize {
+        LIMIT_ALERT_THRESHOLDS
+            .iter()
+            .filter(|threshold| self.used_percent >= **threshold)
+            .count()
+    }
+
+    fn text(&self) -> String {
+        format!("{}: {:.0}%", self.window, self.used_percent)
+    }
+}
+
+#[derive(Serialize)]
+struct Status {
+    limits: Vec<Limit>,
+    today_cost_usd: f64,
+}
+
+#[derive(Serialize)]
+struct WaybarOutput {
+    text: String,
+    tooltip: String,
+    class: &'static str,
+    percentage: u8,
+}
+
+/// Reads the database directly: no ingest, migrations, or pricing sync, so
+/// prompts and status bars can call it on every render. `json` prints the raw
+/// numbers instead of a formatted line.
+pub fn run(data_dir: PathBuf, args: StatuslineArgs, json: bool) -> Result<(), io::Error> {
+    let paths = AppPaths::new(data_dir.clone());
+    if !paths.db_path.exists() {
+        return Ok(());
+    }
+    let context = AppContext {
+        app_state: AppState::new(paths.db_path, paths.pricing_defaults_path),
+        app_data_dir: data_dir,
+        legacy_backup_dir: None,
+    };
+    let status = load(&context).map_err(io::Error::other)?;
+    if json {
+        return print_json(&status);
+    }
+    println!("{}", render(&status, args.format, Utc::now()));
+    Ok(())
+}
+
+fn load(context: &AppContext) -> tracker_app::Result<Status> {
+    let latest = app_api::limits_latest(context)?;
+    let today = app_api::summary(
+        context,
+        RangeRequest {
+            range: Some("today".to_string()),
+            start: None,
+            end: None,
+            home_id: None,
+        },
+    )?;
+    Ok(Status {
+        limits: [
+            Limit::new("5h", latest.primary),
+            Limit::new("7d", latest.secondary),
+        ]
+        .into_iter()
+        .flatten()
+        .collect(),
+        today_cost_usd: today.total_cost_usd.unwrap_or(0.0),
+    })
+}
+
+fn render(status: &Status, format: StatuslineFormat, now: DateTime<Utc>) -> String {
+    let cost = format!("today ${:.2}", status.today_cost_usd);
+    let colored = |color: fn(usize) -> String, reset: &str| {
+        status
+            .limits
+            .iter()
+            .map(|limit| format!("{}{}{reset}", color(limit.band()), limit.text()))
+            .chain([cost.clone()])
+            .collect::<Vec<_>>()
+            .join(SEPARATOR)
+    };
+    match format {
+        StatuslineFormat::Plain => colored(|_| String::new(), ""),
+        StatuslineFormat::Tmux => {
+            colored(|band| format!("#[fg={}]", TMUX_COLORS[band]), "#[default]")
+        }
+        StatuslineFormat::Starship => {
+            colored(|band| format!("\x1b[{}m", ANSI_COLORS[band]), "\x1b[0m")
+        }
+        StatuslineFormat::Waybar => {
+            let band = status.limits.iter().map(Limit::band).max().unwrap_or(0);
+            let used = status
+                .limits
+                .iter()
+                .map(|limit| limit.used_percent)
+                .fold(0.0, f64::max);
+            let tooltip = status
+                .limits
+                .iter()
+                .map(
+                    |limit| match DateTime::parse_from_rfc3339(&limit.reset_at) {
+                        Ok(reset) => format!(
+                            "{} limit {:.0}% used, resets in {}",
+                            limit.window,
+                            limit.used_percent,
+                            duration((reset.with_timezone(&Utc) - now).num_seconds())
+                        ),
+                        Err(_) => format!("{} limit {:.0}% used", limit.window, limit.used_percent),
+                    },
+                )
+                .chain([format!("Today ${:.2}", status.today_cost_usd)])
+                .collect::<Vec<_>>()
+                .join("\n");
+            let output = WaybarOutput {
+                text: colored(|_| String::new(), ""),
+                tooltip,
+                class: WAYBAR_CLASSES[band],
+                percentage: used.round() as u8,
+            };
+            serde_json::to_string(&output).unwrap_or_default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_each_format() {
+        let now = Utc::now();
+        let status = Status {
+            limits: vec![
+                Limit {
+                    window: "5h",
+                    used_percent: 62.0,
+                    reset_at: (now + chrono::Duration::minutes(90)).to_rfc3339(),
+                },
+                Limit {
+                    window: "7d",
+                    used_percent: 38.0,
+                    reset_at: String::new(),
+                },
+            ],
+            today_cost_usd: 4.123,
+        };
+
+        assert_eq!(
+            render(&status, StatuslineFormat::Plain, now),
+            "5h: 62% • 7d: 38% • today $4.12"
+        );
+        assert_eq!(
+            render(&status, StatuslineFormat::Tmux, now),
+            "#[fg=yellow]5h: 62%#[default] • #[fg=green]7d: 38%#[default] • today $4.12"
+        );
+        let waybar: serde_json::Value =
+            serde_json::from_str(&render(&status, StatuslineFormat::Waybar, now)).expect("json");
+        assert_eq!(waybar["class"], "notice");
+        assert_eq!(waybar["percentage"], 62);
+        assert_eq!(
+            waybar["tooltip"],
+            "5h limit 62% used, resets in 1h 30m\n7d limit 38% used\nToday $4.12"
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use std::io;
+
+use app_api::{AppContext, RangeRequest};
+use tracker_core::HomeScope;
+
+use crate::args::SummaryArgs;
+use crate::format::{print_json, thousands, usd};
+
+pub fn run(context: &AppContext, args: SummaryArgs, json: bool) -> Result<(), io::Error> {
+    if args.refresh
+        && let Err(err) = context.app_state.services.ingest.run()
+    {
+        tracing::warn!(error = %err, "failed to refresh data before summary");
+    }
+
+    let home_id = args
+        .home
+        .map(|value| value.parse::<HomeScope>())
+        .transpose()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err.to_string()))?;
+    let range = args.range.unwrap_or_else(|| "today".to_string());
+    let request = RangeRequest {
+        range: Some(range.clone()),
+        start: None,
+        end: None,
+        home_id,
+    };
+    if args.by_user {
+        return by_user(context, request, &range, json);
+    }
+    let summary = app_api::summary(context, request).map_err(io::Error::other)?;
+
+    if json {
+        return print_json(&summary);
+    }
+
+    println!("Codex usage ({range})");
+    println!("  Total tokens     {:>15}", thousands(summary.total_tokens));
+    println!("  Input            {:>15}", thousands(summary.input_tokens));
+    println!(
+        "  Cached input     {:>15}",
+        thousands(summary.cached_input_tokens)
+    );
+    println!(
+        "  Output           {:>15}",
+        thousands(summary.output_tokens)
+    );
+    println!(
+        "  Reasoning output {:>15}",
+        thousands(summary.reasoning_output_tokens)
+    );
+    println!("  Cost             {:>15}", usd(summary.total_cost_usd));
+    if let Some(estimated) = summary.estimated_cost_usd {
+        println!("    estimated      {:>15}", usd(Some(estimated)));
+    }
+    if let Some(source) = summary.cost_source {
+        println!("  Cost source      {:>15}", source.as_str());
+    }
+    Ok(())
+}
+
+fn by_user(
+    context: &AppContext,
+    request: RangeRequest,
+    range: &str,
+    json: bool,
+) -> Result<(), io::Error> {
+    let users = app_api::breakdown_users(context, request).map_err(io::Error::other)?;
+    if json {
+        return print_json(&users);
+    }
+
+    println!("Codex usage by user ({range})");
+    println!(
+        "  {:<24}  {:>5}  {:>15}  {:>10}",
+        "USER", "HOMES", "TOKENS", "COST"
+    );
+    for item in &users {
+        println!(
+            "  {:<24}  {:>5}  {:>15}  {:>10}",
+            item.user.as_deref().unwrap_or("(no user)"),
+            item.home_ids.len(),
+            thousands(item.total_tokens),
+            usd(item.total_cost_usd)
+        );
+    }
+    Ok(())
+}
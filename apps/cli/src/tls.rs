@@ -0,0 +1,40 @@
+use std::future::Future;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use axum::Router;
+use axum_server::Handle;
+use axum_server::tls_rustls::RustlsConfig;
+
+pub async fn load_config(cert: &Path, key: &Path) -> Result<RustlsConfig, io::Error> {
+    // Both ring (via this crate) and aws-lc-rs may be compiled in; pick one
+    // explicitly so rustls does not refuse to choose.
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    RustlsConfig::from_pem_file(cert, key).await.map_err(|err| {
+        io::Error::other(format!(
+            "load TLS certificate {} / key {}: {}",
+            cert.display(),
+            key.display(),
+            err
+        ))
+    })
+}
+
+pub async fn serve(
+    listener: tokio::net::TcpListener,
+    config: RustlsConfig,
+    router: Router,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<(), io::Error> {
+    let handle = Handle::new();
+    let shutdown_handle = handle.clone();
+    tokio::spawn(async move {
+        shutdown.await;
+        shutdown_handle.graceful_shutdown(Some(Duration::from_secs(5)));
+    });
+    axum_server::from_tcp_rustls(listener.into_std()?, config)
+        .handle(handle)
+        .serve(router.into_make_service())
+        .await
+}
@@ -0,0 +1,49 @@
+use std::io;
+
+use app_api::{ApiTokensCreateRequest, ApiTokensRevokeRequest, AppContext};
+
+use crate::args::TokenCommand;
+use crate::format::print_json;
+
+pub fn run(context: &AppContext, command: TokenCommand, json: bool) -> Result<(), io::Error> {
+    match command {
+        TokenCommand::Create { label } => {
+            let created = app_api::api_tokens_create(context, ApiTokensCreateRequest { label })
+                .map_err(to_io)?;
+            if json {
+                return print_json(&created);
+            }
+            println!(
+                "Created token {} ({}).",
+                created.token.id, created.token.label
+            );
+            println!("{}", created.secret);
+            println!("Store it now; it cannot be shown again.");
+        }
+        TokenCommand::List => {
+            let tokens = app_api::api_tokens_list(context).map_err(to_io)?;
+            if json {
+                return print_json(&tokens);
+            }
+            if tokens.is_empty() {
+                println!("No API tokens.");
+            }
+            for token in tokens {
+                println!("{}  {}  {}", token.id, token.created_at, token.label);
+            }
+        }
+        TokenCommand::Revoke { id } => {
+            let revoked = app_api::api_tokens_revoke(context, ApiTokensRevokeRequest { id })
+                .map_err(to_io)?;
+            if json {
+                return print_json(&revoked);
+            }
+            println!("Revoked token {}.", revoked.revoked);
+        }
+    }
+    Ok(())
+}
+
+fn to_io(err: tracker_app::AppError) -> io::Error {
+    io::Error::other(err)
+}
@@ -0,0 +1,204 @@
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+use app_api::{AppContext, ContextSessionsRequest, RangeRequest};
+use chrono::{DateTime, Local, Utc};
+use serde::Serialize;
+use tracker_core::{
+    ActiveSession, ContextPressureStats, UsageLimitCurrentWindow, UsageLimitSnapshot, UsageSummary,
+};
+
+use crate::args::TopArgs;
+use crate::format::{duration, thousands, usd};
+
+const DEFAULT_INTERVAL_SECS: u64 = 2;
+const MAX_SESSION_ROWS: usize = 15;
+/// Move the cursor home and clear the screen.
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+
+#[derive(Serialize)]
+struct Frame {
+    today: UsageSummary,
+    pressure: ContextPressureStats,
+    limits: [LimitRow; 2],
+    sessions: Vec<ActiveSession>,
+}
+
+#[derive(Serialize)]
+struct LimitRow {
+    label: &'static str,
+    snapshot: Option<UsageLimitSnapshot>,
+    window: Option<UsageLimitCurrentWindow>,
+}
+
+/// With `json`, prints each frame as one JSON line instead of redrawing.
+pub fn run(context: &AppContext, args: TopArgs, json: bool) -> Result<(), io::Error> {
+    let interval = Duration::from_secs(args.interval.unwrap_or(DEFAULT_INTERVAL_SECS));
+    loop {
+        if let Err(err) = context.app_state.services.ingest.run() {
+            tracing::warn!(error = %err, "failed to refresh data");
+        }
+        let frame = load_frame(context).map_err(io::Error::other)?;
+        let mut stdout = io::stdout().lock();
+        if json {
+            serde_json::to_writer(&mut stdout, &frame).map_err(io::Error::other)?;
+            writeln!(stdout)?;
+            stdout.flush()?;
+            if args.once {
+                return Ok(());
+            }
+            drop(stdout);
+            thread::sleep(interval);
+            continue;
+        }
+        let body = render(&frame, interval, Utc::now());
+        if args.once {
+            stdout.write_all(body.as_bytes())?;
+            return stdout.flush();
+        }
+        write!(stdout, "{CLEAR_SCREEN}{body}")?;
+        stdout.flush()?;
+        drop(stdout);
+        thread::sleep(interval);
+    }
+}
+
+fn load_frame(context: &AppContext) -> tracker_app::Result<Frame> {
+    let today = || RangeRequest {
+        range: Some("today".to_string()),
+        start: None,
+        end: None,
+        home_id: None,
+    };
+    let latest = app_api::limits_latest(context)?;
+    let current = app_api::limits_current(context)?;
+    Ok(Frame {
+        today: app_api::summary(context, today())?,
+        pressure: app_api::context_stats(context, today())?,
+        limits: [
+            LimitRow {
+                label: "5h",
+                snapshot: latest.primary.map(|status| status.snapshot),
+                window: current.primary,
+            },
+            LimitRow {
+                label: "7d",
+                snapshot: latest.secondary.map(|status| status.snapshot),
+                window: current.secondary,
+            },
+        ],
+        sessions: app_api::context_sessions(
+            context,
+            ContextSessionsRequest {
+                active_minutes: None,
+                home_id: None,
+            },
+        )?,
+    })
+}
+
+fn render(frame: &Frame, interval: Duration, now: DateTime<Utc>) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "codex-tracker top  {}  (every {}s, Ctrl-C to quit)\n",
+        now.with_timezone(&Local).format("%H:%M:%S"),
+        interval.as_secs()
+    );
+    let _ = writeln!(
+        out,
+        "Today     {} tokens  {}",
+        thousands(frame.today.total_tokens),
+        usd(frame.today.total_cost_usd)
+    );
+    match frame.pressure.avg_pressure_pct {
+        Some(pct) => {
+            let _ = writeln!(
+                out,
+                "Context   {pct:.0}% average pressure over {} samples, p90 {:.0}%, {} above 80%",
+                frame.pressure.sample_count,
+                frame.pressure.p90_pressure_pct.unwrap_or(pct),
+                frame.pressure.high_pressure_count
+            );
+        }
+        None => {
+            let _ = writeln!(out, "Context   no samples today");
+        }
+    }
+    out.push('\n');
+
+    for LimitRow {
+        label,
+        snapshot,
+        window,
+    } in &frame.limits
+    {
+        let Some(snapshot) = snapshot else {
+            let _ = writeln!(out, "{label:<4}  no limit data");
+            continue;
+        };
+        let resets = duration((snapshot.reset_at.as_datetime() - now).num_seconds());
+        let (tokens, cost) = window
+            .as_ref()
+            .map(|window| (window.total_tokens.unwrap_or(0), window.total_cost_usd))
+            .unwrap_or((0, None));
+        let _ = writeln!(
+            out,
+            "{label:<4}  {:>5.1}% left  resets in {resets:<8}  {} tokens  {}",
+            snapshot.percent_left,
+            thousands(tokens),
+            usd(cost)
+        );
+    }
+    out.push('\n');
+
+    if frame.sessions.is_empty() {
+        let _ = writeln!(out, "No active sessions.");
+        return out;
+    }
+    let _ = writeln!(
+        out,
+        "{:<12}  {:<20}  {:>8}  {:>9}",
+        "SESSION", "MODEL", "CONTEXT", "LAST SEEN"
+    );
+    for session in frame.sessions.iter().take(MAX_SESSION_ROWS) {
+        let context = if session.context_window > 0 {
+            format!(
+                "{:.0}%",
+                session.context_used as f64 / session.context_window as f64 * 100.0
+            )
+        } else {
+            "-".to_string()
+        };
+        let last_seen = DateTime::parse_from_rfc3339(&session.last_seen)
+            .map(|ts| {
+                format!(
+                    "{} ago",
+                    duration((now - ts.with_timezone(&Utc)).num_seconds())
+                )
+            })
+            .unwrap_or_else(|_| session.last_seen.clone());
+        let _ = writeln!(
+            out,
+            "{:<12}  {:<20}  {:>8}  {:>9}",
+            truncate(&session.session_id, 12),
+            truncate(&session.model, 20),
+            context,
+            last_seen
+        );
+    }
+    if frame.sessions.len() > MAX_SESSION_ROWS {
+        let _ = writeln!(
+            out,
+            "... and {} more",
+            frame.sessions.len() - MAX_SESSION_ROWS
+        );
+    }
+    out
+}
+
+fn truncate(value: &str, width: usize) -> String {
+    value.chars().take(width).collect()
+}
@@ -0,0 +1,244 @@
+use app_api::{
+    AppContext, LimitsResponse, LimitsWindowsRequest, RangeRequest, SessionsRequest,
+    TimeseriesRequest,
+};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tracker_core::{
+    ModelBreakdown, SessionBreakdown, TimeSeriesPoint, UsageLimitCurrentResponse, UsageLimitWindow,
+    UsageSummary,
+};
+
+/// `(range name, label)` cycled with `r` / `R`.
+pub const RANGES: [(&str, &str); 5] = [
+    ("today", "Today"),
+    ("last7days", "Last 7 days"),
+    ("last14days", "Last 14 days"),
+    ("thismonth", "This month"),
+    ("alltime", "All time"),
+];
+
+const SESSION_ROWS: u32 = 100;
+const WINDOW_ROWS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tab {
+    Summary,
+    Timeseries,
+    Models,
+    Sessions,
+    Limits,
+}
+
+impl Tab {
+    pub const ALL: [Tab; 5] = [
+        Tab::Summary,
+        Tab::Timeseries,
+        Tab::Models,
+        Tab::Sessions,
+        Tab::Limits,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Tab::Summary => "Summary",
+            Tab::Timeseries => "Timeseries",
+            Tab::Models => "Models",
+            Tab::Sessions => "Sessions",
+            Tab::Limits => "Limits",
+        }
+    }
+
+    pub fn index(self) -> usize {
+        Tab::ALL.iter().position(|tab| *tab == self).unwrap_or(0)
+    }
+
+    fn offset(self, delta: isize) -> Tab {
+        let len = Tab::ALL.len() as isize;
+        Tab::ALL[(self.index() as isize + delta).rem_euclid(len) as usize]
+    }
+}
+
+/// What the main loop should do after a key press.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    None,
+    /// Re-run the queries (the range changed).
+    Reload,
+    /// Ingest new log lines, then reload.
+    Refresh,
+    Quit,
+}
+
+#[derive(Default)]
+pub struct Data {
+    pub summary: UsageSummary,
+    pub series: Vec<TimeSeriesPoint>,
+    pub models: Vec<ModelBreakdown>,
+    pub sessions: Vec<SessionBreakdown>,
+    pub limits: Option<LimitsResponse>,
+    pub current: Option<UsageLimitCurrentResponse>,
+    pub windows: Vec<UsageLimitWindow>,
+}
+
+pub struct App {
+    pub tab: Tab,
+    pub range: usize,
+    /// First visible row in the Models / Sessions tables.
+    pub scroll: usize,
+    pub data: Data,
+    pub error: Option<String>,
+}
+
+impl App {
+    pub fn new() -> Self {
+        Self {
+            tab: Tab::Summary,
+            range: 0,
+            scroll: 0,
+            data: Data::default(),
+            error: None,
+        }
+    }
+
+    pub fn range_name(&self) -> &'static str {
+        RANGES[self.range].0
+    }
+
+    pub fn range_label(&self) -> &'static str {
+        RANGES[self.range].1
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Action::Quit;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => Action::Quit,
+            KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => self.select(self.tab.offset(1)),
+            KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => {
+                self.select(self.tab.offset(-1))
+            }
+            KeyCode::Char(ch @ '1'..='5') => self.select(Tab::ALL[ch as usize - '1' as usize]),
+            KeyCode::Char('r') => self.cycle_range(1),
+            KeyCode::Char('R') => self.cycle_range(-1),
+            KeyCode::Char('u') => Action::Refresh,
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.scroll = (self.scroll + 1).min(self.row_count().saturating_sub(1));
+                Action::None
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.scroll = self.scroll.saturating_sub(1);
+                Action::None
+            }
+            _ => Action::None,
+        }
+    }
+
+    fn select(&mut self, tab: Tab) -> Action {
+        self.tab = tab;
+        self.scroll = 0;
+        Action::None
+    }
+
+    fn cycle_range(&mut self, delta: isize) -> Action {
+        self.range = (self.range as isize + delta).rem_euclid(RANGES.len() as isize) as usize;
+        self.scroll = 0;
+        Action::Reload
+    }
+
+    fn row_count(&self) -> usize {
+        match self.tab {
+            Tab::Models => self.data.models.len(),
+            Tab::Sessions => self.data.sessions.len(),
+            _ => 0,
+        }
+    }
+
+    /// Runs every query for the selected range; a failure keeps the previous
+    /// data on screen and shows the error in the status line.
+    pub fn reload(&mut self, context: &AppContext) {
+        match self.load(context) {
+            Ok(data) => {
+                self.data = data;
+                self.error = None;
+            }
+            Err(err) => self.error = Some(err.to_string()),
+        }
+    }
+
+    fn load(&self, context: &AppContext) -> tracker_app::Result<Data> {
+        let range = || RangeRequest {
+            range: Some(self.range_name().to_string()),
+            start: None,
+            end: None,
+            home_id: None,
+        };
+        let bucket = if self.range_name() == "today" {
+            "hour"
+        } else {
+            "day"
+        };
+        Ok(Data {
+            summary: app_api::summary(context, range())?,
+            series: app_api::timeseries(
+                context,
+                TimeseriesRequest {
+                    range: Some(self.range_name().to_string()),
+                    start: None,
+                    end: None,
+                    bucket: Some(bucket.to_string()),
+                    metric: Some("tokens".to_string()),
+                    home_id: None,
+                    tz_offset_minutes: None,
+                },
+            )?,
+            models: app_api::breakdown(context, range())?,
+            sessions: app_api::sessions(
+                context,
+                SessionsRequest {
+                    range: Some(self.range_name().to_string()),
+                    limit: Some(SESSION_ROWS),
+                    ..SessionsRequest::default()
+                },
+            )?,
+            limits: Some(app_api::limits_latest(context)?),
+            current: Some(app_api::limits_current(context)?),
+            windows: app_api::limits_7d_windows(
+                context,
+                LimitsWindowsRequest {
+                    limit: Some(WINDOW_ROWS),
+                },
+            )?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(app: &mut App, code: KeyCode) -> Action {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn keys_switch_tabs_and_ranges() {
+        let mut app = App::new();
+        assert_eq!(press(&mut app, KeyCode::Tab), Action::None);
+        assert_eq!(app.tab, Tab::Timeseries);
+        press(&mut app, KeyCode::BackTab);
+        press(&mut app, KeyCode::BackTab);
+        assert_eq!(app.tab, Tab::Limits);
+        press(&mut app, KeyCode::Char('3'));
+        assert_eq!(app.tab, Tab::Models);
+
+        assert_eq!(press(&mut app, KeyCode::Char('r')), Action::Reload);
+        assert_eq!(app.range_name(), "last7days");
+        press(&mut app, KeyCode::Char('R'));
+        press(&mut app, KeyCode::Char('R'));
+        assert_eq!(app.range_name(), "alltime");
+
+        assert_eq!(press(&mut app, KeyCode::Char('u')), Action::Refresh);
+        assert_eq!(press(&mut app, KeyCode::Char('q')), Action::Quit);
+    }
+}
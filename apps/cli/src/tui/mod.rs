@@ -0,0 +1,69 @@
+mod app;
+mod ui;
+
+use std::io;
+use std::time::{Duration, Instant};
+
+use app_api::AppContext;
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+
+use crate::args::TuiArgs;
+
+use app::{Action, App};
+
+const DEFAULT_REFRESH_SECS: u64 = 10;
+/// How long to wait for a key before checking the refresh timer.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+pub fn run(context: &AppContext, args: TuiArgs) -> Result<(), io::Error> {
+    let refresh = Duration::from_secs(args.refresh.unwrap_or(DEFAULT_REFRESH_SECS));
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, context, refresh);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(
+    terminal: &mut DefaultTerminal,
+    context: &AppContext,
+    refresh: Duration,
+) -> Result<(), io::Error> {
+    let mut app = App::new();
+    refresh_data(context, &mut app);
+    let mut last_refresh = Instant::now();
+
+    loop {
+        terminal.draw(|frame| ui::draw(frame, &app))?;
+
+        if event::poll(POLL_INTERVAL)?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            match app.handle_key(key) {
+                Action::Quit => return Ok(()),
+                Action::Reload => app.reload(context),
+                Action::Refresh => {
+                    refresh_data(context, &mut app);
+                    last_refresh = Instant::now();
+                }
+                Action::None => {}
+            }
+        }
+
+        if last_refresh.elapsed() >= refresh {
+            refresh_data(context, &mut app);
+            last_refresh = Instant::now();
+        }
+    }
+}
+
+/// Ingests new log lines and reloads. Ingest failures go to the status line
+/// since logging would draw over the alternate screen.
+fn refresh_data(context: &AppContext, app: &mut App) {
+    let ingested = context.app_state.services.ingest.run();
+    app.reload(context);
+    if let Err(err) = ingested {
+        app.error = Some(format!("ingest failed: {err}"));
+    }
+}
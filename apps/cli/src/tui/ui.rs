@@ -0,0 +1,260 @@
+use chrono::Utc;
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Gauge, Paragraph, Row, Sparkline, Table, Tabs};
+use tracker_core::{UsageLimitCurrentWindow, UsageLimitSnapshot};
+
+use crate::format::{duration, local_time, thousands, usd};
+
+use super::app::{App, Tab};
+
+const HELP: &str = "tab/←→ switch  1-5 jump  r/R range  ↑↓ scroll  u refresh  q quit";
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [tabs_area, body, status] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let tabs = Tabs::new(Tab::ALL.iter().map(|tab| tab.title()))
+        .select(app.tab.index())
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" codex-tracker · {} ", app.range_label())),
+        );
+    frame.render_widget(tabs, tabs_area);
+
+    match app.tab {
+        Tab::Summary => draw_summary(frame, body, app),
+        Tab::Timeseries => draw_timeseries(frame, body, app),
+        Tab::Models => draw_models(frame, body, app),
+        Tab::Sessions => draw_sessions(frame, body, app),
+        Tab::Limits => draw_limits(frame, body, app),
+    }
+
+    let status_line = match &app.error {
+        Some(err) => Line::from(Span::styled(
+            format!("error: {err}"),
+            Style::default().fg(Color::Red),
+        )),
+        None => Line::from(Span::styled(HELP, Style::default().fg(Color::DarkGray))),
+    };
+    frame.render_widget(Paragraph::new(status_line), status);
+}
+
+fn draw_summary(frame: &mut Frame, area: Rect, app: &App) {
+    let summary = &app.data.summary;
+    let row = |label: &str, tokens: u64, cost: Option<f64>| {
+        Row::new([
+            Cell::from(label.to_string()),
+            Cell::from(thousands(tokens)),
+            Cell::from(usd(cost)),
+        ])
+    };
+    let rows = [
+        row("Input", summary.input_tokens, summary.input_cost_usd),
+        row(
+            "Cached input",
+            summary.cached_input_tokens,
+            summary.cached_input_cost_usd,
+        ),
+        row("Output", summary.output_tokens, summary.output_cost_usd),
+        row("Reasoning output", summary.reasoning_output_tokens, None),
+        row("Total", summary.total_tokens, summary.total_cost_usd)
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    ];
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(18),
+            Constraint::Length(16),
+            Constraint::Length(12),
+        ],
+    )
+    .header(header(["", "Tokens", "Cost"]))
+    .block(Block::default().borders(Borders::ALL).title(" Totals "));
+    frame.render_widget(table, area);
+}
+
+fn draw_timeseries(frame: &mut Frame, area: Rect, app: &App) {
+    let values: Vec<u64> = app
+        .data
+        .series
+        .iter()
+        .map(|point| point.value.max(0.0) as u64)
+        .collect();
+    let peak = values.iter().copied().max().unwrap_or(0);
+    let [chart, legend] = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(area);
+    let sparkline = Sparkline::default()
+        .data(&values)
+        .style(Style::default().fg(Color::Cyan))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" Tokens per bucket (peak {}) ", thousands(peak))),
+        );
+    frame.render_widget(sparkline, chart);
+    let span = match (app.data.series.first(), app.data.series.last()) {
+        (Some(first), Some(last)) => format!(
+            "{} → {}",
+            local_time(&first.bucket_start),
+            local_time(&last.bucket_start)
+        ),
+        _ => "no data".to_string(),
+    };
+    frame.render_widget(Paragraph::new(span), legend);
+}
+
+fn draw_models(frame: &mut Frame, area: Rect, app: &App) {
+    let total = app.data.summary.total_tokens.max(1) as f64;
+    let rows = app.data.models.iter().skip(app.scroll).map(|model| {
+        Row::new([
+            Cell::from(model.model.clone()),
+            Cell::from(thousands(model.total_tokens)),
+            Cell::from(format!("{:.1}%", model.total_tokens as f64 / total * 100.0)),
+            Cell::from(usd(model.total_cost_usd)),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(20),
+            Constraint::Length(16),
+            Constraint::Length(8),
+            Constraint::Length(12),
+        ],
+    )
+    .header(header(["Model", "Tokens", "Share", "Cost"]))
+    .block(Block::default().borders(Borders::ALL).title(" Models "));
+    frame.render_widget(table, area);
+}
+
+fn draw_sessions(frame: &mut Frame, area: Rect, app: &App) {
+    let rows = app.data.sessions.iter().skip(app.scroll).map(|session| {
+        Row::new([
+            Cell::from(session.session_id.clone()),
+            Cell::from(session.model.clone()),
+            Cell::from(local_time(&session.last_seen)),
+            Cell::from(thousands(session.total_tokens)),
+            Cell::from(usd(session.total_cost_usd)),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(20),
+            Constraint::Length(18),
+            Constraint::Length(16),
+            Constraint::Length(14),
+            Constraint::Length(10),
+        ],
+    )
+    .header(header(["Session", "Model", "Last seen", "Tokens", "Cost"]))
+    .block(Block::default().borders(Borders::ALL).title(" Sessions "));
+    frame.render_widget(table, area);
+}
+
+fn draw_limits(frame: &mut Frame, area: Rect, app: &App) {
+    let [primary, secondary, history] = Layout::vertical([
+        Constraint::Length(4),
+        Constraint::Length(4),
+        Constraint::Min(0),
+    ])
+    .areas(area);
+    let limits = app.data.limits.as_ref();
+    let current = app.data.current.as_ref();
+    draw_limit_gauge(
+        frame,
+        primary,
+        "5h",
+        limits.and_then(|limits| limits.primary.as_ref().map(|status| &status.snapshot)),
+        current.and_then(|current| current.primary.as_ref()),
+    );
+    draw_limit_gauge(
+        frame,
+        secondary,
+        "7d",
+        limits.and_then(|limits| limits.secondary.as_ref().map(|status| &status.snapshot)),
+        current.and_then(|current| current.secondary.as_ref()),
+    );
+
+    let rows = app.data.windows.iter().map(|window| {
+        Row::new([
+            Cell::from(
+                window
+                    .window_start
+                    .as_deref()
+                    .map(local_time)
+                    .unwrap_or_else(|| "?".to_string()),
+            ),
+            Cell::from(local_time(&window.window_end)),
+            Cell::from(thousands(window.total_tokens.unwrap_or(0))),
+            Cell::from(usd(window.total_cost_usd)),
+            Cell::from(if window.complete { "" } else { "in progress" }),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(16),
+            Constraint::Length(16),
+            Constraint::Length(14),
+            Constraint::Length(10),
+            Constraint::Min(11),
+        ],
+    )
+    .header(header(["Start", "End", "Tokens", "Cost", ""]))
+    .block(Block::default().borders(Borders::ALL).title(" 7d windows "));
+    frame.render_widget(table, history);
+}
+
+fn draw_limit_gauge(
+    frame: &mut Frame,
+    area: Rect,
+    label: &str,
+    snapshot: Option<&UsageLimitSnapshot>,
+    window: Option<&UsageLimitCurrentWindow>,
+) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" {label} limit "));
+    let Some(snapshot) = snapshot else {
+        frame.render_widget(Paragraph::new("no limit data").block(block), area);
+        return;
+    };
+    let used = (100.0 - snapshot.percent_left).clamp(0.0, 100.0);
+    let resets = duration((snapshot.reset_at.as_datetime() - Utc::now()).num_seconds());
+    let spent = window
+        .map(|window| {
+            format!(
+                " · {} tokens · {}",
+                thousands(window.total_tokens.unwrap_or(0)),
+                usd(window.total_cost_usd)
+            )
+        })
+        .unwrap_or_default();
+    let color = match snapshot.percent_left {
+        left if left < 10.0 => Color::Red,
+        left if left < 30.0 => Color::Yellow,
+        _ => Color::Green,
+    };
+    let gauge = Gauge::default()
+        .block(block)
+        .gauge_style(Style::default().fg(color))
+        .ratio(used / 100.0)
+        .label(format!(
+            "{:.1}% left · resets in {resets}{spent}",
+            snapshot.percent_left
+        ));
+    frame.render_widget(gauge, area);
+}
+
+fn header<const N: usize>(titles: [&'static str; N]) -> Row<'static> {
+    Row::new(titles).style(Style::default().add_modifier(Modifier::BOLD))
+}
@@ -2,12 +2,20 @@ use tauri::State;
 
 use crate::api::to_error;
 use crate::app::DesktopState;
-use app_api::{ContextSessionsRequest, EventsRequest, RangeRequest, TimeseriesRequest};
+use app_api::{
+    ActivityByWeekdayRequest, ActivityRequest, CompactionsRequest, ContextPeaksRequest,
+    ContextSessionsRequest, DashboardRequest, DashboardResponse, EventDetailResponse, EventRequest,
+    EventsRequest, EventsResponse, HomeRequest, RangeRequest, SessionLifetimesRequest,
+    SessionTimelineRequest, TimeseriesGroupedRequest, TimeseriesRequest, TopRequestsRequest,
+};
 use tracker_core::{
-    ActiveSession, ContextPressureStats, ModelBreakdown, ModelCostBreakdown,
-    ModelEffortCostBreakdown, ModelEffortTokenBreakdown, ModelTokenBreakdown, TimeSeriesPoint,
-    UsageEvent, UsageSummary,
+    ActiveSession, ActivityReport, CompactionReport, ContextPressureStats, GroupedTimeSeries,
+    HomeBreakdown, HomeScope, ModelBreakdown, ModelCostBreakdown, ModelEffortCostBreakdown,
+    ModelEffortTokenBreakdown, ModelTokenBreakdown, ProviderBreakdown, RequestBreakdown,
+    SessionContextPeak, SessionLifetimeReport, SessionTimeline, TimeSeriesPoint, UsageSummary,
+    UserBreakdown, WeekdayHourAverages,
 };
+use tracker_error::TrackerError;
 
 #[tauri::command]
 pub fn summary(
@@ -15,23 +23,42 @@ pub fn summary(
     range: Option<String>,
     start: Option<String>,
     end: Option<String>,
-) -> Result<UsageSummary, String> {
-    app_api::summary(&state, RangeRequest { range, start, end }).map_err(to_error)
+    home_id: Option<HomeScope>,
+) -> Result<UsageSummary, TrackerError> {
+    app_api::summary(
+        &state,
+        RangeRequest {
+            range,
+            start,
+            end,
+            home_id,
+        },
+    )
+    .map_err(to_error)
 }
 
 #[tauri::command]
 pub fn context_latest(
     state: State<DesktopState>,
-) -> Result<Option<tracker_core::ContextStatus>, String> {
-    app_api::context_latest(&state).map_err(to_error)
+    home_id: Option<i64>,
+) -> Result<Option<tracker_core::ContextStatus>, TrackerError> {
+    app_api::context_latest(&state, HomeRequest { home_id }).map_err(to_error)
 }
 
 #[tauri::command]
 pub fn context_sessions(
     state: State<DesktopState>,
     active_minutes: Option<u32>,
-) -> Result<Vec<ActiveSession>, String> {
-    app_api::context_sessions(&state, ContextSessionsRequest { active_minutes }).map_err(to_error)
+    home_id: Option<i64>,
+) -> Result<Vec<ActiveSession>, TrackerError> {
+    app_api::context_sessions(
+        &state,
+        ContextSessionsRequest {
+            active_minutes,
+            home_id,
+        },
+    )
+    .map_err(to_error)
 }
 
 #[tauri::command]
@@ -40,8 +67,18 @@ pub fn context_stats(
     range: Option<String>,
     start: Option<String>,
     end: Option<String>,
-) -> Result<ContextPressureStats, String> {
-    app_api::context_stats(&state, RangeRequest { range, start, end }).map_err(to_error)
+    home_id: Option<HomeScope>,
+) -> Result<ContextPressureStats, TrackerError> {
+    app_api::context_stats(
+        &state,
+        RangeRequest {
+            range,
+            start,
+            end,
+            home_id,
+        },
+    )
+    .map_err(to_error)
 }
 
 #[tauri::command]
@@ -52,7 +89,9 @@ pub fn timeseries(
     end: Option<String>,
     bucket: Option<String>,
     metric: Option<String>,
-) -> Result<Vec<TimeSeriesPoint>, String> {
+    home_id: Option<HomeScope>,
+    tz_offset_minutes: Option<i32>,
+) -> Result<Vec<TimeSeriesPoint>, TrackerError> {
     app_api::timeseries(
         &state,
         TimeseriesRequest {
@@ -61,6 +100,36 @@ pub fn timeseries(
             end,
             bucket,
             metric,
+            home_id,
+            tz_offset_minutes,
+        },
+    )
+    .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn timeseries_grouped(
+    state: State<DesktopState>,
+    range: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    bucket: Option<String>,
+    metric: Option<String>,
+    group_by: String,
+    home_id: Option<HomeScope>,
+    tz_offset_minutes: Option<i32>,
+) -> Result<Vec<GroupedTimeSeries>, TrackerError> {
+    app_api::timeseries_grouped(
+        &state,
+        TimeseriesGroupedRequest {
+            range,
+            start,
+            end,
+            bucket,
+            metric,
+            group_by,
+            home_id,
+            tz_offset_minutes,
         },
     )
     .map_err(to_error)
@@ -72,8 +141,78 @@ pub fn breakdown(
     range: Option<String>,
     start: Option<String>,
     end: Option<String>,
-) -> Result<Vec<ModelBreakdown>, String> {
-    app_api::breakdown(&state, RangeRequest { range, start, end }).map_err(to_error)
+    home_id: Option<HomeScope>,
+) -> Result<Vec<ModelBreakdown>, TrackerError> {
+    app_api::breakdown(
+        &state,
+        RangeRequest {
+            range,
+            start,
+            end,
+            home_id,
+        },
+    )
+    .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn breakdown_homes(
+    state: State<DesktopState>,
+    range: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    home_id: Option<HomeScope>,
+) -> Result<Vec<HomeBreakdown>, TrackerError> {
+    app_api::breakdown_homes(
+        &state,
+        RangeRequest {
+            range,
+            start,
+            end,
+            home_id,
+        },
+    )
+    .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn breakdown_users(
+    state: State<DesktopState>,
+    range: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    home_id: Option<HomeScope>,
+) -> Result<Vec<UserBreakdown>, TrackerError> {
+    app_api::breakdown_users(
+        &state,
+        RangeRequest {
+            range,
+            start,
+            end,
+            home_id,
+        },
+    )
+    .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn breakdown_providers(
+    state: State<DesktopState>,
+    range: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    home_id: Option<HomeScope>,
+) -> Result<Vec<ProviderBreakdown>, TrackerError> {
+    app_api::breakdown_providers(
+        &state,
+        RangeRequest {
+            range,
+            start,
+            end,
+            home_id,
+        },
+    )
+    .map_err(to_error)
 }
 
 #[tauri::command]
@@ -82,8 +221,18 @@ pub fn breakdown_tokens(
     range: Option<String>,
     start: Option<String>,
     end: Option<String>,
-) -> Result<Vec<ModelTokenBreakdown>, String> {
-    app_api::breakdown_tokens(&state, RangeRequest { range, start, end }).map_err(to_error)
+    home_id: Option<HomeScope>,
+) -> Result<Vec<ModelTokenBreakdown>, TrackerError> {
+    app_api::breakdown_tokens(
+        &state,
+        RangeRequest {
+            range,
+            start,
+            end,
+            home_id,
+        },
+    )
+    .map_err(to_error)
 }
 
 #[tauri::command]
@@ -92,8 +241,18 @@ pub fn breakdown_costs(
     range: Option<String>,
     start: Option<String>,
     end: Option<String>,
-) -> Result<Vec<ModelCostBreakdown>, String> {
-    app_api::breakdown_costs(&state, RangeRequest { range, start, end }).map_err(to_error)
+    home_id: Option<HomeScope>,
+) -> Result<Vec<ModelCostBreakdown>, TrackerError> {
+    app_api::breakdown_costs(
+        &state,
+        RangeRequest {
+            range,
+            start,
+            end,
+            home_id,
+        },
+    )
+    .map_err(to_error)
 }
 
 #[tauri::command]
@@ -102,8 +261,18 @@ pub fn breakdown_effort_tokens(
     range: Option<String>,
     start: Option<String>,
     end: Option<String>,
-) -> Result<Vec<ModelEffortTokenBreakdown>, String> {
-    app_api::breakdown_effort_tokens(&state, RangeRequest { range, start, end }).map_err(to_error)
+    home_id: Option<HomeScope>,
+) -> Result<Vec<ModelEffortTokenBreakdown>, TrackerError> {
+    app_api::breakdown_effort_tokens(
+        &state,
+        RangeRequest {
+            range,
+            start,
+            end,
+            home_id,
+        },
+    )
+    .map_err(to_error)
 }
 
 #[tauri::command]
@@ -112,8 +281,108 @@ pub fn breakdown_effort_costs(
     range: Option<String>,
     start: Option<String>,
     end: Option<String>,
-) -> Result<Vec<ModelEffortCostBreakdown>, String> {
-    app_api::breakdown_effort_costs(&state, RangeRequest { range, start, end }).map_err(to_error)
+    home_id: Option<HomeScope>,
+) -> Result<Vec<ModelEffortCostBreakdown>, TrackerError> {
+    app_api::breakdown_effort_costs(
+        &state,
+        RangeRequest {
+            range,
+            start,
+            end,
+            home_id,
+        },
+    )
+    .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn models(
+    state: State<DesktopState>,
+    range: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    home_id: Option<HomeScope>,
+) -> Result<Vec<String>, TrackerError> {
+    app_api::models(
+        &state,
+        RangeRequest {
+            range,
+            start,
+            end,
+            home_id,
+        },
+    )
+    .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn efforts(
+    state: State<DesktopState>,
+    range: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    home_id: Option<HomeScope>,
+) -> Result<Vec<String>, TrackerError> {
+    app_api::efforts(
+        &state,
+        RangeRequest {
+            range,
+            start,
+            end,
+            home_id,
+        },
+    )
+    .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn sources(
+    state: State<DesktopState>,
+    range: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    home_id: Option<HomeScope>,
+) -> Result<Vec<String>, TrackerError> {
+    app_api::sources(
+        &state,
+        RangeRequest {
+            range,
+            start,
+            end,
+            home_id,
+        },
+    )
+    .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn dashboard(
+    state: State<DesktopState>,
+    range: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    bucket: Option<String>,
+    tz_offset_minutes: Option<i32>,
+    model: Option<String>,
+    events_limit: Option<u32>,
+    active_minutes: Option<u32>,
+    home_id: Option<i64>,
+) -> Result<DashboardResponse, TrackerError> {
+    app_api::dashboard(
+        &state,
+        DashboardRequest {
+            range,
+            start,
+            end,
+            bucket,
+            tz_offset_minutes,
+            model,
+            events_limit,
+            active_minutes,
+            home_id,
+        },
+    )
+    .map_err(to_error)
 }
 
 #[tauri::command]
@@ -125,7 +394,8 @@ pub fn events(
     limit: Option<u32>,
     offset: Option<u32>,
     model: Option<String>,
-) -> Result<Vec<UsageEvent>, String> {
+    home_id: Option<i64>,
+) -> Result<EventsResponse, TrackerError> {
     app_api::events(
         &state,
         EventsRequest {
@@ -135,6 +405,168 @@ pub fn events(
             limit,
             offset,
             model,
+            home_id,
+        },
+    )
+    .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn event(
+    state: State<DesktopState>,
+    id: String,
+    home_id: Option<i64>,
+) -> Result<EventDetailResponse, TrackerError> {
+    app_api::event(&state, EventRequest { id, home_id }).map_err(to_error)
+}
+
+#[tauri::command]
+pub fn activity(
+    state: State<DesktopState>,
+    range: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    home_id: Option<HomeScope>,
+    idle_gap_minutes: Option<u32>,
+    tz_offset_minutes: Option<i32>,
+) -> Result<ActivityReport, TrackerError> {
+    app_api::activity(
+        &state,
+        ActivityRequest {
+            range,
+            start,
+            end,
+            home_id,
+            idle_gap_minutes,
+            tz_offset_minutes,
+        },
+    )
+    .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn activity_by_weekday(
+    state: State<DesktopState>,
+    range: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    home_id: Option<HomeScope>,
+    tz_offset_minutes: Option<i32>,
+) -> Result<WeekdayHourAverages, TrackerError> {
+    app_api::activity_by_weekday(
+        &state,
+        ActivityByWeekdayRequest {
+            range,
+            start,
+            end,
+            home_id,
+            tz_offset_minutes,
+        },
+    )
+    .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn compactions(
+    state: State<DesktopState>,
+    range: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    home_id: Option<HomeScope>,
+    tz_offset_minutes: Option<i32>,
+) -> Result<CompactionReport, TrackerError> {
+    app_api::compactions(
+        &state,
+        CompactionsRequest {
+            range,
+            start,
+            end,
+            home_id,
+            tz_offset_minutes,
+        },
+    )
+    .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn session_lifetimes(
+    state: State<DesktopState>,
+    range: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    home_id: Option<HomeScope>,
+    idle_minutes: Option<u32>,
+) -> Result<SessionLifetimeReport, TrackerError> {
+    app_api::session_lifetimes(
+        &state,
+        SessionLifetimesRequest {
+            range,
+            start,
+            end,
+            home_id,
+            idle_minutes,
+        },
+    )
+    .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn context_peaks(
+    state: State<DesktopState>,
+    range: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    threshold_pct: Option<f64>,
+    limit: Option<u32>,
+    home_id: Option<HomeScope>,
+) -> Result<Vec<SessionContextPeak>, TrackerError> {
+    app_api::context_peaks(
+        &state,
+        ContextPeaksRequest {
+            range,
+            start,
+            end,
+            threshold_pct,
+            limit,
+            home_id,
+        },
+    )
+    .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn top_requests(
+    state: State<DesktopState>,
+    range: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    limit: Option<u32>,
+    home_id: Option<HomeScope>,
+) -> Result<Vec<RequestBreakdown>, TrackerError> {
+    app_api::top_requests(
+        &state,
+        TopRequestsRequest {
+            range,
+            start,
+            end,
+            limit,
+            home_id,
+        },
+    )
+    .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn session_timeline(
+    state: State<DesktopState>,
+    session_id: String,
+    home_id: Option<i64>,
+) -> Result<SessionTimeline, TrackerError> {
+    app_api::session_timeline(
+        &state,
+        SessionTimelineRequest {
+            session_id,
+            home_id,
         },
     )
     .map_err(to_error)
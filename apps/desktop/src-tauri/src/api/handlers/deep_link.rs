@@ -0,0 +1,10 @@
+use tauri::State;
+
+use crate::app::deep_link::{DeepLink, PendingDeepLink};
+
+/// The `codex-tracker://` link the app was launched with, if the dashboard
+/// has not taken it yet.
+#[tauri::command]
+pub fn deep_link_pending(pending: State<PendingDeepLink>) -> Option<DeepLink> {
+    pending.take()
+}
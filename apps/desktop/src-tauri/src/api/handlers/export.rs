@@ -0,0 +1,74 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use tauri::{AppHandle, State};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::api::{internal_error, to_error};
+use crate::app::DesktopState;
+use app_api::RangeRequest;
+use tracker_app::export::RecordFormat;
+use tracker_core::HomeScope;
+use tracker_error::TrackerError;
+
+/// Asks where to save usage for the given range and writes it there: usage
+/// events, or one row per day with `daily`. The chosen file's extension picks
+/// the format (`.json` for a JSON array, CSV otherwise). Returns the path
+/// written, or `None` when the dialog was cancelled.
+#[tauri::command]
+pub async fn export_data(
+    app: AppHandle,
+    state: State<'_, DesktopState>,
+    range: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    home_id: Option<HomeScope>,
+    daily: Option<bool>,
+) -> Result<Option<String>, TrackerError> {
+    let daily = daily.unwrap_or(false);
+    let contents = if daily { "daily" } else { "events" };
+    let dialog = app
+        .dialog()
+        .file()
+        .set_title("Export usage")
+        .set_file_name(format!("codex-tracker-{contents}.csv"))
+        .add_filter("CSV", &["csv"])
+        .add_filter("JSON", &["json"]);
+    let Some(path) = tauri::async_runtime::spawn_blocking(move || dialog.blocking_save_file())
+        .await
+        .map_err(|err| internal_error(format!("save dialog: {}", err)))?
+    else {
+        return Ok(None);
+    };
+    let path = path.into_path().map_err(internal_error)?;
+
+    let context = state.inner().clone();
+    let req = RangeRequest {
+        range,
+        start,
+        end,
+        home_id,
+    };
+    let written = path.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let format = format_for(&written);
+        let writer = BufWriter::new(File::create(&written).map_err(internal_error)?);
+        let result = if daily {
+            app_api::export_daily(&context, req, format, writer)
+        } else {
+            app_api::export_events(&context, req, format, writer)
+        };
+        result.map_err(to_error)
+    })
+    .await
+    .map_err(|err| internal_error(format!("export task: {}", err)))??;
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+fn format_for(path: &Path) -> RecordFormat {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("json") => RecordFormat::Json,
+        _ => RecordFormat::Csv,
+    }
+}
@@ -2,11 +2,12 @@ use tauri::State;
 
 use crate::api::to_error;
 use crate::app::DesktopState;
-use app_api::HomesResponse;
+use app_api::{HomeCandidateResponse, HomesInspectResponse, HomesResponse};
 use tracker_core::CodexHome;
+use tracker_error::TrackerError;
 
 #[tauri::command]
-pub fn homes_list(state: State<DesktopState>) -> Result<HomesResponse, String> {
+pub fn homes_list(state: State<DesktopState>) -> Result<HomesResponse, TrackerError> {
     app_api::homes_list(&state).map_err(to_error)
 }
 
@@ -15,24 +16,82 @@ pub fn homes_create(
     state: State<DesktopState>,
     path: String,
     label: Option<String>,
-) -> Result<CodexHome, String> {
+) -> Result<CodexHome, TrackerError> {
     app_api::homes_create(&state, app_api::HomesCreateRequest { path, label }).map_err(to_error)
 }
 
+/// Lets the home picker warn about a folder without session logs before it
+/// is added.
 #[tauri::command]
-pub fn homes_set_active(state: State<DesktopState>, id: i64) -> Result<CodexHome, String> {
+pub fn homes_inspect(
+    state: State<DesktopState>,
+    path: String,
+) -> Result<HomesInspectResponse, TrackerError> {
+    app_api::homes_inspect(&state, app_api::HomesInspectRequest { path }).map_err(to_error)
+}
+
+#[tauri::command]
+pub fn homes_discover(
+    state: State<DesktopState>,
+) -> Result<Vec<HomeCandidateResponse>, TrackerError> {
+    app_api::homes_discover(&state).map_err(to_error)
+}
+
+#[tauri::command]
+pub fn homes_set_active(state: State<DesktopState>, id: i64) -> Result<CodexHome, TrackerError> {
     app_api::homes_set_active(&state, app_api::HomesSetActiveRequest { id }).map_err(to_error)
 }
 
 #[tauri::command]
-pub fn homes_delete(state: State<DesktopState>, id: i64) -> Result<serde_json::Value, String> {
+pub fn homes_rename(
+    state: State<DesktopState>,
+    id: i64,
+    label: String,
+) -> Result<CodexHome, TrackerError> {
+    app_api::homes_rename(&state, app_api::HomesRenameRequest { id, label }).map_err(to_error)
+}
+
+#[tauri::command]
+pub fn homes_update(
+    state: State<DesktopState>,
+    id: i64,
+    label: Option<String>,
+    path: Option<String>,
+    user: Option<String>,
+) -> Result<CodexHome, TrackerError> {
+    app_api::homes_update(
+        &state,
+        id,
+        app_api::HomesUpdateRequest { label, path, user },
+    )
+    .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn homes_archive(state: State<DesktopState>, id: i64) -> Result<CodexHome, TrackerError> {
+    app_api::homes_archive(&state, app_api::HomesArchiveRequest { id }).map_err(to_error)
+}
+
+#[tauri::command]
+pub fn homes_unarchive(state: State<DesktopState>, id: i64) -> Result<CodexHome, TrackerError> {
+    app_api::homes_unarchive(&state, app_api::HomesArchiveRequest { id }).map_err(to_error)
+}
+
+#[tauri::command]
+pub fn homes_delete(
+    state: State<DesktopState>,
+    id: i64,
+) -> Result<serde_json::Value, TrackerError> {
     let response =
         app_api::homes_delete(&state, app_api::HomesDeleteRequest { id }).map_err(to_error)?;
     Ok(serde_json::json!({ "deleted": response.deleted }))
 }
 
 #[tauri::command]
-pub fn homes_clear_data(state: State<DesktopState>, id: i64) -> Result<serde_json::Value, String> {
+pub fn homes_clear_data(
+    state: State<DesktopState>,
+    id: i64,
+) -> Result<serde_json::Value, TrackerError> {
     let response = app_api::homes_clear_data(&state, app_api::HomesClearDataRequest { id })
         .map_err(to_error)?;
     Ok(serde_json::json!({ "cleared": response.cleared }))
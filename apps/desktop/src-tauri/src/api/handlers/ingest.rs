@@ -1,14 +1,55 @@
 use tauri::State;
 
-use crate::api::to_error;
+use crate::api::{internal_error, to_error};
 use crate::app::DesktopState;
+use app_api::{
+    HomeRequest, IngestCursorResponse, IngestCursorsResetRequest, RefreshRequest, RefreshResponse,
+    ResetResponse,
+};
 use ingest::IngestStats;
+use tracker_error::TrackerError;
 
+/// While tracking is paused the dashboard keeps refreshing, but this scans
+/// nothing.
 #[tauri::command]
-pub async fn ingest(state: State<'_, DesktopState>) -> Result<IngestStats, String> {
+pub async fn ingest(state: State<'_, DesktopState>) -> Result<IngestStats, TrackerError> {
     let app_state = state.app_state.clone();
     tauri::async_runtime::spawn_blocking(move || app_state.services.ingest.run())
         .await
-        .map_err(|err| format!("ingest task: {}", err))?
+        .map_err(|err| internal_error(format!("ingest task: {}", err)))?
+        .map_err(to_error)
+}
+
+/// Ingests, then reads back the summary for the range and the limits.
+#[tauri::command]
+pub async fn refresh(
+    state: State<'_, DesktopState>,
+    range: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+) -> Result<RefreshResponse, TrackerError> {
+    let context = state.inner().clone();
+    let req = RefreshRequest { range, start, end };
+    tauri::async_runtime::spawn_blocking(move || app_api::refresh(&context, req))
+        .await
+        .map_err(|err| internal_error(format!("refresh task: {}", err)))?
+        .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn ingest_cursors(
+    state: State<DesktopState>,
+    home_id: Option<i64>,
+) -> Result<Vec<IngestCursorResponse>, TrackerError> {
+    app_api::ingest_cursors(&state, HomeRequest { home_id }).map_err(to_error)
+}
+
+#[tauri::command]
+pub fn ingest_cursors_reset(
+    state: State<DesktopState>,
+    file_path: Option<String>,
+    home_id: Option<i64>,
+) -> Result<ResetResponse, TrackerError> {
+    app_api::ingest_cursors_reset(&state, IngestCursorsResetRequest { file_path, home_id })
         .map_err(to_error)
 }
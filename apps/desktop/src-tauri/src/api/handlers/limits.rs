@@ -2,16 +2,19 @@ use tauri::State;
 
 use crate::api::to_error;
 use crate::app::DesktopState;
-use app_api::LimitsResponse;
-use tracker_core::{UsageLimitCurrentResponse, UsageLimitWindow};
+use app_api::{DeletedResponse, LimitsResponse};
+use tracker_core::{CustomLimit, UsageLimitCurrentResponse, UsageLimitSnapshot, UsageLimitWindow};
+use tracker_error::TrackerError;
 
 #[tauri::command]
-pub fn limits_latest(state: State<DesktopState>) -> Result<LimitsResponse, String> {
+pub fn limits_latest(state: State<DesktopState>) -> Result<LimitsResponse, TrackerError> {
     app_api::limits_latest(&state).map_err(to_error)
 }
 
 #[tauri::command]
-pub fn limits_current(state: State<DesktopState>) -> Result<UsageLimitCurrentResponse, String> {
+pub fn limits_current(
+    state: State<DesktopState>,
+) -> Result<UsageLimitCurrentResponse, TrackerError> {
     app_api::limits_current(&state).map_err(to_error)
 }
 
@@ -19,6 +22,66 @@ pub fn limits_current(state: State<DesktopState>) -> Result<UsageLimitCurrentRes
 pub fn limits_7d_windows(
     state: State<DesktopState>,
     limit: Option<usize>,
-) -> Result<Vec<UsageLimitWindow>, String> {
+) -> Result<Vec<UsageLimitWindow>, TrackerError> {
     app_api::limits_7d_windows(&state, app_api::LimitsWindowsRequest { limit }).map_err(to_error)
 }
+
+#[tauri::command]
+pub fn custom_limits_list(state: State<DesktopState>) -> Result<Vec<CustomLimit>, TrackerError> {
+    app_api::custom_limits_list(&state).map_err(to_error)
+}
+
+#[tauri::command]
+pub fn custom_limits_create(
+    state: State<DesktopState>,
+    name: String,
+    period: String,
+    metric: String,
+    amount: f64,
+) -> Result<CustomLimit, TrackerError> {
+    app_api::custom_limits_create(
+        &state,
+        app_api::CustomLimitsCreateRequest {
+            name,
+            period,
+            metric,
+            amount,
+        },
+    )
+    .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn custom_limits_delete(
+    state: State<DesktopState>,
+    id: i64,
+) -> Result<DeletedResponse, TrackerError> {
+    app_api::custom_limits_delete(&state, app_api::CustomLimitsDeleteRequest { id })
+        .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn limits_snapshots(
+    state: State<DesktopState>,
+    range: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    limit_type: Option<String>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+    home_id: Option<i64>,
+) -> Result<Vec<UsageLimitSnapshot>, TrackerError> {
+    app_api::limits_snapshots(
+        &state,
+        app_api::LimitsSnapshotsRequest {
+            range,
+            start,
+            end,
+            limit_type,
+            limit,
+            offset,
+            home_id,
+        },
+    )
+    .map_err(to_error)
+}
@@ -1,7 +1,13 @@
 pub(crate) mod analytics;
+pub(crate) mod deep_link;
+pub(crate) mod export;
 pub(crate) mod homes;
 pub(crate) mod ingest;
 pub(crate) mod limits;
 pub(crate) mod logs;
 pub(crate) mod pricing;
+pub(crate) mod schedules;
 pub(crate) mod settings;
+pub(crate) mod system;
+pub(crate) mod updates;
+pub(crate) mod webhooks;
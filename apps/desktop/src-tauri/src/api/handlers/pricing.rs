@@ -2,11 +2,12 @@ use tauri::State;
 
 use crate::api::to_error;
 use crate::app::DesktopState;
-use app_api::PricingRuleResponse;
-use tracker_core::PricingRuleInput;
+use app_api::{PricingRecomputeResponse, PricingRuleResponse};
+use tracker_core::{CostRecomputeProgress, ModelAlias, PricingRuleInput};
+use tracker_error::TrackerError;
 
 #[tauri::command]
-pub fn pricing_list(state: State<DesktopState>) -> Result<Vec<PricingRuleResponse>, String> {
+pub fn pricing_list(state: State<DesktopState>) -> Result<Vec<PricingRuleResponse>, TrackerError> {
     app_api::pricing_list(&state).map_err(to_error)
 }
 
@@ -14,14 +15,38 @@ pub fn pricing_list(state: State<DesktopState>) -> Result<Vec<PricingRuleRespons
 pub fn pricing_replace(
     state: State<DesktopState>,
     rules: Vec<PricingRuleInput>,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, TrackerError> {
     let response = app_api::pricing_replace(&state, app_api::PricingReplaceRequest { rules })
         .map_err(to_error)?;
     Ok(serde_json::json!({ "updated": response.updated }))
 }
 
 #[tauri::command]
-pub fn pricing_recompute(state: State<DesktopState>) -> Result<serde_json::Value, String> {
-    let response = app_api::pricing_recompute(&state).map_err(to_error)?;
+pub fn model_aliases_list(state: State<DesktopState>) -> Result<Vec<ModelAlias>, TrackerError> {
+    app_api::model_aliases_list(&state).map_err(to_error)
+}
+
+#[tauri::command]
+pub fn model_aliases_replace(
+    state: State<DesktopState>,
+    aliases: Vec<ModelAlias>,
+) -> Result<serde_json::Value, TrackerError> {
+    let response =
+        app_api::model_aliases_replace(&state, app_api::ModelAliasesReplaceRequest { aliases })
+            .map_err(to_error)?;
     Ok(serde_json::json!({ "updated": response.updated }))
 }
+
+#[tauri::command]
+pub fn pricing_recompute(
+    state: State<DesktopState>,
+) -> Result<PricingRecomputeResponse, TrackerError> {
+    app_api::pricing_recompute(&state).map_err(to_error)
+}
+
+#[tauri::command]
+pub fn pricing_recompute_status(
+    state: State<DesktopState>,
+) -> Result<Option<CostRecomputeProgress>, TrackerError> {
+    app_api::pricing_recompute_status(&state).map_err(to_error)
+}
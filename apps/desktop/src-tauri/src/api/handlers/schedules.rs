@@ -0,0 +1,50 @@
+use tauri::State;
+
+use crate::api::to_error;
+use crate::app::DesktopState;
+use tracker_core::Schedule;
+use tracker_error::TrackerError;
+
+#[tauri::command]
+pub fn schedules_list(state: State<DesktopState>) -> Result<Vec<Schedule>, TrackerError> {
+    app_api::schedules_list(&state).map_err(to_error)
+}
+
+#[tauri::command]
+pub fn schedules_create(
+    state: State<DesktopState>,
+    payload: String,
+    local_time: String,
+    target: String,
+    destination: String,
+    smtp_url: Option<String>,
+    email_from: Option<String>,
+) -> Result<Schedule, TrackerError> {
+    app_api::schedules_create(
+        &state,
+        app_api::SchedulesCreateRequest {
+            payload,
+            local_time,
+            target,
+            destination,
+            smtp_url,
+            email_from,
+        },
+    )
+    .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn schedules_delete(
+    state: State<DesktopState>,
+    id: i64,
+) -> Result<serde_json::Value, TrackerError> {
+    let response = app_api::schedules_delete(&state, app_api::SchedulesDeleteRequest { id })
+        .map_err(to_error)?;
+    Ok(serde_json::json!({ "deleted": response.deleted }))
+}
+
+#[tauri::command]
+pub fn schedules_send(state: State<DesktopState>, id: i64) -> Result<Schedule, TrackerError> {
+    app_api::schedules_send(&state, app_api::SchedulesSendRequest { id }).map_err(to_error)
+}
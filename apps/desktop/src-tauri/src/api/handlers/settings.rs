@@ -1,26 +1,88 @@
-use tauri::State;
+use tauri::{AppHandle, State};
 
-use crate::api::to_error;
-use crate::app::DesktopState;
-use app_api::SettingsResponse;
+use crate::api::{internal_error, to_error};
+use crate::app::{DesktopState, autostart, shortcut};
+use app_api::{SentResponse, SettingsImportResponse, SettingsResponse};
+use tracker_app::bundle::SettingsBundle;
+use tracker_core::ActiveSessionRule;
+use tracker_error::TrackerError;
 
 #[tauri::command]
-pub fn settings_get(state: State<DesktopState>) -> Result<SettingsResponse, String> {
+pub fn settings_get(state: State<DesktopState>) -> Result<SettingsResponse, TrackerError> {
     app_api::settings_get(&state).map_err(to_error)
 }
 
 #[tauri::command]
 pub fn settings_put(
+    app: AppHandle,
     state: State<DesktopState>,
     codex_home: Option<String>,
     context_active_minutes: Option<u32>,
-) -> Result<SettingsResponse, String> {
+    monthly_budget_usd: Option<f64>,
+    auto_ingest_secs: Option<u32>,
+    launch_at_login: Option<bool>,
+    global_shortcut: Option<String>,
+    tracking_paused: Option<bool>,
+    limit_warn_percent: Option<u32>,
+    limit_critical_percent: Option<u32>,
+    active_session_rule: Option<ActiveSessionRule>,
+    slack_webhook_url: Option<String>,
+    discord_webhook_url: Option<String>,
+    daily_summary: Option<bool>,
+) -> Result<SettingsResponse, TrackerError> {
+    // Register the login item and shortcut first so a failure leaves the
+    // settings as they were.
+    if let Some(enabled) = launch_at_login {
+        autostart::apply(&app, enabled).map_err(internal_error)?;
+    }
+    if let Some(accelerator) = global_shortcut.as_deref() {
+        shortcut::apply(&app, accelerator).map_err(internal_error)?;
+    }
     app_api::settings_put(
         &state,
         app_api::SettingsPutRequest {
             codex_home,
             context_active_minutes,
+            monthly_budget_usd,
+            auto_ingest_secs,
+            launch_at_login,
+            global_shortcut,
+            tracking_paused,
+            limit_warn_percent,
+            limit_critical_percent,
+            active_session_rule,
+            slack_webhook_url,
+            discord_webhook_url,
+            daily_summary,
         },
     )
     .map_err(to_error)
 }
+
+#[tauri::command]
+pub fn settings_export(state: State<DesktopState>) -> Result<SettingsBundle, TrackerError> {
+    app_api::settings_export(&state).map_err(to_error)
+}
+
+#[tauri::command]
+pub fn settings_import(
+    app: AppHandle,
+    state: State<DesktopState>,
+    bundle: SettingsBundle,
+) -> Result<SettingsImportResponse, TrackerError> {
+    autostart::apply(&app, bundle.settings.launch_at_login).map_err(internal_error)?;
+    shortcut::apply(&app, &bundle.settings.global_shortcut).map_err(internal_error)?;
+    app_api::settings_import(&state, bundle).map_err(to_error)
+}
+
+#[tauri::command]
+pub async fn notifications_test(
+    state: State<'_, DesktopState>,
+) -> Result<SentResponse, TrackerError> {
+    let notifications = state.app_state.services.notifications.clone();
+    let sent = tauri::async_runtime::spawn_blocking(move || notifications.send_test())
+        .await
+        .map_err(|err| internal_error(format!("notifications task: {}", err)))?
+        .map_err(to_error)?;
+    Ok(SentResponse { sent: sent as i64 })
+}
@@ -0,0 +1,6 @@
+use tracker_app::version::VersionInfo;
+
+#[tauri::command]
+pub fn version() -> VersionInfo {
+    app_api::version()
+}
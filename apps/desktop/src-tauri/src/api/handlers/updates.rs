@@ -0,0 +1,63 @@
+use serde::Serialize;
+use tauri::AppHandle;
+use tauri_plugin_updater::{Update, UpdaterExt};
+use tracker_error::TrackerError;
+
+use crate::api::internal_error;
+
+/// Minisign public key matching the release signing key, set by the release
+/// workflow. Builds without it (local and CI builds) cannot verify updates, so
+/// checking fails. The release manifest endpoint is in `tauri.conf.json`.
+const UPDATE_PUBKEY: Option<&str> = option_env!("CODEX_TRACKER_UPDATER_PUBKEY");
+
+#[derive(Debug, Serialize)]
+pub struct UpdateCheck {
+    pub current_version: String,
+    /// `None` when this is the latest release.
+    pub version: Option<String>,
+    pub notes: Option<String>,
+    pub date: Option<String>,
+}
+
+#[tauri::command]
+pub async fn updates_check(app: AppHandle) -> Result<UpdateCheck, TrackerError> {
+    let update = find_update(&app).await?;
+    Ok(UpdateCheck {
+        current_version: app.package_info().version.to_string(),
+        version: update.as_ref().map(|update| update.version.clone()),
+        notes: update.as_ref().and_then(|update| update.body.clone()),
+        date: update
+            .as_ref()
+            .and_then(|update| update.date.map(|date| date.to_string())),
+    })
+}
+
+/// Downloads and installs the latest release, then restarts into it.
+/// Returns without restarting when already up to date.
+#[tauri::command]
+pub async fn updates_install(app: AppHandle) -> Result<(), TrackerError> {
+    let Some(update) = find_update(&app).await? else {
+        return Ok(());
+    };
+    tracing::info!(version = %update.version, "installing update");
+    update
+        .download_and_install(|_, _| {}, || {})
+        .await
+        .map_err(|err| internal_error(format!("install update: {err}")))?;
+    app.restart();
+}
+
+async fn find_update(app: &AppHandle) -> Result<Option<Update>, TrackerError> {
+    let Some(pubkey) = UPDATE_PUBKEY else {
+        return Err(internal_error(
+            "this build of Codex Tracker cannot check for updates",
+        ));
+    };
+    app.updater_builder()
+        .pubkey(pubkey)
+        .build()
+        .map_err(internal_error)?
+        .check()
+        .await
+        .map_err(|err| internal_error(format!("check for updates: {err}")))
+}
@@ -0,0 +1,52 @@
+use tauri::State;
+
+use crate::api::to_error;
+use crate::app::DesktopState;
+use tracker_core::{Webhook, WebhookDelivery};
+use tracker_error::TrackerError;
+
+#[tauri::command]
+pub fn webhooks_list(state: State<DesktopState>) -> Result<Vec<Webhook>, TrackerError> {
+    app_api::webhooks_list(&state).map_err(to_error)
+}
+
+#[tauri::command]
+pub fn webhooks_create(
+    state: State<DesktopState>,
+    url: String,
+    secret: Option<String>,
+    event_types: Vec<String>,
+) -> Result<Webhook, TrackerError> {
+    app_api::webhooks_create(
+        &state,
+        app_api::WebhooksCreateRequest {
+            url,
+            secret,
+            event_types,
+        },
+    )
+    .map_err(to_error)
+}
+
+#[tauri::command]
+pub fn webhooks_delete(
+    state: State<DesktopState>,
+    id: i64,
+) -> Result<serde_json::Value, TrackerError> {
+    let response = app_api::webhooks_delete(&state, app_api::WebhooksDeleteRequest { id })
+        .map_err(to_error)?;
+    Ok(serde_json::json!({ "deleted": response.deleted }))
+}
+
+#[tauri::command]
+pub fn webhook_deliveries(
+    state: State<DesktopState>,
+    webhook_id: Option<i64>,
+    limit: Option<u32>,
+) -> Result<Vec<WebhookDelivery>, TrackerError> {
+    app_api::webhook_deliveries(
+        &state,
+        app_api::WebhookDeliveriesRequest { webhook_id, limit },
+    )
+    .map_err(to_error)
+}
@@ -1,5 +1,15 @@
+use tracker_app::AppError;
+use tracker_error::{ErrorCode, TrackerError};
+
 pub(crate) mod handlers;
 
-pub(crate) fn to_error(err: impl std::fmt::Display) -> String {
-    err.to_string()
+/// Commands reject with `{ code, message }`, the same codes the HTTP API
+/// returns, so the dashboard handles errors one way on both transports.
+pub(crate) fn to_error(err: AppError) -> TrackerError {
+    err.into()
+}
+
+/// Failures outside the app layer: dialogs, plugins, and background tasks.
+pub(crate) fn internal_error(err: impl std::fmt::Display) -> TrackerError {
+    TrackerError::new(ErrorCode::Internal, err.to_string())
 }
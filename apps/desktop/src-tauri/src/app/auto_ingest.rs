@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::app::DesktopState;
+
+/// How long to wait before checking again while auto-ingest is off.
+const DISABLED_POLL: Duration = Duration::from_secs(60);
+
+/// Re-ingests the active home every `auto_ingest_secs` (a setting, so changes
+/// apply from the next tick) and emits `data-updated` when new usage arrived,
+/// keeping an open dashboard current. Skipped while tracking is paused.
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let secs = app
+                .state::<DesktopState>()
+                .app_state
+                .services
+                .settings
+                .get()
+                .map(|settings| settings.auto_ingest_secs)
+                .unwrap_or_else(|err| {
+                    tracing::warn!(error = %err, "failed to read auto-ingest interval");
+                    0
+                });
+            if secs == 0 {
+                tokio::time::sleep(DISABLED_POLL).await;
+                continue;
+            }
+            tokio::time::sleep(Duration::from_secs(secs.into())).await;
+            let app = app.clone();
+            if let Err(err) = tauri::async_runtime::spawn_blocking(move || ingest(&app)).await {
+                tracing::warn!(error = %err, "auto-ingest task failed");
+            }
+        }
+    });
+}
+
+/// Runs one ingest and tells the webview when it stored new usage, messages,
+/// or limit readings.
+pub fn ingest(app: &AppHandle) {
+    let state = app.state::<DesktopState>();
+    match state.app_state.services.ingest.run() {
+        Ok(stats) if stats.rows_inserted() > 0 => {
+            if let Err(err) = app.emit("data-updated", stats) {
+                tracing::warn!(error = %err, "failed to emit data-updated");
+            }
+        }
+        Ok(_) => {}
+        Err(err) => tracing::warn!(error = %err, "auto-ingest failed"),
+    }
+}
@@ -0,0 +1,42 @@
+use tauri::{AppHandle, Manager};
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::app::DesktopState;
+
+/// Registers or removes the app as an OS login item (a launch agent on
+/// macOS, the registry on Windows, an XDG autostart entry on Linux).
+pub fn apply(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    let autolaunch = app.autolaunch();
+    let result = if enabled {
+        autolaunch.enable()
+    } else {
+        autolaunch.disable()
+    };
+    result.map_err(|err| format!("update login item: {err}"))
+}
+
+/// Brings the login item in line with the stored `launch_at_login` setting,
+/// e.g. after the app moved and the old entry points at a stale path.
+pub fn sync(app: &AppHandle) {
+    let enabled = match app
+        .state::<DesktopState>()
+        .app_state
+        .services
+        .settings
+        .get()
+    {
+        Ok(settings) => settings.launch_at_login,
+        Err(err) => {
+            tracing::warn!(error = %err, "failed to read launch at login setting");
+            return;
+        }
+    };
+    // Enabling again rewrites the entry with the current executable path.
+    let registered = app.autolaunch().is_enabled().unwrap_or(false);
+    if !enabled && !registered {
+        return;
+    }
+    if let Err(err) = apply(app, enabled) {
+        tracing::warn!(error = %err, "failed to sync login item");
+    }
+}
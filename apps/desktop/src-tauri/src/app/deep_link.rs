@@ -0,0 +1,89 @@
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::app::open_dashboard;
+
+pub const SCHEME: &str = "codex-tracker";
+/// Dashboard ranges a `codex-tracker://range/<range>` link may select.
+const RANGES: [&str; 5] = ["today", "last7days", "last14days", "thismonth", "alltime"];
+
+/// The dashboard view a `codex-tracker://` link asks for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "view", rename_all = "snake_case")]
+pub enum DeepLink {
+    /// `codex-tracker://session/<id>`
+    Session { session_id: String },
+    /// `codex-tracker://range/<range>`
+    Range { range: String },
+}
+
+impl DeepLink {
+    pub fn parse(url: &str) -> Option<Self> {
+        let rest = url.strip_prefix(SCHEME)?.strip_prefix("://")?;
+        let rest = rest.split(['?', '#']).next().unwrap_or_default();
+        let (kind, value) = rest.split_once('/')?;
+        let value = value.trim_end_matches('/');
+        if value.is_empty() {
+            return None;
+        }
+        match kind {
+            "session" => Some(DeepLink::Session {
+                session_id: value.to_string(),
+            }),
+            "range" if RANGES.contains(&value) => Some(DeepLink::Range {
+                range: value.to_string(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// A link that opened the app before the webview could listen for
+/// `deep-link` events; the dashboard takes it once it has loaded.
+#[derive(Default)]
+pub struct PendingDeepLink(Mutex<Option<DeepLink>>);
+
+impl PendingDeepLink {
+    pub fn take(&self) -> Option<DeepLink> {
+        self.0.lock().ok().and_then(|mut link| link.take())
+    }
+}
+
+/// Handles links that launch the app and links opened while it runs.
+pub fn setup(app: &tauri::App) -> tauri::Result<()> {
+    app.manage(PendingDeepLink::default());
+    // macOS registers the scheme from the bundle; elsewhere register it at
+    // runtime so links work in development builds too.
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(err) = app.deep_link().register_all() {
+        tracing::warn!(error = %err, "failed to register the codex-tracker:// scheme");
+    }
+    if let Ok(Some(urls)) = app.deep_link().get_current()
+        && let Some(link) = urls.iter().find_map(|url| DeepLink::parse(url.as_str()))
+        && let Ok(mut pending) = app.state::<PendingDeepLink>().0.lock()
+    {
+        *pending = Some(link);
+    }
+    let handle = app.handle().clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            open(&handle, url.as_str());
+        }
+    });
+    Ok(())
+}
+
+/// Brings the dashboard forward and tells it which view to show.
+pub fn open(app: &AppHandle, url: &str) {
+    let Some(link) = DeepLink::parse(url) else {
+        tracing::warn!(url, "ignoring unsupported deep link");
+        return;
+    };
+    open_dashboard(app);
+    if let Err(err) = app.emit("deep-link", link) {
+        tracing::warn!(error = %err, "failed to emit deep link");
+    }
+}
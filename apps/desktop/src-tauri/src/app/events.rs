@@ -0,0 +1,15 @@
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::app::DesktopState;
+
+/// Forwards every app event to the webviews as `app-event`, so open windows
+/// refresh after repricing or a home switch made from another window.
+pub fn forward(app: AppHandle) {
+    let state = app.state::<DesktopState>();
+    let events = state.app_state.services.events.clone();
+    events.subscribe(move |event, _| {
+        if let Err(err) = app.emit("app-event", event) {
+            tracing::warn!(error = %err, "failed to emit app-event");
+        }
+    });
+}
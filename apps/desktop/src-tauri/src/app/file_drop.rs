@@ -0,0 +1,89 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::{AppHandle, DragDropEvent, Emitter, Manager, Window, WindowEvent};
+
+use crate::app::DesktopState;
+
+/// Extensions accepted from a drop; everything else is left out.
+const LOG_EXTENSIONS: [&str; 2] = ["jsonl", "log"];
+
+/// What the webview is told after a drop, so it can show a toast.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum DropOutcome {
+    Ingested {
+        files: usize,
+        events_inserted: usize,
+    },
+    Failed {
+        message: String,
+    },
+}
+
+/// Ingests `.jsonl`/`.log` files dropped on the window into the active home
+/// and emits `logs-dropped` with the outcome. New usage also emits
+/// `data-updated`, like background auto-ingest.
+pub fn handle(window: &Window, event: &WindowEvent) {
+    let WindowEvent::DragDrop(DragDropEvent::Drop { paths, .. }) = event else {
+        return;
+    };
+    let files = log_files(paths);
+    let app = window.app_handle().clone();
+    if files.is_empty() {
+        emit(
+            &app,
+            DropOutcome::Failed {
+                message: "Drop .jsonl or .log files to ingest them".to_string(),
+            },
+        );
+        return;
+    }
+    tauri::async_runtime::spawn_blocking(move || ingest(&app, &files));
+}
+
+fn ingest(app: &AppHandle, files: &[PathBuf]) {
+    let state = app.state::<DesktopState>();
+    let outcome = match app_api::ingest_files(&state, files) {
+        Ok(stats) => {
+            if stats.events_inserted > 0
+                && let Err(err) = app.emit("data-updated", &stats)
+            {
+                tracing::warn!(error = %err, "failed to emit data-updated");
+            }
+            DropOutcome::Ingested {
+                files: stats.files_scanned,
+                events_inserted: stats.events_inserted,
+            }
+        }
+        Err(err) => {
+            tracing::warn!(error = %err, "dropped log ingest failed");
+            DropOutcome::Failed {
+                message: err.to_string(),
+            }
+        }
+    };
+    emit(app, outcome);
+}
+
+fn emit(app: &AppHandle, outcome: DropOutcome) {
+    if let Err(err) = app.emit("logs-dropped", outcome) {
+        tracing::warn!(error = %err, "failed to emit logs-dropped");
+    }
+}
+
+fn log_files(paths: &[PathBuf]) -> Vec<PathBuf> {
+    paths
+        .iter()
+        .filter(|path| is_log_file(path))
+        .cloned()
+        .collect()
+}
+
+fn is_log_file(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| LOG_EXTENSIONS.contains(&ext))
+}
@@ -0,0 +1,24 @@
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+const LABEL: &str = "mini";
+
+/// Opens the always-on-top mini window (today's cost, 5h window, active
+/// sessions), or closes it when it is open. Returns whether it is now shown.
+pub fn toggle(app: &AppHandle) -> tauri::Result<bool> {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        window.close()?;
+        return Ok(false);
+    }
+    // The same bundle as the dashboard; the marker makes it render the
+    // widget instead.
+    WebviewWindowBuilder::new(app, LABEL, WebviewUrl::App("index.html".into()))
+        .initialization_script("window.__CODEX_TRACKER_VIEW__ = \"mini\";")
+        .title("Codex Tracker")
+        .inner_size(200.0, 120.0)
+        .resizable(false)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .build()?;
+    Ok(true)
+}
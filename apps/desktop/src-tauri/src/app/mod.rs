@@ -1,3 +1,28 @@
+pub mod auto_ingest;
+pub mod autostart;
+pub mod deep_link;
+pub mod events;
+pub mod file_drop;
+pub mod mini;
+pub mod notify;
+pub mod shortcut;
 pub mod startup;
+pub mod tray;
+
+use tauri::{AppHandle, Manager};
 
 pub type DesktopState = app_api::AppContext;
+
+/// Shows, restores, and focuses the main window.
+pub fn open_dashboard(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let shown = window
+        .show()
+        .and_then(|_| window.unminimize())
+        .and_then(|_| window.set_focus());
+    if let Err(err) = shown {
+        tracing::warn!(error = %err, "failed to show the dashboard");
+    }
+}
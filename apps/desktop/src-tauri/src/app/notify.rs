@@ -0,0 +1,47 @@
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tracker_app::alerts::{self, AlertSnapshot, DESKTOP_ALERT_THRESHOLDS};
+use tracker_app::notifications::Message;
+
+use crate::app::DesktopState;
+
+/// Raises a native notification when a limit window or the monthly budget
+/// crosses one of [`DESKTOP_ALERT_THRESHOLDS`] between two checks.
+#[derive(Default)]
+pub struct AlertNotifier {
+    last: Option<AlertSnapshot>,
+}
+
+impl AlertNotifier {
+    /// The first check only records a baseline, so restarting the app does
+    /// not repeat notifications for levels already passed.
+    pub fn check(&mut self, app: &AppHandle) {
+        let state = app.state::<DesktopState>();
+        let snapshot = match state.app_state.services.limits.alert_snapshot() {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                tracing::warn!(error = %err, "failed to read alert levels");
+                return;
+            }
+        };
+        let crossed = match &self.last {
+            Some(before) => {
+                alerts::crossed_thresholds(before, &snapshot, &DESKTOP_ALERT_THRESHOLDS)
+            }
+            None => Vec::new(),
+        };
+        self.last = Some(snapshot);
+        for alert in crossed {
+            let message = Message::alert(&alert);
+            if let Err(err) = app
+                .notification()
+                .builder()
+                .title(&message.title)
+                .body(message.lines.join("\n"))
+                .show()
+            {
+                tracing::warn!(error = %err, "failed to show notification");
+            }
+        }
+    }
+}
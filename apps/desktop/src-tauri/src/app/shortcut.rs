@@ -0,0 +1,71 @@
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::app::{DesktopState, open_dashboard};
+
+/// Replaces the registered global shortcut with `accelerator` (e.g.
+/// `CommandOrControl+Shift+U`); an empty one just removes it. Fails without
+/// changing anything when the accelerator does not parse.
+pub fn apply(app: &AppHandle, accelerator: &str) -> Result<(), String> {
+    let accelerator = accelerator.trim();
+    let shortcut = if accelerator.is_empty() {
+        None
+    } else {
+        Some(
+            accelerator
+                .parse::<Shortcut>()
+                .map_err(|err| format!("invalid shortcut {accelerator:?}: {err}"))?,
+        )
+    };
+    let shortcuts = app.global_shortcut();
+    shortcuts
+        .unregister_all()
+        .map_err(|err| format!("clear global shortcut: {err}"))?;
+    if let Some(shortcut) = shortcut {
+        shortcuts
+            .register(shortcut)
+            .map_err(|err| format!("register {accelerator}: {err}"))?;
+    }
+    Ok(())
+}
+
+/// Registers the stored `global_shortcut` setting at startup.
+pub fn sync(app: &AppHandle) {
+    let accelerator = match app
+        .state::<DesktopState>()
+        .app_state
+        .services
+        .settings
+        .get()
+    {
+        Ok(settings) => settings.global_shortcut,
+        Err(err) => {
+            tracing::warn!(error = %err, "failed to read global shortcut setting");
+            return;
+        }
+    };
+    // Another app may already own the combination; the dashboard still works.
+    if let Err(err) = apply(app, &accelerator) {
+        tracing::warn!(error = %err, "failed to register global shortcut");
+    }
+}
+
+/// Hides the dashboard when it is in front, and brings it up otherwise.
+pub fn handle(app: &AppHandle, _shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let in_front = window.is_visible().unwrap_or(false)
+        && !window.is_minimized().unwrap_or(false)
+        && window.is_focused().unwrap_or(false);
+    if !in_front {
+        open_dashboard(app);
+        return;
+    }
+    if let Err(err) = window.hide() {
+        tracing::warn!(error = %err, "failed to hide the dashboard");
+    }
+}
@@ -34,10 +34,10 @@ pub fn initialize(app: &tauri::App) -> Result<DesktopState, Box<dyn std::error::
         return Err(boxed_err(format!("failed to initialize database: {}", err)));
     }
     if is_fresh_db && let Err(err) = app_state.apply_pricing_defaults() {
-        eprintln!("failed to apply pricing defaults: {}", err);
+        tracing::warn!(error = %err, "failed to apply pricing defaults");
     }
     if let Err(err) = app_state.sync_pricing_defaults() {
-        eprintln!("failed to sync pricing defaults: {}", err);
+        tracing::warn!(error = %err, "failed to sync pricing defaults");
     }
     let refresh_state = app_state.clone();
     let app_handle = app.handle().clone();
@@ -46,11 +46,11 @@ pub fn initialize(app: &tauri::App) -> Result<DesktopState, Box<dyn std::error::
         match result {
             Ok(stats) => {
                 if let Err(err) = app_handle.emit("ingest:complete", stats) {
-                    eprintln!("failed to emit ingest complete: {}", err);
+                    tracing::warn!(error = %err, "failed to emit ingest complete");
                 }
             }
             Err(err) => {
-                eprintln!("failed to refresh data on startup: {}", err);
+                tracing::error!(error = %err, "failed to refresh data on startup");
             }
         }
     });
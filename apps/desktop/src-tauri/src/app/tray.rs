@@ -0,0 +1,163 @@
+use std::thread;
+use std::time::Duration;
+
+use app_api::LimitsResponse;
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::app::mini;
+use crate::app::notify::AlertNotifier;
+use crate::app::{DesktopState, open_dashboard};
+
+const TRAY_ID: &str = "main";
+/// How often the tray refreshes the limit percentage and checks notification
+/// thresholds.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Adds the tray (menu bar on macOS) item showing how much of the 5h window
+/// is used, and starts the timer that keeps it current and raises limit and
+/// budget notifications. Ingesting is left to
+/// [`crate::app::auto_ingest`].
+pub fn setup(app: &tauri::App) -> tauri::Result<()> {
+    let open = MenuItem::with_id(app, "open", "Open dashboard", true, None::<&str>)?;
+    let ingest = MenuItem::with_id(app, "ingest", "Run ingest now", true, None::<&str>)?;
+    let paused = is_paused(app.handle());
+    let pause = CheckMenuItem::with_id(app, "pause", "Pause tracking", true, paused, None::<&str>)?;
+    let mini_widget =
+        CheckMenuItem::with_id(app, "mini", "Mini widget", true, false, None::<&str>)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let quit = PredefinedMenuItem::quit(app, None)?;
+    let menu = Menu::with_items(
+        app,
+        &[&open, &ingest, &pause, &mini_widget, &separator, &quit],
+    )?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Codex Tracker")
+        .menu(&menu)
+        .show_menu_on_left_click(true);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    let pause_item = pause.clone();
+    builder
+        .on_menu_event(move |app, event| match event.id().as_ref() {
+            "open" => open_dashboard(app),
+            "ingest" => {
+                let app = app.clone();
+                tauri::async_runtime::spawn_blocking(move || run_ingest(&app));
+            }
+            "pause" => {
+                let paused = !is_paused(app);
+                let settings = &app.state::<DesktopState>().app_state.services.settings;
+                if let Err(err) = settings.set_tracking_paused(paused) {
+                    tracing::warn!(error = %err, "failed to pause tracking");
+                }
+                sync_pause_item(&pause_item, is_paused(app));
+                refresh(app);
+            }
+            "mini" => {
+                let shown = mini::toggle(app).unwrap_or_else(|err| {
+                    tracing::warn!(error = %err, "failed to toggle the mini widget");
+                    false
+                });
+                if let Err(err) = mini_widget.set_checked(shown) {
+                    tracing::warn!(error = %err, "failed to update mini widget menu item");
+                }
+            }
+            _ => {}
+        })
+        .build(app)?;
+
+    let app = app.handle().clone();
+    refresh(&app);
+    thread::spawn(move || {
+        let mut notifier = AlertNotifier::default();
+        notifier.check(&app);
+        loop {
+            thread::sleep(REFRESH_INTERVAL);
+            // Tracking can also be paused from Settings or the HTTP API.
+            sync_pause_item(&pause, is_paused(&app));
+            refresh(&app);
+            notifier.check(&app);
+        }
+    });
+    Ok(())
+}
+
+/// Ingests the active home, tells the dashboard, and refreshes the tray.
+fn run_ingest(app: &AppHandle) {
+    let state = app.state::<DesktopState>();
+    match state.app_state.services.ingest.run() {
+        Ok(stats) => {
+            if let Err(err) = app.emit("ingest:complete", stats) {
+                tracing::warn!(error = %err, "failed to emit ingest complete");
+            }
+        }
+        Err(err) => tracing::warn!(error = %err, "tray ingest failed"),
+    }
+    refresh(app);
+}
+
+fn refresh(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let latest = match app_api::limits_latest(&app.state::<DesktopState>()) {
+        Ok(latest) => latest,
+        Err(err) => {
+            tracing::warn!(error = %err, "failed to read limits for the tray");
+            return;
+        }
+    };
+    let (title, tooltip) = labels(&latest, is_paused(app));
+    // Only macOS shows a title next to the icon; elsewhere the tooltip
+    // carries the percentage.
+    if let Err(err) = tray
+        .set_title(Some(title))
+        .and_then(|_| tray.set_tooltip(Some(tooltip)))
+    {
+        tracing::warn!(error = %err, "failed to update the tray");
+    }
+}
+
+/// The stored `tracking_paused` setting, shared with the CLI and HTTP API.
+fn is_paused(app: &AppHandle) -> bool {
+    let settings = &app.state::<DesktopState>().app_state.services.settings;
+    settings
+        .get()
+        .map(|settings| settings.tracking_paused)
+        .unwrap_or_else(|err| {
+            tracing::warn!(error = %err, "failed to read tracking paused setting");
+            false
+        })
+}
+
+fn sync_pause_item(item: &CheckMenuItem<tauri::Wry>, paused: bool) {
+    if let Err(err) = item.set_checked(paused) {
+        tracing::warn!(error = %err, "failed to update pause menu item");
+    }
+}
+
+fn labels(latest: &LimitsResponse, paused: bool) -> (String, String) {
+    let mut title = match &latest.primary {
+        Some(status) => format!("5h {:.0}%", status.used_percent),
+        None => "5h –".to_string(),
+    };
+    let mut tooltip = match &latest.primary {
+        Some(status) => format!(
+            "Codex Tracker: 5h limit {:.0}% used, resets in {}",
+            status.used_percent, status.resets_in
+        ),
+        None => "Codex Tracker: no 5h limit data yet".to_string(),
+    };
+    if let Some(status) = &latest.secondary {
+        tooltip.push_str(&format!(", 7d limit {:.0}% used", status.used_percent));
+    }
+    if paused {
+        title.push_str(" (paused)");
+        tooltip.push_str(" (tracking paused)");
+    }
+    (title, tooltip)
+}
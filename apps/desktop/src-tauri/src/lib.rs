@@ -2,44 +2,133 @@ mod api;
 mod app;
 
 use tauri::Manager;
+use tauri_plugin_window_state::StateFlags;
 
 pub fn run() {
+    tracker_app::init_tracing();
     tauri::Builder::default()
+        // Registered first so a second launch (e.g. from a deep link on
+        // Windows or Linux) hands its URL to the running app and exits.
+        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+            app::open_dashboard(app);
+        }))
+        .plugin(tauri_plugin_autostart::init(
+            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+            None,
+        ))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_fs::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(app::shortcut::handle)
+                .build(),
+        )
+        // Restores the main window's size, position, and maximized state when
+        // it is created, and saves them on close and exit. Visibility is left
+        // out so a launch at login still opens the dashboard; the selected
+        // range is kept by the webview and the home in the database.
+        .plugin(
+            tauri_plugin_window_state::Builder::new()
+                .with_state_flags(StateFlags::all() & !StateFlags::VISIBLE)
+                .build(),
+        )
         .setup(|app| {
             let state = app::startup::initialize(app)?;
             app.manage(state);
+            app::events::forward(app.handle().clone());
+            app::tray::setup(app)?;
+            app::auto_ingest::spawn(app.handle().clone());
+            app.state::<app::DesktopState>()
+                .app_state
+                .services
+                .schedules
+                .clone()
+                .spawn();
+            app::autostart::sync(app.handle());
+            app::shortcut::sync(app.handle());
+            app::deep_link::setup(app)?;
             Ok(())
         })
+        .on_window_event(app::file_drop::handle)
         .invoke_handler(tauri::generate_handler![
             api::handlers::analytics::summary,
             api::handlers::analytics::context_latest,
             api::handlers::analytics::context_sessions,
             api::handlers::analytics::context_stats,
             api::handlers::analytics::timeseries,
+            api::handlers::analytics::timeseries_grouped,
             api::handlers::analytics::breakdown,
+            api::handlers::analytics::breakdown_homes,
+            api::handlers::analytics::breakdown_users,
+            api::handlers::analytics::breakdown_providers,
             api::handlers::analytics::breakdown_tokens,
             api::handlers::analytics::breakdown_costs,
             api::handlers::analytics::breakdown_effort_tokens,
             api::handlers::analytics::breakdown_effort_costs,
+            api::handlers::analytics::models,
+            api::handlers::analytics::efforts,
+            api::handlers::analytics::sources,
+            api::handlers::analytics::dashboard,
             api::handlers::analytics::events,
+            api::handlers::analytics::event,
+            api::handlers::analytics::context_peaks,
+            api::handlers::analytics::top_requests,
+            api::handlers::analytics::activity,
+            api::handlers::analytics::activity_by_weekday,
+            api::handlers::analytics::compactions,
+            api::handlers::analytics::session_lifetimes,
+            api::handlers::analytics::session_timeline,
+            api::handlers::deep_link::deep_link_pending,
+            api::handlers::export::export_data,
             api::handlers::limits::limits_latest,
             api::handlers::limits::limits_current,
             api::handlers::limits::limits_7d_windows,
+            api::handlers::limits::custom_limits_list,
+            api::handlers::limits::custom_limits_create,
+            api::handlers::limits::custom_limits_delete,
+            api::handlers::limits::limits_snapshots,
             api::handlers::ingest::ingest,
+            api::handlers::ingest::refresh,
+            api::handlers::ingest::ingest_cursors,
+            api::handlers::ingest::ingest_cursors_reset,
             api::handlers::logs::open_logs_dir,
             api::handlers::pricing::pricing_list,
             api::handlers::pricing::pricing_replace,
             api::handlers::pricing::pricing_recompute,
+            api::handlers::pricing::pricing_recompute_status,
+            api::handlers::pricing::model_aliases_list,
+            api::handlers::pricing::model_aliases_replace,
             api::handlers::settings::settings_get,
             api::handlers::settings::settings_put,
+            api::handlers::settings::settings_export,
+            api::handlers::settings::settings_import,
+            api::handlers::settings::notifications_test,
+            api::handlers::system::version,
+            api::handlers::updates::updates_check,
+            api::handlers::updates::updates_install,
             api::handlers::homes::homes_list,
             api::handlers::homes::homes_create,
+            api::handlers::homes::homes_inspect,
+            api::handlers::homes::homes_discover,
             api::handlers::homes::homes_set_active,
+            api::handlers::homes::homes_rename,
+            api::handlers::homes::homes_update,
+            api::handlers::homes::homes_archive,
+            api::handlers::homes::homes_unarchive,
             api::handlers::homes::homes_delete,
-            api::handlers::homes::homes_clear_data
+            api::handlers::homes::homes_clear_data,
+            api::handlers::webhooks::webhooks_list,
+            api::handlers::webhooks::webhooks_create,
+            api::handlers::webhooks::webhooks_delete,
+            api::handlers::webhooks::webhook_deliveries,
+            api::handlers::schedules::schedules_list,
+            api::handlers::schedules::schedules_create,
+            api::handlers::schedules::schedules_delete,
+            api::handlers::schedules::schedules_send
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
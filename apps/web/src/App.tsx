@@ -1,13 +1,17 @@
 import { useCallback, useEffect, useState } from "react";
+import { takePendingDeepLink } from "./data/codexApi";
+import type { DeepLink, DropOutcome } from "./domain/types";
 import { DashboardPage } from "./features/dashboard/DashboardPage";
 import { SettingsPage } from "./features/settings/SettingsPage";
 import { useSettingsState } from "./features/settings/useSettingsState";
 import { Toast, type ToastMessage } from "./features/shared/Toast";
+import { isTauriRuntime } from "./shared/tauri";
 
 export default function App() {
   const [toast, setToast] = useState<ToastMessage | null>(null);
   const [isSettingsOpen, setIsSettingsOpen] = useState(false);
   const [dashboardRefreshToken, setDashboardRefreshToken] = useState(0);
+  const [deepLink, setDeepLink] = useState<DeepLink | null>(null);
 
   const handleToast = useCallback((message: ToastMessage) => {
     setToast(message);
@@ -22,6 +26,80 @@ export default function App() {
     onDashboardRefresh: requestDashboardRefresh
   });
 
+  useEffect(() => {
+    if (!isTauriRuntime()) {
+      return;
+    }
+    const openLink = (link: DeepLink) => {
+      setIsSettingsOpen(false);
+      setDeepLink(link);
+    };
+    let unlisten: (() => void) | null = null;
+    let cancelled = false;
+    (async () => {
+      try {
+        const { listen } = await import("@tauri-apps/api/event");
+        if (cancelled) {
+          return;
+        }
+        unlisten = await listen<DeepLink>("deep-link", (event) => openLink(event.payload));
+        const pending = await takePendingDeepLink();
+        if (pending && !cancelled) {
+          openLink(pending);
+        }
+      } catch (err) {
+        handleToast({
+          message: err instanceof Error ? err.message : "Deep links unavailable",
+          tone: "info"
+        });
+      }
+    })();
+    return () => {
+      cancelled = true;
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, [handleToast]);
+
+  useEffect(() => {
+    if (!isTauriRuntime()) {
+      return;
+    }
+    let unlisten: (() => void) | null = null;
+    let cancelled = false;
+    (async () => {
+      try {
+        const { listen } = await import("@tauri-apps/api/event");
+        if (cancelled) {
+          return;
+        }
+        unlisten = await listen<DropOutcome>("logs-dropped", (event) => {
+          const outcome = event.payload;
+          if (outcome.status === "failed") {
+            handleToast({ message: outcome.message, tone: "error" });
+            return;
+          }
+          const files = outcome.files === 1 ? "1 file" : `${outcome.files} files`;
+          const events =
+            outcome.events_inserted === 1 ? "1 event" : `${outcome.events_inserted} events`;
+          handleToast({ message: `Ingested ${events} from ${files}`, tone: "info" });
+        });
+      } catch (err) {
+        handleToast({
+          message: err instanceof Error ? err.message : "File drop unavailable",
+          tone: "info"
+        });
+      }
+    })();
+    return () => {
+      cancelled = true;
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, [handleToast]);
+
   useEffect(() => {
     if (!toast) {
       return;
@@ -44,6 +122,8 @@ export default function App() {
         <DashboardPage
           activeMinutes={settingsState.activeMinutes}
           refreshToken={dashboardRefreshToken}
+          deepLink={deepLink}
+          onDeepLinkHandled={() => setDeepLink(null)}
           onOpenSettings={() => setIsSettingsOpen(true)}
           onToast={handleToast}
         />
@@ -8,34 +8,77 @@ function getCsrfToken(): string | undefined {
   return token && token.length > 0 ? token : undefined;
 }
 
+/** A failed command, with the stable code from the backend (`db_locked`, `bad_range`, ...). */
+export class CommandError extends Error {
+  readonly code: string;
+
+  constructor(code: string, message: string) {
+    super(message);
+    this.name = "CommandError";
+    this.code = code;
+  }
+}
+
+type ErrorPayload = { code?: string; message?: string };
+
+type CachedResponse = { etag: string; body: unknown };
+
+// Last ETag-tagged response per command + args, replayed on 304 Not Modified.
+const responseCache = new Map<string, CachedResponse>();
+
 export async function invokeCommand<T>(command: string, args?: CommandArgs): Promise<T> {
   if (isTauriRuntime()) {
     const { invoke } = await import("@tauri-apps/api/core");
-    return invoke<T>(command, args);
+    try {
+      return await invoke<T>(command, args);
+    } catch (err) {
+      if (typeof err === "string") {
+        throw new CommandError("internal", err);
+      }
+      const payload = err as ErrorPayload;
+      throw new CommandError(payload?.code ?? "internal", payload?.message ?? "Command failed");
+    }
   }
 
   const csrfToken = getCsrfToken();
+  const body = JSON.stringify(args ?? {});
+  const cacheKey = `${command}:${body}`;
+  const cached = responseCache.get(cacheKey);
   const response = await fetch(`/api/${command}`, {
     method: "POST",
     headers: {
       "Content-Type": "application/json",
-      ...(csrfToken ? { "X-Codex-Token": csrfToken } : {})
+      ...(csrfToken ? { "X-Codex-Token": csrfToken } : {}),
+      ...(cached ? { "If-None-Match": cached.etag } : {})
     },
-    body: JSON.stringify(args ?? {})
+    body
   });
 
+  if (response.status === 304 && cached) {
+    return cached.body as T;
+  }
+
   if (!response.ok) {
     let message = `Request failed (${response.status})`;
+    let code = "internal";
     try {
-      const payload = (await response.json()) as { message?: string };
+      const payload = (await response.json()) as ErrorPayload;
       if (payload?.message) {
         message = payload.message;
       }
+      if (payload?.code) {
+        code = payload.code;
+      }
     } catch {
       // ignore JSON parse errors
     }
-    throw new Error(message);
+    throw new CommandError(code, message);
   }
 
-  return response.json() as Promise<T>;
+  const payload = (await response.json()) as T;
+  const etag = response.headers.get("ETag");
+  if (etag) {
+    responseCache.set(cacheKey, { etag, body: payload });
+  }
+  return payload;
 }
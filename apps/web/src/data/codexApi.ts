@@ -1,25 +1,40 @@
 import type {
   ActiveSession,
+  ActiveSessionRule,
   ActiveSessionsParams,
   CodexHome,
   ContextPressureStats,
+  DashboardParams,
+  DashboardResponse,
+  DeepLink,
   EventsParams,
+  EventsResponse,
+  HomeBreakdown,
+  HomeCandidate,
+  HomeInspection,
   HomesResponse,
   IngestStats,
   LimitsResponse,
   ModelCostBreakdown,
+  ModelAlias,
   ModelEffortCostBreakdown,
+  PricingRecomputeResponse,
   PricingRule,
   PricingRuleApi,
   RangeParams,
+  SessionTimeline,
   SettingsResponse,
   TimeSeriesParams,
   TimeSeriesPoint,
-  UsageEvent,
   UsageLimitCurrentResponse,
+  UsageLimitSnapshot,
   UsageLimitWindow,
-  UsageSummary
+  UpdateCheck,
+  UsageSummary,
+  UserBreakdown,
+  VersionInfo
 } from "../domain/types";
+import { isTauriRuntime } from "../shared/tauri";
 import { invokeCommand } from "./client";
 
 export async function getSummary(params: RangeParams): Promise<UsageSummary> {
@@ -30,6 +45,14 @@ export async function getTimeSeries(params: TimeSeriesParams): Promise<TimeSerie
   return invokeCommand("timeseries", params);
 }
 
+export async function getBreakdownHomes(params: RangeParams): Promise<HomeBreakdown[]> {
+  return invokeCommand("breakdown_homes", params);
+}
+
+export async function getBreakdownUsers(params: RangeParams): Promise<UserBreakdown[]> {
+  return invokeCommand("breakdown_users", params);
+}
+
 export async function getBreakdownCosts(params: RangeParams): Promise<ModelCostBreakdown[]> {
   return invokeCommand("breakdown_costs", params);
 }
@@ -40,6 +63,18 @@ export async function getBreakdownEffortCosts(
   return invokeCommand("breakdown_effort_costs", params);
 }
 
+export async function getModels(params: RangeParams): Promise<string[]> {
+  return invokeCommand("models", params);
+}
+
+export async function getEfforts(params: RangeParams): Promise<string[]> {
+  return invokeCommand("efforts", params);
+}
+
+export async function getSources(params: RangeParams): Promise<string[]> {
+  return invokeCommand("sources", params);
+}
+
 export async function getContextStats(params: RangeParams): Promise<ContextPressureStats> {
   return invokeCommand("context_stats", params);
 }
@@ -56,10 +91,36 @@ export async function getLimitWindows(limit = 8): Promise<UsageLimitWindow[]> {
   return invokeCommand("limits_7d_windows", { limit });
 }
 
-export async function getEvents(params: EventsParams): Promise<UsageEvent[]> {
+export async function getLimitSnapshots(
+  params: Omit<RangeParams, "home_id"> & {
+    limit_type?: "5h" | "7d";
+    limit?: number;
+    offset?: number;
+    home_id?: number;
+  }
+): Promise<UsageLimitSnapshot[]> {
+  return invokeCommand("limits_snapshots", params);
+}
+
+export async function getDashboard(params: DashboardParams): Promise<DashboardResponse> {
+  return invokeCommand("dashboard", params);
+}
+
+export async function getEvents(params: EventsParams): Promise<EventsResponse> {
   return invokeCommand("events", params);
 }
 
+export async function getSessionTimeline(
+  sessionId: string,
+  homeId?: number
+): Promise<SessionTimeline> {
+  return invokeCommand("session_timeline", { session_id: sessionId, home_id: homeId });
+}
+
+export async function takePendingDeepLink(): Promise<DeepLink | null> {
+  return invokeCommand("deep_link_pending");
+}
+
 export async function getActiveSessions(
   params: ActiveSessionsParams
 ): Promise<ActiveSession[]> {
@@ -78,7 +139,15 @@ export async function replacePricing(rules: PricingRule[]): Promise<{ updated: n
   return invokeCommand("pricing_replace", { rules });
 }
 
-export async function recomputePricing(): Promise<{ updated: number }> {
+export async function listModelAliases(): Promise<ModelAlias[]> {
+  return invokeCommand("model_aliases_list");
+}
+
+export async function replaceModelAliases(aliases: ModelAlias[]): Promise<{ updated: number }> {
+  return invokeCommand("model_aliases_replace", { aliases });
+}
+
+export async function recomputePricing(): Promise<PricingRecomputeResponse> {
   return invokeCommand("pricing_recompute");
 }
 
@@ -93,10 +162,31 @@ export async function createHome(payload: {
   return invokeCommand("homes_create", payload);
 }
 
+export async function inspectHome(path: string): Promise<HomeInspection> {
+  return invokeCommand("homes_inspect", { path });
+}
+
+export async function discoverHomes(): Promise<HomeCandidate[]> {
+  // The HTTP route is nested; the desktop command cannot contain a slash.
+  return invokeCommand(isTauriRuntime() ? "homes_discover" : "homes/discover");
+}
+
 export async function setActiveHome(id: number): Promise<CodexHome> {
   return invokeCommand("homes_set_active", { id });
 }
 
+export async function renameHome(id: number, label: string): Promise<CodexHome> {
+  return invokeCommand("homes_rename", { id, label });
+}
+
+export async function archiveHome(id: number): Promise<CodexHome> {
+  return invokeCommand("homes_archive", { id });
+}
+
+export async function unarchiveHome(id: number): Promise<CodexHome> {
+  return invokeCommand("homes_unarchive", { id });
+}
+
 export async function deleteHome(id: number): Promise<{ deleted: number }> {
   return invokeCommand("homes_delete", { id });
 }
@@ -105,6 +195,10 @@ export async function clearHomeData(id: number): Promise<{ cleared: number }> {
   return invokeCommand("homes_clear_data", { id });
 }
 
+export async function getVersion(): Promise<VersionInfo> {
+  return invokeCommand("version");
+}
+
 export async function getSettings(): Promise<SettingsResponse> {
   return invokeCommand("settings_get");
 }
@@ -112,10 +206,46 @@ export async function getSettings(): Promise<SettingsResponse> {
 export async function updateSettings(payload: {
   codex_home?: string;
   context_active_minutes?: number;
+  active_session_rule?: ActiveSessionRule;
+  monthly_budget_usd?: number;
+  auto_ingest_secs?: number;
+  launch_at_login?: boolean;
+  global_shortcut?: string;
+  tracking_paused?: boolean;
+  limit_warn_percent?: number;
+  limit_critical_percent?: number;
+  slack_webhook_url?: string;
+  discord_webhook_url?: string;
+  daily_summary?: boolean;
 }): Promise<SettingsResponse> {
   return invokeCommand("settings_put", payload);
 }
 
+export async function sendTestNotification(): Promise<{ sent: number }> {
+  return invokeCommand("notifications_test");
+}
+
+/**
+ * Desktop only: asks where to save the range's usage (CSV or JSON, picked by
+ * the file extension) and writes it. Resolves to the saved path, or null when
+ * the dialog was cancelled.
+ */
+export async function exportData(
+  params: RangeParams & { daily?: boolean }
+): Promise<string | null> {
+  return invokeCommand("export_data", params);
+}
+
+/** Desktop only: checks the release manifest for a newer version. */
+export async function checkForUpdates(): Promise<UpdateCheck> {
+  return invokeCommand("updates_check");
+}
+
+/** Desktop only: installs the latest release and restarts the app. */
+export async function installUpdate(): Promise<void> {
+  await invokeCommand("updates_install");
+}
+
 export async function openLogsDir(): Promise<void> {
   await invokeCommand("open_logs_dir");
 }
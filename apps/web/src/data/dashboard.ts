@@ -1,6 +1,7 @@
 import type {
   ActiveSession,
   ContextPressureStats,
+  EventTotals,
   ModelCostBreakdown,
   ModelEffortCostBreakdown,
   RangeParams,
@@ -11,18 +12,7 @@ import type {
   UsageSummary,
   LimitsResponse
 } from "../domain/types";
-import {
-  getActiveSessions,
-  getBreakdownCosts,
-  getBreakdownEffortCosts,
-  getContextStats,
-  getEvents,
-  getLimitWindows,
-  getLimitsCurrent,
-  getLimitsLatest,
-  getSummary,
-  getTimeSeries
-} from "./codexApi";
+import { getDashboard, getLimitWindows, getLimitsCurrent, getLimitsLatest } from "./codexApi";
 
 export type DashboardQuery = {
   rangeParams: RangeParams;
@@ -43,52 +33,43 @@ export type DashboardPayload = {
   limitCurrent: UsageLimitCurrentResponse;
   limitWindows: UsageLimitWindow[];
   events: UsageEvent[];
+  eventTotals: EventTotals;
   activeSessions: ActiveSession[];
+  models: string[];
 };
 
 export async function fetchDashboardData(query: DashboardQuery): Promise<DashboardPayload> {
   const { rangeParams, chartBucket, modelFilter, eventsLimit = 200, activeMinutes } = query;
-  const [
-    summary,
-    tokensSeries,
-    costSeries,
-    breakdown,
-    effortBreakdown,
-    contextStats,
-    limits,
-    limitCurrent,
-    limitWindows,
-    events,
-    activeSessions
-  ] = await Promise.all([
-    getSummary(rangeParams),
-    getTimeSeries({ ...rangeParams, bucket: chartBucket, metric: "tokens" }),
-    getTimeSeries({ ...rangeParams, bucket: chartBucket, metric: "cost" }),
-    getBreakdownCosts(rangeParams),
-    getBreakdownEffortCosts(rangeParams),
-    getContextStats(rangeParams),
-    getLimitsLatest(),
-    getLimitsCurrent(),
-    getLimitWindows(8),
-    getEvents({
+  // Usage panels come from one snapshot so their numbers agree; limits are
+  // separate snapshots of their own.
+  const [dashboard, limits, limitCurrent, limitWindows] = await Promise.all([
+    getDashboard({
       ...rangeParams,
-      limit: eventsLimit,
-      model: modelFilter === "all" ? undefined : modelFilter
+      bucket: chartBucket,
+      // Bucket in the browser's timezone, which may differ from the server's.
+      tz_offset_minutes: -new Date().getTimezoneOffset(),
+      model: modelFilter === "all" ? undefined : modelFilter,
+      events_limit: eventsLimit,
+      active_minutes: activeMinutes
     }),
-    getActiveSessions({ active_minutes: activeMinutes })
+    getLimitsLatest(),
+    getLimitsCurrent(),
+    getLimitWindows(8)
   ]);
 
   return {
-    summary,
-    tokensSeries,
-    costSeries,
-    breakdown,
-    effortBreakdown,
-    contextStats,
+    summary: dashboard.summary,
+    tokensSeries: dashboard.tokens_series,
+    costSeries: dashboard.cost_series,
+    breakdown: dashboard.breakdown,
+    effortBreakdown: dashboard.effort_breakdown,
+    contextStats: dashboard.context_stats,
     limits,
     limitCurrent,
     limitWindows,
-    events,
-    activeSessions
+    events: dashboard.events.events,
+    eventTotals: dashboard.events,
+    activeSessions: dashboard.active_sessions,
+    models: dashboard.models
   };
 }
@@ -1,3 +1,12 @@
+export type TokenEfficiency = {
+  output_input_ratio: number | null;
+  reasoning_output_share: number | null;
+  cached_input_share: number | null;
+};
+
+/** Where a dollar figure came from; aggregates report the least trustworthy. */
+export type CostSource = "reported" | "computed" | "estimated";
+
 export type UsageSummary = {
   total_tokens: number;
   input_tokens: number;
@@ -8,6 +17,9 @@ export type UsageSummary = {
   input_cost_usd: number | null;
   cached_input_cost_usd: number | null;
   output_cost_usd: number | null;
+  estimated_cost_usd?: number | null;
+  cost_source?: CostSource | null;
+  efficiency: TokenEfficiency;
 };
 
 export type TimeSeriesPoint = {
@@ -26,6 +38,7 @@ export type ModelCostBreakdown = {
   cached_input_cost_usd: number | null;
   output_cost_usd: number | null;
   total_cost_usd: number | null;
+  efficiency: TokenEfficiency;
 };
 
 export type ModelEffortCostBreakdown = {
@@ -58,11 +71,56 @@ export type UsageEvent = {
     context_window: number;
   };
   cost_usd: number | null;
+  cost_source?: CostSource | null;
   reasoning_effort: string | null;
   source: string;
   session_id: string;
 };
 
+export type EventTotals = {
+  total_count: number;
+  total_tokens: number;
+  total_cost_usd: number | null;
+  cost_source?: CostSource | null;
+};
+
+export type EventsResponse = EventTotals & {
+  events: UsageEvent[];
+};
+
+export type SessionTimelineEntry =
+  | { kind: "message"; ts: string; role: string }
+  | {
+      kind: "token_count";
+      ts: string;
+      model: string;
+      usage: UsageEvent["usage"];
+      context_used: number;
+      context_window: number;
+      cost_usd: number | null;
+    }
+  | {
+      kind: "effort_change";
+      ts: string;
+      model: string;
+      from: string | null;
+      to: string | null;
+    };
+
+export type SessionTimeline = {
+  session_id: string;
+  entries: SessionTimelineEntry[];
+};
+
+/** A view requested by a `codex-tracker://` link in the desktop app. */
+export type DeepLink =
+  | { view: "session"; session_id: string }
+  | { view: "range"; range: string };
+
+export type DropOutcome =
+  | { status: "ingested"; files: number; events_inserted: number }
+  | { status: "failed"; message: string };
+
 export type ActiveSession = {
   session_id: string;
   model: string;
@@ -70,12 +128,27 @@ export type ActiveSession = {
   session_start: string;
   context_used: number;
   context_window: number;
+  window_tokens?: number;
+};
+
+export type ActiveMinTokens = {
+  model_pattern: string;
+  min_tokens: number;
+};
+
+export type ActiveSessionRule = {
+  require_open_context: boolean;
+  min_tokens: ActiveMinTokens[];
 };
 
 export type ContextPressureStats = {
   avg_context_used: number | null;
   avg_context_window: number | null;
   avg_pressure_pct: number | null;
+  p50_pressure_pct: number | null;
+  p90_pressure_pct: number | null;
+  p99_pressure_pct: number | null;
+  high_pressure_count: number;
   sample_count: number;
 };
 
@@ -95,6 +168,7 @@ export type UsageLimitWindow = {
   total_cost_usd: number | null;
   message_count: number | null;
   complete: boolean;
+  interpolated: boolean;
 };
 
 export type UsageLimitCurrentWindow = {
@@ -105,14 +179,40 @@ export type UsageLimitCurrentWindow = {
   message_count: number | null;
 };
 
+export type CustomLimit = {
+  id: number;
+  name: string;
+  period: "week" | "month";
+  metric: "tokens" | "cost_usd";
+  amount: number;
+  created_at: string;
+};
+
+export type CustomLimitWindow = CustomLimit & {
+  window_start: string;
+  window_end: string;
+  used: number;
+  percent_left: number;
+};
+
 export type UsageLimitCurrentResponse = {
   primary: UsageLimitCurrentWindow | null;
   secondary: UsageLimitCurrentWindow | null;
+  custom?: CustomLimitWindow[];
+};
+
+export type LimitLevel = "ok" | "warn" | "critical";
+
+export type LimitStatus = UsageLimitSnapshot & {
+  used_percent: number;
+  status: LimitLevel;
+  resets_in_secs: number;
+  resets_in: string;
 };
 
 export type LimitsResponse = {
-  primary: UsageLimitSnapshot | null;
-  secondary: UsageLimitSnapshot | null;
+  primary: LimitStatus | null;
+  secondary: LimitStatus | null;
 };
 
 export type PricingRule = {
@@ -123,6 +223,25 @@ export type PricingRule = {
   output_per_1m: number;
   effective_from: string;
   effective_to?: string | null;
+  provider?: string | null;
+  fallback?: boolean;
+};
+
+export type ModelAlias = {
+  alias: string;
+  model: string;
+};
+
+export type CostRecomputeProgress = {
+  codex_home_id: number;
+  processed: number;
+  total: number;
+  complete: boolean;
+};
+
+export type PricingRecomputeResponse = {
+  updated: number;
+  progress: CostRecomputeProgress;
 };
 
 export type PricingRuleApi = PricingRule & {
@@ -137,40 +256,116 @@ export type CodexHome = {
   path: string;
   created_at: string;
   last_seen_at?: string | null;
+  archived?: boolean;
+  user?: string | null;
 };
 
 export type IngestStats = {
   files_scanned: number;
   files_skipped: number;
   events_inserted: number;
+  messages_inserted: number;
+  limit_snapshots_inserted: number;
   bytes_read: number;
   issues: { file_path: string; message: string }[];
 };
 
+/** Forwarded by the desktop app as `app-event` after a write lands. */
+export type AppEvent =
+  | {
+      kind: "ingest_finished";
+      home_id: number | null;
+      events_inserted: number;
+      rows_inserted: number;
+    }
+  | { kind: "pricing_changed" }
+  | { kind: "costs_recomputed"; home_id: number }
+  | { kind: "home_switched"; home_id: number }
+  | { kind: "usage_changed" };
+
+export type UpdateCheck = {
+  current_version: string;
+  /** Null when already on the latest release. */
+  version: string | null;
+  notes: string | null;
+  date: string | null;
+};
+
+export type HomeCandidate = {
+  path: string;
+  log_files: number;
+  home_id?: number | null;
+};
+
+export type HomeInspection = {
+  path: string;
+  exists: boolean;
+  has_sessions_dir: boolean;
+  log_files: number;
+  looks_like_codex_home: boolean;
+};
+
 export type HomesResponse = {
   active_home_id: number | null;
   homes: CodexHome[];
 };
 
+export type VersionInfo = {
+  version: string;
+  git_sha: string;
+  build_date: string;
+  schema_version: number;
+};
+
 export type SettingsResponse = {
   codex_home: string;
   active_home_id: number;
   context_active_minutes?: number;
+  active_session_rule?: ActiveSessionRule;
+  monthly_budget_usd?: number | null;
+  auto_ingest_secs?: number;
+  launch_at_login?: boolean;
+  global_shortcut?: string;
+  tracking_paused?: boolean;
+  limit_warn_percent?: number;
+  limit_critical_percent?: number;
+  slack_webhook_configured?: boolean;
+  discord_webhook_configured?: boolean;
+  daily_summary?: boolean;
   db_path?: string;
   pricing_defaults_path?: string;
   app_data_dir?: string;
   legacy_backup_dir?: string | null;
 };
 
+export type HomeBreakdown = {
+  codex_home_id: number;
+  label: string;
+  path: string;
+  user?: string | null;
+  total_tokens: number;
+  total_cost_usd?: number | null;
+};
+
+export type UserBreakdown = {
+  user?: string | null;
+  home_ids: number[];
+  total_tokens: number;
+  total_cost_usd?: number | null;
+};
+
 export type RangeParams = {
   range?: string;
   start?: string;
   end?: string;
+  /** Query this home instead of the active one; "all" aggregates every home. */
+  home_id?: number | "all";
 };
 
 export type TimeSeriesParams = RangeParams & {
   bucket?: string;
   metric?: string;
+  tz_offset_minutes?: number;
 };
 
 export type EventsParams = RangeParams & {
@@ -179,6 +374,28 @@ export type EventsParams = RangeParams & {
   model?: string;
 };
 
+export type DashboardParams = RangeParams & {
+  bucket?: string;
+  tz_offset_minutes?: number;
+  model?: string;
+  events_limit?: number;
+  active_minutes?: number;
+};
+
+/** Usage panels read from one database snapshot. */
+export type DashboardResponse = {
+  summary: UsageSummary;
+  tokens_series: TimeSeriesPoint[];
+  cost_series: TimeSeriesPoint[];
+  breakdown: ModelCostBreakdown[];
+  effort_breakdown: ModelEffortCostBreakdown[];
+  context_stats: ContextPressureStats;
+  events: EventsResponse;
+  active_sessions: ActiveSession[];
+  models: string[];
+};
+
 export type ActiveSessionsParams = {
   active_minutes?: number;
+  home_id?: number;
 };
@@ -1,8 +1,14 @@
 import { useCallback, useEffect, useMemo, useRef, useState } from "react";
 import type { ChangeEvent, KeyboardEvent } from "react";
 import type { SelectOption } from "../../components/Select";
-import { openLogsDir } from "../../data/codexApi";
-import type { ActiveSession, UsageLimitWindow } from "../../domain/types";
+import { exportData, getSessionTimeline, openLogsDir } from "../../data/codexApi";
+import type {
+  ActiveSession,
+  DeepLink,
+  SessionTimeline,
+  SessionTimelineEntry,
+  UsageLimitWindow
+} from "../../domain/types";
 import {
   AUTO_REFRESH_OPTIONS,
   RANGE_OPTIONS,
@@ -17,6 +23,7 @@ import { clampPercent } from "../../shared/math";
 import { formatDateInputValue } from "../../shared/dates";
 import { buildRangeParams, rangeLabel } from "../../shared/range";
 import { safeStorageGet, safeStorageSet } from "../../shared/storage";
+import { isTauriRuntime } from "../../shared/tauri";
 import type { ToastMessage } from "../shared/Toast";
 import { DashboardHeader } from "./components/DashboardHeader";
 import { ActiveSessionsPanel } from "./components/ActiveSessionsPanel";
@@ -30,15 +37,41 @@ import { useDashboardData } from "./useDashboardData";
 type DashboardPageProps = {
   activeMinutes: number;
   refreshToken?: number;
+  deepLink?: DeepLink | null;
+  onDeepLinkHandled?: () => void;
   onOpenSettings: () => void;
   onToast?: (toast: ToastMessage) => void;
 };
 
 type LimitWindowRow = UsageLimitWindow & { delta?: number | null };
 
+type TokenCountEntry = Extract<SessionTimelineEntry, { kind: "token_count" }>;
+
+/** Session details for a deep-linked session that may no longer be active. */
+function sessionFromTimeline(timeline: SessionTimeline): ActiveSession | null {
+  const { entries } = timeline;
+  if (entries.length === 0) {
+    return null;
+  }
+  const usage = entries.filter(
+    (entry): entry is TokenCountEntry => entry.kind === "token_count"
+  );
+  const latest = usage[usage.length - 1];
+  return {
+    session_id: timeline.session_id,
+    model: latest?.model ?? "unknown",
+    session_start: entries[0].ts,
+    last_seen: entries[entries.length - 1].ts,
+    context_used: latest?.context_used ?? 0,
+    context_window: latest?.context_window ?? 0
+  };
+}
+
 export function DashboardPage({
   activeMinutes,
   refreshToken,
+  deepLink,
+  onDeepLinkHandled,
   onOpenSettings,
   onToast
 }: DashboardPageProps) {
@@ -79,7 +112,9 @@ export function DashboardPage({
   const limitCurrent = data?.limitCurrent ?? null;
   const limitWindows = data?.limitWindows ?? [];
   const events = data?.events ?? [];
+  const eventTotals = data?.eventTotals ?? null;
   const activeSessions = data?.activeSessions ?? [];
+  const models = data?.models ?? [];
 
   const showSummarySkeleton = loading && !summary;
   const isRefreshing = loading || isIngesting;
@@ -108,13 +143,14 @@ export function DashboardPage({
     []
   );
 
-  const modelSelectOptions = useMemo<SelectOption[]>(() => {
-    const models = new Set(breakdown.map((item) => item.model));
-    return ["all", ...Array.from(models).sort()].map((model) => ({
-      value: model,
-      label: model === "all" ? "All models" : model
-    }));
-  }, [breakdown]);
+  const modelSelectOptions = useMemo<SelectOption[]>(
+    () =>
+      ["all", ...models].map((model) => ({
+        value: model,
+        label: model === "all" ? "All models" : model
+      })),
+    [models]
+  );
 
   const autoRefreshInterval = useMemo(() => {
     return AUTO_REFRESH_OPTIONS.find((option) => option.value === autoRefresh)?.ms ?? 0;
@@ -135,7 +171,11 @@ export function DashboardPage({
       : `Average context size for ${rangeLabel(range)}: —`;
   const rangeAvgTooltip =
     contextSampleCount > 0
-      ? "Average context usage and pressure for events with known context in this range."
+      ? `Average context usage and pressure for events with known context in this range. Pressure p50 ${formatPercent(
+          contextStats?.p50_pressure_pct ?? 0
+        )} · p90 ${formatPercent(contextStats?.p90_pressure_pct ?? 0)} · p99 ${formatPercent(
+          contextStats?.p99_pressure_pct ?? 0
+        )} · ${formatNumber(contextStats?.high_pressure_count ?? 0)} events above 80%.`
       : "No context window data in this range yet.";
 
   const primaryLimitPercent = clampPercent(limits?.primary?.percent_left ?? 100);
@@ -196,6 +236,36 @@ export function DashboardPage({
     safeStorageSet(STORAGE_KEYS.range, range);
   }, [range]);
 
+  useEffect(() => {
+    if (!deepLink) {
+      return;
+    }
+    onDeepLinkHandled?.();
+    if (deepLink.view === "range") {
+      const option = RANGE_OPTIONS.find((item) => item.value === deepLink.range);
+      if (option) {
+        setRange(option.value);
+      }
+      return;
+    }
+    const sessionId = deepLink.session_id;
+    getSessionTimeline(sessionId)
+      .then((timeline) => {
+        const session = sessionFromTimeline(timeline);
+        if (session) {
+          setSelectedSession(session);
+        } else {
+          onToast?.({ message: `Session ${sessionId} not found`, tone: "error" });
+        }
+      })
+      .catch((err) => {
+        onToast?.({
+          message: err instanceof Error ? err.message : "Failed to open session",
+          tone: "error"
+        });
+      });
+  }, [deepLink, onDeepLinkHandled, onToast]);
+
   useEffect(() => {
     safeStorageSet(STORAGE_KEYS.rangeStart, customStart);
   }, [customStart]);
@@ -229,6 +299,20 @@ export function DashboardPage({
     }
   }, [onToast]);
 
+  const handleExport = useCallback(async () => {
+    try {
+      const path = await exportData(rangeParams);
+      if (path) {
+        onToast?.({ message: `Exported to ${path}`, tone: "info" });
+      }
+    } catch (err) {
+      onToast?.({
+        message: err instanceof Error ? err.message : "Export failed",
+        tone: "error"
+      });
+    }
+  }, [onToast, rangeParams]);
+
   useEffect(() => {
     function handleKeyDown(event: KeyboardEvent) {
       if (event.defaultPrevented) {
@@ -325,6 +409,7 @@ export function DashboardPage({
         onDateInputKeyDown={handleDateInputKeyDown}
         onRefresh={ingest}
         onOpenLogs={handleOpenLogs}
+        onExport={isTauriRuntime() ? handleExport : undefined}
         onOpenSettings={onOpenSettings}
         isRefreshing={isRefreshing}
         ingestStatus={ingestStatus}
@@ -364,6 +449,7 @@ export function DashboardPage({
       />
       <EventsPanel
         events={events}
+        totals={eventTotals}
         loading={loading}
         modelFilter={modelFilter}
         modelOptions={modelSelectOptions}
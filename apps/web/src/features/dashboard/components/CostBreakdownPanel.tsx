@@ -16,6 +16,7 @@ import {
   formatCostPerMillion,
   formatCurrency,
   formatEffort,
+  formatEfficiency,
   formatNumber
 } from "../../../shared/formatters";
 
@@ -236,7 +237,11 @@ export function CostBreakdownPanel({
                           total_cost_usd: item.total_cost_usd
                         };
                     const rows = [
-                      <tr key={`${item.model}-total`} className="model-row">
+                      <tr
+                        key={`${item.model}-total`}
+                        className="model-row"
+                        title={formatEfficiency(item.efficiency)}
+                      >
                         <td>
                           {isExpandable ? (
                             <button
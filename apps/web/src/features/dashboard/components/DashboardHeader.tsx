@@ -22,6 +22,8 @@ type DashboardHeaderProps = {
   onDateInputKeyDown: (event: KeyboardEvent<HTMLInputElement>) => void;
   onRefresh: () => void;
   onOpenLogs: () => void;
+  /** Set in the desktop app, which can open a native save dialog. */
+  onExport?: () => void;
   onOpenSettings: () => void;
   isRefreshing: boolean;
   ingestStatus: string;
@@ -46,6 +48,7 @@ export function DashboardHeader({
   onDateInputKeyDown,
   onRefresh,
   onOpenLogs,
+  onExport,
   onOpenSettings,
   isRefreshing,
   ingestStatus,
@@ -188,6 +191,26 @@ export function DashboardHeader({
                 />
               </svg>
             </button>
+            {onExport && (
+              <button
+                className="icon-button icon-button-ghost small"
+                type="button"
+                onClick={onExport}
+                title="Export range"
+                aria-label="Export range"
+              >
+                <svg viewBox="0 0 24 24" width="16" height="16" aria-hidden="true">
+                  <path
+                    d="M12 4v11m0 0-4-4m4 4 4-4M5 19h14"
+                    fill="none"
+                    stroke="currentColor"
+                    strokeWidth="1.6"
+                    strokeLinecap="round"
+                    strokeLinejoin="round"
+                  />
+                </svg>
+              </button>
+            )}
             <button
               className="icon-button small"
               type="button"
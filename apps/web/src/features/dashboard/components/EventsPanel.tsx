@@ -1,12 +1,13 @@
 import { useEffect, useMemo, useState } from "react";
 import type { SelectOption } from "../../../components/Select";
 import { SelectField } from "../../../components/Select";
-import type { UsageEvent } from "../../../domain/types";
+import type { EventTotals, UsageEvent } from "../../../domain/types";
 import { EVENTS_PER_PAGE } from "../../../shared/constants";
 import { formatCurrency, formatEffort, formatNumber } from "../../../shared/formatters";
 
 type EventsPanelProps = {
   events: UsageEvent[];
+  totals: EventTotals | null;
   loading: boolean;
   modelFilter: string;
   modelOptions: SelectOption[];
@@ -16,6 +17,7 @@ type EventsPanelProps = {
 
 export function EventsPanel({
   events,
+  totals,
   loading,
   modelFilter,
   modelOptions,
@@ -43,7 +45,15 @@ export function EventsPanel({
       <div className="panel-header">
         <div>
           <h2>Recent Events</h2>
-          <p>Filtered by range and model.</p>
+          <p>
+            {totals
+              ? `${formatNumber(totals.total_count)} events, ${formatNumber(
+                  totals.total_tokens
+                )} tokens, ${formatCurrency(totals.total_cost_usd)}${
+                  totals.cost_source ? ` (${totals.cost_source})` : ""
+                }`
+              : "Filtered by range and model."}
+          </p>
         </div>
         <div className="filters">
           <label className="label">Model</label>
@@ -84,7 +94,9 @@ export function EventsPanel({
                   <td>{formatNumber(event.usage.total_tokens)}</td>
                   <td>{formatNumber(event.usage.input_tokens)}</td>
                   <td>{formatNumber(event.usage.output_tokens)}</td>
-                  <td>{formatCurrency(event.cost_usd)}</td>
+                  <td title={event.cost_source ? `Cost ${event.cost_source}` : undefined}>
+                    {formatCurrency(event.cost_usd)}
+                  </td>
                 </tr>
               ))
             )}
@@ -8,6 +8,7 @@ import {
   formatRelativeReset,
   formatResetLabel
 } from "../../../shared/formatters";
+import { clampPercent } from "../../../shared/math";
 
 type LimitWindowRow = UsageLimitWindow & { delta?: number | null };
 
@@ -59,7 +60,10 @@ export function LimitsPanel({
             </p>
           </div>
           <div className="limit-progress" aria-hidden="true">
-            <div className="limit-progress-fill" style={{ width: `${primaryLimitPercent}%` }} />
+            <div
+              className={`limit-progress-fill ${limits?.primary?.status ?? "ok"}`}
+              style={{ width: `${primaryLimitPercent}%` }}
+            />
           </div>
           <p className="card-meta card-meta-compact">
             <span className="limit-inline">Messages</span>{" "}
@@ -84,7 +88,10 @@ export function LimitsPanel({
             </p>
           </div>
           <div className="limit-progress" aria-hidden="true">
-            <div className="limit-progress-fill" style={{ width: `${secondaryLimitPercent}%` }} />
+            <div
+              className={`limit-progress-fill ${limits?.secondary?.status ?? "ok"}`}
+              style={{ width: `${secondaryLimitPercent}%` }}
+            />
           </div>
           <p className="card-meta card-meta-compact">
             <span className="limit-inline">Messages</span>{" "}
@@ -97,6 +104,33 @@ export function LimitsPanel({
             {formatCurrency(limitCurrent?.secondary?.total_cost_usd)}
           </p>
         </div>
+        {(limitCurrent?.custom ?? []).map((limit) => {
+          const format = limit.metric === "cost_usd" ? formatCurrency : formatNumber;
+          return (
+            <div className="limit-card" key={limit.id}>
+              <p className="card-label">{limit.name}</p>
+              <div className="limit-inline-row">
+                <p className="limit-value tabular-nums">
+                  {formatLimitPercentLeft(limit.percent_left)}
+                </p>
+                <p className="card-meta limit-inline-meta">
+                  Resets {formatResetLabel(limit.window_end)} ·{" "}
+                  {formatRelativeReset(limit.window_end)}
+                </p>
+              </div>
+              <div className="limit-progress" aria-hidden="true">
+                <div
+                  className="limit-progress-fill"
+                  style={{ width: `${clampPercent(limit.percent_left)}%` }}
+                />
+              </div>
+              <p className="card-meta card-meta-compact">
+                <span className="limit-inline">Used</span> {format(limit.used)} of{" "}
+                {format(limit.amount)} per {limit.period}
+              </p>
+            </div>
+          );
+        })}
       </div>
       {showLimitDetails &&
         (limitWindowRows.length === 0 ? (
@@ -143,6 +177,14 @@ export function LimitsPanel({
                       <span className="limits-cell" data-label="Window">
                         {startLabel} → {endLabel}
                         {isCurrent && <span className="limit-badge">Current</span>}
+                        {window.interpolated && (
+                          <span
+                            className="limit-badge"
+                            title="No snapshot saw this reset; it is placed 7 days after the previous one."
+                          >
+                            Estimated
+                          </span>
+                        )}
                       </span>
                       <span className="limits-cell" data-label="Tokens">
                         {formatNumber(window.total_tokens)}
@@ -50,6 +50,14 @@ export function SummaryCards({ summary, showSkeleton }: SummaryCardsProps) {
                 <span>Input {formatCurrency(summary?.input_cost_usd)}</span>
                 <span className="meta-sep">•</span>
                 <span>Output {formatCurrency(summary?.output_cost_usd)}</span>
+                {summary?.estimated_cost_usd != null && (
+                  <>
+                    <span className="meta-sep">•</span>
+                    <span title="Priced by the fallback rule for models no pricing rule matches">
+                      Estimated {formatCurrency(summary.estimated_cost_usd)}
+                    </span>
+                  </>
+                )}
               </>
             )}
           </p>
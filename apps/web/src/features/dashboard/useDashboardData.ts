@@ -1,5 +1,5 @@
 import { useCallback, useEffect, useMemo, useRef, useState } from "react";
-import type { IngestStats, RangeParams } from "../../domain/types";
+import type { AppEvent, IngestStats, RangeParams } from "../../domain/types";
 import { fetchDashboardData, type DashboardPayload } from "../../data/dashboard";
 import { clearCached, getCached, setCached } from "../../data/cache";
 import { runIngest } from "../../data/codexApi";
@@ -123,7 +123,7 @@ export function useDashboardData({
     if (!isTauriRuntime()) {
       return;
     }
-    let unlisten: (() => void) | null = null;
+    const unlisteners: Array<() => void> = [];
     let cancelled = false;
     (async () => {
       try {
@@ -131,13 +131,29 @@ export function useDashboardData({
         if (cancelled) {
           return;
         }
-        unlisten = await listen<IngestStats>("ingest:complete", (event) => {
-          if (event.payload) {
-            setIngestStats(event.payload);
-          }
-          invalidateCache();
-          refresh({ force: true });
-        });
+        // `data-updated` comes from the desktop app's background auto-ingest.
+        for (const name of ["ingest:complete", "data-updated"]) {
+          unlisteners.push(
+            await listen<IngestStats>(name, (event) => {
+              if (event.payload) {
+                setIngestStats(event.payload);
+              }
+              invalidateCache();
+              refresh({ force: true });
+            })
+          );
+        }
+        // Ingests are covered above; this catches repricing and home
+        // switches made from another window, such as settings.
+        unlisteners.push(
+          await listen<AppEvent>("app-event", (event) => {
+            const kind = event.payload?.kind;
+            if (kind === "costs_recomputed" || kind === "home_switched" || kind === "usage_changed") {
+              invalidateCache();
+              refresh({ force: true });
+            }
+          })
+        );
       } catch (err) {
         onToast?.({
           message: err instanceof Error ? err.message : "Ingest listener unavailable",
@@ -147,9 +163,7 @@ export function useDashboardData({
     })();
     return () => {
       cancelled = true;
-      if (unlisten) {
-        unlisten();
-      }
+      unlisteners.forEach((unlisten) => unlisten());
     };
   }, [invalidateCache, onToast, refresh]);
 
@@ -0,0 +1,94 @@
+import { useCallback, useEffect, useState } from "react";
+import { getActiveSessions, getLimitsLatest, getSettings, getSummary } from "../../data/codexApi";
+import { formatCurrency, formatPercentWhole } from "../../shared/formatters";
+import { clampPercent } from "../../shared/math";
+
+/** How often the widget reloads when no ingest event arrives. */
+const REFRESH_MS = 30_000;
+
+type MiniStats = {
+  todayCost: number | null;
+  windowPercent: number | null;
+  activeSessions: number;
+};
+
+/**
+ * Compact view for the desktop app's always-on-top mini window: today's
+ * cost, how much of the 5h window is used, and the active session count.
+ */
+export function MiniWidget() {
+  const [stats, setStats] = useState<MiniStats | null>(null);
+  const [error, setError] = useState("");
+
+  const load = useCallback(async () => {
+    try {
+      const settings = await getSettings();
+      const [summary, limits, sessions] = await Promise.all([
+        getSummary({ range: "today" }),
+        getLimitsLatest(),
+        getActiveSessions({ active_minutes: settings.context_active_minutes ?? 60 })
+      ]);
+      setStats({
+        todayCost: summary.total_cost_usd,
+        windowPercent: limits.primary ? clampPercent(100 - limits.primary.percent_left) : null,
+        activeSessions: sessions.length
+      });
+      setError("");
+    } catch (err) {
+      setError(err instanceof Error ? err.message : "Load failed");
+    }
+  }, []);
+
+  useEffect(() => {
+    load();
+    const intervalId = window.setInterval(load, REFRESH_MS);
+    return () => window.clearInterval(intervalId);
+  }, [load]);
+
+  useEffect(() => {
+    const unlisteners: Array<() => void> = [];
+    let cancelled = false;
+    (async () => {
+      try {
+        const { listen } = await import("@tauri-apps/api/event");
+        for (const name of ["ingest:complete", "data-updated"]) {
+          const unlisten = await listen(name, () => load());
+          if (cancelled) {
+            unlisten();
+          } else {
+            unlisteners.push(unlisten);
+          }
+        }
+      } catch {
+        // The interval keeps the widget current without events.
+      }
+    })();
+    return () => {
+      cancelled = true;
+      unlisteners.forEach((unlisten) => unlisten());
+    };
+  }, [load]);
+
+  return (
+    <div className="mini-widget" data-tauri-drag-region>
+      {error ? (
+        <p className="mini-widget-error">{error}</p>
+      ) : (
+        <dl className="mini-widget-stats" data-tauri-drag-region>
+          <div>
+            <dt>Today</dt>
+            <dd>{stats ? formatCurrency(stats.todayCost) : "—"}</dd>
+          </div>
+          <div>
+            <dt>5h window</dt>
+            <dd>{stats ? formatPercentWhole(stats.windowPercent) : "—"}</dd>
+          </div>
+          <div>
+            <dt>Active</dt>
+            <dd>{stats ? stats.activeSessions : "—"}</dd>
+          </div>
+        </dl>
+      )}
+    </div>
+  );
+}
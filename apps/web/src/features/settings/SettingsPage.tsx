@@ -4,6 +4,7 @@ import { HomesSection } from "./components/HomesSection";
 import { DisplaySection } from "./components/DisplaySection";
 import { StorageSection } from "./components/StorageSection";
 import { PricingSection } from "./components/PricingSection";
+import { ModelAliasesSection } from "./components/ModelAliasesSection";
 
 type SettingsPageProps = {
   state: SettingsState;
@@ -16,6 +17,7 @@ export function SettingsPage({ state, isOpen, onClose }: SettingsPageProps) {
     settingsTab,
     setSettingsTab,
     homes,
+    homeCandidates,
     activeHomeId,
     activeHome,
     homeSelectOptions,
@@ -34,10 +36,24 @@ export function SettingsPage({ state, isOpen, onClose }: SettingsPageProps) {
     pricingHasIssues,
     pricingIssueMap,
     pricingRows,
+    modelAliases,
+    aliasesDirty,
+    aliasesStatus,
     storageInfo,
     tauriAvailable,
     activeMinutesInput,
     setActiveMinutesInput,
+    requireOpenContext,
+    setRequireOpenContext,
+    minTokensInput,
+    setMinTokensInput,
+    launchAtLogin,
+    launchStatus,
+    shortcutInput,
+    setShortcutInput,
+    shortcutStatus,
+    availableUpdate,
+    updateStatus,
     setNewHomeLabel,
     setNewHomePath,
     setDeleteConfirm,
@@ -47,16 +63,27 @@ export function SettingsPage({ state, isOpen, onClose }: SettingsPageProps) {
     handleAddHome,
     handleSetActiveHome,
     handleDeleteHome,
+    handleArchiveHome,
+    handleDiscoverHomes,
+    handleAddCandidate,
     handleDeleteData,
     handleSavePricing,
     handleRecomputeCosts,
+    handleSaveModelAliases,
     handleSaveActiveMinutes,
+    handleToggleLaunchAtLogin,
+    handleSaveShortcut,
+    handleCheckForUpdates,
+    handleInstallUpdate,
     handleCopyPath,
     handleRevealPath,
     updatePricingRule,
     addPricingRule,
     duplicatePricingRule,
-    deletePricingRule
+    deletePricingRule,
+    addModelAlias,
+    updateModelAlias,
+    deleteModelAlias
   } = state;
 
   useEffect(() => {
@@ -134,6 +161,7 @@ export function SettingsPage({ state, isOpen, onClose }: SettingsPageProps) {
         <div className="settings-content">
           <HomesSection
             homes={homes}
+            homeCandidates={homeCandidates}
             activeHomeId={activeHomeId}
             activeHome={activeHome}
             homeSelectOptions={homeSelectOptions}
@@ -147,8 +175,11 @@ export function SettingsPage({ state, isOpen, onClose }: SettingsPageProps) {
             onRefreshHomes={refreshHomes}
             onSetActiveHome={handleSetActiveHome}
             onDeleteHome={handleDeleteHome}
+            onArchiveHome={handleArchiveHome}
             onPickHomePath={handlePickHomePath}
             onAddHome={handleAddHome}
+            onDiscoverHomes={handleDiscoverHomes}
+            onAddCandidate={handleAddCandidate}
             onDeleteData={handleDeleteData}
             onNewHomePathChange={setNewHomePath}
             onNewHomeLabelChange={setNewHomeLabel}
@@ -157,8 +188,24 @@ export function SettingsPage({ state, isOpen, onClose }: SettingsPageProps) {
           <DisplaySection
             activeMinutesInput={activeMinutesInput}
             onActiveMinutesInputChange={setActiveMinutesInput}
+            requireOpenContext={requireOpenContext}
+            onToggleRequireOpenContext={() => setRequireOpenContext(!requireOpenContext)}
+            minTokensInput={minTokensInput}
+            onMinTokensInputChange={setMinTokensInput}
             onSaveActiveMinutes={handleSaveActiveMinutes}
             settingsStatus={settingsStatus}
+            launchAtLoginAvailable={tauriAvailable}
+            launchAtLogin={launchAtLogin}
+            launchStatus={launchStatus}
+            onToggleLaunchAtLogin={handleToggleLaunchAtLogin}
+            shortcutInput={shortcutInput}
+            onShortcutInputChange={setShortcutInput}
+            onSaveShortcut={handleSaveShortcut}
+            shortcutStatus={shortcutStatus}
+            availableUpdate={availableUpdate}
+            updateStatus={updateStatus}
+            onCheckForUpdates={handleCheckForUpdates}
+            onInstallUpdate={handleInstallUpdate}
           />
           <StorageSection
             storageInfo={storageInfo}
@@ -183,6 +230,15 @@ export function SettingsPage({ state, isOpen, onClose }: SettingsPageProps) {
             onDuplicateRule={duplicatePricingRule}
             onDeleteRule={deletePricingRule}
           />
+          <ModelAliasesSection
+            aliases={modelAliases}
+            dirty={aliasesDirty}
+            status={aliasesStatus}
+            onAdd={addModelAlias}
+            onSave={handleSaveModelAliases}
+            onUpdate={updateModelAlias}
+            onDelete={deleteModelAlias}
+          />
         </div>
       </div>
     </section>
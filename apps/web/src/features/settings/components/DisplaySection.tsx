@@ -1,15 +1,47 @@
 type DisplaySectionProps = {
   activeMinutesInput: string;
   onActiveMinutesInputChange: (value: string) => void;
+  requireOpenContext: boolean;
+  onToggleRequireOpenContext: () => void;
+  minTokensInput: string;
+  onMinTokensInputChange: (value: string) => void;
   onSaveActiveMinutes: () => void;
   settingsStatus: string;
+  launchAtLoginAvailable: boolean;
+  launchAtLogin: boolean;
+  launchStatus: string;
+  onToggleLaunchAtLogin: () => void;
+  shortcutInput: string;
+  onShortcutInputChange: (value: string) => void;
+  onSaveShortcut: () => void;
+  shortcutStatus: string;
+  availableUpdate: string | null;
+  updateStatus: string;
+  onCheckForUpdates: () => void;
+  onInstallUpdate: () => void;
 };
 
 export function DisplaySection({
   activeMinutesInput,
   onActiveMinutesInputChange,
+  requireOpenContext,
+  onToggleRequireOpenContext,
+  minTokensInput,
+  onMinTokensInputChange,
   onSaveActiveMinutes,
-  settingsStatus
+  settingsStatus,
+  launchAtLoginAvailable,
+  launchAtLogin,
+  launchStatus,
+  onToggleLaunchAtLogin,
+  shortcutInput,
+  onShortcutInputChange,
+  onSaveShortcut,
+  shortcutStatus,
+  availableUpdate,
+  updateStatus,
+  onCheckForUpdates,
+  onInstallUpdate
 }: DisplaySectionProps) {
   return (
     <section id="settings-display" className="panel settings-section">
@@ -27,6 +59,23 @@ export function DisplaySection({
         value={activeMinutesInput}
         onChange={(event) => onActiveMinutesInputChange(event.target.value)}
       />
+      <label className="label">Require Open Context</label>
+      <div className="row">
+        <button
+          className="button"
+          aria-pressed={requireOpenContext}
+          onClick={onToggleRequireOpenContext}
+        >
+          {requireOpenContext ? "On" : "Off"}
+        </button>
+      </div>
+      <label className="label">Minimum Tokens in Window</label>
+      <input
+        className="input"
+        placeholder="gpt-5*-codex=5000, *=500"
+        value={minTokensInput}
+        onChange={(event) => onMinTokensInputChange(event.target.value)}
+      />
       <div className="row">
         <button className="button" onClick={onSaveActiveMinutes}>
           Save Window
@@ -35,7 +84,64 @@ export function DisplaySection({
           {settingsStatus}
         </span>
       </div>
-      <div className="note">Updates the Active Sessions panel and refresh cycle.</div>
+      <div className="note">
+        Updates the Active Sessions panel and refresh cycle. Sessions below the first matching
+        pattern's token minimum are hidden, as are full or empty contexts when required open.
+      </div>
+      {launchAtLoginAvailable ? (
+        <>
+          <label className="label">Launch at Login</label>
+          <div className="row">
+            <button
+              className="button"
+              aria-pressed={launchAtLogin}
+              onClick={onToggleLaunchAtLogin}
+            >
+              {launchAtLogin ? "On" : "Off"}
+            </button>
+            <span className="status" role="status" aria-live="polite">
+              {launchStatus}
+            </span>
+          </div>
+          <div className="note">
+            Start Codex Tracker when you log in so tracking runs in the background.
+          </div>
+          <label className="label">Global Shortcut</label>
+          <input
+            className="input"
+            type="text"
+            placeholder="CommandOrControl+Shift+U"
+            value={shortcutInput}
+            onChange={(event) => onShortcutInputChange(event.target.value)}
+          />
+          <div className="row">
+            <button className="button" onClick={onSaveShortcut}>
+              Save Shortcut
+            </button>
+            <span className="status" role="status" aria-live="polite">
+              {shortcutStatus}
+            </span>
+          </div>
+          <div className="note">
+            Shows or hides the dashboard from any app. Leave empty to turn it off.
+          </div>
+          <label className="label">Updates</label>
+          <div className="row">
+            {availableUpdate ? (
+              <button className="button" onClick={onInstallUpdate}>
+                Install {availableUpdate} and Restart
+              </button>
+            ) : (
+              <button className="button" onClick={onCheckForUpdates}>
+                Check for Updates
+              </button>
+            )}
+            <span className="status" role="status" aria-live="polite">
+              {updateStatus}
+            </span>
+          </div>
+        </>
+      ) : null}
     </section>
   );
 }
@@ -1,9 +1,11 @@
 import type { SelectOption } from "../../../components/Select";
 import { SelectField } from "../../../components/Select";
-import type { CodexHome } from "../../../domain/types";
+import type { CodexHome, HomeCandidate } from "../../../domain/types";
+import { formatNumber } from "../../../shared/formatters";
 
 type HomesSectionProps = {
   homes: CodexHome[];
+  homeCandidates: HomeCandidate[] | null;
   activeHomeId: number | null;
   activeHome: CodexHome | null;
   homeSelectOptions: SelectOption[];
@@ -17,8 +19,11 @@ type HomesSectionProps = {
   onRefreshHomes: () => void;
   onSetActiveHome: (id: number) => void;
   onDeleteHome: (id: number) => void;
+  onArchiveHome: (id: number, archived: boolean) => void;
   onPickHomePath: () => void;
   onAddHome: () => void;
+  onDiscoverHomes: () => void;
+  onAddCandidate: (candidate: HomeCandidate) => void;
   onDeleteData: () => void;
   onNewHomePathChange: (value: string) => void;
   onNewHomeLabelChange: (value: string) => void;
@@ -27,6 +32,7 @@ type HomesSectionProps = {
 
 export function HomesSection({
   homes,
+  homeCandidates,
   activeHomeId,
   activeHome,
   homeSelectOptions,
@@ -40,8 +46,11 @@ export function HomesSection({
   onRefreshHomes,
   onSetActiveHome,
   onDeleteHome,
+  onArchiveHome,
   onPickHomePath,
   onAddHome,
+  onDiscoverHomes,
+  onAddCandidate,
   onDeleteData,
   onNewHomePathChange,
   onNewHomeLabelChange,
@@ -89,13 +98,28 @@ export function HomesSection({
                     <span className="mono">{home.path}</span>
                   </td>
                   <td>{home.last_seen_at ? new Date(home.last_seen_at).toLocaleString() : "-"}</td>
-                  <td>{home.id === activeHomeId ? <span className="badge">Active</span> : "—"}</td>
+                  <td>
+                    {home.id === activeHomeId ? (
+                      <span className="badge">Active</span>
+                    ) : home.archived ? (
+                      "Archived"
+                    ) : (
+                      "—"
+                    )}
+                  </td>
                   <td className="table-actions">
-                    {home.id !== activeHomeId && (
+                    {home.id !== activeHomeId && !home.archived && (
                       <button className="button ghost small" onClick={() => onSetActiveHome(home.id)}>
                         Make Active
                       </button>
                     )}
+                    <button
+                      className="button ghost small"
+                      onClick={() => onArchiveHome(home.id, !home.archived)}
+                      disabled={!home.archived && homes.length === 1}
+                    >
+                      {home.archived ? "Unarchive" : "Archive"}
+                    </button>
                     <button
                       className="button ghost small"
                       onClick={() => onDeleteHome(home.id)}
@@ -138,6 +162,39 @@ export function HomesSection({
         </div>
       </div>
       <label className="label">Add Home</label>
+      <div className="row">
+        <button className="button ghost small" type="button" onClick={onDiscoverHomes}>
+          Find Codex Homes
+        </button>
+      </div>
+      {homeCandidates && homeCandidates.length > 0 && (
+        <div className="table-wrap">
+          <table className="compact-table">
+            <thead>
+              <tr>
+                <th>Path</th>
+                <th>Session Logs</th>
+                <th>Actions</th>
+              </tr>
+            </thead>
+            <tbody>
+              {homeCandidates.map((candidate) => (
+                <tr key={candidate.path}>
+                  <td>
+                    <span className="mono">{candidate.path}</span>
+                  </td>
+                  <td>{formatNumber(candidate.log_files)}</td>
+                  <td className="table-actions">
+                    <button className="button ghost small" onClick={() => onAddCandidate(candidate)}>
+                      Add
+                    </button>
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        </div>
+      )}
       <div className="input-row">
         <input
           className="input"
@@ -0,0 +1,108 @@
+import type { ModelAlias } from "../../../domain/types";
+
+type ModelAliasesSectionProps = {
+  aliases: ModelAlias[];
+  dirty: boolean;
+  status: string;
+  onAdd: () => void;
+  onSave: () => void;
+  onUpdate: (index: number, patch: Partial<ModelAlias>) => void;
+  onDelete: (index: number) => void;
+};
+
+export function ModelAliasesSection({
+  aliases,
+  dirty,
+  status,
+  onAdd,
+  onSave,
+  onUpdate,
+  onDelete
+}: ModelAliasesSectionProps) {
+  return (
+    <section id="settings-model-aliases" className="panel settings-pricing settings-section">
+      <div className="panel-header">
+        <div>
+          <h2>Model Aliases</h2>
+          <p>Report and price a logged model name as another model.</p>
+        </div>
+      </div>
+      <div className="pricing-toolbar">
+        <div className="pricing-actions">
+          <button className="button ghost" onClick={onAdd}>
+            Add Alias
+          </button>
+          <button className="button" onClick={onSave} disabled={!dirty}>
+            Save Aliases
+          </button>
+        </div>
+        <div className="pricing-meta">
+          <span className={`status ${dirty ? "status-warn" : ""}`}>
+            {dirty ? "Unsaved changes" : "All changes saved"}
+          </span>
+          {status && (
+            <span className="status" role="status" aria-live="polite">
+              {status}
+            </span>
+          )}
+        </div>
+      </div>
+      {aliases.length === 0 ? (
+        <div className="note">No model aliases yet.</div>
+      ) : (
+        <div className="table-wrap">
+          <table className="pricing-table">
+            <thead>
+              <tr>
+                <th className="pricing-model-col">Logged Model</th>
+                <th className="pricing-model-col">Reported As</th>
+                <th>Actions</th>
+              </tr>
+            </thead>
+            <tbody>
+              {aliases.map((entry, index) => (
+                <tr key={index}>
+                  <td className="pricing-model-cell">
+                    <input
+                      className="input pricing-model"
+                      value={entry.alias}
+                      placeholder="gpt-5.2-codex-preview"
+                      onChange={(event) => onUpdate(index, { alias: event.target.value })}
+                    />
+                  </td>
+                  <td className="pricing-model-cell">
+                    <input
+                      className="input pricing-model"
+                      value={entry.model}
+                      placeholder="gpt-5.2-codex"
+                      onChange={(event) => onUpdate(index, { model: event.target.value })}
+                    />
+                  </td>
+                  <td className="pricing-actions-cell">
+                    <div className="table-actions table-actions-compact">
+                      <button
+                        className="icon-button icon-button-ghost small"
+                        type="button"
+                        onClick={() => onDelete(index)}
+                        aria-label="Delete model alias"
+                        title="Delete"
+                      >
+                        <svg viewBox="0 0 24 24" width="14" height="14" aria-hidden="true">
+                          <path d="M9 3h6l1 2h4v2H4V5h4l1-2z" fill="currentColor" opacity="0.6" />
+                          <path
+                            d="M6 7h12l-1 13a2 2 0 0 1-2 2H9a2 2 0 0 1-2-2L6 7z"
+                            fill="currentColor"
+                          />
+                        </svg>
+                      </button>
+                    </div>
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        </div>
+      )}
+    </section>
+  );
+}
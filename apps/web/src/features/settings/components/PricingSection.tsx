@@ -97,6 +97,7 @@ export function PricingSection({
             <thead>
               <tr>
                 <th className="pricing-model-col">Model Pattern</th>
+                <th>Provider</th>
                 <th>Input / 1M</th>
                 <th>Cached / 1M</th>
                 <th>Output / 1M</th>
@@ -127,6 +128,19 @@ export function PricingSection({
                           onUpdateRule(index, { model_pattern: event.target.value })
                         }
                       />
+                      {rule.fallback && (
+                        <div className="input-hint">Fallback for models no rule matches</div>
+                      )}
+                    </td>
+                    <td>
+                      <input
+                        className="input"
+                        value={rule.provider ?? ""}
+                        placeholder="Any"
+                        onChange={(event) =>
+                          onUpdateRule(index, { provider: event.target.value || null })
+                        }
+                      />
                     </td>
                     <td>
                       <input
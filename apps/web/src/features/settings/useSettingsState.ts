@@ -1,15 +1,31 @@
 import { useCallback, useEffect, useMemo, useState } from "react";
-import type { CodexHome, PricingRule, PricingRuleApi } from "../../domain/types";
+import type {
+  ActiveMinTokens,
+  ActiveSessionRule,
+  CodexHome,
+  HomeCandidate,
+  ModelAlias,
+  PricingRule,
+  PricingRuleApi
+} from "../../domain/types";
 import {
+  archiveHome,
+  checkForUpdates,
   clearHomeData,
   createHome,
   deleteHome,
+  discoverHomes,
   getSettings,
+  inspectHome,
+  installUpdate,
   listHomes,
+  listModelAliases,
   listPricing,
   recomputePricing,
+  replaceModelAliases,
   replacePricing,
   setActiveHome,
+  unarchiveHome,
   updateSettings
 } from "../../data/codexApi";
 import type { SettingsTabValue } from "../../shared/constants";
@@ -35,6 +51,7 @@ type SettingsStateOptions = {
 export function useSettingsState({ onToast, onDashboardRefresh }: SettingsStateOptions) {
   const [settingsTab, setSettingsTab] = useState<SettingsTabValue>("settings-homes");
   const [homes, setHomes] = useState<CodexHome[]>([]);
+  const [homeCandidates, setHomeCandidates] = useState<HomeCandidate[] | null>(null);
   const [activeHomeId, setActiveHomeId] = useState<number | null>(null);
   const [newHomePath, setNewHomePath] = useState("");
   const [newHomeLabel, setNewHomeLabel] = useState("");
@@ -48,9 +65,20 @@ export function useSettingsState({ onToast, onDashboardRefresh }: SettingsStateO
   const [pricingFilter, setPricingFilter] = useState("");
   const [pricingBusy, setPricingBusy] = useState(false);
   const [pricingLastRecompute, setPricingLastRecompute] = useState<string | null>(null);
+  const [modelAliases, setModelAliases] = useState<ModelAlias[]>([]);
+  const [aliasesDirty, setAliasesDirty] = useState(false);
+  const [aliasesStatus, setAliasesStatus] = useState("");
   const [storageInfo, setStorageInfo] = useState<StorageInfo | null>(null);
   const [activeMinutes, setActiveMinutes] = useState(60);
   const [activeMinutesInput, setActiveMinutesInput] = useState("60");
+  const [requireOpenContext, setRequireOpenContext] = useState(false);
+  const [minTokensInput, setMinTokensInput] = useState("");
+  const [launchAtLogin, setLaunchAtLogin] = useState(false);
+  const [launchStatus, setLaunchStatus] = useState("");
+  const [shortcutInput, setShortcutInput] = useState("");
+  const [shortcutStatus, setShortcutStatus] = useState("");
+  const [availableUpdate, setAvailableUpdate] = useState<string | null>(null);
+  const [updateStatus, setUpdateStatus] = useState("");
   const tauriAvailable = isTauriRuntime();
 
   const deleteReady = deleteConfirm.trim().toLowerCase() === "delete";
@@ -83,10 +111,11 @@ export function useSettingsState({ onToast, onDashboardRefresh }: SettingsStateO
   }, [homes, activeHomeId]);
 
   const homeSelectOptions = useMemo(() => {
-    if (homes.length === 0) {
+    const pickable = homes.filter((home) => !home.archived);
+    if (pickable.length === 0) {
       return [{ value: "none", label: "No homes found", disabled: true }];
     }
-    return homes.map((home) => ({
+    return pickable.map((home) => ({
       value: String(home.id),
       label: home.label || home.path
     }));
@@ -121,6 +150,18 @@ export function useSettingsState({ onToast, onDashboardRefresh }: SettingsStateO
     }
   }, [onToast]);
 
+  const refreshModelAliases = useCallback(async () => {
+    try {
+      setModelAliases(await listModelAliases());
+      setAliasesDirty(false);
+    } catch (err) {
+      onToast?.({
+        message: err instanceof Error ? err.message : "Failed to load model aliases",
+        tone: "error"
+      });
+    }
+  }, [onToast]);
+
   const refreshHomes = useCallback(async () => {
     try {
       const data = await listHomes();
@@ -140,6 +181,9 @@ export function useSettingsState({ onToast, onDashboardRefresh }: SettingsStateO
       const minutes = data.context_active_minutes ?? 60;
       setActiveMinutes(minutes);
       setActiveMinutesInput(minutes.toString());
+      applyActiveSessionRule(data.active_session_rule);
+      setLaunchAtLogin(data.launch_at_login ?? false);
+      setShortcutInput(data.global_shortcut ?? "");
       setStorageInfo({
         dbPath: data.db_path,
         pricingDefaultsPath: data.pricing_defaults_path,
@@ -156,9 +200,10 @@ export function useSettingsState({ onToast, onDashboardRefresh }: SettingsStateO
 
   useEffect(() => {
     refreshPricing();
+    refreshModelAliases();
     refreshHomes();
     refreshSettings();
-  }, [refreshHomes, refreshPricing, refreshSettings]);
+  }, [refreshHomes, refreshModelAliases, refreshPricing, refreshSettings]);
 
   useEffect(() => {
     const storedTab = safeStorageGet(STORAGE_KEYS.settingsTab);
@@ -176,19 +221,38 @@ export function useSettingsState({ onToast, onDashboardRefresh }: SettingsStateO
     safeStorageSet(STORAGE_KEYS.settingsTab, settingsTab);
   }, [settingsTab]);
 
-  async function validateHomePath(path: string) {
+  /**
+   * Desktop only: checks the folder before it becomes a home and asks for
+   * confirmation when no session logs were found in it. Resolves to the
+   * number of logs found, or null when the home should not be added.
+   */
+  async function inspectHomePath(path: string): Promise<number | null> {
     if (!tauriAvailable) {
-      return true;
+      return 0;
     }
     try {
-      const { exists } = await import("@tauri-apps/plugin-fs");
-      return await exists(path);
+      const inspection = await inspectHome(path);
+      if (!inspection.exists) {
+        setHomeStatus("Path does not exist");
+        return null;
+      }
+      if (inspection.log_files > 0) {
+        return inspection.log_files;
+      }
+      const problem = inspection.looks_like_codex_home
+        ? "has a sessions folder but no session logs yet"
+        : "does not look like a Codex home: no sessions folder or session logs found";
+      if (!window.confirm(`${inspection.path} ${problem}. Add it anyway?`)) {
+        setHomeStatus("Not added");
+        return null;
+      }
+      return 0;
     } catch (err) {
       onToast?.({
         message: err instanceof Error ? err.message : "Path validation unavailable",
         tone: "info"
       });
-      return true;
+      return 0;
     }
   }
 
@@ -211,15 +275,43 @@ export function useSettingsState({ onToast, onDashboardRefresh }: SettingsStateO
     }
   }
 
+  async function handleDiscoverHomes() {
+    setHomeStatus("Searching...");
+    try {
+      const found = await discoverHomes();
+      setHomeCandidates(found.filter((candidate) => candidate.home_id == null));
+      setHomeStatus(found.length ? "" : "No codex homes found");
+    } catch (err) {
+      setHomeStatus(err instanceof Error ? err.message : "Search failed");
+    }
+  }
+
+  async function handleAddCandidate(candidate: HomeCandidate) {
+    setHomeStatus("Adding...");
+    try {
+      const created = await createHome({ path: candidate.path });
+      setActiveHomeId(created.id);
+      setHomeCandidates(
+        (current) => current?.filter((item) => item.path !== candidate.path) ?? null
+      );
+      await refreshHomes();
+      onDashboardRefresh?.();
+      setHomeStatus(
+        `Added (${formatNumber(candidate.log_files)} session ${candidate.log_files === 1 ? "log" : "logs"})`
+      );
+    } catch (err) {
+      setHomeStatus(err instanceof Error ? err.message : "Add failed");
+    }
+  }
+
   async function handleAddHome() {
     const path = newHomePath.trim();
     if (!path) {
       setHomeStatus("Path required");
       return;
     }
-    const exists = await validateHomePath(path);
-    if (!exists) {
-      setHomeStatus("Path does not exist");
+    const logFiles = await inspectHomePath(path);
+    if (logFiles === null) {
       return;
     }
     setHomeStatus("Adding...");
@@ -233,7 +325,11 @@ export function useSettingsState({ onToast, onDashboardRefresh }: SettingsStateO
       setNewHomeLabel("");
       await refreshHomes();
       onDashboardRefresh?.();
-      setHomeStatus("Added");
+      setHomeStatus(
+        logFiles > 0
+          ? `Added (${formatNumber(logFiles)} session ${logFiles === 1 ? "log" : "logs"})`
+          : "Added"
+      );
     } catch (err) {
       setHomeStatus(err instanceof Error ? err.message : "Add failed");
     }
@@ -270,6 +366,18 @@ export function useSettingsState({ onToast, onDashboardRefresh }: SettingsStateO
     }
   }
 
+  async function handleArchiveHome(homeId: number, archived: boolean) {
+    setHomeStatus(archived ? "Archiving..." : "Restoring...");
+    try {
+      await (archived ? archiveHome(homeId) : unarchiveHome(homeId));
+      await refreshHomes();
+      onDashboardRefresh?.();
+      setHomeStatus(archived ? "Archived" : "Restored");
+    } catch (err) {
+      setHomeStatus(err instanceof Error ? err.message : "Update failed");
+    }
+  }
+
   async function handleDeleteData() {
     if (!activeHomeId) {
       setDangerStatus("Select a home first");
@@ -324,18 +432,59 @@ export function useSettingsState({ onToast, onDashboardRefresh }: SettingsStateO
     }
   }
 
+  async function handleSaveModelAliases() {
+    const aliases = modelAliases
+      .map((entry) => ({ alias: entry.alias.trim(), model: entry.model.trim() }))
+      .filter((entry) => entry.alias || entry.model);
+    if (aliases.some((entry) => !entry.alias || !entry.model)) {
+      setAliasesStatus("Each alias needs a logged name and a model");
+      return;
+    }
+    setAliasesStatus("Saving...");
+    try {
+      const result = await replaceModelAliases(aliases);
+      setModelAliases(aliases);
+      setAliasesDirty(false);
+      setAliasesStatus(`Saved, repriced ${formatNumber(result.updated)} rows`);
+      onDashboardRefresh?.();
+    } catch (err) {
+      setAliasesStatus(err instanceof Error ? err.message : "Save failed");
+    }
+  }
+
+  function applyActiveSessionRule(rule?: ActiveSessionRule) {
+    setRequireOpenContext(rule?.require_open_context ?? false);
+    setMinTokensInput(
+      (rule?.min_tokens ?? [])
+        .map((entry) => `${entry.model_pattern}=${entry.min_tokens}`)
+        .join(", ")
+    );
+  }
+
   async function handleSaveActiveMinutes() {
     const parsed = Number(activeMinutesInput);
     if (!Number.isFinite(parsed) || parsed <= 0) {
       setSettingsStatus("Enter a valid minute value");
       return;
     }
+    const minTokens = parseMinTokens(minTokensInput);
+    if (!minTokens) {
+      setSettingsStatus("Enter minimums as pattern=tokens, separated by commas");
+      return;
+    }
     setSettingsStatus("Saving...");
     try {
-      const data = await updateSettings({ context_active_minutes: parsed });
+      const data = await updateSettings({
+        context_active_minutes: parsed,
+        active_session_rule: {
+          require_open_context: requireOpenContext,
+          min_tokens: minTokens
+        }
+      });
       const minutes = data.context_active_minutes ?? parsed;
       setActiveMinutes(minutes);
       setActiveMinutesInput(minutes.toString());
+      applyActiveSessionRule(data.active_session_rule);
       setSettingsStatus("Saved");
       setStorageInfo({
         dbPath: data.db_path,
@@ -349,6 +498,54 @@ export function useSettingsState({ onToast, onDashboardRefresh }: SettingsStateO
     }
   }
 
+  async function handleToggleLaunchAtLogin() {
+    const next = !launchAtLogin;
+    setLaunchStatus("Saving...");
+    try {
+      const data = await updateSettings({ launch_at_login: next });
+      setLaunchAtLogin(data.launch_at_login ?? next);
+      setLaunchStatus("Saved");
+    } catch (err) {
+      setLaunchStatus(err instanceof Error ? err.message : "Save failed");
+    }
+  }
+
+  async function handleSaveShortcut() {
+    setShortcutStatus("Saving...");
+    try {
+      const data = await updateSettings({ global_shortcut: shortcutInput.trim() });
+      setShortcutInput(data.global_shortcut ?? "");
+      setShortcutStatus(data.global_shortcut ? "Saved" : "Turned off");
+    } catch (err) {
+      setShortcutStatus(err instanceof Error ? err.message : "Save failed");
+    }
+  }
+
+  async function handleCheckForUpdates() {
+    setUpdateStatus("Checking...");
+    try {
+      const result = await checkForUpdates();
+      setAvailableUpdate(result.version);
+      setUpdateStatus(
+        result.version
+          ? `Version ${result.version} is available`
+          : `Up to date (${result.current_version})`
+      );
+    } catch (err) {
+      setUpdateStatus(err instanceof Error ? err.message : "Update check failed");
+    }
+  }
+
+  async function handleInstallUpdate() {
+    setUpdateStatus("Installing...");
+    try {
+      // Restarts into the new version once installed.
+      await installUpdate();
+    } catch (err) {
+      setUpdateStatus(err instanceof Error ? err.message : "Update failed");
+    }
+  }
+
   async function handleCopyPath(value?: string) {
     if (!value) {
       return;
@@ -432,10 +629,28 @@ export function useSettingsState({ onToast, onDashboardRefresh }: SettingsStateO
     setPricingDirty(true);
   }
 
+  function addModelAlias() {
+    setModelAliases((prev) => [...prev, { alias: "", model: "" }]);
+    setAliasesDirty(true);
+  }
+
+  function updateModelAlias(index: number, patch: Partial<ModelAlias>) {
+    setModelAliases((prev) =>
+      prev.map((entry, idx) => (idx === index ? { ...entry, ...patch } : entry))
+    );
+    setAliasesDirty(true);
+  }
+
+  function deleteModelAlias(index: number) {
+    setModelAliases((prev) => prev.filter((_, idx) => idx !== index));
+    setAliasesDirty(true);
+  }
+
   return {
     settingsTab,
     setSettingsTab,
     homes,
+    homeCandidates,
     activeHomeId,
     activeHome,
     homeSelectOptions,
@@ -455,11 +670,25 @@ export function useSettingsState({ onToast, onDashboardRefresh }: SettingsStateO
     pricingHasIssues,
     pricingIssueMap,
     pricingRows,
+    modelAliases,
+    aliasesDirty,
+    aliasesStatus,
     storageInfo,
     tauriAvailable,
     activeMinutes,
     activeMinutesInput,
     setActiveMinutesInput,
+    requireOpenContext,
+    setRequireOpenContext,
+    minTokensInput,
+    setMinTokensInput,
+    launchAtLogin,
+    launchStatus,
+    shortcutInput,
+    setShortcutInput,
+    shortcutStatus,
+    availableUpdate,
+    updateStatus,
     setNewHomeLabel,
     setNewHomePath,
     setDeleteConfirm,
@@ -467,19 +696,46 @@ export function useSettingsState({ onToast, onDashboardRefresh }: SettingsStateO
     refreshHomes,
     handlePickHomePath,
     handleAddHome,
+    handleDiscoverHomes,
+    handleAddCandidate,
     handleSetActiveHome,
     handleDeleteHome,
+    handleArchiveHome,
     handleDeleteData,
     handleSavePricing,
     handleRecomputeCosts,
+    handleSaveModelAliases,
     handleSaveActiveMinutes,
+    handleToggleLaunchAtLogin,
+    handleSaveShortcut,
+    handleCheckForUpdates,
+    handleInstallUpdate,
     handleCopyPath,
     handleRevealPath,
     updatePricingRule,
     addPricingRule,
     duplicatePricingRule,
-    deletePricingRule
+    deletePricingRule,
+    addModelAlias,
+    updateModelAlias,
+    deleteModelAlias
   };
 }
 
 export type SettingsState = ReturnType<typeof useSettingsState>;
+
+function parseMinTokens(input: string): ActiveMinTokens[] | null {
+  const entries: ActiveMinTokens[] = [];
+  for (const part of input.split(",")) {
+    if (!part.trim()) {
+      continue;
+    }
+    const [pattern, tokens] = part.split("=").map((value) => value.trim());
+    const minTokens = Number(tokens);
+    if (!pattern || !Number.isInteger(minTokens) || minTokens < 0) {
+      return null;
+    }
+    entries.push({ model_pattern: pattern, min_tokens: minTokens });
+  }
+  return entries;
+}
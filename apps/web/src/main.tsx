@@ -1,16 +1,22 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
 import App from "./App";
+import { MiniWidget } from "./features/mini/MiniWidget";
 import "./styles.css";
 
+// The desktop app's mini window loads the same bundle and marks itself
+// before the page runs.
+const isMiniView =
+  (window as Window & { __CODEX_TRACKER_VIEW__?: string }).__CODEX_TRACKER_VIEW__ === "mini";
+
 ReactDOM.createRoot(document.getElementById("root")!).render(
-  <React.StrictMode>
-    <App />
-  </React.StrictMode>
+  <React.StrictMode>{isMiniView ? <MiniWidget /> : <App />}</React.StrictMode>
 );
 
 const bootScreen = document.getElementById("boot-screen");
-if (bootScreen) {
+if (bootScreen && isMiniView) {
+  bootScreen.remove();
+} else if (bootScreen) {
   window.setTimeout(() => {
     window.requestAnimationFrame(() => {
       bootScreen.classList.add("is-hidden");
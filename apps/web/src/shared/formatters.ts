@@ -1,3 +1,5 @@
+import type { TokenEfficiency } from "../domain/types";
+
 const resolvedLocale = "en-US";
 
 const currency = new Intl.NumberFormat(resolvedLocale, {
@@ -69,6 +71,16 @@ export function formatPercent(value: number | null | undefined) {
   return `${value.toFixed(1)}%`;
 }
 
+export function formatEfficiency(efficiency: TokenEfficiency) {
+  const share = (value: number | null) =>
+    value === null ? "-" : formatPercent(value * 100);
+  const ratio =
+    efficiency.output_input_ratio === null ? "-" : efficiency.output_input_ratio.toFixed(2);
+  return `Output/input ${ratio} · reasoning ${share(
+    efficiency.reasoning_output_share
+  )} of output · cached ${share(efficiency.cached_input_share)} of input`;
+}
+
 export function formatPercentWhole(value: number | null | undefined) {
   if (value === null || value === undefined) return "-";
   return `${Math.round(value)}%`;
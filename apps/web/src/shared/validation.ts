@@ -43,9 +43,11 @@ export function validatePricingRules(rules: PricingRule[]): PricingIssue[] {
         field: "range"
       });
     }
-    const list = rangesByModel.get(rule.model_pattern) ?? [];
+    // Rules for different providers may cover the same dates.
+    const key = `${rule.model_pattern}\u0000${(rule.provider ?? "").trim().toLowerCase()}`;
+    const list = rangesByModel.get(key) ?? [];
     list.push({ index, start, end });
-    rangesByModel.set(rule.model_pattern, list);
+    rangesByModel.set(key, list);
   });
 
   rangesByModel.forEach((ranges) => {
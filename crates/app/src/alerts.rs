@@ -0,0 +1,265 @@
+use chrono::{DateTime, Datelike, Local, SecondsFormat, TimeZone};
+use serde::Serialize;
+use tracker_core::{CodexHome, UsageLimitSnapshot};
+use tracker_db::Db;
+
+use crate::config::RangeParams;
+use crate::error::Result;
+use crate::util::time::resolve_range;
+
+/// Used-percent levels that raise a limit alert when crossed.
+pub const LIMIT_ALERT_THRESHOLDS: [f64; 3] = [50.0, 75.0, 90.0];
+
+/// Used-percent levels of a limit window or the monthly budget that raise a
+/// desktop notification.
+pub const DESKTOP_ALERT_THRESHOLDS: [f64; 2] = [80.0, 95.0];
+
+const LIMIT_TYPES: [&str; 2] = ["5h", "7d"];
+
+/// Point-in-time view of the values alerts are evaluated against.
+#[derive(Debug, Clone, Default)]
+pub struct AlertSnapshot {
+    pub limits: Vec<UsageLimitSnapshot>,
+    pub month_cost_usd: f64,
+    pub monthly_budget_usd: Option<f64>,
+    /// Start of next month (RFC 3339), when month-to-date spend resets.
+    pub budget_reset_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Alert {
+    LimitThreshold {
+        limit_type: String,
+        threshold_percent: f64,
+        used_percent: f64,
+        reset_at: String,
+    },
+    BudgetExceeded {
+        monthly_budget_usd: f64,
+        month_cost_usd: f64,
+    },
+    /// Month-to-date spend passed a share of the budget; raised by
+    /// [`crossed_thresholds`] only.
+    BudgetThreshold {
+        threshold_percent: f64,
+        monthly_budget_usd: f64,
+        month_cost_usd: f64,
+        reset_at: String,
+    },
+}
+
+pub fn capture(db: &Db, home: &CodexHome) -> Result<AlertSnapshot> {
+    let mut limits = Vec::new();
+    for limit_type in LIMIT_TYPES {
+        if let Some(snapshot) = db.latest_limit_snapshot_current(home.id, limit_type)? {
+            limits.push(snapshot);
+        }
+    }
+    let month = resolve_range(&RangeParams {
+        range: Some("thismonth".to_string()),
+        start: None,
+        end: None,
+    })?;
+    let summary = db.summary(&month, home.id)?;
+    Ok(AlertSnapshot {
+        limits,
+        month_cost_usd: summary.total_cost_usd.unwrap_or(0.0),
+        monthly_budget_usd: db.get_monthly_budget_usd()?,
+        budget_reset_at: next_month_start(Local::now()).unwrap_or_default(),
+    })
+}
+
+fn next_month_start(now: DateTime<Local>) -> Option<String> {
+    let (year, month) = match now.month() {
+        12 => (now.year() + 1, 1),
+        month => (now.year(), month + 1),
+    };
+    let start = Local.with_ymd_and_hms(year, month, 1, 0, 0, 0).earliest()?;
+    Some(start.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+/// Returns the alerts whose thresholds were crossed between two snapshots.
+///
+/// Only the highest crossed limit threshold is reported per limit type so a
+/// single ingest that jumps from 40% to 95% produces one alert, not three.
+pub fn crossed(before: &AlertSnapshot, after: &AlertSnapshot) -> Vec<Alert> {
+    let mut alerts = limit_crossings(before, after, &LIMIT_ALERT_THRESHOLDS);
+    if let Some(budget) = after.monthly_budget_usd
+        && before.month_cost_usd < budget
+        && after.month_cost_usd >= budget
+    {
+        alerts.push(Alert::BudgetExceeded {
+            monthly_budget_usd: budget,
+            month_cost_usd: after.month_cost_usd,
+        });
+    }
+    alerts
+}
+
+/// Like [`crossed`], but checks limit windows and the monthly budget against
+/// the same used-percent `thresholds`, reporting the highest crossed one.
+pub fn crossed_thresholds(
+    before: &AlertSnapshot,
+    after: &AlertSnapshot,
+    thresholds: &[f64],
+) -> Vec<Alert> {
+    let mut alerts = limit_crossings(before, after, thresholds);
+    if let Some(budget) = after.monthly_budget_usd.filter(|budget| *budget > 0.0) {
+        let used_before = before.month_cost_usd / budget * 100.0;
+        let used_after = after.month_cost_usd / budget * 100.0;
+        if let Some(threshold_percent) = highest_crossed(thresholds, used_before, used_after) {
+            alerts.push(Alert::BudgetThreshold {
+                threshold_percent,
+                monthly_budget_usd: budget,
+                month_cost_usd: after.month_cost_usd,
+                reset_at: after.budget_reset_at.clone(),
+            });
+        }
+    }
+    alerts
+}
+
+fn highest_crossed(thresholds: &[f64], before: f64, after: f64) -> Option<f64> {
+    thresholds
+        .iter()
+        .copied()
+        .rfind(|threshold| before < *threshold && after >= *threshold)
+}
+
+fn limit_crossings(
+    before: &AlertSnapshot,
+    after: &AlertSnapshot,
+    thresholds: &[f64],
+) -> Vec<Alert> {
+    let mut alerts = Vec::new();
+    for current in &after.limits {
+        let used_after = 100.0 - current.percent_left;
+        let used_before = before
+            .limits
+            .iter()
+            .find(|prev| prev.limit_type == current.limit_type && prev.reset_at == current.reset_at)
+            .map(|prev| 100.0 - prev.percent_left)
+            .unwrap_or(0.0);
+        if let Some(threshold_percent) = highest_crossed(thresholds, used_before, used_after) {
+            alerts.push(Alert::LimitThreshold {
+                limit_type: current.limit_type.clone(),
+                threshold_percent,
+                used_percent: used_after,
+                reset_at: current.reset_at.to_string(),
+            });
+        }
+    }
+    alerts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit(limit_type: &str, percent_left: f64, reset_at: &str) -> UsageLimitSnapshot {
+        UsageLimitSnapshot {
+            limit_type: limit_type.to_string(),
+            percent_left,
+            reset_at: reset_at.parse().expect("timestamp"),
+            observed_at: "2025-12-19T10:00:00Z".parse().expect("timestamp"),
+            source: "source-a".to_string(),
+            raw_line: None,
+        }
+    }
+
+    #[test]
+    fn reports_highest_crossed_limit_threshold() {
+        let before = AlertSnapshot {
+            limits: vec![limit("5h", 60.0, "2025-12-19T12:00:00Z")],
+            ..Default::default()
+        };
+        let after = AlertSnapshot {
+            limits: vec![limit("5h", 5.0, "2025-12-19T12:00:00Z")],
+            ..Default::default()
+        };
+        let alerts = crossed(&before, &after);
+        assert_eq!(alerts.len(), 1);
+        match &alerts[0] {
+            Alert::LimitThreshold {
+                threshold_percent, ..
+            } => assert_eq!(*threshold_percent, 90.0),
+            other => panic!("unexpected alert {other:?}"),
+        }
+    }
+
+    #[test]
+    fn new_window_counts_from_zero() {
+        let before = AlertSnapshot {
+            limits: vec![limit("7d", 5.0, "2025-12-19T12:00:00Z")],
+            ..Default::default()
+        };
+        let after = AlertSnapshot {
+            limits: vec![limit("7d", 40.0, "2025-12-26T12:00:00Z")],
+            ..Default::default()
+        };
+        let alerts = crossed(&before, &after);
+        assert_eq!(alerts.len(), 1);
+    }
+
+    #[test]
+    fn budget_alert_fires_once() {
+        let before = AlertSnapshot {
+            month_cost_usd: 90.0,
+            monthly_budget_usd: Some(100.0),
+            ..Default::default()
+        };
+        let after = AlertSnapshot {
+            month_cost_usd: 120.0,
+            monthly_budget_usd: Some(100.0),
+            ..Default::default()
+        };
+        assert_eq!(
+            crossed(&before, &after),
+            vec![Alert::BudgetExceeded {
+                monthly_budget_usd: 100.0,
+                month_cost_usd: 120.0,
+            }]
+        );
+        assert!(crossed(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn desktop_thresholds_cover_limits_and_budget() {
+        let before = AlertSnapshot {
+            limits: vec![limit("5h", 30.0, "2025-12-19T12:00:00Z")],
+            month_cost_usd: 70.0,
+            monthly_budget_usd: Some(100.0),
+            budget_reset_at: "2026-01-01T00:00:00Z".to_string(),
+        };
+        let after = AlertSnapshot {
+            limits: vec![limit("5h", 4.0, "2025-12-19T12:00:00Z")],
+            month_cost_usd: 85.0,
+            ..before.clone()
+        };
+        let alerts = crossed_thresholds(&before, &after, &DESKTOP_ALERT_THRESHOLDS);
+        assert_eq!(alerts.len(), 2);
+        match &alerts[0] {
+            Alert::LimitThreshold {
+                threshold_percent,
+                reset_at,
+                ..
+            } => {
+                assert_eq!(*threshold_percent, 95.0);
+                assert_eq!(reset_at, "2025-12-19T12:00:00.000Z");
+            }
+            other => panic!("unexpected alert {other:?}"),
+        }
+        assert_eq!(
+            alerts[1],
+            Alert::BudgetThreshold {
+                threshold_percent: 80.0,
+                monthly_budget_usd: 100.0,
+                month_cost_usd: 85.0,
+                reset_at: "2026-01-01T00:00:00Z".to_string(),
+            }
+        );
+        // The 50% limit level only applies to webhooks and chat.
+        assert!(crossed_thresholds(&after, &after, &DESKTOP_ALERT_THRESHOLDS).is_empty());
+    }
+}
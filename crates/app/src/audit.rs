@@ -0,0 +1,154 @@
+//! Integrity audit behind `codex-tracker db audit`.
+//!
+//! Re-derives a range's usage from the rollout files, without the ingest
+//! pipeline's cursors or its delta code, and diffs it per session log
+//! against what the database reports for the same range. A divergence
+//! points at a cursor that skipped or repeated lines, or at delta logic
+//! that no longer agrees with the logs.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracker_core::{CodexHome, TimeRange, Timestamp};
+use tracker_db::SourceUsage;
+
+use crate::error::{AppError, Result};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct AuditTotals {
+    pub events: u64,
+    pub total_tokens: u64,
+}
+
+impl AuditTotals {
+    fn add(&mut self, other: AuditTotals) {
+        self.events += other.events;
+        self.total_tokens += other.total_tokens;
+    }
+}
+
+impl From<SourceUsage> for AuditTotals {
+    fn from(usage: SourceUsage) -> Self {
+        Self {
+            events: usage.events,
+            total_tokens: usage.usage.total_tokens,
+        }
+    }
+}
+
+/// A session log whose events or tokens in the range differ between the
+/// file and the database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SourceDivergence {
+    pub source: String,
+    pub log: AuditTotals,
+    pub db: AuditTotals,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditReport {
+    pub home_id: i64,
+    pub start: String,
+    pub end: String,
+    /// Log files read; files last modified before `start` cannot hold
+    /// events in the range and are skipped.
+    pub files_scanned: usize,
+    /// Session logs with usage in the range, in the files or the database.
+    pub sources_checked: usize,
+    pub log: AuditTotals,
+    pub db: AuditTotals,
+    pub divergences: Vec<SourceDivergence>,
+}
+
+/// Re-reads the home's logs for the range and diffs them against
+/// `db_usage`, the database's [`tracker_db::Db::usage_by_source`] for the
+/// same range.
+pub fn audit_home(
+    home: &CodexHome,
+    range: &TimeRange,
+    db_usage: &BTreeMap<String, SourceUsage>,
+) -> Result<AuditReport> {
+    let (files_scanned, log_usage) = log_usage_by_source(Path::new(&home.path), range)?;
+    let mut sources = log_usage.keys().chain(db_usage.keys()).collect::<Vec<_>>();
+    sources.sort();
+    sources.dedup();
+    let mut report = AuditReport {
+        home_id: home.id,
+        start: range.start.clone(),
+        end: range.end.clone(),
+        files_scanned,
+        sources_checked: sources.len(),
+        log: AuditTotals::default(),
+        db: AuditTotals::default(),
+        divergences: Vec::new(),
+    };
+    for source in sources {
+        let totals = |usage: &BTreeMap<String, SourceUsage>| {
+            usage
+                .get(source)
+                .copied()
+                .map(AuditTotals::from)
+                .unwrap_or_default()
+        };
+        let (log, db) = (totals(&log_usage), totals(db_usage));
+        report.log.add(log);
+        report.db.add(db);
+        if log != db {
+            report.divergences.push(SourceDivergence {
+                source: source.clone(),
+                log,
+                db,
+            });
+        }
+    }
+    Ok(report)
+}
+
+/// Usage per session log with events in the range, keyed by path like the
+/// database's sources, and how many files were read.
+fn log_usage_by_source(
+    codex_home: &Path,
+    range: &TimeRange,
+) -> Result<(usize, BTreeMap<String, SourceUsage>)> {
+    let parse = |value: &str| {
+        value
+            .parse::<Timestamp>()
+            .map_err(|err| AppError::BadRange(format!("invalid range bound {value}: {err}")))
+    };
+    let (start, end) = (parse(&range.start)?, parse(&range.end)?);
+    let mut files_scanned = 0;
+    let mut by_source = BTreeMap::new();
+    for path in ingest::log_files(codex_home) {
+        let modified = fs::metadata(&path).and_then(|metadata| metadata.modified());
+        if modified.is_ok_and(|modified| DateTime::<Utc>::from(modified) < start.as_datetime()) {
+            continue;
+        }
+        let Ok(file) = File::open(&path) else {
+            continue;
+        };
+        files_scanned += 1;
+        let source = path.to_string_lossy().to_string();
+        let mut seen = HashSet::new();
+        let mut events = ingest::usage_events_from_reader(BufReader::new(file), &source)
+            .into_iter()
+            .filter(|event| event.ts >= start && event.ts < end && seen.insert(event.id.clone()))
+            .collect::<Vec<_>>();
+        events.sort_by_key(|event| event.ts);
+        let count = events.len() as u64;
+        if let Some(usage) = ingest::totals_from_usage(events.into_iter().map(|event| event.usage))
+        {
+            by_source.insert(
+                source,
+                SourceUsage {
+                    events: count,
+                    usage,
+                },
+            );
+        }
+    }
+    Ok((files_scanned, by_source))
+}
@@ -0,0 +1,182 @@
+use crate::alerts::LIMIT_ALERT_THRESHOLDS;
+
+/// Badges served under `/badge/<name>.svg`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BadgeKind {
+    TodayCost,
+    TodayTokens,
+    Limit5h,
+    Limit7d,
+}
+
+impl BadgeKind {
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        match name.strip_suffix(".svg")? {
+            "today-cost" => Some(BadgeKind::TodayCost),
+            "today-tokens" => Some(BadgeKind::TodayTokens),
+            "limit-5h" => Some(BadgeKind::Limit5h),
+            "limit-7d" => Some(BadgeKind::Limit7d),
+            _ => None,
+        }
+    }
+}
+
+const COLOR_INFO: &str = "#007ec6";
+const COLOR_UNKNOWN: &str = "#9f9f9f";
+/// Colors for used-percent below each alert threshold, then above the last.
+const LIMIT_COLORS: [&str; 4] = ["#4c1", "#dfb317", "#fe7d37", "#e05d44"];
+
+/// A two-part "label | message" badge in the shields.io flat style.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Badge {
+    pub label: String,
+    pub message: String,
+    pub color: &'static str,
+}
+
+impl Badge {
+    pub fn today_cost(cost_usd: Option<f64>) -> Self {
+        Self {
+            label: "codex today".to_string(),
+            message: cost_usd.map_or_else(|| "n/a".to_string(), |cost| format!("${cost:.2}")),
+            color: if cost_usd.is_some() {
+                COLOR_INFO
+            } else {
+                COLOR_UNKNOWN
+            },
+        }
+    }
+
+    pub fn today_tokens(tokens: u64) -> Self {
+        Self {
+            label: "codex today".to_string(),
+            message: format!("{} tokens", format_compact(tokens)),
+            color: COLOR_INFO,
+        }
+    }
+
+    /// `percent_left` as reported by Codex; `None` when no current window.
+    pub fn limit(limit_type: &str, percent_left: Option<f64>) -> Self {
+        let label = format!("codex {limit_type} limit");
+        match percent_left {
+            Some(left) => {
+                let used = (100.0 - left).clamp(0.0, 100.0);
+                let band = LIMIT_ALERT_THRESHOLDS
+                    .iter()
+                    .filter(|threshold| used >= **threshold)
+                    .count();
+                Self {
+                    label,
+                    message: format!("{used:.0}% used"),
+                    color: LIMIT_COLORS[band],
+                }
+            }
+            None => Self {
+                label,
+                message: "n/a".to_string(),
+                color: COLOR_UNKNOWN,
+            },
+        }
+    }
+
+    pub fn to_svg(&self) -> String {
+        let label = escape_xml(&self.label);
+        let message = escape_xml(&self.message);
+        let label_width = text_width(&self.label) + 10;
+        let message_width = text_width(&self.message) + 10;
+        let width = label_width + message_width;
+        let label_x = label_width as f64 / 2.0;
+        let message_x = label_width as f64 + message_width as f64 / 2.0;
+        let color = self.color;
+        format!(
+            concat!(
+                r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {message}">"##,
+                r##"<title>{label}: {message}</title>"##,
+                r##"<linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>"##,
+                r##"<clipPath id="r"><rect width="{width}" height="20" rx="3" fill="#fff"/></clipPath>"##,
+                r##"<g clip-path="url(#r)"><rect width="{label_width}" height="20" fill="#555"/><rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/><rect width="{width}" height="20" fill="url(#s)"/></g>"##,
+                r##"<g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">"##,
+                r##"<text x="{label_x}" y="15" fill="#010101" fill-opacity=".3">{label}</text><text x="{label_x}" y="14">{label}</text>"##,
+                r##"<text x="{message_x}" y="15" fill="#010101" fill-opacity=".3">{message}</text><text x="{message_x}" y="14">{message}</text>"##,
+                r##"</g></svg>"##
+            ),
+            width = width,
+            label = label,
+            message = message,
+            label_width = label_width,
+            message_width = message_width,
+            color = color,
+            label_x = label_x,
+            message_x = message_x,
+        )
+    }
+}
+
+/// Rough Verdana 11px advance; close enough for short ASCII labels.
+fn text_width(text: &str) -> u32 {
+    text.chars()
+        .map(|ch| match ch {
+            'i' | 'l' | '.' | ',' | ':' | '\'' | '|' => 4,
+            ' ' | 'f' | 'j' | 'r' | 't' => 5,
+            'm' | 'w' | 'M' | 'W' | '%' => 10,
+            _ => 7,
+        })
+        .sum()
+}
+
+pub(crate) fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub(crate) fn format_compact(value: u64) -> String {
+    const UNITS: [(u64, &str); 3] = [(1_000_000_000, "B"), (1_000_000, "M"), (1_000, "k")];
+    for (scale, suffix) in UNITS {
+        if value >= scale {
+            return format!("{:.1}{suffix}", value as f64 / scale as f64);
+        }
+    }
+    value.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_badge_names() {
+        assert_eq!(
+            BadgeKind::from_file_name("today-cost.svg"),
+            Some(BadgeKind::TodayCost)
+        );
+        assert_eq!(
+            BadgeKind::from_file_name("limit-7d.svg"),
+            Some(BadgeKind::Limit7d)
+        );
+        assert_eq!(BadgeKind::from_file_name("today-cost.png"), None);
+        assert_eq!(BadgeKind::from_file_name("nope.svg"), None);
+    }
+
+    #[test]
+    fn limit_badge_color_follows_alert_thresholds() {
+        assert_eq!(Badge::limit("5h", Some(80.0)).color, "#4c1");
+        assert_eq!(Badge::limit("5h", Some(40.0)).color, "#dfb317");
+        assert_eq!(Badge::limit("5h", Some(20.0)).color, "#fe7d37");
+        assert_eq!(Badge::limit("5h", Some(5.0)).color, "#e05d44");
+        assert_eq!(Badge::limit("5h", Some(5.0)).message, "95% used");
+        assert_eq!(Badge::limit("7d", None).message, "n/a");
+    }
+
+    #[test]
+    fn renders_svg_with_escaped_text() {
+        let svg = Badge::today_cost(Some(1.5)).to_svg();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(">$1.50</text>"));
+        assert_eq!(format_compact(1_234_567), "1.2M");
+        assert_eq!(format_compact(999), "999");
+        assert_eq!(escape_xml("a<b&c"), "a&lt;b&amp;c");
+    }
+}
@@ -0,0 +1,287 @@
+//! Settings bundles: pricing, model aliases, the monthly budget, notification
+//! channels, webhooks, homes, and desktop preferences as one JSON document, so
+//! a setup can be reproduced on another machine. Usage data, ingest cursors,
+//! and API tokens stay behind.
+//!
+//! Bundles hold secrets (channel URLs and webhook signing secrets), so they
+//! should be stored like a password file.
+
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use tracker_core::{ActiveSessionRule, ModelAlias, PricingRuleInput};
+
+use crate::error::{AppError, Result};
+use crate::notifications::Channel;
+use crate::services::{AppServices, validate_active_session_rule};
+use crate::webhooks::EVENT_TYPES;
+
+/// Bumped when a bundle can no longer be read by older releases.
+pub const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SettingsBundle {
+    pub version: u32,
+    pub exported_at: String,
+    pub settings: BundleSettings,
+    /// An empty list keeps the current pricing rules.
+    #[serde(default)]
+    pub pricing: Vec<PricingRuleInput>,
+    /// An empty list keeps the current aliases.
+    #[serde(default)]
+    pub model_aliases: Vec<ModelAlias>,
+    #[serde(default)]
+    pub homes: Vec<BundleHome>,
+    #[serde(default)]
+    pub webhooks: Vec<BundleWebhook>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BundleSettings {
+    pub context_active_minutes: u32,
+    pub monthly_budget_usd: Option<f64>,
+    pub auto_ingest_secs: u32,
+    pub launch_at_login: bool,
+    pub global_shortcut: String,
+    pub slack_webhook_url: Option<String>,
+    pub discord_webhook_url: Option<String>,
+    pub daily_summary: bool,
+    #[serde(default)]
+    pub active_session_rule: ActiveSessionRule,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BundleHome {
+    pub path: String,
+    pub label: String,
+    #[serde(default)]
+    pub active: bool,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub user: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BundleWebhook {
+    pub url: String,
+    pub secret: Option<String>,
+    pub event_types: Vec<String>,
+}
+
+/// What [`import_bundle`] changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BundleImportStats {
+    pub pricing_rules: usize,
+    pub model_aliases: usize,
+    /// Homes created; homes whose path is already tracked are relabeled.
+    pub homes_created: usize,
+    /// Webhooks created; ones whose URL is already registered are skipped.
+    pub webhooks_created: usize,
+}
+
+pub fn export_bundle(services: &AppServices) -> Result<SettingsBundle> {
+    let snapshot = services.settings.get()?;
+    let notifications = services.notifications.settings()?;
+    let pricing = services
+        .pricing
+        .list_rules()?
+        .into_iter()
+        .map(PricingRuleInput::from)
+        .collect();
+    let model_aliases = services.pricing.list_aliases()?;
+    let homes = services
+        .homes
+        .list()?
+        .into_iter()
+        .map(|home| BundleHome {
+            active: home.id == snapshot.active_home_id,
+            path: home.path,
+            label: home.label,
+            archived: home.archived,
+            user: home.user,
+        })
+        .collect();
+    let webhooks = services
+        .webhooks
+        .list()?
+        .into_iter()
+        .map(|hook| BundleWebhook {
+            url: hook.url,
+            secret: hook.secret,
+            event_types: hook.event_types,
+        })
+        .collect();
+    Ok(SettingsBundle {
+        version: BUNDLE_VERSION,
+        exported_at: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        settings: BundleSettings {
+            context_active_minutes: snapshot.context_active_minutes,
+            monthly_budget_usd: snapshot.monthly_budget_usd,
+            auto_ingest_secs: snapshot.auto_ingest_secs,
+            launch_at_login: snapshot.launch_at_login,
+            global_shortcut: snapshot.global_shortcut,
+            slack_webhook_url: notifications.slack_webhook_url,
+            discord_webhook_url: notifications.discord_webhook_url,
+            daily_summary: notifications.daily_summary,
+            active_session_rule: snapshot.active_session_rule,
+        },
+        pricing,
+        model_aliases,
+        homes,
+        webhooks,
+    })
+}
+
+/// Applies a bundle on top of the current setup. Everything is validated
+/// first, so a bad bundle changes nothing. Homes and webhooks are merged by
+/// path and URL; settings, channels, and (non-empty) pricing and model
+/// aliases are replaced.
+pub fn import_bundle(services: &AppServices, bundle: &SettingsBundle) -> Result<BundleImportStats> {
+    validate(bundle)?;
+    let mut stats = BundleImportStats::default();
+
+    let settings = &bundle.settings;
+    services.settings.update(
+        None,
+        Some(settings.context_active_minutes),
+        // Zero clears the budget.
+        Some(settings.monthly_budget_usd.unwrap_or(0.0)),
+        Some(settings.auto_ingest_secs),
+        Some(settings.launch_at_login),
+        Some(&settings.global_shortcut),
+    )?;
+    services
+        .settings
+        .set_active_session_rule(&settings.active_session_rule)?;
+    let notifications = &services.notifications;
+    notifications.set_channel_url(
+        Channel::Slack,
+        settings.slack_webhook_url.as_deref().unwrap_or(""),
+    )?;
+    notifications.set_channel_url(
+        Channel::Discord,
+        settings.discord_webhook_url.as_deref().unwrap_or(""),
+    )?;
+    notifications.set_daily_summary(settings.daily_summary)?;
+
+    if !bundle.pricing.is_empty() {
+        stats.pricing_rules = services.pricing.replace_rules(&bundle.pricing)?;
+    }
+    if !bundle.model_aliases.is_empty() {
+        stats.model_aliases = services.pricing.replace_aliases(&bundle.model_aliases)?;
+    }
+
+    let homes = &services.homes;
+    let previous_active = homes.active()?;
+    let mut active = None;
+    let mut archived = Vec::new();
+    for entry in &bundle.homes {
+        let existing = homes
+            .list()?
+            .into_iter()
+            .find(|home| home.path == entry.path);
+        let home = match existing {
+            Some(home) => homes.update(home.id, Some(&entry.label), None, None)?,
+            None => {
+                stats.homes_created += 1;
+                homes.create(&entry.path, Some(&entry.label))?
+            }
+        };
+        if let Some(user) = &entry.user {
+            homes.update(home.id, None, None, Some(user))?;
+        }
+        if entry.active {
+            active = Some(home.id);
+        }
+        if entry.archived {
+            archived.push(home.id);
+        } else if home.archived {
+            homes.unarchive(home.id)?;
+        }
+    }
+    // Creating a home makes it active; settle on the bundle's choice.
+    homes.set_active(active.unwrap_or(previous_active.id))?;
+    for id in archived {
+        homes.archive(id)?;
+    }
+
+    let existing = services.webhooks.list()?;
+    for hook in &bundle.webhooks {
+        if existing.iter().any(|current| current.url == hook.url) {
+            continue;
+        }
+        services
+            .webhooks
+            .create(&hook.url, hook.secret.as_deref(), &hook.event_types)?;
+        stats.webhooks_created += 1;
+    }
+    Ok(stats)
+}
+
+fn validate(bundle: &SettingsBundle) -> Result<()> {
+    let invalid = |message: String| Err(AppError::InvalidInput(message));
+    if bundle.version == 0 || bundle.version > BUNDLE_VERSION {
+        return invalid(format!(
+            "unsupported settings bundle version {}",
+            bundle.version
+        ));
+    }
+    for rule in &bundle.pricing {
+        let prices = [
+            rule.input_per_1m,
+            rule.cached_input_per_1m,
+            rule.output_per_1m,
+        ];
+        if rule.model_pattern.trim().is_empty()
+            || prices
+                .iter()
+                .any(|price| !price.is_finite() || *price < 0.0)
+        {
+            return invalid(format!("invalid pricing rule {:?}", rule.model_pattern));
+        }
+    }
+    for alias in &bundle.model_aliases {
+        if alias.alias.trim().is_empty() || alias.model.trim().is_empty() {
+            return invalid("model aliases need an alias and a model".to_string());
+        }
+    }
+    let settings = &bundle.settings;
+    validate_active_session_rule(&settings.active_session_rule)?;
+    for url in [&settings.slack_webhook_url, &settings.discord_webhook_url]
+        .into_iter()
+        .flatten()
+    {
+        if !url.is_empty() && !url.starts_with("https://") {
+            return invalid(format!("notification url {url} must start with https://"));
+        }
+    }
+    for home in &bundle.homes {
+        if home.path.trim().is_empty() || home.label.trim().is_empty() {
+            return invalid("homes need a path and a label".to_string());
+        }
+        if home.active && home.archived {
+            return invalid(format!("home {} cannot be active and archived", home.path));
+        }
+    }
+    for hook in &bundle.webhooks {
+        if !(hook.url.starts_with("http://") || hook.url.starts_with("https://")) {
+            return invalid(format!(
+                "webhook url {} must start with http:// or https://",
+                hook.url
+            ));
+        }
+        if hook.event_types.is_empty()
+            || hook
+                .event_types
+                .iter()
+                .any(|event_type| !EVENT_TYPES.contains(&event_type.as_str()))
+        {
+            return invalid(format!("webhook {} has unsupported event types", hook.url));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use chrono::{DateTime, Duration, Utc};
+use tracker_core::UsageLimitSnapshot;
+
+/// How long each reset event spans in the calendar.
+const EVENT_MINUTES: i64 = 15;
+
+const ICS_TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Renders upcoming limit resets as an iCalendar (RFC 5545) feed.
+///
+/// Each reset becomes a short event whose UID is derived from the limit type
+/// and reset time, so calendar apps update rather than duplicate it when the
+/// feed is refreshed.
+pub fn limit_resets_ics(resets: &[UsageLimitSnapshot], now: DateTime<Utc>) -> String {
+    let stamp = now.format(ICS_TIME_FORMAT).to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Codex Tracker//Limit resets//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:Codex limit resets".to_string(),
+        "REFRESH-INTERVAL;VALUE=DURATION:PT1H".to_string(),
+    ];
+    for reset in resets {
+        let start = reset.reset_at.as_datetime();
+        let end = start + Duration::minutes(EVENT_MINUTES);
+        let start_text = start.format(ICS_TIME_FORMAT).to_string();
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!(
+            "UID:limit-{}-{}@codex-tracker",
+            escape_text(&reset.limit_type),
+            start_text
+        ));
+        lines.push(format!("DTSTAMP:{stamp}"));
+        lines.push(format!("DTSTART:{start_text}"));
+        lines.push(format!("DTEND:{}", end.format(ICS_TIME_FORMAT)));
+        lines.push(format!(
+            "SUMMARY:{}",
+            escape_text(&format!("Codex {} limit resets", reset.limit_type))
+        ));
+        lines.push(format!(
+            "DESCRIPTION:{}",
+            escape_text(&format!(
+                "{:.0}% left as of {}",
+                reset.percent_left, reset.observed_at
+            ))
+        ));
+        lines.push("TRANSP:TRANSPARENT".to_string());
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in lines {
+        push_folded(&mut out, &line);
+    }
+    out
+}
+
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Content lines are limited to 75 octets; longer ones continue on the next
+/// line after a single space.
+fn push_folded(out: &mut String, line: &str) {
+    let mut width = 0;
+    for ch in line.chars() {
+        let len = ch.len_utf8();
+        if width + len > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += len;
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reset(limit_type: &str, reset_at: &str) -> UsageLimitSnapshot {
+        UsageLimitSnapshot {
+            limit_type: limit_type.to_string(),
+            percent_left: 42.4,
+            reset_at: reset_at.parse().expect("timestamp"),
+            observed_at: "2026-01-01T10:00:00Z".parse().expect("timestamp"),
+            source: "session.jsonl".to_string(),
+            raw_line: None,
+        }
+    }
+
+    #[test]
+    fn renders_one_event_per_reset() {
+        let now = DateTime::parse_from_rfc3339("2026-01-01T12:00:00Z")
+            .expect("now")
+            .with_timezone(&Utc);
+        let ics = limit_resets_ics(
+            &[
+                reset("5h", "2026-01-01T14:30:00Z"),
+                reset("7d", "2026-01-05T09:00:00+02:00"),
+            ],
+            now,
+        );
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ics.contains("DTSTART:20260101T143000Z\r\n"));
+        assert!(ics.contains("DTEND:20260101T144500Z\r\n"));
+        assert!(ics.contains("DTSTART:20260105T070000Z\r\n"));
+        assert!(ics.contains("UID:limit-7d-20260105T070000Z@codex-tracker\r\n"));
+        assert!(ics.contains("DESCRIPTION:42% left as of 2026-01-01T10:00:00.000Z\r\n"));
+    }
+
+    #[test]
+    fn folds_long_lines() {
+        let mut out = String::new();
+        push_folded(&mut out, &"x".repeat(100));
+        let lines: Vec<&str> = out.split("\r\n").collect();
+        assert_eq!(lines[0].len(), 75);
+        assert_eq!(lines[1], format!(" {}", "x".repeat(25)));
+    }
+}
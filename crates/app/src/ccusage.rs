@@ -0,0 +1,714 @@
+//! Reports shaped like `ccusage --json` output (daily, session, and 5-hour
+//! blocks), so statusline scripts written for that tool can read Codex usage,
+//! and the reverse: importing daily or session reports as usage events.
+//!
+//! Codex counts cached tokens inside `input_tokens`; they are reported here
+//! as `cacheReadTokens` and subtracted from `inputTokens`. Codex has no cache
+//! writes, so `cacheCreationTokens` is always zero on export and counted as
+//! uncached input on import.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, Duration, DurationRound, NaiveDate, SecondsFormat, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use tracker_core::{
+    ContextStatus, CostSource, Timestamp, UsageEvent, UsageTotals, session_id_from_source,
+};
+use tracker_db::RowUsage;
+
+use crate::error::{AppError, Result};
+
+/// Length of a usage block, matching the Codex / Claude 5h limit window.
+const BLOCK_HOURS: i64 = 5;
+const UNKNOWN_PROJECT: &str = "Unknown Project";
+/// Starts the `source` (and id) of every imported event, so imports can be
+/// told apart from ingested logs.
+pub const IMPORT_SOURCE_PREFIX: &str = "ccusage:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CcusageKind {
+    Daily,
+    Session,
+    Blocks,
+}
+
+impl CcusageKind {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "daily" => Ok(CcusageKind::Daily),
+            "session" => Ok(CcusageKind::Session),
+            "blocks" => Ok(CcusageKind::Blocks),
+            other => Err(AppError::InvalidInput(format!(
+                "unsupported ccusage report {other}"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CcusageTotals {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_tokens: u64,
+    pub total_cost: f64,
+}
+
+impl CcusageTotals {
+    fn add(&mut self, row: &RowUsage) {
+        let usage = row.usage;
+        self.input_tokens += usage.input_tokens.saturating_sub(usage.cached_input_tokens);
+        self.output_tokens += usage.output_tokens;
+        self.cache_read_tokens += usage.cached_input_tokens;
+        self.total_tokens += usage.total_tokens;
+        self.total_cost += row.cost_usd.unwrap_or(0.0);
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CcusageModelBreakdown {
+    pub model_name: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CcusageDaily {
+    pub date: String,
+    #[serde(flatten)]
+    pub totals: CcusageTotals,
+    pub models_used: Vec<String>,
+    pub model_breakdowns: Vec<CcusageModelBreakdown>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CcusageSession {
+    pub session_id: String,
+    #[serde(flatten)]
+    pub totals: CcusageTotals,
+    pub last_activity: String,
+    pub models_used: Vec<String>,
+    pub model_breakdowns: Vec<CcusageModelBreakdown>,
+    pub project_path: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CcusageTokenCounts {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    pub cache_read_input_tokens: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CcusageBurnRate {
+    pub tokens_per_minute: f64,
+    pub cost_per_hour: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CcusageProjection {
+    pub total_tokens: u64,
+    pub total_cost: f64,
+    pub remaining_minutes: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CcusageBlock {
+    pub id: String,
+    pub start_time: String,
+    pub end_time: String,
+    pub actual_end_time: Option<String>,
+    pub is_active: bool,
+    pub is_gap: bool,
+    pub entries: usize,
+    pub token_counts: CcusageTokenCounts,
+    pub total_tokens: u64,
+    #[serde(rename = "costUSD")]
+    pub cost_usd: f64,
+    pub models: Vec<String>,
+    pub burn_rate: Option<CcusageBurnRate>,
+    pub projection: Option<CcusageProjection>,
+}
+
+/// One of the three report shapes; serialized exactly like `ccusage <kind> --json`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(untagged)]
+pub enum CcusageReport {
+    Daily {
+        daily: Vec<CcusageDaily>,
+        totals: CcusageTotals,
+    },
+    Session {
+        sessions: Vec<CcusageSession>,
+        totals: CcusageTotals,
+    },
+    Blocks {
+        blocks: Vec<CcusageBlock>,
+    },
+}
+
+/// Builds the report from per-event deltas (see `Db::usage_deltas`). Days
+/// are calendar dates in `tz`; blocks are anchored in UTC like ccusage.
+pub fn build_report<Tz: TimeZone>(
+    kind: CcusageKind,
+    rows: &[RowUsage],
+    tz: &Tz,
+    now: DateTime<Utc>,
+) -> CcusageReport {
+    match kind {
+        CcusageKind::Daily => {
+            let (daily, totals) = daily(rows, tz);
+            CcusageReport::Daily { daily, totals }
+        }
+        CcusageKind::Session => {
+            let (sessions, totals) = sessions(rows, tz);
+            CcusageReport::Session { sessions, totals }
+        }
+        CcusageKind::Blocks => CcusageReport::Blocks {
+            blocks: blocks(rows, now),
+        },
+    }
+}
+
+/// `ccusage daily --json` or `ccusage session --json` output.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CcusageImport {
+    Daily { daily: Vec<ImportEntry> },
+    Session { sessions: Vec<ImportEntry> },
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct ImportEntry {
+    date: Option<String>,
+    session_id: Option<String>,
+    last_activity: Option<String>,
+    #[serde(flatten)]
+    tokens: ImportTokens,
+    total_cost: Option<f64>,
+    models_used: Vec<String>,
+    model_breakdowns: Vec<ImportBreakdown>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct ImportTokens {
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_creation_tokens: u64,
+    cache_read_tokens: u64,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct ImportBreakdown {
+    model_name: String,
+    #[serde(flatten)]
+    tokens: ImportTokens,
+    cost: Option<f64>,
+}
+
+/// Turns a daily or session report into usage events: one per day (or
+/// session) and model, timestamped at local midnight of the day (or of the
+/// session's last activity) in `tz`. Ids are derived from the report, so
+/// importing the same file twice adds nothing. Each event gets its own
+/// `source`, so token deltas never span two of them.
+pub fn import_events<Tz: TimeZone>(contents: &str, tz: &Tz) -> Result<Vec<UsageEvent>> {
+    let report: CcusageImport = serde_json::from_str(contents).map_err(|_| {
+        AppError::InvalidInput(
+            "expected the JSON output of `ccusage daily --json` or `ccusage session --json`"
+                .to_string(),
+        )
+    })?;
+    let (kind, entries) = match report {
+        CcusageImport::Daily { daily } => ("daily", daily),
+        CcusageImport::Session { sessions } => ("session", sessions),
+    };
+    let mut events = Vec::new();
+    for entry in entries {
+        // Daily events are grouped into one pseudo-session per day.
+        let (key, date, session_id) = match (kind, &entry) {
+            (
+                "daily",
+                ImportEntry {
+                    date: Some(date), ..
+                },
+            ) => (date.clone(), date.clone(), format!("ccusage-{date}")),
+            (
+                "session",
+                ImportEntry {
+                    session_id: Some(id),
+                    last_activity: Some(last_activity),
+                    ..
+                },
+            ) => (id.clone(), last_activity.clone(), id.clone()),
+            _ => {
+                return Err(AppError::InvalidInput(format!(
+                    "ccusage {kind} entry without a date (or sessionId and lastActivity)"
+                )));
+            }
+        };
+        let ts = import_ts(&date, tz)?;
+        let breakdowns = if entry.model_breakdowns.is_empty() {
+            vec![ImportBreakdown {
+                model_name: entry
+                    .models_used
+                    .first()
+                    .cloned()
+                    .unwrap_or_else(|| "unknown".to_string()),
+                tokens: entry.tokens,
+                cost: entry.total_cost,
+            }]
+        } else {
+            entry.model_breakdowns
+        };
+        for breakdown in breakdowns {
+            let tokens = breakdown.tokens;
+            let input =
+                tokens.input_tokens + tokens.cache_read_tokens + tokens.cache_creation_tokens;
+            if input + tokens.output_tokens == 0 {
+                continue;
+            }
+            let source = format!(
+                "{IMPORT_SOURCE_PREFIX}{kind}:{key}:{}",
+                breakdown.model_name
+            );
+            events.push(UsageEvent {
+                id: source.clone(),
+                ts,
+                model: breakdown.model_name,
+                usage: UsageTotals {
+                    input_tokens: input,
+                    cached_input_tokens: tokens.cache_read_tokens,
+                    output_tokens: tokens.output_tokens,
+                    reasoning_output_tokens: 0,
+                    total_tokens: input + tokens.output_tokens,
+                },
+                context: ContextStatus::default(),
+                cost_usd: breakdown.cost,
+                cost_source: Some(CostSource::Reported),
+                reasoning_effort: None,
+                provider: None,
+                source,
+                session_id: session_id.clone(),
+                request_id: None,
+                raw_json: None,
+            });
+        }
+    }
+    Ok(events)
+}
+
+/// Local midnight of a `YYYY-MM-DD` date; RFC 3339 timestamps pass through.
+fn import_ts<Tz: TimeZone>(value: &str, tz: &Tz) -> Result<Timestamp> {
+    if let Some(ts) = parse_utc(value) {
+        return Ok(ts.into());
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| {
+            tz.from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+                .earliest()
+        })
+        .map(|midnight| midnight.with_timezone(&Utc).into())
+        .ok_or_else(|| AppError::InvalidInput(format!("invalid ccusage date {value}")))
+}
+
+fn daily<Tz: TimeZone>(rows: &[RowUsage], tz: &Tz) -> (Vec<CcusageDaily>, CcusageTotals) {
+    let mut by_date: BTreeMap<String, Vec<&RowUsage>> = BTreeMap::new();
+    for row in rows {
+        if let Some(date) = local_date(&row.ts, tz) {
+            by_date.entry(date).or_default().push(row);
+        }
+    }
+    let mut totals = CcusageTotals::default();
+    let daily = by_date
+        .into_iter()
+        .map(|(date, rows)| {
+            let (day_totals, models_used, model_breakdowns) = summarize(&rows);
+            add_totals(&mut totals, &day_totals);
+            CcusageDaily {
+                date,
+                totals: day_totals,
+                models_used,
+                model_breakdowns,
+            }
+        })
+        .collect();
+    (daily, totals)
+}
+
+fn sessions<Tz: TimeZone>(rows: &[RowUsage], tz: &Tz) -> (Vec<CcusageSession>, CcusageTotals) {
+    let mut by_session: BTreeMap<String, Vec<&RowUsage>> = BTreeMap::new();
+    for row in rows {
+        by_session
+            .entry(session_id_from_source(&row.source))
+            .or_default()
+            .push(row);
+    }
+    let mut totals = CcusageTotals::default();
+    let mut sessions: Vec<CcusageSession> = by_session
+        .into_iter()
+        .map(|(session_id, rows)| {
+            let (session_totals, models_used, model_breakdowns) = summarize(&rows);
+            add_totals(&mut totals, &session_totals);
+            let last_ts = rows.iter().map(|row| row.ts.as_str()).max().unwrap_or("");
+            CcusageSession {
+                session_id,
+                totals: session_totals,
+                last_activity: local_date(last_ts, tz).unwrap_or_default(),
+                models_used,
+                model_breakdowns,
+                project_path: UNKNOWN_PROJECT.to_string(),
+            }
+        })
+        .collect();
+    // ccusage lists the most recently active sessions first.
+    sessions.sort_by(|a, b| b.last_activity.cmp(&a.last_activity));
+    (sessions, totals)
+}
+
+/// A block's start and its entries with parsed timestamps.
+type BlockEntries<'a> = (DateTime<Utc>, Vec<(DateTime<Utc>, &'a RowUsage)>);
+
+fn blocks(rows: &[RowUsage], now: DateTime<Utc>) -> Vec<CcusageBlock> {
+    let window = Duration::hours(BLOCK_HOURS);
+    let mut groups: Vec<BlockEntries> = Vec::new();
+    for row in rows {
+        let Some(ts) = parse_utc(&row.ts) else {
+            continue;
+        };
+        let starts_new = match groups.last() {
+            Some((start, entries)) => {
+                let last = entries.last().map(|(ts, _)| *ts).unwrap_or(*start);
+                ts - *start > window || ts - last > window
+            }
+            None => true,
+        };
+        if starts_new {
+            let start = ts.duration_trunc(Duration::hours(1)).unwrap_or(ts);
+            groups.push((start, Vec::new()));
+        }
+        if let Some((_, entries)) = groups.last_mut() {
+            entries.push((ts, row));
+        }
+    }
+
+    let mut blocks = Vec::new();
+    let mut previous_end: Option<DateTime<Utc>> = None;
+    for (start, entries) in groups {
+        let end = start + window;
+        let first = entries.first().map(|(ts, _)| *ts).unwrap_or(start);
+        let last = entries.last().map(|(ts, _)| *ts).unwrap_or(start);
+        if let Some(gap_start) = previous_end
+            && start - gap_start > window
+        {
+            blocks.push(gap_block(gap_start, start));
+        }
+        previous_end = Some(last);
+
+        let mut counts = CcusageTokenCounts::default();
+        let mut total_tokens = 0;
+        let mut cost_usd = 0.0;
+        let mut models = BTreeSet::new();
+        for (_, row) in &entries {
+            let usage = row.usage;
+            counts.input_tokens += usage.input_tokens.saturating_sub(usage.cached_input_tokens);
+            counts.output_tokens += usage.output_tokens;
+            counts.cache_read_input_tokens += usage.cached_input_tokens;
+            total_tokens += usage.total_tokens;
+            cost_usd += row.cost_usd.unwrap_or(0.0);
+            models.insert(row.model.clone());
+        }
+        let is_active = now < end && now - last < window;
+        let elapsed_minutes = (last - first).num_seconds() as f64 / 60.0;
+        let burn_rate = (is_active && elapsed_minutes > 0.0).then(|| CcusageBurnRate {
+            tokens_per_minute: total_tokens as f64 / elapsed_minutes,
+            cost_per_hour: cost_usd / elapsed_minutes * 60.0,
+        });
+        let projection = burn_rate.as_ref().map(|rate| {
+            let remaining_minutes = (end - now).num_minutes().max(0);
+            CcusageProjection {
+                total_tokens: total_tokens
+                    + (rate.tokens_per_minute * remaining_minutes as f64).round() as u64,
+                total_cost: cost_usd + rate.cost_per_hour * remaining_minutes as f64 / 60.0,
+                remaining_minutes,
+            }
+        });
+        blocks.push(CcusageBlock {
+            id: format_utc(start),
+            start_time: format_utc(start),
+            end_time: format_utc(end),
+            actual_end_time: Some(format_utc(last)),
+            is_active,
+            is_gap: false,
+            entries: entries.len(),
+            token_counts: counts,
+            total_tokens,
+            cost_usd,
+            models: models.into_iter().collect(),
+            burn_rate,
+            projection,
+        });
+    }
+    blocks
+}
+
+fn gap_block(start: DateTime<Utc>, end: DateTime<Utc>) -> CcusageBlock {
+    CcusageBlock {
+        id: format!("gap-{}", format_utc(start)),
+        start_time: format_utc(start),
+        end_time: format_utc(end),
+        actual_end_time: None,
+        is_active: false,
+        is_gap: true,
+        entries: 0,
+        token_counts: CcusageTokenCounts::default(),
+        total_tokens: 0,
+        cost_usd: 0.0,
+        models: Vec::new(),
+        burn_rate: None,
+        projection: None,
+    }
+}
+
+fn summarize(rows: &[&RowUsage]) -> (CcusageTotals, Vec<String>, Vec<CcusageModelBreakdown>) {
+    let mut totals = CcusageTotals::default();
+    let mut by_model: BTreeMap<String, CcusageModelBreakdown> = BTreeMap::new();
+    for row in rows {
+        totals.add(row);
+        let entry = by_model
+            .entry(row.model.clone())
+            .or_insert_with(|| CcusageModelBreakdown {
+                model_name: row.model.clone(),
+                ..CcusageModelBreakdown::default()
+            });
+        let usage = row.usage;
+        entry.input_tokens += usage.input_tokens.saturating_sub(usage.cached_input_tokens);
+        entry.output_tokens += usage.output_tokens;
+        entry.cache_read_tokens += usage.cached_input_tokens;
+        entry.cost += row.cost_usd.unwrap_or(0.0);
+    }
+    let models_used = by_model.keys().cloned().collect();
+    let mut breakdowns: Vec<CcusageModelBreakdown> = by_model.into_values().collect();
+    breakdowns.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+    (totals, models_used, breakdowns)
+}
+
+fn add_totals(totals: &mut CcusageTotals, other: &CcusageTotals) {
+    totals.input_tokens += other.input_tokens;
+    totals.output_tokens += other.output_tokens;
+    totals.cache_creation_tokens += other.cache_creation_tokens;
+    totals.cache_read_tokens += other.cache_read_tokens;
+    totals.total_tokens += other.total_tokens;
+    totals.total_cost += other.total_cost;
+}
+
+fn parse_utc(ts: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(ts)
+        .ok()
+        .map(|value| value.with_timezone(&Utc))
+}
+
+fn local_date<Tz: TimeZone>(ts: &str, tz: &Tz) -> Option<String> {
+    parse_utc(ts).map(|value| {
+        value
+            .with_timezone(tz)
+            .date_naive()
+            .format("%Y-%m-%d")
+            .to_string()
+    })
+}
+
+fn format_utc(value: DateTime<Utc>) -> String {
+    value.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tracker_core::UsageTotals;
+
+    fn row(ts: &str, model: &str, source: &str, input: u64, cached: u64, output: u64) -> RowUsage {
+        RowUsage {
+            id: format!("{source}-{ts}"),
+            ts: ts.to_string(),
+            model: model.to_string(),
+            usage: UsageTotals {
+                input_tokens: input,
+                cached_input_tokens: cached,
+                output_tokens: output,
+                reasoning_output_tokens: 0,
+                total_tokens: input + output,
+            },
+            cost_usd: Some(0.5),
+            source: source.to_string(),
+            reasoning_effort: None,
+            provider: None,
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        parse_utc("2026-01-02T12:00:00Z").expect("now")
+    }
+
+    #[test]
+    fn daily_report_matches_ccusage_shape() {
+        let rows = vec![
+            row("2026-01-01T10:00:00Z", "gpt-5.1", "a", 100, 40, 10),
+            row("2026-01-01T11:00:00Z", "gpt-5.2", "a", 50, 0, 5),
+            row("2026-01-02T09:00:00Z", "gpt-5.1", "b", 10, 0, 1),
+        ];
+        let report = build_report(CcusageKind::Daily, &rows, &Utc, now());
+        let value = serde_json::to_value(&report).expect("json");
+
+        assert_eq!(value["daily"].as_array().expect("daily").len(), 2);
+        assert_eq!(
+            value["daily"][0],
+            json!({
+                "date": "2026-01-01",
+                "inputTokens": 110,
+                "outputTokens": 15,
+                "cacheCreationTokens": 0,
+                "cacheReadTokens": 40,
+                "totalTokens": 165,
+                "totalCost": 1.0,
+                "modelsUsed": ["gpt-5.1", "gpt-5.2"],
+                "modelBreakdowns": [
+                    {
+                        "modelName": "gpt-5.1",
+                        "inputTokens": 60,
+                        "outputTokens": 10,
+                        "cacheCreationTokens": 0,
+                        "cacheReadTokens": 40,
+                        "cost": 0.5
+                    },
+                    {
+                        "modelName": "gpt-5.2",
+                        "inputTokens": 50,
+                        "outputTokens": 5,
+                        "cacheCreationTokens": 0,
+                        "cacheReadTokens": 0,
+                        "cost": 0.5
+                    }
+                ]
+            })
+        );
+        assert_eq!(value["totals"]["totalTokens"], 176);
+        assert_eq!(value["totals"]["totalCost"], 1.5);
+    }
+
+    #[test]
+    fn imported_reports_round_trip_to_usage_events() {
+        let rows = vec![
+            row("2026-01-01T10:00:00Z", "gpt-5.1", "a", 100, 40, 10),
+            row("2026-01-01T11:00:00Z", "gpt-5.2", "a", 50, 0, 5),
+            row("2026-01-02T09:00:00Z", "gpt-5.1", "b", 10, 0, 1),
+        ];
+        let daily = build_report(CcusageKind::Daily, &rows, &Utc, now());
+        let contents = serde_json::to_string(&daily).expect("json");
+        let events = import_events(&contents, &Utc).expect("import");
+
+        assert_eq!(events.len(), 3);
+        let first = &events[0];
+        assert_eq!(first.id, "ccusage:daily:2026-01-01:gpt-5.1");
+        assert_eq!(first.source, first.id);
+        assert_eq!(first.session_id, "ccusage-2026-01-01");
+        assert_eq!(first.ts.to_string(), "2026-01-01T00:00:00.000Z");
+        assert_eq!(first.usage.input_tokens, 100);
+        assert_eq!(first.usage.cached_input_tokens, 40);
+        assert_eq!(first.usage.total_tokens, 110);
+        assert_eq!(first.cost_usd, Some(0.5));
+        assert_eq!(
+            events
+                .iter()
+                .map(|event| event.usage.total_tokens)
+                .sum::<u64>(),
+            176
+        );
+
+        let sessions = build_report(CcusageKind::Session, &rows, &Utc, now());
+        let contents = serde_json::to_string(&sessions).expect("json");
+        let events = import_events(&contents, &Utc).expect("import sessions");
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].session_id, "b");
+        assert_eq!(events[0].ts.to_string(), "2026-01-02T00:00:00.000Z");
+
+        let blocks = build_report(CcusageKind::Blocks, &rows, &Utc, now());
+        let contents = serde_json::to_string(&blocks).expect("json");
+        assert!(import_events(&contents, &Utc).is_err());
+        assert!(import_events(r#"{"daily":[{"inputTokens":1}]}"#, &Utc).is_err());
+    }
+
+    #[test]
+    fn sessions_are_most_recent_first() {
+        let rows = vec![
+            row("2025-12-30T10:00:00Z", "gpt-5.1", "old", 10, 0, 1),
+            row("2026-01-02T10:00:00Z", "gpt-5.1", "new", 10, 0, 1),
+        ];
+        let report = build_report(CcusageKind::Session, &rows, &Utc, now());
+        let value = serde_json::to_value(&report).expect("json");
+        assert_eq!(value["sessions"][0]["sessionId"], "new");
+        assert_eq!(value["sessions"][0]["lastActivity"], "2026-01-02");
+        assert_eq!(value["sessions"][1]["sessionId"], "old");
+        assert_eq!(value["totals"]["totalTokens"], 22);
+    }
+
+    #[test]
+    fn blocks_split_on_five_hour_windows_and_gaps() {
+        let rows = vec![
+            row("2026-01-01T00:30:00Z", "gpt-5.1", "a", 10, 0, 0),
+            row("2026-01-01T04:00:00Z", "gpt-5.1", "a", 10, 0, 0),
+            // Past the first block's 05:00 end.
+            row("2026-01-01T05:30:00Z", "gpt-5.1", "a", 10, 0, 0),
+            // More than five hours idle: a gap block precedes this one.
+            row("2026-01-02T10:15:00Z", "gpt-5.1", "a", 10, 0, 0),
+            row("2026-01-02T11:15:00Z", "gpt-5.1", "a", 20, 0, 0),
+        ];
+        let CcusageReport::Blocks { blocks } =
+            build_report(CcusageKind::Blocks, &rows, &Utc, now())
+        else {
+            panic!("expected blocks");
+        };
+
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(blocks[0].start_time, "2026-01-01T00:00:00.000Z");
+        assert_eq!(blocks[0].end_time, "2026-01-01T05:00:00.000Z");
+        assert_eq!(blocks[0].entries, 2);
+        assert_eq!(blocks[1].start_time, "2026-01-01T05:00:00.000Z");
+        assert!(blocks[2].is_gap);
+        let active = &blocks[3];
+        assert!(active.is_active);
+        assert_eq!(active.start_time, "2026-01-02T10:00:00.000Z");
+        assert_eq!(active.total_tokens, 30);
+        let burn_rate = active.burn_rate.as_ref().expect("burn rate");
+        assert_eq!(burn_rate.tokens_per_minute, 0.5);
+        let projection = active.projection.as_ref().expect("projection");
+        assert_eq!(projection.remaining_minutes, 180);
+        assert_eq!(projection.total_tokens, 120);
+    }
+}
@@ -0,0 +1,273 @@
+//! Health checks behind `codex-tracker doctor`.
+//!
+//! Checks only read: the database is opened without migrating so an old
+//! schema shows up as a finding instead of being fixed silently.
+
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+use tracker_core::CodexHome;
+use tracker_db::{Db, SCHEMA_VERSION};
+
+use crate::app::AppConfig;
+
+/// How many unpriced models a finding names before summarising the rest.
+const MAX_LISTED_MODELS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warn,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    /// What to do about a warning or error.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl Check {
+    pub fn ok(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Ok,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    pub fn warn(name: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    pub fn error(name: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Error,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+/// Runs the database and codex home checks. Stops after the database
+/// checks when the database is missing or unreadable.
+pub fn run_checks(config: &AppConfig) -> Vec<Check> {
+    let mut checks = Vec::new();
+    let db_path = &config.db_path;
+    if !db_path.exists() {
+        checks.push(Check::warn(
+            "database",
+            format!("no database at {}", db_path.display()),
+            "run `codex-tracker ingest` to create it",
+        ));
+        return checks;
+    }
+    let db = match Db::open(db_path) {
+        Ok(db) => db,
+        Err(err) => {
+            checks.push(unreadable_db(db_path, err));
+            return checks;
+        }
+    };
+    let level = match db.schema_level() {
+        Ok(level) => level,
+        Err(err) => {
+            checks.push(unreadable_db(db_path, err));
+            return checks;
+        }
+    };
+    checks.push(Check::ok(
+        "database",
+        format!("opened {}", db_path.display()),
+    ));
+    checks.push(if level >= SCHEMA_VERSION {
+        Check::ok("schema", format!("all {SCHEMA_VERSION} migrations applied"))
+    } else {
+        Check::warn(
+            "schema",
+            format!("{level} of {SCHEMA_VERSION} migrations applied"),
+            "run `codex-tracker ingest` to apply the rest",
+        )
+    });
+    if level < SCHEMA_VERSION {
+        return checks;
+    }
+
+    let homes = match db.list_homes() {
+        Ok(homes) => homes,
+        Err(err) => {
+            checks.push(unreadable_db(db_path, err));
+            return checks;
+        }
+    };
+    if homes.is_empty() {
+        checks.push(Check::warn(
+            "codex_home",
+            "no codex homes configured",
+            "add one with `codex-tracker homes add <path>`",
+        ));
+    }
+    for home in &homes {
+        check_home(&db, home, &mut checks);
+    }
+    checks
+}
+
+fn check_home(db: &Db, home: &CodexHome, checks: &mut Vec<Check>) {
+    let path = Path::new(&home.path);
+    if !path.is_dir() {
+        checks.push(Check::error(
+            "codex_home",
+            format!("{} ({}) does not exist", home.label, home.path),
+            format!(
+                "restore it, or remove the home with `codex-tracker homes remove {} --yes`",
+                home.id
+            ),
+        ));
+        return;
+    }
+    checks.push(Check::ok(
+        "codex_home",
+        format!("{} ({})", home.label, home.path),
+    ));
+
+    let files = ingest::log_files(path).len();
+    checks.push(if files == 0 {
+        Check::warn(
+            "log_files",
+            format!("no session logs under {}", path.join("sessions").display()),
+            "check that this is the directory Codex writes to (CODEX_HOME)",
+        )
+    } else {
+        Check::ok(
+            "log_files",
+            format!("{files} session logs in {}", home.label),
+        )
+    });
+
+    match db.list_cursors(home.id) {
+        Ok(cursors) => {
+            let stale = cursors
+                .iter()
+                .filter(|cursor| match fs::metadata(&cursor.file_path) {
+                    Ok(metadata) => cursor.byte_offset > metadata.len(),
+                    Err(_) => true,
+                })
+                .count();
+            checks.push(if stale == 0 {
+                Check::ok(
+                    "cursors",
+                    format!("{} ingest cursors in {} are valid", cursors.len(), home.label),
+                )
+            } else {
+                Check::warn(
+                    "cursors",
+                    format!(
+                        "{stale} of {} ingest cursors in {} point past the end of their file or at a missing file",
+                        cursors.len(),
+                        home.label
+                    ),
+                    "run `codex-tracker ingest`; shortened files are read again from the start",
+                )
+            });
+        }
+        Err(err) => checks.push(Check::error(
+            "cursors",
+            format!("failed to read ingest cursors: {err}"),
+            "the database may be damaged; restore it from a backup",
+        )),
+    }
+
+    match db.unpriced_models(home.id) {
+        Ok(models) if models.is_empty() => checks.push(Check::ok(
+            "pricing",
+            format!("every model in {} has a price", home.label),
+        )),
+        Ok(models) => {
+            let mut names = models
+                .iter()
+                .take(MAX_LISTED_MODELS)
+                .map(|(model, events)| format!("{model} ({events} events)"))
+                .collect::<Vec<_>>();
+            if models.len() > MAX_LISTED_MODELS {
+                names.push(format!("{} more", models.len() - MAX_LISTED_MODELS));
+            }
+            checks.push(Check::warn(
+                "pricing",
+                format!("no pricing rule for {}", names.join(", ")),
+                "add one with `codex-tracker pricing set <model> --input <usd> --output <usd>`",
+            ));
+        }
+        Err(err) => checks.push(Check::error(
+            "pricing",
+            format!("failed to read event costs: {err}"),
+            "the database may be damaged; restore it from a backup",
+        )),
+    }
+}
+
+fn unreadable_db(path: &Path, err: impl std::fmt::Display) -> Check {
+    Check::error(
+        "database",
+        format!("cannot read {}: {err}", path.display()),
+        "restore the file from a backup, or move it aside to start over",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_report_missing_homes_and_damaged_db() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let config = AppConfig {
+            db_path: dir.path().join("tracker.sqlite"),
+            pricing_defaults_path: dir.path().join("pricing.json"),
+        };
+        let checks = run_checks(&config);
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].status, CheckStatus::Warn);
+
+        let mut db = Db::open(&config.db_path).expect("open db");
+        db.migrate().expect("migrate");
+        db.add_home(&dir.path().join("gone").to_string_lossy(), Some("Gone"))
+            .expect("add home");
+        drop(db);
+        let checks = run_checks(&config);
+        assert_eq!(checks[0].status, CheckStatus::Ok);
+        assert_eq!(checks[1].name, "schema");
+        assert_eq!(checks[1].status, CheckStatus::Ok);
+        let gone = checks
+            .iter()
+            .find(|check| check.message.starts_with("Gone"))
+            .expect("home check");
+        assert_eq!(gone.status, CheckStatus::Error);
+        assert!(
+            gone.hint
+                .as_deref()
+                .unwrap_or_default()
+                .contains("homes remove")
+        );
+
+        fs::write(&config.db_path, "not a database").expect("overwrite");
+        let _ = fs::remove_file(dir.path().join("tracker.sqlite-wal"));
+        let _ = fs::remove_file(dir.path().join("tracker.sqlite-shm"));
+        let checks = run_checks(&config);
+        assert_eq!(checks[0].name, "database");
+        assert_eq!(checks[0].status, CheckStatus::Error);
+    }
+}
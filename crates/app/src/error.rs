@@ -1,5 +1,6 @@
 use serde::Serialize;
 use thiserror::Error;
+use tracker_error::{ErrorCode, TrackerError};
 
 #[derive(Debug, Error)]
 pub enum AppError {
@@ -13,35 +14,60 @@ pub enum AppError {
     Serde(#[from] serde_json::Error),
     #[error("{0}")]
     InvalidInput(String),
+    /// A range name, start, or end that does not resolve to a time range.
+    #[error("{0}")]
+    BadRange(String),
+    #[error("{0}")]
+    HomeNotFound(String),
     #[error("{0}")]
     NotFound(String),
     #[error("{0}")]
     Message(String),
 }
 
+impl AppError {
+    /// The stable code frontends report alongside the message.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::InvalidInput(_) => ErrorCode::InvalidInput,
+            AppError::BadRange(_) => ErrorCode::BadRange,
+            AppError::HomeNotFound(_) => ErrorCode::HomeNotFound,
+            AppError::NotFound(_) => ErrorCode::NotFound,
+            AppError::Db(err) | AppError::Ingest(ingest::IngestError::Db(err))
+                if err.is_locked() =>
+            {
+                ErrorCode::DbLocked
+            }
+            AppError::Db(_)
+            | AppError::Ingest(_)
+            | AppError::Io(_)
+            | AppError::Serde(_)
+            | AppError::Message(_) => ErrorCode::Internal,
+        }
+    }
+}
+
+impl From<AppError> for TrackerError {
+    fn from(err: AppError) -> Self {
+        TrackerError::new(err.code(), err.to_string())
+    }
+}
+
 pub type Result<T> = std::result::Result<T, AppError>;
 
 #[derive(Debug, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ApiError {
     pub status: u16,
     pub message: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub code: Option<String>,
+    pub code: ErrorCode,
 }
 
 impl From<AppError> for ApiError {
     fn from(err: AppError) -> Self {
-        let (status, code) = match err {
-            AppError::InvalidInput(_) => (400, Some("invalid_input".to_string())),
-            AppError::NotFound(_) => (404, Some("not_found".to_string())),
-            AppError::Db(_)
-            | AppError::Ingest(_)
-            | AppError::Io(_)
-            | AppError::Serde(_)
-            | AppError::Message(_) => (500, None),
-        };
+        let code = err.code();
         Self {
-            status,
+            status: code.http_status(),
             message: err.to_string(),
             code,
         }
@@ -0,0 +1,124 @@
+//! In-process notifications between services and frontends.
+//!
+//! Services publish an [`AppEvent`] after a write lands; subscribers react
+//! to it instead of every caller having to remember the follow-up work. The
+//! service registry subscribes the analytics cache (cleared on any change)
+//! and the cost recompute (run when prices change, and resumed after an
+//! ingest if an earlier run was interrupted); frontends subscribe to push
+//! updates to open windows.
+
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AppEvent {
+    /// An ingest or import finished. `home_id` is `None` for a directory
+    /// ingested by path; `rows_inserted` also counts messages and limit
+    /// snapshots.
+    IngestFinished {
+        home_id: Option<i64>,
+        events_inserted: usize,
+        rows_inserted: usize,
+    },
+    /// Pricing rules or model aliases were replaced.
+    PricingChanged,
+    /// Stored costs of a home were re-priced.
+    CostsRecomputed { home_id: i64 },
+    /// Another home became active.
+    HomeSwitched { home_id: i64 },
+    /// Usage was removed or reassigned: a prune, merge, cleared or deleted
+    /// home, or a home's user changed.
+    UsageChanged,
+}
+
+impl AppEvent {
+    /// Whether cached analytics may be stale after this event.
+    pub fn changes_usage(&self) -> bool {
+        !matches!(
+            self,
+            AppEvent::IngestFinished {
+                rows_inserted: 0,
+                ..
+            }
+        )
+    }
+}
+
+type Subscriber = Arc<dyn Fn(&AppEvent, &EventBus) + Send + Sync>;
+
+/// Fans published events out to every subscriber, synchronously and in
+/// subscription order. Subscribers get the bus to publish follow-up events,
+/// so they never need to hold a clone of it.
+#[derive(Clone, Default)]
+pub struct EventBus {
+    subscribers: Arc<Mutex<Vec<Subscriber>>>,
+}
+
+impl EventBus {
+    pub fn subscribe(&self, subscriber: impl Fn(&AppEvent, &EventBus) + Send + Sync + 'static) {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push(Arc::new(subscriber));
+    }
+
+    pub fn publish(&self, event: AppEvent) {
+        tracing::debug!(?event, "publishing app event");
+        // Copied out so a subscriber can publish or subscribe in turn.
+        let subscribers = self
+            .subscribers
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone();
+        for subscriber in subscribers {
+            subscriber(&event, self);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subscribers_see_events_and_their_follow_ups_in_order() {
+        let bus = EventBus::default();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        bus.subscribe(|event, bus| {
+            if *event == AppEvent::PricingChanged {
+                bus.publish(AppEvent::CostsRecomputed { home_id: 1 });
+            }
+        });
+        let log = seen.clone();
+        bus.subscribe(move |event, _| log.lock().unwrap().push(event.clone()));
+
+        bus.publish(AppEvent::PricingChanged);
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                AppEvent::CostsRecomputed { home_id: 1 },
+                AppEvent::PricingChanged,
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_ingests_do_not_change_usage() {
+        let empty = AppEvent::IngestFinished {
+            home_id: Some(1),
+            events_inserted: 0,
+            rows_inserted: 0,
+        };
+        assert!(!empty.changes_usage());
+        let limits_only = AppEvent::IngestFinished {
+            home_id: Some(1),
+            events_inserted: 0,
+            rows_inserted: 2,
+        };
+        assert!(limits_only.changes_usage());
+        assert!(AppEvent::HomeSwitched { home_id: 1 }.changes_usage());
+    }
+}
@@ -0,0 +1,164 @@
+//! Hash-chained JSON Lines for audits.
+//!
+//! Each line is an event record plus `seq`, `prev_hash`, and `hash`, where
+//! `hash` is the SHA-256 (hex) of the line's JSON without the `hash` key and
+//! `prev_hash` repeats the previous line's `hash` (64 zeros on the first
+//! line). Editing, dropping, or reordering a line breaks every hash after
+//! it, so a monthly export can be checked against a `head` hash recorded
+//! when it was produced.
+
+use std::io::{BufRead, Write};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use tracker_core::UsageEvent;
+
+use crate::error::Result;
+use crate::export::records::EventRecord;
+
+/// `prev_hash` of the first record.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Outcome of [`verify_chain`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChainVerification {
+    /// Lines read, including a broken one.
+    pub records: u64,
+    /// `hash` of the last intact record; hand this out with the export.
+    pub head: String,
+    /// 1-based line of the first record whose hash or link does not match.
+    pub broken_at: Option<u64>,
+}
+
+/// Writes events as hash-chained JSON Lines and returns the last hash.
+pub fn write_chained_usage_events<W: Write>(
+    events: &[UsageEvent],
+    mut writer: W,
+) -> Result<String> {
+    let mut prev_hash = GENESIS_HASH.to_string();
+    for (index, event) in events.iter().enumerate() {
+        let Value::Object(mut record) = serde_json::to_value(EventRecord::from(event))? else {
+            unreachable!("event records serialize as objects");
+        };
+        record.insert("seq".to_string(), Value::from(index as u64 + 1));
+        record.insert("prev_hash".to_string(), Value::from(prev_hash));
+        let hash = record_hash(&record)?;
+        record.insert("hash".to_string(), Value::from(hash.clone()));
+        serde_json::to_writer(&mut writer, &record)?;
+        writer.write_all(b"\n")?;
+        prev_hash = hash;
+    }
+    writer.flush()?;
+    Ok(prev_hash)
+}
+
+/// Re-hashes a chained export and reports the first line that does not
+/// match; stops reading there.
+pub fn verify_chain<R: BufRead>(reader: R) -> Result<ChainVerification> {
+    let mut verification = ChainVerification {
+        records: 0,
+        head: GENESIS_HASH.to_string(),
+        broken_at: None,
+    };
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        verification.records += 1;
+        let Some(hash) = linked_hash(&line, &verification.head) else {
+            verification.broken_at = Some(verification.records);
+            break;
+        };
+        verification.head = hash;
+    }
+    Ok(verification)
+}
+
+/// The line's `hash` when its `prev_hash` is `prev_hash` and the hash
+/// matches its contents.
+fn linked_hash(line: &str, prev_hash: &str) -> Option<String> {
+    let Ok(Value::Object(mut record)) = serde_json::from_str::<Value>(line) else {
+        return None;
+    };
+    let Some(Value::String(hash)) = record.remove("hash") else {
+        return None;
+    };
+    let linked = record.get("prev_hash").and_then(Value::as_str) == Some(prev_hash);
+    (linked && record_hash(&record).ok()? == hash).then_some(hash)
+}
+
+fn record_hash(record: &Map<String, Value>) -> Result<String> {
+    Ok(hex::encode(Sha256::digest(serde_json::to_vec(record)?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracker_core::{ContextStatus, UsageTotals};
+
+    fn event(id: &str, total_tokens: u64) -> UsageEvent {
+        UsageEvent {
+            id: id.to_string(),
+            ts: "2026-01-01T10:00:00Z".parse().expect("timestamp"),
+            model: "gpt-5.1".to_string(),
+            usage: UsageTotals {
+                input_tokens: total_tokens,
+                cached_input_tokens: 0,
+                output_tokens: 0,
+                reasoning_output_tokens: 0,
+                total_tokens,
+            },
+            context: ContextStatus::default(),
+            cost_usd: Some(0.25),
+            cost_source: None,
+            reasoning_effort: None,
+            provider: None,
+            source: "/logs/a.jsonl".to_string(),
+            session_id: "s1".to_string(),
+            request_id: None,
+            raw_json: None,
+        }
+    }
+
+    #[test]
+    fn chained_export_verifies_and_detects_edits() {
+        let mut out = Vec::new();
+        let head = write_chained_usage_events(
+            &[event("1", 100), event("2", 200), event("3", 300)],
+            &mut out,
+        )
+        .expect("export");
+        let text = String::from_utf8(out).expect("utf8");
+
+        let verified = verify_chain(text.as_bytes()).expect("verify");
+        assert_eq!(
+            verified,
+            ChainVerification {
+                records: 3,
+                head,
+                broken_at: None,
+            }
+        );
+        let first: Value = serde_json::from_str(text.lines().next().expect("line")).expect("json");
+        assert_eq!(first["prev_hash"], GENESIS_HASH);
+        assert_eq!(first["seq"], 1);
+
+        let tampered = text.replacen("\"total_tokens\":200", "\"total_tokens\":20", 1);
+        let verified = verify_chain(tampered.as_bytes()).expect("verify");
+        assert_eq!(verified.broken_at, Some(2));
+        assert_eq!(verified.head, first["hash"]);
+
+        let dropped = text
+            .lines()
+            .enumerate()
+            .filter(|(index, _)| *index != 1)
+            .map(|(_, line)| format!("{line}\n"))
+            .collect::<String>();
+        assert_eq!(
+            verify_chain(dropped.as_bytes()).expect("verify").broken_at,
+            Some(2)
+        );
+    }
+}
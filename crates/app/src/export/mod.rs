@@ -0,0 +1,12 @@
+mod chain;
+#[cfg(feature = "parquet")]
+mod parquet;
+mod records;
+
+pub use chain::{ChainVerification, GENESIS_HASH, verify_chain, write_chained_usage_events};
+
+#[cfg(feature = "parquet")]
+pub use parquet::write_usage_events_parquet;
+pub use records::{
+    DailyRollup, RecordFormat, daily_rollups, write_daily_rollups, write_usage_events,
+};
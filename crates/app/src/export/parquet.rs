@@ -0,0 +1,100 @@
+use std::io::Write;
+use std::sync::Arc;
+
+use arrow_array::{
+    ArrayRef, Float64Array, RecordBatch, StringArray, TimestampMillisecondArray, UInt64Array,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use tracker_core::UsageEvent;
+
+use crate::error::{AppError, Result};
+
+const BATCH_ROWS: usize = 8192;
+
+/// Writes usage events as a Snappy-compressed Parquet file.
+///
+/// `raw_json` is intentionally left out; the file is meant for columnar
+/// analysis, not for replaying the original log lines.
+pub fn write_usage_events_parquet<W: Write + Send>(events: &[UsageEvent], writer: W) -> Result<()> {
+    let schema = usage_event_schema();
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer =
+        ArrowWriter::try_new(writer, schema.clone(), Some(props)).map_err(parquet_error)?;
+    for chunk in events.chunks(BATCH_ROWS) {
+        let batch = usage_event_batch(&schema, chunk)?;
+        writer.write(&batch).map_err(parquet_error)?;
+    }
+    writer.close().map_err(parquet_error)?;
+    Ok(())
+}
+
+fn usage_event_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new(
+            "ts",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            true,
+        ),
+        Field::new("model", DataType::Utf8, false),
+        Field::new("reasoning_effort", DataType::Utf8, true),
+        Field::new("source", DataType::Utf8, false),
+        Field::new("session_id", DataType::Utf8, false),
+        Field::new("request_id", DataType::Utf8, true),
+        Field::new("input_tokens", DataType::UInt64, false),
+        Field::new("cached_input_tokens", DataType::UInt64, false),
+        Field::new("output_tokens", DataType::UInt64, false),
+        Field::new("reasoning_output_tokens", DataType::UInt64, false),
+        Field::new("total_tokens", DataType::UInt64, false),
+        Field::new("context_used", DataType::UInt64, false),
+        Field::new("context_window", DataType::UInt64, false),
+        Field::new("cost_usd", DataType::Float64, true),
+    ]))
+}
+
+fn usage_event_batch(schema: &SchemaRef, events: &[UsageEvent]) -> Result<RecordBatch> {
+    let strings = |f: fn(&UsageEvent) -> &str| -> ArrayRef {
+        Arc::new(StringArray::from_iter_values(events.iter().map(f)))
+    };
+    let optional_strings = |f: fn(&UsageEvent) -> Option<&str>| -> ArrayRef {
+        Arc::new(events.iter().map(f).collect::<StringArray>())
+    };
+    let counts = |f: fn(&UsageEvent) -> u64| -> ArrayRef {
+        Arc::new(UInt64Array::from_iter_values(events.iter().map(f)))
+    };
+    let timestamps = TimestampMillisecondArray::from_iter_values(
+        events
+            .iter()
+            .map(|event| event.ts.as_datetime().timestamp_millis()),
+    );
+    let costs: Float64Array = events.iter().map(|event| event.cost_usd).collect();
+
+    let columns = vec![
+        strings(|event| &event.id),
+        Arc::new(timestamps.with_timezone("UTC")) as ArrayRef,
+        strings(|event| &event.model),
+        optional_strings(|event| event.reasoning_effort.as_deref()),
+        strings(|event| &event.source),
+        strings(|event| &event.session_id),
+        optional_strings(|event| event.request_id.as_deref()),
+        counts(|event| event.usage.input_tokens),
+        counts(|event| event.usage.cached_input_tokens),
+        counts(|event| event.usage.output_tokens),
+        counts(|event| event.usage.reasoning_output_tokens),
+        counts(|event| event.usage.total_tokens),
+        counts(|event| event.context.context_used),
+        counts(|event| event.context.context_window),
+        Arc::new(costs) as ArrayRef,
+    ];
+    RecordBatch::try_new(schema.clone(), columns)
+        .map_err(|err| AppError::Message(format!("parquet error: {err}")))
+}
+
+fn parquet_error(err: parquet::errors::ParquetError) -> AppError {
+    AppError::Message(format!("parquet error: {err}"))
+}
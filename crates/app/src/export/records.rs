@@ -0,0 +1,344 @@
+//! Row-oriented exports (CSV, JSON, JSON Lines) for spreadsheets and scripts.
+//!
+//! Events use the same flat columns as the Parquet export; `raw_json` is left
+//! out for the same reason.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use chrono::{DateTime, TimeZone};
+use serde::Serialize;
+use tracker_core::{Timestamp, UsageEvent};
+use tracker_db::RowUsage;
+
+use crate::error::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordFormat {
+    Csv,
+    /// A single JSON array.
+    Json,
+    /// One JSON object per line.
+    Jsonl,
+}
+
+/// Usage totals for one local calendar day.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DailyRollup {
+    pub date: String,
+    pub events: u64,
+    pub input_tokens: u64,
+    pub cached_input_tokens: u64,
+    pub output_tokens: u64,
+    pub reasoning_output_tokens: u64,
+    pub total_tokens: u64,
+    /// `None` when no event of the day had a matching pricing rule.
+    pub cost_usd: Option<f64>,
+}
+
+/// Groups usage rows by local date (in `tz`), oldest day first.
+pub fn daily_rollups<Tz: TimeZone>(rows: &[RowUsage], tz: &Tz) -> Vec<DailyRollup> {
+    let mut by_date: BTreeMap<String, DailyRollup> = BTreeMap::new();
+    for row in rows {
+        let Ok(ts) = DateTime::parse_from_rfc3339(&row.ts) else {
+            continue;
+        };
+        let date = ts
+            .with_timezone(tz)
+            .date_naive()
+            .format("%Y-%m-%d")
+            .to_string();
+        let day = by_date.entry(date.clone()).or_insert_with(|| DailyRollup {
+            date,
+            ..DailyRollup::default()
+        });
+        day.events += 1;
+        day.input_tokens += row.usage.input_tokens;
+        day.cached_input_tokens += row.usage.cached_input_tokens;
+        day.output_tokens += row.usage.output_tokens;
+        day.reasoning_output_tokens += row.usage.reasoning_output_tokens;
+        day.total_tokens += row.usage.total_tokens;
+        if let Some(cost) = row.cost_usd {
+            *day.cost_usd.get_or_insert(0.0) += cost;
+        }
+    }
+    by_date.into_values().collect()
+}
+
+pub fn write_usage_events<W: Write>(
+    events: &[UsageEvent],
+    format: RecordFormat,
+    writer: W,
+) -> Result<()> {
+    write_records(events.iter().map(EventRecord::from), format, writer)
+}
+
+pub fn write_daily_rollups<W: Write>(
+    days: &[DailyRollup],
+    format: RecordFormat,
+    writer: W,
+) -> Result<()> {
+    write_records(days.iter(), format, writer)
+}
+
+/// A flat row with a fixed column order for the CSV header.
+trait Record: Serialize {
+    const COLUMNS: &'static [&'static str];
+
+    fn fields(&self) -> Vec<String>;
+}
+
+#[derive(Serialize)]
+pub(super) struct EventRecord<'a> {
+    id: &'a str,
+    ts: Timestamp,
+    model: &'a str,
+    reasoning_effort: Option<&'a str>,
+    source: &'a str,
+    session_id: &'a str,
+    request_id: Option<&'a str>,
+    input_tokens: u64,
+    cached_input_tokens: u64,
+    output_tokens: u64,
+    reasoning_output_tokens: u64,
+    total_tokens: u64,
+    context_used: u64,
+    context_window: u64,
+    cost_usd: Option<f64>,
+}
+
+impl<'a> From<&'a UsageEvent> for EventRecord<'a> {
+    fn from(event: &'a UsageEvent) -> Self {
+        Self {
+            id: &event.id,
+            ts: event.ts,
+            model: &event.model,
+            reasoning_effort: event.reasoning_effort.as_deref(),
+            source: &event.source,
+            session_id: &event.session_id,
+            request_id: event.request_id.as_deref(),
+            input_tokens: event.usage.input_tokens,
+            cached_input_tokens: event.usage.cached_input_tokens,
+            output_tokens: event.usage.output_tokens,
+            reasoning_output_tokens: event.usage.reasoning_output_tokens,
+            total_tokens: event.usage.total_tokens,
+            context_used: event.context.context_used,
+            context_window: event.context.context_window,
+            cost_usd: event.cost_usd,
+        }
+    }
+}
+
+impl Record for EventRecord<'_> {
+    const COLUMNS: &'static [&'static str] = &[
+        "id",
+        "ts",
+        "model",
+        "reasoning_effort",
+        "source",
+        "session_id",
+        "request_id",
+        "input_tokens",
+        "cached_input_tokens",
+        "output_tokens",
+        "reasoning_output_tokens",
+        "total_tokens",
+        "context_used",
+        "context_window",
+        "cost_usd",
+    ];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.ts.to_string(),
+            self.model.to_string(),
+            self.reasoning_effort.unwrap_or_default().to_string(),
+            self.source.to_string(),
+            self.session_id.to_string(),
+            self.request_id.unwrap_or_default().to_string(),
+            self.input_tokens.to_string(),
+            self.cached_input_tokens.to_string(),
+            self.output_tokens.to_string(),
+            self.reasoning_output_tokens.to_string(),
+            self.total_tokens.to_string(),
+            self.context_used.to_string(),
+            self.context_window.to_string(),
+            optional_number(self.cost_usd),
+        ]
+    }
+}
+
+impl Record for &DailyRollup {
+    const COLUMNS: &'static [&'static str] = &[
+        "date",
+        "events",
+        "input_tokens",
+        "cached_input_tokens",
+        "output_tokens",
+        "reasoning_output_tokens",
+        "total_tokens",
+        "cost_usd",
+    ];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.date.clone(),
+            self.events.to_string(),
+            self.input_tokens.to_string(),
+            self.cached_input_tokens.to_string(),
+            self.output_tokens.to_string(),
+            self.reasoning_output_tokens.to_string(),
+            self.total_tokens.to_string(),
+            optional_number(self.cost_usd),
+        ]
+    }
+}
+
+/// Writes one record at a time so large ranges are not buffered twice.
+fn write_records<R: Record, W: Write>(
+    records: impl Iterator<Item = R>,
+    format: RecordFormat,
+    mut writer: W,
+) -> Result<()> {
+    match format {
+        RecordFormat::Csv => {
+            write_csv_line(&mut writer, R::COLUMNS.iter().copied())?;
+            for record in records {
+                let fields = record.fields();
+                write_csv_line(&mut writer, fields.iter().map(String::as_str))?;
+            }
+        }
+        RecordFormat::Json => {
+            writer.write_all(b"[")?;
+            for (index, record) in records.enumerate() {
+                writer.write_all(if index == 0 { b"\n" } else { b",\n" })?;
+                serde_json::to_writer(&mut writer, &record)?;
+            }
+            writer.write_all(b"\n]\n")?;
+        }
+        RecordFormat::Jsonl => {
+            for record in records {
+                serde_json::to_writer(&mut writer, &record)?;
+                writer.write_all(b"\n")?;
+            }
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_csv_line<'a, W: Write>(
+    writer: &mut W,
+    fields: impl Iterator<Item = &'a str>,
+) -> Result<()> {
+    let line = fields.map(csv_field).collect::<Vec<_>>().join(",");
+    writer.write_all(line.as_bytes())?;
+    writer.write_all(b"\r\n")?;
+    Ok(())
+}
+
+/// Quotes a field per RFC 4180 when it contains a delimiter, quote, or
+/// line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn optional_number(value: Option<f64>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+    use tracker_core::{ContextStatus, UsageTotals};
+
+    fn usage(input: u64, output: u64) -> UsageTotals {
+        UsageTotals {
+            input_tokens: input,
+            cached_input_tokens: 0,
+            output_tokens: output,
+            reasoning_output_tokens: 0,
+            total_tokens: input + output,
+        }
+    }
+
+    fn row(ts: &str, input: u64, cost: Option<f64>) -> RowUsage {
+        RowUsage {
+            id: ts.to_string(),
+            ts: ts.to_string(),
+            model: "gpt-5.1".to_string(),
+            usage: usage(input, 0),
+            cost_usd: cost,
+            source: "a".to_string(),
+            reasoning_effort: None,
+            provider: None,
+        }
+    }
+
+    #[test]
+    fn daily_rollups_group_by_local_date() {
+        let rows = vec![
+            row("2026-01-01T10:00:00Z", 100, Some(0.5)),
+            row("2026-01-01T23:30:00Z", 50, None),
+            row("2026-01-02T09:00:00Z", 10, Some(0.25)),
+        ];
+        let days = daily_rollups(&rows, &FixedOffset::east_opt(3600).expect("offset"));
+
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].date, "2026-01-01");
+        assert_eq!(days[0].events, 1);
+        assert_eq!(days[1].date, "2026-01-02");
+        assert_eq!(days[1].input_tokens, 60);
+        assert_eq!(days[1].cost_usd, Some(0.25));
+    }
+
+    #[test]
+    fn events_write_as_csv_and_jsonl() {
+        let event = UsageEvent {
+            id: "1".to_string(),
+            ts: "2026-01-01T10:00:00Z".parse().expect("timestamp"),
+            model: "gpt-5.1".to_string(),
+            usage: usage(100, 10),
+            context: ContextStatus {
+                context_used: 110,
+                context_window: 1000,
+            },
+            cost_usd: None,
+            cost_source: None,
+            reasoning_effort: Some("high".to_string()),
+            provider: None,
+            source: "/logs/a, \"b\".jsonl".to_string(),
+            session_id: "s1".to_string(),
+            request_id: None,
+            raw_json: Some("{}".to_string()),
+        };
+
+        let mut csv = Vec::new();
+        write_usage_events(std::slice::from_ref(&event), RecordFormat::Csv, &mut csv).expect("csv");
+        let csv = String::from_utf8(csv).expect("utf8");
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some(EventRecord::COLUMNS.join(",").as_str()));
+        assert_eq!(
+            lines.next(),
+            Some(
+                "1,2026-01-01T10:00:00.000Z,gpt-5.1,high,\"/logs/a, \"\"b\"\".jsonl\",s1,,100,0,10,0,110,110,1000,"
+            )
+        );
+
+        let mut jsonl = Vec::new();
+        write_usage_events(&[event.clone(), event], RecordFormat::Jsonl, &mut jsonl)
+            .expect("jsonl");
+        let jsonl = String::from_utf8(jsonl).expect("utf8");
+        let first: serde_json::Value =
+            serde_json::from_str(jsonl.lines().next().expect("line")).expect("json");
+        assert_eq!(jsonl.lines().count(), 2);
+        assert_eq!(first["context_window"], 1000);
+        assert!(first.get("raw_json").is_none());
+    }
+}
@@ -1,18 +1,35 @@
+pub mod alerts;
 pub mod app;
+pub mod audit;
+pub mod badge;
+pub mod bundle;
+pub mod calendar;
+pub mod ccusage;
 pub mod config;
+pub mod doctor;
 pub mod error;
+pub mod events;
+pub mod export;
+pub mod logging;
+pub mod notifications;
 pub mod pricing;
+pub mod report;
+pub mod schedules;
 pub mod services;
 pub mod startup;
+pub mod statsd;
 pub mod util;
+pub mod version;
+pub mod webhooks;
 
 pub use app::{AppConfig, AppState};
 pub use config::RangeParams;
 pub use error::{ApiError, AppError, Result};
+pub use logging::init_tracing;
 pub use pricing::{
     apply_pricing_defaults, load_initial_pricing, load_pricing_defaults, sync_pricing_defaults,
     write_pricing_defaults,
 };
 pub use services::{AppServices, SettingsSnapshot};
 pub use startup::{AppPaths, ensure_app_data_dir, migrate_legacy_storage};
-pub use util::time::{normalize_rfc3339_to_utc, resolve_range};
+pub use util::time::{normalize_rfc3339_to_utc, preceding_range, resolve_range};
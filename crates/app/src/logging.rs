@@ -0,0 +1,21 @@
+use tracing_subscriber::EnvFilter;
+
+/// Environment variable holding a `tracing` filter directive such as
+/// `debug` or `info,ingest=debug`. Falls back to `RUST_LOG`, then
+/// [`DEFAULT_FILTER`].
+pub const LOG_ENV: &str = "CODEX_TRACKER_LOG";
+
+/// Per-request logs stay quiet unless asked for; the dashboard polls often.
+pub const DEFAULT_FILTER: &str = "info,tower_http=warn";
+
+/// Installs the process-wide stderr subscriber. Safe to call more than once;
+/// later calls are no-ops.
+pub fn init_tracing() {
+    let filter = EnvFilter::try_from_env(LOG_ENV)
+        .or_else(|_| EnvFilter::try_from_default_env())
+        .unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .try_init();
+}
@@ -0,0 +1,185 @@
+use std::time::Duration;
+
+use serde_json::{Value, json};
+use tracker_core::UsageSummary;
+
+use crate::alerts::Alert;
+
+pub const SLACK_WEBHOOK_URL_KEY: &str = "notify_slack_webhook_url";
+pub const DISCORD_WEBHOOK_URL_KEY: &str = "notify_discord_webhook_url";
+pub const DAILY_SUMMARY_KEY: &str = "notify_daily_summary";
+/// Local date (`YYYY-MM-DD`) of the last day a summary was sent for.
+pub const DAILY_SUMMARY_LAST_KEY: &str = "notify_daily_summary_last";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Chat services that accept incoming-webhook messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    Slack,
+    Discord,
+}
+
+impl Channel {
+    pub const ALL: [Channel; 2] = [Channel::Slack, Channel::Discord];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Channel::Slack => "slack",
+            Channel::Discord => "discord",
+        }
+    }
+
+    pub fn setting_key(self) -> &'static str {
+        match self {
+            Channel::Slack => SLACK_WEBHOOK_URL_KEY,
+            Channel::Discord => DISCORD_WEBHOOK_URL_KEY,
+        }
+    }
+
+    /// JSON body for the channel's incoming webhook.
+    pub fn payload(self, message: &Message) -> Value {
+        match self {
+            Channel::Slack => json!({ "text": message.render("*") }),
+            Channel::Discord => json!({ "content": message.render("**") }),
+        }
+    }
+}
+
+/// A short chat message: a bold title followed by plain lines.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub title: String,
+    pub lines: Vec<String>,
+}
+
+impl Message {
+    fn render(&self, bold: &str) -> String {
+        let mut text = format!("{bold}{}{bold}", self.title);
+        for line in &self.lines {
+            text.push('\n');
+            text.push_str(line);
+        }
+        text
+    }
+
+    pub fn test() -> Self {
+        Self {
+            title: "Codex Tracker".to_string(),
+            lines: vec!["Notifications are set up.".to_string()],
+        }
+    }
+
+    pub fn alert(alert: &Alert) -> Self {
+        match alert {
+            Alert::LimitThreshold {
+                limit_type,
+                threshold_percent,
+                used_percent,
+                reset_at,
+            } => Self {
+                title: format!("Codex {limit_type} limit passed {threshold_percent:.0}%"),
+                lines: vec![
+                    format!("{used_percent:.0}% used"),
+                    format!("Resets at {reset_at}"),
+                ],
+            },
+            Alert::BudgetExceeded {
+                monthly_budget_usd,
+                month_cost_usd,
+            } => Self {
+                title: "Codex monthly budget exceeded".to_string(),
+                lines: vec![format!(
+                    "${month_cost_usd:.2} spent of ${monthly_budget_usd:.2} this month"
+                )],
+            },
+            Alert::BudgetThreshold {
+                threshold_percent,
+                monthly_budget_usd,
+                month_cost_usd,
+                reset_at,
+            } => Self {
+                title: format!("Codex monthly budget passed {threshold_percent:.0}%"),
+                lines: vec![
+                    format!("${month_cost_usd:.2} spent of ${monthly_budget_usd:.2} this month"),
+                    format!("Resets at {reset_at}"),
+                ],
+            },
+        }
+    }
+
+    pub fn daily_summary(date: &str, summary: &UsageSummary) -> Self {
+        let cost = summary
+            .total_cost_usd
+            .map_or_else(|| "n/a".to_string(), |cost| format!("${cost:.2}"));
+        Self {
+            title: format!("Codex usage for {date}"),
+            lines: vec![
+                format!("Cost: {cost}"),
+                format!(
+                    "Tokens: {} ({} input, {} cached, {} output)",
+                    summary.total_tokens,
+                    summary.input_tokens,
+                    summary.cached_input_tokens,
+                    summary.output_tokens
+                ),
+            ],
+        }
+    }
+}
+
+/// Posts the message once; chat webhooks are best-effort and not retried.
+pub(crate) fn send(channel: Channel, url: &str, message: &Message) -> Result<(), String> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(REQUEST_TIMEOUT))
+        .http_status_as_error(false)
+        .build()
+        .into();
+    let body = channel.payload(message).to_string();
+    let response = agent
+        .post(url)
+        .header("content-type", "application/json")
+        .header(
+            "user-agent",
+            concat!("codex-tracker/", env!("CARGO_PKG_VERSION")),
+        )
+        .send(body.as_str())
+        .map_err(|err| err.to_string())?;
+    let status = response.status().as_u16();
+    if (200..300).contains(&status) {
+        Ok(())
+    } else {
+        Err(format!("{} webhook returned HTTP {status}", channel.name()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_payload_per_channel() {
+        let message = Message {
+            title: "Title".to_string(),
+            lines: vec!["one".to_string(), "two".to_string()],
+        };
+        assert_eq!(
+            Channel::Slack.payload(&message),
+            json!({ "text": "*Title*\none\ntwo" })
+        );
+        assert_eq!(
+            Channel::Discord.payload(&message),
+            json!({ "content": "**Title**\none\ntwo" })
+        );
+    }
+
+    #[test]
+    fn describes_budget_alerts() {
+        let message = Message::alert(&Alert::BudgetExceeded {
+            monthly_budget_usd: 50.0,
+            month_cost_usd: 51.25,
+        });
+        assert_eq!(message.title, "Codex monthly budget exceeded");
+        assert_eq!(message.lines, vec!["$51.25 spent of $50.00 this month"]);
+    }
+}
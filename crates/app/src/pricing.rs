@@ -25,14 +25,7 @@ pub fn sync_pricing_defaults(db_path: &Path, defaults_path: &Path) -> Result<()>
     }
     let inputs = rules
         .into_iter()
-        .map(|rule| PricingRuleInput {
-            model_pattern: rule.model_pattern,
-            input_per_1m: rule.input_per_1m,
-            cached_input_per_1m: rule.cached_input_per_1m,
-            output_per_1m: rule.output_per_1m,
-            effective_from: rule.effective_from,
-            effective_to: rule.effective_to,
-        })
+        .map(PricingRuleInput::from)
         .collect::<Vec<_>>();
     write_pricing_defaults(defaults_path, &inputs)
 }
@@ -0,0 +1,616 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, NaiveDate, SecondsFormat, Utc};
+use serde::Serialize;
+use tracker_core::{
+    ModelBreakdown, SessionBreakdown, TimeRange, TimeSeriesPoint, UsageLimitSnapshot, UsageSummary,
+};
+
+use crate::badge::{escape_xml, format_compact};
+use crate::error::{AppError, Result};
+
+/// How many models / sessions the digest lists.
+pub const REPORT_TOP_N: usize = 5;
+
+/// Rolling window a report covers; deltas compare against the window before.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportPeriod {
+    Day,
+    Week,
+    Month,
+}
+
+impl ReportPeriod {
+    pub fn parse(value: Option<&str>) -> Result<Self> {
+        match value.unwrap_or("day") {
+            "day" => Ok(ReportPeriod::Day),
+            "week" => Ok(ReportPeriod::Week),
+            "month" => Ok(ReportPeriod::Month),
+            other => Err(AppError::InvalidInput(format!(
+                "unsupported report period {other}"
+            ))),
+        }
+    }
+
+    fn length(self) -> Duration {
+        match self {
+            ReportPeriod::Day => Duration::days(1),
+            ReportPeriod::Week => Duration::days(7),
+            ReportPeriod::Month => Duration::days(30),
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            ReportPeriod::Day => "last 24 hours",
+            ReportPeriod::Week => "last 7 days",
+            ReportPeriod::Month => "last 30 days",
+        }
+    }
+
+    /// The reported window ending at `now`, and the one before it.
+    pub fn ranges(self, now: DateTime<Utc>) -> (TimeRange, TimeRange) {
+        let start = now - self.length();
+        let previous_start = start - self.length();
+        let stamp = |value: DateTime<Utc>| value.to_rfc3339_opts(SecondsFormat::Millis, true);
+        (
+            TimeRange {
+                start: stamp(start),
+                end: stamp(now),
+            },
+            TimeRange {
+                start: stamp(previous_start),
+                end: stamp(start),
+            },
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+    /// A standalone HTML page with inline styles and pre-rendered SVG
+    /// charts, for sharing as a single file.
+    Snapshot,
+    /// The report data itself, for scripts.
+    Json,
+}
+
+impl ReportFormat {
+    pub fn parse(value: Option<&str>) -> Result<Self> {
+        match value.unwrap_or("markdown") {
+            "markdown" | "md" => Ok(ReportFormat::Markdown),
+            "html" => Ok(ReportFormat::Html),
+            "snapshot" => Ok(ReportFormat::Snapshot),
+            "json" => Ok(ReportFormat::Json),
+            other => Err(AppError::InvalidInput(format!(
+                "unsupported report format {other}"
+            ))),
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "text/markdown; charset=utf-8",
+            ReportFormat::Html | ReportFormat::Snapshot => "text/html; charset=utf-8",
+            ReportFormat::Json => "application/json",
+        }
+    }
+}
+
+/// Data behind a usage digest.
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub period: ReportPeriod,
+    pub range: TimeRange,
+    pub current: UsageSummary,
+    pub previous: UsageSummary,
+    pub top_models: Vec<ModelBreakdown>,
+    pub top_sessions: Vec<SessionBreakdown>,
+    pub limits: Vec<UsageLimitSnapshot>,
+    /// One entry per local day the range touches, including empty days.
+    pub daily: Vec<DailyUsage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DailyUsage {
+    /// `YYYY-MM-DD` in local time.
+    pub date: String,
+    pub total_tokens: u64,
+    pub total_cost_usd: f64,
+}
+
+impl DailyUsage {
+    /// Merges daily token and cost buckets into one entry per day from
+    /// `first` to `last`, filling days without usage with zeros.
+    pub fn fill(
+        first: NaiveDate,
+        last: NaiveDate,
+        tokens: &[TimeSeriesPoint],
+        cost: &[TimeSeriesPoint],
+    ) -> Vec<Self> {
+        let tokens = by_day(tokens);
+        let cost = by_day(cost);
+        first
+            .iter_days()
+            .take_while(|date| *date <= last)
+            .map(|date| {
+                let date = date.format("%Y-%m-%d").to_string();
+                DailyUsage {
+                    total_tokens: tokens.get(date.as_str()).copied().unwrap_or_default() as u64,
+                    total_cost_usd: cost.get(date.as_str()).copied().unwrap_or_default(),
+                    date,
+                }
+            })
+            .collect()
+    }
+}
+
+enum Block {
+    Table {
+        headers: Vec<&'static str>,
+        rows: Vec<Vec<String>>,
+    },
+    List(Vec<String>),
+    Text(String),
+}
+
+impl Report {
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Markdown => self.to_markdown(),
+            ReportFormat::Html => self.to_html(),
+            ReportFormat::Snapshot => self.to_snapshot(),
+            ReportFormat::Json => serde_json::to_string_pretty(self).unwrap_or_default(),
+        }
+    }
+
+    fn title(&self) -> String {
+        format!("Codex usage report: {}", self.period.title())
+    }
+
+    fn subtitle(&self) -> String {
+        format!(
+            "{} to {}",
+            format_time(&self.range.start),
+            format_time(&self.range.end)
+        )
+    }
+
+    fn sections(&self) -> Vec<(&'static str, Block)> {
+        let totals = Block::Table {
+            headers: vec!["", "This period", "Previous", "Change"],
+            rows: vec![
+                vec![
+                    "Tokens".to_string(),
+                    format_compact(self.current.total_tokens),
+                    format_compact(self.previous.total_tokens),
+                    format_delta(
+                        self.current.total_tokens as f64,
+                        self.previous.total_tokens as f64,
+                    ),
+                ],
+                vec![
+                    "Cost".to_string(),
+                    format_cost(self.current.total_cost_usd),
+                    format_cost(self.previous.total_cost_usd),
+                    match (self.current.total_cost_usd, self.previous.total_cost_usd) {
+                        (Some(current), Some(previous)) => format_delta(current, previous),
+                        _ => "n/a".to_string(),
+                    },
+                ],
+            ],
+        };
+        let models = if self.top_models.is_empty() {
+            Block::Text("No usage.".to_string())
+        } else {
+            Block::Table {
+                headers: vec!["Model", "Tokens", "Cost"],
+                rows: self
+                    .top_models
+                    .iter()
+                    .map(|item| {
+                        vec![
+                            item.model.clone(),
+                            format_compact(item.total_tokens),
+                            format_cost(item.total_cost_usd),
+                        ]
+                    })
+                    .collect(),
+            }
+        };
+        let sessions = if self.top_sessions.is_empty() {
+            Block::Text("No sessions.".to_string())
+        } else {
+            Block::Table {
+                headers: vec!["Session", "Model", "Tokens", "Cost", "Last seen"],
+                rows: self
+                    .top_sessions
+                    .iter()
+                    .map(|item| {
+                        vec![
+                            item.session_id.clone(),
+                            item.model.clone(),
+                            format_compact(item.total_tokens),
+                            format_cost(item.total_cost_usd),
+                            format_time(&item.last_seen),
+                        ]
+                    })
+                    .collect(),
+            }
+        };
+        let limits = if self.limits.is_empty() {
+            Block::Text("No current limit windows.".to_string())
+        } else {
+            Block::List(
+                self.limits
+                    .iter()
+                    .map(|limit| {
+                        format!(
+                            "{}: {:.0}% left, resets {}",
+                            limit.limit_type,
+                            limit.percent_left,
+                            format_time(&limit.reset_at.to_string())
+                        )
+                    })
+                    .collect(),
+            )
+        };
+        vec![
+            ("Totals", totals),
+            ("Top models", models),
+            ("Top sessions", sessions),
+            ("Limits", limits),
+        ]
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n_{}_\n", self.title(), self.subtitle());
+        for (heading, block) in self.sections() {
+            out.push_str(&format!("\n## {heading}\n\n"));
+            match block {
+                Block::Table { headers, rows } => {
+                    out.push_str(&markdown_row(headers.iter().map(|value| value.to_string())));
+                    out.push_str(&markdown_row(
+                        (0..headers.len())
+                            .map(|index| if index == 0 { "---" } else { "---:" }.to_string()),
+                    ));
+                    for row in rows {
+                        out.push_str(&markdown_row(row.into_iter()));
+                    }
+                }
+                Block::List(items) => {
+                    for item in items {
+                        out.push_str(&format!("- {item}\n"));
+                    }
+                }
+                Block::Text(text) => {
+                    out.push_str(&text);
+                    out.push('\n');
+                }
+            }
+        }
+        out
+    }
+
+    pub fn to_html(&self) -> String {
+        let title = escape_xml(&self.title());
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n<body>\n<h1>{title}</h1>\n<p><em>{}</em></p>\n",
+            escape_xml(&self.subtitle())
+        );
+        for (heading, block) in self.sections() {
+            out.push_str(&format!("<h2>{heading}</h2>\n"));
+            match block {
+                Block::Table { headers, rows } => {
+                    out.push_str("<table>\n<tr>");
+                    for header in headers {
+                        out.push_str(&format!("<th>{}</th>", escape_xml(header)));
+                    }
+                    out.push_str("</tr>\n");
+                    for row in rows {
+                        out.push_str("<tr>");
+                        for cell in row {
+                            out.push_str(&format!("<td>{}</td>", escape_xml(&cell)));
+                        }
+                        out.push_str("</tr>\n");
+                    }
+                    out.push_str("</table>\n");
+                }
+                Block::List(items) => {
+                    out.push_str("<ul>\n");
+                    for item in items {
+                        out.push_str(&format!("<li>{}</li>\n", escape_xml(&item)));
+                    }
+                    out.push_str("</ul>\n");
+                }
+                Block::Text(text) => {
+                    out.push_str(&format!("<p>{}</p>\n", escape_xml(&text)));
+                }
+            }
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    /// The HTML report as one self-contained file: inline styles and SVG
+    /// charts, no scripts or external requests, so it opens anywhere.
+    pub fn to_snapshot(&self) -> String {
+        let html = self.to_html();
+        let mut charts = String::from("<h2>Daily usage</h2>\n");
+        if self.daily.is_empty() {
+            charts.push_str("<p>No usage.</p>\n");
+        } else {
+            charts.push_str(&bar_chart("Tokens per day", &self.daily, |day| {
+                (day.total_tokens as f64, format_compact(day.total_tokens))
+            }));
+            charts.push_str(&bar_chart("Cost per day", &self.daily, |day| {
+                (day.total_cost_usd, format_cost(Some(day.total_cost_usd)))
+            }));
+        }
+        if let Some(top) = self.top_models.first().filter(|top| top.total_tokens > 0) {
+            charts.push_str("<h2>Model share</h2>\n<table class=\"share\">\n");
+            for item in &self.top_models {
+                let percent = item.total_tokens as f64 / top.total_tokens as f64 * 100.0;
+                charts.push_str(&format!(
+                    "<tr><td>{}</td><td class=\"bar\"><span style=\"width:{percent:.1}%\"></span></td><td>{}</td></tr>\n",
+                    escape_xml(&item.model),
+                    format_compact(item.total_tokens)
+                ));
+            }
+            charts.push_str("</table>\n");
+        }
+        let generated = format!(
+            "<footer>Generated by codex-tracker on {}</footer>\n</body>",
+            Utc::now().format("%Y-%m-%d %H:%M UTC")
+        );
+        html.replacen(
+            "</head>",
+            &format!("<meta name=\"viewport\" content=\"width=device-width\">\n<style>{SNAPSHOT_CSS}</style>\n</head>"),
+            1,
+        )
+        .replacen("<h2>Top models</h2>", &format!("{charts}<h2>Top models</h2>"), 1)
+        .replacen("</body>", &generated, 1)
+    }
+}
+
+const SNAPSHOT_CSS: &str = "body{font-family:-apple-system,'Segoe UI',sans-serif;max-width:760px;margin:2em auto;padding:0 1em;color:#1f2328}\
+table{border-collapse:collapse;margin-bottom:1em}\
+th,td{padding:4px 10px;border-bottom:1px solid #d0d7de;text-align:right}\
+th:first-child,td:first-child{text-align:left}\
+.share{width:100%}.share .bar{width:60%}\
+.share .bar span{display:block;height:12px;background:#0969da;border-radius:2px}\
+svg{display:block;margin-bottom:1.5em}\
+footer{margin-top:2em;color:#656d76;font-size:0.85em}";
+
+const CHART_WIDTH: f64 = 720.0;
+const CHART_HEIGHT: f64 = 140.0;
+
+/// A captioned SVG bar chart with one bar per day; hovering a bar shows its
+/// date and value.
+fn bar_chart(
+    caption: &str,
+    days: &[DailyUsage],
+    value: impl Fn(&DailyUsage) -> (f64, String),
+) -> String {
+    let values: Vec<(f64, String)> = days.iter().map(value).collect();
+    let max = values.iter().map(|(value, _)| *value).fold(0.0, f64::max);
+    let max_label = values
+        .iter()
+        .find(|(value, _)| *value == max)
+        .map(|(_, label)| label.clone())
+        .unwrap_or_default();
+    let slot = CHART_WIDTH / days.len() as f64;
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {CHART_WIDTH} {}\" width=\"100%\" role=\"img\" aria-label=\"{}\">\n",
+        CHART_HEIGHT + 40.0,
+        escape_xml(caption)
+    );
+    out.push_str(&format!(
+        "<text x=\"0\" y=\"12\" font-size=\"12\" font-weight=\"bold\">{}</text><text x=\"{CHART_WIDTH}\" y=\"12\" font-size=\"11\" text-anchor=\"end\" fill=\"#656d76\">max {}</text>\n",
+        escape_xml(caption),
+        escape_xml(&max_label)
+    ));
+    for (index, (day, (value, label))) in days.iter().zip(&values).enumerate() {
+        let height = if max > 0.0 {
+            value / max * CHART_HEIGHT
+        } else {
+            0.0
+        };
+        out.push_str(&format!(
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{height:.1}\" fill=\"#0969da\"><title>{}: {}</title></rect>\n",
+            index as f64 * slot + slot * 0.1,
+            20.0 + CHART_HEIGHT - height,
+            slot * 0.8,
+            day.date,
+            escape_xml(label)
+        ));
+    }
+    let axis = CHART_HEIGHT + 34.0;
+    out.push_str(&format!(
+        "<text x=\"0\" y=\"{axis}\" font-size=\"11\" fill=\"#656d76\">{}</text><text x=\"{CHART_WIDTH}\" y=\"{axis}\" font-size=\"11\" text-anchor=\"end\" fill=\"#656d76\">{}</text>\n</svg>\n",
+        days[0].date,
+        days[days.len() - 1].date
+    ));
+    out
+}
+
+/// Daily buckets keyed by their `YYYY-MM-DD` date.
+fn by_day(points: &[TimeSeriesPoint]) -> HashMap<&str, f64> {
+    points
+        .iter()
+        .filter_map(|point| Some((point.bucket_start.get(..10)?, point.value)))
+        .collect()
+}
+
+fn markdown_row(cells: impl Iterator<Item = String>) -> String {
+    let cells: Vec<String> = cells.map(|cell| cell.replace('|', "\\|")).collect();
+    format!("| {} |\n", cells.join(" | "))
+}
+
+fn format_cost(cost_usd: Option<f64>) -> String {
+    cost_usd.map_or_else(|| "n/a".to_string(), |cost| format!("${cost:.2}"))
+}
+
+pub fn format_delta(current: f64, previous: f64) -> String {
+    if previous == 0.0 {
+        return if current == 0.0 { "0%" } else { "new" }.to_string();
+    }
+    format!("{:+.0}%", (current - previous) / previous * 100.0)
+}
+
+fn format_time(value: &str) -> String {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| {
+            time.with_timezone(&Utc)
+                .format("%Y-%m-%d %H:%M UTC")
+                .to_string()
+        })
+        .unwrap_or_else(|_| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(total_tokens: u64, total_cost_usd: Option<f64>) -> UsageSummary {
+        UsageSummary {
+            total_tokens,
+            total_cost_usd,
+            ..UsageSummary::default()
+        }
+    }
+
+    fn report() -> Report {
+        let now = DateTime::parse_from_rfc3339("2026-01-02T12:00:00Z")
+            .expect("now")
+            .with_timezone(&Utc);
+        let (range, _) = ReportPeriod::Day.ranges(now);
+        Report {
+            period: ReportPeriod::Day,
+            range,
+            current: summary(1_500, Some(3.0)),
+            previous: summary(1_000, Some(4.0)),
+            top_models: vec![ModelBreakdown {
+                model: "gpt-5.1".to_string(),
+                total_tokens: 1_500,
+                total_cost_usd: Some(3.0),
+            }],
+            top_sessions: vec![SessionBreakdown {
+                session_id: "a|b".to_string(),
+                model: "gpt-5.1".to_string(),
+                last_seen: "2026-01-02T11:30:00Z".to_string(),
+                total_tokens: 1_500,
+                total_cost_usd: None,
+                message_count: 4,
+            }],
+            limits: Vec::new(),
+            daily: vec![
+                DailyUsage {
+                    date: "2026-01-01".to_string(),
+                    total_tokens: 500,
+                    total_cost_usd: 1.0,
+                },
+                DailyUsage {
+                    date: "2026-01-02".to_string(),
+                    total_tokens: 1_000,
+                    total_cost_usd: 2.0,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn ranges_cover_consecutive_windows() {
+        let now = DateTime::parse_from_rfc3339("2026-01-08T00:00:00Z")
+            .expect("now")
+            .with_timezone(&Utc);
+        let (current, previous) = ReportPeriod::Week.ranges(now);
+        assert_eq!(current.start, "2026-01-01T00:00:00.000Z");
+        assert_eq!(current.end, "2026-01-08T00:00:00.000Z");
+        assert_eq!(previous.start, "2025-12-25T00:00:00.000Z");
+        assert_eq!(previous.end, current.start);
+    }
+
+    #[test]
+    fn markdown_includes_totals_and_deltas() {
+        let markdown = report().to_markdown();
+        assert!(markdown.starts_with("# Codex usage report: last 24 hours\n"));
+        assert!(markdown.contains("_2026-01-01 12:00 UTC to 2026-01-02 12:00 UTC_"));
+        assert!(markdown.contains("| Tokens | 1.5k | 1.0k | +50% |\n"));
+        assert!(markdown.contains("| Cost | $3.00 | $4.00 | -25% |\n"));
+        assert!(markdown.contains("| a\\|b | gpt-5.1 | 1.5k | n/a | 2026-01-02 11:30 UTC |\n"));
+        assert!(markdown.contains("No current limit windows."));
+    }
+
+    #[test]
+    fn html_escapes_cells() {
+        let mut report = report();
+        report.top_models[0].model = "<gpt>".to_string();
+        let html = report.to_html();
+        assert!(html.contains("<td>&lt;gpt&gt;</td>"));
+        assert!(html.ends_with("</html>\n"));
+    }
+
+    #[test]
+    fn daily_usage_fills_days_without_usage() {
+        let point = |bucket_start: &str, value| TimeSeriesPoint {
+            bucket_start: bucket_start.to_string(),
+            value,
+        };
+        let days = DailyUsage::fill(
+            NaiveDate::from_ymd_opt(2026, 1, 30).expect("first"),
+            NaiveDate::from_ymd_opt(2026, 2, 2).expect("last"),
+            &[
+                point("2026-01-30T00:00:00+01:00", 100.0),
+                point("2026-02-02T00:00:00+01:00", 50.0),
+            ],
+            &[point("2026-02-02T00:00:00+01:00", 0.25)],
+        );
+        assert_eq!(days.len(), 4);
+        assert_eq!(days[0].total_tokens, 100);
+        assert_eq!(days[1].date, "2026-01-31");
+        assert_eq!(days[1].total_tokens, 0);
+        assert_eq!(days[3].total_tokens, 50);
+        assert_eq!(days[3].total_cost_usd, 0.25);
+    }
+
+    #[test]
+    fn snapshot_is_a_single_file_with_charts() {
+        let mut report = report();
+        report.top_models[0].model = "<gpt>".to_string();
+        let html = report.render(ReportFormat::Snapshot);
+        assert!(html.contains("<style>"));
+        assert!(!html.contains("<script") && !html.contains("<link"));
+        assert_eq!(html.matches("<svg").count(), 2);
+        assert!(html.contains("<title>2026-01-02: 1.0k</title>"));
+        assert!(html.contains("<title>2026-01-01: $1.00</title>"));
+        assert!(
+            html.contains("<td>&lt;gpt&gt;</td><td class=\"bar\"><span style=\"width:100.0%\">")
+        );
+        assert!(html.find("Daily usage") < html.find("Top models"));
+        assert!(html.ends_with("</html>\n"));
+    }
+
+    #[test]
+    fn parses_period_and_format() {
+        assert_eq!(
+            ReportPeriod::parse(None).expect("default"),
+            ReportPeriod::Day
+        );
+        assert_eq!(
+            ReportPeriod::parse(Some("month")).expect("month"),
+            ReportPeriod::Month
+        );
+        assert!(ReportPeriod::parse(Some("year")).is_err());
+        assert_eq!(
+            ReportFormat::parse(Some("html")).expect("html"),
+            ReportFormat::Html
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&report().render(ReportFormat::Json)).expect("json");
+        assert_eq!(json["period"], "day");
+    }
+}
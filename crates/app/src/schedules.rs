@@ -0,0 +1,184 @@
+use std::time::Duration;
+
+use chrono::{NaiveDateTime, NaiveTime};
+use serde::Serialize;
+use tracker_core::{Schedule, UsageLimitSnapshot, UsageSummary};
+
+pub const PAYLOAD_REPORT: &str = "report";
+pub const PAYLOAD_STATUS: &str = "status";
+pub const PAYLOADS: [&str; 2] = [PAYLOAD_REPORT, PAYLOAD_STATUS];
+
+pub const TARGET_WEBHOOK: &str = "webhook";
+pub const TARGET_EMAIL: &str = "email";
+pub const TARGETS: [&str; 2] = [TARGET_WEBHOOK, TARGET_EMAIL];
+
+/// `event` of the JSON body posted to webhook targets.
+pub const EVENT_SCHEDULE_REPORT: &str = "schedule.report";
+pub const EVENT_SCHEDULE_STATUS: &str = "schedule.status";
+
+/// How often the runner checks for due schedules.
+pub const TICK: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Today's usage and the latest 5h / 7d limit readings.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusSnapshot {
+    /// Local date (`YYYY-MM-DD`) the usage is for.
+    pub date: String,
+    pub usage: UsageSummary,
+    pub limits: Vec<UsageLimitSnapshot>,
+}
+
+impl StatusSnapshot {
+    pub fn to_text(&self) -> String {
+        let cost = self
+            .usage
+            .total_cost_usd
+            .map_or_else(|| "n/a".to_string(), |cost| format!("${cost:.2}"));
+        let mut lines = vec![
+            format!("Codex status for {}", self.date),
+            format!("Cost: {cost}"),
+            format!("Tokens: {}", self.usage.total_tokens),
+        ];
+        for limit in &self.limits {
+            lines.push(format!(
+                "{}: {:.0}% left, resets {}",
+                limit.limit_type, limit.percent_left, limit.reset_at
+            ));
+        }
+        lines.join("\n") + "\n"
+    }
+}
+
+/// Parses a schedule's `HH:MM` local time.
+pub fn parse_local_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+/// Whether an enabled schedule's time has passed on `now`'s date and it has
+/// not run that day yet.
+pub fn is_due(schedule: &Schedule, now: NaiveDateTime) -> bool {
+    let today = now.date().format("%Y-%m-%d").to_string();
+    schedule.enabled
+        && schedule.last_sent_on.as_deref() != Some(today.as_str())
+        && parse_local_time(&schedule.local_time).is_some_and(|time| now.time() >= time)
+}
+
+/// Posts the body once; a missed run is reported, not retried.
+pub(crate) fn post_json(url: &str, event: &str, body: &str) -> Result<(), String> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(REQUEST_TIMEOUT))
+        .http_status_as_error(false)
+        .build()
+        .into();
+    let response = agent
+        .post(url)
+        .header("content-type", "application/json")
+        .header(
+            "user-agent",
+            concat!("codex-tracker/", env!("CARGO_PKG_VERSION")),
+        )
+        .header(crate::webhooks::EVENT_HEADER, event)
+        .send(body)
+        .map_err(|err| err.to_string())?;
+    let status = response.status().as_u16();
+    if (200..300).contains(&status) {
+        Ok(())
+    } else {
+        Err(format!("HTTP {status}"))
+    }
+}
+
+/// Sends a plain-text email through the SMTP server at `smtp_url`
+/// (`smtp[s]://user:password@host[:port]`).
+#[cfg(feature = "email")]
+pub(crate) fn send_email(
+    smtp_url: &str,
+    from: &str,
+    to: &str,
+    subject: &str,
+    text: String,
+) -> Result<(), String> {
+    use lettre::message::{Mailbox, header::ContentType};
+    use lettre::{Message, SmtpTransport, Transport};
+
+    let mailbox = |value: &str| {
+        value
+            .parse::<Mailbox>()
+            .map_err(|err| format!("invalid address {value}: {err}"))
+    };
+    let message = Message::builder()
+        .from(mailbox(from)?)
+        .to(mailbox(to)?)
+        .subject(subject)
+        .header(ContentType::TEXT_PLAIN)
+        .body(text)
+        .map_err(|err| err.to_string())?;
+    let mailer = SmtpTransport::from_url(smtp_url)
+        .map_err(|err| err.to_string())?
+        .timeout(Some(REQUEST_TIMEOUT))
+        .build();
+    mailer.send(&message).map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+#[cfg(not(feature = "email"))]
+pub(crate) fn send_email(
+    _smtp_url: &str,
+    _from: &str,
+    _to: &str,
+    _subject: &str,
+    _text: String,
+) -> Result<(), String> {
+    Err("this build has no email support".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(local_time: &str, last_sent_on: Option<&str>) -> Schedule {
+        Schedule {
+            id: 1,
+            payload: PAYLOAD_STATUS.to_string(),
+            local_time: local_time.to_string(),
+            target: TARGET_WEBHOOK.to_string(),
+            destination: "http://127.0.0.1:9/hook".to_string(),
+            smtp_url: None,
+            email_from: None,
+            enabled: true,
+            last_sent_on: last_sent_on.map(str::to_string),
+            last_error: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    fn at(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").expect("time")
+    }
+
+    #[test]
+    fn parses_hours_and_minutes_only() {
+        assert_eq!(parse_local_time("07:30"), NaiveTime::from_hms_opt(7, 30, 0));
+        assert_eq!(parse_local_time("24:00"), None);
+        assert_eq!(parse_local_time("7am"), None);
+    }
+
+    #[test]
+    fn due_once_a_day_after_the_local_time() {
+        assert!(!is_due(&schedule("08:00", None), at("2026-03-02 07:59")));
+        assert!(is_due(&schedule("08:00", None), at("2026-03-02 08:00")));
+        assert!(is_due(
+            &schedule("08:00", Some("2026-03-01")),
+            at("2026-03-02 23:10")
+        ));
+        assert!(!is_due(
+            &schedule("08:00", Some("2026-03-02")),
+            at("2026-03-02 23:10")
+        ));
+
+        let mut disabled = schedule("08:00", None);
+        disabled.enabled = false;
+        assert!(!is_due(&disabled, at("2026-03-02 09:00")));
+    }
+}
@@ -1,103 +1,475 @@
-use chrono::{Duration, SecondsFormat, Utc};
+use std::collections::BTreeMap;
 
-use crate::error::Result;
-use crate::services::{SharedConfig, open_db, require_active_home};
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, MappedLocalTime, NaiveDate, NaiveDateTime,
+    SecondsFormat, TimeZone, Timelike, Utc,
+};
+
+use crate::error::{AppError, Result};
+use crate::report::{DailyUsage, REPORT_TOP_N, Report, ReportPeriod};
+use crate::services::{
+    AnalyticsCache, CacheKey, SharedConfig, open_db_read_only, resolve_home, resolve_scope,
+    settings,
+};
+use crate::util::time::preceding_range;
 use tracker_core::{
-    ActiveSession, ContextPressureStats, ContextStatus, ModelBreakdown, ModelCostBreakdown,
-    ModelEffortCostBreakdown, ModelEffortTokenBreakdown, ModelTokenBreakdown, TimeRange,
-    TimeSeriesPoint, UsageEvent, UsageSummary,
+    ActiveSession, ActivityReport, ActivitySegment, CompactionReport, ContextPressureStats,
+    ContextStatus, DailyActivity, DailyCompactions, EventTotals, GroupedTimeSeries, HomeBreakdown,
+    HomeScope, ModelBreakdown, ModelCostBreakdown, ModelEffortCostBreakdown,
+    ModelEffortTokenBreakdown, ModelTokenBreakdown, PeriodAverage, ProviderBreakdown,
+    RangeComparison, RequestBreakdown, SessionActivity, SessionBreakdown, SessionCompactions,
+    SessionContextPeak, SessionLifetimeReport, SessionSpan, SessionTimeline, TimeRange,
+    TimeSeriesPoint, Timestamp, UsageEvent, UsageSummary, UserBreakdown, WeekdayHourAverages,
+    is_compaction, split_activity,
 };
-use tracker_db::{Bucket, Db, Metric};
+use tracker_db::{Bucket, Db, GroupBy, Metric};
+
+/// Gap between events after which a session counts as idle, unless the
+/// request sets one.
+pub const DEFAULT_IDLE_GAP_MINUTES: u32 = 30;
+
+/// Panel parameters for [`AnalyticsService::dashboard`] besides the range.
+#[derive(Debug, Clone)]
+pub struct DashboardQuery {
+    pub bucket: Bucket,
+    /// Minutes east of UTC to bucket in; this machine's time zone by default.
+    pub utc_offset_minutes: Option<i32>,
+    /// Filters the events page and its totals.
+    pub model: Option<String>,
+    pub events_limit: u32,
+    /// Window for active sessions; the stored setting by default.
+    pub active_minutes: Option<u32>,
+}
+
+/// The dashboard's usage panels, all read from the same snapshot.
+#[derive(Debug, Clone)]
+pub struct Dashboard {
+    pub summary: UsageSummary,
+    pub tokens_series: Vec<TimeSeriesPoint>,
+    pub cost_series: Vec<TimeSeriesPoint>,
+    pub breakdown: Vec<ModelCostBreakdown>,
+    pub effort_breakdown: Vec<ModelEffortCostBreakdown>,
+    pub context_stats: ContextPressureStats,
+    pub events: Vec<UsageEvent>,
+    pub event_totals: EventTotals,
+    pub active_sessions: Vec<ActiveSession>,
+    pub models: Vec<String>,
+}
 
 #[derive(Clone)]
 pub struct AnalyticsService {
     config: SharedConfig,
+    cache: AnalyticsCache,
 }
 
 impl AnalyticsService {
-    pub(super) fn new(config: SharedConfig) -> Self {
-        Self { config }
+    pub(super) fn new(config: SharedConfig, cache: AnalyticsCache) -> Self {
+        Self { config, cache }
+    }
+
+    /// Serves `key` from the short-lived response cache, computing and
+    /// storing it on a miss. Ingest, pricing, and home changes clear it.
+    pub fn cached<T>(&self, key: CacheKey, compute: impl FnOnce() -> Result<T>) -> Result<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.cache.get_or_compute(key, compute)
     }
 
     fn db(&self) -> Result<Db> {
-        open_db(&self.config)
+        open_db_read_only(&self.config)
     }
 
-    pub fn summary(&self, range: &TimeRange) -> Result<UsageSummary> {
+    /// Changes whenever the data behind the requested aggregates changes.
+    pub fn fingerprint(&self, home: Option<HomeScope>) -> Result<String> {
         let mut db = self.db()?;
-        let home = require_active_home(&mut db)?;
-        Ok(db.summary(range, home.id)?)
+        let scope = resolve_scope(&mut db, home)?;
+        Ok(db.usage_fingerprint(scope)?)
     }
 
-    pub fn context_latest(&self) -> Result<Option<ContextStatus>> {
+    pub fn summary(&self, range: &TimeRange, home: Option<HomeScope>) -> Result<UsageSummary> {
         let mut db = self.db()?;
-        let home = require_active_home(&mut db)?;
+        let scope = resolve_scope(&mut db, home)?;
+        Ok(db.summary(range, scope)?)
+    }
+
+    pub fn context_latest(&self, home: Option<HomeScope>) -> Result<Option<ContextStatus>> {
+        let mut db = self.db()?;
+        let home = resolve_home(&mut db, home)?;
         Ok(db.latest_context(home.id)?)
     }
 
-    pub fn context_sessions(&self, active_minutes: Option<u32>) -> Result<Vec<ActiveSession>> {
+    pub fn context_sessions(
+        &self,
+        active_minutes: Option<u32>,
+        home: Option<HomeScope>,
+    ) -> Result<Vec<ActiveSession>> {
         let mut db = self.db()?;
-        let home = require_active_home(&mut db)?;
+        let home = resolve_home(&mut db, home)?;
         let minutes = match active_minutes {
             Some(value) => value,
             None => db.get_context_active_minutes()?,
         };
         let since = (Utc::now() - Duration::minutes(minutes as i64))
             .to_rfc3339_opts(SecondsFormat::Millis, true);
-        Ok(db.active_sessions(home.id, &since)?)
+        let rule = settings::active_session_rule(&db)?;
+        let mut sessions = db.active_sessions(home.id, &since)?;
+        sessions.retain(|session| rule.admits(session));
+        Ok(sessions)
     }
 
-    pub fn context_stats(&self, range: &TimeRange) -> Result<ContextPressureStats> {
+    pub fn context_stats(
+        &self,
+        range: &TimeRange,
+        home: Option<HomeScope>,
+    ) -> Result<ContextPressureStats> {
         let mut db = self.db()?;
-        let home = require_active_home(&mut db)?;
+        let home = resolve_home(&mut db, home)?;
         Ok(db.context_pressure_stats(range, home.id)?)
     }
 
+    /// Buckets are local to `utc_offset_minutes` (minutes east of UTC),
+    /// defaulting to this machine's time zone, DST changes included.
     pub fn timeseries(
         &self,
         range: &TimeRange,
         bucket: Bucket,
         metric: Metric,
+        home: Option<HomeScope>,
+        utc_offset_minutes: Option<i32>,
     ) -> Result<Vec<TimeSeriesPoint>> {
+        let zone = resolve_zone(utc_offset_minutes)?;
         let mut db = self.db()?;
-        let home = require_active_home(&mut db)?;
-        Ok(db.timeseries(range, bucket, metric, home.id)?)
+        let scope = resolve_scope(&mut db, home)?;
+        Ok(db.timeseries(range, bucket, metric, scope, &zone)?)
     }
 
-    pub fn breakdown(&self, range: &TimeRange) -> Result<Vec<ModelBreakdown>> {
+    /// [`Self::timeseries`] with one series per model or reasoning effort.
+    pub fn timeseries_grouped(
+        &self,
+        range: &TimeRange,
+        bucket: Bucket,
+        metric: Metric,
+        group_by: GroupBy,
+        home: Option<HomeScope>,
+        utc_offset_minutes: Option<i32>,
+    ) -> Result<Vec<GroupedTimeSeries>> {
+        let zone = resolve_zone(utc_offset_minutes)?;
         let mut db = self.db()?;
-        let home = require_active_home(&mut db)?;
-        Ok(db.breakdown_by_model(range, home.id)?)
+        let scope = resolve_scope(&mut db, home)?;
+        Ok(db.timeseries_grouped(range, bucket, metric, group_by, scope, &zone)?)
     }
 
-    pub fn breakdown_tokens(&self, range: &TimeRange) -> Result<Vec<ModelTokenBreakdown>> {
+    /// Splits each session's usage and message events into segments at
+    /// gaps longer than `idle_gap_minutes` and sums the segments' length per
+    /// session and per local day (in `utc_offset_minutes`, as for
+    /// [`Self::timeseries`]). A segment crossing midnight counts on both days.
+    pub fn activity(
+        &self,
+        range: &TimeRange,
+        idle_gap_minutes: Option<u32>,
+        home: Option<HomeScope>,
+        utc_offset_minutes: Option<i32>,
+    ) -> Result<ActivityReport> {
+        let idle_gap_minutes = idle_gap_minutes.unwrap_or(DEFAULT_IDLE_GAP_MINUTES);
+        if idle_gap_minutes == 0 {
+            return Err(AppError::InvalidInput(
+                "idle_gap_minutes must be at least 1".to_string(),
+            ));
+        }
+        let zone = resolve_zone(utc_offset_minutes)?;
+        let mut db = self.db()?;
+        let scope = resolve_scope(&mut db, home)?;
+        let times = db.session_activity_times(range, scope)?;
+        activity_report(times, idle_gap_minutes, zone)
+    }
+
+    /// Counts auto-compactions (see [`tracker_core::is_compaction`]) per
+    /// session and per local day, in `utc_offset_minutes` as for
+    /// [`Self::timeseries`]. Only drops between two events in the range count.
+    pub fn compactions(
+        &self,
+        range: &TimeRange,
+        home: Option<HomeScope>,
+        utc_offset_minutes: Option<i32>,
+    ) -> Result<CompactionReport> {
+        let zone = resolve_zone(utc_offset_minutes)?;
         let mut db = self.db()?;
-        let home = require_active_home(&mut db)?;
-        Ok(db.breakdown_by_model_tokens(range, home.id)?)
+        let scope = resolve_scope(&mut db, home)?;
+        let samples = db.session_context_samples(range, scope)?;
+        compaction_report(samples, zone)
     }
 
-    pub fn breakdown_costs(&self, range: &TimeRange) -> Result<Vec<ModelCostBreakdown>> {
+    /// How many sessions started in the range, how they ended, and how long
+    /// they ran. Without a logged shutdown, a session idle for
+    /// `idle_minutes` counts as abandoned.
+    pub fn session_lifetimes(
+        &self,
+        range: &TimeRange,
+        idle_minutes: Option<u32>,
+        home: Option<HomeScope>,
+    ) -> Result<SessionLifetimeReport> {
+        let idle_minutes = idle_minutes.unwrap_or(DEFAULT_IDLE_GAP_MINUTES);
+        if idle_minutes == 0 {
+            return Err(AppError::InvalidInput(
+                "idle_minutes must be at least 1".to_string(),
+            ));
+        }
+        let range_end = range
+            .end
+            .parse::<Timestamp>()
+            .map_err(|err| AppError::BadRange(format!("invalid range end {}: {err}", range.end)))?;
+        let mut db = self.db()?;
+        let scope = resolve_scope(&mut db, home)?;
+        let spans = db.session_spans(range, scope)?;
+        Ok(session_lifetime_report(
+            &spans,
+            idle_minutes,
+            range_end.as_datetime().min(Utc::now()),
+            Utc::now(),
+        ))
+    }
+
+    /// Hourly tokens and cost folded into averages per weekday and per hour
+    /// of the day, local to `utc_offset_minutes` as for [`Self::timeseries`].
+    pub fn weekday_hour_averages(
+        &self,
+        range: &TimeRange,
+        home: Option<HomeScope>,
+        utc_offset_minutes: Option<i32>,
+    ) -> Result<WeekdayHourAverages> {
+        let zone = resolve_zone(utc_offset_minutes)?;
+        let mut db = self.db()?;
+        let scope = resolve_scope(&mut db, home)?;
+        let tokens = db.timeseries(range, Bucket::Hour, Metric::Tokens, scope, &zone)?;
+        let costs = db.timeseries(range, Bucket::Hour, Metric::Cost, scope, &zone)?;
+        weekday_hour_averages(&tokens, &costs, range, zone)
+    }
+
+    pub fn breakdown_homes(
+        &self,
+        range: &TimeRange,
+        home: Option<HomeScope>,
+    ) -> Result<Vec<HomeBreakdown>> {
         let mut db = self.db()?;
-        let home = require_active_home(&mut db)?;
-        Ok(db.breakdown_by_model_costs(range, home.id)?)
+        let scope = resolve_scope(&mut db, home)?;
+        Ok(db.breakdown_by_home(range, scope)?)
+    }
+
+    /// Per-user totals for team mode; unlike the other breakdowns this
+    /// covers every home unless `home` narrows it.
+    pub fn breakdown_users(
+        &self,
+        range: &TimeRange,
+        home: Option<HomeScope>,
+    ) -> Result<Vec<UserBreakdown>> {
+        let mut db = self.db()?;
+        let scope = resolve_scope(&mut db, Some(home.unwrap_or(HomeScope::All)))?;
+        Ok(db.breakdown_by_user(range, scope)?)
+    }
+
+    pub fn breakdown(
+        &self,
+        range: &TimeRange,
+        home: Option<HomeScope>,
+    ) -> Result<Vec<ModelBreakdown>> {
+        let mut db = self.db()?;
+        let scope = resolve_scope(&mut db, home)?;
+        Ok(db.breakdown_by_model(range, scope)?)
+    }
+
+    pub fn breakdown_providers(
+        &self,
+        range: &TimeRange,
+        home: Option<HomeScope>,
+    ) -> Result<Vec<ProviderBreakdown>> {
+        let mut db = self.db()?;
+        let scope = resolve_scope(&mut db, home)?;
+        Ok(db.breakdown_by_provider(range, scope)?)
+    }
+
+    pub fn breakdown_tokens(
+        &self,
+        range: &TimeRange,
+        home: Option<HomeScope>,
+    ) -> Result<Vec<ModelTokenBreakdown>> {
+        let mut db = self.db()?;
+        let scope = resolve_scope(&mut db, home)?;
+        Ok(db.breakdown_by_model_tokens(range, scope)?)
+    }
+
+    pub fn breakdown_costs(
+        &self,
+        range: &TimeRange,
+        home: Option<HomeScope>,
+    ) -> Result<Vec<ModelCostBreakdown>> {
+        let mut db = self.db()?;
+        let scope = resolve_scope(&mut db, home)?;
+        Ok(db.breakdown_by_model_costs(range, scope)?)
     }
 
     pub fn breakdown_effort_tokens(
         &self,
         range: &TimeRange,
+        home: Option<HomeScope>,
     ) -> Result<Vec<ModelEffortTokenBreakdown>> {
         let mut db = self.db()?;
-        let home = require_active_home(&mut db)?;
-        Ok(db.breakdown_by_model_effort_tokens(range, home.id)?)
+        let scope = resolve_scope(&mut db, home)?;
+        Ok(db.breakdown_by_model_effort_tokens(range, scope)?)
     }
 
     pub fn breakdown_effort_costs(
         &self,
         range: &TimeRange,
+        home: Option<HomeScope>,
     ) -> Result<Vec<ModelEffortCostBreakdown>> {
         let mut db = self.db()?;
-        let home = require_active_home(&mut db)?;
-        Ok(db.breakdown_by_model_effort_costs(range, home.id)?)
+        let scope = resolve_scope(&mut db, home)?;
+        Ok(db.breakdown_by_model_effort_costs(range, scope)?)
+    }
+
+    /// Distinct models seen in the range, for filter dropdowns.
+    pub fn models(&self, range: &TimeRange, home: Option<HomeScope>) -> Result<Vec<String>> {
+        let mut db = self.db()?;
+        let scope = resolve_scope(&mut db, home)?;
+        Ok(db.distinct_models(range, scope)?)
+    }
+
+    /// Distinct reasoning efforts seen in the range.
+    pub fn efforts(&self, range: &TimeRange, home: Option<HomeScope>) -> Result<Vec<String>> {
+        let mut db = self.db()?;
+        let scope = resolve_scope(&mut db, home)?;
+        Ok(db.distinct_efforts(range, scope)?)
+    }
+
+    /// Distinct session log files seen in the range.
+    pub fn sources(&self, range: &TimeRange, home: Option<HomeScope>) -> Result<Vec<String>> {
+        let mut db = self.db()?;
+        let scope = resolve_scope(&mut db, home)?;
+        Ok(db.distinct_sources(range, scope)?)
+    }
+
+    /// Sessions active in the range, by total tokens.
+    pub fn sessions(
+        &self,
+        range: &TimeRange,
+        limit: usize,
+        home: Option<HomeScope>,
+    ) -> Result<Vec<SessionBreakdown>> {
+        let mut db = self.db()?;
+        let scope = resolve_scope(&mut db, home)?;
+        Ok(db.breakdown_by_session(range, scope, limit)?)
+    }
+
+    /// Sessions whose context reached `threshold_pct` percent of the window
+    /// in the range, fullest first.
+    pub fn context_peaks(
+        &self,
+        range: &TimeRange,
+        threshold_pct: f64,
+        limit: usize,
+        home: Option<HomeScope>,
+    ) -> Result<Vec<SessionContextPeak>> {
+        if !(0.0..=100.0).contains(&threshold_pct) {
+            return Err(AppError::InvalidInput(
+                "threshold_pct must be between 0 and 100".to_string(),
+            ));
+        }
+        let mut db = self.db()?;
+        let scope = resolve_scope(&mut db, home)?;
+        Ok(db.session_context_peaks(range, scope, threshold_pct, limit)?)
+    }
+
+    pub fn top_requests(
+        &self,
+        range: &TimeRange,
+        limit: usize,
+        home: Option<HomeScope>,
+    ) -> Result<Vec<RequestBreakdown>> {
+        let mut db = self.db()?;
+        let scope = resolve_scope(&mut db, home)?;
+        Ok(db.breakdown_by_request(range, scope, limit)?)
+    }
+
+    pub fn session_timeline(
+        &self,
+        session_id: &str,
+        home: Option<HomeScope>,
+    ) -> Result<SessionTimeline> {
+        let mut db = self.db()?;
+        let home = resolve_home(&mut db, home)?;
+        let timeline = db.session_timeline(home.id, session_id)?;
+        if timeline.entries.is_empty() {
+            return Err(AppError::NotFound(format!(
+                "session {session_id} not found"
+            )));
+        }
+        Ok(timeline)
+    }
+
+    /// The stored event, for working out why a row got an odd model or
+    /// context window.
+    pub fn event(&self, id: &str, home: Option<HomeScope>) -> Result<UsageEvent> {
+        let mut db = self.db()?;
+        let home = resolve_home(&mut db, home)?;
+        db.get_usage_event(home.id, id)?
+            .ok_or_else(|| AppError::NotFound(format!("event {id} not found")))
+    }
+
+    /// Digest of the period ending now, with deltas against the one before.
+    /// Limit status is only included for a single home.
+    /// Compares `range` with `baseline`, or with the window of the same
+    /// length just before it.
+    pub fn compare(
+        &self,
+        range: &TimeRange,
+        baseline: Option<&TimeRange>,
+        home: Option<HomeScope>,
+    ) -> Result<RangeComparison> {
+        let baseline = match baseline {
+            Some(baseline) => baseline.clone(),
+            None => preceding_range(range)?,
+        };
+        let mut db = self.db()?;
+        let scope = resolve_scope(&mut db, home)?;
+        Ok(db.compare_ranges(range, &baseline, scope)?)
+    }
+
+    pub fn report(&self, period: ReportPeriod, home: Option<HomeScope>) -> Result<Report> {
+        let mut db = self.db()?;
+        let scope = resolve_scope(&mut db, home)?;
+        let (range, previous_range) = period.ranges(Utc::now());
+        let mut top_models = db.breakdown_by_model(&range, scope)?;
+        top_models.truncate(REPORT_TOP_N);
+        let limits = match scope.home_id() {
+            Some(home_id) => ["5h", "7d"]
+                .into_iter()
+                .map(|limit_type| db.latest_limit_snapshot_current(home_id, limit_type))
+                .filter_map(|snapshot| snapshot.transpose())
+                .collect::<std::result::Result<Vec<_>, _>>()?,
+            None => Vec::new(),
+        };
+        let local_date = |value: &str| {
+            DateTime::parse_from_rfc3339(value)
+                .map(|time| time.with_timezone(&Local).date_naive())
+                .map_err(|err| AppError::InvalidInput(err.to_string()))
+        };
+        let daily = DailyUsage::fill(
+            local_date(&range.start)?,
+            local_date(&range.end)?,
+            &db.timeseries(&range, Bucket::Day, Metric::Tokens, scope, &Local)?,
+            &db.timeseries(&range, Bucket::Day, Metric::Cost, scope, &Local)?,
+        );
+        Ok(Report {
+            period,
+            current: db.summary(&range, scope)?,
+            previous: db.summary(&previous_range, scope)?,
+            top_models,
+            top_sessions: db.breakdown_by_session(&range, scope, REPORT_TOP_N)?,
+            limits,
+            daily,
+            range,
+        })
     }
 
     pub fn events(
@@ -106,9 +478,387 @@ impl AnalyticsService {
         model: Option<&str>,
         limit: u32,
         offset: u32,
+        home: Option<HomeScope>,
     ) -> Result<Vec<UsageEvent>> {
         let mut db = self.db()?;
-        let home = require_active_home(&mut db)?;
+        let home = resolve_home(&mut db, home)?;
         Ok(db.list_usage_events(range, model, limit, offset, home.id)?)
     }
+
+    pub fn event_totals(
+        &self,
+        range: &TimeRange,
+        model: Option<&str>,
+        home: Option<HomeScope>,
+    ) -> Result<EventTotals> {
+        let mut db = self.db()?;
+        let home = resolve_home(&mut db, home)?;
+        Ok(db.usage_event_totals(range, model, home.id)?)
+    }
+
+    /// Reads every dashboard panel in one read transaction, so the numbers
+    /// agree with each other even when an ingest commits halfway through.
+    pub fn dashboard(
+        &self,
+        range: &TimeRange,
+        query: &DashboardQuery,
+        home: Option<HomeScope>,
+    ) -> Result<Dashboard> {
+        let zone = resolve_zone(query.utc_offset_minutes)?;
+        let model = query.model.as_deref();
+        let mut db = self.db()?;
+        db.with_snapshot(|db| {
+            let home = resolve_home(db, home)?;
+            let scope = HomeScope::Home(home.id);
+            let active_minutes = match query.active_minutes {
+                Some(value) => value,
+                None => db.get_context_active_minutes()?,
+            };
+            let since = (Utc::now() - Duration::minutes(active_minutes as i64))
+                .to_rfc3339_opts(SecondsFormat::Millis, true);
+            let rule = settings::active_session_rule(db)?;
+            let mut active_sessions = db.active_sessions(home.id, &since)?;
+            active_sessions.retain(|session| rule.admits(session));
+            Ok(Dashboard {
+                summary: db.summary(range, scope)?,
+                tokens_series: db.timeseries(range, query.bucket, Metric::Tokens, scope, &zone)?,
+                cost_series: db.timeseries(range, query.bucket, Metric::Cost, scope, &zone)?,
+                breakdown: db.breakdown_by_model_costs(range, scope)?,
+                effort_breakdown: db.breakdown_by_model_effort_costs(range, scope)?,
+                context_stats: db.context_pressure_stats(range, home.id)?,
+                events: db.list_usage_events(range, model, query.events_limit, 0, home.id)?,
+                event_totals: db.usage_event_totals(range, model, home.id)?,
+                active_sessions,
+                models: db.distinct_models(range, scope)?,
+            })
+        })
+    }
+}
+
+/// Time zone buckets are taken in: an offset the caller asked for, or this
+/// machine's zone, whose offset follows DST changes within the range.
+#[derive(Debug, Clone, Copy)]
+enum BucketZone {
+    Fixed(FixedOffset),
+    Local,
+}
+
+impl TimeZone for BucketZone {
+    type Offset = FixedOffset;
+
+    fn from_offset(offset: &FixedOffset) -> Self {
+        Self::Fixed(*offset)
+    }
+
+    fn offset_from_local_date(&self, local: &NaiveDate) -> MappedLocalTime<FixedOffset> {
+        match self {
+            Self::Fixed(offset) => MappedLocalTime::Single(*offset),
+            Self::Local => Local.offset_from_local_date(local),
+        }
+    }
+
+    fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> MappedLocalTime<FixedOffset> {
+        match self {
+            Self::Fixed(offset) => MappedLocalTime::Single(*offset),
+            Self::Local => Local.offset_from_local_datetime(local),
+        }
+    }
+
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+        match self {
+            Self::Fixed(offset) => *offset,
+            Self::Local => Local.offset_from_utc_date(utc),
+        }
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+        match self {
+            Self::Fixed(offset) => *offset,
+            Self::Local => Local.offset_from_utc_datetime(utc),
+        }
+    }
+}
+
+/// Minutes east of UTC as a fixed zone, defaulting to this machine's zone.
+fn resolve_zone(minutes: Option<i32>) -> Result<BucketZone> {
+    match minutes {
+        Some(minutes) => minutes
+            .checked_mul(60)
+            .and_then(FixedOffset::east_opt)
+            .map(BucketZone::Fixed)
+            .ok_or_else(|| {
+                AppError::InvalidInput(format!("unsupported UTC offset {minutes} minutes"))
+            }),
+        None => Ok(BucketZone::Local),
+    }
+}
+
+const WEEKDAY_LABELS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Sums hourly buckets per weekday and hour, then divides by the calendar
+/// days from the first day with usage (or the range start, if later) to the
+/// range end. Counting every day keeps idle days in the average.
+fn weekday_hour_averages(
+    tokens: &[TimeSeriesPoint],
+    costs: &[TimeSeriesPoint],
+    range: &TimeRange,
+    zone: BucketZone,
+) -> Result<WeekdayHourAverages> {
+    let local = |value: &str| {
+        DateTime::parse_from_rfc3339(value)
+            .map(|time| time.with_timezone(&zone))
+            .map_err(|err| AppError::InvalidInput(format!("invalid time {value}: {err}")))
+    };
+    let mut weekday_totals = [(0.0, 0.0); 7];
+    let mut hour_totals = [(0.0, 0.0); 24];
+    let mut first_day: Option<NaiveDate> = None;
+    for (is_cost, points) in [(false, tokens), (true, costs)] {
+        for point in points {
+            let time = local(&point.bucket_start)?;
+            let day = time.date_naive();
+            first_day = Some(first_day.map_or(day, |first| first.min(day)));
+            let weekday = &mut weekday_totals[time.weekday().num_days_from_monday() as usize];
+            let hour = &mut hour_totals[time.hour() as usize];
+            let (weekday, hour) = if is_cost {
+                (&mut weekday.1, &mut hour.1)
+            } else {
+                (&mut weekday.0, &mut hour.0)
+            };
+            *weekday += point.value;
+            *hour += point.value;
+        }
+    }
+
+    let mut weekday_days = [0u64; 7];
+    let mut days = 0u64;
+    if let Some(first_day) = first_day {
+        let start = first_day.max(local(&range.start)?.date_naive());
+        // The range end is exclusive.
+        let end = (local(&range.end)? - Duration::milliseconds(1)).date_naive();
+        for day in start.iter_days().take_while(|day| *day <= end) {
+            weekday_days[day.weekday().num_days_from_monday() as usize] += 1;
+            days += 1;
+        }
+    }
+    let average = |total: f64, count: u64| {
+        if count == 0 {
+            0.0
+        } else {
+            total / count as f64
+        }
+    };
+    let weekdays = WEEKDAY_LABELS
+        .iter()
+        .zip(weekday_totals)
+        .zip(weekday_days)
+        .enumerate()
+        .map(|(index, ((label, (tokens, cost)), count))| PeriodAverage {
+            index: index as u32,
+            label: label.to_string(),
+            avg_tokens: average(tokens, count),
+            avg_cost_usd: average(cost, count),
+        })
+        .collect();
+    let hours = hour_totals
+        .iter()
+        .enumerate()
+        .map(|(hour, (tokens, cost))| PeriodAverage {
+            index: hour as u32,
+            label: format!("{hour:02}:00"),
+            avg_tokens: average(*tokens, days),
+            avg_cost_usd: average(*cost, days),
+        })
+        .collect();
+    Ok(WeekdayHourAverages {
+        days,
+        weekdays,
+        hours,
+    })
+}
+
+/// Builds the report from `(session_id, ts, context_used)` rows ordered by
+/// session and time.
+/// Lifetime stats of `spans`, oldest start first, as of `now`. Sessions per
+/// day are counted from the first start to `until`, over at least a day.
+fn session_lifetime_report(
+    spans: &[SessionSpan],
+    idle_minutes: u32,
+    until: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> SessionLifetimeReport {
+    let idle = Duration::minutes(i64::from(idle_minutes));
+    let mut report = SessionLifetimeReport {
+        idle_minutes,
+        sessions: spans.len() as u64,
+        ..SessionLifetimeReport::default()
+    };
+    let mut lengths = Vec::new();
+    for span in spans {
+        let end = match span.ended_at {
+            Some(ended_at) => {
+                report.completed += 1;
+                ended_at
+            }
+            None if now - span.last_event_at.as_datetime() >= idle => {
+                report.abandoned += 1;
+                span.last_event_at
+            }
+            None => {
+                report.open += 1;
+                continue;
+            }
+        };
+        let length = end.as_datetime() - span.started_at.as_datetime();
+        lengths.push(length.num_milliseconds() as f64 / 1000.0);
+    }
+    if !lengths.is_empty() {
+        report.avg_length_secs = Some(lengths.iter().sum::<f64>() / lengths.len() as f64);
+    }
+    if let Some(first) = spans.first() {
+        let days = (until - first.started_at.as_datetime()).num_seconds() as f64 / 86_400.0;
+        report.sessions_per_day = report.sessions as f64 / days.max(1.0);
+    }
+    report
+}
+
+fn compaction_report(
+    samples: Vec<(String, String, u64)>,
+    zone: BucketZone,
+) -> Result<CompactionReport> {
+    let mut days: BTreeMap<NaiveDate, u64> = BTreeMap::new();
+    let mut sessions: Vec<SessionCompactions> = Vec::new();
+    let mut previous: Option<(String, u64)> = None;
+    for (session_id, ts, context_used) in samples {
+        let compacted = matches!(
+            &previous,
+            Some((last_session, last_context))
+                if *last_session == session_id && is_compaction(*last_context, context_used)
+        );
+        if compacted {
+            let day = DateTime::parse_from_rfc3339(&ts)
+                .map_err(|err| AppError::InvalidInput(format!("invalid event time {ts}: {err}")))?
+                .with_timezone(&zone)
+                .date_naive();
+            *days.entry(day).or_default() += 1;
+            match sessions.last_mut() {
+                Some(session) if session.session_id == session_id => {
+                    session.compactions += 1;
+                    session.last_compacted_at = ts;
+                }
+                _ => sessions.push(SessionCompactions {
+                    session_id: session_id.clone(),
+                    compactions: 1,
+                    last_compacted_at: ts,
+                }),
+            }
+        }
+        previous = Some((session_id, context_used));
+    }
+    sessions.sort_by(|a, b| {
+        b.compactions
+            .cmp(&a.compactions)
+            .then_with(|| a.session_id.cmp(&b.session_id))
+    });
+    Ok(CompactionReport {
+        total: sessions.iter().map(|session| session.compactions).sum(),
+        days: days
+            .into_iter()
+            .map(|(day, compactions)| DailyCompactions {
+                day: day.format("%Y-%m-%d").to_string(),
+                compactions,
+            })
+            .collect(),
+        sessions,
+    })
+}
+
+/// Builds the report from `(session_id, ts)` pairs ordered by session and
+/// time.
+fn activity_report(
+    times: Vec<(String, String)>,
+    idle_gap_minutes: u32,
+    zone: BucketZone,
+) -> Result<ActivityReport> {
+    let timestamp = |ts: i64| {
+        DateTime::from_timestamp(ts, 0)
+            .unwrap_or_default()
+            .to_rfc3339_opts(SecondsFormat::Millis, true)
+    };
+    let mut by_session: Vec<(String, Vec<i64>)> = Vec::new();
+    for (session_id, ts) in times {
+        let time = DateTime::parse_from_rfc3339(&ts)
+            .map_err(|err| AppError::InvalidInput(format!("invalid event time {ts}: {err}")))?
+            .timestamp();
+        match by_session.last_mut() {
+            Some((current, session_times)) if *current == session_id => session_times.push(time),
+            _ => by_session.push((session_id, vec![time])),
+        }
+    }
+
+    let mut days: BTreeMap<NaiveDate, (u64, u64)> = BTreeMap::new();
+    let mut sessions = Vec::new();
+    for (session_id, mut session_times) in by_session {
+        // Usage and message events are merged as text; their timestamps
+        // may differ in precision.
+        session_times.sort_unstable();
+        let spans = split_activity(&session_times, i64::from(idle_gap_minutes) * 60);
+        let mut session_days = Vec::new();
+        for span in &spans {
+            let mut start = span.start;
+            loop {
+                let day = zone
+                    .timestamp_opt(start, 0)
+                    .single()
+                    .map(|time| time.date_naive())
+                    .unwrap_or_default();
+                let next_midnight = day
+                    .succ_opt()
+                    .and_then(|next| next.and_hms_opt(0, 0, 0))
+                    .and_then(|midnight| zone.from_local_datetime(&midnight).earliest())
+                    .map_or(i64::MAX, |midnight| midnight.timestamp());
+                let end = span.end.min(next_midnight);
+                days.entry(day).or_default().0 += (end - start) as u64;
+                if !session_days.contains(&day) {
+                    session_days.push(day);
+                }
+                if end >= span.end {
+                    break;
+                }
+                start = end;
+            }
+        }
+        for day in session_days {
+            days.entry(day).or_default().1 += 1;
+        }
+        sessions.push(SessionActivity {
+            session_id,
+            active_secs: spans.iter().map(|span| span.duration_secs()).sum(),
+            segments: spans
+                .iter()
+                .map(|span| ActivitySegment {
+                    start: timestamp(span.start),
+                    end: timestamp(span.end),
+                    event_count: span.events,
+                })
+                .collect(),
+        });
+    }
+    sessions.sort_by(|a, b| {
+        b.active_secs
+            .cmp(&a.active_secs)
+            .then_with(|| a.session_id.cmp(&b.session_id))
+    });
+    Ok(ActivityReport {
+        idle_gap_minutes,
+        total_active_secs: sessions.iter().map(|session| session.active_secs).sum(),
+        days: days
+            .into_iter()
+            .map(|(day, (active_secs, sessions))| DailyActivity {
+                day: day.format("%Y-%m-%d").to_string(),
+                active_secs,
+                sessions,
+            })
+            .collect(),
+        sessions,
+    })
 }
@@ -0,0 +1,130 @@
+use chrono::Utc;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+use crate::error::{AppError, Result};
+use crate::services::{SharedConfig, open_db};
+use tracker_core::ApiToken;
+use tracker_db::Db;
+
+const SETTING_PREFIX: &str = "api_token:";
+const TOKEN_PREFIX: &str = "ctk_";
+
+/// Bearer tokens for the HTTP API.
+///
+/// Each token lives in `app_setting` under `api_token:<id>` as
+/// `<sha256 hex> <created_at> <label>`; the plaintext is only returned once,
+/// from [`ApiTokensService::create`].
+#[derive(Clone)]
+pub struct ApiTokensService {
+    config: SharedConfig,
+}
+
+impl ApiTokensService {
+    pub(super) fn new(config: SharedConfig) -> Self {
+        Self { config }
+    }
+
+    fn db(&self) -> Result<Db> {
+        open_db(&self.config)
+    }
+
+    pub fn list(&self) -> Result<Vec<ApiToken>> {
+        let db = self.db()?;
+        Ok(db
+            .list_settings_with_prefix(SETTING_PREFIX)?
+            .into_iter()
+            .filter_map(|(key, value)| parse_record(&key, &value).map(|(token, _)| token))
+            .collect())
+    }
+
+    /// Creates a token and returns it with its plaintext value.
+    pub fn create(&self, label: Option<&str>) -> Result<(ApiToken, String)> {
+        let id = random_hex(4);
+        let secret = format!("{TOKEN_PREFIX}{id}_{}", random_hex(16));
+        let label = label
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .unwrap_or("API token")
+            .replace(['\n', '\r'], " ");
+        let created_at = Utc::now().to_rfc3339();
+        let db = self.db()?;
+        db.set_setting(
+            &format!("{SETTING_PREFIX}{id}"),
+            &format!("{} {} {}", hash_token(&secret), created_at, label),
+        )?;
+        Ok((
+            ApiToken {
+                id,
+                label,
+                created_at,
+            },
+            secret,
+        ))
+    }
+
+    pub fn revoke(&self, id: &str) -> Result<()> {
+        let db = self.db()?;
+        if db.delete_setting(&format!("{SETTING_PREFIX}{id}"))? == 0 {
+            return Err(AppError::NotFound("api token not found".to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn has_tokens(&self) -> Result<bool> {
+        Ok(!self.list()?.is_empty())
+    }
+
+    /// Checks a presented bearer token against the stored hashes.
+    pub fn verify(&self, token: &str) -> Result<bool> {
+        let Some(id) = token
+            .strip_prefix(TOKEN_PREFIX)
+            .and_then(|rest| rest.split_once('_'))
+            .map(|(id, _)| id)
+        else {
+            return Ok(false);
+        };
+        let db = self.db()?;
+        let key = format!("{SETTING_PREFIX}{id}");
+        let Some(value) = db.get_setting(&key)? else {
+            return Ok(false);
+        };
+        let Some((_, stored_hash)) = parse_record(&key, &value) else {
+            return Ok(false);
+        };
+        Ok(constant_time_eq(
+            stored_hash.as_bytes(),
+            hash_token(token).as_bytes(),
+        ))
+    }
+}
+
+fn parse_record(key: &str, value: &str) -> Option<(ApiToken, String)> {
+    let id = key.strip_prefix(SETTING_PREFIX)?;
+    let mut parts = value.splitn(3, ' ');
+    let hash = parts.next()?.to_string();
+    let created_at = parts.next()?.to_string();
+    let label = parts.next().unwrap_or_default().to_string();
+    Some((
+        ApiToken {
+            id: id.to_string(),
+            label,
+            created_at,
+        },
+        hash,
+    ))
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn random_hex(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,145 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::config::RangeParams;
+use crate::error::Result;
+use tracker_core::HomeScope;
+
+/// How long a cached aggregate is served. Writes in this process clear the
+/// cache right away; the TTL bounds how far relative ranges drift and how
+/// long writes by another process (a CLI ingest next to `serve`) go unseen.
+const TTL: Duration = Duration::from_secs(15);
+const MAX_ENTRIES: usize = 256;
+
+/// One cached aggregate: the endpoint, the range as requested (so relative
+/// ranges such as `today` share an entry), the home, and any endpoint
+/// parameters added with [`CacheKey::param`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    endpoint: &'static str,
+    range: (Option<String>, Option<String>, Option<String>),
+    home: Option<String>,
+    params: String,
+}
+
+impl CacheKey {
+    pub fn new(endpoint: &'static str, range: &RangeParams, home: Option<HomeScope>) -> Self {
+        Self {
+            endpoint,
+            range: (range.range.clone(), range.start.clone(), range.end.clone()),
+            home: home.map(|home| home.to_string()),
+            params: String::new(),
+        }
+    }
+
+    pub fn param(mut self, value: impl fmt::Debug) -> Self {
+        self.params.push_str(&format!("{value:?};"));
+        self
+    }
+}
+
+struct Entry {
+    stored_at: Instant,
+    value: Arc<dyn Any + Send + Sync>,
+}
+
+/// Small in-process TTL cache for analytics responses, shared by the
+/// services and cleared whenever usage data or pricing changes.
+#[derive(Clone, Default)]
+pub struct AnalyticsCache {
+    entries: Arc<Mutex<HashMap<CacheKey, Entry>>>,
+    /// Bumped by [`AnalyticsCache::invalidate`], so a value computed while
+    /// an ingest landed is not stored.
+    generation: Arc<AtomicU64>,
+}
+
+impl AnalyticsCache {
+    pub fn get_or_compute<T>(&self, key: CacheKey, compute: impl FnOnce() -> Result<T>) -> Result<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        if let Some(entry) = self.entries().get(&key)
+            && entry.stored_at.elapsed() < TTL
+            && let Some(value) = entry.value.downcast_ref::<T>()
+        {
+            return Ok(value.clone());
+        }
+        let generation = self.generation.load(Ordering::SeqCst);
+        let value = compute()?;
+        let mut entries = self.entries();
+        if self.generation.load(Ordering::SeqCst) == generation {
+            if entries.len() >= MAX_ENTRIES {
+                entries.retain(|_, entry| entry.stored_at.elapsed() < TTL);
+            }
+            if entries.len() >= MAX_ENTRIES {
+                entries.clear();
+            }
+            entries.insert(
+                key,
+                Entry {
+                    stored_at: Instant::now(),
+                    value: Arc::new(value.clone()),
+                },
+            );
+        }
+        Ok(value)
+    }
+
+    pub fn invalidate(&self) {
+        let mut entries = self.entries();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        entries.clear();
+    }
+
+    fn entries(&self) -> MutexGuard<'_, HashMap<CacheKey, Entry>> {
+        // Entries are replaced whole, so a panic elsewhere cannot leave one
+        // half-written.
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serves_cached_values_until_invalidated() {
+        let cache = AnalyticsCache::default();
+        let range = RangeParams {
+            range: Some("today".to_string()),
+            ..RangeParams::default()
+        };
+        let key = || CacheKey::new("summary", &range, None).param("day");
+        let mut calls = 0;
+        let mut compute = |value: u64| {
+            calls += 1;
+            Ok(value)
+        };
+        assert_eq!(
+            cache.get_or_compute(key(), || compute(1)).expect("value"),
+            1
+        );
+        assert_eq!(
+            cache.get_or_compute(key(), || compute(2)).expect("value"),
+            1
+        );
+        let other = CacheKey::new("summary", &range, Some(HomeScope::All));
+        assert_eq!(
+            cache.get_or_compute(other, || compute(3)).expect("value"),
+            3
+        );
+
+        cache.invalidate();
+        assert_eq!(
+            cache.get_or_compute(key(), || compute(4)).expect("value"),
+            4
+        );
+        assert_eq!(calls, 3);
+    }
+}
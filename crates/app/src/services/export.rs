@@ -0,0 +1,102 @@
+use std::io::Write;
+
+use chrono::{Local, Utc};
+
+use crate::ccusage::{self, CcusageKind, CcusageReport};
+use crate::error::Result;
+use crate::export::{self, RecordFormat};
+use crate::services::{SharedConfig, open_db, resolve_home, resolve_scope};
+use tracker_core::{HomeScope, TimeRange, UsageEvent};
+use tracker_db::Db;
+
+#[derive(Clone)]
+pub struct ExportService {
+    config: SharedConfig,
+}
+
+impl ExportService {
+    pub(super) fn new(config: SharedConfig) -> Self {
+        Self { config }
+    }
+
+    fn db(&self) -> Result<Db> {
+        open_db(&self.config)
+    }
+
+    /// All usage events in the range for the home (default: active), oldest
+    /// first.
+    pub fn usage_events(
+        &self,
+        range: &TimeRange,
+        home: Option<HomeScope>,
+    ) -> Result<Vec<UsageEvent>> {
+        let mut db = self.db()?;
+        let home = resolve_home(&mut db, home)?;
+        Ok(db.export_usage_events(range, home.id)?)
+    }
+
+    /// Usage in the range as a `ccusage`-compatible report; `home_id: "all"`
+    /// spans every home.
+    pub fn ccusage(
+        &self,
+        kind: CcusageKind,
+        range: &TimeRange,
+        home: Option<HomeScope>,
+    ) -> Result<CcusageReport> {
+        let mut db = self.db()?;
+        let scope = resolve_scope(&mut db, home)?;
+        let rows = db.usage_deltas(range, scope)?;
+        Ok(ccusage::build_report(kind, &rows, &Local, Utc::now()))
+    }
+
+    /// Writes the range's usage events as CSV, JSON, or JSON Lines.
+    pub fn write_usage_events<W: Write>(
+        &self,
+        range: &TimeRange,
+        home: Option<HomeScope>,
+        format: RecordFormat,
+        writer: W,
+    ) -> Result<()> {
+        let events = self.usage_events(range, home)?;
+        export::write_usage_events(&events, format, writer)
+    }
+
+    /// Writes the range's usage events as hash-chained JSON Lines and
+    /// returns the last record's hash.
+    pub fn write_chained_usage_events<W: Write>(
+        &self,
+        range: &TimeRange,
+        home: Option<HomeScope>,
+        writer: W,
+    ) -> Result<String> {
+        let events = self.usage_events(range, home)?;
+        export::write_chained_usage_events(&events, writer)
+    }
+
+    /// Writes per-day totals (local dates) for the range; `home_id: "all"`
+    /// spans every home.
+    pub fn write_daily_rollups<W: Write>(
+        &self,
+        range: &TimeRange,
+        home: Option<HomeScope>,
+        format: RecordFormat,
+        writer: W,
+    ) -> Result<()> {
+        let mut db = self.db()?;
+        let scope = resolve_scope(&mut db, home)?;
+        let rows = db.usage_deltas(range, scope)?;
+        export::write_daily_rollups(&export::daily_rollups(&rows, &Local), format, writer)
+    }
+
+    #[cfg(feature = "parquet")]
+    pub fn usage_events_parquet(
+        &self,
+        range: &TimeRange,
+        home: Option<HomeScope>,
+    ) -> Result<Vec<u8>> {
+        let events = self.usage_events(range, home)?;
+        let mut buffer = Vec::new();
+        crate::export::write_usage_events_parquet(&events, &mut buffer)?;
+        Ok(buffer)
+    }
+}
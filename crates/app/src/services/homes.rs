@@ -1,16 +1,46 @@
+use std::path::Path;
+
 use crate::error::{AppError, Result};
+use crate::events::{AppEvent, EventBus};
 use crate::services::{SharedConfig, missing_home, open_db, require_active_home};
 use tracker_core::CodexHome;
 use tracker_db::Db;
 
+/// What a directory holds, checked before it is added as a home.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HomeInspection {
+    pub exists: bool,
+    pub has_sessions_dir: bool,
+    /// Session logs ingest would read under `sessions/`.
+    pub log_files: usize,
+}
+
+impl HomeInspection {
+    /// Codex writes its rollout logs under `sessions/`; a directory without
+    /// either is most likely not a codex home.
+    pub fn looks_like_codex_home(&self) -> bool {
+        self.has_sessions_dir || self.log_files > 0
+    }
+}
+
+/// A codex home found on disk by [`HomesService::discover`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HomeCandidate {
+    pub path: String,
+    pub log_files: usize,
+    /// The home already tracking this directory, if any.
+    pub home_id: Option<i64>,
+}
+
 #[derive(Clone)]
 pub struct HomesService {
     config: SharedConfig,
+    events: EventBus,
 }
 
 impl HomesService {
-    pub(super) fn new(config: SharedConfig) -> Self {
-        Self { config }
+    pub(super) fn new(config: SharedConfig, events: EventBus) -> Self {
+        Self { config, events }
     }
 
     fn db(&self) -> Result<Db> {
@@ -27,39 +57,174 @@ impl HomesService {
         require_active_home(&mut db)
     }
 
+    /// Looks at `path` without touching the database, so a picker can warn
+    /// before a directory that is not a codex home gets added.
+    pub fn inspect(&self, path: &Path) -> HomeInspection {
+        if !path.is_dir() {
+            return HomeInspection::default();
+        }
+        HomeInspection {
+            exists: true,
+            has_sessions_dir: path.join("sessions").is_dir(),
+            log_files: ingest::log_files(path).len(),
+        }
+    }
+
+    /// Adds a home and makes it active. Adding the path of an archived home
+    /// brings that home back instead.
+    /// Codex homes with session logs in the usual locations, most logs first,
+    /// so first-run setup can offer them instead of asking for a path.
+    pub fn discover(&self) -> Result<Vec<HomeCandidate>> {
+        let homes = self.db()?.list_homes()?;
+        let tracked = |path: &Path| {
+            homes
+                .iter()
+                .find(|home| {
+                    std::fs::canonicalize(&home.path).is_ok_and(|home_path| home_path == path)
+                })
+                .map(|home| home.id)
+        };
+        Ok(ingest::discover_codex_homes()
+            .into_iter()
+            .map(|found| HomeCandidate {
+                home_id: tracked(&found.path),
+                path: found.path.to_string_lossy().to_string(),
+                log_files: found.log_files,
+            })
+            .collect())
+    }
+
     pub fn create(&self, path: &str, label: Option<&str>) -> Result<CodexHome> {
         let db = self.db()?;
-        let home = db.get_or_create_home(path, label)?;
+        let mut home = db.get_or_create_home(path, label)?;
+        if home.archived {
+            db.set_home_archived(home.id, false)?;
+            home.archived = false;
+        }
         db.set_active_home(home.id)?;
         db.update_home_last_seen(home.id)?;
+        self.events
+            .publish(AppEvent::HomeSwitched { home_id: home.id });
         Ok(home)
     }
 
     pub fn set_active(&self, id: i64) -> Result<CodexHome> {
         let db = self.db()?;
         let home = db.get_home_by_id(id)?.ok_or_else(missing_home)?;
+        if home.archived {
+            return Err(AppError::InvalidInput(
+                "home is archived; unarchive it first".to_string(),
+            ));
+        }
         db.set_active_home(home.id)?;
         db.update_home_last_seen(home.id)?;
+        self.events
+            .publish(AppEvent::HomeSwitched { home_id: home.id });
         Ok(home)
     }
 
+    pub fn rename(&self, id: i64, label: &str) -> Result<CodexHome> {
+        self.update(id, Some(label), None, None)
+    }
+
+    /// Changes the label, path, and/or user of a home, leaving
+    /// `last_seen_at` alone. Logs under a new path are ingested as new files,
+    /// so it should not hold sessions already ingested from the old one. An
+    /// empty `user` clears it.
+    pub fn update(
+        &self,
+        id: i64,
+        label: Option<&str>,
+        path: Option<&str>,
+        user: Option<&str>,
+    ) -> Result<CodexHome> {
+        let label = label.map(str::trim);
+        if label == Some("") {
+            return Err(AppError::InvalidInput("label is required".to_string()));
+        }
+        let path = path.map(str::trim);
+        if path == Some("") {
+            return Err(AppError::InvalidInput("path is required".to_string()));
+        }
+        let db = self.db()?;
+        let home = db.get_home_by_id(id)?.ok_or_else(missing_home)?;
+        if let Some(path) = path
+            && path != home.path
+        {
+            if db.get_home_by_path(path)?.is_some() {
+                return Err(AppError::InvalidInput(format!(
+                    "another home already uses {path}"
+                )));
+            }
+            db.update_home_path(id, path)?;
+        }
+        if let Some(label) = label {
+            db.rename_home(id, label)?;
+        }
+        if let Some(user) = user.map(str::trim) {
+            db.set_home_user(id, (!user.is_empty()).then_some(user))?;
+            self.events.publish(AppEvent::UsageChanged);
+        }
+        db.get_home_by_id(id)?.ok_or_else(missing_home)
+    }
+
     pub fn delete(&self, id: i64) -> Result<()> {
         let mut db = self.db()?;
         let active = require_active_home(&mut db)?;
         if active.id == id {
-            let homes = db.list_homes()?;
-            let replacement = homes
-                .into_iter()
-                .find(|home| home.id != id)
+            let replacement = replacement_home(&db, id)?
                 .ok_or_else(|| AppError::InvalidInput("cannot delete the last home".to_string()))?;
             db.set_active_home(replacement.id)?;
+            self.events.publish(AppEvent::HomeSwitched {
+                home_id: replacement.id,
+            });
+        }
+        db.delete_home(id)?;
+        self.events.publish(AppEvent::UsageChanged);
+        Ok(())
+    }
+
+    /// Hides a home from pickers and ingest while keeping its usage, which
+    /// still counts when querying all homes. The active home hands over to
+    /// another one first.
+    pub fn archive(&self, id: i64) -> Result<CodexHome> {
+        let mut db = self.db()?;
+        db.get_home_by_id(id)?.ok_or_else(missing_home)?;
+        let active = require_active_home(&mut db)?;
+        if active.id == id {
+            let replacement = replacement_home(&db, id)?.ok_or_else(|| {
+                AppError::InvalidInput("cannot archive the last home".to_string())
+            })?;
+            db.set_active_home(replacement.id)?;
+            self.events.publish(AppEvent::HomeSwitched {
+                home_id: replacement.id,
+            });
         }
-        Ok(db.delete_home(id)?)
+        db.set_home_archived(id, true)?;
+        db.get_home_by_id(id)?.ok_or_else(missing_home)
+    }
+
+    pub fn unarchive(&self, id: i64) -> Result<CodexHome> {
+        let db = self.db()?;
+        db.get_home_by_id(id)?.ok_or_else(missing_home)?;
+        db.set_home_archived(id, false)?;
+        db.get_home_by_id(id)?.ok_or_else(missing_home)
     }
 
     pub fn clear_data(&self, id: i64) -> Result<()> {
         let mut db = self.db()?;
         db.get_home_by_id(id)?.ok_or_else(missing_home)?;
-        Ok(db.clear_home_data(id)?)
+        db.clear_home_data(id)?;
+        self.events.publish(AppEvent::UsageChanged);
+        Ok(())
     }
 }
+
+/// The home that becomes active when `id` is deleted or archived: the oldest
+/// other home that is not archived.
+fn replacement_home(db: &Db, id: i64) -> Result<Option<CodexHome>> {
+    Ok(db
+        .list_homes()?
+        .into_iter()
+        .find(|home| home.id != id && !home.archived))
+}
@@ -1,27 +1,259 @@
-use std::path::Path;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
-use crate::error::Result;
-use crate::services::{SharedConfig, open_db, require_active_home};
+use chrono::{DateTime, Duration, Local};
+
+use serde_json::json;
+
+use crate::alerts::{self, Alert};
+use crate::ccusage;
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, EventBus};
+use crate::notifications::Message;
+use crate::services::{
+    NotificationsService, SharedConfig, WebhooksService, missing_home, open_db, require_active_home,
+};
+use crate::webhooks::{EVENT_BUDGET_EXCEEDED, EVENT_INGEST_COMPLETED, EVENT_LIMIT_THRESHOLD};
 use ingest::IngestStats;
-use tracker_db::Db;
+use tracker_core::{TimeRange, Timestamp};
+use tracker_db::{Db, IngestCursor};
+
+/// Outcome of [`IngestService::import_ccusage`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportStats {
+    pub imported: usize,
+    /// Already imported by an earlier run.
+    pub duplicates: usize,
+    /// On days the home already has ingested usage for.
+    pub overlapping: usize,
+}
+
+/// Which codex home a one-off ingest reads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IngestTarget {
+    Active,
+    Home(i64),
+    /// A codex home directory; registered as a home if it is new.
+    Path(PathBuf),
+}
 
 #[derive(Clone)]
 pub struct IngestService {
     config: SharedConfig,
+    webhooks: WebhooksService,
+    notifications: NotificationsService,
+    events: EventBus,
 }
 
 impl IngestService {
-    pub(super) fn new(config: SharedConfig) -> Self {
-        Self { config }
+    pub(super) fn new(
+        config: SharedConfig,
+        webhooks: WebhooksService,
+        notifications: NotificationsService,
+        events: EventBus,
+    ) -> Self {
+        Self {
+            config,
+            webhooks,
+            notifications,
+            events,
+        }
+    }
+
+    fn finished(&self, home_id: Option<i64>, stats: &IngestStats) {
+        self.events.publish(AppEvent::IngestFinished {
+            home_id,
+            events_inserted: stats.events_inserted,
+            rows_inserted: stats.rows_inserted(),
+        });
     }
 
     fn db(&self) -> Result<Db> {
         open_db(&self.config)
     }
 
+    /// Ingests the active home and raises webhooks and notifications. Does
+    /// nothing while tracking is paused, so schedulers and refreshes before
+    /// a command can call it unconditionally.
     pub fn run(&self) -> Result<IngestStats> {
         let mut db = self.db()?;
+        if db.get_tracking_paused()? {
+            tracing::debug!("tracking is paused; skipping ingest");
+            return Ok(IngestStats::default());
+        }
         let home = require_active_home(&mut db)?;
-        Ok(ingest::ingest_codex_home(&mut db, Path::new(&home.path))?)
+        let has_webhooks = self.webhooks.has_subscribers()?;
+        let has_channels = self.notifications.has_channels()?;
+        let before = if has_webhooks || has_channels {
+            Some(alerts::capture(&db, &home)?)
+        } else {
+            None
+        };
+        let stats = ingest::ingest_codex_home(&mut db, Path::new(&home.path))?;
+        self.finished(Some(home.id), &stats);
+
+        if let Some(before) = before {
+            let after = alerts::capture(&db, &home)?;
+            if has_webhooks {
+                self.webhooks.dispatch(
+                    EVENT_INGEST_COMPLETED,
+                    json!({ "home_id": home.id, "stats": &stats }),
+                )?;
+            }
+            for alert in alerts::crossed(&before, &after) {
+                if has_channels {
+                    self.notifications.notify(Message::alert(&alert))?;
+                }
+                if has_webhooks {
+                    let event_type = match alert {
+                        Alert::LimitThreshold { .. } => EVENT_LIMIT_THRESHOLD,
+                        Alert::BudgetExceeded { .. } => EVENT_BUDGET_EXCEEDED,
+                        // Not raised by `crossed`; desktop notifications only.
+                        Alert::BudgetThreshold { .. } => continue,
+                    };
+                    self.webhooks
+                        .dispatch(event_type, json!({ "home_id": home.id, "alert": alert }))?;
+                }
+            }
+        }
+        if has_channels && let Err(err) = self.notifications.send_daily_summary_if_due(&db, &home) {
+            tracing::warn!(error = %err, "failed to send daily summary");
+        }
+        Ok(stats)
+    }
+
+    /// Ingests log files picked outside any codex home, such as ones dropped on
+    /// the desktop window, into the active home. Like `run_once`, it skips
+    /// webhooks and notifications: the files are usually old sessions.
+    pub fn ingest_files(&self, files: &[PathBuf]) -> Result<IngestStats> {
+        let mut db = self.db()?;
+        require_tracking(&db)?;
+        let home = require_active_home(&mut db)?;
+        let stats = ingest::ingest_log_files(&mut db, Path::new(&home.path), files)?;
+        self.finished(Some(home.id), &stats);
+        Ok(stats)
+    }
+
+    /// Imports a ccusage daily or session report into one home (the active
+    /// one by default). Days that already have usage ingested from Codex logs
+    /// are skipped, so the import only fills in history the logs no longer
+    /// cover and nothing is counted twice.
+    pub fn import_ccusage(&self, contents: &str, home: Option<i64>) -> Result<ImportStats> {
+        let events = ccusage::import_events(contents, &Local)?;
+        let mut db = self.db()?;
+        let home = match home {
+            Some(id) => db.get_home_by_id(id)?.ok_or_else(missing_home)?,
+            None => require_active_home(&mut db)?,
+        };
+        let (Some(start), Some(end)) = (
+            events.iter().map(|event| event.ts).min(),
+            events.iter().map(|event| event.ts).max(),
+        ) else {
+            return Ok(ImportStats::default());
+        };
+        let range = TimeRange {
+            start: start.to_string(),
+            end: Timestamp::from(end.as_datetime() + Duration::days(1)).to_string(),
+        };
+        let local_date = |ts: &str| {
+            DateTime::parse_from_rfc3339(ts)
+                .ok()
+                .map(|ts| ts.with_timezone(&Local).date_naive())
+        };
+        let ingested_days: HashSet<_> = db
+            .usage_deltas(&range, home.id)?
+            .iter()
+            .filter(|row| !row.source.starts_with(ccusage::IMPORT_SOURCE_PREFIX))
+            .filter_map(|row| local_date(&row.ts))
+            .collect();
+        let (overlapping, events): (Vec<_>, Vec<_>) = events.into_iter().partition(|event| {
+            ingested_days.contains(&event.ts.as_datetime().with_timezone(&Local).date_naive())
+        });
+        let imported = db.insert_usage_events(home.id, &events)?;
+        self.finished(
+            Some(home.id),
+            &IngestStats {
+                events_inserted: imported,
+                ..IngestStats::default()
+            },
+        );
+        Ok(ImportStats {
+            imported,
+            duplicates: events.len() - imported,
+            overlapping: overlapping.len(),
+        })
+    }
+
+    /// Cursors of one home (the active one by default), by file path.
+    pub fn cursors(&self, home: Option<i64>) -> Result<Vec<IngestCursor>> {
+        let mut db = self.db()?;
+        let home = match home {
+            Some(id) => db.get_home_by_id(id)?.ok_or_else(missing_home)?,
+            None => require_active_home(&mut db)?,
+        };
+        Ok(db.list_cursors(home.id)?)
+    }
+
+    /// Drops the cursor of `file_path`, or of every file in the home, so the
+    /// next ingest re-reads them, e.g. after editing the database by hand.
+    /// Returns how many cursors were removed.
+    pub fn reset_cursors(&self, home: Option<i64>, file_path: Option<&str>) -> Result<usize> {
+        let mut db = self.db()?;
+        let home = match home {
+            Some(id) => db.get_home_by_id(id)?.ok_or_else(missing_home)?,
+            None => require_active_home(&mut db)?,
+        };
+        Ok(db.reset_cursors(home.id, file_path)?)
+    }
+
+    /// Ingests one home on demand. A dry run ingests into a throwaway copy of
+    /// the database, so the stats are exact but nothing is persisted. Only a
+    /// real run of the active home triggers webhooks and notifications.
+    pub fn run_once(&self, target: IngestTarget, dry_run: bool) -> Result<IngestStats> {
+        let mut db = self.db()?;
+        if !dry_run {
+            require_tracking(&db)?;
+        }
+        if target == IngestTarget::Active && !dry_run {
+            return self.run();
+        }
+        let (home_id, path) = match target {
+            IngestTarget::Active => {
+                let home = require_active_home(&mut db)?;
+                (Some(home.id), PathBuf::from(home.path))
+            }
+            IngestTarget::Home(id) => {
+                let home = db.get_home_by_id(id)?.ok_or_else(missing_home)?;
+                if home.archived {
+                    return Err(AppError::InvalidInput(
+                        "home is archived; unarchive it to ingest".to_string(),
+                    ));
+                }
+                (Some(home.id), PathBuf::from(home.path))
+            }
+            IngestTarget::Path(path) => (None, path),
+        };
+        if !dry_run {
+            let stats = ingest::ingest_codex_home(&mut db, &path)?;
+            self.finished(home_id, &stats);
+            return Ok(stats);
+        }
+        let scratch = tempfile::tempdir()?;
+        let copy = scratch.path().join("dry-run.sqlite");
+        db.copy_to(&copy)?;
+        drop(db);
+        let mut copy = Db::open(&copy)?;
+        Ok(ingest::ingest_codex_home(&mut copy, &path)?)
+    }
+}
+
+/// Explicit ingests fail while tracking is paused rather than silently
+/// recording nothing.
+fn require_tracking(db: &Db) -> Result<()> {
+    if db.get_tracking_paused()? {
+        return Err(AppError::InvalidInput(
+            "tracking is paused; resume it to ingest".to_string(),
+        ));
     }
+    Ok(())
 }
@@ -1,8 +1,21 @@
-use crate::error::Result;
-use crate::services::{SharedConfig, open_db, require_active_home};
-use tracker_core::{UsageLimitCurrentResponse, UsageLimitSnapshot, UsageLimitWindow};
+use chrono::{Local, Utc};
+
+use crate::alerts::{self, AlertSnapshot};
+use crate::calendar;
+use crate::error::{AppError, Result};
+use crate::services::{SharedConfig, open_db, require_active_home, resolve_home};
+use crate::util::time::calendar_period_range;
+use tracker_core::{
+    CustomLimit, CustomLimitWindow, HomeScope, LimitThresholds, TimeRange,
+    UsageLimitCurrentResponse, UsageLimitSnapshot, UsageLimitWindow,
+};
 use tracker_db::Db;
 
+/// Calendar periods a custom limit can reset on.
+pub const CUSTOM_LIMIT_PERIODS: [&str; 2] = ["week", "month"];
+/// What a custom limit counts.
+pub const CUSTOM_LIMIT_METRICS: [&str; 2] = ["tokens", "cost_usd"];
+
 #[derive(Clone)]
 pub struct LimitsService {
     config: SharedConfig,
@@ -25,12 +38,108 @@ impl LimitsService {
         Ok((primary, secondary))
     }
 
+    /// The configured percent-used cutoffs for the `warn` and `critical`
+    /// limit levels.
+    pub fn thresholds(&self) -> Result<LimitThresholds> {
+        let db = self.db()?;
+        Ok(LimitThresholds {
+            warn_percent: f64::from(db.get_limit_warn_percent()?),
+            critical_percent: f64::from(db.get_limit_critical_percent()?),
+        })
+    }
+
     pub fn current(&self) -> Result<UsageLimitCurrentResponse> {
         let mut db = self.db()?;
         let home = require_active_home(&mut db)?;
         let primary = db.limit_current_window(home.id, "5h")?;
         let secondary = db.limit_current_window(home.id, "7d")?;
-        Ok(UsageLimitCurrentResponse { primary, secondary })
+        let now = Local::now();
+        let custom = db
+            .list_custom_limits()?
+            .into_iter()
+            .map(|limit| custom_limit_window(&db, home.id, limit, now))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(UsageLimitCurrentResponse {
+            primary,
+            secondary,
+            custom,
+        })
+    }
+
+    pub fn custom_limits(&self) -> Result<Vec<CustomLimit>> {
+        Ok(self.db()?.list_custom_limits()?)
+    }
+
+    pub fn create_custom_limit(
+        &self,
+        name: &str,
+        period: &str,
+        metric: &str,
+        amount: f64,
+    ) -> Result<CustomLimit> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::InvalidInput(
+                "custom limit name is required".to_string(),
+            ));
+        }
+        if !CUSTOM_LIMIT_PERIODS.contains(&period) {
+            return Err(AppError::InvalidInput(format!(
+                "unsupported period {period}; expected week or month"
+            )));
+        }
+        if !CUSTOM_LIMIT_METRICS.contains(&metric) {
+            return Err(AppError::InvalidInput(format!(
+                "unsupported metric {metric}; expected tokens or cost_usd"
+            )));
+        }
+        if !(amount.is_finite() && amount > 0.0) {
+            return Err(AppError::InvalidInput(
+                "custom limit amount must be greater than 0".to_string(),
+            ));
+        }
+        Ok(self
+            .db()?
+            .create_custom_limit(name, period, metric, amount)?)
+    }
+
+    pub fn delete_custom_limit(&self, id: i64) -> Result<usize> {
+        Ok(self.db()?.delete_custom_limit(id)?)
+    }
+
+    /// Current limit windows and month-to-date spend for the active home,
+    /// to evaluate alerts against outside of ingest (e.g. on a timer).
+    pub fn alert_snapshot(&self) -> Result<AlertSnapshot> {
+        let mut db = self.db()?;
+        let home = require_active_home(&mut db)?;
+        alerts::capture(&db, &home)
+    }
+
+    /// Upcoming 5h / 7d resets as an iCalendar feed.
+    pub fn resets_ics(&self) -> Result<String> {
+        let (primary, secondary) = self.latest()?;
+        let resets: Vec<_> = primary.into_iter().chain(secondary).collect();
+        Ok(calendar::limit_resets_ics(&resets, Utc::now()))
+    }
+
+    pub fn snapshots(
+        &self,
+        range: &TimeRange,
+        limit_type: Option<&str>,
+        limit: u32,
+        offset: u32,
+        home: Option<HomeScope>,
+    ) -> Result<Vec<UsageLimitSnapshot>> {
+        if let Some(value) = limit_type
+            && !matches!(value, "5h" | "7d")
+        {
+            return Err(AppError::InvalidInput(format!(
+                "unsupported limit type {value}"
+            )));
+        }
+        let mut db = self.db()?;
+        let home = resolve_home(&mut db, home)?;
+        Ok(db.list_limit_snapshots(home.id, range, limit_type, limit, offset)?)
     }
 
     pub fn windows_7d(&self, limit: usize) -> Result<Vec<UsageLimitWindow>> {
@@ -39,3 +148,27 @@ impl LimitsService {
         Ok(db.limit_windows_7d(home.id, limit)?)
     }
 }
+
+/// Usage of `home_id` against `limit` over the calendar period containing
+/// `now`.
+fn custom_limit_window(
+    db: &Db,
+    home_id: i64,
+    limit: CustomLimit,
+    now: chrono::DateTime<Local>,
+) -> Result<CustomLimitWindow> {
+    let range = calendar_period_range(&limit.period, now)?;
+    let summary = db.summary(&range, home_id)?;
+    let used = match limit.metric.as_str() {
+        "cost_usd" => summary.total_cost_usd.unwrap_or(0.0),
+        _ => summary.total_tokens as f64,
+    };
+    let percent_left = (100.0 - used / limit.amount * 100.0).clamp(0.0, 100.0);
+    Ok(CustomLimitWindow {
+        limit,
+        window_start: range.start,
+        window_end: range.end,
+        used,
+        percent_left,
+    })
+}
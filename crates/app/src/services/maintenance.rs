@@ -0,0 +1,127 @@
+use std::fs;
+use std::path::Path;
+
+use crate::audit::{self, AuditReport};
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, EventBus};
+use crate::services::{SharedConfig, missing_home, open_db, open_db_read_only, resolve_home};
+use crate::util::time::normalize_date_or_rfc3339_to_utc;
+use tracker_core::{HomeScope, TimeRange};
+use tracker_db::{Db, MergeStats, PruneStats, SCHEMA_VERSION};
+
+/// Database file size before and after [`MaintenanceService::compact`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactStats {
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+}
+
+#[derive(Clone)]
+pub struct MaintenanceService {
+    config: SharedConfig,
+    events: EventBus,
+}
+
+impl MaintenanceService {
+    pub(super) fn new(config: SharedConfig, events: EventBus) -> Self {
+        Self { config, events }
+    }
+
+    fn db(&self) -> Result<Db> {
+        open_db(&self.config)
+    }
+
+    /// Deletes usage older than `before` (a `YYYY-MM-DD` local date or an
+    /// RFC 3339 timestamp) from one home, or from every home by default.
+    pub fn prune(&self, before: &str, home: Option<HomeScope>) -> Result<PruneStats> {
+        let before = normalize_date_or_rfc3339_to_utc(before)?;
+        let mut db = self.db()?;
+        let home = match home {
+            Some(HomeScope::Home(id)) => {
+                db.get_home_by_id(id)?.ok_or_else(missing_home)?;
+                HomeScope::Home(id)
+            }
+            _ => HomeScope::All,
+        };
+        let stats = db.prune_before(&before, home)?;
+        self.events.publish(AppEvent::UsageChanged);
+        Ok(stats)
+    }
+
+    /// Imports usage, messages, and limit snapshots from another tracker
+    /// database (for example one copied from another machine), mapping its
+    /// codex homes to ours by path. The other file is only read. A `user`
+    /// marks it as a teammate's database, whose homes are labeled with
+    /// that user for per-user breakdowns.
+    pub fn merge(&self, path: &Path, user: Option<&str>) -> Result<MergeStats> {
+        let user = user.map(str::trim);
+        if user == Some("") {
+            return Err(AppError::InvalidInput("user is required".to_string()));
+        }
+        if !path.is_file() {
+            return Err(AppError::NotFound(format!(
+                "no database at {}",
+                path.display()
+            )));
+        }
+        if fs::canonicalize(&self.config.db_path).ok() == Some(fs::canonicalize(path)?) {
+            return Err(AppError::InvalidInput(
+                "cannot merge the database into itself".to_string(),
+            ));
+        }
+        // `Db::open` would switch the file to WAL mode.
+        let level = Db::open_read_only(path)
+            .and_then(|other| other.schema_level())
+            .map_err(|err| {
+                AppError::InvalidInput(format!(
+                    "{} is not a codex-tracker database: {err}",
+                    path.display()
+                ))
+            })?;
+        if level == 0 {
+            return Err(AppError::InvalidInput(format!(
+                "{} is not a codex-tracker database",
+                path.display()
+            )));
+        }
+        if level < SCHEMA_VERSION {
+            return Err(AppError::InvalidInput(format!(
+                "{} has {level} of {SCHEMA_VERSION} migrations applied; upgrade codex-tracker \
+                 where it came from and run `codex-tracker ingest` there first",
+                path.display()
+            )));
+        }
+        let stats = self.db()?.merge_from(path, user)?;
+        self.events.publish(AppEvent::UsageChanged);
+        Ok(stats)
+    }
+
+    /// Compares the range's usage re-read from the home's rollout files
+    /// (the active home by default) with the database. Nothing is written.
+    pub fn audit(&self, range: &TimeRange, home: Option<i64>) -> Result<AuditReport> {
+        let mut db = open_db_read_only(&self.config)?;
+        let home = resolve_home(&mut db, home.map(HomeScope::Home))?;
+        let db_usage = db.usage_by_source(range, home.id)?;
+        audit::audit_home(&home, range, &db_usage)
+    }
+
+    /// Runs `VACUUM` and `ANALYZE`, reporting the on-disk size (including the
+    /// WAL) before and after.
+    pub fn compact(&self) -> Result<CompactStats> {
+        let bytes_before = db_size(&self.config.db_path);
+        self.db()?.compact()?;
+        Ok(CompactStats {
+            bytes_before,
+            bytes_after: db_size(&self.config.db_path),
+        })
+    }
+}
+
+fn db_size(path: &Path) -> u64 {
+    let wal = path.with_extension("sqlite-wal");
+    [path, wal.as_path()]
+        .iter()
+        .filter_map(|path| fs::metadata(path).ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
@@ -1,23 +1,38 @@
 mod analytics;
+mod api_tokens;
+mod cache;
+mod export;
 mod homes;
 mod ingest;
 mod limits;
+mod maintenance;
+mod notifications;
 mod pricing;
+mod schedules;
 mod settings;
+mod webhooks;
 
 use std::sync::Arc;
 
 use crate::app::AppConfig;
 use crate::error::{AppError, Result};
-use tracker_core::CodexHome;
+use crate::events::EventBus;
+use tracker_core::{CodexHome, HomeScope};
 use tracker_db::Db;
 
-pub use analytics::AnalyticsService;
-pub use homes::HomesService;
-pub use ingest::IngestService;
+pub use analytics::{AnalyticsService, DEFAULT_IDLE_GAP_MINUTES, Dashboard, DashboardQuery};
+pub use api_tokens::ApiTokensService;
+pub use cache::{AnalyticsCache, CacheKey};
+pub use export::ExportService;
+pub use homes::{HomeCandidate, HomeInspection, HomesService};
+pub use ingest::{ImportStats, IngestService, IngestTarget};
 pub use limits::LimitsService;
+pub use maintenance::{CompactStats, MaintenanceService};
+pub use notifications::{NotificationSettings, NotificationsService};
 pub use pricing::PricingService;
-pub use settings::{SettingsService, SettingsSnapshot};
+pub use schedules::SchedulesService;
+pub use settings::{SettingsService, SettingsSnapshot, validate_active_session_rule};
+pub use webhooks::WebhooksService;
 
 type SharedConfig = Arc<AppConfig>;
 
@@ -25,23 +40,57 @@ type SharedConfig = Arc<AppConfig>;
 #[derive(Clone)]
 pub struct AppServices {
     pub analytics: AnalyticsService,
+    pub api_tokens: ApiTokensService,
+    pub export: ExportService,
     pub ingest: IngestService,
     pub limits: LimitsService,
+    pub maintenance: MaintenanceService,
+    pub notifications: NotificationsService,
     pub pricing: PricingService,
+    pub schedules: SchedulesService,
     pub homes: HomesService,
     pub settings: SettingsService,
+    pub webhooks: WebhooksService,
+    /// Published to by the services after writes; subscribe to follow them.
+    pub events: EventBus,
 }
 
 impl AppServices {
     pub fn new(config: &AppConfig) -> Self {
         let shared = Arc::new(config.clone());
+        let cache = AnalyticsCache::default();
+        let events = EventBus::default();
+        // Subscribed first, so costs are re-priced before the cache and
+        // frontends hear about a pricing change or an ingest.
+        pricing::subscribe_recompute(shared.clone(), &events);
+        let analytics_cache = cache.clone();
+        events.subscribe(move |event, _| {
+            if event.changes_usage() {
+                analytics_cache.invalidate();
+            }
+        });
+        let webhooks = WebhooksService::new(shared.clone());
+        let notifications = NotificationsService::new(shared.clone());
+        let analytics = AnalyticsService::new(shared.clone(), cache);
         Self {
-            analytics: AnalyticsService::new(shared.clone()),
-            ingest: IngestService::new(shared.clone()),
+            analytics: analytics.clone(),
+            api_tokens: ApiTokensService::new(shared.clone()),
+            export: ExportService::new(shared.clone()),
+            ingest: IngestService::new(
+                shared.clone(),
+                webhooks.clone(),
+                notifications.clone(),
+                events.clone(),
+            ),
             limits: LimitsService::new(shared.clone()),
-            pricing: PricingService::new(shared.clone()),
-            homes: HomesService::new(shared.clone()),
-            settings: SettingsService::new(shared),
+            maintenance: MaintenanceService::new(shared.clone(), events.clone()),
+            notifications,
+            pricing: PricingService::new(shared.clone(), events.clone()),
+            schedules: SchedulesService::new(shared.clone(), analytics),
+            homes: HomesService::new(shared.clone(), events.clone()),
+            settings: SettingsService::new(shared, events.clone()),
+            webhooks,
+            events,
         }
     }
 }
@@ -50,10 +99,41 @@ fn open_db(config: &SharedConfig) -> Result<Db> {
     Ok(Db::open(&config.db_path)?)
 }
 
+/// A read-only connection for query-only services, so they never contend
+/// with ingest for the write lock. Until a home is active, resolving it
+/// creates the default one, which needs a writable connection.
+fn open_db_read_only(config: &SharedConfig) -> Result<Db> {
+    let db = Db::open_read_only(&config.db_path)?;
+    if db.get_active_home()?.is_some() {
+        return Ok(db);
+    }
+    open_db(config)
+}
+
 fn require_active_home(db: &mut Db) -> Result<CodexHome> {
     Ok(db.ensure_active_home()?)
 }
 
+/// The single home a request targets: the explicit home when given,
+/// otherwise the active home. `all` is rejected for per-home endpoints.
+fn resolve_home(db: &mut Db, home: Option<HomeScope>) -> Result<CodexHome> {
+    match home {
+        Some(HomeScope::Home(id)) => db.get_home_by_id(id)?.ok_or_else(missing_home),
+        Some(HomeScope::All) => Err(AppError::InvalidInput(
+            "home_id \"all\" is not supported for this endpoint".to_string(),
+        )),
+        None => require_active_home(db),
+    }
+}
+
+/// Like [`resolve_home`], but lets aggregate endpoints span every home.
+fn resolve_scope(db: &mut Db, home: Option<HomeScope>) -> Result<HomeScope> {
+    match home {
+        Some(HomeScope::All) => Ok(HomeScope::All),
+        other => Ok(HomeScope::Home(resolve_home(db, other)?.id)),
+    }
+}
+
 fn missing_home() -> AppError {
-    AppError::NotFound("home not found".to_string())
+    AppError::HomeNotFound("home not found".to_string())
 }
@@ -0,0 +1,148 @@
+use std::thread;
+
+use chrono::{Duration, Local, NaiveDate, NaiveTime, SecondsFormat, TimeZone, Utc};
+
+use crate::error::{AppError, Result};
+use crate::notifications::{self, Channel, DAILY_SUMMARY_KEY, DAILY_SUMMARY_LAST_KEY, Message};
+use crate::services::{SharedConfig, open_db};
+use tracker_core::{CodexHome, TimeRange};
+use tracker_db::Db;
+
+/// Slack / Discord notification settings.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NotificationSettings {
+    pub slack_webhook_url: Option<String>,
+    pub discord_webhook_url: Option<String>,
+    pub daily_summary: bool,
+}
+
+impl NotificationSettings {
+    pub fn channels(&self) -> Vec<(Channel, &str)> {
+        let mut channels = Vec::new();
+        if let Some(url) = &self.slack_webhook_url {
+            channels.push((Channel::Slack, url.as_str()));
+        }
+        if let Some(url) = &self.discord_webhook_url {
+            channels.push((Channel::Discord, url.as_str()));
+        }
+        channels
+    }
+}
+
+#[derive(Clone)]
+pub struct NotificationsService {
+    config: SharedConfig,
+}
+
+impl NotificationsService {
+    pub(super) fn new(config: SharedConfig) -> Self {
+        Self { config }
+    }
+
+    fn db(&self) -> Result<Db> {
+        open_db(&self.config)
+    }
+
+    pub fn settings(&self) -> Result<NotificationSettings> {
+        let db = self.db()?;
+        Ok(NotificationSettings {
+            slack_webhook_url: db.get_setting(Channel::Slack.setting_key())?,
+            discord_webhook_url: db.get_setting(Channel::Discord.setting_key())?,
+            daily_summary: db.get_setting(DAILY_SUMMARY_KEY)?.as_deref() == Some("1"),
+        })
+    }
+
+    /// Stores a channel's webhook URL; an empty value removes the channel.
+    pub fn set_channel_url(&self, channel: Channel, url: &str) -> Result<()> {
+        let db = self.db()?;
+        let url = url.trim();
+        if url.is_empty() {
+            db.delete_setting(channel.setting_key())?;
+            return Ok(());
+        }
+        if !url.starts_with("https://") {
+            return Err(AppError::InvalidInput(format!(
+                "{} webhook url must start with https://",
+                channel.name()
+            )));
+        }
+        db.set_setting(channel.setting_key(), url)?;
+        Ok(())
+    }
+
+    pub fn set_daily_summary(&self, enabled: bool) -> Result<()> {
+        let db = self.db()?;
+        db.set_setting(DAILY_SUMMARY_KEY, if enabled { "1" } else { "0" })?;
+        Ok(())
+    }
+
+    pub fn has_channels(&self) -> Result<bool> {
+        Ok(!self.settings()?.channels().is_empty())
+    }
+
+    /// Sends a test message to every configured channel and waits for the
+    /// result, so setup mistakes surface immediately.
+    pub fn send_test(&self) -> Result<usize> {
+        let settings = self.settings()?;
+        let channels = settings.channels();
+        if channels.is_empty() {
+            return Err(AppError::InvalidInput(
+                "no notification channels configured".to_string(),
+            ));
+        }
+        let message = Message::test();
+        for (channel, url) in &channels {
+            notifications::send(*channel, url, &message).map_err(AppError::Message)?;
+        }
+        Ok(channels.len())
+    }
+
+    /// Sends the message to every configured channel on a background thread.
+    pub fn notify(&self, message: Message) -> Result<()> {
+        let settings = self.settings()?;
+        if settings.channels().is_empty() {
+            return Ok(());
+        }
+        thread::spawn(move || {
+            for (channel, url) in settings.channels() {
+                if let Err(err) = notifications::send(channel, url, &message) {
+                    tracing::warn!(channel = channel.name(), error = %err, "notification failed");
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Sends yesterday's summary for the home once per day, on the first
+    /// call after local midnight.
+    pub fn send_daily_summary_if_due(&self, db: &Db, home: &CodexHome) -> Result<bool> {
+        let settings = self.settings()?;
+        if !settings.daily_summary || settings.channels().is_empty() {
+            return Ok(false);
+        }
+        let today = Local::now().date_naive();
+        let yesterday = today - Duration::days(1);
+        let date = yesterday.format("%Y-%m-%d").to_string();
+        if db.get_setting(DAILY_SUMMARY_LAST_KEY)?.as_deref() == Some(date.as_str()) {
+            return Ok(false);
+        }
+        let range = TimeRange {
+            start: local_midnight_utc(yesterday)?,
+            end: local_midnight_utc(today)?,
+        };
+        let summary = db.summary(&range, home.id)?;
+        db.set_setting(DAILY_SUMMARY_LAST_KEY, &date)?;
+        self.notify(Message::daily_summary(&date, &summary))?;
+        Ok(true)
+    }
+}
+
+pub(super) fn local_midnight_utc(date: NaiveDate) -> Result<String> {
+    let midnight = Local
+        .from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .earliest()
+        .ok_or_else(|| AppError::InvalidInput("invalid local date".to_string()))?;
+    Ok(midnight
+        .with_timezone(&Utc)
+        .to_rfc3339_opts(SecondsFormat::Millis, true))
+}
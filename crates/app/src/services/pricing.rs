@@ -1,17 +1,19 @@
-use crate::error::Result;
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, EventBus};
 use crate::pricing;
 use crate::services::{SharedConfig, open_db, require_active_home};
-use tracker_core::PricingRuleInput;
+use tracker_core::{CostRecomputeProgress, ModelAlias, PricingRuleInput};
 use tracker_db::Db;
 
 #[derive(Clone)]
 pub struct PricingService {
     config: SharedConfig,
+    events: EventBus,
 }
 
 impl PricingService {
-    pub(super) fn new(config: SharedConfig) -> Self {
-        Self { config }
+    pub(super) fn new(config: SharedConfig, events: EventBus) -> Self {
+        Self { config, events }
     }
 
     fn db(&self) -> Result<Db> {
@@ -23,19 +25,110 @@ impl PricingService {
         Ok(db.list_pricing_rules()?)
     }
 
+    /// Replaces the pricing rules, which re-prices the active home's stored
+    /// costs. Providers are stored lowercase, and a blank one means the rule
+    /// prices any provider.
     pub fn replace_rules(&self, rules: &[PricingRuleInput]) -> Result<usize> {
+        let rules = rules
+            .iter()
+            .cloned()
+            .map(|mut rule| {
+                rule.provider = rule
+                    .provider
+                    .map(|provider| provider.trim().to_ascii_lowercase())
+                    .filter(|provider| !provider.is_empty());
+                rule
+            })
+            .collect::<Vec<_>>();
         let mut db = self.db()?;
-        let updated = db.replace_pricing_rules(rules)?;
-        if let Err(err) = pricing::write_pricing_defaults(&self.config.pricing_defaults_path, rules)
+        let updated = db.replace_pricing_rules(&rules)?;
+        if let Err(err) =
+            pricing::write_pricing_defaults(&self.config.pricing_defaults_path, &rules)
         {
-            eprintln!("failed to update pricing defaults: {}", err);
+            tracing::warn!(error = %err, "failed to update pricing defaults");
         }
+        self.events.publish(AppEvent::PricingChanged);
         Ok(updated)
     }
 
-    pub fn recompute_costs(&self) -> Result<usize> {
+    pub fn list_aliases(&self) -> Result<Vec<ModelAlias>> {
+        let db = self.db()?;
+        Ok(db.list_model_aliases()?)
+    }
+
+    /// Replaces the alias table and recomputes the active home's stored
+    /// costs, since aliases change which pricing rule an event matches.
+    pub fn replace_aliases(&self, aliases: &[ModelAlias]) -> Result<usize> {
+        for alias in aliases {
+            if alias.alias.trim().is_empty() || alias.model.trim().is_empty() {
+                return Err(AppError::InvalidInput(
+                    "model aliases need an alias and a model".to_string(),
+                ));
+            }
+            if alias.alias == alias.model {
+                return Err(AppError::InvalidInput(format!(
+                    "model alias {} maps to itself",
+                    alias.alias
+                )));
+            }
+        }
+        let saved = self.db()?.replace_model_aliases(aliases)?;
+        self.events.publish(AppEvent::PricingChanged);
+        Ok(saved)
+    }
+
+    /// Re-prices the active home's events in chunks, continuing an earlier
+    /// run that was interrupted. Returns the events updated by this call and
+    /// the progress afterwards.
+    pub fn recompute_costs(&self) -> Result<(usize, CostRecomputeProgress)> {
         let mut db = self.db()?;
         let home = require_active_home(&mut db)?;
-        Ok(db.update_event_costs(home.id)?)
+        let recomputed = db.resume_event_costs(home.id)?;
+        self.events
+            .publish(AppEvent::CostsRecomputed { home_id: home.id });
+        Ok(recomputed)
+    }
+
+    /// Progress of the running or most recent cost recompute.
+    pub fn recompute_progress(&self) -> Result<Option<CostRecomputeProgress>> {
+        Ok(self.db()?.cost_recompute_progress()?)
+    }
+}
+
+/// Keeps stored costs in line with the prices: a pricing change re-prices
+/// the active home from scratch, and an ingest finishes a recompute that was
+/// interrupted, so no caller has to run one afterwards.
+pub(super) fn subscribe_recompute(config: SharedConfig, events: &EventBus) {
+    events.subscribe(move |event, events| {
+        let recomputed = match event {
+            AppEvent::PricingChanged => recompute_active_home(&config).map(Some),
+            AppEvent::IngestFinished { .. } => resume_interrupted_recompute(&config),
+            _ => return,
+        };
+        match recomputed {
+            Ok(Some(home_id)) => events.publish(AppEvent::CostsRecomputed { home_id }),
+            Ok(None) => {}
+            Err(err) => tracing::warn!(error = %err, ?event, "failed to recompute costs"),
+        }
+    });
+}
+
+fn recompute_active_home(config: &SharedConfig) -> Result<i64> {
+    let mut db = open_db(config)?;
+    let home = require_active_home(&mut db)?;
+    db.update_event_costs(home.id)?;
+    Ok(home.id)
+}
+
+/// The active home's id when an interrupted recompute of it was finished.
+fn resume_interrupted_recompute(config: &SharedConfig) -> Result<Option<i64>> {
+    let mut db = open_db(config)?;
+    let home = require_active_home(&mut db)?;
+    match db.cost_recompute_progress()? {
+        Some(progress) if !progress.complete && progress.codex_home_id == home.id => {
+            db.resume_event_costs(home.id)?;
+            Ok(Some(home.id))
+        }
+        _ => Ok(None),
     }
 }
@@ -0,0 +1,227 @@
+use std::thread::{self, JoinHandle};
+
+use chrono::{Local, NaiveDate, SecondsFormat, Utc};
+use serde_json::{Value, json};
+
+use crate::error::{AppError, Result};
+use crate::report::{ReportFormat, ReportPeriod};
+use crate::schedules::{
+    self, EVENT_SCHEDULE_REPORT, EVENT_SCHEDULE_STATUS, PAYLOAD_REPORT, PAYLOADS, StatusSnapshot,
+    TARGET_EMAIL, TARGET_WEBHOOK, TARGETS,
+};
+use crate::services::notifications::local_midnight_utc;
+use crate::services::{AnalyticsService, SharedConfig, open_db, require_active_home};
+use tracker_core::{Schedule, ScheduleInput, TimeRange};
+use tracker_db::Db;
+
+#[derive(Clone)]
+pub struct SchedulesService {
+    config: SharedConfig,
+    analytics: AnalyticsService,
+}
+
+impl SchedulesService {
+    pub(super) fn new(config: SharedConfig, analytics: AnalyticsService) -> Self {
+        Self { config, analytics }
+    }
+
+    fn db(&self) -> Result<Db> {
+        open_db(&self.config)
+    }
+
+    pub fn list(&self) -> Result<Vec<Schedule>> {
+        Ok(self.db()?.list_schedules()?)
+    }
+
+    pub fn create(&self, input: &ScheduleInput) -> Result<Schedule> {
+        let invalid = |message: String| Err(AppError::InvalidInput(message));
+        let trimmed = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        let input = ScheduleInput {
+            payload: input.payload.trim().to_string(),
+            local_time: input.local_time.trim().to_string(),
+            target: input.target.trim().to_string(),
+            destination: input.destination.trim().to_string(),
+            smtp_url: trimmed(&input.smtp_url),
+            email_from: trimmed(&input.email_from),
+        };
+        if !PAYLOADS.contains(&input.payload.as_str()) {
+            return invalid(format!("unsupported payload {}", input.payload));
+        }
+        let Some(local_time) = schedules::parse_local_time(&input.local_time) else {
+            return invalid("local_time must be HH:MM".to_string());
+        };
+        match input.target.as_str() {
+            TARGET_WEBHOOK => {
+                if !(input.destination.starts_with("http://")
+                    || input.destination.starts_with("https://"))
+                {
+                    return invalid("webhook url must start with http:// or https://".to_string());
+                }
+            }
+            TARGET_EMAIL => {
+                if !cfg!(feature = "email") {
+                    return invalid("this build has no email support".to_string());
+                }
+                if !input.destination.contains('@') {
+                    return invalid("email destination must be an address".to_string());
+                }
+                if !input
+                    .smtp_url
+                    .as_deref()
+                    .is_some_and(|url| url.starts_with("smtp://") || url.starts_with("smtps://"))
+                {
+                    return invalid(
+                        "email targets need an smtp:// or smtps:// smtp_url".to_string(),
+                    );
+                }
+            }
+            other => {
+                return invalid(format!(
+                    "unsupported target {other}; expected one of {}",
+                    TARGETS.join(", ")
+                ));
+            }
+        }
+        // First sent at the next occurrence of its time, not on the next check.
+        let now = Local::now().naive_local();
+        let skip_today = (now.time() >= local_time).then(|| now.date().to_string());
+        Ok(self.db()?.create_schedule(&input, skip_today.as_deref())?)
+    }
+
+    pub fn delete(&self, id: i64) -> Result<usize> {
+        Ok(self.db()?.delete_schedule(id)?)
+    }
+
+    /// Sends a schedule now, whether or not it is due, and records the run.
+    pub fn send(&self, id: i64) -> Result<Schedule> {
+        let db = self.db()?;
+        let schedule = db
+            .get_schedule(id)?
+            .ok_or_else(|| AppError::NotFound("schedule not found".to_string()))?;
+        let today = Local::now().date_naive();
+        let outcome = self.deliver(&schedule, today);
+        db.record_schedule_run(
+            id,
+            &today.to_string(),
+            outcome.as_ref().err().map(String::as_str),
+        )?;
+        outcome.map_err(AppError::Message)?;
+        db.get_schedule(id)?
+            .ok_or_else(|| AppError::NotFound("schedule not found".to_string()))
+    }
+
+    /// Sends every schedule whose local time has passed today and that has
+    /// not run yet; returns how many ran. A failed run is recorded on the
+    /// schedule and retried the next day.
+    pub fn run_due(&self) -> Result<usize> {
+        let db = self.db()?;
+        let now = Local::now().naive_local();
+        let today = now.date();
+        let mut ran = 0;
+        for schedule in db.list_schedules()? {
+            if !schedules::is_due(&schedule, now) {
+                continue;
+            }
+            let outcome = self.deliver(&schedule, today);
+            if let Err(err) = &outcome {
+                tracing::warn!(schedule_id = schedule.id, error = %err, "scheduled send failed");
+            }
+            db.record_schedule_run(
+                schedule.id,
+                &today.to_string(),
+                outcome.as_ref().err().map(String::as_str),
+            )?;
+            ran += 1;
+        }
+        Ok(ran)
+    }
+
+    /// Checks for due schedules every minute on a background thread.
+    pub fn spawn(self) -> JoinHandle<()> {
+        thread::spawn(move || {
+            loop {
+                if let Err(err) = self.run_due() {
+                    tracing::warn!(error = %err, "schedule run failed");
+                }
+                thread::sleep(schedules::TICK);
+            }
+        })
+    }
+
+    /// Renders the schedule's payload and sends it to its target.
+    fn deliver(&self, schedule: &Schedule, today: NaiveDate) -> std::result::Result<(), String> {
+        let (event, subject, data, text) = if schedule.payload == PAYLOAD_REPORT {
+            let report = self
+                .analytics
+                .report(ReportPeriod::Day, None)
+                .map_err(|err| err.to_string())?;
+            (
+                EVENT_SCHEDULE_REPORT,
+                format!("Codex usage report for {today}"),
+                serde_json::to_value(&report).map_err(|err| err.to_string())?,
+                report.render(ReportFormat::Markdown),
+            )
+        } else {
+            let status = self.status(today).map_err(|err| err.to_string())?;
+            (
+                EVENT_SCHEDULE_STATUS,
+                format!("Codex status for {today}"),
+                serde_json::to_value(&status).map_err(|err| err.to_string())?,
+                status.to_text(),
+            )
+        };
+        if schedule.target == TARGET_EMAIL {
+            let smtp_url = schedule
+                .smtp_url
+                .as_deref()
+                .ok_or_else(|| "schedule has no smtp_url".to_string())?;
+            let from = schedule
+                .email_from
+                .as_deref()
+                .unwrap_or(&schedule.destination);
+            return schedules::send_email(smtp_url, from, &schedule.destination, &subject, text);
+        }
+        schedules::post_json(
+            &schedule.destination,
+            event,
+            &webhook_body(schedule, event, data),
+        )
+    }
+
+    fn status(&self, today: NaiveDate) -> Result<StatusSnapshot> {
+        let range = TimeRange {
+            start: local_midnight_utc(today)?,
+            end: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        };
+        let usage = self.analytics.summary(&range, None)?;
+        let mut db = self.db()?;
+        let home = require_active_home(&mut db)?;
+        let limits = ["5h", "7d"]
+            .into_iter()
+            .map(|limit_type| db.latest_limit_snapshot_current(home.id, limit_type))
+            .filter_map(|snapshot| snapshot.transpose())
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(StatusSnapshot {
+            date: today.to_string(),
+            usage,
+            limits,
+        })
+    }
+}
+
+/// Same envelope as event webhooks, plus the schedule that sent it.
+fn webhook_body(schedule: &Schedule, event: &str, data: Value) -> String {
+    json!({
+        "event": event,
+        "created_at": Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        "schedule_id": schedule.id,
+        "data": data,
+    })
+    .to_string()
+}
@@ -1,23 +1,37 @@
-use crate::error::Result;
+use crate::error::{AppError, Result};
+use crate::events::{AppEvent, EventBus};
 use crate::services::{SharedConfig, open_db, require_active_home};
+use tracker_core::ActiveSessionRule;
 use tracker_db::Db;
 
+const ACTIVE_SESSION_RULE_KEY: &str = "active_session_rule";
+
 /// Snapshot of user-configurable settings stored in the DB.
 #[derive(Debug, Clone)]
 pub struct SettingsSnapshot {
     pub codex_home: String,
     pub active_home_id: i64,
     pub context_active_minutes: u32,
+    pub monthly_budget_usd: Option<f64>,
+    pub auto_ingest_secs: u32,
+    pub launch_at_login: bool,
+    /// Empty when the desktop app's global shortcut is turned off.
+    pub global_shortcut: String,
+    pub tracking_paused: bool,
+    pub limit_warn_percent: u32,
+    pub limit_critical_percent: u32,
+    pub active_session_rule: ActiveSessionRule,
 }
 
 #[derive(Clone)]
 pub struct SettingsService {
     config: SharedConfig,
+    events: EventBus,
 }
 
 impl SettingsService {
-    pub(super) fn new(config: SharedConfig) -> Self {
-        Self { config }
+    pub(super) fn new(config: SharedConfig, events: EventBus) -> Self {
+        Self { config, events }
     }
 
     fn db(&self) -> Result<Db> {
@@ -28,26 +42,126 @@ impl SettingsService {
         let mut db = self.db()?;
         let home = require_active_home(&mut db)?;
         let context_active_minutes = db.get_context_active_minutes()?;
+        let monthly_budget_usd = db.get_monthly_budget_usd()?;
+        let auto_ingest_secs = db.get_auto_ingest_secs()?;
+        let launch_at_login = db.get_launch_at_login()?;
+        let global_shortcut = db.get_global_shortcut()?;
+        let tracking_paused = db.get_tracking_paused()?;
+        let limit_warn_percent = db.get_limit_warn_percent()?;
+        let limit_critical_percent = db.get_limit_critical_percent()?;
+        let active_session_rule = active_session_rule(&db)?;
         Ok(SettingsSnapshot {
             codex_home: home.path,
             active_home_id: home.id,
             context_active_minutes,
+            monthly_budget_usd,
+            auto_ingest_secs,
+            launch_at_login,
+            global_shortcut,
+            tracking_paused,
+            limit_warn_percent,
+            limit_critical_percent,
+            active_session_rule,
         })
     }
 
+    /// Replaces what, besides recent events, makes a session active.
+    pub fn set_active_session_rule(&self, rule: &ActiveSessionRule) -> Result<()> {
+        validate_active_session_rule(rule)?;
+        let value = serde_json::to_string(rule)?;
+        Ok(self.db()?.set_setting(ACTIVE_SESSION_RULE_KEY, &value)?)
+    }
+
+    /// Pauses or resumes ingest everywhere: schedulers and refreshes skip it,
+    /// and explicit ingests are refused, until tracking is resumed.
+    pub fn set_tracking_paused(&self, paused: bool) -> Result<()> {
+        Ok(self.db()?.set_tracking_paused(paused)?)
+    }
+
+    /// Sets the percent-used cutoffs for the limit `warn` and `critical`
+    /// levels; a missing one keeps its stored value. Both must be within
+    /// 0-100, with `warn` no higher than `critical`.
+    pub fn set_limit_thresholds(&self, warn: Option<u32>, critical: Option<u32>) -> Result<()> {
+        let db = self.db()?;
+        let warn = match warn {
+            Some(warn) => warn,
+            None => db.get_limit_warn_percent()?,
+        };
+        let critical = match critical {
+            Some(critical) => critical,
+            None => db.get_limit_critical_percent()?,
+        };
+        if critical > 100 || warn > critical {
+            return Err(AppError::InvalidInput(format!(
+                "limit thresholds must satisfy warn <= critical <= 100 (got {warn} and {critical})"
+            )));
+        }
+        db.set_limit_warn_percent(warn)?;
+        db.set_limit_critical_percent(critical)?;
+        Ok(())
+    }
+
+    /// Applies the provided settings; a `monthly_budget_usd` of zero or less
+    /// clears the budget.
     pub fn update(
         &self,
         codex_home: Option<&str>,
         context_active_minutes: Option<u32>,
+        monthly_budget_usd: Option<f64>,
+        auto_ingest_secs: Option<u32>,
+        launch_at_login: Option<bool>,
+        global_shortcut: Option<&str>,
     ) -> Result<()> {
         let db = self.db()?;
         if let Some(codex_home) = codex_home {
             let home = db.get_or_create_home(codex_home, Some("Default"))?;
+            if home.archived {
+                db.set_home_archived(home.id, false)?;
+            }
             db.set_active_home(home.id)?;
+            self.events
+                .publish(AppEvent::HomeSwitched { home_id: home.id });
         }
         if let Some(minutes) = context_active_minutes {
             db.set_context_active_minutes(minutes)?;
         }
+        if let Some(budget) = monthly_budget_usd {
+            db.set_monthly_budget_usd((budget > 0.0).then_some(budget))?;
+        }
+        if let Some(secs) = auto_ingest_secs {
+            db.set_auto_ingest_secs(secs)?;
+        }
+        if let Some(enabled) = launch_at_login {
+            db.set_launch_at_login(enabled)?;
+        }
+        if let Some(shortcut) = global_shortcut {
+            db.set_global_shortcut(shortcut)?;
+        }
         Ok(())
     }
 }
+
+pub fn validate_active_session_rule(rule: &ActiveSessionRule) -> Result<()> {
+    if rule
+        .min_tokens
+        .iter()
+        .any(|entry| entry.model_pattern.trim().is_empty())
+    {
+        return Err(AppError::InvalidInput(
+            "active session token minimums need a model pattern".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// The stored active-session rule; the default (recent events only) when
+/// none was saved or it no longer parses.
+pub(super) fn active_session_rule(db: &Db) -> Result<ActiveSessionRule> {
+    let Some(value) = db.get_setting(ACTIVE_SESSION_RULE_KEY)? else {
+        return Ok(ActiveSessionRule::default());
+    };
+    Ok(serde_json::from_str(&value).unwrap_or_else(|err| {
+        tracing::warn!(error = %err, "ignoring invalid active session rule");
+        ActiveSessionRule::default()
+    }))
+}
@@ -0,0 +1,155 @@
+use std::thread;
+
+use chrono::{SecondsFormat, Utc};
+use serde_json::{Value, json};
+
+use crate::error::{AppError, Result};
+use crate::services::{SharedConfig, open_db};
+use crate::webhooks::{
+    self, EVENT_TYPES, MAX_ATTEMPTS, STATUS_DELIVERED, STATUS_FAILED, STATUS_PENDING,
+};
+use tracker_core::{Webhook, WebhookDelivery};
+use tracker_db::Db;
+
+#[derive(Clone)]
+pub struct WebhooksService {
+    config: SharedConfig,
+}
+
+impl WebhooksService {
+    pub(super) fn new(config: SharedConfig) -> Self {
+        Self { config }
+    }
+
+    fn db(&self) -> Result<Db> {
+        open_db(&self.config)
+    }
+
+    pub fn list(&self) -> Result<Vec<Webhook>> {
+        let db = self.db()?;
+        Ok(db.list_webhooks()?)
+    }
+
+    pub fn create(
+        &self,
+        url: &str,
+        secret: Option<&str>,
+        event_types: &[String],
+    ) -> Result<Webhook> {
+        let url = url.trim();
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(AppError::InvalidInput(
+                "webhook url must start with http:// or https://".to_string(),
+            ));
+        }
+        if event_types.is_empty() {
+            return Err(AppError::InvalidInput(
+                "at least one event type is required".to_string(),
+            ));
+        }
+        if let Some(unknown) = event_types
+            .iter()
+            .find(|event_type| !EVENT_TYPES.contains(&event_type.as_str()))
+        {
+            return Err(AppError::InvalidInput(format!(
+                "unsupported event type {unknown}"
+            )));
+        }
+        let secret = secret.map(str::trim).filter(|value| !value.is_empty());
+        let db = self.db()?;
+        Ok(db.create_webhook(url, secret, event_types)?)
+    }
+
+    pub fn delete(&self, id: i64) -> Result<usize> {
+        let db = self.db()?;
+        Ok(db.delete_webhook(id)?)
+    }
+
+    pub fn deliveries(&self, webhook_id: Option<i64>, limit: u32) -> Result<Vec<WebhookDelivery>> {
+        let db = self.db()?;
+        Ok(db.list_webhook_deliveries(webhook_id, limit)?)
+    }
+
+    /// Whether any enabled webhook listens for at least one event.
+    pub fn has_subscribers(&self) -> Result<bool> {
+        Ok(self.db()?.list_webhooks()?.iter().any(|hook| hook.enabled))
+    }
+
+    /// Records a delivery for every subscribed webhook and returns their ids.
+    pub fn enqueue(&self, event_type: &str, data: Value) -> Result<Vec<i64>> {
+        let db = self.db()?;
+        let body = json!({
+            "event": event_type,
+            "created_at": Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            "data": data,
+        })
+        .to_string();
+        let mut ids = Vec::new();
+        for hook in db.list_webhooks()? {
+            if hook.enabled && hook.event_types.iter().any(|value| value == event_type) {
+                ids.push(db.insert_webhook_delivery(hook.id, event_type, &body)?);
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Enqueues the event and delivers it on a background thread.
+    pub fn dispatch(&self, event_type: &str, data: Value) -> Result<()> {
+        let ids = self.enqueue(event_type, data)?;
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let service = self.clone();
+        thread::spawn(move || {
+            for id in ids {
+                if let Err(err) = service.deliver(id) {
+                    tracing::warn!(delivery_id = id, error = %err, "webhook delivery failed");
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Attempts a delivery until it succeeds, hits a permanent error, or
+    /// runs out of attempts, sleeping with exponential backoff in between.
+    pub fn deliver(&self, delivery_id: i64) -> Result<WebhookDelivery> {
+        let db = self.db()?;
+        let missing = || AppError::NotFound("webhook delivery not found".to_string());
+        let delivery = db.get_webhook_delivery(delivery_id)?.ok_or_else(missing)?;
+        let hook = db
+            .get_webhook(delivery.webhook_id)?
+            .ok_or_else(|| AppError::NotFound("webhook not found".to_string()))?;
+
+        let mut attempts = delivery.attempts;
+        while attempts < MAX_ATTEMPTS {
+            if attempts > 0 {
+                thread::sleep(webhooks::backoff(attempts));
+            }
+            let attempt = webhooks::post(
+                &hook.url,
+                hook.secret.as_deref(),
+                &delivery.event_type,
+                delivery.id,
+                &delivery.payload,
+            );
+            attempts += 1;
+            let status = if attempt.succeeded() {
+                STATUS_DELIVERED
+            } else if attempt.retryable() && attempts < MAX_ATTEMPTS {
+                STATUS_PENDING
+            } else {
+                STATUS_FAILED
+            };
+            db.record_webhook_attempt(
+                delivery.id,
+                status,
+                attempt.response_status,
+                attempt.error.as_deref(),
+            )?;
+            if status != STATUS_PENDING {
+                break;
+            }
+        }
+        db.get_webhook_delivery(delivery_id)?.ok_or_else(missing)
+    }
+}
@@ -0,0 +1,183 @@
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::thread;
+use std::time::Duration;
+
+use crate::config::RangeParams;
+use crate::error::{AppError, Result};
+use crate::services::AppServices;
+use crate::util::time::resolve_range;
+
+pub const DEFAULT_STATSD_PREFIX: &str = "codex_tracker";
+pub const DEFAULT_STATSD_INTERVAL_SECS: u64 = 60;
+
+/// Keeps datagrams under a typical MTU so nothing is fragmented.
+const MAX_DATAGRAM_BYTES: usize = 1400;
+
+#[derive(Debug, Clone)]
+pub struct StatsdConfig {
+    /// `host:port` of the StatsD / DogStatsD agent.
+    pub address: String,
+    pub prefix: String,
+    pub interval: Duration,
+    /// DogStatsD tags (`key:value`) added to every metric.
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gauge {
+    pub name: String,
+    pub value: f64,
+    pub tags: Vec<String>,
+}
+
+impl Gauge {
+    fn new(name: &str, value: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            value,
+            tags: Vec::new(),
+        }
+    }
+
+    fn tagged(mut self, tag: String) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    /// `prefix.name:value|g|#tags` in the DogStatsD line format.
+    pub fn line(&self, prefix: &str, global_tags: &[String]) -> String {
+        let mut line = if prefix.is_empty() {
+            format!("{}:{}|g", self.name, self.value)
+        } else {
+            format!("{prefix}.{}:{}|g", self.name, self.value)
+        };
+        let tags: Vec<&str> = global_tags
+            .iter()
+            .chain(&self.tags)
+            .map(String::as_str)
+            .collect();
+        if !tags.is_empty() {
+            line.push_str("|#");
+            line.push_str(&tags.join(","));
+        }
+        line
+    }
+}
+
+/// Current token, cost, and limit gauges for the active home.
+pub fn collect_gauges(services: &AppServices) -> Result<Vec<Gauge>> {
+    let range_params = |range: &str| RangeParams {
+        range: Some(range.to_string()),
+        start: None,
+        end: None,
+    };
+    let today_range = resolve_range(&range_params("today"))?;
+    let today = services.analytics.summary(&today_range, None)?;
+    let compactions = services.analytics.compactions(&today_range, None, None)?;
+    let month = services
+        .analytics
+        .summary(&resolve_range(&range_params("thismonth"))?, None)?;
+
+    let mut gauges = vec![
+        Gauge::new("tokens.today", today.total_tokens as f64),
+        Gauge::new("tokens.input.today", today.input_tokens as f64),
+        Gauge::new(
+            "tokens.cached_input.today",
+            today.cached_input_tokens as f64,
+        ),
+        Gauge::new("tokens.output.today", today.output_tokens as f64),
+        Gauge::new("tokens.month", month.total_tokens as f64),
+        Gauge::new("compactions.today", compactions.total as f64),
+    ];
+    if let Some(cost) = today.total_cost_usd {
+        gauges.push(Gauge::new("cost_usd.today", cost));
+    }
+    if let Some(cost) = month.total_cost_usd {
+        gauges.push(Gauge::new("cost_usd.month", cost));
+    }
+    let (primary, secondary) = services.limits.latest()?;
+    for snapshot in primary.into_iter().chain(secondary) {
+        gauges.push(
+            Gauge::new("limit.percent_left", snapshot.percent_left)
+                .tagged(format!("window:{}", snapshot.limit_type)),
+        );
+    }
+    Ok(gauges)
+}
+
+/// Periodically pushes [`collect_gauges`] to a StatsD agent over UDP.
+pub struct StatsdExporter {
+    socket: UdpSocket,
+    config: StatsdConfig,
+}
+
+impl StatsdExporter {
+    pub fn connect(config: StatsdConfig) -> Result<Self> {
+        let addr =
+            config.address.to_socket_addrs()?.next().ok_or_else(|| {
+                AppError::InvalidInput(format!("cannot resolve {}", config.address))
+            })?;
+        let socket = UdpSocket::bind(if addr.is_ipv6() {
+            "[::]:0"
+        } else {
+            "0.0.0.0:0"
+        })?;
+        socket.connect(addr)?;
+        Ok(Self { socket, config })
+    }
+
+    /// Sends one round of gauges and returns how many were sent.
+    pub fn flush(&self, services: &AppServices) -> Result<usize> {
+        let gauges = collect_gauges(services)?;
+        let lines: Vec<String> = gauges
+            .iter()
+            .map(|gauge| gauge.line(&self.config.prefix, &self.config.tags))
+            .collect();
+        let mut datagram = String::new();
+        for line in &lines {
+            if !datagram.is_empty() && datagram.len() + 1 + line.len() > MAX_DATAGRAM_BYTES {
+                self.socket.send(datagram.as_bytes())?;
+                datagram.clear();
+            }
+            if !datagram.is_empty() {
+                datagram.push('\n');
+            }
+            datagram.push_str(line);
+        }
+        if !datagram.is_empty() {
+            self.socket.send(datagram.as_bytes())?;
+        }
+        Ok(lines.len())
+    }
+
+    /// Flushes every `interval` on a background thread for the life of the
+    /// process. Failures are logged and retried on the next tick.
+    pub fn spawn(self, services: AppServices) -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            loop {
+                if let Err(err) = self.flush(&services) {
+                    tracing::warn!(error = %err, "statsd flush failed");
+                }
+                thread::sleep(self.config.interval);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_dogstatsd_lines() {
+        let gauge = Gauge::new("limit.percent_left", 42.5).tagged("window:5h".to_string());
+        assert_eq!(
+            gauge.line("codex_tracker", &["team:ml".to_string()]),
+            "codex_tracker.limit.percent_left:42.5|g|#team:ml,window:5h"
+        );
+        assert_eq!(
+            Gauge::new("tokens.today", 12.0).line("", &[]),
+            "tokens.today:12|g"
+        );
+    }
+}
@@ -1,4 +1,6 @@
-use chrono::{DateTime, Datelike, Duration, Local, SecondsFormat, TimeZone, Utc};
+use chrono::{
+    DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, SecondsFormat, TimeZone, Utc,
+};
 
 use crate::config::RangeParams;
 use crate::error::{AppError, Result};
@@ -28,7 +30,7 @@ pub fn resolve_range(params: &RangeParams) -> Result<TimeRange> {
                     0,
                 )
                 .single()
-                .ok_or_else(|| AppError::InvalidInput("invalid local date".to_string()))?;
+                .ok_or_else(|| AppError::BadRange("invalid local date".to_string()))?;
             (start, now_local)
         }
         "last7days" => {
@@ -43,21 +45,33 @@ pub fn resolve_range(params: &RangeParams) -> Result<TimeRange> {
             let start = Local
                 .with_ymd_and_hms(now_local.year(), now_local.month(), 1, 0, 0, 0)
                 .single()
-                .ok_or_else(|| AppError::InvalidInput("invalid local date".to_string()))?;
+                .ok_or_else(|| AppError::BadRange("invalid local date".to_string()))?;
             (start, now_local)
         }
+        "prevmonth" => {
+            let (year, month) = match now_local.month() {
+                1 => (now_local.year() - 1, 12),
+                month => (now_local.year(), month - 1),
+            };
+            let start = Local
+                .with_ymd_and_hms(year, month, 1, 0, 0, 0)
+                .single()
+                .ok_or_else(|| AppError::BadRange("invalid local date".to_string()))?;
+            let end = Local
+                .with_ymd_and_hms(now_local.year(), now_local.month(), 1, 0, 0, 0)
+                .single()
+                .ok_or_else(|| AppError::BadRange("invalid local date".to_string()))?;
+            (start, end)
+        }
         "alltime" => {
             let start = Local
                 .with_ymd_and_hms(1970, 1, 1, 0, 0, 0)
                 .single()
-                .ok_or_else(|| AppError::InvalidInput("invalid local date".to_string()))?;
+                .ok_or_else(|| AppError::BadRange("invalid local date".to_string()))?;
             (start, now_local)
         }
         value => {
-            return Err(AppError::InvalidInput(format!(
-                "unsupported range {}",
-                value
-            )));
+            return Err(AppError::BadRange(format!("unsupported range {}", value)));
         }
     };
     let start = start_local
@@ -69,10 +83,85 @@ pub fn resolve_range(params: &RangeParams) -> Result<TimeRange> {
     Ok(TimeRange { start, end })
 }
 
+/// The window of the same length that ends where `range` starts.
+pub fn preceding_range(range: &TimeRange) -> Result<TimeRange> {
+    let parse = |value: &str| {
+        DateTime::parse_from_rfc3339(value)
+            .map(|time| time.with_timezone(&Utc))
+            .map_err(|err| AppError::BadRange(format!("invalid datetime: {}", err)))
+    };
+    let start = parse(&range.start)?;
+    let end = parse(&range.end)?;
+    Ok(TimeRange {
+        start: (start - (end - start)).to_rfc3339_opts(SecondsFormat::Millis, true),
+        end: range.start.clone(),
+    })
+}
+
+/// The local calendar `week` (from Monday) or `month` containing `now`,
+/// ending where the next one starts.
+pub fn calendar_period_range(period: &str, now: DateTime<Local>) -> Result<TimeRange> {
+    let today = now.date_naive();
+    let first_of = |year: i32, month: u32| {
+        NaiveDate::from_ymd_opt(year, month, 1)
+            .ok_or_else(|| AppError::BadRange("invalid local date".to_string()))
+    };
+    let (start, end) = match period {
+        "week" => {
+            let start = today - Duration::days(i64::from(today.weekday().num_days_from_monday()));
+            (start, start + Duration::days(7))
+        }
+        "month" => {
+            let (year, month) = match today.month() {
+                12 => (today.year() + 1, 1),
+                month => (today.year(), month + 1),
+            };
+            (
+                first_of(today.year(), today.month())?,
+                first_of(year, month)?,
+            )
+        }
+        value => {
+            return Err(AppError::InvalidInput(format!(
+                "unsupported period {value}"
+            )));
+        }
+    };
+    let midnight = |date: NaiveDate| {
+        Local
+            .from_local_datetime(&date.and_time(NaiveTime::MIN))
+            .earliest()
+            .map(|time| {
+                time.with_timezone(&Utc)
+                    .to_rfc3339_opts(SecondsFormat::Millis, true)
+            })
+            .ok_or_else(|| AppError::BadRange(format!("invalid local date: {date}")))
+    };
+    Ok(TimeRange {
+        start: midnight(start)?,
+        end: midnight(end)?,
+    })
+}
+
 pub fn normalize_rfc3339_to_utc(value: &str) -> Result<String> {
     let parsed = DateTime::parse_from_rfc3339(value)
-        .map_err(|err| AppError::InvalidInput(format!("invalid datetime: {}", err)))?;
+        .map_err(|err| AppError::BadRange(format!("invalid datetime: {}", err)))?;
     Ok(parsed
         .with_timezone(&Utc)
         .to_rfc3339_opts(SecondsFormat::Millis, true))
 }
+
+/// Accepts an RFC 3339 timestamp, or a `YYYY-MM-DD` date meaning local
+/// midnight at the start of that day.
+pub fn normalize_date_or_rfc3339_to_utc(value: &str) -> Result<String> {
+    let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") else {
+        return normalize_rfc3339_to_utc(value);
+    };
+    let midnight = Local
+        .from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .earliest()
+        .ok_or_else(|| AppError::BadRange(format!("invalid local date: {value}")))?;
+    Ok(midnight
+        .with_timezone(&Utc)
+        .to_rfc3339_opts(SecondsFormat::Millis, true))
+}
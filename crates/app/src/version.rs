@@ -0,0 +1,24 @@
+use serde::Serialize;
+
+/// Identifies the running build when triaging reports across the desktop
+/// app, CLI, and server.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VersionInfo {
+    pub version: String,
+    /// Short commit hash, or `unknown` when built outside a git checkout.
+    pub git_sha: String,
+    /// UTC build date (`YYYY-MM-DD`).
+    pub build_date: String,
+    /// Number of database migrations this build applies.
+    pub schema_version: u32,
+}
+
+pub fn version_info() -> VersionInfo {
+    VersionInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        git_sha: env!("CODEX_TRACKER_GIT_SHA").to_string(),
+        build_date: env!("CODEX_TRACKER_BUILD_DATE").to_string(),
+        schema_version: tracker_db::SCHEMA_VERSION,
+    }
+}
@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+pub const EVENT_INGEST_COMPLETED: &str = "ingest.completed";
+pub const EVENT_LIMIT_THRESHOLD: &str = "limit.threshold";
+pub const EVENT_BUDGET_EXCEEDED: &str = "budget.exceeded";
+pub const EVENT_TYPES: [&str; 3] = [
+    EVENT_INGEST_COMPLETED,
+    EVENT_LIMIT_THRESHOLD,
+    EVENT_BUDGET_EXCEEDED,
+];
+
+pub const EVENT_HEADER: &str = "x-codex-tracker-event";
+pub const DELIVERY_HEADER: &str = "x-codex-tracker-delivery";
+pub const SIGNATURE_HEADER: &str = "x-codex-tracker-signature";
+
+pub const MAX_ATTEMPTS: u32 = 5;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_DELIVERED: &str = "delivered";
+pub const STATUS_FAILED: &str = "failed";
+
+/// Outcome of a single POST to a webhook endpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attempt {
+    pub response_status: Option<u16>,
+    pub error: Option<String>,
+}
+
+impl Attempt {
+    pub fn succeeded(&self) -> bool {
+        matches!(self.response_status, Some(status) if (200..300).contains(&status))
+    }
+
+    /// Client errors other than timeouts and rate limiting will not succeed on retry.
+    pub fn retryable(&self) -> bool {
+        match self.response_status {
+            Some(status) if (400..500).contains(&status) => status == 408 || status == 429,
+            _ => !self.succeeded(),
+        }
+    }
+}
+
+/// `sha256=<hex>` HMAC of the request body, GitHub-style.
+pub fn sign_payload(secret: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts any key length");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Delay before the given retry (1-based), doubling each time.
+pub fn backoff(attempt: u32) -> Duration {
+    INITIAL_BACKOFF * 2u32.saturating_pow(attempt.saturating_sub(1))
+}
+
+pub(crate) fn post(
+    url: &str,
+    secret: Option<&str>,
+    event_type: &str,
+    delivery_id: i64,
+    body: &str,
+) -> Attempt {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(REQUEST_TIMEOUT))
+        .http_status_as_error(false)
+        .build()
+        .into();
+    let mut request = agent
+        .post(url)
+        .header("content-type", "application/json")
+        .header(
+            "user-agent",
+            concat!("codex-tracker/", env!("CARGO_PKG_VERSION")),
+        )
+        .header(EVENT_HEADER, event_type)
+        .header(DELIVERY_HEADER, delivery_id.to_string());
+    if let Some(secret) = secret {
+        request = request.header(SIGNATURE_HEADER, sign_payload(secret, body));
+    }
+    match request.send(body) {
+        Ok(response) => {
+            let status = response.status().as_u16();
+            let error = (!(200..300).contains(&status)).then(|| format!("HTTP {status}"));
+            Attempt {
+                response_status: Some(status),
+                error,
+            }
+        }
+        Err(err) => Attempt {
+            response_status: None,
+            error: Some(err.to_string()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_payload_with_hmac_sha256() {
+        assert_eq!(
+            sign_payload("It's a Secret to Everybody", "Hello, World!"),
+            "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17"
+        );
+    }
+
+    #[test]
+    fn backoff_doubles() {
+        assert_eq!(backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff(2), Duration::from_secs(2));
+        assert_eq!(backoff(4), Duration::from_secs(8));
+    }
+
+    #[test]
+    fn client_errors_are_not_retried() {
+        let attempt = Attempt {
+            response_status: Some(404),
+            error: Some("HTTP 404".to_string()),
+        };
+        assert!(!attempt.retryable());
+        let attempt = Attempt {
+            response_status: Some(503),
+            error: Some("HTTP 503".to_string()),
+        };
+        assert!(attempt.retryable());
+        let attempt = Attempt {
+            response_status: None,
+            error: Some("connection refused".to_string()),
+        };
+        assert!(attempt.retryable());
+    }
+}
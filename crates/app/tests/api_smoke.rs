@@ -1,6 +1,19 @@
+use std::net::UdpSocket;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{Local, TimeZone, Utc};
 use tempfile::tempdir;
-use tracker_app::AppState;
-use tracker_core::{ContextStatus, TimeRange, UsageEvent, UsageTotals, session_id_from_source};
+use tracker_app::bundle;
+use tracker_app::events::AppEvent;
+use tracker_app::notifications::Channel;
+use tracker_app::services::{CacheKey, IngestTarget};
+use tracker_app::statsd::{StatsdConfig, StatsdExporter};
+use tracker_app::{AppState, RangeParams, resolve_range};
+use tracker_core::{
+    ActiveMinTokens, ActiveSessionRule, ContextStatus, HomeScope, MessageEvent, PricingRuleInput,
+    TimeRange, UsageEvent, UsageTotals, session_id_from_source,
+};
 
 #[test]
 fn summary_service_smoke() {
@@ -26,7 +39,7 @@ fn summary_service_smoke() {
     };
     let event = UsageEvent {
         id: "e1".to_string(),
-        ts: "2025-12-19T10:00:00Z".to_string(),
+        ts: "2025-12-19T10:00:00Z".parse().expect("timestamp"),
         model: "gpt-5.2".to_string(),
         usage,
         context: ContextStatus {
@@ -34,13 +47,15 @@ fn summary_service_smoke() {
             context_window: 100,
         },
         cost_usd: None,
+        cost_source: None,
         reasoning_effort: None,
+        provider: None,
         source: "source-a".to_string(),
         session_id: session_id_from_source("source-a"),
         request_id: None,
         raw_json: None,
     };
-    db.insert_usage_events(home.id, &[event])
+    db.insert_usage_events(home.id, std::slice::from_ref(&event))
         .expect("insert events");
 
     let range = TimeRange {
@@ -50,7 +65,1149 @@ fn summary_service_smoke() {
     let summary = app_state
         .services
         .analytics
-        .summary(&range)
+        .summary(&range, None)
         .expect("summary");
     assert_eq!(summary.total_tokens, 12);
+
+    let other = db
+        .get_or_create_home("/tmp/other-codex-home", Some("Other"))
+        .expect("other home");
+    db.set_active_home(other.id).expect("switch home");
+    let analytics = &app_state.services.analytics;
+    assert_eq!(
+        analytics
+            .summary(&range, None)
+            .expect("active")
+            .total_tokens,
+        0
+    );
+    assert_eq!(
+        analytics
+            .summary(&range, Some(HomeScope::Home(home.id)))
+            .expect("override")
+            .total_tokens,
+        12
+    );
+    assert!(matches!(
+        analytics.summary(&range, Some(HomeScope::Home(9999))),
+        Err(tracker_app::AppError::HomeNotFound(_))
+    ));
+
+    let other_event = UsageEvent {
+        id: "e2".to_string(),
+        source: "/tmp/other-codex-home/sessions/source-b".to_string(),
+        ..event
+    };
+    db.insert_usage_events(other.id, &[other_event])
+        .expect("insert other events");
+    assert_eq!(
+        analytics
+            .summary(&range, Some(HomeScope::All))
+            .expect("all homes")
+            .total_tokens,
+        24
+    );
+    let per_home = analytics
+        .breakdown_homes(&range, Some(HomeScope::All))
+        .expect("per home");
+    let with_usage: Vec<i64> = per_home
+        .iter()
+        .filter(|item| item.total_tokens == 12)
+        .map(|item| item.codex_home_id)
+        .collect();
+    assert_eq!(with_usage.len(), 2);
+    assert!(with_usage.contains(&home.id) && with_usage.contains(&other.id));
+    assert!(matches!(
+        analytics.events(&range, None, 10, 0, Some(HomeScope::All)),
+        Err(tracker_app::AppError::InvalidInput(_))
+    ));
+}
+
+#[test]
+fn archived_homes_keep_usage_but_leave_pickers_and_ingest() {
+    let dir = tempdir().expect("temp dir");
+    let app_state = AppState::new(
+        dir.path().join("app.sqlite"),
+        dir.path().join("pricing.json"),
+    );
+    app_state.setup_db().expect("setup db");
+    let homes = &app_state.services.homes;
+    let old = homes
+        .create(&dir.path().join("old").to_string_lossy(), Some("Old"))
+        .expect("old home");
+    let mut db = app_state.open_db().expect("open db");
+    let event = UsageEvent {
+        id: "e1".to_string(),
+        ts: "2025-12-19T10:00:00Z".parse().expect("timestamp"),
+        model: "gpt-5.2".to_string(),
+        usage: UsageTotals {
+            total_tokens: 12,
+            ..UsageTotals::default()
+        },
+        context: ContextStatus::default(),
+        cost_usd: None,
+        cost_source: None,
+        reasoning_effort: None,
+        provider: None,
+        source: "source-a".to_string(),
+        session_id: session_id_from_source("source-a"),
+        request_id: None,
+        raw_json: None,
+    };
+    db.insert_usage_events(old.id, &[event])
+        .expect("insert events");
+
+    assert!(homes.archive(old.id).expect("archive").archived);
+    assert_ne!(homes.active().expect("active").id, old.id);
+    assert!(matches!(
+        homes.set_active(old.id),
+        Err(tracker_app::AppError::InvalidInput(_))
+    ));
+    assert!(matches!(
+        app_state
+            .services
+            .ingest
+            .run_once(IngestTarget::Home(old.id), false),
+        Err(tracker_app::AppError::InvalidInput(_))
+    ));
+
+    let range = TimeRange {
+        start: "2025-12-19T00:00:00Z".to_string(),
+        end: "2025-12-20T00:00:00Z".to_string(),
+    };
+    let analytics = &app_state.services.analytics;
+    assert_eq!(
+        analytics
+            .summary(&range, Some(HomeScope::All))
+            .expect("all homes")
+            .total_tokens,
+        12
+    );
+
+    assert!(!homes.unarchive(old.id).expect("unarchive").archived);
+    homes.set_active(old.id).expect("active after unarchive");
+}
+
+#[test]
+fn api_tokens_are_hashed_and_revocable() {
+    let dir = tempdir().expect("temp dir");
+    let app_state = AppState::new(
+        dir.path().join("app.sqlite"),
+        dir.path().join("pricing.json"),
+    );
+    app_state.setup_db().expect("setup db");
+    let tokens = &app_state.services.api_tokens;
+
+    let (token, secret) = tokens.create(Some("laptop")).expect("create");
+    assert!(tokens.verify(&secret).expect("verify"));
+    assert!(!tokens.verify("ctk_bogus_token").expect("verify bogus"));
+
+    let db = app_state.open_db().expect("open db");
+    let stored = db
+        .get_setting(&format!("api_token:{}", token.id))
+        .expect("setting")
+        .expect("stored token");
+    assert!(!stored.contains(&secret));
+
+    assert_eq!(tokens.list().expect("list"), vec![token.clone()]);
+    tokens.revoke(&token.id).expect("revoke");
+    assert!(!tokens.verify(&secret).expect("verify revoked"));
+}
+
+#[test]
+fn ingest_dry_run_leaves_database_untouched() {
+    let dir = tempdir().expect("temp dir");
+    let app_state = AppState::new(
+        dir.path().join("app.sqlite"),
+        dir.path().join("pricing.json"),
+    );
+    app_state.setup_db().expect("setup db");
+
+    let codex_home = dir.path().join("codex");
+    let log_dir = codex_home.join("sessions/2025/12/19");
+    std::fs::create_dir_all(&log_dir).expect("create log dir");
+    let line = r#"{"timestamp":"2025-12-19T21:31:36.168Z","type":"event_msg","payload":{"type":"token_count","info":{"model":"gpt-test","total_token_usage":{"input_tokens":1,"cached_input_tokens":0,"output_tokens":1,"reasoning_output_tokens":0,"total_tokens":2},"model_context_window":100}}}"#;
+    std::fs::write(
+        log_dir.join("rollout-2025-12-19T21-31-36.jsonl"),
+        format!("{line}\n"),
+    )
+    .expect("write log");
+    let home = app_state
+        .open_db()
+        .expect("open db")
+        .get_or_create_home(&codex_home.to_string_lossy(), Some("Codex"))
+        .expect("home");
+    let ingest = &app_state.services.ingest;
+
+    let stats = ingest
+        .run_once(IngestTarget::Home(home.id), true)
+        .expect("dry run");
+    assert_eq!(stats.events_inserted, 1);
+    let db = app_state.open_db().expect("open db");
+    assert_eq!(db.count_usage_events(home.id).expect("count"), 0);
+
+    let stats = ingest
+        .run_once(IngestTarget::Path(codex_home), false)
+        .expect("ingest");
+    assert_eq!(stats.events_inserted, 1);
+    assert_eq!(db.count_usage_events(home.id).expect("count"), 1);
+}
+
+#[test]
+fn paused_tracking_records_nothing() {
+    let dir = tempdir().expect("temp dir");
+    let app_state = AppState::new(
+        dir.path().join("app.sqlite"),
+        dir.path().join("pricing.json"),
+    );
+    app_state.setup_db().expect("setup db");
+
+    let codex_home = dir.path().join("codex");
+    let log_dir = codex_home.join("sessions/2025/12/19");
+    std::fs::create_dir_all(&log_dir).expect("create log dir");
+    let line = r#"{"timestamp":"2025-12-19T21:31:36.168Z","type":"event_msg","payload":{"type":"token_count","info":{"model":"gpt-test","total_token_usage":{"input_tokens":1,"cached_input_tokens":0,"output_tokens":1,"reasoning_output_tokens":0,"total_tokens":2},"model_context_window":100}}}"#;
+    std::fs::write(
+        log_dir.join("rollout-2025-12-19T21-31-36.jsonl"),
+        format!("{line}\n"),
+    )
+    .expect("write log");
+    let services = &app_state.services;
+    services
+        .homes
+        .create(&codex_home.to_string_lossy(), Some("Codex"))
+        .expect("create home");
+
+    services.settings.set_tracking_paused(true).expect("pause");
+    assert!(services.settings.get().expect("settings").tracking_paused);
+    assert_eq!(services.ingest.run().expect("run").events_inserted, 0);
+    assert!(
+        services
+            .ingest
+            .run_once(IngestTarget::Path(codex_home.clone()), false)
+            .is_err()
+    );
+    let stats = services
+        .ingest
+        .run_once(IngestTarget::Active, true)
+        .expect("dry run");
+    assert_eq!(stats.events_inserted, 1);
+
+    services
+        .settings
+        .set_tracking_paused(false)
+        .expect("resume");
+    assert_eq!(services.ingest.run().expect("run").events_inserted, 1);
+}
+
+#[test]
+fn audit_reports_log_lines_missing_from_the_database() {
+    let dir = tempdir().expect("temp dir");
+    let app_state = AppState::new(
+        dir.path().join("app.sqlite"),
+        dir.path().join("pricing.json"),
+    );
+    app_state.setup_db().expect("setup db");
+
+    let codex_home = dir.path().join("codex");
+    let log_dir = codex_home.join("sessions/2025/12/19");
+    std::fs::create_dir_all(&log_dir).expect("create log dir");
+    let line = |ts: &str, total: u64| {
+        format!(
+            r#"{{"timestamp":"{ts}","type":"event_msg","payload":{{"type":"token_count","info":{{"model":"gpt-test","total_token_usage":{{"input_tokens":{total},"cached_input_tokens":0,"output_tokens":0,"reasoning_output_tokens":0,"total_tokens":{total}}},"model_context_window":100}}}}}}"#
+        )
+    };
+    let log_path = log_dir.join("rollout-2025-12-19T21-31-36.jsonl");
+    std::fs::write(
+        &log_path,
+        format!(
+            "{}\n{}\n",
+            line("2025-12-19T21:31:36.168Z", 10),
+            line("2025-12-19T21:35:00.000Z", 25)
+        ),
+    )
+    .expect("write log");
+    let services = &app_state.services;
+    services
+        .homes
+        .create(&codex_home.to_string_lossy(), Some("Codex"))
+        .expect("create home");
+    services.ingest.run().expect("ingest");
+
+    let range = TimeRange {
+        start: "2025-12-19T00:00:00.000Z".to_string(),
+        end: "2025-12-20T00:00:00.000Z".to_string(),
+    };
+    let report = services.maintenance.audit(&range, None).expect("audit");
+    assert_eq!(report.files_scanned, 1);
+    assert_eq!(report.sources_checked, 1);
+    assert_eq!((report.log.events, report.log.total_tokens), (2, 25));
+    assert_eq!(report.log, report.db);
+    assert!(report.divergences.is_empty());
+
+    // A line ingest has not read yet, as a cursor bug would leave it.
+    let mut contents = std::fs::read_to_string(&log_path).expect("read log");
+    contents.push_str(&format!("{}\n", line("2025-12-19T21:40:00.000Z", 40)));
+    std::fs::write(&log_path, contents).expect("append log");
+    let report = services.maintenance.audit(&range, None).expect("audit");
+    assert_eq!(report.divergences.len(), 1);
+    let divergence = &report.divergences[0];
+    assert_eq!(divergence.source, log_path.to_string_lossy());
+    assert_eq!(
+        (divergence.log.events, divergence.log.total_tokens),
+        (3, 40)
+    );
+    assert_eq!((divergence.db.events, divergence.db.total_tokens), (2, 25));
+}
+
+#[test]
+fn cached_analytics_refresh_after_ingest() {
+    let dir = tempdir().expect("temp dir");
+    let app_state = AppState::new(
+        dir.path().join("app.sqlite"),
+        dir.path().join("pricing.json"),
+    );
+    app_state.setup_db().expect("setup db");
+
+    let codex_home = dir.path().join("codex");
+    let log_dir = codex_home.join("sessions/2025/12/19");
+    std::fs::create_dir_all(&log_dir).expect("create log dir");
+    let line = |ts: &str, total: u64| {
+        format!(
+            r#"{{"timestamp":"{ts}","type":"event_msg","payload":{{"type":"token_count","info":{{"model":"gpt-test","total_token_usage":{{"input_tokens":{total},"cached_input_tokens":0,"output_tokens":0,"reasoning_output_tokens":0,"total_tokens":{total}}},"model_context_window":100}}}}}}"#
+        )
+    };
+    let log = log_dir.join("rollout-2025-12-19T21-31-36.jsonl");
+    std::fs::write(&log, format!("{}\n", line("2025-12-19T21:31:36.168Z", 2))).expect("write log");
+    let services = &app_state.services;
+    services
+        .homes
+        .create(&codex_home.to_string_lossy(), Some("Codex"))
+        .expect("create home");
+    services.ingest.run().expect("run");
+
+    let range = RangeParams {
+        range: None,
+        start: Some("2025-12-19T00:00:00Z".to_string()),
+        end: Some("2025-12-20T00:00:00Z".to_string()),
+    };
+    let total_tokens = || {
+        services
+            .analytics
+            .cached(CacheKey::new("summary", &range, None), || {
+                services.analytics.summary(&resolve_range(&range)?, None)
+            })
+            .expect("summary")
+            .total_tokens
+    };
+    assert_eq!(total_tokens(), 2);
+
+    let mut contents = std::fs::read_to_string(&log).expect("read log");
+    contents.push_str(&format!("{}\n", line("2025-12-19T21:40:00.000Z", 5)));
+    std::fs::write(&log, contents).expect("append log");
+    assert_eq!(total_tokens(), 2);
+    services.ingest.run().expect("run");
+    assert_eq!(total_tokens(), 5);
+}
+
+#[test]
+fn pricing_changes_reprice_stored_costs_and_notify_subscribers() {
+    let dir = tempdir().expect("temp dir");
+    let app_state = AppState::new(
+        dir.path().join("app.sqlite"),
+        dir.path().join("pricing.json"),
+    );
+    app_state.setup_db().expect("setup db");
+
+    let codex_home = dir.path().join("codex");
+    let log_dir = codex_home.join("sessions/2025/12/19");
+    std::fs::create_dir_all(&log_dir).expect("create log dir");
+    std::fs::write(
+        log_dir.join("rollout-2025-12-19T21-31-36.jsonl"),
+        r#"{"timestamp":"2025-12-19T21:31:36.168Z","type":"event_msg","payload":{"type":"token_count","info":{"model":"gpt-test","total_token_usage":{"input_tokens":1000000,"cached_input_tokens":0,"output_tokens":0,"reasoning_output_tokens":0,"total_tokens":1000000},"model_context_window":100}}}
+"#,
+    )
+    .expect("write log");
+    let services = &app_state.services;
+    let home = services
+        .homes
+        .create(&codex_home.to_string_lossy(), Some("Codex"))
+        .expect("create home");
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let log = seen.clone();
+    services
+        .events
+        .subscribe(move |event, _| log.lock().unwrap().push(event.clone()));
+    services.ingest.run().expect("run");
+
+    let range = RangeParams {
+        range: None,
+        start: Some("2025-12-19T00:00:00Z".to_string()),
+        end: Some("2025-12-20T00:00:00Z".to_string()),
+    };
+    let cost = || {
+        services
+            .analytics
+            .cached(CacheKey::new("summary", &range, None), || {
+                services.analytics.summary(&resolve_range(&range)?, None)
+            })
+            .expect("summary")
+            .total_cost_usd
+    };
+    assert_eq!(cost(), None);
+
+    services
+        .pricing
+        .replace_rules(&[PricingRuleInput {
+            model_pattern: "gpt-test".to_string(),
+            input_per_1m: 2.0,
+            cached_input_per_1m: 0.2,
+            output_per_1m: 8.0,
+            effective_from: "2025-01-01T00:00:00Z".to_string(),
+            effective_to: None,
+            provider: None,
+            fallback: false,
+        }])
+        .expect("pricing");
+    assert_eq!(cost(), Some(2.0));
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec![
+            AppEvent::IngestFinished {
+                home_id: Some(home.id),
+                events_inserted: 1,
+                rows_inserted: 1,
+            },
+            AppEvent::CostsRecomputed { home_id: home.id },
+            AppEvent::PricingChanged,
+        ]
+    );
+}
+
+#[test]
+fn home_inspection_counts_session_logs() {
+    let dir = tempdir().expect("temp dir");
+    let app_state = AppState::new(
+        dir.path().join("app.sqlite"),
+        dir.path().join("pricing.json"),
+    );
+    let homes = &app_state.services.homes;
+
+    let codex_home = dir.path().join("codex");
+    let log_dir = codex_home.join("sessions/2025/12/19");
+    std::fs::create_dir_all(&log_dir).expect("create log dir");
+    std::fs::write(log_dir.join("rollout-2025-12-19T21-31-36.jsonl"), "{}\n").expect("write log");
+    let inspection = homes.inspect(&codex_home);
+    assert!(inspection.exists);
+    assert_eq!(inspection.log_files, 1);
+    assert!(inspection.looks_like_codex_home());
+
+    let inspection = homes.inspect(&log_dir);
+    assert!(inspection.exists);
+    assert!(!inspection.looks_like_codex_home());
+    assert!(!homes.inspect(&dir.path().join("missing")).exists);
+}
+
+#[test]
+fn prune_and_compact_manage_database_size() {
+    let dir = tempdir().expect("temp dir");
+    let app_state = AppState::new(
+        dir.path().join("app.sqlite"),
+        dir.path().join("pricing.json"),
+    );
+    app_state.setup_db().expect("setup db");
+    let maintenance = &app_state.services.maintenance;
+
+    assert!(maintenance.prune("last year", None).is_err());
+    assert!(
+        maintenance
+            .prune("2025-01-01", Some(HomeScope::Home(9999)))
+            .is_err()
+    );
+    let pruned = maintenance.prune("2025-01-01", None).expect("prune");
+    assert_eq!(pruned.usage_events, 0);
+
+    let compacted = maintenance.compact().expect("compact");
+    assert!(compacted.bytes_after > 0);
+}
+
+#[test]
+fn ccusage_import_skips_days_already_ingested() {
+    let dir = tempdir().expect("temp dir");
+    let app_state = AppState::new(
+        dir.path().join("app.sqlite"),
+        dir.path().join("pricing.json"),
+    );
+    app_state.setup_db().expect("setup db");
+    let mut db = app_state.open_db().expect("open db");
+    let home = db
+        .get_or_create_home(&dir.path().to_string_lossy(), Some("Default"))
+        .expect("home");
+    db.set_active_home(home.id).expect("active home");
+    let noon = Local
+        .with_ymd_and_hms(2025, 6, 10, 12, 0, 0)
+        .single()
+        .expect("local noon")
+        .with_timezone(&Utc);
+    let usage = UsageTotals {
+        input_tokens: 10,
+        total_tokens: 10,
+        ..UsageTotals::default()
+    };
+    let event = UsageEvent {
+        id: "logged".to_string(),
+        ts: noon.into(),
+        model: "gpt-5.1".to_string(),
+        usage,
+        context: ContextStatus::default(),
+        cost_usd: None,
+        cost_source: None,
+        reasoning_effort: None,
+        provider: None,
+        source: "rollout.jsonl".to_string(),
+        session_id: session_id_from_source("rollout.jsonl"),
+        request_id: None,
+        raw_json: None,
+    };
+    db.insert_usage_events(home.id, std::slice::from_ref(&event))
+        .expect("insert events");
+
+    let report = r#"{"daily": [
+        {"date": "2025-06-09", "inputTokens": 100, "outputTokens": 5, "cacheReadTokens": 20,
+         "modelBreakdowns": [{"modelName": "claude-sonnet-4", "inputTokens": 100,
+                              "outputTokens": 5, "cacheReadTokens": 20, "cost": 0.25}]},
+        {"date": "2025-06-10", "inputTokens": 50, "outputTokens": 5, "modelsUsed": ["gpt-5.1"]}
+    ]}"#;
+    let ingest = &app_state.services.ingest;
+    let stats = ingest.import_ccusage(report, None).expect("import");
+    assert_eq!(stats.imported, 1);
+    assert_eq!(stats.overlapping, 1);
+    let again = ingest.import_ccusage(report, None).expect("import again");
+    assert_eq!(again.imported, 0);
+    assert_eq!(again.duplicates, 1);
+    assert!(ingest.import_ccusage(r#"{"blocks": []}"#, None).is_err());
+
+    let range = TimeRange {
+        start: "2025-06-01T00:00:00Z".to_string(),
+        end: "2025-07-01T00:00:00Z".to_string(),
+    };
+    let summary = app_state
+        .services
+        .analytics
+        .summary(&range, None)
+        .expect("summary");
+    assert_eq!(summary.total_tokens, 135);
+}
+
+#[test]
+fn merge_rejects_files_that_are_not_other_tracker_databases() {
+    let dir = tempdir().expect("temp dir");
+    let db_path = dir.path().join("app.sqlite");
+    let app_state = AppState::new(db_path.clone(), dir.path().join("pricing.json"));
+    app_state.setup_db().expect("setup db");
+    let maintenance = &app_state.services.maintenance;
+
+    assert!(
+        maintenance
+            .merge(&dir.path().join("missing.sqlite"), None)
+            .is_err()
+    );
+    assert!(maintenance.merge(&db_path, None).is_err());
+    let not_db = dir.path().join("notes.txt");
+    std::fs::write(&not_db, "not a database").expect("write");
+    assert!(maintenance.merge(&not_db, None).is_err());
+
+    let other = AppState::new(
+        dir.path().join("other.sqlite"),
+        dir.path().join("other-pricing.json"),
+    );
+    other.setup_db().expect("setup other");
+    let merged = maintenance
+        .merge(&dir.path().join("other.sqlite"), None)
+        .expect("merge");
+    assert_eq!(merged.usage_events, 0);
+    assert!(
+        maintenance
+            .merge(&dir.path().join("other.sqlite"), Some(" "))
+            .is_err()
+    );
+}
+
+#[test]
+fn merge_leaves_the_other_database_untouched() {
+    let dir = tempdir().expect("temp dir");
+    let app_state = AppState::new(
+        dir.path().join("app.sqlite"),
+        dir.path().join("pricing.json"),
+    );
+    app_state.setup_db().expect("setup db");
+    let other = AppState::new(
+        dir.path().join("other.sqlite"),
+        dir.path().join("other-pricing.json"),
+    );
+    other.setup_db().expect("setup other");
+    // A snapshot, like a backup someone hands over, is in rollback mode.
+    let backup = dir.path().join("backup.sqlite");
+    tracker_db::Db::open(dir.path().join("other.sqlite"))
+        .expect("open other")
+        .copy_to(&backup)
+        .expect("snapshot");
+    let before = std::fs::read(&backup).expect("read backup");
+
+    app_state
+        .services
+        .maintenance
+        .merge(&backup, None)
+        .expect("merge");
+
+    assert_eq!(std::fs::read(&backup).expect("read backup"), before);
+    let mut wal = backup.into_os_string();
+    wal.push("-wal");
+    assert!(!std::path::Path::new(&wal).exists());
+}
+
+#[test]
+fn notification_channels_are_configurable() {
+    let dir = tempdir().expect("temp dir");
+    let app_state = AppState::new(
+        dir.path().join("app.sqlite"),
+        dir.path().join("pricing.json"),
+    );
+    app_state.setup_db().expect("setup db");
+    let notifications = &app_state.services.notifications;
+
+    assert!(notifications.send_test().is_err());
+    assert!(
+        notifications
+            .set_channel_url(Channel::Slack, "http://hooks.slack.com/x")
+            .is_err()
+    );
+
+    notifications
+        .set_channel_url(Channel::Slack, "https://hooks.slack.com/services/x")
+        .expect("set slack");
+    notifications
+        .set_daily_summary(true)
+        .expect("daily summary");
+    let settings = notifications.settings().expect("settings");
+    assert_eq!(
+        settings.slack_webhook_url.as_deref(),
+        Some("https://hooks.slack.com/services/x")
+    );
+    assert!(settings.discord_webhook_url.is_none());
+    assert!(settings.daily_summary);
+
+    notifications
+        .set_channel_url(Channel::Slack, "")
+        .expect("clear slack");
+    assert!(!notifications.has_channels().expect("has channels"));
+}
+
+#[test]
+fn statsd_exporter_sends_gauges() {
+    let dir = tempdir().expect("temp dir");
+    let app_state = AppState::new(
+        dir.path().join("app.sqlite"),
+        dir.path().join("pricing.json"),
+    );
+    app_state.setup_db().expect("setup db");
+
+    let agent = UdpSocket::bind("127.0.0.1:0").expect("bind agent");
+    agent
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .expect("timeout");
+    let exporter = StatsdExporter::connect(StatsdConfig {
+        address: agent.local_addr().expect("addr").to_string(),
+        prefix: "codex".to_string(),
+        interval: Duration::from_secs(60),
+        tags: vec!["env:test".to_string()],
+    })
+    .expect("connect");
+
+    let sent = exporter.flush(&app_state.services).expect("flush");
+    assert!(sent > 0);
+    let mut buf = [0u8; 2048];
+    let len = agent.recv(&mut buf).expect("datagram");
+    let payload = String::from_utf8_lossy(&buf[..len]);
+    assert!(payload.contains("codex.tokens.today:0|g|#env:test"));
+}
+
+#[test]
+fn desktop_settings_have_defaults_and_persist() {
+    let dir = tempdir().expect("temp dir");
+    let app_state = AppState::new(
+        dir.path().join("app.sqlite"),
+        dir.path().join("pricing.json"),
+    );
+    app_state.setup_db().expect("setup db");
+    let settings = &app_state.services.settings;
+    let snapshot = settings.get().expect("settings");
+    assert_eq!(snapshot.auto_ingest_secs, 60);
+    assert!(!snapshot.launch_at_login);
+    assert_eq!(snapshot.global_shortcut, "CommandOrControl+Shift+U");
+
+    settings
+        .update(None, None, None, Some(0), Some(true), Some(" "))
+        .expect("update desktop settings");
+    let snapshot = settings.get().expect("settings");
+    assert_eq!(snapshot.auto_ingest_secs, 0);
+    assert!(snapshot.launch_at_login);
+    assert_eq!(snapshot.global_shortcut, "");
+    assert_eq!(snapshot.context_active_minutes, 60);
+}
+
+#[test]
+fn settings_bundle_reproduces_a_setup_elsewhere() {
+    let dir = tempdir().expect("temp dir");
+    let source = AppState::new(
+        dir.path().join("source.sqlite"),
+        dir.path().join("source-pricing.json"),
+    );
+    source.setup_db().expect("setup source");
+    let services = &source.services;
+    services
+        .settings
+        .update(None, Some(30), Some(50.0), Some(120), Some(true), None)
+        .expect("update settings");
+    services
+        .notifications
+        .set_channel_url(Channel::Slack, "https://hooks.slack.com/services/x")
+        .expect("set slack");
+    let work = services
+        .homes
+        .create("/tmp/bundle-work", Some("Work"))
+        .expect("work home");
+    let old = services
+        .homes
+        .create("/tmp/bundle-old", Some("Old"))
+        .expect("old home");
+    services.homes.archive(old.id).expect("archive");
+    services.homes.set_active(work.id).expect("activate work");
+    services
+        .webhooks
+        .create(
+            "https://example.com/hook",
+            Some("s3cret"),
+            &["budget.exceeded".to_string()],
+        )
+        .expect("webhook");
+    services
+        .pricing
+        .replace_rules(&[PricingRuleInput {
+            model_pattern: "gpt-5".to_string(),
+            input_per_1m: 1.25,
+            cached_input_per_1m: 0.125,
+            output_per_1m: 10.0,
+            effective_from: "2025-01-01T00:00:00Z".to_string(),
+            effective_to: None,
+            provider: None,
+            fallback: false,
+        }])
+        .expect("pricing");
+    let bundle = bundle::export_bundle(services).expect("export");
+
+    let target = AppState::new(
+        dir.path().join("target.sqlite"),
+        dir.path().join("target-pricing.json"),
+    );
+    target.setup_db().expect("setup target");
+    let json = serde_json::to_string(&bundle).expect("serialize");
+    let mut bad = bundle.clone();
+    bad.version = bundle::BUNDLE_VERSION + 1;
+    assert!(bundle::import_bundle(&target.services, &bad).is_err());
+
+    let parsed = serde_json::from_str(&json).expect("parse");
+    let stats = bundle::import_bundle(&target.services, &parsed).expect("import");
+    assert_eq!(stats.pricing_rules, 1);
+    assert_eq!(stats.webhooks_created, 1);
+    let snapshot = target.services.settings.get().expect("settings");
+    assert_eq!(snapshot.codex_home, "/tmp/bundle-work");
+    assert_eq!(snapshot.context_active_minutes, 30);
+    assert_eq!(snapshot.monthly_budget_usd, Some(50.0));
+    assert!(snapshot.launch_at_login);
+    let homes = target.services.homes.list().expect("homes");
+    let imported_old = homes
+        .iter()
+        .find(|home| home.path == "/tmp/bundle-old")
+        .expect("old home imported");
+    assert!(imported_old.archived);
+    assert_eq!(imported_old.label, "Old");
+    let hooks = target.services.webhooks.list().expect("webhooks");
+    assert_eq!(hooks[0].secret.as_deref(), Some("s3cret"));
+
+    // Importing again adds nothing new.
+    let again = bundle::import_bundle(&target.services, &parsed).expect("reimport");
+    assert_eq!((again.homes_created, again.webhooks_created), (0, 0));
+}
+
+#[test]
+fn compactions_count_sharp_context_drops_per_session_and_day() {
+    let dir = tempdir().expect("temp dir");
+    let app_state = AppState::new(
+        dir.path().join("app.sqlite"),
+        dir.path().join("pricing.json"),
+    );
+    app_state.setup_db().expect("setup db");
+    let home = app_state.services.homes.active().expect("active home");
+    let mut db = app_state.open_db().expect("open db");
+    let event = |id: &str, ts: &str, source: &str, context_used: u64| UsageEvent {
+        id: id.to_string(),
+        ts: ts.parse().expect("timestamp"),
+        model: "gpt-5.2".to_string(),
+        usage: UsageTotals::default(),
+        context: ContextStatus {
+            context_used,
+            context_window: 200_000,
+        },
+        cost_usd: None,
+        cost_source: None,
+        reasoning_effort: None,
+        provider: None,
+        source: source.to_string(),
+        session_id: session_id_from_source(source),
+        request_id: None,
+        raw_json: None,
+    };
+    db.insert_usage_events(
+        home.id,
+        &[
+            // Compacted twice: before and after midnight.
+            event("a1", "2025-12-19T23:00:00.000Z", "source-a", 150_000),
+            event("a2", "2025-12-19T23:10:00.000Z", "source-a", 30_000),
+            event("a3", "2025-12-20T01:00:00.000Z", "source-a", 120_000),
+            event("a4", "2025-12-20T01:05:00.000Z", "source-a", 25_000),
+            // Small contexts and a new session starting small do not count.
+            event("b1", "2025-12-20T02:00:00.000Z", "source-b", 15_000),
+            event("b2", "2025-12-20T02:05:00.000Z", "source-b", 2_000),
+            event("c1", "2025-12-20T03:00:00.000Z", "source-c", 90_000),
+            event("c2", "2025-12-20T03:05:00.000Z", "source-c", 50_000),
+        ],
+    )
+    .expect("insert events");
+
+    let range = TimeRange {
+        start: "2025-12-19T00:00:00Z".to_string(),
+        end: "2025-12-21T00:00:00Z".to_string(),
+    };
+    let report = app_state
+        .services
+        .analytics
+        .compactions(&range, None, Some(0))
+        .expect("compactions");
+    assert_eq!(report.total, 2);
+    assert_eq!(report.sessions.len(), 1);
+    assert_eq!(report.sessions[0].session_id, "source-a");
+    assert_eq!(report.sessions[0].compactions, 2);
+    assert_eq!(
+        report.sessions[0].last_compacted_at,
+        "2025-12-20T01:05:00.000Z"
+    );
+    let days = report
+        .days
+        .iter()
+        .map(|day| (day.day.as_str(), day.compactions))
+        .collect::<Vec<_>>();
+    assert_eq!(days, vec![("2025-12-19", 1), ("2025-12-20", 1)]);
+}
+
+#[test]
+fn custom_limits_track_usage_over_their_calendar_period() {
+    let dir = tempdir().expect("temp dir");
+    let app_state = AppState::new(
+        dir.path().join("app.sqlite"),
+        dir.path().join("pricing.json"),
+    );
+    app_state.setup_db().expect("setup db");
+    let home = app_state.services.homes.active().expect("active home");
+    let limits = &app_state.services.limits;
+
+    assert!(
+        limits
+            .create_custom_limit("Daily", "day", "tokens", 1_000.0)
+            .is_err()
+    );
+    assert!(
+        limits
+            .create_custom_limit("Monthly", "month", "tokens", 0.0)
+            .is_err()
+    );
+    let monthly = limits
+        .create_custom_limit(" Monthly tokens ", "month", "tokens", 1_000.0)
+        .expect("create limit");
+    assert_eq!(monthly.name, "Monthly tokens");
+    limits
+        .create_custom_limit("Weekly spend", "week", "cost_usd", 10.0)
+        .expect("create limit");
+
+    let mut db = app_state.open_db().expect("open db");
+    let now = Utc::now();
+    db.insert_usage_events(
+        home.id,
+        &[UsageEvent {
+            id: "e1".to_string(),
+            ts: now.into(),
+            model: "gpt-5.2".to_string(),
+            usage: UsageTotals {
+                total_tokens: 250,
+                ..UsageTotals::default()
+            },
+            context: ContextStatus {
+                context_used: 250,
+                context_window: 200_000,
+            },
+            cost_usd: None,
+            cost_source: None,
+            reasoning_effort: None,
+            provider: None,
+            source: "source-a".to_string(),
+            session_id: session_id_from_source("source-a"),
+            request_id: None,
+            raw_json: None,
+        }],
+    )
+    .expect("insert events");
+
+    let current = limits.current().expect("current");
+    assert_eq!(current.custom.len(), 2);
+    let window = &current.custom[0];
+    assert_eq!(window.limit.id, monthly.id);
+    assert_eq!(window.used, 250.0);
+    assert_eq!(window.percent_left, 75.0);
+    let now = now.to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    assert!(window.window_start <= now && now < window.window_end);
+    assert_eq!(current.custom[1].percent_left, 100.0);
+
+    assert_eq!(limits.delete_custom_limit(monthly.id).expect("delete"), 1);
+    assert_eq!(limits.custom_limits().expect("list").len(), 1);
+}
+
+#[test]
+fn activity_splits_sessions_at_idle_gaps_and_midnight() {
+    let dir = tempdir().expect("temp dir");
+    let app_state = AppState::new(
+        dir.path().join("app.sqlite"),
+        dir.path().join("pricing.json"),
+    );
+    app_state.setup_db().expect("setup db");
+    let home = app_state.services.homes.active().expect("active home");
+    let mut db = app_state.open_db().expect("open db");
+    let event = |id: &str, ts: &str, source: &str| UsageEvent {
+        id: id.to_string(),
+        ts: ts.parse().expect("timestamp"),
+        model: "gpt-5.2".to_string(),
+        usage: UsageTotals::default(),
+        context: ContextStatus::default(),
+        cost_usd: None,
+        cost_source: None,
+        reasoning_effort: None,
+        provider: None,
+        source: source.to_string(),
+        session_id: session_id_from_source(source),
+        request_id: None,
+        raw_json: None,
+    };
+    db.insert_usage_events(
+        home.id,
+        &[
+            // 20 minutes spanning midnight, then a new segment after 3 hours.
+            event("a1", "2025-12-19T23:50:00.000Z", "source-a"),
+            event("a2", "2025-12-20T00:10:00.000Z", "source-a"),
+            event("a3", "2025-12-20T03:10:00.000Z", "source-a"),
+            event("a4", "2025-12-20T03:15:00.000Z", "source-a"),
+            event("b1", "2025-12-20T09:00:00.000Z", "source-b"),
+        ],
+    )
+    .expect("insert events");
+    db.insert_message_events(
+        home.id,
+        &[MessageEvent {
+            id: "m1".to_string(),
+            ts: "2025-12-20T08:58:00.000Z".parse().expect("timestamp"),
+            role: "user".to_string(),
+            source: "source-b".to_string(),
+            session_id: session_id_from_source("source-b"),
+            raw_json: None,
+        }],
+    )
+    .expect("insert messages");
+
+    let range = TimeRange {
+        start: "2025-12-19T00:00:00Z".to_string(),
+        end: "2025-12-21T00:00:00Z".to_string(),
+    };
+    let report = app_state
+        .services
+        .analytics
+        .activity(&range, None, None, Some(0))
+        .expect("activity");
+    assert_eq!(report.idle_gap_minutes, 30);
+    assert_eq!(report.total_active_secs, 27 * 60);
+    assert_eq!(report.sessions[0].session_id, "source-a");
+    assert_eq!(report.sessions[0].active_secs, 25 * 60);
+    assert_eq!(report.sessions[0].segments.len(), 2);
+    assert_eq!(report.sessions[1].active_secs, 2 * 60);
+    let days = report
+        .days
+        .iter()
+        .map(|day| (day.day.as_str(), day.active_secs, day.sessions))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        days,
+        vec![("2025-12-19", 10 * 60, 1), ("2025-12-20", 17 * 60, 2)]
+    );
+
+    let strict = app_state
+        .services
+        .analytics
+        .activity(&range, Some(1), None, Some(0))
+        .expect("strict activity");
+    assert_eq!(strict.total_active_secs, 0);
+}
+
+#[test]
+fn active_session_rule_hides_idle_and_closed_sessions() {
+    let dir = tempdir().expect("temp dir");
+    let app_state = AppState::new(
+        dir.path().join("app.sqlite"),
+        dir.path().join("pricing.json"),
+    );
+    app_state.setup_db().expect("setup db");
+    let home = app_state.services.homes.active().expect("active home");
+    let mut db = app_state.open_db().expect("open db");
+    let recent = (Utc::now() - chrono::Duration::minutes(5)).to_rfc3339();
+    let event = |source: &str, model: &str, total_tokens: u64, context_used: u64| UsageEvent {
+        id: source.to_string(),
+        ts: recent.parse().expect("timestamp"),
+        model: model.to_string(),
+        usage: UsageTotals {
+            total_tokens,
+            ..UsageTotals::default()
+        },
+        context: ContextStatus {
+            context_used,
+            context_window: 1000,
+        },
+        cost_usd: None,
+        cost_source: None,
+        reasoning_effort: None,
+        provider: None,
+        source: source.to_string(),
+        session_id: session_id_from_source(source),
+        request_id: None,
+        raw_json: None,
+    };
+    db.insert_usage_events(
+        home.id,
+        &[
+            event("idle", "gpt-5.1-codex", 3000, 500),
+            event("full", "gpt-5.1-codex", 8000, 1000),
+            event("small", "gpt-5.2", 600, 500),
+        ],
+    )
+    .expect("insert events");
+
+    let services = &app_state.services;
+    let active = || {
+        let mut ids = services
+            .analytics
+            .context_sessions(Some(60), None)
+            .expect("context sessions")
+            .into_iter()
+            .map(|session| session.session_id)
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids
+    };
+    assert_eq!(active(), ["full", "idle", "small"]);
+
+    services
+        .settings
+        .set_active_session_rule(&ActiveSessionRule {
+            require_open_context: true,
+            min_tokens: vec![ActiveMinTokens {
+                model_pattern: "gpt-5*-codex".to_string(),
+                min_tokens: 5000,
+            }],
+        })
+        .expect("set rule");
+    assert_eq!(active(), ["small"]);
+    assert!(
+        services
+            .settings
+            .set_active_session_rule(&ActiveSessionRule {
+                require_open_context: false,
+                min_tokens: vec![ActiveMinTokens {
+                    model_pattern: " ".to_string(),
+                    min_tokens: 1,
+                }],
+            })
+            .is_err()
+    );
+}
+
+#[test]
+fn session_lifetimes_tell_shutdowns_from_abandoned_sessions() {
+    let dir = tempdir().expect("temp dir");
+    let app_state = AppState::new(
+        dir.path().join("app.sqlite"),
+        dir.path().join("pricing.json"),
+    );
+    app_state.setup_db().expect("setup db");
+
+    let codex_home = dir.path().join("codex");
+    let log_dir = codex_home.join("sessions/2025/12/19");
+    std::fs::create_dir_all(&log_dir).expect("create log dir");
+    let tokens = |ts: &str, total: u64| {
+        format!(
+            r#"{{"timestamp":"{ts}","type":"event_msg","payload":{{"type":"token_count","info":{{"model":"gpt-test","total_token_usage":{{"input_tokens":{total},"cached_input_tokens":0,"output_tokens":0,"reasoning_output_tokens":0,"total_tokens":{total}}},"model_context_window":100}}}}}}"#
+        )
+    };
+    let shutdown = r#"{"timestamp":"2025-12-19T21:45:00.000Z","type":"event_msg","payload":{"type":"shutdown_complete"}}"#;
+    std::fs::write(
+        log_dir.join("rollout-2025-12-19T21-31-36.jsonl"),
+        [
+            tokens("2025-12-19T21:31:36.000Z", 2),
+            tokens("2025-12-19T21:41:36.000Z", 5),
+            shutdown.to_string(),
+        ]
+        .join("\n"),
+    )
+    .expect("write completed log");
+    std::fs::write(
+        log_dir.join("rollout-2025-12-19T22-00-00.jsonl"),
+        [
+            tokens("2025-12-19T22:00:00.000Z", 3),
+            tokens("2025-12-19T22:10:00.000Z", 7),
+        ]
+        .join("\n"),
+    )
+    .expect("write abandoned log");
+    let services = &app_state.services;
+    services
+        .homes
+        .create(&codex_home.to_string_lossy(), Some("Codex"))
+        .expect("create home");
+    services.ingest.run().expect("run");
+
+    let range = TimeRange {
+        start: "2025-12-19T00:00:00Z".to_string(),
+        end: "2025-12-20T00:00:00Z".to_string(),
+    };
+    let report = services
+        .analytics
+        .session_lifetimes(&range, None, None)
+        .expect("session lifetimes");
+    assert_eq!(report.idle_minutes, 30);
+    assert_eq!(report.sessions, 2);
+    assert_eq!(report.completed, 1);
+    assert_eq!(report.abandoned, 1);
+    assert_eq!(report.open, 0);
+    // 13m24s to the shutdown and 10m to the last event.
+    assert_eq!(report.avg_length_secs, Some(702.0));
+    assert_eq!(report.sessions_per_day, 2.0);
+    assert!(
+        services
+            .analytics
+            .session_lifetimes(&range, Some(0), None)
+            .is_err()
+    );
 }
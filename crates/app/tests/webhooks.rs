@@ -0,0 +1,153 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+
+use serde_json::json;
+use tempfile::tempdir;
+use tracker_app::AppState;
+use tracker_app::webhooks::{EVENT_INGEST_COMPLETED, SIGNATURE_HEADER, sign_payload};
+
+struct CapturedRequest {
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+/// Accepts one connection, records the request, and replies with `status`.
+fn serve_once(status: u16) -> (String, mpsc::Receiver<CapturedRequest>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let url = format!("http://{}/hook", listener.local_addr().expect("addr"));
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().expect("accept");
+        let mut reader = BufReader::new(stream.try_clone().expect("clone"));
+        let mut headers = Vec::new();
+        let mut line = String::new();
+        reader.read_line(&mut line).expect("request line");
+        loop {
+            line.clear();
+            reader.read_line(&mut line).expect("header");
+            let trimmed = line.trim_end();
+            if trimmed.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = trimmed.split_once(':') {
+                headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+            }
+        }
+        let length = headers
+            .iter()
+            .find(|(name, _)| name == "content-length")
+            .and_then(|(_, value)| value.parse::<usize>().ok())
+            .unwrap_or(0);
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).expect("body");
+        let mut stream = stream;
+        write!(
+            stream,
+            "HTTP/1.1 {status} OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+        )
+        .expect("write response");
+        tx.send(CapturedRequest {
+            headers,
+            body: String::from_utf8(body).expect("utf8 body"),
+        })
+        .expect("send capture");
+    });
+    (url, rx)
+}
+
+fn setup_app() -> (tempfile::TempDir, AppState) {
+    let dir = tempdir().expect("temp dir");
+    let app_state = AppState::new(
+        dir.path().join("app.sqlite"),
+        dir.path().join("pricing.json"),
+    );
+    app_state.setup_db().expect("setup db");
+    (dir, app_state)
+}
+
+#[test]
+fn delivers_signed_payload_and_logs_success() {
+    let (_dir, app_state) = setup_app();
+    let (url, captured) = serve_once(200);
+    let webhooks = &app_state.services.webhooks;
+    webhooks
+        .create(&url, Some("s3cret"), &[EVENT_INGEST_COMPLETED.to_string()])
+        .expect("create webhook");
+
+    let ids = webhooks
+        .enqueue(EVENT_INGEST_COMPLETED, json!({ "events_inserted": 3 }))
+        .expect("enqueue");
+    assert_eq!(ids.len(), 1);
+    let delivery = webhooks.deliver(ids[0]).expect("deliver");
+
+    assert_eq!(delivery.status, "delivered");
+    assert_eq!(delivery.attempts, 1);
+    assert_eq!(delivery.response_status, Some(200));
+
+    let request = captured.recv().expect("captured request");
+    let payload: serde_json::Value = serde_json::from_str(&request.body).expect("json");
+    assert_eq!(payload["event"], EVENT_INGEST_COMPLETED);
+    assert_eq!(payload["data"]["events_inserted"], 3);
+    let signature = request
+        .headers
+        .iter()
+        .find(|(name, _)| name == SIGNATURE_HEADER)
+        .map(|(_, value)| value.clone())
+        .expect("signature header");
+    assert_eq!(signature, sign_payload("s3cret", &request.body));
+}
+
+#[test]
+fn client_errors_fail_without_retry() {
+    let (_dir, app_state) = setup_app();
+    let (url, _captured) = serve_once(404);
+    let webhooks = &app_state.services.webhooks;
+    webhooks
+        .create(&url, None, &[EVENT_INGEST_COMPLETED.to_string()])
+        .expect("create webhook");
+
+    let ids = webhooks
+        .enqueue(EVENT_INGEST_COMPLETED, json!({}))
+        .expect("enqueue");
+    let delivery = webhooks.deliver(ids[0]).expect("deliver");
+
+    assert_eq!(delivery.status, "failed");
+    assert_eq!(delivery.attempts, 1);
+    assert_eq!(delivery.response_status, Some(404));
+    let log = webhooks.deliveries(None, 10).expect("deliveries");
+    assert_eq!(log.len(), 1);
+}
+
+#[test]
+fn rejects_unknown_event_types() {
+    let (_dir, app_state) = setup_app();
+    let err = app_state
+        .services
+        .webhooks
+        .create(
+            "https://example.com/hook",
+            None,
+            &["usage.spike".to_string()],
+        )
+        .expect_err("unknown event type");
+    assert!(err.to_string().contains("usage.spike"));
+}
+
+#[test]
+fn skips_webhooks_not_subscribed_to_event() {
+    let (_dir, app_state) = setup_app();
+    let webhooks = &app_state.services.webhooks;
+    webhooks
+        .create(
+            "https://example.com/hook",
+            None,
+            &["budget.exceeded".to_string()],
+        )
+        .expect("create webhook");
+    let ids = webhooks
+        .enqueue(EVENT_INGEST_COMPLETED, json!({}))
+        .expect("enqueue");
+    assert!(ids.is_empty());
+}
@@ -1,20 +1,48 @@
+use std::io::Write;
 use std::path::PathBuf;
 
 use ingest::IngestStats;
+use tracker_app::audit::AuditReport;
+use tracker_app::badge::{Badge, BadgeKind};
+use tracker_app::bundle::{self, SettingsBundle};
+use tracker_app::ccusage::{CcusageKind, CcusageReport};
+use tracker_app::export::RecordFormat;
+use tracker_app::notifications::Channel;
+use tracker_app::report::{ReportFormat, ReportPeriod};
+use tracker_app::services::{AnalyticsService, CacheKey, DashboardQuery, IngestTarget};
+use tracker_app::version::{VersionInfo, version_info};
 use tracker_app::{AppError, RangeParams, Result};
 use tracker_core::{
-    ActiveSession, ContextPressureStats, ContextStatus, ModelBreakdown, ModelCostBreakdown,
-    ModelEffortCostBreakdown, ModelEffortTokenBreakdown, ModelTokenBreakdown, TimeRange,
-    TimeSeriesPoint, UsageEvent, UsageSummary,
+    ActiveSession, ActivityReport, ApiToken, CompactionReport, ContextPressureStats, ContextStatus,
+    CostRecomputeProgress, CustomLimit, GroupedTimeSeries, HomeBreakdown, HomeScope,
+    LimitThresholds, ModelAlias, ModelBreakdown, ModelCostBreakdown, ModelEffortCostBreakdown,
+    ModelEffortTokenBreakdown, ModelTokenBreakdown, ProviderBreakdown, RangeComparison,
+    RequestBreakdown, Schedule, ScheduleInput, SessionBreakdown, SessionContextPeak,
+    SessionLifetimeReport, SessionTimeline, TimeRange, TimeSeriesPoint, Timestamp,
+    UsageLimitSnapshot, UsageSummary, UserBreakdown, Webhook, WebhookDelivery, WeekdayHourAverages,
+    format_duration,
 };
-use tracker_db::{Bucket, Metric};
+use tracker_db::{Bucket, GroupBy, Metric};
 
 use crate::{
-    AppContext, ClearedResponse, ContextSessionsRequest, DeletedResponse, EventsRequest,
-    HomesClearDataRequest, HomesCreateRequest, HomesDeleteRequest, HomesResponse,
-    HomesSetActiveRequest, LimitsResponse, LimitsWindowsRequest, OkResponse, PricingReplaceRequest,
-    PricingRuleResponse, RangeRequest, SettingsPutRequest, SettingsResponse, TimeseriesRequest,
-    UpdatedResponse, expand_home_path,
+    ActivityByWeekdayRequest, ActivityRequest, ApiTokenCreatedResponse, ApiTokensCreateRequest,
+    ApiTokensRevokeRequest, AppContext, AuditRequest, CcusageRequest, ClearedResponse,
+    CompactResponse, CompactionsRequest, CompareRequest, ContextPeaksRequest,
+    ContextSessionsRequest, CustomLimitsCreateRequest, CustomLimitsDeleteRequest, DashboardRequest,
+    DashboardResponse, DeletedResponse, EventDetailResponse, EventRequest, EventsRequest,
+    EventsResponse, HealthResponse, HomeCandidateResponse, HomeRequest, HomesArchiveRequest,
+    HomesClearDataRequest, HomesCreateRequest, HomesDeleteRequest, HomesInspectRequest,
+    HomesInspectResponse, HomesRenameRequest, HomesResponse, HomesSetActiveRequest,
+    HomesUpdateRequest, ImportRequest, ImportResponse, IngestCursorResponse,
+    IngestCursorsResetRequest, LimitStatus, LimitsResponse, LimitsSnapshotsRequest,
+    LimitsWindowsRequest, MergeRequest, MergeResponse, ModelAliasesReplaceRequest, OkResponse,
+    PricingRecomputeResponse, PricingReplaceRequest, PricingRuleResponse, PruneRequest,
+    PruneResponse, RangeRequest, RefreshRequest, RefreshResponse, ReportRequest, ResetResponse,
+    RevokedResponse, SchedulesCreateRequest, SchedulesDeleteRequest, SchedulesSendRequest,
+    SentResponse, SessionLifetimesRequest, SessionTimelineRequest, SessionsRequest,
+    SettingsImportResponse, SettingsPutRequest, SettingsResponse, TimeseriesGroupedRequest,
+    TimeseriesRequest, TopRequestsRequest, UpdatedResponse, WebhookDeliveriesRequest,
+    WebhooksCreateRequest, WebhooksDeleteRequest, expand_home_path,
 };
 
 fn resolve_range(
@@ -22,7 +50,29 @@ fn resolve_range(
     start: Option<String>,
     end: Option<String>,
 ) -> Result<TimeRange> {
-    tracker_app::resolve_range(&RangeParams { range, start, end })
+    tracker_app::resolve_range(&range_params(range, start, end))
+}
+
+fn range_params(range: Option<String>, start: Option<String>, end: Option<String>) -> RangeParams {
+    RangeParams { range, start, end }
+}
+
+/// Serves a hot aggregate from the analytics response cache. The range is
+/// only resolved on a miss, so relative ranges such as `today` share an
+/// entry until it expires or new usage is ingested.
+fn cached<T>(
+    ctx: &AppContext,
+    key: CacheKey,
+    range: &RangeParams,
+    compute: impl FnOnce(&AnalyticsService, &TimeRange) -> Result<T>,
+) -> Result<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    let analytics = &ctx.app_state.services.analytics;
+    analytics.cached(key, || {
+        compute(analytics, &tracker_app::resolve_range(range)?)
+    })
 }
 
 fn parse_bucket(bucket: Option<String>) -> Result<Bucket> {
@@ -47,13 +97,48 @@ fn parse_metric(metric: Option<String>) -> Result<Metric> {
     }
 }
 
+fn parse_group_by(group_by: &str) -> Result<GroupBy> {
+    match group_by {
+        "model" => Ok(GroupBy::Model),
+        "effort" => Ok(GroupBy::Effort),
+        value => Err(AppError::InvalidInput(format!(
+            "unsupported group_by {}",
+            value
+        ))),
+    }
+}
+
+/// Opaque value that changes whenever a home's aggregates may change.
+pub fn usage_fingerprint(ctx: &AppContext, home: Option<HomeScope>) -> Result<String> {
+    ctx.app_state.services.analytics.fingerprint(home)
+}
+
 pub fn summary(ctx: &AppContext, req: RangeRequest) -> Result<UsageSummary> {
+    let range = range_params(req.range, req.start, req.end);
+    let key = CacheKey::new("summary", &range, req.home_id);
+    cached(ctx, key, &range, |analytics, range| {
+        analytics.summary(range, req.home_id)
+    })
+}
+
+pub fn compare(ctx: &AppContext, req: CompareRequest) -> Result<RangeComparison> {
     let range = resolve_range(req.range, req.start, req.end)?;
-    ctx.app_state.services.analytics.summary(&range)
+    let baseline = if req.to_range.is_some() || req.to_start.is_some() {
+        Some(resolve_range(req.to_range, req.to_start, req.to_end)?)
+    } else {
+        None
+    };
+    ctx.app_state
+        .services
+        .analytics
+        .compare(&range, baseline.as_ref(), req.home_id)
 }
 
-pub fn context_latest(ctx: &AppContext) -> Result<Option<ContextStatus>> {
-    ctx.app_state.services.analytics.context_latest()
+pub fn context_latest(ctx: &AppContext, req: HomeRequest) -> Result<Option<ContextStatus>> {
+    ctx.app_state
+        .services
+        .analytics
+        .context_latest(req.home_id.map(HomeScope::Home))
 }
 
 pub fn context_sessions(
@@ -63,80 +148,451 @@ pub fn context_sessions(
     ctx.app_state
         .services
         .analytics
-        .context_sessions(req.active_minutes)
+        .context_sessions(req.active_minutes, req.home_id.map(HomeScope::Home))
 }
 
 pub fn context_stats(ctx: &AppContext, req: RangeRequest) -> Result<ContextPressureStats> {
-    let range = resolve_range(req.range, req.start, req.end)?;
-    ctx.app_state.services.analytics.context_stats(&range)
+    let range = range_params(req.range, req.start, req.end);
+    let key = CacheKey::new("context_stats", &range, req.home_id);
+    cached(ctx, key, &range, |analytics, range| {
+        analytics.context_stats(range, req.home_id)
+    })
 }
 
 pub fn timeseries(ctx: &AppContext, req: TimeseriesRequest) -> Result<Vec<TimeSeriesPoint>> {
-    let range = resolve_range(req.range, req.start, req.end)?;
+    let range = range_params(req.range, req.start, req.end);
     let bucket = parse_bucket(req.bucket)?;
     let metric = parse_metric(req.metric)?;
-    ctx.app_state
-        .services
-        .analytics
-        .timeseries(&range, bucket, metric)
+    let key = CacheKey::new("timeseries", &range, req.home_id)
+        .param(bucket)
+        .param(metric)
+        .param(req.tz_offset_minutes);
+    cached(ctx, key, &range, |analytics, range| {
+        analytics.timeseries(range, bucket, metric, req.home_id, req.tz_offset_minutes)
+    })
+}
+
+pub fn timeseries_grouped(
+    ctx: &AppContext,
+    req: TimeseriesGroupedRequest,
+) -> Result<Vec<GroupedTimeSeries>> {
+    let range = range_params(req.range, req.start, req.end);
+    let bucket = parse_bucket(req.bucket)?;
+    let metric = parse_metric(req.metric)?;
+    let group_by = parse_group_by(&req.group_by)?;
+    let key = CacheKey::new("timeseries_grouped", &range, req.home_id)
+        .param(bucket)
+        .param(metric)
+        .param(group_by)
+        .param(req.tz_offset_minutes);
+    cached(ctx, key, &range, |analytics, range| {
+        analytics.timeseries_grouped(
+            range,
+            bucket,
+            metric,
+            group_by,
+            req.home_id,
+            req.tz_offset_minutes,
+        )
+    })
+}
+
+pub fn breakdown_homes(ctx: &AppContext, req: RangeRequest) -> Result<Vec<HomeBreakdown>> {
+    let range = range_params(req.range, req.start, req.end);
+    let key = CacheKey::new("breakdown_homes", &range, req.home_id);
+    cached(ctx, key, &range, |analytics, range| {
+        analytics.breakdown_homes(range, req.home_id)
+    })
+}
+
+/// Per-user totals across every home unless `home_id` narrows it.
+pub fn breakdown_users(ctx: &AppContext, req: RangeRequest) -> Result<Vec<UserBreakdown>> {
+    let range = range_params(req.range, req.start, req.end);
+    let key = CacheKey::new("breakdown_users", &range, req.home_id);
+    cached(ctx, key, &range, |analytics, range| {
+        analytics.breakdown_users(range, req.home_id)
+    })
 }
 
 pub fn breakdown(ctx: &AppContext, req: RangeRequest) -> Result<Vec<ModelBreakdown>> {
-    let range = resolve_range(req.range, req.start, req.end)?;
-    ctx.app_state.services.analytics.breakdown(&range)
+    let range = range_params(req.range, req.start, req.end);
+    let key = CacheKey::new("breakdown", &range, req.home_id);
+    cached(ctx, key, &range, |analytics, range| {
+        analytics.breakdown(range, req.home_id)
+    })
+}
+
+pub fn breakdown_providers(ctx: &AppContext, req: RangeRequest) -> Result<Vec<ProviderBreakdown>> {
+    let range = range_params(req.range, req.start, req.end);
+    let key = CacheKey::new("breakdown_providers", &range, req.home_id);
+    cached(ctx, key, &range, |analytics, range| {
+        analytics.breakdown_providers(range, req.home_id)
+    })
 }
 
 pub fn breakdown_tokens(ctx: &AppContext, req: RangeRequest) -> Result<Vec<ModelTokenBreakdown>> {
-    let range = resolve_range(req.range, req.start, req.end)?;
-    ctx.app_state.services.analytics.breakdown_tokens(&range)
+    let range = range_params(req.range, req.start, req.end);
+    let key = CacheKey::new("breakdown_tokens", &range, req.home_id);
+    cached(ctx, key, &range, |analytics, range| {
+        analytics.breakdown_tokens(range, req.home_id)
+    })
 }
 
 pub fn breakdown_costs(ctx: &AppContext, req: RangeRequest) -> Result<Vec<ModelCostBreakdown>> {
-    let range = resolve_range(req.range, req.start, req.end)?;
-    ctx.app_state.services.analytics.breakdown_costs(&range)
+    let range = range_params(req.range, req.start, req.end);
+    let key = CacheKey::new("breakdown_costs", &range, req.home_id);
+    cached(ctx, key, &range, |analytics, range| {
+        analytics.breakdown_costs(range, req.home_id)
+    })
 }
 
 pub fn breakdown_effort_tokens(
     ctx: &AppContext,
     req: RangeRequest,
 ) -> Result<Vec<ModelEffortTokenBreakdown>> {
+    let range = range_params(req.range, req.start, req.end);
+    let key = CacheKey::new("breakdown_effort_tokens", &range, req.home_id);
+    cached(ctx, key, &range, |analytics, range| {
+        analytics.breakdown_effort_tokens(range, req.home_id)
+    })
+}
+
+pub fn breakdown_effort_costs(
+    ctx: &AppContext,
+    req: RangeRequest,
+) -> Result<Vec<ModelEffortCostBreakdown>> {
+    let range = range_params(req.range, req.start, req.end);
+    let key = CacheKey::new("breakdown_effort_costs", &range, req.home_id);
+    cached(ctx, key, &range, |analytics, range| {
+        analytics.breakdown_effort_costs(range, req.home_id)
+    })
+}
+
+/// Distinct models with usage in the range, sorted.
+pub fn models(ctx: &AppContext, req: RangeRequest) -> Result<Vec<String>> {
+    let range = range_params(req.range, req.start, req.end);
+    let key = CacheKey::new("models", &range, req.home_id);
+    cached(ctx, key, &range, |analytics, range| {
+        analytics.models(range, req.home_id)
+    })
+}
+
+/// Distinct reasoning efforts with usage in the range, sorted.
+pub fn efforts(ctx: &AppContext, req: RangeRequest) -> Result<Vec<String>> {
+    let range = range_params(req.range, req.start, req.end);
+    let key = CacheKey::new("efforts", &range, req.home_id);
+    cached(ctx, key, &range, |analytics, range| {
+        analytics.efforts(range, req.home_id)
+    })
+}
+
+/// Distinct session log files with usage in the range, sorted.
+pub fn sources(ctx: &AppContext, req: RangeRequest) -> Result<Vec<String>> {
+    let range = range_params(req.range, req.start, req.end);
+    let key = CacheKey::new("sources", &range, req.home_id);
+    cached(ctx, key, &range, |analytics, range| {
+        analytics.sources(range, req.home_id)
+    })
+}
+
+pub fn events(ctx: &AppContext, req: EventsRequest) -> Result<EventsResponse> {
+    let range = resolve_range(req.range, req.start, req.end)?;
+    let limit = req.limit.unwrap_or(200).min(1000);
+    let offset = req.offset.unwrap_or(0);
+    let home = req.home_id.map(HomeScope::Home);
+    let analytics = &ctx.app_state.services.analytics;
+    let events = analytics.events(&range, req.model.as_deref(), limit, offset, home)?;
+    let totals = analytics.event_totals(&range, req.model.as_deref(), home)?;
+    Ok(EventsResponse { events, totals })
+}
+
+/// Every dashboard usage panel in one call, read from a single snapshot.
+/// Not cached: entries for the separate endpoints may be from different
+/// instants.
+pub fn dashboard(ctx: &AppContext, req: DashboardRequest) -> Result<DashboardResponse> {
     let range = resolve_range(req.range, req.start, req.end)?;
+    let query = DashboardQuery {
+        bucket: parse_bucket(req.bucket)?,
+        utc_offset_minutes: req.tz_offset_minutes,
+        model: req.model,
+        events_limit: req.events_limit.unwrap_or(200).min(1000),
+        active_minutes: req.active_minutes,
+    };
+    let dashboard = ctx.app_state.services.analytics.dashboard(
+        &range,
+        &query,
+        req.home_id.map(HomeScope::Home),
+    )?;
+    Ok(dashboard.into())
+}
+
+pub fn event(ctx: &AppContext, req: EventRequest) -> Result<EventDetailResponse> {
+    let event = ctx
+        .app_state
+        .services
+        .analytics
+        .event(&req.id, req.home_id.map(HomeScope::Home))?;
+    let raw = event
+        .raw_json
+        .as_deref()
+        .and_then(|raw| serde_json::from_str(raw).ok());
+    Ok(EventDetailResponse { event, raw })
+}
+
+/// Sessions in the range with the most tokens first.
+pub fn sessions(ctx: &AppContext, req: SessionsRequest) -> Result<Vec<SessionBreakdown>> {
+    let range = resolve_range(req.range, req.start, req.end)?;
+    let limit = req.limit.unwrap_or(50).min(1000) as usize;
     ctx.app_state
         .services
         .analytics
-        .breakdown_effort_tokens(&range)
+        .sessions(&range, limit, req.home_id)
 }
 
-pub fn breakdown_effort_costs(
+/// Active time per session and per day, with sessions split at idle gaps.
+pub fn activity(ctx: &AppContext, req: ActivityRequest) -> Result<ActivityReport> {
+    let range = resolve_range(req.range, req.start, req.end)?;
+    ctx.app_state.services.analytics.activity(
+        &range,
+        req.idle_gap_minutes,
+        req.home_id,
+        req.tz_offset_minutes,
+    )
+}
+
+/// Average tokens and cost per weekday and per hour of the day.
+pub fn activity_by_weekday(
     ctx: &AppContext,
-    req: RangeRequest,
-) -> Result<Vec<ModelEffortCostBreakdown>> {
+    req: ActivityByWeekdayRequest,
+) -> Result<WeekdayHourAverages> {
+    let range = resolve_range(req.range, req.start, req.end)?;
+    ctx.app_state.services.analytics.weekday_hour_averages(
+        &range,
+        req.home_id,
+        req.tz_offset_minutes,
+    )
+}
+
+/// Context compactions per session and per day.
+pub fn compactions(ctx: &AppContext, req: CompactionsRequest) -> Result<CompactionReport> {
     let range = resolve_range(req.range, req.start, req.end)?;
     ctx.app_state
         .services
         .analytics
-        .breakdown_effort_costs(&range)
+        .compactions(&range, req.home_id, req.tz_offset_minutes)
 }
 
-pub fn events(ctx: &AppContext, req: EventsRequest) -> Result<Vec<UsageEvent>> {
+/// Counts, endings, and average length of the sessions started in a range.
+pub fn session_lifetimes(
+    ctx: &AppContext,
+    req: SessionLifetimesRequest,
+) -> Result<SessionLifetimeReport> {
     let range = resolve_range(req.range, req.start, req.end)?;
-    let limit = req.limit.unwrap_or(200).min(1000);
-    let offset = req.offset.unwrap_or(0);
     ctx.app_state
         .services
         .analytics
-        .events(&range, req.model.as_deref(), limit, offset)
+        .session_lifetimes(&range, req.idle_minutes, req.home_id)
+}
+
+/// Sessions whose context got closest to the window, fullest first.
+pub fn context_peaks(
+    ctx: &AppContext,
+    req: ContextPeaksRequest,
+) -> Result<Vec<SessionContextPeak>> {
+    let range = resolve_range(req.range, req.start, req.end)?;
+    let limit = req.limit.unwrap_or(20).min(1000) as usize;
+    ctx.app_state.services.analytics.context_peaks(
+        &range,
+        req.threshold_pct.unwrap_or(80.0),
+        limit,
+        req.home_id,
+    )
+}
+
+/// Requests (events grouped by `request_id`) that added the most tokens,
+/// largest first.
+pub fn top_requests(ctx: &AppContext, req: TopRequestsRequest) -> Result<Vec<RequestBreakdown>> {
+    let range = resolve_range(req.range, req.start, req.end)?;
+    let limit = req.limit.unwrap_or(20).min(1000) as usize;
+    ctx.app_state
+        .services
+        .analytics
+        .top_requests(&range, limit, req.home_id)
+}
+
+pub fn session_timeline(ctx: &AppContext, req: SessionTimelineRequest) -> Result<SessionTimeline> {
+    ctx.app_state
+        .services
+        .analytics
+        .session_timeline(&req.session_id, req.home_id.map(HomeScope::Home))
+}
+
+pub fn export_events(
+    ctx: &AppContext,
+    req: RangeRequest,
+    format: RecordFormat,
+    writer: impl Write,
+) -> Result<()> {
+    let range = resolve_range(req.range, req.start, req.end)?;
+    ctx.app_state
+        .services
+        .export
+        .write_usage_events(&range, req.home_id, format, writer)
+}
+
+/// Returns the hash of the last record, which pins the whole export.
+pub fn export_events_chained(
+    ctx: &AppContext,
+    req: RangeRequest,
+    writer: impl Write,
+) -> Result<String> {
+    let range = resolve_range(req.range, req.start, req.end)?;
+    ctx.app_state
+        .services
+        .export
+        .write_chained_usage_events(&range, req.home_id, writer)
+}
+
+pub fn export_daily(
+    ctx: &AppContext,
+    req: RangeRequest,
+    format: RecordFormat,
+    writer: impl Write,
+) -> Result<()> {
+    let range = resolve_range(req.range, req.start, req.end)?;
+    ctx.app_state
+        .services
+        .export
+        .write_daily_rollups(&range, req.home_id, format, writer)
+}
+
+#[cfg(feature = "parquet")]
+pub fn export_events_parquet(ctx: &AppContext, req: RangeRequest) -> Result<Vec<u8>> {
+    let range = resolve_range(req.range, req.start, req.end)?;
+    ctx.app_state
+        .services
+        .export
+        .usage_events_parquet(&range, req.home_id)
 }
 
 pub fn limits_latest(ctx: &AppContext) -> Result<LimitsResponse> {
-    let (primary, secondary) = ctx.app_state.services.limits.latest()?;
-    Ok(LimitsResponse { primary, secondary })
+    let limits = &ctx.app_state.services.limits;
+    let (primary, secondary) = limits.latest()?;
+    let thresholds = limits.thresholds()?;
+    let now = Timestamp::now();
+    let status = |snapshot| limit_status(snapshot, &thresholds, now);
+    Ok(LimitsResponse {
+        primary: primary.map(status),
+        secondary: secondary.map(status),
+    })
+}
+
+fn limit_status(
+    snapshot: UsageLimitSnapshot,
+    thresholds: &LimitThresholds,
+    now: Timestamp,
+) -> LimitStatus {
+    let used_percent = (100.0 - snapshot.percent_left).clamp(0.0, 100.0);
+    let resets_in_secs = (snapshot.reset_at.as_datetime() - now.as_datetime())
+        .num_seconds()
+        .max(0);
+    LimitStatus {
+        snapshot,
+        used_percent,
+        status: thresholds.level(used_percent),
+        resets_in_secs,
+        resets_in: format_duration(resets_in_secs),
+    }
 }
 
 pub fn limits_current(ctx: &AppContext) -> Result<tracker_core::UsageLimitCurrentResponse> {
     ctx.app_state.services.limits.current()
 }
 
+pub fn custom_limits_list(ctx: &AppContext) -> Result<Vec<CustomLimit>> {
+    ctx.app_state.services.limits.custom_limits()
+}
+
+pub fn custom_limits_create(
+    ctx: &AppContext,
+    req: CustomLimitsCreateRequest,
+) -> Result<CustomLimit> {
+    ctx.app_state.services.limits.create_custom_limit(
+        &req.name,
+        &req.period,
+        &req.metric,
+        req.amount,
+    )
+}
+
+pub fn custom_limits_delete(
+    ctx: &AppContext,
+    req: CustomLimitsDeleteRequest,
+) -> Result<DeletedResponse> {
+    if ctx.app_state.services.limits.delete_custom_limit(req.id)? == 0 {
+        return Err(AppError::NotFound("custom limit not found".to_string()));
+    }
+    Ok(DeletedResponse { deleted: req.id })
+}
+
+pub fn limits_resets_ics(ctx: &AppContext) -> Result<String> {
+    ctx.app_state.services.limits.resets_ics()
+}
+
+pub fn ccusage(ctx: &AppContext, req: CcusageRequest) -> Result<CcusageReport> {
+    let kind = CcusageKind::parse(&req.report)?;
+    let range = resolve_range(
+        Some(req.range.unwrap_or_else(|| "alltime".to_string())),
+        req.start,
+        req.end,
+    )?;
+    ctx.app_state
+        .services
+        .export
+        .ccusage(kind, &range, req.home_id)
+}
+
+/// Renders the usage digest; returns the format alongside the body so callers
+/// can pick a content type.
+pub fn report(ctx: &AppContext, req: ReportRequest) -> Result<(ReportFormat, String)> {
+    let period = ReportPeriod::parse(req.period.as_deref())?;
+    let format = ReportFormat::parse(req.format.as_deref())?;
+    let report = ctx
+        .app_state
+        .services
+        .analytics
+        .report(period, req.home_id)?;
+    Ok((format, report.render(format)))
+}
+
+/// Renders `/badge/<name>` (e.g. `today-cost.svg`) for the active home.
+pub fn badge_svg(ctx: &AppContext, name: &str) -> Result<String> {
+    let kind = BadgeKind::from_file_name(name)
+        .ok_or_else(|| AppError::NotFound(format!("unknown badge {name}")))?;
+    let services = &ctx.app_state.services;
+    let badge = match kind {
+        BadgeKind::TodayCost | BadgeKind::TodayTokens => {
+            let today = resolve_range(Some("today".to_string()), None, None)?;
+            let summary = services.analytics.summary(&today, None)?;
+            if kind == BadgeKind::TodayCost {
+                Badge::today_cost(summary.total_cost_usd)
+            } else {
+                Badge::today_tokens(summary.total_tokens)
+            }
+        }
+        BadgeKind::Limit5h | BadgeKind::Limit7d => {
+            let (primary, secondary) = services.limits.latest()?;
+            let (limit_type, snapshot) = if kind == BadgeKind::Limit5h {
+                ("5h", primary)
+            } else {
+                ("7d", secondary)
+            };
+            Badge::limit(limit_type, snapshot.map(|snapshot| snapshot.percent_left))
+        }
+    };
+    Ok(badge.to_svg())
+}
+
 pub fn limits_7d_windows(
     ctx: &AppContext,
     req: LimitsWindowsRequest,
@@ -145,6 +601,22 @@ pub fn limits_7d_windows(
     ctx.app_state.services.limits.windows_7d(limit)
 }
 
+pub fn limits_snapshots(
+    ctx: &AppContext,
+    req: LimitsSnapshotsRequest,
+) -> Result<Vec<UsageLimitSnapshot>> {
+    let range = resolve_range(req.range, req.start, req.end)?;
+    let limit = req.limit.unwrap_or(200).min(1000);
+    let offset = req.offset.unwrap_or(0);
+    ctx.app_state.services.limits.snapshots(
+        &range,
+        req.limit_type.as_deref(),
+        limit,
+        offset,
+        req.home_id.map(HomeScope::Home),
+    )
+}
+
 pub fn pricing_list(ctx: &AppContext) -> Result<Vec<PricingRuleResponse>> {
     let rules = ctx.app_state.services.pricing.list_rules()?;
     let response = rules
@@ -160,6 +632,8 @@ pub fn pricing_list(ctx: &AppContext) -> Result<Vec<PricingRuleResponse>> {
             output_per_1k: rule.output_per_1m / 1000.0,
             effective_from: rule.effective_from,
             effective_to: rule.effective_to,
+            provider: rule.provider,
+            fallback: rule.fallback,
         })
         .collect();
     Ok(response)
@@ -172,19 +646,54 @@ pub fn pricing_replace(ctx: &AppContext, req: PricingReplaceRequest) -> Result<U
     })
 }
 
-pub fn pricing_recompute(ctx: &AppContext) -> Result<UpdatedResponse> {
-    let updated = ctx.app_state.services.pricing.recompute_costs()?;
+pub fn model_aliases_list(ctx: &AppContext) -> Result<Vec<ModelAlias>> {
+    ctx.app_state.services.pricing.list_aliases()
+}
+
+pub fn model_aliases_replace(
+    ctx: &AppContext,
+    req: ModelAliasesReplaceRequest,
+) -> Result<UpdatedResponse> {
+    let count = ctx
+        .app_state
+        .services
+        .pricing
+        .replace_aliases(&req.aliases)?;
     Ok(UpdatedResponse {
+        updated: count as i64,
+    })
+}
+
+pub fn pricing_recompute(ctx: &AppContext) -> Result<PricingRecomputeResponse> {
+    let (updated, progress) = ctx.app_state.services.pricing.recompute_costs()?;
+    Ok(PricingRecomputeResponse {
         updated: updated as i64,
+        progress,
     })
 }
 
+pub fn pricing_recompute_status(ctx: &AppContext) -> Result<Option<CostRecomputeProgress>> {
+    ctx.app_state.services.pricing.recompute_progress()
+}
+
 pub fn settings_get(ctx: &AppContext) -> Result<SettingsResponse> {
     let snapshot = ctx.app_state.services.settings.get()?;
+    let notifications = ctx.app_state.services.notifications.settings()?;
     Ok(SettingsResponse {
         codex_home: snapshot.codex_home,
         active_home_id: snapshot.active_home_id,
         context_active_minutes: snapshot.context_active_minutes,
+        monthly_budget_usd: snapshot.monthly_budget_usd,
+        auto_ingest_secs: snapshot.auto_ingest_secs,
+        launch_at_login: snapshot.launch_at_login,
+        global_shortcut: snapshot.global_shortcut,
+        tracking_paused: snapshot.tracking_paused,
+        limit_warn_percent: snapshot.limit_warn_percent,
+        limit_critical_percent: snapshot.limit_critical_percent,
+        active_session_rule: snapshot.active_session_rule,
+        slack_webhook_configured: notifications.slack_webhook_url.is_some(),
+        discord_webhook_configured: notifications.discord_webhook_url.is_some(),
+        daily_summary: notifications.daily_summary,
         db_path: ctx.app_state.config.db_path.to_string_lossy().to_string(),
         pricing_defaults_path: ctx
             .app_state
@@ -201,13 +710,64 @@ pub fn settings_get(ctx: &AppContext) -> Result<SettingsResponse> {
 }
 
 pub fn settings_put(ctx: &AppContext, req: SettingsPutRequest) -> Result<SettingsResponse> {
-    ctx.app_state
-        .services
-        .settings
-        .update(req.codex_home.as_deref(), req.context_active_minutes)?;
+    ctx.app_state.services.settings.update(
+        req.codex_home.as_deref(),
+        req.context_active_minutes,
+        req.monthly_budget_usd,
+        req.auto_ingest_secs,
+        req.launch_at_login,
+        req.global_shortcut.as_deref(),
+    )?;
+    if let Some(paused) = req.tracking_paused {
+        ctx.app_state
+            .services
+            .settings
+            .set_tracking_paused(paused)?;
+    }
+    if req.limit_warn_percent.is_some() || req.limit_critical_percent.is_some() {
+        ctx.app_state
+            .services
+            .settings
+            .set_limit_thresholds(req.limit_warn_percent, req.limit_critical_percent)?;
+    }
+    if let Some(rule) = &req.active_session_rule {
+        ctx.app_state
+            .services
+            .settings
+            .set_active_session_rule(rule)?;
+    }
+    let notifications = &ctx.app_state.services.notifications;
+    if let Some(url) = req.slack_webhook_url.as_deref() {
+        notifications.set_channel_url(Channel::Slack, url)?;
+    }
+    if let Some(url) = req.discord_webhook_url.as_deref() {
+        notifications.set_channel_url(Channel::Discord, url)?;
+    }
+    if let Some(enabled) = req.daily_summary {
+        notifications.set_daily_summary(enabled)?;
+    }
     settings_get(ctx)
 }
 
+pub fn settings_export(ctx: &AppContext) -> Result<SettingsBundle> {
+    bundle::export_bundle(&ctx.app_state.services)
+}
+
+pub fn settings_import(ctx: &AppContext, req: SettingsBundle) -> Result<SettingsImportResponse> {
+    let stats = bundle::import_bundle(&ctx.app_state.services, &req)?;
+    Ok(SettingsImportResponse {
+        pricing_rules: stats.pricing_rules,
+        model_aliases: stats.model_aliases,
+        homes_created: stats.homes_created,
+        webhooks_created: stats.webhooks_created,
+    })
+}
+
+pub fn notifications_test(ctx: &AppContext) -> Result<SentResponse> {
+    let sent = ctx.app_state.services.notifications.send_test()?;
+    Ok(SentResponse { sent: sent as i64 })
+}
+
 pub fn homes_list(ctx: &AppContext) -> Result<HomesResponse> {
     let active = ctx.app_state.services.homes.active()?;
     let homes = ctx.app_state.services.homes.list()?;
@@ -230,6 +790,34 @@ pub fn homes_create(ctx: &AppContext, req: HomesCreateRequest) -> Result<tracker
     ctx.app_state.services.homes.create(path, label)
 }
 
+pub fn homes_discover(ctx: &AppContext) -> Result<Vec<HomeCandidateResponse>> {
+    let candidates = ctx.app_state.services.homes.discover()?;
+    Ok(candidates
+        .into_iter()
+        .map(|candidate| HomeCandidateResponse {
+            path: candidate.path,
+            log_files: candidate.log_files,
+            home_id: candidate.home_id,
+        })
+        .collect())
+}
+
+pub fn homes_inspect(ctx: &AppContext, req: HomesInspectRequest) -> Result<HomesInspectResponse> {
+    let path = req.path.trim();
+    if path.is_empty() {
+        return Err(AppError::InvalidInput("path is required".to_string()));
+    }
+    let path = expand_home_path(path);
+    let inspection = ctx.app_state.services.homes.inspect(&path);
+    Ok(HomesInspectResponse {
+        path: path.to_string_lossy().to_string(),
+        exists: inspection.exists,
+        has_sessions_dir: inspection.has_sessions_dir,
+        log_files: inspection.log_files,
+        looks_like_codex_home: inspection.looks_like_codex_home(),
+    })
+}
+
 pub fn homes_set_active(
     ctx: &AppContext,
     req: HomesSetActiveRequest,
@@ -237,21 +825,129 @@ pub fn homes_set_active(
     ctx.app_state.services.homes.set_active(req.id)
 }
 
+pub fn homes_rename(ctx: &AppContext, req: HomesRenameRequest) -> Result<tracker_core::CodexHome> {
+    ctx.app_state.services.homes.rename(req.id, &req.label)
+}
+
+pub fn homes_update(
+    ctx: &AppContext,
+    id: i64,
+    req: HomesUpdateRequest,
+) -> Result<tracker_core::CodexHome> {
+    ctx.app_state.services.homes.update(
+        id,
+        req.label.as_deref(),
+        req.path.as_deref(),
+        req.user.as_deref(),
+    )
+}
+
 pub fn homes_delete(ctx: &AppContext, req: HomesDeleteRequest) -> Result<DeletedResponse> {
     ctx.app_state.services.homes.delete(req.id)?;
     Ok(DeletedResponse { deleted: req.id })
 }
 
+pub fn homes_archive(
+    ctx: &AppContext,
+    req: HomesArchiveRequest,
+) -> Result<tracker_core::CodexHome> {
+    ctx.app_state.services.homes.archive(req.id)
+}
+
+pub fn homes_unarchive(
+    ctx: &AppContext,
+    req: HomesArchiveRequest,
+) -> Result<tracker_core::CodexHome> {
+    ctx.app_state.services.homes.unarchive(req.id)
+}
+
 pub fn homes_clear_data(ctx: &AppContext, req: HomesClearDataRequest) -> Result<ClearedResponse> {
     ctx.app_state.services.homes.clear_data(req.id)?;
     Ok(ClearedResponse { cleared: req.id })
 }
 
+pub fn webhooks_list(ctx: &AppContext) -> Result<Vec<Webhook>> {
+    ctx.app_state.services.webhooks.list()
+}
+
+pub fn webhooks_create(ctx: &AppContext, req: WebhooksCreateRequest) -> Result<Webhook> {
+    ctx.app_state
+        .services
+        .webhooks
+        .create(&req.url, req.secret.as_deref(), &req.event_types)
+}
+
+pub fn webhooks_delete(ctx: &AppContext, req: WebhooksDeleteRequest) -> Result<DeletedResponse> {
+    if ctx.app_state.services.webhooks.delete(req.id)? == 0 {
+        return Err(AppError::NotFound("webhook not found".to_string()));
+    }
+    Ok(DeletedResponse { deleted: req.id })
+}
+
+pub fn webhook_deliveries(
+    ctx: &AppContext,
+    req: WebhookDeliveriesRequest,
+) -> Result<Vec<WebhookDelivery>> {
+    let limit = req.limit.unwrap_or(100).min(1000);
+    ctx.app_state
+        .services
+        .webhooks
+        .deliveries(req.webhook_id, limit)
+}
+
+pub fn schedules_list(ctx: &AppContext) -> Result<Vec<Schedule>> {
+    ctx.app_state.services.schedules.list()
+}
+
+pub fn schedules_create(ctx: &AppContext, req: SchedulesCreateRequest) -> Result<Schedule> {
+    ctx.app_state.services.schedules.create(&ScheduleInput {
+        payload: req.payload,
+        local_time: req.local_time,
+        target: req.target,
+        destination: req.destination,
+        smtp_url: req.smtp_url,
+        email_from: req.email_from,
+    })
+}
+
+pub fn schedules_delete(ctx: &AppContext, req: SchedulesDeleteRequest) -> Result<DeletedResponse> {
+    if ctx.app_state.services.schedules.delete(req.id)? == 0 {
+        return Err(AppError::NotFound("schedule not found".to_string()));
+    }
+    Ok(DeletedResponse { deleted: req.id })
+}
+
+/// Sends a schedule immediately, e.g. to check its target.
+pub fn schedules_send(ctx: &AppContext, req: SchedulesSendRequest) -> Result<Schedule> {
+    ctx.app_state.services.schedules.send(req.id)
+}
+
+pub fn api_tokens_list(ctx: &AppContext) -> Result<Vec<ApiToken>> {
+    ctx.app_state.services.api_tokens.list()
+}
+
+pub fn api_tokens_create(
+    ctx: &AppContext,
+    req: ApiTokensCreateRequest,
+) -> Result<ApiTokenCreatedResponse> {
+    let (token, secret) = ctx
+        .app_state
+        .services
+        .api_tokens
+        .create(req.label.as_deref())?;
+    Ok(ApiTokenCreatedResponse { token, secret })
+}
+
+pub fn api_tokens_revoke(ctx: &AppContext, req: ApiTokensRevokeRequest) -> Result<RevokedResponse> {
+    ctx.app_state.services.api_tokens.revoke(&req.id)?;
+    Ok(RevokedResponse { revoked: req.id })
+}
+
 pub fn logs_dir(ctx: &AppContext) -> Result<PathBuf> {
     let home = ctx.app_state.services.homes.active()?;
     let path = expand_home_path(&home.path);
     if !path.exists() {
-        return Err(AppError::NotFound(format!(
+        return Err(AppError::HomeNotFound(format!(
             "Codex home not found at {}",
             path.display()
         )));
@@ -263,6 +959,159 @@ pub fn ingest(ctx: &AppContext) -> Result<IngestStats> {
     ctx.app_state.services.ingest.run()
 }
 
+/// Ingests (which also finishes any interrupted cost recompute) and only
+/// then reads the summary and limits, all in one call.
+pub fn refresh(ctx: &AppContext, req: RefreshRequest) -> Result<RefreshResponse> {
+    let pricing = &ctx.app_state.services.pricing;
+    let interrupted = pricing
+        .recompute_progress()?
+        .is_some_and(|progress| !progress.complete);
+    let ingest = ingest(ctx)?;
+    let recompute = if interrupted {
+        pricing
+            .recompute_progress()?
+            .filter(|progress| progress.complete)
+    } else {
+        None
+    };
+    let summary = summary(
+        ctx,
+        RangeRequest {
+            range: req.range,
+            start: req.start,
+            end: req.end,
+            home_id: None,
+        },
+    )?;
+    let limits = limits_latest(ctx)?;
+    Ok(RefreshResponse {
+        ingest,
+        recompute,
+        summary,
+        limits,
+    })
+}
+
+pub fn ingest_once(ctx: &AppContext, target: IngestTarget, dry_run: bool) -> Result<IngestStats> {
+    ctx.app_state.services.ingest.run_once(target, dry_run)
+}
+
+pub fn ingest_files(ctx: &AppContext, files: &[PathBuf]) -> Result<IngestStats> {
+    ctx.app_state.services.ingest.ingest_files(files)
+}
+
+pub fn ingest_cursors(ctx: &AppContext, req: HomeRequest) -> Result<Vec<IngestCursorResponse>> {
+    let cursors = ctx.app_state.services.ingest.cursors(req.home_id)?;
+    Ok(cursors
+        .into_iter()
+        .map(|cursor| IngestCursorResponse {
+            file_path: cursor.file_path,
+            byte_offset: cursor.byte_offset,
+            mtime: cursor.mtime,
+            updated_at: cursor.updated_at,
+        })
+        .collect())
+}
+
+pub fn ingest_cursors_reset(
+    ctx: &AppContext,
+    req: IngestCursorsResetRequest,
+) -> Result<ResetResponse> {
+    let reset = ctx
+        .app_state
+        .services
+        .ingest
+        .reset_cursors(req.home_id, req.file_path.as_deref())?;
+    if reset == 0
+        && let Some(file_path) = &req.file_path
+    {
+        return Err(AppError::NotFound(format!(
+            "no ingest cursor for {file_path}"
+        )));
+    }
+    Ok(ResetResponse {
+        reset: reset as i64,
+    })
+}
+
+pub fn maintenance_prune(ctx: &AppContext, req: PruneRequest) -> Result<PruneResponse> {
+    let stats = ctx
+        .app_state
+        .services
+        .maintenance
+        .prune(&req.before, req.home_id)?;
+    Ok(PruneResponse {
+        before: req.before,
+        usage_events: stats.usage_events,
+        message_events: stats.message_events,
+        limit_snapshots: stats.limit_snapshots,
+    })
+}
+
+pub fn usage_import(ctx: &AppContext, req: ImportRequest) -> Result<ImportResponse> {
+    let stats = match req.from.as_str() {
+        "ccusage" => ctx
+            .app_state
+            .services
+            .ingest
+            .import_ccusage(&req.contents, req.home_id)?,
+        other => {
+            return Err(AppError::InvalidInput(format!(
+                "unsupported import source {other} (supported: ccusage)"
+            )));
+        }
+    };
+    Ok(ImportResponse {
+        from: req.from,
+        imported: stats.imported,
+        duplicates: stats.duplicates,
+        overlapping: stats.overlapping,
+    })
+}
+
+pub fn maintenance_merge(ctx: &AppContext, req: MergeRequest) -> Result<MergeResponse> {
+    let path = expand_home_path(&req.path);
+    let stats = ctx
+        .app_state
+        .services
+        .maintenance
+        .merge(&path, req.user.as_deref())?;
+    Ok(MergeResponse {
+        path: path.display().to_string(),
+        homes_added: stats.homes_added,
+        usage_events: stats.usage_events,
+        message_events: stats.message_events,
+        limit_snapshots: stats.limit_snapshots,
+    })
+}
+
+pub fn maintenance_audit(ctx: &AppContext, req: AuditRequest) -> Result<AuditReport> {
+    let range = resolve_range(req.range, req.start, req.end)?;
+    ctx.app_state
+        .services
+        .maintenance
+        .audit(&range, req.home_id)
+}
+
+pub fn maintenance_compact(ctx: &AppContext) -> Result<CompactResponse> {
+    let stats = ctx.app_state.services.maintenance.compact()?;
+    Ok(CompactResponse {
+        bytes_before: stats.bytes_before,
+        bytes_after: stats.bytes_after,
+    })
+}
+
 pub fn ok() -> OkResponse {
     OkResponse { ok: true }
 }
+
+pub fn version() -> VersionInfo {
+    version_info()
+}
+
+pub fn health() -> HealthResponse {
+    HealthResponse {
+        status: "ok".to_string(),
+        version: version_info(),
+    }
+}
@@ -1,26 +1,116 @@
 use serde::Deserialize;
-use tracker_core::PricingRuleInput;
+use tracker_core::{ActiveSessionRule, HomeScope, ModelAlias, PricingRuleInput};
 
 #[derive(Debug, Deserialize, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct EmptyRequest {}
 
+#[derive(Debug, Deserialize, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HomeRequest {
+    /// Home to query instead of the active one.
+    pub home_id: Option<i64>,
+}
+
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct RangeRequest {
     pub range: Option<String>,
     pub start: Option<String>,
     pub end: Option<String>,
+    /// Home to query instead of the active one, or `"all"` to aggregate
+    /// every home (summary, breakdowns, and timeseries only).
+    pub home_id: Option<HomeScope>,
 }
 
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CompareRequest {
+    pub range: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    /// Baseline range name; without it (or `to_start`), the baseline is the
+    /// window of the same length just before the range.
+    pub to_range: Option<String>,
+    pub to_start: Option<String>,
+    pub to_end: Option<String>,
+    pub home_id: Option<HomeScope>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CcusageRequest {
+    /// `daily`, `session`, or `blocks`.
+    pub report: String,
+    /// Defaults to `alltime`, like ccusage.
+    pub range: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub home_id: Option<HomeScope>,
+}
+
+/// Query parameters for `/api/report`.
+#[derive(Debug, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+pub struct ReportRequest {
+    /// `day` (default), `week`, or `month`.
+    pub period: Option<String>,
+    /// `markdown` (default), `html`, `snapshot` (standalone HTML with
+    /// charts), or `json`.
+    pub format: Option<String>,
+    pub home_id: Option<HomeScope>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct TimeseriesRequest {
     pub range: Option<String>,
     pub start: Option<String>,
     pub end: Option<String>,
     pub bucket: Option<String>,
     pub metric: Option<String>,
+    /// Home to query instead of the active one, or `"all"` to aggregate
+    /// every home (summary, breakdowns, and timeseries only).
+    pub home_id: Option<HomeScope>,
+    /// Minutes east of UTC to bucket in (e.g. `-300` for UTC-5); defaults
+    /// to the server's time zone, DST changes included.
+    pub tz_offset_minutes: Option<i32>,
 }
 
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TimeseriesGroupedRequest {
+    pub range: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub bucket: Option<String>,
+    pub metric: Option<String>,
+    /// `model` or `effort`.
+    pub group_by: String,
+    pub home_id: Option<HomeScope>,
+    pub tz_offset_minutes: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DashboardRequest {
+    pub range: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    /// `hour` or `day` (default) for both series.
+    pub bucket: Option<String>,
+    /// Minutes east of UTC to bucket in; defaults to the server's offset.
+    pub tz_offset_minutes: Option<i32>,
+    /// Filters the events page and its totals.
+    pub model: Option<String>,
+    pub events_limit: Option<u32>,
+    pub active_minutes: Option<u32>,
+    /// Home to query instead of the active one.
+    pub home_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct EventsRequest {
     pub range: Option<String>,
     pub start: Option<String>,
@@ -28,46 +118,385 @@ pub struct EventsRequest {
     pub limit: Option<u32>,
     pub offset: Option<u32>,
     pub model: Option<String>,
+    /// Home to query instead of the active one.
+    pub home_id: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SessionsRequest {
+    pub range: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub limit: Option<u32>,
+    pub home_id: Option<HomeScope>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ActivityRequest {
+    pub range: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub home_id: Option<HomeScope>,
+    /// Events further apart than this start a new segment (default 30).
+    pub idle_gap_minutes: Option<u32>,
+    /// Minutes east of UTC that days are taken in; defaults to the server's
+    /// time zone.
+    pub tz_offset_minutes: Option<i32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ActivityByWeekdayRequest {
+    pub range: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub home_id: Option<HomeScope>,
+    /// Minutes east of UTC that weekdays and hours are taken in; defaults to
+    /// the server's time zone.
+    pub tz_offset_minutes: Option<i32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CompactionsRequest {
+    pub range: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub home_id: Option<HomeScope>,
+    /// Minutes east of UTC that days are taken in; defaults to the server's
+    /// time zone.
+    pub tz_offset_minutes: Option<i32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SessionLifetimesRequest {
+    pub range: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub home_id: Option<HomeScope>,
+    /// Idle time after which a session without a shutdown counts as
+    /// abandoned (default 30).
+    pub idle_minutes: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ContextPeaksRequest {
+    pub range: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    /// Minimum peak context pressure, in percent (default 80).
+    pub threshold_pct: Option<f64>,
+    /// Number of sessions to return (default 20, at most 1000).
+    pub limit: Option<u32>,
+    pub home_id: Option<HomeScope>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TopRequestsRequest {
+    pub range: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    /// Number of requests to return (default 20, at most 1000).
+    pub limit: Option<u32>,
+    pub home_id: Option<HomeScope>,
 }
 
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EventRequest {
+    pub id: String,
+    /// Home to query instead of the active one.
+    pub home_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SessionTimelineRequest {
+    pub session_id: String,
+    /// Home to query instead of the active one.
+    pub home_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ContextSessionsRequest {
     pub active_minutes: Option<u32>,
+    /// Home to query instead of the active one.
+    pub home_id: Option<i64>,
 }
 
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct LimitsWindowsRequest {
     pub limit: Option<usize>,
 }
 
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CustomLimitsCreateRequest {
+    pub name: String,
+    /// `week` (resets Monday at local midnight) or `month`.
+    pub period: String,
+    /// `tokens` or `cost_usd`.
+    pub metric: String,
+    pub amount: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CustomLimitsDeleteRequest {
+    pub id: i64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema, utoipa::IntoParams))]
+pub struct LimitsSnapshotsRequest {
+    pub range: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    /// `5h` or `7d`; both when omitted. Also accepted as `type`.
+    #[serde(alias = "type")]
+    pub limit_type: Option<String>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+    /// Home to query instead of the active one.
+    pub home_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct PricingReplaceRequest {
     pub rules: Vec<PricingRuleInput>,
 }
 
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ModelAliasesReplaceRequest {
+    pub aliases: Vec<ModelAlias>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct HomesCreateRequest {
     pub path: String,
     pub label: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HomesInspectRequest {
+    pub path: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct HomesSetActiveRequest {
     pub id: i64,
 }
 
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HomesRenameRequest {
+    pub id: i64,
+    pub label: String,
+}
+
+/// Fields to change on a home; omitted ones are kept.
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HomesUpdateRequest {
+    pub label: Option<String>,
+    pub path: Option<String>,
+    /// Who the home's usage belongs to in team mode; empty clears it.
+    #[serde(default)]
+    pub user: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct HomesDeleteRequest {
     pub id: i64,
 }
 
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HomesArchiveRequest {
+    pub id: i64,
+}
+
+/// Range of the summary `/api/refresh` returns, for the active home.
+#[derive(Debug, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RefreshRequest {
+    pub range: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct IngestCursorsResetRequest {
+    /// Log file whose cursor to reset; every cursor in the home when omitted.
+    pub file_path: Option<String>,
+    /// Home to reset instead of the active one.
+    pub home_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct HomesClearDataRequest {
     pub id: i64,
 }
 
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PruneRequest {
+    /// `YYYY-MM-DD` (local midnight) or an RFC 3339 timestamp.
+    pub before: String,
+    /// Home to prune; every home when omitted.
+    pub home_id: Option<HomeScope>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImportRequest {
+    /// The tool the data comes from; only `ccusage` is supported.
+    pub from: String,
+    /// The tool's export, e.g. the output of `ccusage daily --json`.
+    pub contents: String,
+    /// Home to import into instead of the active one.
+    pub home_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MergeRequest {
+    /// Another codex-tracker database file; it is only read.
+    pub path: String,
+    /// Teammate the database belongs to; its homes are labeled with them.
+    #[serde(default)]
+    pub user: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AuditRequest {
+    pub range: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    /// Home to audit instead of the active one.
+    pub home_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct SettingsPutRequest {
     pub codex_home: Option<String>,
     pub context_active_minutes: Option<u32>,
+    /// Monthly spend budget in USD; zero or a negative value clears it.
+    #[serde(default)]
+    pub monthly_budget_usd: Option<f64>,
+    /// Seconds between background ingests in the desktop app; 0 turns them
+    /// off.
+    #[serde(default)]
+    pub auto_ingest_secs: Option<u32>,
+    /// Start the desktop app at login. Only the desktop app registers the
+    /// login item; elsewhere the preference is just stored.
+    #[serde(default)]
+    pub launch_at_login: Option<bool>,
+    /// Desktop accelerator that shows or hides the window, such as
+    /// `CommandOrControl+Shift+U`; an empty string turns it off.
+    #[serde(default)]
+    pub global_shortcut: Option<String>,
+    /// Pause ingest everywhere (schedulers, refreshes, and explicit runs)
+    /// until set back to false.
+    #[serde(default)]
+    pub tracking_paused: Option<bool>,
+    /// Percent used at which a limit's status turns `warn`; 0-100 and no
+    /// higher than `limit_critical_percent`.
+    #[serde(default)]
+    pub limit_warn_percent: Option<u32>,
+    /// Percent used at which a limit's status turns `critical`; 0-100.
+    #[serde(default)]
+    pub limit_critical_percent: Option<u32>,
+    /// What besides events within `context_active_minutes` makes a session
+    /// active; replaces the stored rule.
+    #[serde(default)]
+    pub active_session_rule: Option<ActiveSessionRule>,
+    /// Slack incoming-webhook URL; an empty string removes it.
+    #[serde(default)]
+    pub slack_webhook_url: Option<String>,
+    /// Discord webhook URL; an empty string removes it.
+    #[serde(default)]
+    pub discord_webhook_url: Option<String>,
+    /// Post yesterday's usage summary to the channels once a day.
+    #[serde(default)]
+    pub daily_summary: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhooksCreateRequest {
+    pub url: String,
+    pub secret: Option<String>,
+    pub event_types: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhooksDeleteRequest {
+    pub id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookDeliveriesRequest {
+    pub webhook_id: Option<i64>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SchedulesCreateRequest {
+    /// `report` or `status`.
+    pub payload: String,
+    /// Local `HH:MM` to send at.
+    pub local_time: String,
+    /// `webhook` or `email`.
+    pub target: String,
+    /// The webhook URL or the email recipient.
+    pub destination: String,
+    /// Required for email: `smtp[s]://user:password@host[:port]`.
+    pub smtp_url: Option<String>,
+    /// Sender for email; defaults to the recipient.
+    pub email_from: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SchedulesDeleteRequest {
+    pub id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SchedulesSendRequest {
+    pub id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApiTokensCreateRequest {
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApiTokensRevokeRequest {
+    pub id: String,
 }
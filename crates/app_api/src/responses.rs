@@ -1,7 +1,15 @@
+use ingest::IngestStats;
 use serde::Serialize;
-use tracker_core::{CodexHome, UsageLimitSnapshot};
+use tracker_app::services::Dashboard;
+use tracker_app::version::VersionInfo;
+use tracker_core::{
+    ActiveSession, ActiveSessionRule, ApiToken, CodexHome, ContextPressureStats,
+    CostRecomputeProgress, EventTotals, LimitLevel, ModelCostBreakdown, ModelEffortCostBreakdown,
+    TimeSeriesPoint, UsageEvent, UsageLimitSnapshot, UsageSummary,
+};
 
 #[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct PricingRuleResponse {
     pub id: Option<i64>,
     pub model_pattern: String,
@@ -13,25 +21,138 @@ pub struct PricingRuleResponse {
     pub output_per_1k: f64,
     pub effective_from: String,
     pub effective_to: Option<String>,
+    pub provider: Option<String>,
+    /// Default pricing for models no other rule matches.
+    pub fallback: bool,
 }
 
+/// A page of events with totals over everything the filter matches.
 #[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EventsResponse {
+    pub events: Vec<UsageEvent>,
+    #[serde(flatten)]
+    pub totals: EventTotals,
+}
+
+/// The dashboard's usage panels, read from one database snapshot so they
+/// agree with each other.
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DashboardResponse {
+    pub summary: UsageSummary,
+    pub tokens_series: Vec<TimeSeriesPoint>,
+    pub cost_series: Vec<TimeSeriesPoint>,
+    pub breakdown: Vec<ModelCostBreakdown>,
+    pub effort_breakdown: Vec<ModelEffortCostBreakdown>,
+    pub context_stats: ContextPressureStats,
+    pub events: EventsResponse,
+    pub active_sessions: Vec<ActiveSession>,
+    pub models: Vec<String>,
+}
+
+impl From<Dashboard> for DashboardResponse {
+    fn from(dashboard: Dashboard) -> Self {
+        Self {
+            summary: dashboard.summary,
+            tokens_series: dashboard.tokens_series,
+            cost_series: dashboard.cost_series,
+            breakdown: dashboard.breakdown,
+            effort_breakdown: dashboard.effort_breakdown,
+            context_stats: dashboard.context_stats,
+            events: EventsResponse {
+                events: dashboard.events,
+                totals: dashboard.event_totals,
+            },
+            active_sessions: dashboard.active_sessions,
+            models: dashboard.models,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EventDetailResponse {
+    #[serde(flatten)]
+    pub event: UsageEvent,
+    /// `raw_json` parsed into an object; `None` when the event has no raw
+    /// line or it is not valid JSON.
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub raw: Option<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct HomesResponse {
     pub active_home_id: Option<i64>,
     pub homes: Vec<CodexHome>,
 }
 
 #[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HomeCandidateResponse {
+    pub path: String,
+    pub log_files: usize,
+    /// Set when the directory is already tracked as a home.
+    pub home_id: Option<i64>,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HomesInspectResponse {
+    pub path: String,
+    pub exists: bool,
+    pub has_sessions_dir: bool,
+    /// Session logs found under `sessions/`.
+    pub log_files: usize,
+    /// False when the directory has neither `sessions/` nor any session
+    /// logs, so adding it deserves a warning.
+    pub looks_like_codex_home: bool,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct LimitsResponse {
-    pub primary: Option<UsageLimitSnapshot>,
-    pub secondary: Option<UsageLimitSnapshot>,
+    pub primary: Option<LimitStatus>,
+    pub secondary: Option<LimitStatus>,
+}
+
+/// A limit snapshot with what clients would otherwise derive from it.
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LimitStatus {
+    #[serde(flatten)]
+    pub snapshot: UsageLimitSnapshot,
+    /// `100 - percent_left`, clamped to 0-100.
+    pub used_percent: f64,
+    /// `used_percent` against the `limit_warn_percent` and
+    /// `limit_critical_percent` settings.
+    pub status: LimitLevel,
+    /// Seconds until `reset_at`; 0 once it has passed.
+    pub resets_in_secs: i64,
+    /// `resets_in_secs` for display, e.g. `3h 12m`.
+    pub resets_in: String,
 }
 
 #[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct SettingsResponse {
     pub codex_home: String,
     pub active_home_id: i64,
     pub context_active_minutes: u32,
+    pub monthly_budget_usd: Option<f64>,
+    pub auto_ingest_secs: u32,
+    pub launch_at_login: bool,
+    /// Empty when the global shortcut is turned off.
+    pub global_shortcut: String,
+    pub tracking_paused: bool,
+    pub limit_warn_percent: u32,
+    pub limit_critical_percent: u32,
+    pub active_session_rule: ActiveSessionRule,
+    /// Webhook URLs are secrets, so only whether one is set is returned.
+    pub slack_webhook_configured: bool,
+    pub discord_webhook_configured: bool,
+    pub daily_summary: bool,
     pub db_path: String,
     pub pricing_defaults_path: String,
     pub app_data_dir: String,
@@ -39,21 +160,135 @@ pub struct SettingsResponse {
 }
 
 #[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct UpdatedResponse {
     pub updated: i64,
 }
 
 #[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PricingRecomputeResponse {
+    /// Events re-priced by this call; fewer than `progress.total` when it
+    /// resumed an interrupted recompute.
+    pub updated: i64,
+    pub progress: CostRecomputeProgress,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct DeletedResponse {
     pub deleted: i64,
 }
 
 #[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ClearedResponse {
     pub cleared: i64,
 }
 
 #[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ResetResponse {
+    pub reset: i64,
+}
+
+/// Data read back after the ingest that produced it, so clients never see
+/// a summary from before their own refresh.
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RefreshResponse {
+    pub ingest: IngestStats,
+    /// Set when an interrupted cost recompute was finished along the way.
+    pub recompute: Option<CostRecomputeProgress>,
+    pub summary: UsageSummary,
+    pub limits: LimitsResponse,
+}
+
+/// How far ingest has read one log file.
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct IngestCursorResponse {
+    pub file_path: String,
+    pub byte_offset: u64,
+    /// File modification time when it was last read.
+    pub mtime: Option<String>,
+    pub updated_at: String,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PruneResponse {
+    pub before: String,
+    pub usage_events: u64,
+    pub message_events: u64,
+    pub limit_snapshots: u64,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImportResponse {
+    pub from: String,
+    pub imported: usize,
+    pub duplicates: usize,
+    pub overlapping: usize,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SettingsImportResponse {
+    pub pricing_rules: usize,
+    pub model_aliases: usize,
+    pub homes_created: usize,
+    pub webhooks_created: usize,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MergeResponse {
+    pub path: String,
+    pub homes_added: u64,
+    pub usage_events: u64,
+    pub message_events: u64,
+    pub limit_snapshots: u64,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CompactResponse {
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SentResponse {
+    pub sent: i64,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HealthResponse {
+    pub status: String,
+    pub version: VersionInfo,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct OkResponse {
     pub ok: bool,
 }
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApiTokenCreatedResponse {
+    #[serde(flatten)]
+    pub token: ApiToken,
+    /// Plaintext bearer token; it is not stored and cannot be shown again.
+    pub secret: String,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RevokedResponse {
+    pub revoked: String,
+}
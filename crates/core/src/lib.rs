@@ -1,7 +1,15 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::path::Path;
+use std::str::FromStr;
+
+mod timestamp;
+
+pub use timestamp::Timestamp;
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
 pub struct UsageTotals {
     pub input_tokens: u64,
     pub cached_input_tokens: u64,
@@ -10,7 +18,47 @@ pub struct UsageTotals {
     pub total_tokens: u64,
 }
 
+impl UsageTotals {
+    pub fn efficiency(&self) -> TokenEfficiency {
+        TokenEfficiency::from_tokens(
+            self.input_tokens,
+            self.cached_input_tokens,
+            self.output_tokens,
+            self.reasoning_output_tokens,
+        )
+    }
+}
+
+/// Ratios derived from token totals, for comparing models on more than raw
+/// totals. Each is `None` when its denominator is zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct TokenEfficiency {
+    /// Output tokens per input token.
+    pub output_input_ratio: Option<f64>,
+    /// Fraction of output tokens spent on reasoning.
+    pub reasoning_output_share: Option<f64>,
+    /// Fraction of input tokens served from the cache.
+    pub cached_input_share: Option<f64>,
+}
+
+impl TokenEfficiency {
+    pub fn from_tokens(input: u64, cached_input: u64, output: u64, reasoning_output: u64) -> Self {
+        let ratio = |numerator: u64, denominator: u64| {
+            (denominator > 0).then(|| numerator as f64 / denominator as f64)
+        };
+        Self {
+            output_input_ratio: ratio(output, input),
+            reasoning_output_share: ratio(reasoning_output, output),
+            cached_input_share: ratio(cached_input, input),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
 pub struct ContextStatus {
     pub context_used: u64,
     pub context_window: u64,
@@ -28,14 +76,38 @@ impl ContextStatus {
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ContextPressureStats {
     pub avg_context_used: Option<f64>,
     pub avg_context_window: Option<f64>,
     pub avg_pressure_pct: Option<f64>,
+    /// Nearest-rank percentiles of context pressure (used / window), in percent.
+    pub p50_pressure_pct: Option<f64>,
+    pub p90_pressure_pct: Option<f64>,
+    pub p99_pressure_pct: Option<f64>,
+    /// Events above 80% pressure, where Codex gets close to auto-compacting.
+    pub high_pressure_count: u64,
     pub sample_count: u64,
 }
 
+/// The fullest a session's context got in a range, for finding
+/// conversations that should have been restarted sooner.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SessionContextPeak {
+    pub session_id: String,
+    pub model: String,
+    pub peak_context_used: u64,
+    pub context_window: u64,
+    /// `peak_context_used` as a percentage of `context_window`.
+    pub peak_pressure_pct: f64,
+    /// When the peak was first reached.
+    pub peak_at: String,
+    pub event_count: u64,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ActiveSession {
     pub session_id: String,
     pub model: String,
@@ -43,9 +115,51 @@ pub struct ActiveSession {
     pub session_start: String,
     pub context_used: u64,
     pub context_window: u64,
+    /// Tokens the session used within the active window.
+    #[serde(default)]
+    pub window_tokens: u64,
+}
+
+/// Tokens a session on a matching model must use within the active window
+/// to count as active.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ActiveMinTokens {
+    /// Model name, with `*` wildcards as in pricing rules.
+    pub model_pattern: String,
+    pub min_tokens: u64,
+}
+
+/// What makes a session active beyond having events within the active
+/// window (`context_active_minutes`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ActiveSessionRule {
+    /// Also require the latest event's context to be open: a known window
+    /// that is not yet full.
+    #[serde(default)]
+    pub require_open_context: bool,
+    /// The first entry matching the session's model applies; models no
+    /// entry matches need no minimum.
+    #[serde(default)]
+    pub min_tokens: Vec<ActiveMinTokens>,
+}
+
+impl ActiveSessionRule {
+    pub fn admits(&self, session: &ActiveSession) -> bool {
+        let open = session.context_window > 0 && session.context_used < session.context_window;
+        let min_tokens = self
+            .min_tokens
+            .iter()
+            .find(|entry| model_matches_pattern(&session.model, &entry.model_pattern))
+            .map_or(0, |entry| entry.min_tokens);
+        (open || !self.require_open_context) && session.window_tokens >= min_tokens
+    }
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
 pub struct UsageSummary {
     pub total_tokens: u64,
     pub input_tokens: u64,
@@ -56,20 +170,46 @@ pub struct UsageSummary {
     pub input_cost_usd: Option<f64>,
     pub cached_input_cost_usd: Option<f64>,
     pub output_cost_usd: Option<f64>,
+    /// The part of `total_cost_usd` priced by a fallback rule, for models no
+    /// other rule covers; `None` when none was.
+    #[serde(default)]
+    pub estimated_cost_usd: Option<f64>,
+    /// The least trustworthy source among the priced events.
+    #[serde(default)]
+    pub cost_source: Option<CostSource>,
+    #[serde(default)]
+    pub efficiency: TokenEfficiency,
+}
+
+/// Count and sums over every event matching an events filter, not just the
+/// page returned.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EventTotals {
+    pub total_count: u64,
+    pub total_tokens: u64,
+    pub total_cost_usd: Option<f64>,
+    /// The least trustworthy source among the priced events.
+    #[serde(default)]
+    pub cost_source: Option<CostSource>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
 pub struct UsageLimitSnapshot {
     pub limit_type: String,
     pub percent_left: f64,
-    pub reset_at: String,
-    pub observed_at: String,
+    pub reset_at: Timestamp,
+    pub observed_at: Timestamp,
     pub source: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub raw_line: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
 pub struct UsageLimitWindow {
     pub window_start: Option<String>,
     pub window_end: String,
@@ -77,9 +217,14 @@ pub struct UsageLimitWindow {
     pub total_cost_usd: Option<f64>,
     pub message_count: Option<u64>,
     pub complete: bool,
+    /// The window ends at a reset no snapshot saw (Codex was not running),
+    /// placed 7 days after the previous one.
+    #[serde(default)]
+    pub interpolated: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct UsageLimitCurrentWindow {
     pub window_start: String,
     pub window_end: String,
@@ -88,21 +233,162 @@ pub struct UsageLimitCurrentWindow {
     pub message_count: Option<u64>,
 }
 
+/// Where a dollar figure came from, from most to least trustworthy; an
+/// aggregate reports the greatest (least trustworthy) source it summed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "graphql", derive(async_graphql::Enum))]
+pub enum CostSource {
+    /// Recorded with the usage itself, e.g. by an imported ccusage report.
+    Reported,
+    /// Priced from a pricing rule for the model.
+    Computed,
+    /// Priced from a fallback rule, for a model no rule covers.
+    Estimated,
+}
+
+impl CostSource {
+    /// The source of a cost priced with `rule`.
+    pub fn for_rule(rule: &PricingRule) -> Self {
+        if rule.fallback {
+            CostSource::Estimated
+        } else {
+            CostSource::Computed
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CostSource::Reported => "reported",
+            CostSource::Computed => "computed",
+            CostSource::Estimated => "estimated",
+        }
+    }
+}
+
+impl FromStr for CostSource {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "reported" => Ok(CostSource::Reported),
+            "computed" => Ok(CostSource::Computed),
+            "estimated" => Ok(CostSource::Estimated),
+            _ => Err(format!("unknown cost source: {value}")),
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl rusqlite::ToSql for CostSource {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        Ok(self.as_str().into())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl rusqlite::types::FromSql for CostSource {
+    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|err: String| rusqlite::types::FromSqlError::Other(err.into()))
+    }
+}
+
+/// How close a limit is to running out, by the percent used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum LimitLevel {
+    Ok,
+    Warn,
+    Critical,
+}
+
+/// Percent-used cutoffs at which a limit turns `warn` and `critical`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LimitThresholds {
+    pub warn_percent: f64,
+    pub critical_percent: f64,
+}
+
+impl Default for LimitThresholds {
+    fn default() -> Self {
+        Self {
+            warn_percent: 75.0,
+            critical_percent: 90.0,
+        }
+    }
+}
+
+impl LimitThresholds {
+    pub fn level(&self, used_percent: f64) -> LimitLevel {
+        if used_percent >= self.critical_percent {
+            LimitLevel::Critical
+        } else if used_percent >= self.warn_percent {
+            LimitLevel::Warn
+        } else {
+            LimitLevel::Ok
+        }
+    }
+}
+
+/// A user-defined budget of `amount` tokens or USD (`metric`) per local
+/// calendar `period` (`week`, starting Monday, or `month`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CustomLimit {
+    pub id: i64,
+    pub name: String,
+    pub period: String,
+    /// `tokens` or `cost_usd`.
+    pub metric: String,
+    pub amount: f64,
+    pub created_at: String,
+}
+
+/// A custom limit evaluated over its current period; the window ends, and
+/// the usage resets, at `window_end`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CustomLimitWindow {
+    #[serde(flatten)]
+    pub limit: CustomLimit,
+    pub window_start: String,
+    pub window_end: String,
+    /// Tokens or USD used so far, per the limit's `metric`.
+    pub used: f64,
+    pub percent_left: f64,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct UsageLimitCurrentResponse {
     pub primary: Option<UsageLimitCurrentWindow>,
     pub secondary: Option<UsageLimitCurrentWindow>,
+    #[serde(default)]
+    pub custom: Vec<CustomLimitWindow>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
 pub struct UsageEvent {
     pub id: String,
-    pub ts: String,
+    pub ts: Timestamp,
     pub model: String,
     pub usage: UsageTotals,
     pub context: ContextStatus,
     pub cost_usd: Option<f64>,
+    /// Where `cost_usd` came from; `None` while it is unknown.
+    #[serde(default)]
+    pub cost_source: Option<CostSource>,
     pub reasoning_effort: Option<String>,
+    /// Who served the request (`openai`, `azure`, `openrouter`, ...), from
+    /// the log's `session_meta`; `None` counts as [`DEFAULT_PROVIDER`].
+    pub provider: Option<String>,
     pub source: String,
     pub session_id: String,
     pub request_id: Option<String>,
@@ -110,9 +396,10 @@ pub struct UsageEvent {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct MessageEvent {
     pub id: String,
-    pub ts: String,
+    pub ts: Timestamp,
     pub role: String,
     pub source: String,
     pub session_id: String,
@@ -120,6 +407,8 @@ pub struct MessageEvent {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
 pub struct PricingRule {
     pub id: Option<i64>,
     pub model_pattern: String,
@@ -128,9 +417,40 @@ pub struct PricingRule {
     pub output_per_1m: f64,
     pub effective_from: String,
     pub effective_to: Option<String>,
+    /// Only prices events from this provider, taking precedence over rules
+    /// without one; `None` prices any provider.
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Default pricing: prices events whose model no other rule matches,
+    /// whatever `model_pattern` says. Costs it produces are reported as
+    /// estimated.
+    #[serde(default)]
+    pub fallback: bool,
+}
+
+impl PricingRule {
+    /// Whether the rule prices `model` from `provider` at `ts` (compared as
+    /// text, like the database does).
+    pub fn matches(&self, model: &str, provider: Option<&str>, ts: &str) -> bool {
+        (self.fallback || model_matches_pattern(model, &self.model_pattern))
+            && provider_matches(provider, self.provider.as_deref())
+            && self.effective_from.as_str() <= ts
+            && self.effective_to.as_deref().is_none_or(|end| ts < end)
+    }
+
+    /// Of several matching rules, the greatest key wins: any model rule over
+    /// a fallback, then provider-scoped over unscoped, then the newest.
+    pub fn precedence(&self) -> (bool, bool, &str) {
+        (
+            !self.fallback,
+            self.provider.is_some(),
+            &self.effective_from,
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct PricingRuleInput {
     pub model_pattern: String,
     pub input_per_1m: f64,
@@ -138,37 +458,543 @@ pub struct PricingRuleInput {
     pub output_per_1m: f64,
     pub effective_from: String,
     pub effective_to: Option<String>,
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub fallback: bool,
+}
+
+impl From<PricingRule> for PricingRuleInput {
+    fn from(rule: PricingRule) -> Self {
+        Self {
+            model_pattern: rule.model_pattern,
+            input_per_1m: rule.input_per_1m,
+            cached_input_per_1m: rule.cached_input_per_1m,
+            output_per_1m: rule.output_per_1m,
+            effective_from: rule.effective_from,
+            effective_to: rule.effective_to,
+            provider: rule.provider,
+            fallback: rule.fallback,
+        }
+    }
+}
+
+/// Reports and prices a model name as logged (`alias`) under another name
+/// (`model`), so renamed or preview models are not counted separately.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ModelAlias {
+    pub alias: String,
+    pub model: String,
+}
+
+/// How far the latest cost recompute got. It is saved with each chunk of
+/// re-priced events, so it can be read while the recompute runs and an
+/// interrupted one can continue where it stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CostRecomputeProgress {
+    pub codex_home_id: i64,
+    /// Events re-priced so far, out of `total`.
+    pub processed: u64,
+    pub total: u64,
+    pub complete: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CodexHome {
     pub id: i64,
     pub label: String,
     pub path: String,
     pub created_at: String,
     pub last_seen_at: Option<String>,
+    /// Hidden from home pickers and skipped by ingest; its usage still counts
+    /// when querying all homes.
+    #[serde(default)]
+    pub archived: bool,
+    /// Who the usage belongs to in team mode; `None` for the local user.
+    #[serde(default)]
+    pub user: Option<String>,
+}
+
+/// Which codex home(s) an analytics query covers. Serialized as a home id or
+/// the string `"all"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HomeScope {
+    Home(i64),
+    All,
+}
+
+impl HomeScope {
+    pub fn home_id(self) -> Option<i64> {
+        match self {
+            HomeScope::Home(id) => Some(id),
+            HomeScope::All => None,
+        }
+    }
+}
+
+impl From<i64> for HomeScope {
+    fn from(id: i64) -> Self {
+        HomeScope::Home(id)
+    }
+}
+
+impl fmt::Display for HomeScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HomeScope::Home(id) => write!(f, "{id}"),
+            HomeScope::All => f.write_str("all"),
+        }
+    }
+}
+
+impl FromStr for HomeScope {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.eq_ignore_ascii_case("all") {
+            return Ok(HomeScope::All);
+        }
+        value
+            .parse::<i64>()
+            .map(HomeScope::Home)
+            .map_err(|_| format!("invalid home_id {value:?}; expected an id or \"all\""))
+    }
+}
+
+impl Serialize for HomeScope {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            HomeScope::Home(id) => serializer.serialize_i64(*id),
+            HomeScope::All => serializer.serialize_str("all"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for HomeScope {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Id(i64),
+            Text(String),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Id(id) => Ok(HomeScope::Home(id)),
+            Raw::Text(text) => text.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+#[cfg(feature = "openapi")]
+impl utoipa::PartialSchema for HomeScope {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        use utoipa::openapi::schema::{
+            KnownFormat, ObjectBuilder, OneOfBuilder, SchemaFormat, Type,
+        };
+        OneOfBuilder::new()
+            .description(Some("A codex home id, or \"all\" to aggregate every home."))
+            .item(
+                ObjectBuilder::new()
+                    .schema_type(Type::Integer)
+                    .format(Some(SchemaFormat::KnownFormat(KnownFormat::Int64))),
+            )
+            .item(
+                ObjectBuilder::new()
+                    .schema_type(Type::String)
+                    .enum_values(Some(["all"])),
+            )
+            .into()
+    }
+}
+
+#[cfg(feature = "openapi")]
+impl utoipa::ToSchema for HomeScope {}
+
+/// Usage totals for one codex home, used when aggregating across homes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HomeBreakdown {
+    pub codex_home_id: i64,
+    pub label: String,
+    pub path: String,
+    pub user: Option<String>,
+    pub total_tokens: u64,
+    pub total_cost_usd: Option<f64>,
+}
+
+/// Usage totals for one user across the homes labeled with their name.
+/// Homes without a user label are grouped under `user: None`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UserBreakdown {
+    pub user: Option<String>,
+    pub home_ids: Vec<i64>,
+    pub total_tokens: u64,
+    pub total_cost_usd: Option<f64>,
+}
+
+/// Usage totals for one session (log file), used for "top sessions" lists.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct SessionBreakdown {
+    pub session_id: String,
+    pub model: String,
+    pub last_seen: String,
+    pub total_tokens: u64,
+    pub total_cost_usd: Option<f64>,
+    /// User and assistant messages in the range.
+    #[serde(default)]
+    pub message_count: u64,
+}
+
+/// Tokens added by one model request (events sharing a `request_id`), used
+/// to find the prompts that grew the context the most.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RequestBreakdown {
+    pub request_id: String,
+    pub session_id: String,
+    pub model: String,
+    /// Timestamp of the request's first `token_count` event.
+    pub ts: String,
+    pub usage: UsageTotals,
+    pub total_cost_usd: Option<f64>,
+    pub event_count: u64,
+}
+
+/// A stretch of continuous activity in a session: consecutive events no
+/// more than the idle gap apart.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ActivitySegment {
+    pub start: String,
+    pub end: String,
+    pub event_count: u64,
+}
+
+/// Active time of one session in the range, split at idle gaps.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SessionActivity {
+    pub session_id: String,
+    pub active_secs: u64,
+    pub segments: Vec<ActivitySegment>,
+}
+
+/// Active time on one local day, summed over sessions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DailyActivity {
+    /// `YYYY-MM-DD` in the requested UTC offset.
+    pub day: String,
+    pub active_secs: u64,
+    /// Sessions with at least one event that day.
+    pub sessions: u64,
+}
+
+/// "Hours of Codex use": active time per session and per day. Time between
+/// events further apart than `idle_gap_minutes` does not count.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ActivityReport {
+    pub idle_gap_minutes: u32,
+    pub total_active_secs: u64,
+    pub days: Vec<DailyActivity>,
+    /// Most active first.
+    pub sessions: Vec<SessionActivity>,
+}
+
+/// Compactions seen in one session, see [`is_compaction`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SessionCompactions {
+    pub session_id: String,
+    pub compactions: u64,
+    pub last_compacted_at: String,
+}
+
+/// Compactions on one local day, summed over sessions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DailyCompactions {
+    /// `YYYY-MM-DD` in the requested UTC offset.
+    pub day: String,
+    pub compactions: u64,
+}
+
+/// How often Codex compacted its context: frequent compaction explains
+/// degraded answers and input tokens being paid for again.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CompactionReport {
+    pub total: u64,
+    pub days: Vec<DailyCompactions>,
+    /// Sessions with at least one compaction, most compactions first.
+    pub sessions: Vec<SessionCompactions>,
+}
+
+/// When a session started and stopped, as stored at ingest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SessionSpan {
+    pub session_id: String,
+    pub started_at: Timestamp,
+    pub last_event_at: Timestamp,
+    /// When Codex logged its shutdown; `None` while the session runs or if
+    /// it was left without one.
+    pub ended_at: Option<Timestamp>,
+}
+
+/// Lifetime stats of the sessions started in a range. A session without a
+/// logged shutdown counts as abandoned once it has been idle for
+/// `idle_minutes`, and as open until then.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SessionLifetimeReport {
+    pub idle_minutes: u32,
+    pub sessions: u64,
+    /// Ended with a shutdown.
+    pub completed: u64,
+    pub abandoned: u64,
+    pub open: u64,
+    /// Start to shutdown, or to the last event for abandoned sessions;
+    /// open sessions are left out.
+    pub avg_length_secs: Option<f64>,
+    /// Sessions started per day, from the first start in the range to the
+    /// range's end (or now, if earlier).
+    pub sessions_per_day: f64,
+}
+
+/// Average usage on one weekday or in one hour of the day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PeriodAverage {
+    /// 0 = Monday for weekdays; the hour (0-23) for hours.
+    pub index: u32,
+    /// `Mon`..`Sun`, or `00:00`..`23:00`.
+    pub label: String,
+    pub avg_tokens: f64,
+    pub avg_cost_usd: f64,
+}
+
+/// Usage averaged per weekday and per hour of the day. Averages divide by
+/// the calendar days counted (from the first day with usage, or the range
+/// start if later, to the range end), so quiet days pull them down.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WeekdayHourAverages {
+    pub days: u64,
+    pub weekdays: Vec<PeriodAverage>,
+    pub hours: Vec<PeriodAverage>,
+}
+
+/// A span of [`split_activity`] output, in Unix seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActivitySpan {
+    pub start: i64,
+    pub end: i64,
+    pub events: u64,
+}
+
+impl ActivitySpan {
+    /// A span with a single event lasts zero seconds.
+    pub fn duration_secs(&self) -> u64 {
+        (self.end - self.start).max(0) as u64
+    }
+}
+
+/// One item in a session's drill-down timeline, ordered by `ts`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SessionTimelineEntry {
+    /// A user or assistant message.
+    Message { ts: String, role: String },
+    /// Tokens added by one `token_count` event.
+    TokenCount {
+        ts: String,
+        model: String,
+        usage: UsageTotals,
+        context_used: u64,
+        context_window: u64,
+        cost_usd: Option<f64>,
+    },
+    /// The reasoning effort differs from the previous token event's.
+    EffortChange {
+        ts: String,
+        model: String,
+        from: Option<String>,
+        to: Option<String>,
+    },
+}
+
+impl SessionTimelineEntry {
+    pub fn ts(&self) -> &str {
+        match self {
+            SessionTimelineEntry::Message { ts, .. }
+            | SessionTimelineEntry::TokenCount { ts, .. }
+            | SessionTimelineEntry::EffortChange { ts, .. } => ts,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SessionTimeline {
+    pub session_id: String,
+    pub entries: Vec<SessionTimelineEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApiToken {
+    pub id: String,
+    pub label: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Webhook {
+    pub id: i64,
+    pub url: String,
+    /// Shared secret used to sign payloads; never serialized back to clients.
+    #[serde(skip_serializing, default)]
+    pub secret: Option<String>,
+    pub has_secret: bool,
+    pub event_types: Vec<String>,
+    pub enabled: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event_type: String,
+    pub payload: String,
+    pub status: String,
+    pub attempts: u32,
+    pub response_status: Option<u16>,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub last_attempt_at: Option<String>,
+}
+
+/// A report or status snapshot sent once a day at a local time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Schedule {
+    pub id: i64,
+    /// `report` (the daily report) or `status` (today's usage and the 5h /
+    /// 7d limits).
+    pub payload: String,
+    /// Local `HH:MM` it is sent at.
+    pub local_time: String,
+    /// `webhook` or `email`.
+    pub target: String,
+    /// The webhook URL or the email recipient.
+    pub destination: String,
+    /// SMTP server for email, `smtp[s]://user:password@host[:port]`; never
+    /// serialized back to clients.
+    #[serde(skip_serializing, default)]
+    pub smtp_url: Option<String>,
+    pub email_from: Option<String>,
+    pub enabled: bool,
+    /// Local date (`YYYY-MM-DD`) it last ran on; a schedule created after
+    /// its time starts out with the creation date, so it first runs the
+    /// next day.
+    pub last_sent_on: Option<String>,
+    /// Why the last run failed, if it did.
+    pub last_error: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ScheduleInput {
+    pub payload: String,
+    pub local_time: String,
+    pub target: String,
+    pub destination: String,
+    pub smtp_url: Option<String>,
+    pub email_from: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct TimeRange {
     pub start: String,
     pub end: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
 pub struct TimeSeriesPoint {
     pub bucket_start: String,
     pub value: f64,
 }
 
+/// One series of a grouped timeseries, such as the cost of one reasoning
+/// effort over time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GroupedTimeSeries {
+    /// The model or effort; `None` for events logged without an effort.
+    pub group: Option<String>,
+    pub points: Vec<TimeSeriesPoint>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
 pub struct ModelBreakdown {
     pub model: String,
     pub total_tokens: u64,
     pub total_cost_usd: Option<f64>,
 }
 
+/// Usage totals for one provider; events logged without one count as
+/// [`DEFAULT_PROVIDER`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ProviderBreakdown {
+    pub provider: String,
+    pub total_tokens: u64,
+    pub total_cost_usd: Option<f64>,
+}
+
+/// One model's totals in a range and in the baseline it is compared with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ModelComparison {
+    pub model: String,
+    pub total_tokens: u64,
+    pub baseline_total_tokens: u64,
+    pub total_cost_usd: Option<f64>,
+    pub baseline_total_cost_usd: Option<f64>,
+}
+
+/// Totals for `range` next to `baseline_range`. `models` covers every model
+/// used in either range, largest in `range` first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RangeComparison {
+    pub range: TimeRange,
+    pub baseline_range: TimeRange,
+    pub current: UsageSummary,
+    pub baseline: UsageSummary,
+    pub models: Vec<ModelComparison>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ModelTokenBreakdown {
     pub model: String,
     pub input_tokens: u64,
@@ -176,9 +1002,12 @@ pub struct ModelTokenBreakdown {
     pub output_tokens: u64,
     pub reasoning_output_tokens: u64,
     pub total_tokens: u64,
+    #[serde(default)]
+    pub efficiency: TokenEfficiency,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ModelEffortTokenBreakdown {
     pub model: String,
     pub reasoning_effort: Option<String>,
@@ -190,6 +1019,7 @@ pub struct ModelEffortTokenBreakdown {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ModelCostBreakdown {
     pub model: String,
     pub input_tokens: u64,
@@ -201,9 +1031,12 @@ pub struct ModelCostBreakdown {
     pub cached_input_cost_usd: Option<f64>,
     pub output_cost_usd: Option<f64>,
     pub total_cost_usd: Option<f64>,
+    #[serde(default)]
+    pub efficiency: TokenEfficiency,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ModelEffortCostBreakdown {
     pub model: String,
     pub reasoning_effort: Option<String>,
@@ -219,6 +1052,7 @@ pub struct ModelEffortCostBreakdown {
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CostBreakdown {
     pub input_cost_usd: f64,
     pub cached_input_cost_usd: f64,
@@ -226,6 +1060,20 @@ pub struct CostBreakdown {
     pub total_cost_usd: f64,
 }
 
+/// Codex's built-in provider, assumed for events logged without one.
+pub const DEFAULT_PROVIDER: &str = "openai";
+
+/// Whether a rule scoped to `rule_provider` (any provider when `None`)
+/// prices an event from `provider`. Names compare case-insensitively.
+pub fn provider_matches(provider: Option<&str>, rule_provider: Option<&str>) -> bool {
+    match rule_provider {
+        Some(rule_provider) => {
+            rule_provider.eq_ignore_ascii_case(provider.unwrap_or(DEFAULT_PROVIDER))
+        }
+        None => true,
+    }
+}
+
 pub fn model_matches_pattern(model: &str, pattern: &str) -> bool {
     let model = model.to_ascii_lowercase();
     let pattern = pattern.to_ascii_lowercase();
@@ -279,6 +1127,48 @@ pub fn compute_cost_breakdown(usage: UsageTotals, rule: &PricingRule) -> CostBre
     }
 }
 
+/// Splits a session's event times (Unix seconds, oldest first) into spans
+/// wherever two consecutive events are more than `idle_gap_secs` apart.
+pub fn split_activity(times: &[i64], idle_gap_secs: i64) -> Vec<ActivitySpan> {
+    let mut spans: Vec<ActivitySpan> = Vec::new();
+    for &time in times {
+        match spans.last_mut() {
+            Some(span) if time - span.end <= idle_gap_secs => {
+                span.end = span.end.max(time);
+                span.events += 1;
+            }
+            _ => spans.push(ActivitySpan {
+                start: time,
+                end: time,
+                events: 1,
+            }),
+        }
+    }
+    spans
+}
+
+/// Context below this many tokens is too small for a drop to mean Codex
+/// compacted it rather than, say, a fresh task in a short session.
+pub const COMPACTION_MIN_CONTEXT: u64 = 20_000;
+
+/// Whether the context going from `previous` to `current` tokens between two
+/// consecutive events of a session looks like an auto-compaction: it shrank
+/// to half or less of a context of at least [`COMPACTION_MIN_CONTEXT`].
+pub fn is_compaction(previous: u64, current: u64) -> bool {
+    previous >= COMPACTION_MIN_CONTEXT && current.saturating_mul(2) <= previous
+}
+
+/// `8100` seconds -> `2h 15m`; under a minute reads `<1m`.
+pub fn format_duration(seconds: i64) -> String {
+    let minutes = seconds.max(0) / 60;
+    match (minutes / (60 * 24), minutes / 60 % 24, minutes % 60) {
+        (0, 0, 0) => "<1m".to_string(),
+        (0, 0, m) => format!("{m}m"),
+        (0, h, m) => format!("{h}h {m}m"),
+        (d, h, _) => format!("{d}d {h}h"),
+    }
+}
+
 pub fn session_id_from_source(source: &str) -> String {
     let file_name = Path::new(source).file_name().and_then(|name| name.to_str());
     let stem =
@@ -298,6 +1188,15 @@ pub fn session_id_from_source(source: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn home_scope_accepts_ids_and_all() {
+        assert_eq!("all".parse::<HomeScope>(), Ok(HomeScope::All));
+        assert_eq!("7".parse::<HomeScope>(), Ok(HomeScope::Home(7)));
+        assert!("home".parse::<HomeScope>().is_err());
+        assert_eq!(HomeScope::All.to_string(), "all");
+        assert_eq!(HomeScope::Home(3).home_id(), Some(3));
+    }
+
     #[test]
     fn cost_breakdown_does_not_double_count_reasoning() {
         let rule = PricingRule {
@@ -308,6 +1207,8 @@ mod tests {
             output_per_1m: 14000.0,
             effective_from: "2025-01-01T00:00:00Z".to_string(),
             effective_to: None,
+            provider: None,
+            fallback: false,
         };
         let usage = UsageTotals {
             input_tokens: 10_000,
@@ -342,6 +1243,8 @@ mod tests {
             output_per_1m: 2000.0,
             effective_from: "2025-01-01T00:00:00Z".to_string(),
             effective_to: None,
+            provider: None,
+            fallback: false,
         };
         let usage = UsageTotals {
             input_tokens: 2_000,
@@ -357,6 +1260,131 @@ mod tests {
         assert!((cost.output_cost_usd - expected_output).abs() < 1e-9);
     }
 
+    #[test]
+    fn active_session_rule_needs_open_context_and_model_minimum() {
+        let session = |model: &str, context_used: u64, window_tokens: u64| ActiveSession {
+            session_id: "s".to_string(),
+            model: model.to_string(),
+            last_seen: "2025-12-19T10:00:00.000Z".to_string(),
+            session_start: "2025-12-19T09:00:00.000Z".to_string(),
+            context_used,
+            context_window: 1000,
+            window_tokens,
+        };
+        assert!(ActiveSessionRule::default().admits(&session("gpt-5", 1000, 0)));
+
+        let rule = ActiveSessionRule {
+            require_open_context: true,
+            min_tokens: vec![
+                ActiveMinTokens {
+                    model_pattern: "gpt-5*-codex".to_string(),
+                    min_tokens: 5000,
+                },
+                ActiveMinTokens {
+                    model_pattern: "*".to_string(),
+                    min_tokens: 100,
+                },
+            ],
+        };
+        assert!(rule.admits(&session("gpt-5", 500, 100)));
+        assert!(!rule.admits(&session("gpt-5", 1000, 100)));
+        assert!(!rule.admits(&session("gpt-5", 500, 99)));
+        assert!(!rule.admits(&session("gpt-5.1-codex", 500, 4999)));
+        assert!(rule.admits(&session("gpt-5.1-codex", 500, 5000)));
+    }
+
+    #[test]
+    fn fallback_rules_match_any_model_below_other_rules() {
+        let rule = |model_pattern: &str, fallback| PricingRule {
+            id: None,
+            model_pattern: model_pattern.to_string(),
+            input_per_1m: 1.0,
+            cached_input_per_1m: 0.1,
+            output_per_1m: 2.0,
+            effective_from: "2025-01-01T00:00:00Z".to_string(),
+            effective_to: None,
+            provider: None,
+            fallback,
+        };
+        let fallback = rule("default", true);
+        let specific = rule("gpt-5*", false);
+
+        assert!(fallback.matches("mystery-model", None, "2025-06-01T00:00:00Z"));
+        assert!(!fallback.matches("mystery-model", None, "2024-06-01T00:00:00Z"));
+        assert!(!specific.matches("mystery-model", None, "2025-06-01T00:00:00Z"));
+        assert!(specific.precedence() > fallback.precedence());
+    }
+
+    #[test]
+    fn provider_rules_match_their_provider_and_default_to_openai() {
+        assert!(provider_matches(Some("azure"), None));
+        assert!(provider_matches(Some("Azure"), Some("azure")));
+        assert!(!provider_matches(Some("openrouter"), Some("azure")));
+        assert!(provider_matches(None, Some("openai")));
+        assert!(!provider_matches(None, Some("azure")));
+    }
+
+    #[test]
+    fn split_activity_breaks_at_idle_gaps() {
+        let spans = split_activity(&[0, 60, 120, 4_000, 4_030, 20_000], 1_800);
+        assert_eq!(
+            spans,
+            vec![
+                ActivitySpan {
+                    start: 0,
+                    end: 120,
+                    events: 3
+                },
+                ActivitySpan {
+                    start: 4_000,
+                    end: 4_030,
+                    events: 2
+                },
+                ActivitySpan {
+                    start: 20_000,
+                    end: 20_000,
+                    events: 1
+                },
+            ]
+        );
+        let active: u64 = spans.iter().map(ActivitySpan::duration_secs).sum();
+        assert_eq!(active, 150);
+        assert!(split_activity(&[], 60).is_empty());
+    }
+
+    #[test]
+    fn efficiency_ratios_skip_empty_denominators() {
+        let efficiency = TokenEfficiency::from_tokens(1_000, 250, 500, 100);
+        assert_eq!(efficiency.output_input_ratio, Some(0.5));
+        assert_eq!(efficiency.reasoning_output_share, Some(0.2));
+        assert_eq!(efficiency.cached_input_share, Some(0.25));
+        assert_eq!(
+            TokenEfficiency::from_tokens(0, 0, 0, 0),
+            TokenEfficiency::default()
+        );
+    }
+
+    #[test]
+    fn compaction_needs_a_sharp_drop_from_a_large_context() {
+        assert!(is_compaction(180_000, 30_000));
+        assert!(is_compaction(40_000, 20_000));
+        assert!(!is_compaction(40_000, 20_001));
+        assert!(!is_compaction(12_000, 1_000));
+        assert!(!is_compaction(30_000, 45_000));
+    }
+
+    #[test]
+    fn limit_thresholds_pick_the_highest_level_reached() {
+        let thresholds = LimitThresholds::default();
+        assert_eq!(thresholds.level(10.0), LimitLevel::Ok);
+        assert_eq!(thresholds.level(75.0), LimitLevel::Warn);
+        assert_eq!(thresholds.level(95.0), LimitLevel::Critical);
+        assert_eq!(
+            serde_json::to_string(&LimitLevel::Critical).expect("serialize"),
+            "\"critical\""
+        );
+    }
+
     #[test]
     fn session_id_from_source_parses_rollout_name() {
         let source = "/tmp/rollout-2025-12-20T00-00-00Z-abc123.jsonl";
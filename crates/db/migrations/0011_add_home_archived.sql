@@ -0,0 +1,2 @@
+-- Archived homes keep their data but are hidden from pickers and never ingested.
+ALTER TABLE codex_home ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+-- Session id read from a log's session_meta line, kept so resumed ingests
+-- tag new events with it.
+ALTER TABLE ingest_cursor ADD COLUMN session_id TEXT;
@@ -0,0 +1,6 @@
+-- Model names to report and price under another name. Events keep the
+-- model they were logged with; aliases are applied when reading.
+CREATE TABLE IF NOT EXISTS model_alias (
+  alias TEXT PRIMARY KEY,
+  model TEXT NOT NULL
+);
@@ -0,0 +1,7 @@
+-- Provider that served each event (from the log's session_meta), the
+-- provider a pricing rule is limited to, and the provider a cursor has seen
+-- so resumed ingests keep tagging events with it. NULL means unknown on
+-- events and any provider on rules.
+ALTER TABLE usage_event ADD COLUMN provider TEXT;
+ALTER TABLE pricing_rule ADD COLUMN provider TEXT;
+ALTER TABLE ingest_cursor ADD COLUMN provider TEXT;
@@ -0,0 +1,5 @@
+-- Raw log lines repeat every line of the session logs and dominate the
+-- database size, so they are now stored zstd-compressed. `migrate` moves
+-- existing lines over and clears the old text column.
+ALTER TABLE usage_event ADD COLUMN raw_json_z BLOB;
+ALTER TABLE message_event ADD COLUMN raw_json_z BLOB;
@@ -0,0 +1,10 @@
+-- User-defined token or cost budgets per calendar week or month, reported
+-- next to the 5h / 7d limits Codex logs.
+CREATE TABLE IF NOT EXISTS custom_limit (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL,
+  period TEXT NOT NULL,
+  metric TEXT NOT NULL,
+  amount REAL NOT NULL,
+  created_at TEXT NOT NULL
+);
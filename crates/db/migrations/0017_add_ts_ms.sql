@@ -0,0 +1,6 @@
+-- Event time as epoch milliseconds next to the RFC 3339 text, so range
+-- filters, ordering, and bucketing compare integers. `migrate` fills it in
+-- for existing rows; ingest writes both columns.
+ALTER TABLE usage_event ADD COLUMN ts_ms INTEGER;
+CREATE INDEX IF NOT EXISTS idx_usage_event_ts_ms ON usage_event (ts_ms);
+CREATE INDEX IF NOT EXISTS idx_usage_event_home_ts_ms ON usage_event (codex_home_id, ts_ms);
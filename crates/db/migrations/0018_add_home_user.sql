@@ -0,0 +1,3 @@
+-- Team mode: the person a home's usage belongs to, so a lead can merge
+-- teammates' databases into one and break usage down per user.
+ALTER TABLE codex_home ADD COLUMN user_label TEXT;
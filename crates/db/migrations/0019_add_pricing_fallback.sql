@@ -0,0 +1,3 @@
+-- Default pricing: a fallback rule prices events whose model no other rule
+-- matches, so they count toward cost as an estimate instead of dropping out.
+ALTER TABLE pricing_rule ADD COLUMN fallback INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,4 @@
+-- Where each event's cost came from: `reported` with the usage (imported
+-- ccusage reports), `computed` from a pricing rule, or `estimated` from a
+-- fallback rule. `migrate` labels existing priced rows.
+ALTER TABLE usage_event ADD COLUMN cost_source TEXT;
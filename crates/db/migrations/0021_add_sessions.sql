@@ -0,0 +1,29 @@
+-- Start, last activity, and shutdown of each session. Kept up to date as
+-- events are inserted; `ended_at` is set when a log records a shutdown.
+CREATE TABLE IF NOT EXISTS session (
+  codex_home_id INTEGER NOT NULL,
+  session_id TEXT NOT NULL,
+  started_at TEXT NOT NULL,
+  last_event_at TEXT NOT NULL,
+  ended_at TEXT,
+  PRIMARY KEY (codex_home_id, session_id),
+  FOREIGN KEY (codex_home_id) REFERENCES codex_home(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_home_started
+  ON session (codex_home_id, started_at);
+
+-- Times are written the way `Timestamp` writes them (milliseconds, `Z`), so
+-- they compare as text with the ones ingest stores later.
+INSERT OR IGNORE INTO session (codex_home_id, session_id, started_at, last_event_at)
+SELECT codex_home_id, session_id,
+       strftime('%Y-%m-%dT%H:%M:%fZ', MIN(julianday(ts))),
+       strftime('%Y-%m-%dT%H:%M:%fZ', MAX(julianday(ts)))
+FROM (
+  SELECT codex_home_id, COALESCE(session_id, source) AS session_id, ts FROM usage_event
+  WHERE codex_home_id IS NOT NULL
+  UNION ALL
+  SELECT codex_home_id, session_id, ts FROM message_event
+  WHERE codex_home_id IS NOT NULL
+)
+GROUP BY codex_home_id, session_id;
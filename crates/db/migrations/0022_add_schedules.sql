@@ -0,0 +1,15 @@
+-- Reports and status snapshots sent once a day at a local time, to a
+-- webhook or by email.
+CREATE TABLE IF NOT EXISTS schedule (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  payload TEXT NOT NULL,
+  local_time TEXT NOT NULL,
+  target TEXT NOT NULL,
+  destination TEXT NOT NULL,
+  smtp_url TEXT,
+  email_from TEXT,
+  enabled INTEGER NOT NULL DEFAULT 1,
+  last_sent_on TEXT,
+  last_error TEXT,
+  created_at TEXT NOT NULL
+);
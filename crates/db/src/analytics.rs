@@ -1,19 +1,29 @@
 use std::collections::{BTreeMap, HashMap};
 
-use chrono::{DateTime, Local};
-use rusqlite::params;
-use tracker_core::{TimeRange, TimeSeriesPoint, UsageEvent, UsageSummary};
+use chrono::{DateTime, Duration, NaiveTime, Offset, TimeZone, Timelike, Utc};
+use rusqlite::{OptionalExtension, ToSql, params, params_from_iter};
+use tracker_core::{
+    CostSource, EventTotals, GroupedTimeSeries, HomeScope, TimeRange, TimeSeriesPoint, UsageEvent,
+    UsageSummary, UsageTotals, compute_cost_usd,
+};
 
 use crate::Db;
 use crate::error::Result;
-use crate::helpers::{compute_cost_from_pricing, compute_totals, delta_usage, row_to_usage_event};
-use crate::types::{Bucket, Metric};
+use crate::helpers::{
+    compute_cost_from_pricing, compute_totals, delta_usage, matching_rule, price_usage,
+    range_millis, row_to_usage_event, rule_matches, usage_from_sums,
+};
+use crate::types::{Bucket, GroupBy, Metric, RowUsage};
+use crate::usage_rows::{ALIASED_MODEL, usage_delta_ctes};
+
+const QUARTER_HOUR_MS: i64 = 900_000;
 
 impl Db {
-    pub fn summary(&self, range: &TimeRange, codex_home_id: i64) -> Result<UsageSummary> {
+    pub fn summary(&self, range: &TimeRange, home: impl Into<HomeScope>) -> Result<UsageSummary> {
         let pricing = self.list_pricing_rules()?;
-        let rows = self.load_usage_rows(range, None, codex_home_id)?;
-        let (totals, cost, cost_known) = compute_totals(rows, &pricing)?;
+        let rows = self.load_usage_rows(range, None, home.into())?;
+        let (totals, cost, cost_source, estimated_cost) = compute_totals(rows, &pricing)?;
+        let cost_known = cost_source.is_some();
         Ok(UsageSummary {
             total_tokens: totals.total_tokens,
             input_tokens: totals.input_tokens,
@@ -40,6 +50,9 @@ impl Db {
             } else {
                 None
             },
+            estimated_cost_usd: estimated_cost,
+            cost_source,
+            efficiency: totals.efficiency(),
         })
     }
 
@@ -58,40 +71,129 @@ impl Db {
             .map_err(crate::error::DbError::from)
     }
 
-    pub fn timeseries(
+    /// Buckets usage by hour or day. Per-source deltas are summed per
+    /// quarter hour in SQL, from a `LAG` window over the range as in
+    /// [`Db::usage_deltas`], and each quarter goes to the hour or day of
+    /// `tz` it falls in, so a range across a DST change keeps its buckets
+    /// local. `bucket_start` carries the offset in effect at the bucket's
+    /// start. Events without a stored cost are priced per quarter hour and
+    /// model with the rule in effect at its first event.
+    pub fn timeseries<Tz: TimeZone>(
         &self,
         range: &TimeRange,
         bucket: Bucket,
         metric: Metric,
-        codex_home_id: i64,
+        home: impl Into<HomeScope>,
+        tz: &Tz,
     ) -> Result<Vec<TimeSeriesPoint>> {
-        let pricing = self.list_pricing_rules()?;
-        let rows = self.load_usage_rows(range, None, codex_home_id)?;
-        let mut buckets: BTreeMap<String, f64> = BTreeMap::new();
-        let mut prev_by_source: HashMap<String, tracker_core::UsageTotals> = HashMap::new();
-        for row in rows {
-            let prev = prev_by_source.get(&row.source);
-            let delta = delta_usage(prev, row.usage);
-            prev_by_source.insert(row.source.clone(), row.usage);
-            let ts = DateTime::parse_from_rfc3339(&row.ts)?;
-            let local = ts.with_timezone(&Local);
-            let bucket_start = match bucket {
-                Bucket::Hour => local.format("%Y-%m-%dT%H:00:00%:z").to_string(),
-                Bucket::Day => local.format("%Y-%m-%dT00:00:00%:z").to_string(),
-            };
+        let series = self.bucketed(range, bucket, metric, None, home.into(), tz)?;
+        Ok(series.into_values().next().unwrap_or_default())
+    }
+
+    /// [`Db::timeseries`] split into one series per model or reasoning
+    /// effort, ordered by group. Events without an effort form the `None`
+    /// group.
+    pub fn timeseries_grouped<Tz: TimeZone>(
+        &self,
+        range: &TimeRange,
+        bucket: Bucket,
+        metric: Metric,
+        group_by: GroupBy,
+        home: impl Into<HomeScope>,
+        tz: &Tz,
+    ) -> Result<Vec<GroupedTimeSeries>> {
+        let series = self.bucketed(range, bucket, metric, Some(group_by), home.into(), tz)?;
+        Ok(series
+            .into_iter()
+            .map(|(group, points)| GroupedTimeSeries { group, points })
+            .collect())
+    }
+
+    fn bucketed<Tz: TimeZone>(
+        &self,
+        range: &TimeRange,
+        bucket: Bucket,
+        metric: Metric,
+        group_by: Option<GroupBy>,
+        home: HomeScope,
+        tz: &Tz,
+    ) -> Result<BTreeMap<Option<String>, Vec<TimeSeriesPoint>>> {
+        let (start_ms, end_ms) = range_millis(range)?;
+        let mut values: Vec<&dyn ToSql> = vec![&start_ms, &end_ms];
+        let home_id = home.home_id();
+        let mut home_filter = String::new();
+        if let Some(home_id) = home_id.as_ref() {
+            values.push(home_id);
+            home_filter = format!(" AND codex_home_id = ?{} ", values.len());
+        }
+        let group = match group_by {
+            None => "NULL",
+            Some(GroupBy::Model) => "model",
+            Some(GroupBy::Effort) => "reasoning_effort",
+        };
+        let ctes = usage_delta_ctes(
+            &["reasoning_effort"],
+            &format!("usage_event WHERE ts_ms >= ?1 AND ts_ms < ?2 {home_filter}"),
+            "source",
+        );
+        // UTC offsets are whole quarter hours, so every event in a quarter
+        // falls in the same local hour and day.
+        let sql = format!(
+            r#"
+            WITH {ctes}
+            SELECT ts_ms / {QUARTER_HOUR_MS} AS quarter, model, MIN(ts),
+                   SUM(delta_total_tokens), TOTAL(cost_usd),
+                   TOTAL(CASE WHEN cost_usd IS NULL THEN delta_input_tokens END),
+                   TOTAL(CASE WHEN cost_usd IS NULL THEN delta_cached_input_tokens END),
+                   TOTAL(CASE WHEN cost_usd IS NULL THEN delta_output_tokens END),
+                   TOTAL(CASE WHEN cost_usd IS NULL THEN delta_reasoning_output_tokens END),
+                   TOTAL(CASE WHEN cost_usd IS NULL THEN delta_total_tokens END),
+                   {group} AS series, provider
+            FROM deltas
+            GROUP BY quarter, model, provider, series
+            "#
+        );
+        let pricing = match metric {
+            Metric::Tokens => Vec::new(),
+            Metric::Cost => self.list_pricing_rules()?,
+        };
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut rows = stmt.query(params_from_iter(values))?;
+        let mut starts: HashMap<i64, (i64, String)> = HashMap::new();
+        let mut series: BTreeMap<Option<String>, BTreeMap<i64, (String, f64)>> = BTreeMap::new();
+        while let Some(row) = rows.next()? {
+            let quarter: i64 = row.get(0)?;
             let value = match metric {
-                Metric::Tokens => delta.total_tokens as f64,
-                Metric::Cost => row
-                    .cost_usd
-                    .unwrap_or_else(|| compute_cost_from_pricing(&pricing, &row, delta)),
+                Metric::Tokens => row.get::<_, i64>(3)? as f64,
+                Metric::Cost => {
+                    let unpriced = usage_from_sums(row, 5)?;
+                    row.get::<_, f64>(4)?
+                        + price_usage(&pricing, row.get(1)?, row.get(11)?, row.get(2)?, unpriced)
+                            .unwrap_or_default()
+                }
             };
-            *buckets.entry(bucket_start).or_insert(0.0) += value;
+            let (start_ms, label) = starts
+                .entry(quarter)
+                .or_insert_with(|| bucket_start(quarter * QUARTER_HOUR_MS, bucket, tz))
+                .clone();
+            series
+                .entry(row.get(10)?)
+                .or_default()
+                .entry(start_ms)
+                .or_insert((label, 0.0))
+                .1 += value;
         }
-        Ok(buckets
+        Ok(series
             .into_iter()
-            .map(|(bucket_start, value)| TimeSeriesPoint {
-                bucket_start,
-                value,
+            .map(|(group, buckets)| {
+                let points = buckets
+                    .into_values()
+                    .map(|(bucket_start, value)| TimeSeriesPoint {
+                        bucket_start,
+                        value,
+                    })
+                    .collect();
+                (group, points)
             })
             .collect())
     }
@@ -108,35 +210,33 @@ impl Db {
             r#"
             SELECT id, ts, model, input_tokens, cached_input_tokens, output_tokens,
                    reasoning_output_tokens, total_tokens, context_used, context_window,
-                   cost_usd, source, session_id, request_id, raw_json, reasoning_effort
+                   cost_usd, source, session_id, request_id, COALESCE(raw_json_z, raw_json),
+                   reasoning_effort, provider, cost_source
             FROM usage_event
-            WHERE codex_home_id = ?1 AND ts >= ?2 AND ts < ?3
+            WHERE codex_home_id = ?1 AND ts_ms >= ?2 AND ts_ms < ?3
             "#,
         );
         if model.is_some() {
-            sql.push_str(" AND model = ?4 ");
-            sql.push_str(" ORDER BY ts DESC LIMIT ?5 OFFSET ?6");
+            // The filter names a model as reported, so it also matches the
+            // aliases that map to it.
+            sql.push_str(&format!(" AND {ALIASED_MODEL} = ?4 "));
+            sql.push_str(" ORDER BY ts_ms DESC LIMIT ?5 OFFSET ?6");
         } else {
-            sql.push_str(" ORDER BY ts DESC LIMIT ?4 OFFSET ?5");
+            sql.push_str(" ORDER BY ts_ms DESC LIMIT ?4 OFFSET ?5");
         }
+        let (start_ms, end_ms) = range_millis(range)?;
         let mut stmt = self.conn.prepare(&sql)?;
         let mut rows = if let Some(model) = model {
             stmt.query(params![
                 codex_home_id,
-                range.start,
-                range.end,
+                start_ms,
+                end_ms,
                 model,
                 limit,
                 offset
             ])?
         } else {
-            stmt.query(params![
-                codex_home_id,
-                range.start,
-                range.end,
-                limit,
-                offset
-            ])?
+            stmt.query(params![codex_home_id, start_ms, end_ms, limit, offset])?
         };
         let mut events = Vec::new();
         while let Some(row) = rows.next()? {
@@ -144,4 +244,151 @@ impl Db {
         }
         Ok(events)
     }
+
+    /// Count, tokens, and cost of the events [`Db::list_usage_events`]
+    /// pages through. Deltas are taken per source before the model filter,
+    /// so a session that switches models is not counted twice.
+    pub fn usage_event_totals(
+        &self,
+        range: &TimeRange,
+        model: Option<&str>,
+        codex_home_id: i64,
+    ) -> Result<EventTotals> {
+        let pricing = self.list_pricing_rules()?;
+        let rows = self.load_usage_rows(range, None, HomeScope::Home(codex_home_id))?;
+        let mut totals = EventTotals::default();
+        let mut cost = 0.0;
+        let mut prev_by_source: HashMap<String, UsageTotals> = HashMap::new();
+        for row in rows {
+            let delta = delta_usage(prev_by_source.get(&row.source), row.usage);
+            prev_by_source.insert(row.source.clone(), row.usage);
+            if model.is_some_and(|model| row.model != model) {
+                continue;
+            }
+            totals.total_count += 1;
+            totals.total_tokens += delta.total_tokens;
+            if let Some(rule) = matching_rule(&pricing, &row) {
+                cost += compute_cost_usd(delta, rule);
+                totals.cost_source = totals.cost_source.max(Some(CostSource::for_rule(rule)));
+            }
+        }
+        totals.total_cost_usd = totals.cost_source.is_some().then_some(cost);
+        Ok(totals)
+    }
+
+    /// A single usage event by id, raw log line included, or `None` when the
+    /// home has no such event.
+    pub fn get_usage_event(&self, codex_home_id: i64, id: &str) -> Result<Option<UsageEvent>> {
+        Ok(self
+            .conn
+            .query_row(
+                r#"
+                SELECT id, ts, model, input_tokens, cached_input_tokens, output_tokens,
+                       reasoning_output_tokens, total_tokens, context_used, context_window,
+                       cost_usd, source, session_id, request_id, COALESCE(raw_json_z, raw_json),
+                       reasoning_effort, provider, cost_source
+                FROM usage_event
+                WHERE codex_home_id = ?1 AND id = ?2
+                "#,
+                params![codex_home_id, id],
+                row_to_usage_event,
+            )
+            .optional()?)
+    }
+
+    /// Returns every usage event in the range, oldest first, for bulk exports.
+    pub fn export_usage_events(
+        &self,
+        range: &TimeRange,
+        codex_home_id: i64,
+    ) -> Result<Vec<UsageEvent>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, ts, model, input_tokens, cached_input_tokens, output_tokens,
+                   reasoning_output_tokens, total_tokens, context_used, context_window,
+                   cost_usd, source, session_id, request_id, COALESCE(raw_json_z, raw_json),
+                   reasoning_effort, provider, cost_source
+            FROM usage_event
+            WHERE codex_home_id = ?1 AND ts_ms >= ?2 AND ts_ms < ?3
+            ORDER BY ts_ms ASC, id ASC
+            "#,
+        )?;
+        let (start_ms, end_ms) = range_millis(range)?;
+        let mut rows = stmt.query(params![codex_home_id, start_ms, end_ms])?;
+        let mut events = Vec::new();
+        while let Some(row) = rows.next()? {
+            events.push(row_to_usage_event(row)?);
+        }
+        Ok(events)
+    }
+
+    /// Per-event token deltas in the range, oldest first. `usage` holds the
+    /// tokens added by each event and `cost_usd` its stored or priced cost
+    /// (`None` when no pricing rule matches).
+    pub fn usage_deltas(
+        &self,
+        range: &TimeRange,
+        home: impl Into<HomeScope>,
+    ) -> Result<Vec<RowUsage>> {
+        let pricing = self.list_pricing_rules()?;
+        let rows = self.load_usage_rows(range, None, home.into())?;
+        let mut prev_by_source: HashMap<String, UsageTotals> = HashMap::new();
+        let mut deltas = Vec::with_capacity(rows.len());
+        for mut row in rows {
+            let delta = delta_usage(prev_by_source.get(&row.source), row.usage);
+            prev_by_source.insert(row.source.clone(), row.usage);
+            if row.cost_usd.is_none() && pricing.iter().any(|rule| rule_matches(rule, &row)) {
+                row.cost_usd = Some(compute_cost_from_pricing(&pricing, &row, delta));
+            }
+            row.usage = delta;
+            deltas.push(row);
+        }
+        deltas.sort_by(|a, b| a.ts.cmp(&b.ts).then_with(|| a.id.cmp(&b.id)));
+        Ok(deltas)
+    }
+
+    /// Cheap fingerprint of everything that feeds aggregate responses for a
+    /// home (or all homes): usage events (count, newest timestamp, stored
+    /// costs) and the pricing rules. It changes whenever ingest or pricing
+    /// updates land.
+    pub fn usage_fingerprint(&self, home: impl Into<HomeScope>) -> Result<String> {
+        let home = home.into();
+        let fingerprint = self.conn.query_row(
+            r#"
+            SELECT
+              (SELECT COUNT(*) || ':' || COALESCE(MAX(ts), '') || ':' || TOTAL(cost_usd)
+               FROM usage_event WHERE ?1 IS NULL OR codex_home_id = ?1)
+              || '|' ||
+              (SELECT COUNT(*) || ':' || COALESCE(MAX(id), 0) FROM pricing_rule)
+            "#,
+            params![home.home_id()],
+            |row| row.get::<_, String>(0),
+        )?;
+        Ok(format!("{home}|{fingerprint}"))
+    }
+}
+
+/// Start of the `tz` hour or day holding the instant `ms`, as epoch millis
+/// and as a local RFC 3339 time with the offset in effect then.
+fn bucket_start<Tz: TimeZone>(ms: i64, bucket: Bucket, tz: &Tz) -> (i64, String) {
+    let time = DateTime::from_timestamp_millis(ms).unwrap_or_default();
+    let local = time.with_timezone(tz);
+    let offset = local.offset().fix();
+    let (start, offset) = match bucket {
+        Bucket::Hour => (time - Duration::minutes(i64::from(local.minute())), offset),
+        Bucket::Day => {
+            let midnight = local.date_naive().and_time(NaiveTime::MIN);
+            match tz.from_local_datetime(&midnight).earliest() {
+                Some(start) => (start.with_timezone(&Utc), start.offset().fix()),
+                // A DST change skipped midnight; the day starts at the
+                // offset after it.
+                None => (midnight.and_utc() - offset, offset),
+            }
+        }
+    };
+    let label = start
+        .with_timezone(&offset)
+        .format("%Y-%m-%dT%H:%M:%S%:z")
+        .to_string();
+    (start.timestamp_millis(), label)
 }
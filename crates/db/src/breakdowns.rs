@@ -1,72 +1,358 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
+use rusqlite::{ToSql, params_from_iter};
 use tracker_core::{
-    CostBreakdown, ModelBreakdown, ModelCostBreakdown, ModelEffortCostBreakdown,
-    ModelEffortTokenBreakdown, ModelTokenBreakdown, TimeRange, UsageTotals,
+    CostBreakdown, DEFAULT_PROVIDER, HomeBreakdown, HomeScope, ModelBreakdown, ModelComparison,
+    ModelCostBreakdown, ModelEffortCostBreakdown, ModelEffortTokenBreakdown, ModelTokenBreakdown,
+    ProviderBreakdown, RangeComparison, RequestBreakdown, SessionBreakdown, TimeRange, UsageTotals,
+    UserBreakdown, session_id_from_source,
 };
 
 use crate::Db;
 use crate::error::Result;
 use crate::helpers::{
     add_usage, compute_cost_breakdown_from_pricing, compute_cost_from_pricing, delta_usage,
-    rule_matches,
+    price_usage, range_millis, rule_matches, usage_from_sums,
 };
+use crate::types::RowUsage;
+use crate::usage_rows::usage_delta_ctes;
 
 impl Db {
+    /// Token and cost totals per codex home, largest first. Homes without
+    /// usage in the range are included with zero totals.
+    pub fn breakdown_by_home(
+        &self,
+        range: &TimeRange,
+        home: impl Into<HomeScope>,
+    ) -> Result<Vec<HomeBreakdown>> {
+        let home = home.into();
+        let homes = self
+            .list_homes()?
+            .into_iter()
+            .filter(|candidate| home.home_id().is_none_or(|id| id == candidate.id));
+        let mut result = Vec::new();
+        for candidate in homes {
+            let summary = self.summary(range, candidate.id)?;
+            result.push(HomeBreakdown {
+                codex_home_id: candidate.id,
+                label: candidate.label,
+                path: candidate.path,
+                user: candidate.user,
+                total_tokens: summary.total_tokens,
+                total_cost_usd: summary.total_cost_usd,
+            });
+        }
+        result.sort_by_key(|item| std::cmp::Reverse(item.total_tokens));
+        Ok(result)
+    }
+
+    /// Token and cost totals per user, summed over their homes, largest
+    /// first. Homes without a user are grouped under `user: None`.
+    pub fn breakdown_by_user(
+        &self,
+        range: &TimeRange,
+        home: impl Into<HomeScope>,
+    ) -> Result<Vec<UserBreakdown>> {
+        let mut users: BTreeMap<Option<String>, UserBreakdown> = BTreeMap::new();
+        for item in self.breakdown_by_home(range, home)? {
+            let entry = users
+                .entry(item.user.clone())
+                .or_insert_with(|| UserBreakdown {
+                    user: item.user,
+                    home_ids: Vec::new(),
+                    total_tokens: 0,
+                    total_cost_usd: None,
+                });
+            entry.home_ids.push(item.codex_home_id);
+            entry.total_tokens += item.total_tokens;
+            if let Some(cost) = item.total_cost_usd {
+                *entry.total_cost_usd.get_or_insert(0.0) += cost;
+            }
+        }
+        let mut result: Vec<UserBreakdown> = users.into_values().collect();
+        for item in &mut result {
+            item.home_ids.sort_unstable();
+        }
+        result.sort_by_key(|item| std::cmp::Reverse(item.total_tokens));
+        Ok(result)
+    }
+
+    /// The `limit` sessions with the most tokens in the range, largest first.
+    pub fn breakdown_by_session(
+        &self,
+        range: &TimeRange,
+        home: impl Into<HomeScope>,
+        limit: usize,
+    ) -> Result<Vec<SessionBreakdown>> {
+        let home = home.into();
+        let pricing = self.list_pricing_rules()?;
+        let rows = self.load_usage_rows(range, None, home)?;
+        let session_ids = self.session_ids_by_source(range, home)?;
+        let mut sessions: HashMap<String, SessionBreakdown> = HashMap::new();
+        let mut prev_by_source: HashMap<String, UsageTotals> = HashMap::new();
+        for row in rows {
+            let prev = prev_by_source.get(&row.source);
+            let delta = delta_usage(prev, row.usage);
+            prev_by_source.insert(row.source.clone(), row.usage);
+            let known =
+                row.cost_usd.is_some() || pricing.iter().any(|rule| rule_matches(rule, &row));
+            let cost = row
+                .cost_usd
+                .unwrap_or_else(|| compute_cost_from_pricing(&pricing, &row, delta));
+            let session_id = session_ids
+                .get(&row.source)
+                .cloned()
+                .unwrap_or_else(|| session_id_from_source(&row.source));
+            let entry = sessions
+                .entry(session_id.clone())
+                .or_insert_with(|| SessionBreakdown {
+                    session_id,
+                    model: row.model.clone(),
+                    last_seen: row.ts.clone(),
+                    total_tokens: 0,
+                    total_cost_usd: None,
+                    message_count: 0,
+                });
+            // Rows are ordered by ts within a source, so the last one wins.
+            entry.model = row.model.clone();
+            entry.last_seen = row.ts.clone();
+            entry.total_tokens += delta.total_tokens;
+            if known {
+                entry.total_cost_usd = Some(entry.total_cost_usd.unwrap_or(0.0) + cost);
+            }
+        }
+        let message_counts = self.message_counts_by_session(range, home)?;
+        let mut result: Vec<SessionBreakdown> = sessions.into_values().collect();
+        for session in &mut result {
+            session.message_count = message_counts
+                .get(&session.session_id)
+                .copied()
+                .unwrap_or(0);
+        }
+        result.sort_by(|a, b| {
+            b.total_tokens
+                .cmp(&a.total_tokens)
+                .then_with(|| a.session_id.cmp(&b.session_id))
+        });
+        result.truncate(limit);
+        Ok(result)
+    }
+
+    /// The `limit` requests that added the most tokens in the range, largest
+    /// first. Deltas are taken per source as everywhere else and summed per
+    /// `request_id`; events without one are left out. The session and model
+    /// are those of the request's first event.
+    pub fn breakdown_by_request(
+        &self,
+        range: &TimeRange,
+        home: impl Into<HomeScope>,
+        limit: usize,
+    ) -> Result<Vec<RequestBreakdown>> {
+        let limit = limit as i64;
+        let (start_ms, end_ms) = range_millis(range)?;
+        let mut values: Vec<&dyn ToSql> = vec![&start_ms, &end_ms, &limit];
+        let home_id = home.into().home_id();
+        let mut home_filter = String::new();
+        if let Some(home_id) = home_id.as_ref() {
+            values.push(home_id);
+            home_filter = format!(" AND codex_home_id = ?{} ", values.len());
+        }
+        let ctes = usage_delta_ctes(
+            &["source", "session_id", "request_id"],
+            &format!("usage_event WHERE ts_ms >= ?1 AND ts_ms < ?2 {home_filter}"),
+            "source",
+        );
+        // With a single MIN aggregate, SQLite takes the bare session,
+        // `model`, and `provider` from the row holding the minimum.
+        let sql = format!(
+            r#"
+            WITH {ctes}
+            SELECT request_id, COALESCE(session_id, source), model, MIN(ts), COUNT(*),
+                   SUM(delta_input_tokens), SUM(delta_cached_input_tokens),
+                   SUM(delta_output_tokens), SUM(delta_reasoning_output_tokens),
+                   SUM(delta_total_tokens), COUNT(cost_usd), TOTAL(cost_usd),
+                   TOTAL(CASE WHEN cost_usd IS NULL THEN delta_input_tokens END),
+                   TOTAL(CASE WHEN cost_usd IS NULL THEN delta_cached_input_tokens END),
+                   TOTAL(CASE WHEN cost_usd IS NULL THEN delta_output_tokens END),
+                   TOTAL(CASE WHEN cost_usd IS NULL THEN delta_reasoning_output_tokens END),
+                   TOTAL(CASE WHEN cost_usd IS NULL THEN delta_total_tokens END), provider
+            FROM deltas
+            WHERE request_id IS NOT NULL
+            GROUP BY request_id
+            ORDER BY SUM(delta_total_tokens) DESC, request_id
+            LIMIT ?3
+            "#
+        );
+        let pricing = self.list_pricing_rules()?;
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut rows = stmt.query(params_from_iter(values))?;
+        let mut result = Vec::new();
+        while let Some(row) = rows.next()? {
+            // Events from before session ids were stored fall back to the
+            // source, which this turns into the file name's id.
+            let session: String = row.get(1)?;
+            let model: String = row.get(2)?;
+            let ts: String = row.get(3)?;
+            let usage = UsageTotals {
+                input_tokens: row.get::<_, i64>(5)? as u64,
+                cached_input_tokens: row.get::<_, i64>(6)? as u64,
+                output_tokens: row.get::<_, i64>(7)? as u64,
+                reasoning_output_tokens: row.get::<_, i64>(8)? as u64,
+                total_tokens: row.get::<_, i64>(9)? as u64,
+            };
+            let stored = (row.get::<_, i64>(10)? > 0).then_some(row.get::<_, f64>(11)?);
+            let unpriced = usage_from_sums(row, 12)?;
+            let priced = if unpriced.total_tokens > 0 {
+                price_usage(&pricing, model.clone(), row.get(17)?, ts.clone(), unpriced)
+            } else {
+                None
+            };
+            let total_cost_usd = match (stored, priced) {
+                (None, None) => None,
+                (stored, priced) => Some(stored.unwrap_or(0.0) + priced.unwrap_or(0.0)),
+            };
+            result.push(RequestBreakdown {
+                request_id: row.get(0)?,
+                session_id: session_id_from_source(&session),
+                model,
+                ts,
+                usage,
+                total_cost_usd,
+                event_count: row.get::<_, i64>(4)? as u64,
+            });
+        }
+        Ok(result)
+    }
+
     pub fn breakdown_by_model(
         &self,
         range: &TimeRange,
-        codex_home_id: i64,
+        home: impl Into<HomeScope>,
     ) -> Result<Vec<ModelBreakdown>> {
+        let totals = self.totals_by(range, home.into(), |row| row.model.clone())?;
+        Ok(totals
+            .into_iter()
+            .map(|(model, total_tokens, total_cost_usd)| ModelBreakdown {
+                model,
+                total_tokens,
+                total_cost_usd,
+            })
+            .collect())
+    }
+
+    /// Token and cost totals per provider, largest first. Events logged
+    /// without a provider count as [`DEFAULT_PROVIDER`].
+    pub fn breakdown_by_provider(
+        &self,
+        range: &TimeRange,
+        home: impl Into<HomeScope>,
+    ) -> Result<Vec<ProviderBreakdown>> {
+        let totals = self.totals_by(range, home.into(), |row| {
+            row.provider
+                .clone()
+                .unwrap_or_else(|| DEFAULT_PROVIDER.to_string())
+        })?;
+        Ok(totals
+            .into_iter()
+            .map(
+                |(provider, total_tokens, total_cost_usd)| ProviderBreakdown {
+                    provider,
+                    total_tokens,
+                    total_cost_usd,
+                },
+            )
+            .collect())
+    }
+
+    /// Total tokens and cost per `key`, largest first. The cost is `None`
+    /// for keys none of whose events could be priced.
+    fn totals_by(
+        &self,
+        range: &TimeRange,
+        home: HomeScope,
+        key: impl Fn(&RowUsage) -> String,
+    ) -> Result<Vec<(String, u64, Option<f64>)>> {
         let pricing = self.list_pricing_rules()?;
-        let rows = self.load_usage_rows(range, None, codex_home_id)?;
-        let mut totals: HashMap<String, UsageTotals> = HashMap::new();
-        let mut costs: HashMap<String, f64> = HashMap::new();
-        let mut cost_known: HashMap<String, bool> = HashMap::new();
+        let rows = self.load_usage_rows(range, None, home)?;
+        let mut totals: HashMap<String, (u64, f64, bool)> = HashMap::new();
         let mut prev_by_source: HashMap<String, UsageTotals> = HashMap::new();
         for row in rows {
             let prev = prev_by_source.get(&row.source);
             let delta = delta_usage(prev, row.usage);
             prev_by_source.insert(row.source.clone(), row.usage);
-            totals
-                .entry(row.model.clone())
-                .and_modify(|value| *value = add_usage(*value, delta))
-                .or_insert(delta);
             let cost_value = row
                 .cost_usd
                 .unwrap_or_else(|| compute_cost_from_pricing(&pricing, &row, delta));
-            costs
-                .entry(row.model.clone())
-                .and_modify(|value| *value += cost_value)
-                .or_insert(cost_value);
-            if row.cost_usd.is_some() || pricing.iter().any(|rule| rule_matches(rule, &row)) {
-                cost_known.insert(row.model.clone(), true);
-            }
+            let known =
+                row.cost_usd.is_some() || pricing.iter().any(|rule| rule_matches(rule, &row));
+            let entry = totals.entry(key(&row)).or_default();
+            entry.0 += delta.total_tokens;
+            entry.1 += cost_value;
+            entry.2 |= known;
         }
-        let mut result: Vec<ModelBreakdown> = totals
+        let mut result: Vec<(String, u64, Option<f64>)> = totals
             .into_iter()
-            .map(|(model, usage)| ModelBreakdown {
-                model: model.clone(),
-                total_tokens: usage.total_tokens,
-                total_cost_usd: cost_known.get(&model).and_then(|known| {
-                    if *known {
-                        costs.get(&model).copied()
-                    } else {
-                        None
-                    }
-                }),
-            })
+            .map(|(key, (tokens, cost, known))| (key, tokens, known.then_some(cost)))
             .collect();
-        result.sort_by(|a, b| b.total_tokens.cmp(&a.total_tokens));
+        result.sort_by_key(|(_, tokens, _)| std::cmp::Reverse(*tokens));
         Ok(result)
     }
 
+    /// Summary and per-model totals for `range` next to `baseline`. Models
+    /// used in only one of the ranges get zero tokens in the other.
+    pub fn compare_ranges(
+        &self,
+        range: &TimeRange,
+        baseline: &TimeRange,
+        home: impl Into<HomeScope>,
+    ) -> Result<RangeComparison> {
+        let home = home.into();
+        let mut baseline_models: HashMap<String, ModelBreakdown> = self
+            .breakdown_by_model(baseline, home)?
+            .into_iter()
+            .map(|item| (item.model.clone(), item))
+            .collect();
+        let mut models: Vec<ModelComparison> = self
+            .breakdown_by_model(range, home)?
+            .into_iter()
+            .map(|item| {
+                let previous = baseline_models.remove(&item.model);
+                ModelComparison {
+                    model: item.model,
+                    total_tokens: item.total_tokens,
+                    baseline_total_tokens: previous.as_ref().map_or(0, |prev| prev.total_tokens),
+                    total_cost_usd: item.total_cost_usd,
+                    baseline_total_cost_usd: previous.and_then(|prev| prev.total_cost_usd),
+                }
+            })
+            .collect();
+        let mut dropped: Vec<ModelComparison> = baseline_models
+            .into_values()
+            .map(|item| ModelComparison {
+                model: item.model,
+                total_tokens: 0,
+                baseline_total_tokens: item.total_tokens,
+                total_cost_usd: None,
+                baseline_total_cost_usd: item.total_cost_usd,
+            })
+            .collect();
+        dropped.sort_by_key(|item| std::cmp::Reverse(item.baseline_total_tokens));
+        models.extend(dropped);
+        Ok(RangeComparison {
+            range: range.clone(),
+            baseline_range: baseline.clone(),
+            current: self.summary(range, home)?,
+            baseline: self.summary(baseline, home)?,
+            models,
+        })
+    }
+
     pub fn breakdown_by_model_tokens(
         &self,
         range: &TimeRange,
-        codex_home_id: i64,
+        home: impl Into<HomeScope>,
     ) -> Result<Vec<ModelTokenBreakdown>> {
-        let rows = self.load_usage_rows(range, None, codex_home_id)?;
+        let rows = self.load_usage_rows(range, None, home.into())?;
         let mut totals: HashMap<String, UsageTotals> = HashMap::new();
         let mut prev_by_source: HashMap<String, UsageTotals> = HashMap::new();
         for row in rows {
@@ -87,19 +373,20 @@ impl Db {
                 output_tokens: usage.output_tokens,
                 reasoning_output_tokens: usage.reasoning_output_tokens,
                 total_tokens: usage.total_tokens,
+                efficiency: usage.efficiency(),
             })
             .collect();
-        result.sort_by(|a, b| b.total_tokens.cmp(&a.total_tokens));
+        result.sort_by_key(|item| std::cmp::Reverse(item.total_tokens));
         Ok(result)
     }
 
     pub fn breakdown_by_model_costs(
         &self,
         range: &TimeRange,
-        codex_home_id: i64,
+        home: impl Into<HomeScope>,
     ) -> Result<Vec<ModelCostBreakdown>> {
         let pricing = self.list_pricing_rules()?;
-        let rows = self.load_usage_rows(range, None, codex_home_id)?;
+        let rows = self.load_usage_rows(range, None, home.into())?;
         let mut totals: HashMap<String, UsageTotals> = HashMap::new();
         let mut costs: HashMap<String, CostBreakdown> = HashMap::new();
         let mut cost_known: HashMap<String, bool> = HashMap::new();
@@ -142,19 +429,20 @@ impl Db {
                     cached_input_cost_usd: known.then_some(cost.cached_input_cost_usd),
                     output_cost_usd: known.then_some(cost.output_cost_usd),
                     total_cost_usd: known.then_some(cost.total_cost_usd),
+                    efficiency: usage.efficiency(),
                 }
             })
             .collect();
-        result.sort_by(|a, b| b.total_tokens.cmp(&a.total_tokens));
+        result.sort_by_key(|item| std::cmp::Reverse(item.total_tokens));
         Ok(result)
     }
 
     pub fn breakdown_by_model_effort_tokens(
         &self,
         range: &TimeRange,
-        codex_home_id: i64,
+        home: impl Into<HomeScope>,
     ) -> Result<Vec<ModelEffortTokenBreakdown>> {
-        let rows = self.load_usage_rows(range, None, codex_home_id)?;
+        let rows = self.load_usage_rows(range, None, home.into())?;
         let mut totals: HashMap<(String, Option<String>), UsageTotals> = HashMap::new();
         let mut prev_by_source: HashMap<String, UsageTotals> = HashMap::new();
         for row in rows {
@@ -179,17 +467,17 @@ impl Db {
                 total_tokens: usage.total_tokens,
             })
             .collect();
-        result.sort_by(|a, b| b.total_tokens.cmp(&a.total_tokens));
+        result.sort_by_key(|item| std::cmp::Reverse(item.total_tokens));
         Ok(result)
     }
 
     pub fn breakdown_by_model_effort_costs(
         &self,
         range: &TimeRange,
-        codex_home_id: i64,
+        home: impl Into<HomeScope>,
     ) -> Result<Vec<ModelEffortCostBreakdown>> {
         let pricing = self.list_pricing_rules()?;
-        let rows = self.load_usage_rows(range, None, codex_home_id)?;
+        let rows = self.load_usage_rows(range, None, home.into())?;
         let mut totals: HashMap<(String, Option<String>), UsageTotals> = HashMap::new();
         let mut costs: HashMap<(String, Option<String>), CostBreakdown> = HashMap::new();
         let mut cost_known: HashMap<String, bool> = HashMap::new();
@@ -240,7 +528,7 @@ impl Db {
                 }
             })
             .collect();
-        result.sort_by(|a, b| b.total_tokens.cmp(&a.total_tokens));
+        result.sort_by_key(|item| std::cmp::Reverse(item.total_tokens));
         Ok(result)
     }
 }
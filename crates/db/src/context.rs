@@ -1,8 +1,12 @@
-use rusqlite::params;
-use tracker_core::{ActiveSession, ContextPressureStats, ContextStatus, TimeRange};
+use rusqlite::{ToSql, params, params_from_iter};
+use tracker_core::{
+    ActiveSession, ContextPressureStats, ContextStatus, HomeScope, SessionContextPeak, TimeRange,
+    session_id_from_source,
+};
 
 use crate::Db;
 use crate::error::Result;
+use crate::helpers::{epoch_millis, range_millis};
 
 impl Db {
     pub fn latest_context(&self, codex_home_id: i64) -> Result<Option<ContextStatus>> {
@@ -11,7 +15,7 @@ impl Db {
             SELECT context_used, context_window
             FROM usage_event
             WHERE codex_home_id = ?1
-            ORDER BY ts DESC
+            ORDER BY ts_ms DESC
             LIMIT 1
             "#,
         )?;
@@ -26,23 +30,36 @@ impl Db {
         }
     }
 
+    /// Sessions with events since `since`, most recent first, each with its
+    /// latest event's model and context. Token totals are cumulative within
+    /// a session's log, so the tokens used since then are the newest total
+    /// less the last one before.
     pub fn active_sessions(&self, codex_home_id: i64, since: &str) -> Result<Vec<ActiveSession>> {
+        let since_ms = epoch_millis(since)?;
         let mut stmt = self.conn.prepare(
             r#"
-            SELECT ue.session_id, ue.ts, latest.start_ts, ue.model, ue.context_used, ue.context_window
+            SELECT ue.session_id, ue.ts, latest.start_ts, ue.model, ue.context_used, ue.context_window,
+                   latest.max_total - COALESCE((
+                       SELECT MAX(total_tokens)
+                       FROM usage_event prior
+                       WHERE prior.codex_home_id = ?1
+                         AND prior.session_id = latest.session_id
+                         AND prior.ts_ms < ?2
+                   ), 0)
             FROM usage_event ue
             INNER JOIN (
-                SELECT session_id, MAX(ts) AS last_ts, MIN(ts) AS start_ts
+                SELECT session_id, MAX(ts_ms) AS last_ts_ms, MIN(ts) AS start_ts,
+                       MAX(total_tokens) AS max_total
                 FROM usage_event
-                WHERE codex_home_id = ?1 AND ts >= ?2
+                WHERE codex_home_id = ?1 AND ts_ms >= ?2
                 GROUP BY session_id
             ) latest
-            ON ue.session_id = latest.session_id AND ue.ts = latest.last_ts
+            ON ue.session_id = latest.session_id AND ue.ts_ms = latest.last_ts_ms
             WHERE ue.codex_home_id = ?1
-            ORDER BY ue.ts DESC
+            ORDER BY ue.ts_ms DESC
             "#,
         )?;
-        let rows = stmt.query_map(params![codex_home_id, since], |row| {
+        let rows = stmt.query_map(params![codex_home_id, since_ms], |row| {
             Ok(ActiveSession {
                 session_id: row.get(0)?,
                 last_seen: row.get(1)?,
@@ -50,6 +67,67 @@ impl Db {
                 model: row.get(3)?,
                 context_used: row.get::<_, i64>(4)? as u64,
                 context_window: row.get::<_, i64>(5)? as u64,
+                window_tokens: row.get::<_, i64>(6)?.max(0) as u64,
+            })
+        })?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Sessions whose context pressure reached `threshold_pct` in the range,
+    /// highest peak first, each with the event where it peaked.
+    pub fn session_context_peaks(
+        &self,
+        range: &TimeRange,
+        home: impl Into<HomeScope>,
+        threshold_pct: f64,
+        limit: usize,
+    ) -> Result<Vec<SessionContextPeak>> {
+        let threshold = threshold_pct / 100.0;
+        let limit = limit as i64;
+        let (start_ms, end_ms) = range_millis(range)?;
+        let mut values: Vec<&dyn ToSql> = vec![&start_ms, &end_ms, &threshold, &limit];
+        let home_id = home.into().home_id();
+        let mut home_filter = String::new();
+        if let Some(home_id) = home_id.as_ref() {
+            values.push(home_id);
+            home_filter = format!(" AND codex_home_id = ?{} ", values.len());
+        }
+        let sql = format!(
+            r#"
+            WITH ranked AS (
+              SELECT
+                COALESCE(session_id, source) AS session,
+                model,
+                context_used,
+                context_window,
+                (context_used * 1.0) / context_window AS pressure,
+                ts,
+                ROW_NUMBER() OVER (
+                  PARTITION BY COALESCE(session_id, source)
+                  ORDER BY (context_used * 1.0) / context_window DESC, ts_ms ASC, id ASC
+                ) AS rank,
+                COUNT(*) OVER (PARTITION BY COALESCE(session_id, source)) AS event_count
+              FROM usage_event
+              WHERE ts_ms >= ?1 AND ts_ms < ?2 AND context_window > 0 {home_filter}
+            )
+            SELECT session, model, context_used, context_window, pressure, ts, event_count
+            FROM ranked
+            WHERE rank = 1 AND pressure >= ?3
+            ORDER BY pressure DESC, context_used DESC, session
+            LIMIT ?4
+            "#
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values), |row| {
+            let session: String = row.get(0)?;
+            Ok(SessionContextPeak {
+                session_id: session_id_from_source(&session),
+                model: row.get(1)?,
+                peak_context_used: row.get::<_, i64>(2)?.max(0) as u64,
+                context_window: row.get::<_, i64>(3)?.max(0) as u64,
+                peak_pressure_pct: row.get::<_, f64>(4)? * 100.0,
+                peak_at: row.get(5)?,
+                event_count: row.get::<_, i64>(6)?.max(0) as u64,
             })
         })?;
         Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
@@ -60,26 +138,49 @@ impl Db {
         range: &TimeRange,
         codex_home_id: i64,
     ) -> Result<ContextPressureStats> {
+        // Percentiles use the nearest rank: the smallest pressure whose rank
+        // reaches p * n once samples are ordered by pressure.
         let mut stmt = self.conn.prepare(
             r#"
+            WITH samples AS (
+              SELECT
+                context_used,
+                context_window,
+                (context_used * 1.0) / context_window AS pressure,
+                ROW_NUMBER() OVER (ORDER BY (context_used * 1.0) / context_window) AS rank,
+                COUNT(*) OVER () AS total
+              FROM usage_event
+              WHERE codex_home_id = ?1
+                AND ts_ms >= ?2
+                AND ts_ms < ?3
+                AND context_window > 0
+            )
             SELECT
               COUNT(*) AS sample_count,
               AVG(context_used) AS avg_context_used,
               AVG(context_window) AS avg_context_window,
-              AVG((context_used * 1.0) / context_window) AS avg_pressure
-            FROM usage_event
-            WHERE codex_home_id = ?1
-              AND ts >= ?2
-              AND ts < ?3
-              AND context_window > 0
+              AVG(pressure) AS avg_pressure,
+              MIN(CASE WHEN rank >= total * 0.50 THEN pressure END) AS p50_pressure,
+              MIN(CASE WHEN rank >= total * 0.90 THEN pressure END) AS p90_pressure,
+              MIN(CASE WHEN rank >= total * 0.99 THEN pressure END) AS p99_pressure,
+              COUNT(CASE WHEN pressure > 0.8 THEN 1 END) AS high_pressure_count
+            FROM samples
             "#,
         )?;
-        let stats = stmt.query_row(params![codex_home_id, range.start, range.end], |row| {
+        let (start_ms, end_ms) = range_millis(range)?;
+        let stats = stmt.query_row(params![codex_home_id, start_ms, end_ms], |row| {
             let sample_count: i64 = row.get(0)?;
+            let pct = |idx: usize| -> rusqlite::Result<Option<f64>> {
+                Ok(row.get::<_, Option<f64>>(idx)?.map(|value| value * 100.0))
+            };
             Ok(ContextPressureStats {
                 avg_context_used: row.get::<_, Option<f64>>(1)?,
                 avg_context_window: row.get::<_, Option<f64>>(2)?,
-                avg_pressure_pct: row.get::<_, Option<f64>>(3)?.map(|value| value * 100.0),
+                avg_pressure_pct: pct(3)?,
+                p50_pressure_pct: pct(4)?,
+                p90_pressure_pct: pct(5)?,
+                p99_pressure_pct: pct(6)?,
+                high_pressure_count: row.get::<_, i64>(7)?.max(0) as u64,
                 sample_count: sample_count.max(0) as u64,
             })
         })?;
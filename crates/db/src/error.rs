@@ -6,4 +6,18 @@ pub enum DbError {
     TimeParse(#[from] chrono::ParseError),
 }
 
+impl DbError {
+    /// Whether SQLite gave up waiting for a lock held by another connection.
+    pub fn is_locked(&self) -> bool {
+        matches!(
+            self,
+            DbError::Sqlite(rusqlite::Error::SqliteFailure(err, _))
+                if matches!(
+                    err.code,
+                    rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked
+                )
+        )
+    }
+}
+
 pub type Result<T> = std::result::Result<T, DbError>;
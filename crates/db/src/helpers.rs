@@ -1,15 +1,17 @@
 use std::collections::HashMap;
 
+use chrono::DateTime;
 use rusqlite::Row;
+use rusqlite::types::{Type, ValueRef};
 use tracker_core::{
-    CodexHome, ContextStatus, CostBreakdown, PricingRule, UsageEvent, UsageTotals,
-    compute_cost_breakdown, model_matches_pattern,
+    CodexHome, ContextStatus, CostBreakdown, CostSource, PricingRule, TimeRange, UsageEvent,
+    UsageTotals, compute_cost_breakdown,
 };
 
 use crate::error::Result;
 use crate::types::RowUsage;
 
-fn normalize_effort(value: Option<String>) -> Option<String> {
+pub(crate) fn normalize_effort(value: Option<String>) -> Option<String> {
     match value {
         Some(value) => {
             let trimmed = value.trim();
@@ -26,6 +28,29 @@ fn normalize_effort(value: Option<String>) -> Option<String> {
     }
 }
 
+/// Epoch milliseconds of an RFC 3339 timestamp, the unit of
+/// `usage_event.ts_ms`.
+pub(crate) fn epoch_millis(value: &str) -> Result<i64> {
+    Ok(DateTime::parse_from_rfc3339(value)?.timestamp_millis())
+}
+
+/// `range` in epoch milliseconds, for filtering on `usage_event.ts_ms`.
+pub(crate) fn range_millis(range: &TimeRange) -> Result<(i64, i64)> {
+    Ok((epoch_millis(&range.start)?, epoch_millis(&range.end)?))
+}
+
+/// SQL computing `ts_ms` from the RFC 3339 text in `column`. `julianday`
+/// keeps the milliseconds, and rounding absorbs its float error.
+pub(crate) fn ts_ms_from_text(column: &str) -> String {
+    format!("CAST(ROUND((julianday({column}) - 2440587.5) * 86400000.0) AS INTEGER)")
+}
+
+/// SQL rewriting the RFC 3339 text in `column` the way `Timestamp` writes
+/// it: UTC, milliseconds, and a `Z` suffix.
+pub(crate) fn timestamp_from_text(column: &str) -> String {
+    format!("strftime('%Y-%m-%dT%H:%M:%fZ', {column})")
+}
+
 pub(crate) fn row_to_usage_row(row: &Row<'_>) -> std::result::Result<RowUsage, rusqlite::Error> {
     Ok(RowUsage {
         id: row.get(0)?,
@@ -41,9 +66,45 @@ pub(crate) fn row_to_usage_row(row: &Row<'_>) -> std::result::Result<RowUsage, r
         cost_usd: row.get(8)?,
         source: row.get(9)?,
         reasoning_effort: normalize_effort(row.get(10)?),
+        provider: row.get(11)?,
     })
 }
 
+/// zstd level for stored log lines; higher levels barely shrink them further
+/// and slow down ingest.
+const RAW_JSON_LEVEL: i32 = 3;
+
+/// Compresses a raw log line for the `raw_json_z` columns.
+pub(crate) fn compress_raw_json(
+    raw_json: Option<&str>,
+) -> std::result::Result<Option<Vec<u8>>, rusqlite::Error> {
+    raw_json
+        .map(|raw_json| {
+            zstd::bulk::compress(raw_json.as_bytes(), RAW_JSON_LEVEL)
+                .map_err(|err| rusqlite::Error::ToSqlConversionFailure(Box::new(err)))
+        })
+        .transpose()
+}
+
+/// Reads a raw log line selected as `COALESCE(raw_json_z, raw_json)`:
+/// compressed lines come back as blobs, lines written by older builds as
+/// text.
+pub(crate) fn raw_json_column(
+    row: &Row<'_>,
+    idx: usize,
+) -> std::result::Result<Option<String>, rusqlite::Error> {
+    let ValueRef::Blob(bytes) = row.get_ref(idx)? else {
+        return row.get(idx);
+    };
+    let conversion = |err: Box<dyn std::error::Error + Send + Sync>| {
+        rusqlite::Error::FromSqlConversionFailure(idx, Type::Blob, err)
+    };
+    let decoded = zstd::decode_all(bytes).map_err(|err| conversion(Box::new(err)))?;
+    String::from_utf8(decoded)
+        .map(Some)
+        .map_err(|err| conversion(Box::new(err)))
+}
+
 pub(crate) fn row_to_usage_event(
     row: &Row<'_>,
 ) -> std::result::Result<UsageEvent, rusqlite::Error> {
@@ -66,8 +127,10 @@ pub(crate) fn row_to_usage_event(
         source: row.get(11)?,
         session_id: row.get(12)?,
         request_id: row.get(13)?,
-        raw_json: row.get(14)?,
+        raw_json: raw_json_column(row, 14)?,
         reasoning_effort: normalize_effort(row.get(15)?),
+        provider: row.get(16)?,
+        cost_source: row.get(17)?,
     })
 }
 
@@ -78,6 +141,8 @@ pub(crate) fn row_to_codex_home(row: &Row<'_>) -> std::result::Result<CodexHome,
         path: row.get(2)?,
         created_at: row.get(3)?,
         last_seen_at: row.get(4)?,
+        archived: row.get(5)?,
+        user: row.get(6)?,
     })
 }
 
@@ -92,6 +157,8 @@ pub(crate) fn row_to_pricing_rule(
         output_per_1m: row.get(4)?,
         effective_from: row.get(5)?,
         effective_to: row.get(6)?,
+        provider: row.get(7)?,
+        fallback: row.get(8)?,
     })
 }
 
@@ -142,53 +209,97 @@ pub(crate) fn compute_cost_breakdown_from_pricing(
     row: &RowUsage,
     delta: UsageTotals,
 ) -> CostBreakdown {
-    if let Some(rule) = pricing
-        .iter()
-        .filter(|rule| rule_matches(rule, row))
-        .max_by(|a, b| a.effective_from.cmp(&b.effective_from))
-    {
+    if let Some(rule) = matching_rule(pricing, row) {
         compute_cost_breakdown(delta, rule)
     } else {
         CostBreakdown::default()
     }
 }
 
+/// The rule that prices `row`, by [`PricingRule::precedence`].
+pub(crate) fn matching_rule<'a>(
+    pricing: &'a [PricingRule],
+    row: &RowUsage,
+) -> Option<&'a PricingRule> {
+    pricing
+        .iter()
+        .filter(|rule| rule_matches(rule, row))
+        .max_by(|a, b| a.precedence().cmp(&b.precedence()))
+}
+
+/// Reads five summed token columns, starting at `first`, in
+/// [`UsageTotals`] field order.
+pub(crate) fn usage_from_sums(
+    row: &Row<'_>,
+    first: usize,
+) -> std::result::Result<UsageTotals, rusqlite::Error> {
+    Ok(UsageTotals {
+        input_tokens: row.get::<_, f64>(first)? as u64,
+        cached_input_tokens: row.get::<_, f64>(first + 1)? as u64,
+        output_tokens: row.get::<_, f64>(first + 2)? as u64,
+        reasoning_output_tokens: row.get::<_, f64>(first + 3)? as u64,
+        total_tokens: row.get::<_, f64>(first + 4)? as u64,
+    })
+}
+
+/// Cost of `usage` for `model` from `provider` under the rule in effect at
+/// `ts`, or `None` when no rule matches.
+pub(crate) fn price_usage(
+    pricing: &[PricingRule],
+    model: String,
+    provider: Option<String>,
+    ts: String,
+    usage: UsageTotals,
+) -> Option<f64> {
+    let row = RowUsage {
+        id: String::new(),
+        ts,
+        model,
+        usage,
+        cost_usd: None,
+        source: String::new(),
+        reasoning_effort: None,
+        provider,
+    };
+    pricing
+        .iter()
+        .any(|rule| rule_matches(rule, &row))
+        .then(|| compute_cost_from_pricing(pricing, &row, usage))
+}
+
 pub(crate) fn rule_matches(rule: &PricingRule, row: &RowUsage) -> bool {
-    if !model_matches_pattern(&row.model, &rule.model_pattern) {
-        return false;
-    }
-    if rule.effective_from > row.ts {
-        return false;
-    }
-    if let Some(ref end) = rule.effective_to
-        && row.ts >= *end
-    {
-        return false;
-    }
-    true
+    rule.matches(&row.model, row.provider.as_deref(), &row.ts)
 }
 
+/// Summed usage and cost of the rows, the least trustworthy source of the
+/// cost (`None` when no row was priced), and the cost priced by fallback
+/// rules (`None` when no row used one).
 pub(crate) fn compute_totals(
     rows: Vec<RowUsage>,
     pricing: &[PricingRule],
-) -> Result<(UsageTotals, CostBreakdown, bool)> {
+) -> Result<(UsageTotals, CostBreakdown, Option<CostSource>, Option<f64>)> {
     let mut totals = UsageTotals::default();
     let mut total_cost = CostBreakdown::default();
-    let mut cost_known = false;
+    let mut cost_source = None;
+    let mut estimated_cost = None;
     let mut prev_by_source: HashMap<String, UsageTotals> = HashMap::new();
     for row in rows {
         let prev = prev_by_source.get(&row.source);
         let delta = delta_usage(prev, row.usage);
         prev_by_source.insert(row.source.clone(), row.usage);
         totals = add_usage(totals, delta);
-        let cost = compute_cost_breakdown_from_pricing(pricing, &row, delta);
-        if pricing.iter().any(|rule| rule_matches(rule, &row)) {
-            cost_known = true;
+        let Some(rule) = matching_rule(pricing, &row) else {
+            continue;
+        };
+        cost_source = cost_source.max(Some(CostSource::for_rule(rule)));
+        let cost = compute_cost_breakdown(delta, rule);
+        if rule.fallback {
+            *estimated_cost.get_or_insert(0.0) += cost.total_cost_usd;
         }
         total_cost.input_cost_usd += cost.input_cost_usd;
         total_cost.cached_input_cost_usd += cost.cached_input_cost_usd;
         total_cost.output_cost_usd += cost.output_cost_usd;
         total_cost.total_cost_usd += cost.total_cost_usd;
     }
-    Ok((totals, total_cost, cost_known))
+    Ok((totals, total_cost, cost_source, estimated_cost))
 }
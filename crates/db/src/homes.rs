@@ -13,7 +13,7 @@ impl Db {
     pub fn list_homes(&self) -> Result<Vec<CodexHome>> {
         let mut stmt = self.conn.prepare(
             r#"
-            SELECT id, label, path, created_at, last_seen_at
+            SELECT id, label, path, created_at, last_seen_at, archived, user_label
             FROM codex_home
             ORDER BY created_at ASC, id ASC
             "#,
@@ -28,7 +28,7 @@ impl Db {
         self.conn
             .query_row(
                 r#"
-                SELECT id, label, path, created_at, last_seen_at
+                SELECT id, label, path, created_at, last_seen_at, archived, user_label
                 FROM codex_home
                 WHERE id = ?1
                 "#,
@@ -43,7 +43,7 @@ impl Db {
         self.conn
             .query_row(
                 r#"
-                SELECT id, label, path, created_at, last_seen_at
+                SELECT id, label, path, created_at, last_seen_at, archived, user_label
                 FROM codex_home
                 WHERE path = ?1
                 "#,
@@ -120,6 +120,39 @@ impl Db {
         Ok(())
     }
 
+    pub fn rename_home(&self, home_id: i64, label: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE codex_home SET label = ?1 WHERE id = ?2",
+            params![label, home_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_home_archived(&self, home_id: i64, archived: bool) -> Result<()> {
+        self.conn.execute(
+            "UPDATE codex_home SET archived = ?1 WHERE id = ?2",
+            params![archived, home_id],
+        )?;
+        Ok(())
+    }
+
+    /// Sets or, with `None`, clears the user a home's usage belongs to.
+    pub fn set_home_user(&self, home_id: i64, user: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE codex_home SET user_label = ?1 WHERE id = ?2",
+            params![user, home_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn update_home_path(&self, home_id: i64, path: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE codex_home SET path = ?1 WHERE id = ?2",
+            params![path, home_id],
+        )?;
+        Ok(())
+    }
+
     pub fn delete_home(&mut self, home_id: i64) -> Result<()> {
         let tx = self.conn.transaction()?;
         tx.execute(
@@ -130,6 +163,10 @@ impl Db {
             "DELETE FROM message_event WHERE codex_home_id = ?1",
             params![home_id],
         )?;
+        tx.execute(
+            "DELETE FROM session WHERE codex_home_id = ?1",
+            params![home_id],
+        )?;
         tx.execute(
             "DELETE FROM usage_limit_snapshot WHERE codex_home_id = ?1",
             params![home_id],
@@ -153,6 +190,10 @@ impl Db {
             "DELETE FROM message_event WHERE codex_home_id = ?1",
             params![home_id],
         )?;
+        tx.execute(
+            "DELETE FROM session WHERE codex_home_id = ?1",
+            params![home_id],
+        )?;
         tx.execute(
             "DELETE FROM usage_limit_snapshot WHERE codex_home_id = ?1",
             params![home_id],
@@ -1,12 +1,18 @@
 use std::collections::HashMap;
 
-use rusqlite::{OptionalExtension, params};
-use tracker_core::{MessageEvent, UsageEvent, UsageLimitSnapshot, UsageTotals};
+use rusqlite::{OptionalExtension, ToSql, params, params_from_iter};
+use tracker_core::{MessageEvent, Timestamp, UsageEvent, UsageLimitSnapshot, UsageTotals};
 
 use crate::Db;
 use crate::error::Result;
+use crate::helpers::compress_raw_json;
+use crate::sessions::extend_session_spans;
 use crate::types::IngestCursor;
 
+/// Events written per multi-row `INSERT`; 100 rows of 20 columns stays far
+/// below SQLite's limit on bound parameters.
+const INSERT_BATCH_ROWS: usize = 100;
+
 impl Db {
     pub fn insert_usage_events(
         &mut self,
@@ -15,44 +21,59 @@ impl Db {
     ) -> Result<usize> {
         let tx = self.conn.transaction()?;
         let mut inserted = 0usize;
-        {
-            let mut stmt = tx.prepare(
-                r#"
-                INSERT OR IGNORE INTO usage_event (
-                  id, ts, model, input_tokens, cached_input_tokens, output_tokens,
-                  reasoning_output_tokens, total_tokens, context_used, context_window,
-                  cost_usd, source, session_id, request_id, raw_json, codex_home_id,
-                  reasoning_effort
-                ) VALUES (
-                  ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17
-                )
-                "#,
-            )?;
-            for event in events {
-                let rows = stmt.execute(params![
-                    event.id,
-                    event.ts,
-                    event.model,
-                    event.usage.input_tokens as i64,
-                    event.usage.cached_input_tokens as i64,
-                    event.usage.output_tokens as i64,
-                    event.usage.reasoning_output_tokens as i64,
-                    event.usage.total_tokens as i64,
-                    event.context.context_used as i64,
-                    event.context.context_window as i64,
-                    event.cost_usd,
-                    event.source,
-                    event.session_id,
-                    event.request_id,
-                    event.raw_json,
-                    codex_home_id,
-                    event.reasoning_effort,
-                ])?;
-                if rows > 0 {
-                    inserted += 1;
-                }
+        for chunk in events.chunks(INSERT_BATCH_ROWS) {
+            // Full batches share one cached statement; only the last, shorter
+            // chunk prepares its own.
+            let mut stmt = tx.prepare_cached(&usage_event_insert_sql(chunk.len()))?;
+            let counts = chunk
+                .iter()
+                .map(|event| {
+                    [
+                        event.usage.input_tokens,
+                        event.usage.cached_input_tokens,
+                        event.usage.output_tokens,
+                        event.usage.reasoning_output_tokens,
+                        event.usage.total_tokens,
+                        event.context.context_used,
+                        event.context.context_window,
+                    ]
+                    .map(|value| value as i64)
+                })
+                .collect::<Vec<_>>();
+            let ts_ms = chunk
+                .iter()
+                .map(|event| event.ts.as_datetime().timestamp_millis())
+                .collect::<Vec<_>>();
+            let raw_json = chunk
+                .iter()
+                .map(|event| compress_raw_json(event.raw_json.as_deref()))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            let mut values: Vec<&dyn ToSql> = Vec::with_capacity(chunk.len() * 20);
+            let rows = chunk.iter().zip(&ts_ms).zip(&counts).zip(&raw_json);
+            for (((event, ts_ms), counts), raw_json) in rows {
+                values.extend([&event.id as &dyn ToSql, &event.ts, ts_ms, &event.model]);
+                values.extend(counts.iter().map(|value| value as &dyn ToSql));
+                values.extend([
+                    &event.cost_usd as &dyn ToSql,
+                    &event.source,
+                    &event.session_id,
+                    &event.request_id,
+                    raw_json,
+                    &codex_home_id,
+                    &event.reasoning_effort,
+                    &event.provider,
+                    &event.cost_source,
+                ]);
             }
+            inserted += stmt.execute(params_from_iter(values))?;
         }
+        extend_session_spans(
+            &tx,
+            codex_home_id,
+            events
+                .iter()
+                .map(|event| (event.session_id.as_str(), event.ts)),
+        )?;
         tx.commit()?;
         Ok(inserted)
     }
@@ -71,7 +92,7 @@ impl Db {
             let mut stmt = tx.prepare(
                 r#"
                 INSERT OR IGNORE INTO message_event (
-                  id, ts, role, source, session_id, raw_json, codex_home_id
+                  id, ts, role, source, session_id, raw_json_z, codex_home_id
                 ) VALUES (
                   ?1, ?2, ?3, ?4, ?5, ?6, ?7
                 )
@@ -84,7 +105,7 @@ impl Db {
                     event.role,
                     event.source,
                     event.session_id,
-                    event.raw_json,
+                    compress_raw_json(event.raw_json.as_deref())?,
                     codex_home_id,
                 ])?;
                 if rows > 0 {
@@ -92,6 +113,13 @@ impl Db {
                 }
             }
         }
+        extend_session_spans(
+            &tx,
+            codex_home_id,
+            events
+                .iter()
+                .map(|event| (event.session_id.as_str(), event.ts)),
+        )?;
         tx.commit()?;
         Ok(inserted)
     }
@@ -104,23 +132,31 @@ impl Db {
         if snapshots.is_empty() {
             return Ok(0);
         }
-        let mut last_by_type: HashMap<String, (f64, String)> = HashMap::new();
+        // Only the latest stored snapshot per limit type matters for the
+        // dedupe; each lookup is a single probe of the (home, type, ts) index.
+        let mut last_by_type: HashMap<String, (f64, Timestamp)> = HashMap::new();
         {
             let mut stmt = self.conn.prepare(
                 r#"
-                SELECT limit_type, percent_left, reset_at
+                SELECT percent_left, reset_at
                 FROM usage_limit_snapshot
-                WHERE codex_home_id = ?1
+                WHERE codex_home_id = ?1 AND limit_type = ?2
                 ORDER BY ts DESC
+                LIMIT 1
                 "#,
             )?;
-            let mut rows = stmt.query(params![codex_home_id])?;
-            while let Some(row) = rows.next()? {
-                let limit_type: String = row.get(0)?;
-                if last_by_type.contains_key(&limit_type) {
+            for snapshot in snapshots {
+                if last_by_type.contains_key(&snapshot.limit_type) {
                     continue;
                 }
-                last_by_type.insert(limit_type, (row.get::<_, f64>(1)?, row.get(2)?));
+                let last = stmt
+                    .query_row(params![codex_home_id, snapshot.limit_type], |row| {
+                        Ok((row.get::<_, f64>(0)?, row.get::<_, Timestamp>(1)?))
+                    })
+                    .optional()?;
+                if let Some(last) = last {
+                    last_by_type.insert(snapshot.limit_type.clone(), last);
+                }
             }
         }
         let tx = self.conn.transaction()?;
@@ -158,10 +194,7 @@ impl Db {
                 if rows > 0 {
                     inserted += 1;
                 }
-                last_by_type.insert(
-                    limit_type,
-                    (snapshot.percent_left, snapshot.reset_at.clone()),
-                );
+                last_by_type.insert(limit_type, (snapshot.percent_left, snapshot.reset_at));
             }
         }
         tx.commit()?;
@@ -172,38 +205,62 @@ impl Db {
         let mut stmt = self.conn.prepare(
             r#"
             SELECT codex_home_id, codex_home, file_path, inode, mtime, byte_offset,
-                   last_event_key, updated_at, last_model, last_effort
+                   last_event_key, updated_at, last_model, last_effort, session_id,
+                   provider
             FROM ingest_cursor
             WHERE codex_home_id = ?1 AND file_path = ?2
             "#,
         )?;
         let mut rows = stmt.query(params![codex_home_id, file_path])?;
         if let Some(row) = rows.next()? {
-            Ok(Some(IngestCursor {
-                codex_home_id: row.get(0)?,
-                codex_home: row.get(1)?,
-                file_path: row.get(2)?,
-                inode: row.get::<_, Option<i64>>(3)?.map(|value| value as u64),
-                mtime: row.get(4)?,
-                byte_offset: row.get::<_, i64>(5)? as u64,
-                last_event_key: row.get(6)?,
-                updated_at: row.get(7)?,
-                last_model: row.get(8)?,
-                last_effort: row.get(9)?,
-            }))
+            Ok(Some(row_to_ingest_cursor(row)?))
         } else {
             Ok(None)
         }
     }
 
+    pub fn list_cursors(&self, codex_home_id: i64) -> Result<Vec<IngestCursor>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT codex_home_id, codex_home, file_path, inode, mtime, byte_offset,
+                   last_event_key, updated_at, last_model, last_effort, session_id,
+                   provider
+            FROM ingest_cursor
+            WHERE codex_home_id = ?1
+            ORDER BY file_path ASC
+            "#,
+        )?;
+        let rows = stmt
+            .query_map(params![codex_home_id], row_to_ingest_cursor)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Forgets the cursor for one file, or every cursor in the home, so the
+    /// next ingest reads those logs from the start. Events already stored are
+    /// kept and re-read lines are deduplicated by id.
+    pub fn reset_cursors(&self, codex_home_id: i64, file_path: Option<&str>) -> Result<usize> {
+        let removed = match file_path {
+            Some(file_path) => self.conn.execute(
+                "DELETE FROM ingest_cursor WHERE codex_home_id = ?1 AND file_path = ?2",
+                params![codex_home_id, file_path],
+            )?,
+            None => self.conn.execute(
+                "DELETE FROM ingest_cursor WHERE codex_home_id = ?1",
+                params![codex_home_id],
+            )?,
+        };
+        Ok(removed)
+    }
+
     pub fn upsert_cursor(&self, cursor: &IngestCursor) -> Result<()> {
         self.conn.execute(
             r#"
             INSERT INTO ingest_cursor (
               codex_home_id, codex_home, file_path, inode, mtime, byte_offset,
-              last_event_key, updated_at, last_model, last_effort
+              last_event_key, updated_at, last_model, last_effort, session_id, provider
             ) VALUES (
-              ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10
+              ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12
             )
             ON CONFLICT(codex_home, file_path) DO UPDATE SET
               codex_home = excluded.codex_home,
@@ -214,7 +271,9 @@ impl Db {
               last_event_key = excluded.last_event_key,
               updated_at = excluded.updated_at,
               last_model = excluded.last_model,
-              last_effort = excluded.last_effort
+              last_effort = excluded.last_effort,
+              session_id = excluded.session_id,
+              provider = excluded.provider
             "#,
             params![
                 cursor.codex_home_id,
@@ -226,7 +285,9 @@ impl Db {
                 cursor.last_event_key,
                 cursor.updated_at,
                 cursor.last_model,
-                cursor.last_effort
+                cursor.last_effort,
+                cursor.session_id,
+                cursor.provider
             ],
         )?;
         Ok(())
@@ -244,7 +305,7 @@ impl Db {
                        reasoning_output_tokens, total_tokens
                 FROM usage_event
                 WHERE codex_home_id = ?1 AND source = ?2
-                ORDER BY ts DESC
+                ORDER BY ts_ms DESC
                 LIMIT 1
                 "#,
                 params![codex_home_id, source],
@@ -262,3 +323,38 @@ impl Db {
             .map_err(crate::error::DbError::from)
     }
 }
+
+fn row_to_ingest_cursor(
+    row: &rusqlite::Row<'_>,
+) -> std::result::Result<IngestCursor, rusqlite::Error> {
+    Ok(IngestCursor {
+        codex_home_id: row.get(0)?,
+        codex_home: row.get(1)?,
+        file_path: row.get(2)?,
+        inode: row.get::<_, Option<i64>>(3)?.map(|value| value as u64),
+        mtime: row.get(4)?,
+        byte_offset: row.get::<_, i64>(5)? as u64,
+        last_event_key: row.get(6)?,
+        updated_at: row.get(7)?,
+        last_model: row.get(8)?,
+        last_effort: row.get(9)?,
+        session_id: row.get(10)?,
+        provider: row.get(11)?,
+    })
+}
+
+/// `INSERT OR IGNORE` of `rows` usage events in one statement.
+fn usage_event_insert_sql(rows: usize) -> String {
+    let row = format!("({})", vec!["?"; 20].join(", "));
+    format!(
+        r#"
+        INSERT OR IGNORE INTO usage_event (
+          id, ts, ts_ms, model, input_tokens, cached_input_tokens, output_tokens,
+          reasoning_output_tokens, total_tokens, context_used, context_window,
+          cost_usd, source, session_id, request_id, raw_json_z, codex_home_id,
+          reasoning_effort, provider, cost_source
+        ) VALUES {}
+        "#,
+        vec![row; rows].join(", ")
+    )
+}
@@ -6,18 +6,29 @@ mod helpers;
 mod homes;
 mod ingest;
 mod limits;
+mod lookups;
+mod maintenance;
+mod merge;
 mod migrations;
+mod options;
 mod pricing;
+mod schedules;
+mod sessions;
 mod settings;
 mod types;
 mod usage_rows;
+mod webhooks;
 
 use std::path::Path;
 
-use rusqlite::Connection;
+use rusqlite::{Connection, OpenFlags};
 
 pub use error::{DbError, Result};
-pub use types::{Bucket, IngestCursor, Metric, RowUsage};
+pub use migrations::SCHEMA_VERSION;
+pub use options::DbOptions;
+pub use types::{
+    Bucket, GroupBy, IngestCursor, MergeStats, Metric, PruneStats, RowUsage, SourceUsage,
+};
 
 /// SQLite-backed repository for tracker data.
 pub struct Db {
@@ -25,13 +36,77 @@ pub struct Db {
 }
 
 impl Db {
+    /// Opens (creating if needed) a read-write connection tuned by
+    /// [`DbOptions::from_env`].
     pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with(path, &DbOptions::from_env())
+    }
+
+    /// Like [`Db::open`], with explicit tuning instead of the environment.
+    pub fn open_with(path: impl AsRef<Path>, options: &DbOptions) -> Result<Self> {
         let conn = Connection::open(path)?;
         conn.pragma_update(None, "journal_mode", "WAL")?;
         conn.pragma_update(None, "synchronous", "NORMAL")?;
         conn.pragma_update(None, "temp_store", "MEMORY")?;
-        conn.pragma_update(None, "cache_size", -20_000)?;
         conn.pragma_update(None, "foreign_keys", "ON")?;
+        conn.pragma_update(None, "wal_autocheckpoint", options.wal_autocheckpoint)?;
+        options.apply(&conn)?;
+        Ok(Self { conn })
+    }
+
+    /// Opens an existing database for queries only. In WAL mode readers
+    /// never block the writer (ingest) or each other, so analytics use
+    /// these; any write fails with `SQLITE_READONLY`. The database must
+    /// already exist and be migrated.
+    pub fn open_read_only(path: impl AsRef<Path>) -> Result<Self> {
+        let conn = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY
+                | OpenFlags::SQLITE_OPEN_URI
+                | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        conn.pragma_update(None, "temp_store", "MEMORY")?;
+        DbOptions::from_env().apply(&conn)?;
         Ok(Self { conn })
     }
+
+    /// Runs `read` in one read transaction (`BEGIN DEFERRED`, started right
+    /// away), so all of its queries see the database as of the same instant
+    /// even while ingest commits on another connection. Meant for reads; a
+    /// write inside upgrades the transaction and can fail with
+    /// `SQLITE_BUSY`.
+    pub fn with_snapshot<T, E>(
+        &mut self,
+        read: impl FnOnce(&mut Db) -> std::result::Result<T, E>,
+    ) -> std::result::Result<T, E>
+    where
+        E: From<DbError>,
+    {
+        self.conn
+            .execute_batch("BEGIN DEFERRED")
+            .map_err(DbError::from)?;
+        // A deferred transaction only pins its snapshot at the first read.
+        let result = match self
+            .conn
+            .query_row("SELECT COUNT(*) FROM sqlite_schema", [], |_| Ok(()))
+        {
+            Ok(()) => read(self),
+            Err(err) => Err(DbError::from(err).into()),
+        };
+        let end = self
+            .conn
+            .execute_batch(if result.is_ok() { "COMMIT" } else { "ROLLBACK" });
+        match (result, end) {
+            (Ok(_), Err(err)) => Err(DbError::from(err).into()),
+            (result, _) => result,
+        }
+    }
+
+    /// Writes a consistent snapshot of the database to `path` (which must not
+    /// exist yet) via `VACUUM INTO`.
+    pub fn copy_to(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.conn
+            .execute("VACUUM INTO ?1", [path.as_ref().to_string_lossy().as_ref()])?;
+        Ok(())
+    }
 }
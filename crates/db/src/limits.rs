@@ -1,11 +1,22 @@
 use std::collections::BTreeMap;
 
 use chrono::{DateTime, Duration, SecondsFormat, Timelike, Utc};
-use rusqlite::params;
-use tracker_core::{TimeRange, UsageLimitCurrentWindow, UsageLimitSnapshot, UsageLimitWindow};
+use rusqlite::{OptionalExtension, Row, ToSql, params, params_from_iter};
+use tracker_core::{
+    CustomLimit, TimeRange, UsageLimitCurrentWindow, UsageLimitSnapshot, UsageLimitWindow,
+};
 
 use crate::Db;
-use crate::error::Result;
+use crate::error::{DbError, Result};
+use crate::helpers::{price_usage, range_millis, usage_from_sums};
+use crate::usage_rows::usage_delta_ctes;
+
+#[derive(Debug, Clone, Default)]
+struct WindowTotals {
+    total_tokens: u64,
+    total_cost_usd: Option<f64>,
+    message_count: u64,
+}
 
 impl Db {
     pub fn latest_limit_snapshot(
@@ -67,6 +78,49 @@ impl Db {
         }
     }
 
+    /// Raw snapshots observed in the range, newest first, optionally for a
+    /// single limit type.
+    pub fn list_limit_snapshots(
+        &self,
+        codex_home_id: i64,
+        range: &TimeRange,
+        limit_type: Option<&str>,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<UsageLimitSnapshot>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT limit_type, percent_left, reset_at, ts, source, raw_line
+            FROM usage_limit_snapshot
+            WHERE codex_home_id = ?1 AND ts >= ?2 AND ts < ?3
+              AND (?4 IS NULL OR limit_type = ?4)
+            ORDER BY ts DESC, id DESC
+            LIMIT ?5 OFFSET ?6
+            "#,
+        )?;
+        let rows = stmt.query_map(
+            params![
+                codex_home_id,
+                range.start,
+                range.end,
+                limit_type,
+                limit,
+                offset
+            ],
+            |row| {
+                Ok(UsageLimitSnapshot {
+                    limit_type: row.get(0)?,
+                    percent_left: row.get(1)?,
+                    reset_at: row.get(2)?,
+                    observed_at: row.get(3)?,
+                    source: row.get(4)?,
+                    raw_line: row.get(5)?,
+                })
+            },
+        )?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
     pub fn limit_windows_7d(
         &self,
         codex_home_id: i64,
@@ -89,10 +143,10 @@ impl Db {
                 reset_set.insert(normalized, ());
             }
         }
-        let resets: Vec<DateTime<Utc>> = reset_set.into_keys().collect();
-        let mut windows = Vec::new();
+        let resets = fill_reset_gaps(reset_set.into_keys().collect());
+        let mut ranges = Vec::new();
         let mut prev: Option<DateTime<Utc>> = None;
-        for reset_at in resets {
+        for (reset_at, interpolated) in resets {
             let complete = prev.is_some();
             let start = prev.unwrap_or_else(|| reset_at - Duration::days(7));
             let range = TimeRange {
@@ -100,31 +154,117 @@ impl Db {
                 end: normalize_limit_boundary(reset_at)
                     .to_rfc3339_opts(SecondsFormat::Millis, true),
             };
-            let summary = self.summary(&range, codex_home_id)?;
-            let message_count = self.message_count_in_range(&range, codex_home_id)?;
-            windows.push(UsageLimitWindow {
-                window_start: Some(range.start),
-                window_end: range.end,
-                total_tokens: Some(summary.total_tokens),
-                total_cost_usd: summary.total_cost_usd,
-                message_count: Some(message_count),
-                complete,
-            });
+            ranges.push((range, complete, interpolated));
             prev = Some(reset_at);
         }
-        if limit == 0 || windows.len() <= limit {
-            return Ok(windows);
+        if limit > 0 && ranges.len() > limit {
+            ranges.drain(..ranges.len() - limit);
         }
-        Ok(windows
-            .into_iter()
-            .rev()
-            .take(limit)
-            .collect::<Vec<_>>()
+        let totals = self.window_totals(
+            codex_home_id,
+            &ranges.iter().map(|(range, ..)| range).collect::<Vec<_>>(),
+        )?;
+        Ok(ranges
             .into_iter()
-            .rev()
+            .zip(totals)
+            .map(
+                |((range, complete, interpolated), totals)| UsageLimitWindow {
+                    window_start: Some(range.start),
+                    window_end: range.end,
+                    total_tokens: Some(totals.total_tokens),
+                    total_cost_usd: totals.total_cost_usd,
+                    message_count: Some(totals.message_count),
+                    complete,
+                    interpolated,
+                },
+            )
             .collect())
     }
 
+    /// Token, cost, and message totals for each of `ranges`, which must not
+    /// overlap, in one grouped pass per table. Deltas restart at each range
+    /// start and tokens are priced per UTC hour and model, matching
+    /// [`Db::summary`] for a single range.
+    fn window_totals(
+        &self,
+        codex_home_id: i64,
+        ranges: &[&TimeRange],
+    ) -> Result<Vec<WindowTotals>> {
+        let mut totals = vec![WindowTotals::default(); ranges.len()];
+        if ranges.is_empty() {
+            return Ok(totals);
+        }
+        let mut values: Vec<&dyn ToSql> = vec![&codex_home_id];
+        let mut windows = Vec::with_capacity(ranges.len());
+        let indexes = (0..ranges.len() as i64).collect::<Vec<_>>();
+        let bounds_ms = ranges
+            .iter()
+            .map(|range| range_millis(range))
+            .collect::<Result<Vec<_>>>()?;
+        for ((index, range), (start_ms, end_ms)) in indexes.iter().zip(ranges).zip(&bounds_ms) {
+            let first = values.len() + 1;
+            values.extend([
+                index as &dyn ToSql,
+                &range.start,
+                &range.end,
+                start_ms,
+                end_ms,
+            ]);
+            let params = (first..first + 5)
+                .map(|idx| format!("?{idx}"))
+                .collect::<Vec<_>>();
+            windows.push(format!("({})", params.join(", ")));
+        }
+        // Messages have no `ts_ms`, so windows carry both forms of bounds.
+        let windows = format!(
+            "windows(window_idx, window_start, window_end, window_start_ms, window_end_ms) \
+             AS (VALUES {})",
+            windows.join(", ")
+        );
+        let ctes = usage_delta_ctes(
+            &["window_idx"],
+            "usage_event JOIN windows ON ts_ms >= window_start_ms AND ts_ms < window_end_ms \
+             WHERE codex_home_id = ?1",
+            "window_idx, source",
+        );
+        let pricing = self.list_pricing_rules()?;
+        let mut stmt = self.conn.prepare(&format!(
+            r#"
+            WITH {windows}, {ctes}
+            SELECT window_idx, model, MIN(ts),
+                   TOTAL(delta_input_tokens), TOTAL(delta_cached_input_tokens),
+                   TOTAL(delta_output_tokens), TOTAL(delta_reasoning_output_tokens),
+                   TOTAL(delta_total_tokens), provider
+            FROM deltas
+            GROUP BY window_idx, ts_ms / 3600000, model, provider
+            "#
+        ))?;
+        let mut rows = stmt.query(params_from_iter(values.iter()))?;
+        while let Some(row) = rows.next()? {
+            let window = &mut totals[row.get::<_, usize>(0)?];
+            let usage = usage_from_sums(row, 3)?;
+            window.total_tokens += usage.total_tokens;
+            if let Some(cost) = price_usage(&pricing, row.get(1)?, row.get(8)?, row.get(2)?, usage)
+            {
+                *window.total_cost_usd.get_or_insert(0.0) += cost;
+            }
+        }
+        let mut stmt = self.conn.prepare(&format!(
+            r#"
+            WITH {windows}
+            SELECT window_idx, COUNT(*)
+            FROM message_event JOIN windows ON ts >= window_start AND ts < window_end
+            WHERE codex_home_id = ?1
+            GROUP BY window_idx
+            "#
+        ))?;
+        let mut rows = stmt.query(params_from_iter(values.iter()))?;
+        while let Some(row) = rows.next()? {
+            totals[row.get::<_, usize>(0)?].message_count = row.get::<_, i64>(1)? as u64;
+        }
+        Ok(totals)
+    }
+
     pub fn limit_current_window(
         &self,
         codex_home_id: i64,
@@ -135,7 +275,7 @@ impl Db {
             Some(value) => value,
             None => return Ok(None),
         };
-        let reset_at = DateTime::parse_from_rfc3339(&snapshot.reset_at)?.with_timezone(&Utc);
+        let reset_at = snapshot.reset_at.as_datetime();
         let duration = match limit_type {
             "5h" => Duration::hours(5),
             "7d" => Duration::days(7),
@@ -156,6 +296,93 @@ impl Db {
             message_count: Some(message_count),
         }))
     }
+
+    pub fn list_custom_limits(&self) -> Result<Vec<CustomLimit>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, name, period, metric, amount, created_at
+            FROM custom_limit
+            ORDER BY id ASC
+            "#,
+        )?;
+        let rows = stmt
+            .query_map([], row_to_custom_limit)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    pub fn get_custom_limit(&self, id: i64) -> Result<Option<CustomLimit>> {
+        self.conn
+            .query_row(
+                r#"
+                SELECT id, name, period, metric, amount, created_at
+                FROM custom_limit
+                WHERE id = ?1
+                "#,
+                params![id],
+                row_to_custom_limit,
+            )
+            .optional()
+            .map_err(DbError::from)
+    }
+
+    pub fn create_custom_limit(
+        &self,
+        name: &str,
+        period: &str,
+        metric: &str,
+        amount: f64,
+    ) -> Result<CustomLimit> {
+        let now = Utc::now().to_rfc3339();
+        self.conn.execute(
+            r#"
+            INSERT INTO custom_limit (name, period, metric, amount, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+            params![name, period, metric, amount, now],
+        )?;
+        let id = self.conn.last_insert_rowid();
+        self.get_custom_limit(id)?
+            .ok_or_else(|| DbError::Sqlite(rusqlite::Error::QueryReturnedNoRows))
+    }
+
+    pub fn delete_custom_limit(&self, id: i64) -> Result<usize> {
+        Ok(self
+            .conn
+            .execute("DELETE FROM custom_limit WHERE id = ?1", params![id])?)
+    }
+}
+
+fn row_to_custom_limit(row: &Row<'_>) -> std::result::Result<CustomLimit, rusqlite::Error> {
+    Ok(CustomLimit {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        period: row.get(2)?,
+        metric: row.get(3)?,
+        amount: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+/// Adds the 7d resets that passed while nothing was logging, stepping 7 days
+/// from each observed reset until the next one is less than a day away, so
+/// the windows have no holes. Synthesized resets are marked `true`.
+fn fill_reset_gaps(resets: Vec<DateTime<Utc>>) -> Vec<(DateTime<Utc>, bool)> {
+    let week = Duration::days(7);
+    let slack = Duration::days(1);
+    let mut filled = Vec::with_capacity(resets.len());
+    let mut prev: Option<DateTime<Utc>> = None;
+    for reset_at in resets {
+        if let Some(mut boundary) = prev {
+            while boundary + week < reset_at - slack {
+                boundary += week;
+                filled.push((boundary, true));
+            }
+        }
+        filled.push((reset_at, false));
+        prev = Some(reset_at);
+    }
+    filled
 }
 
 fn normalize_limit_boundary(value: DateTime<Utc>) -> DateTime<Utc> {
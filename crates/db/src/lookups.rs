@@ -0,0 +1,76 @@
+use std::collections::BTreeSet;
+
+use rusqlite::{ToSql, params_from_iter};
+use tracker_core::{HomeScope, TimeRange};
+
+use crate::Db;
+use crate::error::Result;
+use crate::helpers::{normalize_effort, range_millis};
+use crate::usage_rows::ALIASED_MODEL;
+
+impl Db {
+    /// Models with usage in the range, aliases applied, sorted, for filter
+    /// dropdowns.
+    pub fn distinct_models(
+        &self,
+        range: &TimeRange,
+        home: impl Into<HomeScope>,
+    ) -> Result<Vec<String>> {
+        self.distinct_usage_values(ALIASED_MODEL, range, home.into())
+    }
+
+    /// Reasoning efforts with usage in the range, sorted. Missing and
+    /// unknown efforts are reported as `low`, as everywhere else.
+    pub fn distinct_efforts(
+        &self,
+        range: &TimeRange,
+        home: impl Into<HomeScope>,
+    ) -> Result<Vec<String>> {
+        let raw = self.distinct_optional_values("reasoning_effort", range, home.into())?;
+        let efforts: BTreeSet<String> = raw.into_iter().filter_map(normalize_effort).collect();
+        Ok(efforts.into_iter().collect())
+    }
+
+    /// Session log files with usage in the range, sorted.
+    pub fn distinct_sources(
+        &self,
+        range: &TimeRange,
+        home: impl Into<HomeScope>,
+    ) -> Result<Vec<String>> {
+        self.distinct_usage_values("source", range, home.into())
+    }
+
+    fn distinct_usage_values(
+        &self,
+        column: &str,
+        range: &TimeRange,
+        home: HomeScope,
+    ) -> Result<Vec<String>> {
+        Ok(self
+            .distinct_optional_values(column, range, home)?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    fn distinct_optional_values(
+        &self,
+        column: &str,
+        range: &TimeRange,
+        home: HomeScope,
+    ) -> Result<Vec<Option<String>>> {
+        let mut sql =
+            format!("SELECT DISTINCT {column} FROM usage_event WHERE ts_ms >= ?1 AND ts_ms < ?2");
+        let (start_ms, end_ms) = range_millis(range)?;
+        let mut values: Vec<&dyn ToSql> = vec![&start_ms, &end_ms];
+        let home_id = home.home_id();
+        if let Some(home_id) = home_id.as_ref() {
+            values.push(home_id);
+            sql.push_str(&format!(" AND codex_home_id = ?{}", values.len()));
+        }
+        sql.push_str(&format!(" ORDER BY {column}"));
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values), |row| row.get(0))?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+}
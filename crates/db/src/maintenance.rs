@@ -0,0 +1,98 @@
+use std::collections::{BTreeMap, HashMap};
+
+use rusqlite::params;
+use tracker_core::{HomeScope, TimeRange, UsageTotals};
+
+use crate::Db;
+use crate::error::Result;
+use crate::helpers::{add_usage, delta_usage, epoch_millis};
+use crate::types::{PruneStats, SourceUsage};
+
+impl Db {
+    /// Per-source usage in the range, with deltas taken the same way the
+    /// summary takes them, keyed by source path.
+    pub fn usage_by_source(
+        &self,
+        range: &TimeRange,
+        codex_home_id: i64,
+    ) -> Result<BTreeMap<String, SourceUsage>> {
+        let rows = self.load_usage_rows(range, None, HomeScope::Home(codex_home_id))?;
+        let mut prev_by_source: HashMap<String, UsageTotals> = HashMap::new();
+        let mut by_source: BTreeMap<String, SourceUsage> = BTreeMap::new();
+        for row in rows {
+            let delta = delta_usage(prev_by_source.get(&row.source), row.usage);
+            prev_by_source.insert(row.source.clone(), row.usage);
+            let entry = by_source.entry(row.source).or_default();
+            entry.events += 1;
+            entry.usage = add_usage(entry.usage, delta);
+        }
+        Ok(by_source)
+    }
+
+    /// Deletes data older than `before` (RFC 3339, UTC). Usage and messages
+    /// are removed per session log, and only when the whole log is older, so
+    /// a session spanning the cutoff keeps the earlier events its token
+    /// deltas are computed from. Ingest cursors stay, so pruned logs are not
+    /// read again.
+    pub fn prune_before(&mut self, before: &str, home: HomeScope) -> Result<PruneStats> {
+        let home_id = home.home_id();
+        let before_ms = epoch_millis(before)?;
+        let tx = self.conn.transaction()?;
+        let usage_events = tx.execute(
+            r#"
+            DELETE FROM usage_event
+            WHERE (?2 IS NULL OR codex_home_id = ?2)
+              AND source IN (
+                SELECT source FROM usage_event
+                WHERE (?2 IS NULL OR codex_home_id = ?2)
+                GROUP BY source
+                HAVING MAX(ts_ms) < ?1
+              )
+            "#,
+            params![before_ms, home_id],
+        )?;
+        let message_events = tx.execute(
+            r#"
+            DELETE FROM message_event
+            WHERE (?2 IS NULL OR codex_home_id = ?2)
+              AND source IN (
+                SELECT source FROM message_event
+                WHERE (?2 IS NULL OR codex_home_id = ?2)
+                GROUP BY source
+                HAVING MAX(ts) < ?1
+              )
+            "#,
+            params![before, home_id],
+        )?;
+        // Sessions whose logs were all pruned.
+        tx.execute(
+            r#"
+            DELETE FROM session
+            WHERE (?2 IS NULL OR codex_home_id = ?2) AND last_event_at < ?1
+            "#,
+            params![before, home_id],
+        )?;
+        let limit_snapshots = tx.execute(
+            r#"
+            DELETE FROM usage_limit_snapshot
+            WHERE (?2 IS NULL OR codex_home_id = ?2) AND ts < ?1
+            "#,
+            params![before, home_id],
+        )?;
+        tx.commit()?;
+        Ok(PruneStats {
+            usage_events: usage_events as u64,
+            message_events: message_events as u64,
+            limit_snapshots: limit_snapshots as u64,
+        })
+    }
+
+    /// Rebuilds the file to reclaim space left by deletes, refreshes the
+    /// query planner statistics, and truncates the WAL.
+    pub fn compact(&self) -> Result<()> {
+        self.conn.execute_batch("VACUUM; ANALYZE;")?;
+        self.conn
+            .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        Ok(())
+    }
+}
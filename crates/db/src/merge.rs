@@ -0,0 +1,176 @@
+use std::path::Path;
+
+use rusqlite::{Connection, OptionalExtension, params};
+
+use crate::Db;
+use crate::error::Result;
+use crate::helpers::timestamp_from_text;
+use crate::types::MergeStats;
+
+impl Db {
+    /// Copies usage events, message events, session spans, and limit
+    /// snapshots from another tracker database at `path`, which must already
+    /// be on the current schema. Its codex homes are matched to ours by path and added when
+    /// missing, and events that are already present (same id) are skipped,
+    /// so merging the same file twice changes nothing. Ingest cursors are
+    /// not copied: they describe files on the other machine.
+    ///
+    /// With a `user`, the other database is a teammate's: its homes are
+    /// labeled with that user and tracked as `user:path`, so two machines
+    /// with the same home path stay apart.
+    pub fn merge_from(&mut self, path: impl AsRef<Path>, user: Option<&str>) -> Result<MergeStats> {
+        // Read-only, so merging a backup or a teammate's file never writes
+        // to it.
+        self.conn.execute(
+            "ATTACH DATABASE ?1 AS other",
+            [read_only_uri(path.as_ref())],
+        )?;
+        let merged = merge_attached(&mut self.conn, user);
+        self.conn.execute("DETACH DATABASE other", [])?;
+        merged
+    }
+}
+
+/// `file:` URI opening `path` read-only; `%`, `?`, and `#` are escaped so
+/// they stay part of the path.
+fn read_only_uri(path: &Path) -> String {
+    let path = path
+        .to_string_lossy()
+        .replace('%', "%25")
+        .replace('?', "%3f")
+        .replace('#', "%23");
+    format!("file:{path}?mode=ro")
+}
+
+fn merge_attached(conn: &mut Connection, user: Option<&str>) -> Result<MergeStats> {
+    let tx = conn.transaction()?;
+    tx.execute(
+        "CREATE TEMP TABLE merge_home_map (old_id INTEGER PRIMARY KEY, new_id INTEGER NOT NULL)",
+        [],
+    )?;
+    let mut stats = MergeStats::default();
+    {
+        let mut homes = tx.prepare(
+            "SELECT id, label, path, created_at, last_seen_at, user_label FROM other.codex_home",
+        )?;
+        let mut rows = homes.query([])?;
+        while let Some(row) = rows.next()? {
+            let old_id: i64 = row.get(0)?;
+            let (path, user) = match user {
+                Some(user) => (
+                    format!("{user}:{}", row.get::<_, String>(2)?),
+                    Some(user.to_string()),
+                ),
+                None => (row.get(2)?, row.get::<_, Option<String>>(5)?),
+            };
+            let existing = tx
+                .query_row(
+                    "SELECT id FROM main.codex_home WHERE path = ?1",
+                    params![path],
+                    |row| row.get::<_, i64>(0),
+                )
+                .optional()?;
+            let new_id = match existing {
+                Some(id) => id,
+                None => {
+                    tx.execute(
+                        r#"
+                        INSERT INTO main.codex_home (
+                          label, path, created_at, last_seen_at, user_label
+                        )
+                        VALUES (?1, ?2, ?3, ?4, ?5)
+                        "#,
+                        params![
+                            row.get::<_, String>(1)?,
+                            path,
+                            row.get::<_, String>(3)?,
+                            row.get::<_, Option<String>>(4)?,
+                            user,
+                        ],
+                    )?;
+                    stats.homes_added += 1;
+                    tx.last_insert_rowid()
+                }
+            };
+            tx.execute(
+                "INSERT INTO temp.merge_home_map (old_id, new_id) VALUES (?1, ?2)",
+                params![old_id, new_id],
+            )?;
+        }
+    }
+    stats.usage_events = tx.execute(
+        r#"
+        INSERT OR IGNORE INTO main.usage_event (
+          id, ts, ts_ms, model, input_tokens, cached_input_tokens, output_tokens,
+          reasoning_output_tokens, total_tokens, context_used, context_window,
+          cost_usd, source, session_id, request_id, raw_json, raw_json_z,
+          codex_home_id, reasoning_effort, provider, cost_source
+        )
+        SELECT
+          e.id, e.ts, e.ts_ms, e.model, e.input_tokens, e.cached_input_tokens, e.output_tokens,
+          e.reasoning_output_tokens, e.total_tokens, e.context_used, e.context_window,
+          e.cost_usd, e.source, e.session_id, e.request_id, e.raw_json, e.raw_json_z,
+          m.new_id, e.reasoning_effort, e.provider, e.cost_source
+        FROM other.usage_event e
+        JOIN temp.merge_home_map m ON m.old_id = e.codex_home_id
+        "#,
+        [],
+    )? as u64;
+    stats.message_events = tx.execute(
+        r#"
+        INSERT OR IGNORE INTO main.message_event (
+          id, ts, role, source, session_id, raw_json, raw_json_z, codex_home_id
+        )
+        SELECT e.id, e.ts, e.role, e.source, e.session_id, e.raw_json, e.raw_json_z, m.new_id
+        FROM other.message_event e
+        JOIN temp.merge_home_map m ON m.old_id = e.codex_home_id
+        "#,
+        [],
+    )? as u64;
+    // The other database may hold times an older backfill stored verbatim.
+    let (started_at, last_event_at, ended_at) = (
+        timestamp_from_text("s.started_at"),
+        timestamp_from_text("s.last_event_at"),
+        timestamp_from_text("s.ended_at"),
+    );
+    // Without `WHERE true`, SQLite would read ON CONFLICT as a join clause.
+    tx.execute(
+        &format!(
+            r#"
+            INSERT INTO main.session (
+              codex_home_id, session_id, started_at, last_event_at, ended_at
+            )
+            SELECT m.new_id, s.session_id, {started_at}, {last_event_at}, {ended_at}
+            FROM other.session s
+            JOIN temp.merge_home_map m ON m.old_id = s.codex_home_id
+            WHERE true
+            ON CONFLICT (codex_home_id, session_id) DO UPDATE SET
+              started_at = MIN(started_at, excluded.started_at),
+              last_event_at = MAX(last_event_at, excluded.last_event_at),
+              ended_at = COALESCE(MAX(ended_at, excluded.ended_at), ended_at, excluded.ended_at)
+            "#
+        ),
+        [],
+    )?;
+    // Snapshots have no natural key; one per home, type, and time is enough.
+    stats.limit_snapshots = tx.execute(
+        r#"
+        INSERT INTO main.usage_limit_snapshot (
+          codex_home_id, ts, limit_type, percent_left, reset_at, source, raw_line
+        )
+        SELECT m.new_id, s.ts, s.limit_type, s.percent_left, s.reset_at, s.source, s.raw_line
+        FROM other.usage_limit_snapshot s
+        JOIN temp.merge_home_map m ON m.old_id = s.codex_home_id
+        WHERE NOT EXISTS (
+          SELECT 1 FROM main.usage_limit_snapshot x
+          WHERE x.codex_home_id = m.new_id
+            AND x.limit_type = s.limit_type
+            AND x.ts = s.ts
+        )
+        "#,
+        [],
+    )? as u64;
+    tx.execute("DROP TABLE temp.merge_home_map", [])?;
+    tx.commit()?;
+    Ok(stats)
+}
@@ -4,6 +4,7 @@ use tracker_core::session_id_from_source;
 
 use crate::Db;
 use crate::error::Result;
+use crate::helpers::{compress_raw_json, timestamp_from_text, ts_ms_from_text};
 use crate::homes::load_codex_home_path;
 
 const MIGRATION_0001: &str = include_str!("../migrations/0001_init.sql");
@@ -15,6 +16,19 @@ const MIGRATION_0006: &str = include_str!("../migrations/0006_add_reasoning_effo
 const MIGRATION_0007: &str = include_str!("../migrations/0007_add_usage_limits.sql");
 const MIGRATION_0008: &str = include_str!("../migrations/0008_add_message_events.sql");
 const MIGRATION_0009: &str = include_str!("../migrations/0009_add_cursor_state.sql");
+const MIGRATION_0010: &str = include_str!("../migrations/0010_add_webhooks.sql");
+const MIGRATION_0011: &str = include_str!("../migrations/0011_add_home_archived.sql");
+const MIGRATION_0012: &str = include_str!("../migrations/0012_add_cursor_session_id.sql");
+const MIGRATION_0013: &str = include_str!("../migrations/0013_add_model_aliases.sql");
+const MIGRATION_0014: &str = include_str!("../migrations/0014_add_provider.sql");
+const MIGRATION_0015: &str = include_str!("../migrations/0015_compress_raw_json.sql");
+const MIGRATION_0016: &str = include_str!("../migrations/0016_add_custom_limits.sql");
+const MIGRATION_0017: &str = include_str!("../migrations/0017_add_ts_ms.sql");
+const MIGRATION_0018: &str = include_str!("../migrations/0018_add_home_user.sql");
+const MIGRATION_0019: &str = include_str!("../migrations/0019_add_pricing_fallback.sql");
+const MIGRATION_0020: &str = include_str!("../migrations/0020_add_cost_source.sql");
+const MIGRATION_0021: &str = include_str!("../migrations/0021_add_sessions.sql");
+const MIGRATION_0022: &str = include_str!("../migrations/0022_add_schedules.sql");
 
 const MIGRATIONS: &[(&str, &str)] = &[
     ("0001_init", MIGRATION_0001),
@@ -26,8 +40,27 @@ const MIGRATIONS: &[(&str, &str)] = &[
     ("0007_add_usage_limits", MIGRATION_0007),
     ("0008_add_message_events", MIGRATION_0008),
     ("0009_add_cursor_state", MIGRATION_0009),
+    ("0010_add_webhooks", MIGRATION_0010),
+    ("0011_add_home_archived", MIGRATION_0011),
+    ("0012_add_cursor_session_id", MIGRATION_0012),
+    ("0013_add_model_aliases", MIGRATION_0013),
+    ("0014_add_provider", MIGRATION_0014),
+    ("0015_compress_raw_json", MIGRATION_0015),
+    ("0016_add_custom_limits", MIGRATION_0016),
+    ("0017_add_ts_ms", MIGRATION_0017),
+    ("0018_add_home_user", MIGRATION_0018),
+    ("0019_add_pricing_fallback", MIGRATION_0019),
+    ("0020_add_cost_source", MIGRATION_0020),
+    ("0021_add_sessions", MIGRATION_0021),
+    ("0022_add_schedules", MIGRATION_0022),
 ];
 
+/// `app_setting` key marking that session times were normalized.
+const SESSION_TIMES_NORMALIZED: &str = "session_times_normalized";
+
+/// Number of bundled migrations; increases whenever the schema changes.
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
 impl Db {
     pub fn migrate(&mut self) -> Result<()> {
         let tx = self.conn.transaction()?;
@@ -68,11 +101,104 @@ impl Db {
                 ensure_ingest_cursor_state_columns(&tx)?;
                 continue;
             }
+            if *name == "0011_add_home_archived" && table_has_column(&tx, "codex_home", "archived")?
+            {
+                continue;
+            }
+            if *name == "0012_add_cursor_session_id"
+                && table_has_column(&tx, "ingest_cursor", "session_id")?
+            {
+                continue;
+            }
+            if *name == "0014_add_provider" && table_has_column(&tx, "usage_event", "provider")? {
+                continue;
+            }
+            if *name == "0018_add_home_user" && table_has_column(&tx, "codex_home", "user_label")? {
+                continue;
+            }
+            if *name == "0019_add_pricing_fallback"
+                && table_has_column(&tx, "pricing_rule", "fallback")?
+            {
+                continue;
+            }
+            if *name == "0015_compress_raw_json" {
+                if !table_has_column(&tx, "usage_event", "raw_json_z")? {
+                    tx.execute_batch(sql)?;
+                    compress_raw_json_columns(&tx)?;
+                }
+                continue;
+            }
+            if *name == "0017_add_ts_ms" {
+                if !table_has_column(&tx, "usage_event", "ts_ms")? {
+                    tx.execute_batch(sql)?;
+                    backfill_ts_ms(&tx)?;
+                }
+                continue;
+            }
+            if *name == "0020_add_cost_source" {
+                if !table_has_column(&tx, "usage_event", "cost_source")? {
+                    tx.execute_batch(sql)?;
+                    backfill_cost_source(&tx)?;
+                }
+                continue;
+            }
+            // Backfills from stored events, so it only runs once.
+            if *name == "0021_add_sessions" {
+                if !table_exists(&tx, "session")? {
+                    tx.execute_batch(sql)?;
+                }
+                if !has_setting(&tx, SESSION_TIMES_NORMALIZED)? {
+                    normalize_session_times(&tx)?;
+                }
+                continue;
+            }
             tx.execute_batch(sql)?;
         }
         tx.commit()?;
         Ok(())
     }
+
+    /// How many of the bundled migrations are reflected in the schema, judged
+    /// by the table or column each one introduces. Equals [`SCHEMA_VERSION`]
+    /// once `migrate` has run with this build.
+    pub fn schema_level(&self) -> Result<u32> {
+        let markers: [(&str, Option<&str>); 22] = [
+            ("usage_event", None),
+            ("pricing_rule", Some("cached_input_per_1k")),
+            ("codex_home", None),
+            ("pricing_rule", Some("input_per_1m")),
+            ("usage_event", Some("session_id")),
+            ("usage_event", Some("reasoning_effort")),
+            ("usage_limit_snapshot", None),
+            ("message_event", None),
+            ("ingest_cursor", Some("last_model")),
+            ("webhook", None),
+            ("codex_home", Some("archived")),
+            ("ingest_cursor", Some("session_id")),
+            ("model_alias", None),
+            ("usage_event", Some("provider")),
+            ("usage_event", Some("raw_json_z")),
+            ("custom_limit", None),
+            ("usage_event", Some("ts_ms")),
+            ("codex_home", Some("user_label")),
+            ("pricing_rule", Some("fallback")),
+            ("usage_event", Some("cost_source")),
+            ("session", None),
+            ("schedule", None),
+        ];
+        let mut level = 0;
+        for (table, column) in markers {
+            let present = match column {
+                Some(column) => table_has_column(&self.conn, table, column)?,
+                None => table_exists(&self.conn, table)?,
+            };
+            if !present {
+                break;
+            }
+            level += 1;
+        }
+        Ok(level)
+    }
 }
 
 fn pricing_rule_has_cached_column(conn: &Connection) -> Result<bool> {
@@ -111,6 +237,17 @@ fn table_has_column(conn: &Connection, table: &str, column: &str) -> Result<bool
     Ok(false)
 }
 
+fn table_exists(conn: &Connection, table: &str) -> Result<bool> {
+    let found: Option<i64> = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+            params![table],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(found.is_some())
+}
+
 fn ensure_codex_home_columns(conn: &Connection) -> Result<()> {
     if !table_has_column(conn, "usage_event", "codex_home_id")? {
         conn.execute(
@@ -199,8 +336,13 @@ fn backfill_codex_home(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// Derives session ids for events that have none or still carry the source
+/// path 0005 filled in. Ids taken from `session_meta` are left alone.
 fn backfill_session_ids(conn: &Connection) -> Result<()> {
-    let mut stmt = conn.prepare("SELECT DISTINCT source, session_id FROM usage_event")?;
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT source, session_id FROM usage_event \
+         WHERE session_id IS NULL OR session_id = source",
+    )?;
     let mut rows = stmt.query([])?;
     while let Some(row) = rows.next()? {
         let source: String = row.get(0)?;
@@ -208,10 +350,102 @@ fn backfill_session_ids(conn: &Connection) -> Result<()> {
         let derived = session_id_from_source(&source);
         if session_id.as_deref() != Some(derived.as_str()) {
             conn.execute(
-                "UPDATE usage_event SET session_id = ?1 WHERE source = ?2",
+                "UPDATE usage_event SET session_id = ?1 \
+                 WHERE source = ?2 AND (session_id IS NULL OR session_id = source)",
                 params![derived, source],
             )?;
         }
     }
     Ok(())
 }
+
+/// Moves raw log lines written before 0015 into the compressed column, a
+/// batch at a time so large databases are not loaded into memory at once.
+fn compress_raw_json_columns(conn: &Connection) -> Result<()> {
+    for table in ["usage_event", "message_event"] {
+        let mut select = conn.prepare(&format!(
+            "SELECT rowid, raw_json FROM {table} WHERE raw_json IS NOT NULL LIMIT 1000"
+        ))?;
+        let mut update = conn.prepare(&format!(
+            "UPDATE {table} SET raw_json_z = ?1, raw_json = NULL WHERE rowid = ?2"
+        ))?;
+        loop {
+            let batch = select
+                .query_map([], |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            if batch.is_empty() {
+                break;
+            }
+            for (rowid, raw_json) in batch {
+                update.execute(params![compress_raw_json(Some(&raw_json))?, rowid])?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Labels priced rows: costs of imported ccusage reports (sources
+/// `ccusage:...`) as reported, the rest as computed. A cost recompute
+/// relabels fallback-priced rows as estimated.
+fn backfill_cost_source(conn: &Connection) -> Result<()> {
+    conn.execute(
+        r#"
+        UPDATE usage_event
+        SET cost_source = CASE WHEN source LIKE 'ccusage:%' THEN 'reported' ELSE 'computed' END
+        WHERE cost_usd IS NOT NULL AND cost_source IS NULL
+        "#,
+        [],
+    )?;
+    Ok(())
+}
+
+/// Derives `ts_ms` for events stored before 0017 from their RFC 3339 `ts`.
+fn backfill_ts_ms(conn: &Connection) -> Result<()> {
+    conn.execute(
+        &format!(
+            "UPDATE usage_event SET ts_ms = {} WHERE ts_ms IS NULL",
+            ts_ms_from_text("ts")
+        ),
+        [],
+    )?;
+    Ok(())
+}
+
+/// Rewrites session times an earlier 0021 backfill copied verbatim from
+/// event text (`...:05Z`) into the stored `Timestamp` format, so they
+/// compare as text with the times ingest writes. Records
+/// [`SESSION_TIMES_NORMALIZED`] so it runs once per database.
+fn normalize_session_times(conn: &Connection) -> Result<()> {
+    let started_at = timestamp_from_text("started_at");
+    let last_event_at = timestamp_from_text("last_event_at");
+    let ended_at = timestamp_from_text("ended_at");
+    conn.execute(
+        &format!(
+            r#"
+            UPDATE session
+            SET started_at = {started_at}, last_event_at = {last_event_at}, ended_at = {ended_at}
+            WHERE started_at != {started_at} OR last_event_at != {last_event_at}
+              OR ended_at != {ended_at}
+            "#
+        ),
+        [],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO app_setting (key, value) VALUES (?1, '1')",
+        [SESSION_TIMES_NORMALIZED],
+    )?;
+    Ok(())
+}
+
+fn has_setting(conn: &Connection, key: &str) -> Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM app_setting WHERE key = ?1",
+            [key],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some())
+}
@@ -0,0 +1,73 @@
+use std::env;
+use std::str::FromStr;
+use std::time::Duration;
+
+use rusqlite::Connection;
+
+use crate::error::Result;
+
+/// Connection tuning applied by [`crate::Db::open`] and
+/// [`crate::Db::open_read_only`]. Each field can be overridden with a
+/// `CODEX_TRACKER_SQLITE_*` environment variable; see [`DbOptions::from_env`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DbOptions {
+    /// How long a connection waits on a lock held by another one before
+    /// failing with `SQLITE_BUSY`.
+    pub busy_timeout_ms: u64,
+    /// Bytes of the database file to memory-map; 0 turns mmap off.
+    pub mmap_size: i64,
+    /// Page cache size: pages when positive, KiB when negative.
+    pub cache_size: i64,
+    /// WAL pages after which a commit checkpoints; 0 turns it off.
+    pub wal_autocheckpoint: u32,
+}
+
+impl Default for DbOptions {
+    fn default() -> Self {
+        Self {
+            busy_timeout_ms: 5_000,
+            mmap_size: 0,
+            cache_size: -20_000,
+            wal_autocheckpoint: 1_000,
+        }
+    }
+}
+
+impl DbOptions {
+    pub const BUSY_TIMEOUT_ENV: &str = "CODEX_TRACKER_SQLITE_BUSY_TIMEOUT_MS";
+    pub const MMAP_SIZE_ENV: &str = "CODEX_TRACKER_SQLITE_MMAP_SIZE";
+    pub const CACHE_SIZE_ENV: &str = "CODEX_TRACKER_SQLITE_CACHE_SIZE";
+    pub const WAL_AUTOCHECKPOINT_ENV: &str = "CODEX_TRACKER_SQLITE_WAL_AUTOCHECKPOINT";
+
+    /// The defaults, overridden by any set `CODEX_TRACKER_SQLITE_*`
+    /// variable. Unparsable values are logged and ignored.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            busy_timeout_ms: env_value(Self::BUSY_TIMEOUT_ENV).unwrap_or(defaults.busy_timeout_ms),
+            mmap_size: env_value(Self::MMAP_SIZE_ENV).unwrap_or(defaults.mmap_size),
+            cache_size: env_value(Self::CACHE_SIZE_ENV).unwrap_or(defaults.cache_size),
+            wal_autocheckpoint: env_value(Self::WAL_AUTOCHECKPOINT_ENV)
+                .unwrap_or(defaults.wal_autocheckpoint),
+        }
+    }
+
+    /// Pragmas that are per connection and safe on read-only ones.
+    pub(crate) fn apply(&self, conn: &Connection) -> Result<()> {
+        conn.busy_timeout(Duration::from_millis(self.busy_timeout_ms))?;
+        conn.pragma_update(None, "mmap_size", self.mmap_size)?;
+        conn.pragma_update(None, "cache_size", self.cache_size)?;
+        Ok(())
+    }
+}
+
+fn env_value<T: FromStr>(name: &str) -> Option<T> {
+    let value = env::var(name).ok()?;
+    match value.trim().parse() {
+        Ok(parsed) => Some(parsed),
+        Err(_) => {
+            tracing::warn!(name, value, "ignoring invalid SQLite tuning value");
+            None
+        }
+    }
+}
@@ -1,19 +1,35 @@
 use std::collections::HashMap;
-use std::env;
+use std::thread;
 use std::time::Instant;
 
-use rusqlite::params;
-use tracker_core::{PricingRule, PricingRuleInput, UsageTotals};
+use rusqlite::{Connection, params};
+use tracker_core::{
+    CostRecomputeProgress, CostSource, ModelAlias, PricingRule, PricingRuleInput, UsageTotals,
+    compute_cost_usd,
+};
 
 use crate::Db;
 use crate::error::Result;
-use crate::helpers::{compute_cost_from_pricing, delta_usage, row_to_pricing_rule, rule_matches};
+use crate::helpers::{delta_usage, matching_rule, row_to_pricing_rule};
+use crate::usage_rows::ALIASED_MODEL;
+
+/// Events re-priced per transaction, so writers such as ingest only wait for
+/// one chunk instead of the whole recompute. Chunks end on a source boundary.
+const COST_RECOMPUTE_CHUNK_ROWS: usize = 5_000;
+const PROGRESS_HOME_KEY: &str = "cost_recompute.home_id";
+const PROGRESS_PROCESSED_KEY: &str = "cost_recompute.processed";
+const PROGRESS_TOTAL_KEY: &str = "cost_recompute.total";
+const PROGRESS_COMPLETE_KEY: &str = "cost_recompute.complete";
+/// Last source whose events are all re-priced; only kept while a run is
+/// unfinished and the prices it used are still current.
+const PROGRESS_CURSOR_KEY: &str = "cost_recompute.cursor";
 
 impl Db {
     pub fn list_pricing_rules(&self) -> Result<Vec<PricingRule>> {
         let mut stmt = self.conn.prepare(
             r#"
-            SELECT id, model_pattern, input_per_1m, cached_input_per_1m, output_per_1m, effective_from, effective_to
+            SELECT id, model_pattern, input_per_1m, cached_input_per_1m, output_per_1m, effective_from, effective_to,
+                   provider, fallback
             FROM pricing_rule
             ORDER BY effective_from DESC, id DESC
             "#,
@@ -24,9 +40,60 @@ impl Db {
         Ok(rows)
     }
 
+    /// Models with events that no pricing rule covered, and how many events
+    /// each, most events first.
+    pub fn unpriced_models(&self, codex_home_id: i64) -> Result<Vec<(String, i64)>> {
+        let mut stmt = self.conn.prepare(&format!(
+            r#"
+            SELECT {ALIASED_MODEL} AS model, COUNT(*) AS events
+            FROM usage_event
+            WHERE codex_home_id = ?1 AND cost_usd IS NULL
+            GROUP BY 1
+            ORDER BY events DESC, model ASC
+            "#
+        ))?;
+        let rows = stmt
+            .query_map(params![codex_home_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    pub fn list_model_aliases(&self) -> Result<Vec<ModelAlias>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT alias, model FROM model_alias ORDER BY alias ASC")?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(ModelAlias {
+                    alias: row.get(0)?,
+                    model: row.get(1)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    pub fn replace_model_aliases(&mut self, aliases: &[ModelAlias]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM model_alias", [])?;
+        forget_recompute_cursor(&tx)?;
+        let mut inserted = 0usize;
+        {
+            let mut stmt =
+                tx.prepare("INSERT OR REPLACE INTO model_alias (alias, model) VALUES (?1, ?2)")?;
+            for alias in aliases {
+                stmt.execute(params![alias.alias, alias.model])?;
+                inserted += 1;
+            }
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+
     pub fn replace_pricing_rules(&mut self, rules: &[PricingRuleInput]) -> Result<usize> {
         let tx = self.conn.transaction()?;
         tx.execute("DELETE FROM pricing_rule", [])?;
+        forget_recompute_cursor(&tx)?;
         let mut inserted = 0usize;
         {
             let mut stmt = tx.prepare(
@@ -40,8 +107,10 @@ impl Db {
               cached_input_per_1m,
               output_per_1m,
               effective_from,
-              effective_to
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+              effective_to,
+              provider,
+              fallback
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
             "#,
             )?;
             for rule in rules {
@@ -54,7 +123,9 @@ impl Db {
                     rule.cached_input_per_1m,
                     rule.output_per_1m,
                     rule.effective_from,
-                    rule.effective_to
+                    rule.effective_to,
+                    rule.provider,
+                    rule.fallback
                 ])?;
                 inserted += 1;
             }
@@ -63,45 +134,172 @@ impl Db {
         Ok(inserted)
     }
 
+    /// Re-prices every event of the home from scratch, keeping costs that
+    /// were reported with the usage. Returns the number of events updated.
     pub fn update_event_costs(&mut self, codex_home_id: i64) -> Result<usize> {
-        let timing_enabled = env::var("CODEX_TRACKER_INGEST_TIMING").is_ok();
+        Ok(self.recompute_event_costs(codex_home_id, false)?.0)
+    }
+
+    /// Like [`Db::update_event_costs`], but continues an unfinished recompute
+    /// of the same home after the last source it completed. Returns the
+    /// number of events updated by this call and the progress afterwards.
+    pub fn resume_event_costs(
+        &mut self,
+        codex_home_id: i64,
+    ) -> Result<(usize, CostRecomputeProgress)> {
+        self.recompute_event_costs(codex_home_id, true)
+    }
+
+    /// The latest recompute's progress, if one ever ran.
+    pub fn cost_recompute_progress(&self) -> Result<Option<CostRecomputeProgress>> {
+        let Some(codex_home_id) = self
+            .get_setting(PROGRESS_HOME_KEY)?
+            .and_then(|value| value.parse::<i64>().ok())
+        else {
+            return Ok(None);
+        };
+        let count = |key| -> Result<u64> {
+            Ok(self
+                .get_setting(key)?
+                .and_then(|value| value.parse::<u64>().ok())
+                .unwrap_or(0))
+        };
+        Ok(Some(CostRecomputeProgress {
+            codex_home_id,
+            processed: count(PROGRESS_PROCESSED_KEY)?,
+            total: count(PROGRESS_TOTAL_KEY)?,
+            complete: self.get_setting(PROGRESS_COMPLETE_KEY)?.as_deref() == Some("1"),
+        }))
+    }
+
+    fn recompute_event_costs(
+        &mut self,
+        codex_home_id: i64,
+        resume: bool,
+    ) -> Result<(usize, CostRecomputeProgress)> {
         let start = Instant::now();
         let pricing = self.list_pricing_rules()?;
         let load_start = Instant::now();
         let rows = self.load_usage_rows_all(codex_home_id)?;
-        let rows_len = rows.len();
         let load_duration = load_start.elapsed();
-        let mut prev_by_source: HashMap<String, UsageTotals> = HashMap::new();
-        let tx = self.conn.transaction()?;
+        let saved = self.cost_recompute_progress()?;
+        let cursor = match saved {
+            Some(progress)
+                if resume && progress.codex_home_id == codex_home_id && !progress.complete =>
+            {
+                self.get_setting(PROGRESS_CURSOR_KEY)?
+            }
+            _ => None,
+        };
+        // Rows are ordered by source, so everything up to the cursor is done.
+        let skipped = match cursor.as_deref() {
+            Some(cursor) => rows.partition_point(|row| row.source.as_str() <= cursor),
+            None => 0,
+        };
+        let mut progress = CostRecomputeProgress {
+            codex_home_id,
+            processed: skipped as u64,
+            total: rows.len() as u64,
+            complete: false,
+        };
+        save_recompute_progress(&self.conn, &progress, cursor.as_deref())?;
+
         let update_start = Instant::now();
+        let mut prev_by_source: HashMap<&str, UsageTotals> = HashMap::new();
+        let mut chunk_start = skipped;
         let mut updated = 0usize;
-        {
-            let mut stmt = tx.prepare(
-                "UPDATE usage_event SET cost_usd = ?1 WHERE id = ?2 AND codex_home_id = ?3",
-            )?;
-            for row in rows {
-                let prev = prev_by_source.get(&row.source);
-                let delta = delta_usage(prev, row.usage);
-                prev_by_source.insert(row.source.clone(), row.usage);
-                let cost = if pricing.iter().any(|rule| rule_matches(rule, &row)) {
-                    Some(compute_cost_from_pricing(&pricing, &row, delta))
-                } else {
-                    None
-                };
-                stmt.execute(params![cost, row.id, codex_home_id])?;
-                updated += 1;
+        for (index, row) in rows.iter().enumerate().skip(skipped) {
+            let at_end = index + 1 == rows.len();
+            let ends_source = at_end || rows[index + 1].source != row.source;
+            let full = index + 1 - chunk_start >= COST_RECOMPUTE_CHUNK_ROWS;
+            if !(at_end || ends_source && full) {
+                continue;
+            }
+            let chunk = &rows[chunk_start..=index];
+            let tx = self.conn.transaction()?;
+            {
+                let mut stmt = tx.prepare_cached(
+                    r#"
+                    UPDATE usage_event SET cost_usd = ?1, cost_source = ?2
+                    WHERE id = ?3 AND codex_home_id = ?4 AND cost_source IS NOT 'reported'
+                    "#,
+                )?;
+                for row in chunk {
+                    let prev = prev_by_source.get(row.source.as_str());
+                    let delta = delta_usage(prev, row.usage);
+                    prev_by_source.insert(&row.source, row.usage);
+                    let rule = matching_rule(&pricing, row);
+                    let cost = rule.map(|rule| compute_cost_usd(delta, rule));
+                    let source = rule.map(CostSource::for_rule);
+                    stmt.execute(params![cost, source, row.id, codex_home_id])?;
+                }
             }
+            updated += chunk.len();
+            progress.processed += chunk.len() as u64;
+            progress.complete = index + 1 == rows.len();
+            save_recompute_progress(&tx, &progress, Some(&row.source))?;
+            tx.commit()?;
+            chunk_start = index + 1;
+            // Let a writer waiting on the lock in before the next chunk.
+            thread::yield_now();
         }
-        tx.commit()?;
-        if timing_enabled {
-            eprintln!(
-                "update_event_costs: rows={} load={}ms update={}ms total={}ms",
-                rows_len,
-                load_duration.as_millis(),
-                update_start.elapsed().as_millis(),
-                start.elapsed().as_millis()
-            );
+        if rows.len() == skipped {
+            progress.complete = true;
+            save_recompute_progress(&self.conn, &progress, None)?;
+        }
+        tracing::debug!(
+            codex_home_id,
+            rows = rows.len(),
+            resumed_from = skipped,
+            load_ms = load_duration.as_millis() as u64,
+            update_ms = update_start.elapsed().as_millis() as u64,
+            total_ms = start.elapsed().as_millis() as u64,
+            "recomputed event costs"
+        );
+        Ok((updated, progress))
+    }
+}
+
+fn save_recompute_progress(
+    conn: &Connection,
+    progress: &CostRecomputeProgress,
+    cursor: Option<&str>,
+) -> Result<()> {
+    let mut stmt = conn.prepare_cached(
+        r#"
+        INSERT INTO app_setting (key, value)
+        VALUES (?1, ?2)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value
+        "#,
+    )?;
+    stmt.execute(params![
+        PROGRESS_HOME_KEY,
+        progress.codex_home_id.to_string()
+    ])?;
+    stmt.execute(params![
+        PROGRESS_PROCESSED_KEY,
+        progress.processed.to_string()
+    ])?;
+    stmt.execute(params![PROGRESS_TOTAL_KEY, progress.total.to_string()])?;
+    stmt.execute(params![
+        PROGRESS_COMPLETE_KEY,
+        if progress.complete { "1" } else { "0" }
+    ])?;
+    match cursor {
+        Some(cursor) if !progress.complete => {
+            stmt.execute(params![PROGRESS_CURSOR_KEY, cursor])?;
         }
-        Ok(updated)
+        _ => forget_recompute_cursor(conn)?,
     }
+    Ok(())
+}
+
+/// Drops the resume point, so the next recompute starts over; used when
+/// prices change under an unfinished run.
+fn forget_recompute_cursor(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "DELETE FROM app_setting WHERE key = ?1",
+        [PROGRESS_CURSOR_KEY],
+    )?;
+    Ok(())
 }
@@ -0,0 +1,97 @@
+use chrono::Utc;
+use rusqlite::{OptionalExtension, Row, params};
+use tracker_core::{Schedule, ScheduleInput};
+
+use crate::Db;
+use crate::error::Result;
+
+const SCHEDULE_COLUMNS: &str = "id, payload, local_time, target, destination, smtp_url, \
+     email_from, enabled, last_sent_on, last_error, created_at";
+
+impl Db {
+    pub fn list_schedules(&self) -> Result<Vec<Schedule>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {SCHEDULE_COLUMNS} FROM schedule ORDER BY id ASC"
+        ))?;
+        let rows = stmt
+            .query_map([], row_to_schedule)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    pub fn get_schedule(&self, id: i64) -> Result<Option<Schedule>> {
+        self.conn
+            .query_row(
+                &format!("SELECT {SCHEDULE_COLUMNS} FROM schedule WHERE id = ?1"),
+                params![id],
+                row_to_schedule,
+            )
+            .optional()
+            .map_err(crate::error::DbError::from)
+    }
+
+    /// Stores an enabled schedule. `last_sent_on` is the local date to treat
+    /// as already sent, so a schedule created after its time waits for the
+    /// next day instead of going out on the next check.
+    pub fn create_schedule(
+        &self,
+        input: &ScheduleInput,
+        last_sent_on: Option<&str>,
+    ) -> Result<Schedule> {
+        let now = Utc::now().to_rfc3339();
+        self.conn.execute(
+            r#"
+            INSERT INTO schedule (
+              payload, local_time, target, destination, smtp_url, email_from, enabled,
+              last_sent_on, created_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, 1, ?7, ?8)
+            "#,
+            params![
+                input.payload,
+                input.local_time,
+                input.target,
+                input.destination,
+                input.smtp_url,
+                input.email_from,
+                last_sent_on,
+                now
+            ],
+        )?;
+        let id = self.conn.last_insert_rowid();
+        self.get_schedule(id)?
+            .ok_or_else(|| crate::error::DbError::Sqlite(rusqlite::Error::QueryReturnedNoRows))
+    }
+
+    pub fn delete_schedule(&self, id: i64) -> Result<usize> {
+        Ok(self
+            .conn
+            .execute("DELETE FROM schedule WHERE id = ?1", params![id])?)
+    }
+
+    /// Records a run on the local date `sent_on`, so the schedule is not
+    /// sent again that day, with the error if it failed.
+    pub fn record_schedule_run(&self, id: i64, sent_on: &str, error: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE schedule SET last_sent_on = ?2, last_error = ?3 WHERE id = ?1",
+            params![id, sent_on, error],
+        )?;
+        Ok(())
+    }
+}
+
+fn row_to_schedule(row: &Row<'_>) -> std::result::Result<Schedule, rusqlite::Error> {
+    Ok(Schedule {
+        id: row.get(0)?,
+        payload: row.get(1)?,
+        local_time: row.get(2)?,
+        target: row.get(3)?,
+        destination: row.get(4)?,
+        smtp_url: row.get(5)?,
+        email_from: row.get(6)?,
+        enabled: row.get::<_, i64>(7)? != 0,
+        last_sent_on: row.get(8)?,
+        last_error: row.get(9)?,
+        created_at: row.get(10)?,
+    })
+}
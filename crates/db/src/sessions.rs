@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+
+use rusqlite::{Connection, ToSql, params, params_from_iter};
+use tracker_core::{
+    HomeScope, SessionSpan, SessionTimeline, SessionTimelineEntry, TimeRange, Timestamp,
+    UsageTotals,
+};
+
+use crate::Db;
+use crate::error::Result;
+use crate::helpers::{
+    compute_cost_from_pricing, delta_usage, range_millis, row_to_usage_row, rule_matches,
+};
+
+impl Db {
+    /// Records shutdowns (`(session_id, ts)`) logged by Codex. A shutdown
+    /// logged before the session's last event is ignored: the session was
+    /// resumed after it.
+    pub fn record_session_ends(
+        &mut self,
+        codex_home_id: i64,
+        ends: &[(String, Timestamp)],
+    ) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                r#"
+                INSERT INTO session (codex_home_id, session_id, started_at, last_event_at, ended_at)
+                VALUES (?1, ?2, ?3, ?3, ?3)
+                ON CONFLICT (codex_home_id, session_id) DO UPDATE SET
+                  ended_at = CASE
+                    WHEN excluded.ended_at >= last_event_at THEN excluded.ended_at
+                    ELSE ended_at
+                  END
+                "#,
+            )?;
+            for (session_id, ts) in ends {
+                stmt.execute(params![codex_home_id, session_id, ts])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Stored spans of the sessions started in the range, oldest first.
+    pub fn session_spans(
+        &self,
+        range: &TimeRange,
+        home: impl Into<HomeScope>,
+    ) -> Result<Vec<SessionSpan>> {
+        let mut values: Vec<&dyn ToSql> = vec![&range.start, &range.end];
+        let home_id = home.into().home_id();
+        let mut home_filter = String::new();
+        if let Some(home_id) = home_id.as_ref() {
+            values.push(home_id);
+            home_filter = format!(" AND codex_home_id = ?{} ", values.len());
+        }
+        let sql = format!(
+            r#"
+            SELECT session_id, started_at, last_event_at, ended_at FROM session
+            WHERE started_at >= ?1 AND started_at < ?2 {home_filter}
+            ORDER BY started_at, session_id
+            "#
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values), |row| {
+            Ok(SessionSpan {
+                session_id: row.get(0)?,
+                started_at: row.get(1)?,
+                last_event_at: row.get(2)?,
+                ended_at: row.get(3)?,
+            })
+        })?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Message counts per session id for messages in the range.
+    pub(crate) fn message_counts_by_session(
+        &self,
+        range: &TimeRange,
+        home: HomeScope,
+    ) -> Result<HashMap<String, u64>> {
+        let mut sql = String::from(
+            r#"
+            SELECT session_id, COUNT(*)
+            FROM message_event
+            WHERE ts >= ?1 AND ts < ?2
+            "#,
+        );
+        let mut values: Vec<&dyn ToSql> = vec![&range.start, &range.end];
+        let home_id = home.home_id();
+        if let Some(home_id) = home_id.as_ref() {
+            values.push(home_id);
+            sql.push_str(&format!(" AND codex_home_id = ?{} ", values.len()));
+        }
+        sql.push_str(" GROUP BY session_id");
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values), |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64))
+        })?;
+        Ok(rows.collect::<std::result::Result<HashMap<_, _>, _>>()?)
+    }
+
+    /// The stored session id of each source with usage in the range. Logs
+    /// with a `session_meta` line are grouped by its id rather than by their
+    /// file name, so a renamed or copied log joins its session.
+    pub(crate) fn session_ids_by_source(
+        &self,
+        range: &TimeRange,
+        home: HomeScope,
+    ) -> Result<HashMap<String, String>> {
+        let mut sql = String::from(
+            r#"
+            SELECT source, MAX(session_id)
+            FROM usage_event
+            WHERE ts_ms >= ?1 AND ts_ms < ?2 AND session_id IS NOT NULL
+            "#,
+        );
+        let (start_ms, end_ms) = range_millis(range)?;
+        let mut values: Vec<&dyn ToSql> = vec![&start_ms, &end_ms];
+        let home_id = home.home_id();
+        if let Some(home_id) = home_id.as_ref() {
+            values.push(home_id);
+            sql.push_str(&format!(" AND codex_home_id = ?{} ", values.len()));
+        }
+        sql.push_str(" GROUP BY source");
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values), |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        Ok(rows.collect::<std::result::Result<HashMap<_, _>, _>>()?)
+    }
+
+    /// `(session_id, ts)` of every usage and message event in the range,
+    /// ordered by session and then time, for splitting sessions at idle gaps.
+    pub fn session_activity_times(
+        &self,
+        range: &TimeRange,
+        home: impl Into<HomeScope>,
+    ) -> Result<Vec<(String, String)>> {
+        let (start_ms, end_ms) = range_millis(range)?;
+        let mut values: Vec<&dyn ToSql> = vec![&start_ms, &end_ms, &range.start, &range.end];
+        let home_id = home.into().home_id();
+        let mut home_filter = String::new();
+        if let Some(home_id) = home_id.as_ref() {
+            values.push(home_id);
+            home_filter = format!(" AND codex_home_id = ?{} ", values.len());
+        }
+        let sql = format!(
+            r#"
+            SELECT COALESCE(session_id, source), ts FROM usage_event
+            WHERE ts_ms >= ?1 AND ts_ms < ?2 {home_filter}
+            UNION ALL
+            SELECT session_id, ts FROM message_event
+            WHERE ts >= ?3 AND ts < ?4 {home_filter}
+            ORDER BY 1, 2
+            "#
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values), |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// `(session_id, ts, context_used)` of every usage event in the range
+    /// with a known context size, ordered by session and then time, for
+    /// spotting compactions.
+    pub fn session_context_samples(
+        &self,
+        range: &TimeRange,
+        home: impl Into<HomeScope>,
+    ) -> Result<Vec<(String, String, u64)>> {
+        let (start_ms, end_ms) = range_millis(range)?;
+        let mut values: Vec<&dyn ToSql> = vec![&start_ms, &end_ms];
+        let home_id = home.into().home_id();
+        let mut home_filter = String::new();
+        if let Some(home_id) = home_id.as_ref() {
+            values.push(home_id);
+            home_filter = format!(" AND codex_home_id = ?{} ", values.len());
+        }
+        let sql = format!(
+            r#"
+            SELECT COALESCE(session_id, source), ts, context_used FROM usage_event
+            WHERE ts_ms >= ?1 AND ts_ms < ?2 AND context_used > 0 {home_filter}
+            ORDER BY 1, ts_ms, id
+            "#
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values), |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?.max(0) as u64,
+            ))
+        })?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Messages, per-event token deltas, and reasoning effort changes for one
+    /// session, oldest first. Messages sort before token events with the same
+    /// timestamp.
+    pub fn session_timeline(
+        &self,
+        codex_home_id: i64,
+        session_id: &str,
+    ) -> Result<SessionTimeline> {
+        let pricing = self.list_pricing_rules()?;
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, ts, model, input_tokens, cached_input_tokens, output_tokens,
+                   reasoning_output_tokens, total_tokens, cost_usd, source, reasoning_effort,
+                   provider, context_used, context_window
+            FROM usage_event
+            WHERE codex_home_id = ?1 AND session_id = ?2
+            ORDER BY ts_ms ASC, id ASC
+            "#,
+        )?;
+        let rows = stmt.query_map(params![codex_home_id, session_id], |row| {
+            Ok((
+                row_to_usage_row(row)?,
+                row.get::<_, i64>(12)? as u64,
+                row.get::<_, i64>(13)? as u64,
+            ))
+        })?;
+        let mut token_entries = Vec::new();
+        let mut prev_by_source: HashMap<String, UsageTotals> = HashMap::new();
+        let mut effort: Option<Option<String>> = None;
+        for row in rows {
+            let (row, context_used, context_window) = row?;
+            if effort.as_ref() != Some(&row.reasoning_effort) {
+                token_entries.push(SessionTimelineEntry::EffortChange {
+                    ts: row.ts.clone(),
+                    model: row.model.clone(),
+                    from: effort.flatten(),
+                    to: row.reasoning_effort.clone(),
+                });
+                effort = Some(row.reasoning_effort.clone());
+            }
+            let delta = delta_usage(prev_by_source.get(&row.source), row.usage);
+            prev_by_source.insert(row.source.clone(), row.usage);
+            let cost_usd = row.cost_usd.or_else(|| {
+                pricing
+                    .iter()
+                    .any(|rule| rule_matches(rule, &row))
+                    .then(|| compute_cost_from_pricing(&pricing, &row, delta))
+            });
+            token_entries.push(SessionTimelineEntry::TokenCount {
+                ts: row.ts,
+                model: row.model,
+                usage: delta,
+                context_used,
+                context_window,
+                cost_usd,
+            });
+        }
+
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT ts, role
+            FROM message_event
+            WHERE codex_home_id = ?1 AND session_id = ?2
+            ORDER BY ts ASC, id ASC
+            "#,
+        )?;
+        let messages = stmt
+            .query_map(params![codex_home_id, session_id], |row| {
+                Ok(SessionTimelineEntry::Message {
+                    ts: row.get(0)?,
+                    role: row.get(1)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut entries = messages;
+        entries.extend(token_entries);
+        // Stable sort keeps messages ahead of token events at equal timestamps.
+        entries.sort_by(|a, b| a.ts().cmp(b.ts()));
+        Ok(SessionTimeline {
+            session_id: session_id.to_string(),
+            entries,
+        })
+    }
+}
+
+/// Widens the stored span of each session in `events` (`(session_id, ts)`)
+/// to cover them. An event after a recorded shutdown reopens the session.
+pub(crate) fn extend_session_spans<'a>(
+    conn: &Connection,
+    codex_home_id: i64,
+    events: impl IntoIterator<Item = (&'a str, Timestamp)>,
+) -> Result<()> {
+    let mut spans: HashMap<&str, (Timestamp, Timestamp)> = HashMap::new();
+    for (session_id, ts) in events {
+        spans
+            .entry(session_id)
+            .and_modify(|(start, end)| {
+                *start = (*start).min(ts);
+                *end = (*end).max(ts);
+            })
+            .or_insert((ts, ts));
+    }
+    let mut stmt = conn.prepare_cached(
+        r#"
+        INSERT INTO session (codex_home_id, session_id, started_at, last_event_at)
+        VALUES (?1, ?2, ?3, ?4)
+        ON CONFLICT (codex_home_id, session_id) DO UPDATE SET
+          started_at = MIN(started_at, excluded.started_at),
+          last_event_at = MAX(last_event_at, excluded.last_event_at),
+          ended_at = CASE
+            WHEN excluded.last_event_at > ended_at THEN NULL
+            ELSE ended_at
+          END
+        "#,
+    )?;
+    for (session_id, (start, end)) in spans {
+        stmt.execute(params![codex_home_id, session_id, start, end])?;
+    }
+    Ok(())
+}
@@ -3,6 +3,8 @@ use rusqlite::params;
 use crate::Db;
 use crate::error::Result;
 
+const DEFAULT_GLOBAL_SHORTCUT: &str = "CommandOrControl+Shift+U";
+
 impl Db {
     pub fn get_setting(&self, key: &str) -> Result<Option<String>> {
         let mut stmt = self
@@ -28,6 +30,23 @@ impl Db {
         Ok(())
     }
 
+    pub fn delete_setting(&self, key: &str) -> Result<usize> {
+        Ok(self
+            .conn
+            .execute("DELETE FROM app_setting WHERE key = ?1", [key])?)
+    }
+
+    /// Returns `(key, value)` pairs whose key starts with `prefix`, ordered by key.
+    pub fn list_settings_with_prefix(&self, prefix: &str) -> Result<Vec<(String, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT key, value FROM app_setting WHERE substr(key, 1, length(?1)) = ?1 ORDER BY key",
+        )?;
+        let rows = stmt
+            .query_map([prefix], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
     pub fn get_context_active_minutes(&self) -> Result<u32> {
         let minutes = self
             .get_setting("context_active_minutes")?
@@ -39,4 +58,90 @@ impl Db {
     pub fn set_context_active_minutes(&self, minutes: u32) -> Result<()> {
         self.set_setting("context_active_minutes", &minutes.to_string())
     }
+
+    /// Seconds between background ingests in the desktop app; 0 turns them
+    /// off.
+    pub fn get_auto_ingest_secs(&self) -> Result<u32> {
+        let secs = self
+            .get_setting("auto_ingest_secs")?
+            .and_then(|value| value.parse::<u32>().ok())
+            .unwrap_or(60);
+        Ok(secs)
+    }
+
+    pub fn set_auto_ingest_secs(&self, secs: u32) -> Result<()> {
+        self.set_setting("auto_ingest_secs", &secs.to_string())
+    }
+
+    /// Accelerator that shows or hides the desktop window from anywhere, e.g.
+    /// `CommandOrControl+Shift+U`; empty when turned off.
+    pub fn get_global_shortcut(&self) -> Result<String> {
+        Ok(self
+            .get_setting("global_shortcut")?
+            .unwrap_or_else(|| DEFAULT_GLOBAL_SHORTCUT.to_string()))
+    }
+
+    pub fn set_global_shortcut(&self, shortcut: &str) -> Result<()> {
+        self.set_setting("global_shortcut", shortcut.trim())
+    }
+
+    /// Whether ingest is paused, so no new usage is recorded until resumed.
+    pub fn get_tracking_paused(&self) -> Result<bool> {
+        Ok(self.get_setting("tracking_paused")?.as_deref() == Some("1"))
+    }
+
+    pub fn set_tracking_paused(&self, paused: bool) -> Result<()> {
+        self.set_setting("tracking_paused", if paused { "1" } else { "0" })
+    }
+
+    /// Percent used at which a limit reads `warn`.
+    pub fn get_limit_warn_percent(&self) -> Result<u32> {
+        Ok(self
+            .get_setting("limit_warn_percent")?
+            .and_then(|value| value.parse::<u32>().ok())
+            .unwrap_or(75))
+    }
+
+    pub fn set_limit_warn_percent(&self, percent: u32) -> Result<()> {
+        self.set_setting("limit_warn_percent", &percent.to_string())
+    }
+
+    /// Percent used at which a limit reads `critical`.
+    pub fn get_limit_critical_percent(&self) -> Result<u32> {
+        Ok(self
+            .get_setting("limit_critical_percent")?
+            .and_then(|value| value.parse::<u32>().ok())
+            .unwrap_or(90))
+    }
+
+    pub fn set_limit_critical_percent(&self, percent: u32) -> Result<()> {
+        self.set_setting("limit_critical_percent", &percent.to_string())
+    }
+
+    /// Whether the desktop app registers itself as a login item.
+    pub fn get_launch_at_login(&self) -> Result<bool> {
+        Ok(self.get_setting("launch_at_login")?.as_deref() == Some("1"))
+    }
+
+    pub fn set_launch_at_login(&self, enabled: bool) -> Result<()> {
+        self.set_setting("launch_at_login", if enabled { "1" } else { "0" })
+    }
+
+    pub fn get_monthly_budget_usd(&self) -> Result<Option<f64>> {
+        Ok(self
+            .get_setting("monthly_budget_usd")?
+            .and_then(|value| value.parse::<f64>().ok())
+            .filter(|value| *value > 0.0))
+    }
+
+    /// Stores the monthly budget; `None` clears it.
+    pub fn set_monthly_budget_usd(&self, budget: Option<f64>) -> Result<()> {
+        match budget {
+            Some(value) => self.set_setting("monthly_budget_usd", &value.to_string()),
+            None => {
+                self.delete_setting("monthly_budget_usd")?;
+                Ok(())
+            }
+        }
+    }
 }
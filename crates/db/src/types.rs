@@ -12,6 +12,13 @@ pub enum Metric {
     Cost,
 }
 
+/// What [`crate::Db::timeseries_grouped`] splits series by.
+#[derive(Debug, Clone, Copy)]
+pub enum GroupBy {
+    Model,
+    Effort,
+}
+
 #[derive(Debug, Clone)]
 pub struct RowUsage {
     pub id: String,
@@ -21,6 +28,32 @@ pub struct RowUsage {
     pub cost_usd: Option<f64>,
     pub source: String,
     pub reasoning_effort: Option<String>,
+    pub provider: Option<String>,
+}
+
+/// Rows removed by [`crate::Db::prune_before`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PruneStats {
+    pub usage_events: u64,
+    pub message_events: u64,
+    pub limit_snapshots: u64,
+}
+
+/// Rows copied by [`crate::Db::merge_from`]; duplicates are not counted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MergeStats {
+    pub homes_added: u64,
+    pub usage_events: u64,
+    pub message_events: u64,
+    pub limit_snapshots: u64,
+}
+
+/// One session log's events in a range and their usage summed from
+/// per-event deltas, as [`crate::Db::usage_by_source`] reports it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SourceUsage {
+    pub events: u64,
+    pub usage: UsageTotals,
 }
 
 /// Cursor metadata for incremental ingest runs.
@@ -36,4 +69,9 @@ pub struct IngestCursor {
     pub updated_at: String,
     pub last_model: Option<String>,
     pub last_effort: Option<String>,
+    /// The `id` from the file's `session_meta` line, once it has been read.
+    pub session_id: Option<String>,
+    /// The provider from the file's `session_meta` line, once it has been
+    /// read.
+    pub provider: Option<String>,
 }
@@ -1,56 +1,116 @@
-use rusqlite::params;
-use tracker_core::TimeRange;
+use rusqlite::{ToSql, params, params_from_iter};
+use tracker_core::{HomeScope, TimeRange};
 
 use crate::Db;
 use crate::error::Result;
-use crate::helpers::row_to_usage_row;
+use crate::helpers::{range_millis, row_to_usage_row};
 use crate::types::RowUsage;
 
+/// `usage_event.model` with the `model_alias` table applied, for queries
+/// that read `usage_event` unaliased.
+pub(crate) const ALIASED_MODEL: &str = "COALESCE((SELECT model FROM model_alias \
+     WHERE alias = usage_event.model), usage_event.model)";
+
 impl Db {
+    /// Usage rows in the range with model aliases applied.
     pub(crate) fn load_usage_rows(
         &self,
         range: &TimeRange,
         model: Option<&str>,
-        codex_home_id: i64,
+        home: HomeScope,
     ) -> Result<Vec<RowUsage>> {
-        let mut sql = String::from(
+        let mut sql = format!(
             r#"
-            SELECT id, ts, model, input_tokens, cached_input_tokens, output_tokens,
-                   reasoning_output_tokens, total_tokens, cost_usd, source, reasoning_effort
+            SELECT id, ts, {ALIASED_MODEL}, input_tokens, cached_input_tokens, output_tokens,
+                   reasoning_output_tokens, total_tokens, cost_usd, source, reasoning_effort,
+                   provider
             FROM usage_event
-            WHERE codex_home_id = ?1 AND ts >= ?2 AND ts < ?3
-            "#,
+            WHERE ts_ms >= ?1 AND ts_ms < ?2
+            "#
         );
-        if model.is_some() {
-            sql.push_str(" AND model = ?4 ");
+        let (start_ms, end_ms) = range_millis(range)?;
+        let mut values: Vec<&dyn ToSql> = vec![&start_ms, &end_ms];
+        let home_id = home.home_id();
+        if let Some(home_id) = home_id.as_ref() {
+            values.push(home_id);
+            sql.push_str(&format!(" AND codex_home_id = ?{} ", values.len()));
+        }
+        if let Some(model) = model.as_ref() {
+            values.push(model);
+            sql.push_str(&format!(" AND {ALIASED_MODEL} = ?{} ", values.len()));
         }
-        sql.push_str(" ORDER BY source, ts ASC");
+        // Sources are absolute paths under their home, so per-source deltas
+        // stay correct when several homes are loaded together.
+        sql.push_str(" ORDER BY source, ts_ms ASC");
         let mut stmt = self.conn.prepare(&sql)?;
-        let rows = if let Some(model) = model {
-            stmt.query_map(
-                params![codex_home_id, range.start, range.end, model],
-                row_to_usage_row,
-            )?
-        } else {
-            stmt.query_map(
-                params![codex_home_id, range.start, range.end],
-                row_to_usage_row,
-            )?
-        };
+        let rows = stmt.query_map(params_from_iter(values), row_to_usage_row)?;
         Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
     }
 
     pub(crate) fn load_usage_rows_all(&self, codex_home_id: i64) -> Result<Vec<RowUsage>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare(&format!(
             r#"
-            SELECT id, ts, model, input_tokens, cached_input_tokens, output_tokens,
-                   reasoning_output_tokens, total_tokens, cost_usd, source, reasoning_effort
+            SELECT id, ts, {ALIASED_MODEL}, input_tokens, cached_input_tokens, output_tokens,
+                   reasoning_output_tokens, total_tokens, cost_usd, source, reasoning_effort,
+                   provider
             FROM usage_event
             WHERE codex_home_id = ?1
-            ORDER BY source, ts ASC
-            "#,
-        )?;
+            ORDER BY source, ts_ms ASC
+            "#
+        ))?;
         let rows = stmt.query_map(params![codex_home_id], row_to_usage_row)?;
         Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
     }
 }
+
+/// `WITH` clauses (without the `WITH`) computing per-event token deltas in
+/// SQL, the way [`crate::helpers::delta_usage`] does in Rust: `LAG` over
+/// each `partition` ordered by `ts_ms`, where a drop in `total_tokens` is a
+/// counter reset and the event counts in full. `from` is the `FROM` body,
+/// filters included, and must read `usage_event` under that name. The
+/// `deltas` clause exposes `ts`, `ts_ms`, `model` (aliases applied), `provider`,
+/// `cost_usd`, the `extra` columns, and `delta_*` token columns.
+pub(crate) fn usage_delta_ctes(extra: &[&str], from: &str, partition: &str) -> String {
+    let extra = extra
+        .iter()
+        .map(|column| format!("{column}, "))
+        .collect::<String>();
+    let delta = |column: &str| {
+        format!(
+            "CASE WHEN prev_total_tokens IS NULL OR total_tokens < prev_total_tokens \
+             THEN {column} ELSE MAX({column} - prev_{column}, 0) END AS delta_{column}"
+        )
+    };
+    let columns = [
+        "input_tokens",
+        "cached_input_tokens",
+        "output_tokens",
+        "reasoning_output_tokens",
+        "total_tokens",
+    ];
+    let lagged = columns
+        .iter()
+        .map(|column| format!("LAG({column}) OVER w AS prev_{column}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let deltas = columns
+        .iter()
+        .map(|column| delta(column))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        r#"
+        ordered AS (
+          SELECT {extra}ts, ts_ms, {ALIASED_MODEL} AS model, provider, cost_usd, {plain},
+                 {lagged}
+          FROM {from}
+          WINDOW w AS (PARTITION BY {partition} ORDER BY ts_ms)
+        ),
+        deltas AS (
+          SELECT *, {deltas}
+          FROM ordered
+        )
+        "#,
+        plain = columns.join(", "),
+    )
+}
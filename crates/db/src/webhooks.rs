@@ -0,0 +1,174 @@
+use chrono::Utc;
+use rusqlite::{OptionalExtension, Row, params};
+use tracker_core::{Webhook, WebhookDelivery};
+
+use crate::Db;
+use crate::error::Result;
+
+impl Db {
+    pub fn list_webhooks(&self) -> Result<Vec<Webhook>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, url, secret, event_types, enabled, created_at
+            FROM webhook
+            ORDER BY id ASC
+            "#,
+        )?;
+        let rows = stmt
+            .query_map([], row_to_webhook)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    pub fn get_webhook(&self, id: i64) -> Result<Option<Webhook>> {
+        self.conn
+            .query_row(
+                r#"
+                SELECT id, url, secret, event_types, enabled, created_at
+                FROM webhook
+                WHERE id = ?1
+                "#,
+                params![id],
+                row_to_webhook,
+            )
+            .optional()
+            .map_err(crate::error::DbError::from)
+    }
+
+    pub fn create_webhook(
+        &self,
+        url: &str,
+        secret: Option<&str>,
+        event_types: &[String],
+    ) -> Result<Webhook> {
+        let now = Utc::now().to_rfc3339();
+        self.conn.execute(
+            r#"
+            INSERT INTO webhook (url, secret, event_types, enabled, created_at)
+            VALUES (?1, ?2, ?3, 1, ?4)
+            "#,
+            params![url, secret, event_types.join(","), now],
+        )?;
+        let id = self.conn.last_insert_rowid();
+        self.get_webhook(id)?
+            .ok_or_else(|| crate::error::DbError::Sqlite(rusqlite::Error::QueryReturnedNoRows))
+    }
+
+    pub fn delete_webhook(&self, id: i64) -> Result<usize> {
+        Ok(self
+            .conn
+            .execute("DELETE FROM webhook WHERE id = ?1", params![id])?)
+    }
+
+    pub fn insert_webhook_delivery(
+        &self,
+        webhook_id: i64,
+        event_type: &str,
+        payload: &str,
+    ) -> Result<i64> {
+        let now = Utc::now().to_rfc3339();
+        self.conn.execute(
+            r#"
+            INSERT INTO webhook_delivery (webhook_id, event_type, payload, status, attempts, created_at)
+            VALUES (?1, ?2, ?3, 'pending', 0, ?4)
+            "#,
+            params![webhook_id, event_type, payload, now],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn get_webhook_delivery(&self, id: i64) -> Result<Option<WebhookDelivery>> {
+        self.conn
+            .query_row(
+                r#"
+                SELECT id, webhook_id, event_type, payload, status, attempts,
+                       response_status, error, created_at, last_attempt_at
+                FROM webhook_delivery
+                WHERE id = ?1
+                "#,
+                params![id],
+                row_to_webhook_delivery,
+            )
+            .optional()
+            .map_err(crate::error::DbError::from)
+    }
+
+    /// Records one delivery attempt and its outcome.
+    pub fn record_webhook_attempt(
+        &self,
+        id: i64,
+        status: &str,
+        response_status: Option<u16>,
+        error: Option<&str>,
+    ) -> Result<()> {
+        let now = Utc::now().to_rfc3339();
+        self.conn.execute(
+            r#"
+            UPDATE webhook_delivery
+            SET status = ?2,
+                attempts = attempts + 1,
+                response_status = ?3,
+                error = ?4,
+                last_attempt_at = ?5
+            WHERE id = ?1
+            "#,
+            params![id, status, response_status, error, now],
+        )?;
+        Ok(())
+    }
+
+    pub fn list_webhook_deliveries(
+        &self,
+        webhook_id: Option<i64>,
+        limit: u32,
+    ) -> Result<Vec<WebhookDelivery>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, webhook_id, event_type, payload, status, attempts,
+                   response_status, error, created_at, last_attempt_at
+            FROM webhook_delivery
+            WHERE ?1 IS NULL OR webhook_id = ?1
+            ORDER BY id DESC
+            LIMIT ?2
+            "#,
+        )?;
+        let rows = stmt
+            .query_map(params![webhook_id, limit], row_to_webhook_delivery)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+}
+
+fn row_to_webhook(row: &Row<'_>) -> std::result::Result<Webhook, rusqlite::Error> {
+    let secret: Option<String> = row.get(2)?;
+    let event_types: String = row.get(3)?;
+    Ok(Webhook {
+        id: row.get(0)?,
+        url: row.get(1)?,
+        has_secret: secret.is_some(),
+        secret,
+        event_types: event_types
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+            .collect(),
+        enabled: row.get::<_, i64>(4)? != 0,
+        created_at: row.get(5)?,
+    })
+}
+
+fn row_to_webhook_delivery(row: &Row<'_>) -> std::result::Result<WebhookDelivery, rusqlite::Error> {
+    Ok(WebhookDelivery {
+        id: row.get(0)?,
+        webhook_id: row.get(1)?,
+        event_type: row.get(2)?,
+        payload: row.get(3)?,
+        status: row.get(4)?,
+        attempts: row.get(5)?,
+        response_status: row.get(6)?,
+        error: row.get(7)?,
+        created_at: row.get(8)?,
+        last_attempt_at: row.get(9)?,
+    })
+}
@@ -0,0 +1,309 @@
+mod support;
+
+use chrono::FixedOffset;
+use support::{insert_events, insert_rules, make_event, setup_db, setup_home};
+use tracker_core::{PricingRuleInput, TimeRange, UsageTotals};
+use tracker_db::{Bucket, GroupBy, Metric};
+
+fn input_only(tokens: u64) -> UsageTotals {
+    UsageTotals {
+        input_tokens: tokens,
+        cached_input_tokens: 0,
+        output_tokens: 0,
+        reasoning_output_tokens: 0,
+        total_tokens: tokens,
+    }
+}
+
+#[test]
+fn timeseries_buckets_deltas_in_the_requested_offset() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    insert_rules(
+        db,
+        vec![PricingRuleInput {
+            model_pattern: "gpt-5.2".to_string(),
+            input_per_1m: 1000.0,
+            cached_input_per_1m: 0.0,
+            output_per_1m: 0.0,
+            effective_from: "2025-01-01T00:00:00Z".to_string(),
+            effective_to: None,
+            provider: None,
+            fallback: false,
+        }],
+    );
+    let mut priced = make_event(
+        "e3",
+        "2025-12-20T01:10:00Z",
+        "gpt-5.2",
+        input_only(200),
+        "source-b",
+    );
+    priced.cost_usd = Some(0.5);
+    insert_events(
+        db,
+        home.id,
+        vec![
+            make_event(
+                "e1",
+                "2025-12-19T23:30:00Z",
+                "gpt-5.2",
+                input_only(1000),
+                "source-a",
+            ),
+            make_event(
+                "e2",
+                "2025-12-20T00:30:00Z",
+                "gpt-5.2",
+                input_only(1500),
+                "source-a",
+            ),
+            priced,
+        ],
+    );
+
+    let range = TimeRange {
+        start: "2025-12-19T00:00:00Z".to_string(),
+        end: "2025-12-21T00:00:00Z".to_string(),
+    };
+    let series = |bucket, metric, offset_hours| {
+        db.timeseries(
+            &range,
+            bucket,
+            metric,
+            home.id,
+            &FixedOffset::east_opt(offset_hours * 3600).expect("offset"),
+        )
+        .expect("timeseries")
+        .into_iter()
+        .map(|point| (point.bucket_start, point.value))
+        .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        series(Bucket::Day, Metric::Tokens, 0),
+        vec![
+            ("2025-12-19T00:00:00+00:00".to_string(), 1000.0),
+            ("2025-12-20T00:00:00+00:00".to_string(), 700.0),
+        ]
+    );
+    assert_eq!(
+        series(Bucket::Day, Metric::Tokens, 1),
+        vec![("2025-12-20T00:00:00+01:00".to_string(), 1700.0)]
+    );
+    assert_eq!(
+        series(Bucket::Day, Metric::Cost, 0),
+        vec![
+            ("2025-12-19T00:00:00+00:00".to_string(), 1.0),
+            ("2025-12-20T00:00:00+00:00".to_string(), 1.0),
+        ]
+    );
+    assert_eq!(
+        series(Bucket::Hour, Metric::Tokens, -5),
+        vec![
+            ("2025-12-19T18:00:00-05:00".to_string(), 1000.0),
+            ("2025-12-19T19:00:00-05:00".to_string(), 500.0),
+            ("2025-12-19T20:00:00-05:00".to_string(), 200.0),
+        ]
+    );
+}
+
+#[test]
+fn timeseries_follows_a_time_zone_across_dst() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    // Berlin moves from +01:00 to +02:00 at 2026-03-29T01:00:00Z.
+    let event = |id: &str, ts: &str, tokens: u64| {
+        make_event(id, ts, "gpt-5.2", input_only(tokens), "source-a")
+    };
+    insert_events(
+        db,
+        home.id,
+        vec![
+            event("e1", "2026-03-28T23:30:00Z", 100),
+            event("e2", "2026-03-29T00:30:00Z", 150),
+            event("e3", "2026-03-29T01:30:00Z", 200),
+            event("e4", "2026-03-29T22:30:00Z", 300),
+        ],
+    );
+
+    let range = TimeRange {
+        start: "2026-03-28T00:00:00Z".to_string(),
+        end: "2026-03-31T00:00:00Z".to_string(),
+    };
+    let series = |bucket| {
+        db.timeseries(
+            &range,
+            bucket,
+            Metric::Tokens,
+            home.id,
+            &chrono_tz::Europe::Berlin,
+        )
+        .expect("timeseries")
+        .into_iter()
+        .map(|point| (point.bucket_start, point.value))
+        .collect::<Vec<_>>()
+    };
+
+    // At a fixed +01:00, e4 would land on the 29th.
+    assert_eq!(
+        series(Bucket::Day),
+        vec![
+            ("2026-03-29T00:00:00+01:00".to_string(), 200.0),
+            ("2026-03-30T00:00:00+02:00".to_string(), 100.0),
+        ]
+    );
+    assert_eq!(
+        series(Bucket::Hour),
+        vec![
+            ("2026-03-29T00:00:00+01:00".to_string(), 100.0),
+            ("2026-03-29T01:00:00+01:00".to_string(), 50.0),
+            ("2026-03-29T03:00:00+02:00".to_string(), 50.0),
+            ("2026-03-30T00:00:00+02:00".to_string(), 100.0),
+        ]
+    );
+}
+
+#[test]
+fn timeseries_grouped_attributes_deltas_to_each_events_effort() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    insert_rules(
+        db,
+        vec![PricingRuleInput {
+            model_pattern: "gpt-5.2".to_string(),
+            input_per_1m: 1000.0,
+            cached_input_per_1m: 0.0,
+            output_per_1m: 0.0,
+            effective_from: "2025-01-01T00:00:00Z".to_string(),
+            effective_to: None,
+            provider: None,
+            fallback: false,
+        }],
+    );
+    let with_effort = |id: &str, ts: &str, tokens: u64, source: &str, effort: Option<&str>| {
+        let mut event = make_event(id, ts, "gpt-5.2", input_only(tokens), source);
+        event.reasoning_effort = effort.map(str::to_string);
+        event
+    };
+    insert_events(
+        db,
+        home.id,
+        vec![
+            // Switching effort mid-session: only the delta counts as `high`.
+            with_effort(
+                "e1",
+                "2025-12-19T10:00:00Z",
+                1000,
+                "source-a",
+                Some("medium"),
+            ),
+            with_effort("e2", "2025-12-19T10:30:00Z", 1500, "source-a", Some("high")),
+            with_effort("e3", "2025-12-20T09:00:00Z", 200, "source-b", None),
+        ],
+    );
+
+    let range = TimeRange {
+        start: "2025-12-19T00:00:00Z".to_string(),
+        end: "2025-12-21T00:00:00Z".to_string(),
+    };
+    let series = db
+        .timeseries_grouped(
+            &range,
+            Bucket::Day,
+            Metric::Cost,
+            GroupBy::Effort,
+            home.id,
+            &FixedOffset::east_opt(0).expect("offset"),
+        )
+        .expect("grouped timeseries")
+        .into_iter()
+        .map(|series| {
+            let points = series
+                .points
+                .into_iter()
+                .map(|point| (point.bucket_start, point.value))
+                .collect::<Vec<_>>();
+            (series.group, points)
+        })
+        .collect::<Vec<_>>();
+    let day = |date: &str, value: f64| (format!("{date}T00:00:00+00:00"), value);
+    assert_eq!(
+        series,
+        vec![
+            (None, vec![day("2025-12-20", 0.2)]),
+            (Some("high".to_string()), vec![day("2025-12-19", 0.5)]),
+            (Some("medium".to_string()), vec![day("2025-12-19", 1.0)]),
+        ]
+    );
+}
+
+#[test]
+fn get_usage_event_is_scoped_to_the_home() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    let other = db
+        .get_or_create_home("/tmp/other-home", Some("Other"))
+        .expect("other home");
+    let mut event = make_event(
+        "e1",
+        "2025-12-19T10:00:00Z",
+        "gpt-5.2",
+        input_only(100),
+        "source-a",
+    );
+    event.reasoning_effort = Some("high".to_string());
+    event.raw_json = Some(r#"{"type":"token_count"}"#.to_string());
+    insert_events(db, home.id, vec![event.clone()]);
+
+    let stored = db
+        .get_usage_event(home.id, "e1")
+        .expect("get event")
+        .expect("event exists");
+    assert_eq!(stored, event);
+    assert!(db.get_usage_event(other.id, "e1").expect("get").is_none());
+    assert!(db.get_usage_event(home.id, "e2").expect("get").is_none());
+}
+
+#[test]
+fn insert_usage_events_counts_new_rows_across_batches() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    let events = |count: u32| {
+        (0..count)
+            .map(|index| {
+                let mut event = make_event(
+                    &format!("e{index}"),
+                    &format!("2025-12-19T10:{:02}:{:02}Z", index / 60, index % 60),
+                    "gpt-5.2",
+                    input_only(10),
+                    "source-a",
+                );
+                event.reasoning_effort = Some("high".to_string());
+                event.raw_json = Some(format!(r#"{{"index":{index}}}"#));
+                event
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let inserted = db
+        .insert_usage_events(home.id, &events(250))
+        .expect("insert events");
+    assert_eq!(inserted, 250);
+    let events = events(301);
+    let inserted = db
+        .insert_usage_events(home.id, &events)
+        .expect("insert events");
+    assert_eq!(inserted, 51);
+
+    let stored = db
+        .get_usage_event(home.id, "e300")
+        .expect("get event")
+        .expect("event exists");
+    assert_eq!(stored, events[300]);
+}
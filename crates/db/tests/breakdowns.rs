@@ -1,6 +1,6 @@
 mod support;
 
-use support::{insert_events, make_event, setup_db, setup_home};
+use support::{insert_events, make_event, make_message_event, setup_db, setup_home};
 use tracker_core::{TimeRange, UsageTotals};
 
 #[test]
@@ -58,6 +58,12 @@ fn breakdown_by_model_tokens_handles_resets() {
     assert_eq!(row.output_tokens, 400);
     assert_eq!(row.reasoning_output_tokens, 120);
     assert_eq!(row.total_tokens, 1400);
+    assert_eq!(row.efficiency.output_input_ratio, Some(0.4));
+    assert_eq!(row.efficiency.reasoning_output_share, Some(0.3));
+    assert_eq!(row.efficiency.cached_input_share, Some(0.25));
+
+    let summary = db.summary(&range, home.id).expect("summary");
+    assert_eq!(summary.efficiency, row.efficiency);
 }
 
 #[test]
@@ -148,3 +154,360 @@ fn list_usage_events_defaults_effort_to_low() {
     assert_eq!(events.len(), 1);
     assert_eq!(events[0].reasoning_effort.as_deref(), Some("low"));
 }
+
+#[test]
+fn usage_event_totals_sum_deltas_before_filtering_by_model() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    let usage = |total_tokens| UsageTotals {
+        input_tokens: total_tokens,
+        cached_input_tokens: 0,
+        output_tokens: 0,
+        reasoning_output_tokens: 0,
+        total_tokens,
+    };
+    insert_events(
+        db,
+        home.id,
+        vec![
+            make_event(
+                "e1",
+                "2025-12-19T19:00:00Z",
+                "gpt-5.2",
+                usage(100),
+                "source-a",
+            ),
+            make_event(
+                "e2",
+                "2025-12-19T19:05:00Z",
+                "gpt-4.1",
+                usage(150),
+                "source-a",
+            ),
+            make_event(
+                "e3",
+                "2025-12-19T19:10:00Z",
+                "gpt-5.2",
+                usage(30),
+                "source-b",
+            ),
+        ],
+    );
+
+    let range = TimeRange {
+        start: "2025-12-19T18:00:00Z".to_string(),
+        end: "2025-12-19T20:00:00Z".to_string(),
+    };
+    let totals = db
+        .usage_event_totals(&range, None, home.id)
+        .expect("totals");
+    assert_eq!((totals.total_count, totals.total_tokens), (3, 180));
+
+    let totals = db
+        .usage_event_totals(&range, Some("gpt-4.1"), home.id)
+        .expect("totals");
+    assert_eq!((totals.total_count, totals.total_tokens), (1, 50));
+}
+
+#[test]
+fn export_usage_events_returns_all_events_oldest_first() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    let usage = UsageTotals {
+        input_tokens: 10,
+        cached_input_tokens: 0,
+        output_tokens: 2,
+        reasoning_output_tokens: 0,
+        total_tokens: 12,
+    };
+    insert_events(
+        db,
+        home.id,
+        vec![
+            make_event("e2", "2025-12-19T19:30:00Z", "gpt-5.2", usage, "source-a"),
+            make_event("e1", "2025-12-19T19:00:00Z", "gpt-5.2", usage, "source-a"),
+            make_event("e3", "2025-12-20T19:00:00Z", "gpt-5.2", usage, "source-a"),
+        ],
+    );
+
+    let range = TimeRange {
+        start: "2025-12-19T00:00:00Z".to_string(),
+        end: "2025-12-20T00:00:00Z".to_string(),
+    };
+    let events = db.export_usage_events(&range, home.id).expect("events");
+    let ids: Vec<&str> = events.iter().map(|event| event.id.as_str()).collect();
+    assert_eq!(ids, vec!["e1", "e2"]);
+}
+
+#[test]
+fn usage_fingerprint_changes_with_new_events() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+
+    let empty = db.usage_fingerprint(home.id).expect("fingerprint");
+    assert_eq!(empty, db.usage_fingerprint(home.id).expect("fingerprint"));
+
+    insert_events(
+        db,
+        home.id,
+        vec![make_event(
+            "e1",
+            "2025-12-19T19:00:00Z",
+            "gpt-5.1",
+            UsageTotals {
+                input_tokens: 10,
+                total_tokens: 10,
+                ..UsageTotals::default()
+            },
+            "session-a.jsonl",
+        )],
+    );
+    let after = db.usage_fingerprint(home.id).expect("fingerprint");
+    assert_ne!(empty, after);
+}
+
+#[test]
+fn breakdown_by_session_ranks_sessions_by_tokens() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    let usage = |total_tokens| UsageTotals {
+        input_tokens: total_tokens,
+        cached_input_tokens: 0,
+        output_tokens: 0,
+        reasoning_output_tokens: 0,
+        total_tokens,
+    };
+    insert_events(
+        db,
+        home.id,
+        vec![
+            make_event(
+                "a1",
+                "2025-12-19T19:00:00Z",
+                "gpt-5.1",
+                usage(100),
+                "source-a",
+            ),
+            make_event(
+                "a2",
+                "2025-12-19T19:05:00Z",
+                "gpt-5.2",
+                usage(150),
+                "source-a",
+            ),
+            make_event(
+                "b1",
+                "2025-12-19T19:10:00Z",
+                "gpt-5.1",
+                usage(400),
+                "source-b",
+            ),
+            make_event(
+                "c1",
+                "2025-12-19T19:20:00Z",
+                "gpt-5.1",
+                usage(10),
+                "source-c",
+            ),
+        ],
+    );
+    db.insert_message_events(
+        home.id,
+        &[
+            make_message_event("m1", "2025-12-19T18:59:00Z", "source-a"),
+            make_message_event("m2", "2025-12-19T19:04:00Z", "source-a"),
+            make_message_event("m3", "2025-12-19T21:00:00Z", "source-a"),
+        ],
+    )
+    .expect("insert messages");
+
+    let range = TimeRange {
+        start: "2025-12-19T18:00:00Z".to_string(),
+        end: "2025-12-19T20:00:00Z".to_string(),
+    };
+    let sessions = db
+        .breakdown_by_session(&range, home.id, 2)
+        .expect("sessions");
+
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(sessions[0].session_id, "source-b");
+    assert_eq!(sessions[0].total_tokens, 400);
+    assert_eq!(sessions[1].session_id, "source-a");
+    assert_eq!(sessions[1].total_tokens, 150);
+    assert_eq!(sessions[1].model, "gpt-5.2");
+    assert_eq!(sessions[1].last_seen, "2025-12-19T19:05:00.000Z");
+    assert_eq!(sessions[0].message_count, 0);
+    assert_eq!(sessions[1].message_count, 2);
+}
+
+#[test]
+fn compare_ranges_lines_up_models_from_both_ranges() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    let usage = |total_tokens| UsageTotals {
+        input_tokens: total_tokens,
+        cached_input_tokens: 0,
+        output_tokens: 0,
+        reasoning_output_tokens: 0,
+        total_tokens,
+    };
+    insert_events(
+        db,
+        home.id,
+        vec![
+            make_event("e1", "2025-11-10T10:00:00Z", "gpt-5", usage(500), "a"),
+            make_event("e2", "2025-11-11T10:00:00Z", "gpt-4.1", usage(300), "b"),
+            make_event("e3", "2025-12-10T10:00:00Z", "gpt-5", usage(800), "c"),
+            make_event("e4", "2025-12-11T10:00:00Z", "gpt-5.1", usage(200), "d"),
+        ],
+    );
+
+    let range = TimeRange {
+        start: "2025-12-01T00:00:00Z".to_string(),
+        end: "2026-01-01T00:00:00Z".to_string(),
+    };
+    let baseline = TimeRange {
+        start: "2025-11-01T00:00:00Z".to_string(),
+        end: "2025-12-01T00:00:00Z".to_string(),
+    };
+    let comparison = db
+        .compare_ranges(&range, &baseline, home.id)
+        .expect("compare");
+
+    assert_eq!(comparison.current.total_tokens, 1000);
+    assert_eq!(comparison.baseline.total_tokens, 800);
+    let models = comparison
+        .models
+        .iter()
+        .map(|item| {
+            (
+                item.model.as_str(),
+                item.total_tokens,
+                item.baseline_total_tokens,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        models,
+        vec![
+            ("gpt-5", 800, 500),
+            ("gpt-5.1", 200, 0),
+            ("gpt-4.1", 0, 300)
+        ]
+    );
+}
+
+#[test]
+fn distinct_lookups_list_values_seen_in_range() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    let usage = UsageTotals {
+        input_tokens: 10,
+        cached_input_tokens: 0,
+        output_tokens: 5,
+        reasoning_output_tokens: 0,
+        total_tokens: 15,
+    };
+    let mut high = make_event("e2", "2025-12-19T19:10:00Z", "gpt-5.2", usage, "source-b");
+    high.reasoning_effort = Some("high".to_string());
+    insert_events(
+        db,
+        home.id,
+        vec![
+            make_event("e1", "2025-12-19T19:00:00Z", "gpt-5.1", usage, "source-a"),
+            high,
+            make_event("e3", "2025-12-21T19:00:00Z", "gpt-4.1", usage, "source-c"),
+        ],
+    );
+
+    let range = TimeRange {
+        start: "2025-12-19T00:00:00Z".to_string(),
+        end: "2025-12-20T00:00:00Z".to_string(),
+    };
+    assert_eq!(
+        db.distinct_models(&range, home.id).expect("models"),
+        vec!["gpt-5.1", "gpt-5.2"]
+    );
+    assert_eq!(
+        db.distinct_efforts(&range, home.id).expect("efforts"),
+        vec!["high", "low"]
+    );
+    assert_eq!(
+        db.distinct_sources(&range, home.id).expect("sources"),
+        vec!["source-a", "source-b"]
+    );
+}
+
+#[test]
+fn breakdown_by_request_sums_deltas_per_request() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    let usage = |total_tokens| UsageTotals {
+        input_tokens: total_tokens,
+        cached_input_tokens: 0,
+        output_tokens: 0,
+        reasoning_output_tokens: 0,
+        total_tokens,
+    };
+    let event = |id: &str, ts: &str, total, source: &str, request_id: Option<&str>| {
+        let mut event = make_event(id, ts, "gpt-5.1", usage(total), source);
+        event.request_id = request_id.map(str::to_string);
+        event
+    };
+    insert_events(
+        db,
+        home.id,
+        vec![
+            event("a1", "2025-12-19T19:00:00Z", 100, "source-a", Some("req-1")),
+            event("a2", "2025-12-19T19:01:00Z", 150, "source-a", Some("req-1")),
+            // Counts 5,000 tokens on top of the session's 150.
+            event(
+                "a3",
+                "2025-12-19T19:02:00Z",
+                5_150,
+                "source-a",
+                Some("req-2"),
+            ),
+            event("a4", "2025-12-19T19:03:00Z", 5_200, "source-a", None),
+            event("b1", "2025-12-19T19:04:00Z", 300, "source-b", Some("req-3")),
+        ],
+    );
+
+    let range = TimeRange {
+        start: "2025-12-19T18:00:00Z".to_string(),
+        end: "2025-12-19T20:00:00Z".to_string(),
+    };
+    let requests = db
+        .breakdown_by_request(&range, home.id, 10)
+        .expect("requests");
+
+    let summary = requests
+        .iter()
+        .map(|request| {
+            (
+                request.request_id.as_str(),
+                request.usage.total_tokens,
+                request.event_count,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![("req-2", 5_000, 1), ("req-3", 300, 1), ("req-1", 150, 2)]
+    );
+    assert_eq!(requests[0].session_id, "source-a");
+    assert_eq!(requests[2].ts, "2025-12-19T19:00:00.000Z");
+
+    let top = db
+        .breakdown_by_request(&range, home.id, 1)
+        .expect("top request");
+    assert_eq!(top.len(), 1);
+    assert_eq!(top[0].request_id, "req-2");
+}
@@ -90,6 +90,161 @@ fn context_pressure_stats_averages_known_context_only() {
     assert!((stats.avg_pressure_pct.unwrap() - 50.0).abs() < 1e-6);
 }
 
+#[test]
+fn context_pressure_stats_reports_percentiles_and_high_pressure() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    // Pressures of 10%, 20%, ..., 100%, inserted out of order.
+    let events = [7, 2, 10, 5, 1, 9, 4, 8, 3, 6]
+        .into_iter()
+        .map(|tenth: u64| {
+            let mut event = make_event(
+                &format!("e{tenth}"),
+                &format!("2025-12-19T10:{tenth:02}:00Z"),
+                "gpt-5.2",
+                UsageTotals {
+                    input_tokens: 10,
+                    cached_input_tokens: 0,
+                    output_tokens: 10,
+                    reasoning_output_tokens: 0,
+                    total_tokens: 20,
+                },
+                "source-a",
+            );
+            event.context = ContextStatus {
+                context_used: tenth * 100,
+                context_window: 1000,
+            };
+            event
+        })
+        .collect();
+    insert_events(db, home.id, events);
+
+    let range = TimeRange {
+        start: "2025-12-19T09:00:00Z".to_string(),
+        end: "2025-12-19T12:00:00Z".to_string(),
+    };
+    let stats = db.context_pressure_stats(&range, home.id).expect("stats");
+
+    assert_eq!(stats.sample_count, 10);
+    assert!((stats.p50_pressure_pct.unwrap() - 50.0).abs() < 1e-6);
+    assert!((stats.p90_pressure_pct.unwrap() - 90.0).abs() < 1e-6);
+    assert!((stats.p99_pressure_pct.unwrap() - 100.0).abs() < 1e-6);
+    assert_eq!(stats.high_pressure_count, 2);
+
+    let empty = TimeRange {
+        start: "2025-12-20T00:00:00Z".to_string(),
+        end: "2025-12-21T00:00:00Z".to_string(),
+    };
+    let stats = db.context_pressure_stats(&empty, home.id).expect("stats");
+    assert_eq!(stats.sample_count, 0);
+    assert_eq!(stats.p50_pressure_pct, None);
+    assert_eq!(stats.high_pressure_count, 0);
+}
+
+#[test]
+fn session_context_peaks_lists_sessions_over_threshold() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    let event = |id: &str, ts: &str, source: &str, context_used: u64| {
+        let mut event = make_event(
+            id,
+            ts,
+            "gpt-5.2",
+            UsageTotals {
+                input_tokens: 10,
+                cached_input_tokens: 0,
+                output_tokens: 10,
+                reasoning_output_tokens: 0,
+                total_tokens: 20,
+            },
+            source,
+        );
+        event.context = ContextStatus {
+            context_used,
+            context_window: 1000,
+        };
+        event
+    };
+    insert_events(
+        db,
+        home.id,
+        vec![
+            event(
+                "a1",
+                "2025-12-19T10:00:00Z",
+                "/tmp/rollout-2025-12-19T10-00-00Z-a.jsonl",
+                400,
+            ),
+            event(
+                "a2",
+                "2025-12-19T10:05:00Z",
+                "/tmp/rollout-2025-12-19T10-00-00Z-a.jsonl",
+                850,
+            ),
+            event(
+                "a3",
+                "2025-12-19T10:10:00Z",
+                "/tmp/rollout-2025-12-19T10-00-00Z-a.jsonl",
+                300,
+            ),
+            event(
+                "b1",
+                "2025-12-19T11:00:00Z",
+                "/tmp/rollout-2025-12-19T11-00-00Z-b.jsonl",
+                950,
+            ),
+            event(
+                "b2",
+                "2025-12-19T11:05:00Z",
+                "/tmp/rollout-2025-12-19T11-00-00Z-b.jsonl",
+                950,
+            ),
+            event(
+                "c1",
+                "2025-12-19T12:00:00Z",
+                "/tmp/rollout-2025-12-19T12-00-00Z-c.jsonl",
+                500,
+            ),
+        ],
+    );
+
+    let range = TimeRange {
+        start: "2025-12-19T09:00:00Z".to_string(),
+        end: "2025-12-19T13:00:00Z".to_string(),
+    };
+    let peaks = db
+        .session_context_peaks(&range, home.id, 80.0, 10)
+        .expect("peaks");
+    let summary = peaks
+        .iter()
+        .map(|peak| {
+            (
+                peak.session_id.as_str(),
+                peak.peak_context_used,
+                peak.peak_at.as_str(),
+                peak.event_count,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![
+            ("b", 950, "2025-12-19T11:00:00.000Z", 2),
+            ("a", 850, "2025-12-19T10:05:00.000Z", 3),
+        ]
+    );
+    assert!((peaks[0].peak_pressure_pct - 95.0).abs() < 1e-6);
+
+    let top = db
+        .session_context_peaks(&range, home.id, 0.0, 1)
+        .expect("peaks");
+    assert_eq!(top.len(), 1);
+    assert_eq!(top[0].session_id, "b");
+}
+
 #[test]
 fn active_sessions_returns_latest_per_session() {
     let mut test_db = setup_db();
@@ -149,6 +304,16 @@ fn active_sessions_returns_latest_per_session() {
         .iter()
         .find(|session| session.session_id == "sessiona")
         .expect("session a");
-    assert_eq!(session_a.last_seen, "2025-12-19T19:05:00Z");
-    assert_eq!(session_a.session_start, "2025-12-19T19:00:00Z");
+    assert_eq!(session_a.last_seen, "2025-12-19T19:05:00.000Z");
+    assert_eq!(session_a.session_start, "2025-12-19T19:00:00.000Z");
+    assert_eq!(session_a.window_tokens, 15);
+
+    let recent = db
+        .active_sessions(home.id, "2025-12-19T19:02:00Z")
+        .expect("sessions");
+    let window_tokens = recent
+        .iter()
+        .map(|session| (session.session_id.as_str(), session.window_tokens))
+        .collect::<Vec<_>>();
+    assert_eq!(window_tokens, vec![("sessiona", 3), ("sessionb", 6)]);
 }
@@ -16,3 +16,18 @@ fn set_active_home_returns_expected_home() {
     assert_eq!(active.path, "/tmp/codex-secondary");
     assert_eq!(active.label, "Secondary");
 }
+
+#[test]
+fn rename_home_updates_label() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = db
+        .add_home("/tmp/codex-rename", Some("Old"))
+        .expect("add home");
+
+    db.rename_home(home.id, "Work laptop").expect("rename");
+
+    let renamed = db.get_home_by_id(home.id).expect("get").expect("home");
+    assert_eq!(renamed.label, "Work laptop");
+    assert_eq!(renamed.path, "/tmp/codex-rename");
+}
@@ -3,9 +3,10 @@ mod support;
 use chrono::{Duration, SecondsFormat, Utc};
 use rusqlite::Connection;
 use support::{
-    insert_events, make_event, make_limit_snapshot, make_message_event, setup_db, setup_home,
+    insert_events, insert_rules, make_event, make_limit_snapshot, make_message_event, setup_db,
+    setup_home,
 };
-use tracker_core::UsageTotals;
+use tracker_core::{PricingRuleInput, TimeRange, UsageTotals};
 
 #[test]
 fn insert_limit_snapshots_dedupes_by_percent_and_reset() {
@@ -50,6 +51,68 @@ fn insert_limit_snapshots_dedupes_by_percent_and_reset() {
     assert_eq!(count, 2);
 }
 
+#[test]
+fn insert_limit_snapshots_dedupes_against_latest_stored_per_type() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    let first = vec![
+        make_limit_snapshot(
+            "5h",
+            40.0,
+            "2025-01-01T05:00:00Z",
+            "2025-01-01T00:00:00Z",
+            "source-a",
+        ),
+        make_limit_snapshot(
+            "5h",
+            35.0,
+            "2025-01-01T05:00:00Z",
+            "2025-01-01T00:10:00Z",
+            "source-a",
+        ),
+        make_limit_snapshot(
+            "7d",
+            80.0,
+            "2025-01-07T00:00:00Z",
+            "2025-01-01T00:10:00Z",
+            "source-a",
+        ),
+    ];
+    db.insert_limit_snapshots(home.id, &first)
+        .expect("insert limits");
+
+    let second = vec![
+        // Matches the latest 5h and 7d snapshots, so both are dropped.
+        make_limit_snapshot(
+            "5h",
+            35.0,
+            "2025-01-01T05:00:00Z",
+            "2025-01-01T00:20:00Z",
+            "source-b",
+        ),
+        make_limit_snapshot(
+            "7d",
+            80.0,
+            "2025-01-07T00:00:00Z",
+            "2025-01-01T00:20:00Z",
+            "source-b",
+        ),
+        // Matches an older 5h snapshot only.
+        make_limit_snapshot(
+            "5h",
+            40.0,
+            "2025-01-01T05:00:00Z",
+            "2025-01-01T00:30:00Z",
+            "source-b",
+        ),
+    ];
+    let inserted = db
+        .insert_limit_snapshots(home.id, &second)
+        .expect("insert limits");
+    assert_eq!(inserted, 1);
+}
+
 #[test]
 fn limit_current_window_ignores_stale_snapshot() {
     let mut test_db = setup_db();
@@ -154,3 +217,218 @@ fn limit_windows_7d_uses_reset_boundaries() {
     assert_eq!(windows[1].total_tokens, Some(120));
     assert_eq!(windows[1].message_count, Some(2));
 }
+
+#[test]
+fn limit_windows_7d_fill_missed_resets() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    insert_events(
+        db,
+        home.id,
+        vec![make_event(
+            "e1",
+            "2025-01-17T00:00:00Z",
+            "gpt-5.2",
+            UsageTotals {
+                input_tokens: 50,
+                cached_input_tokens: 0,
+                output_tokens: 10,
+                reasoning_output_tokens: 0,
+                total_tokens: 60,
+            },
+            "source-a",
+        )],
+    );
+    // Nothing was logged across the resets on the 15th and the 22nd.
+    let snapshots = vec![
+        make_limit_snapshot(
+            "7d",
+            70.0,
+            "2025-01-08T00:00:00Z",
+            "2025-01-07T12:00:00Z",
+            "source-a",
+        ),
+        make_limit_snapshot(
+            "7d",
+            55.0,
+            "2025-01-29T00:00:00Z",
+            "2025-01-23T12:00:00Z",
+            "source-a",
+        ),
+    ];
+    db.insert_limit_snapshots(home.id, &snapshots)
+        .expect("insert limits");
+
+    let windows = db.limit_windows_7d(home.id, 0).expect("windows");
+    let summary = windows
+        .iter()
+        .map(|window| {
+            (
+                window.window_end.as_str(),
+                window.interpolated,
+                window.total_tokens,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![
+            ("2025-01-08T00:00:00.000Z", false, Some(0)),
+            ("2025-01-15T00:00:00.000Z", true, Some(0)),
+            ("2025-01-22T00:00:00.000Z", true, Some(60)),
+            ("2025-01-29T00:00:00.000Z", false, Some(0)),
+        ]
+    );
+    assert_eq!(
+        windows[3].window_start.as_deref(),
+        Some("2025-01-22T00:00:00.000Z")
+    );
+}
+
+#[test]
+fn limit_windows_7d_match_per_window_summaries() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    insert_rules(
+        db,
+        vec![PricingRuleInput {
+            model_pattern: "gpt-5.2".to_string(),
+            input_per_1m: 1000.0,
+            cached_input_per_1m: 100.0,
+            output_per_1m: 8000.0,
+            effective_from: "2025-01-01T00:00:00Z".to_string(),
+            effective_to: None,
+            provider: None,
+            fallback: false,
+        }],
+    );
+    let usage = |total: u64| UsageTotals {
+        input_tokens: total / 2,
+        cached_input_tokens: total / 4,
+        output_tokens: total / 2,
+        reasoning_output_tokens: 0,
+        total_tokens: total,
+    };
+    // One session spans the first reset, so its deltas restart there.
+    insert_events(
+        db,
+        home.id,
+        vec![
+            make_event("e1", "2025-01-06T00:00:00Z", "gpt-5.2", usage(400), "a"),
+            make_event("e2", "2025-01-09T00:00:00Z", "gpt-5.2", usage(1000), "a"),
+            make_event("e3", "2025-01-16T00:00:00Z", "gpt-5.2", usage(1600), "a"),
+            make_event("e4", "2025-01-10T00:00:00Z", "unpriced", usage(80), "b"),
+        ],
+    );
+    db.insert_message_events(
+        home.id,
+        &[
+            make_message_event("m1", "2025-01-06T02:00:00Z", "a"),
+            make_message_event("m2", "2025-01-09T02:00:00Z", "a"),
+            make_message_event("m3", "2025-01-10T02:00:00Z", "b"),
+        ],
+    )
+    .expect("insert messages");
+    let snapshots = [
+        "2025-01-08T00:00:00Z",
+        "2025-01-15T00:00:00Z",
+        "2025-01-22T00:00:00Z",
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(index, reset_at)| {
+        make_limit_snapshot(
+            "7d",
+            90.0 - index as f64,
+            reset_at,
+            "2025-01-07T12:00:00Z",
+            "source-a",
+        )
+    })
+    .collect::<Vec<_>>();
+    db.insert_limit_snapshots(home.id, &snapshots)
+        .expect("insert limits");
+
+    let windows = db.limit_windows_7d(home.id, 0).expect("windows");
+    assert_eq!(windows.len(), 3);
+    for window in &windows {
+        let range = TimeRange {
+            start: window.window_start.clone().expect("start"),
+            end: window.window_end.clone(),
+        };
+        let summary = db.summary(&range, home.id).expect("summary");
+        assert_eq!(window.total_tokens, Some(summary.total_tokens));
+        let cost = window.total_cost_usd.expect("cost");
+        assert!((cost - summary.total_cost_usd.expect("summary cost")).abs() < 1e-9);
+    }
+    assert_eq!(windows[1].total_tokens, Some(1080));
+    assert_eq!(windows[1].message_count, Some(2));
+
+    let latest = db.limit_windows_7d(home.id, 1).expect("windows");
+    assert_eq!(latest.len(), 1);
+    assert_eq!(latest[0].window_end, "2025-01-22T00:00:00.000Z");
+    assert_eq!(latest[0].total_tokens, Some(1600));
+    assert_eq!(latest[0].message_count, Some(0));
+}
+
+#[test]
+fn list_limit_snapshots_filters_and_paginates() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    let snapshots = vec![
+        make_limit_snapshot(
+            "5h",
+            90.0,
+            "2025-01-01T05:00:00Z",
+            "2025-01-01T00:00:00Z",
+            "source-a",
+        ),
+        make_limit_snapshot(
+            "7d",
+            80.0,
+            "2025-01-07T00:00:00Z",
+            "2025-01-01T01:00:00Z",
+            "source-a",
+        ),
+        make_limit_snapshot(
+            "7d",
+            70.0,
+            "2025-01-07T00:00:00Z",
+            "2025-01-01T02:00:00Z",
+            "source-a",
+        ),
+        make_limit_snapshot(
+            "7d",
+            60.0,
+            "2025-01-07T00:00:00Z",
+            "2025-01-01T03:00:00Z",
+            "source-a",
+        ),
+    ];
+    db.insert_limit_snapshots(home.id, &snapshots)
+        .expect("insert limits");
+    let range = TimeRange {
+        start: "2025-01-01T00:00:00.000Z".to_string(),
+        end: "2025-01-02T00:00:00.000Z".to_string(),
+    };
+
+    let all = db
+        .list_limit_snapshots(home.id, &range, None, 10, 0)
+        .expect("all snapshots");
+    assert_eq!(all.len(), 4);
+
+    let page = db
+        .list_limit_snapshots(home.id, &range, Some("7d"), 2, 0)
+        .expect("first page");
+    let percents: Vec<f64> = page.iter().map(|row| row.percent_left).collect();
+    assert_eq!(percents, vec![60.0, 70.0]);
+
+    let next = db
+        .list_limit_snapshots(home.id, &range, Some("7d"), 2, 2)
+        .expect("second page");
+    assert_eq!(next.len(), 1);
+    assert_eq!(next[0].percent_left, 80.0);
+}
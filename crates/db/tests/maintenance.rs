@@ -0,0 +1,102 @@
+mod support;
+
+use support::{
+    insert_events, make_event, make_limit_snapshot, make_message_event, setup_db, setup_home,
+};
+use tracker_core::{HomeScope, UsageTotals};
+use tracker_db::PruneStats;
+
+#[test]
+fn prune_before_keeps_sessions_that_span_the_cutoff() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    let usage = |total_tokens| UsageTotals {
+        input_tokens: total_tokens,
+        total_tokens,
+        ..UsageTotals::default()
+    };
+    insert_events(
+        db,
+        home.id,
+        vec![
+            make_event(
+                "old-1",
+                "2024-12-01T10:00:00Z",
+                "gpt-5.1",
+                usage(10),
+                "old.jsonl",
+            ),
+            make_event(
+                "old-2",
+                "2024-12-01T11:00:00Z",
+                "gpt-5.1",
+                usage(20),
+                "old.jsonl",
+            ),
+            make_event(
+                "span-1",
+                "2024-12-31T23:00:00Z",
+                "gpt-5.1",
+                usage(10),
+                "span.jsonl",
+            ),
+            make_event(
+                "span-2",
+                "2025-01-01T01:00:00Z",
+                "gpt-5.1",
+                usage(30),
+                "span.jsonl",
+            ),
+        ],
+    );
+    db.insert_message_events(
+        home.id,
+        &[
+            make_message_event("m1", "2024-12-01T10:00:00Z", "old.jsonl"),
+            make_message_event("m2", "2024-12-31T23:00:00Z", "span.jsonl"),
+            make_message_event("m3", "2025-01-01T01:00:00Z", "span.jsonl"),
+        ],
+    )
+    .expect("messages");
+    db.insert_limit_snapshots(
+        home.id,
+        &[
+            make_limit_snapshot(
+                "5h",
+                80.0,
+                "2024-12-01T15:00:00Z",
+                "2024-12-01T10:00:00Z",
+                "old.jsonl",
+            ),
+            make_limit_snapshot(
+                "5h",
+                60.0,
+                "2025-01-01T05:00:00Z",
+                "2025-01-01T01:00:00Z",
+                "span.jsonl",
+            ),
+        ],
+    )
+    .expect("snapshots");
+
+    let stats = db
+        .prune_before("2025-01-01T00:00:00.000Z", HomeScope::All)
+        .expect("prune");
+    assert_eq!(
+        stats,
+        PruneStats {
+            usage_events: 2,
+            message_events: 1,
+            limit_snapshots: 1,
+        }
+    );
+    assert_eq!(db.count_usage_events(home.id).expect("count"), 2);
+    assert_eq!(db.count_message_events(home.id).expect("count"), 2);
+
+    db.compact().expect("compact");
+    let again = db
+        .prune_before("2025-01-01T00:00:00.000Z", HomeScope::Home(home.id))
+        .expect("prune again");
+    assert_eq!(again, PruneStats::default());
+}
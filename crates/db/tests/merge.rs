@@ -0,0 +1,187 @@
+mod support;
+
+use support::{
+    insert_events, make_event, make_limit_snapshot, make_message_event, setup_db, setup_home,
+};
+use tracker_core::{HomeScope, TimeRange, UsageTotals};
+use tracker_db::MergeStats;
+
+#[test]
+fn merge_from_remaps_homes_and_skips_duplicates() {
+    let usage = |total_tokens| UsageTotals {
+        input_tokens: total_tokens,
+        total_tokens,
+        ..UsageTotals::default()
+    };
+
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    insert_events(
+        db,
+        home.id,
+        vec![make_event(
+            "shared",
+            "2025-01-01T10:00:00Z",
+            "gpt-5.1",
+            usage(10),
+            "a.jsonl",
+        )],
+    );
+
+    // The other database creates its homes in a different order, so its
+    // laptop home has the id our shared home has here.
+    let mut other_db = setup_db();
+    let other = &mut other_db.db;
+    let laptop = other
+        .add_home("/tmp/laptop-codex", Some("Laptop"))
+        .expect("laptop home");
+    let same = setup_home(other);
+    assert_eq!(laptop.id, home.id);
+    insert_events(
+        other,
+        same.id,
+        vec![
+            make_event(
+                "shared",
+                "2025-01-01T10:00:00Z",
+                "gpt-5.1",
+                usage(10),
+                "a.jsonl",
+            ),
+            make_event(
+                "new-same",
+                "2025-01-02T10:00:00Z",
+                "gpt-5.1",
+                usage(20),
+                "b.jsonl",
+            ),
+        ],
+    );
+    insert_events(
+        other,
+        laptop.id,
+        vec![make_event(
+            "new-laptop",
+            "2025-01-03T10:00:00Z",
+            "gpt-5.1",
+            usage(30),
+            "c.jsonl",
+        )],
+    );
+    other
+        .insert_message_events(
+            laptop.id,
+            &[make_message_event("m1", "2025-01-03T10:00:00Z", "c.jsonl")],
+        )
+        .expect("messages");
+    other
+        .insert_limit_snapshots(
+            laptop.id,
+            &[make_limit_snapshot(
+                "5h",
+                40.0,
+                "2025-01-03T15:00:00Z",
+                "2025-01-03T10:00:00Z",
+                "c.jsonl",
+            )],
+        )
+        .expect("snapshots");
+    drop(other_db.db);
+
+    let stats = db.merge_from(&other_db.path, None).expect("merge");
+    assert_eq!(
+        stats,
+        MergeStats {
+            homes_added: 1,
+            usage_events: 2,
+            message_events: 1,
+            limit_snapshots: 1,
+        }
+    );
+    let merged_laptop = db
+        .get_home_by_path("/tmp/laptop-codex")
+        .expect("lookup")
+        .expect("laptop added");
+    assert_eq!(merged_laptop.label, "Laptop");
+    assert_eq!(db.count_usage_events(home.id).expect("count"), 2);
+    assert_eq!(db.count_usage_events(merged_laptop.id).expect("count"), 1);
+    assert_eq!(db.count_message_events(merged_laptop.id).expect("count"), 1);
+
+    let again = db.merge_from(&other_db.path, None).expect("merge again");
+    assert_eq!(again, MergeStats::default());
+}
+
+#[test]
+fn merge_from_with_user_keeps_teammates_apart() {
+    let usage = |total_tokens| UsageTotals {
+        input_tokens: total_tokens,
+        total_tokens,
+        ..UsageTotals::default()
+    };
+
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    insert_events(
+        db,
+        home.id,
+        vec![make_event(
+            "lead",
+            "2025-01-01T10:00:00Z",
+            "gpt-5.1",
+            usage(5),
+            "/tmp/codex-home/lead.jsonl",
+        )],
+    );
+
+    // Both teammates keep their logs at the lead's home path.
+    for (user, total_tokens) in [("alice", 10), ("bob", 30)] {
+        let mut other_db = setup_db();
+        let other = &mut other_db.db;
+        let teammate = setup_home(other);
+        insert_events(
+            other,
+            teammate.id,
+            vec![make_event(
+                user,
+                "2025-01-01T11:00:00Z",
+                "gpt-5.1",
+                usage(total_tokens),
+                &format!("/tmp/codex-home/{user}.jsonl"),
+            )],
+        );
+        drop(other_db.db);
+        let stats = db.merge_from(&other_db.path, Some(user)).expect("merge");
+        assert_eq!(stats.usage_events, 1);
+        let again = db
+            .merge_from(&other_db.path, Some(user))
+            .expect("merge again");
+        assert_eq!(again, MergeStats::default());
+    }
+
+    let alice = db
+        .get_home_by_path("alice:/tmp/codex-home")
+        .expect("lookup")
+        .expect("alice's home");
+    assert_eq!(alice.user.as_deref(), Some("alice"));
+
+    let range = TimeRange {
+        start: "2025-01-01T00:00:00Z".to_string(),
+        end: "2025-01-02T00:00:00Z".to_string(),
+    };
+    let users = db
+        .breakdown_by_user(&range, HomeScope::All)
+        .expect("users")
+        .into_iter()
+        .map(|item| (item.user, item.total_tokens))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        users,
+        vec![
+            (Some("bob".to_string()), 30),
+            (Some("alice".to_string()), 10),
+            (None, 5),
+        ]
+    );
+}
@@ -87,4 +87,198 @@ fn migrate_backfills_codex_home() {
         )
         .expect("session id");
     assert_eq!(session_id, "source-a");
+
+    let span: (String, String, Option<String>) = conn
+        .query_row(
+            "SELECT started_at, last_event_at, ended_at FROM session WHERE session_id = 'source-a'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .expect("session span");
+    assert_eq!(
+        span,
+        (
+            "2025-12-19T19:00:00.000Z".to_string(),
+            "2025-12-19T19:00:00.000Z".to_string(),
+            None
+        )
+    );
+
+    // A shutdown at the backfilled last event still ends the session.
+    db.record_session_ends(
+        home_id,
+        &[(
+            "source-a".to_string(),
+            "2025-12-19T19:00:00Z".parse().expect("timestamp"),
+        )],
+    )
+    .expect("record end");
+    let ended_at: Option<String> = conn
+        .query_row(
+            "SELECT ended_at FROM session WHERE session_id = 'source-a'",
+            [],
+            |row| row.get(0),
+        )
+        .expect("session end");
+    assert_eq!(ended_at.as_deref(), Some("2025-12-19T19:00:00.000Z"));
+
+    // A database an earlier backfill filled has no marker yet; its spans
+    // are rewritten on the next migrate.
+    conn.execute(
+        "DELETE FROM app_setting WHERE key = 'session_times_normalized'",
+        [],
+    )
+    .expect("clear marker");
+    conn.execute(
+        r#"
+        INSERT INTO session (codex_home_id, session_id, started_at, last_event_at)
+        VALUES (?1, 'source-b', '2025-12-19T21:00:05+02:00', '2025-12-19T19:10:00Z')
+        "#,
+        [home_id],
+    )
+    .expect("insert session");
+    db.migrate().expect("migrate again");
+    let span: (String, String) = conn
+        .query_row(
+            "SELECT started_at, last_event_at FROM session WHERE session_id = 'source-b'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .expect("session span");
+    assert_eq!(
+        span,
+        (
+            "2025-12-19T19:00:05.000Z".to_string(),
+            "2025-12-19T19:10:00.000Z".to_string()
+        )
+    );
+}
+
+#[test]
+fn schema_level_counts_applied_migrations() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let db_path = dir.path().join("level.sqlite");
+    {
+        let conn = Connection::open(&db_path).expect("open conn");
+        conn.execute_batch(include_str!("../migrations/0001_init.sql"))
+            .expect("migrate 0001");
+    }
+
+    let mut db = tracker_db::Db::open(&db_path).expect("open db");
+    // 0001 already ships the cached-input column that 0002 adds.
+    assert_eq!(db.schema_level().expect("level"), 2);
+    db.migrate().expect("migrate db");
+    assert_eq!(
+        db.schema_level().expect("level"),
+        tracker_db::SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn migrate_compresses_stored_raw_json() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let db_path = dir.path().join("raw.sqlite");
+    let raw_json = format!(r#"{{"type":"token_count","pad":"{}"}}"#, "x".repeat(4096));
+    {
+        let conn = Connection::open(&db_path).expect("open conn");
+        conn.execute_batch(include_str!("../migrations/0001_init.sql"))
+            .expect("migrate 0001");
+        conn.execute(
+            r#"
+            INSERT INTO usage_event (
+              id, ts, model, input_tokens, cached_input_tokens, output_tokens,
+              reasoning_output_tokens, total_tokens, context_used, context_window,
+              cost_usd, source, request_id, raw_json
+            ) VALUES (
+              'e1', '2025-12-19T19:00:00Z', 'gpt-5.2', 10, 0, 2, 0, 12, 12, 100, NULL, 'source-a', NULL, ?1
+            )
+            "#,
+            [&raw_json],
+        )
+        .expect("insert usage event");
+    }
+
+    let mut db = tracker_db::Db::open(&db_path).expect("open db");
+    db.migrate().expect("migrate db");
+    let home_id = db
+        .get_active_home()
+        .expect("load home")
+        .expect("active home")
+        .id;
+    let event = db
+        .get_usage_event(home_id, "e1")
+        .expect("load event")
+        .expect("event exists");
+    assert_eq!(event.raw_json.as_deref(), Some(raw_json.as_str()));
+
+    let conn = Connection::open(&db_path).expect("open conn");
+    let (text, compressed): (Option<String>, Vec<u8>) = conn
+        .query_row(
+            "SELECT raw_json, raw_json_z FROM usage_event WHERE id = 'e1'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .expect("stored raw json");
+    assert!(text.is_none());
+    assert!(compressed.len() < raw_json.len() / 10);
+}
+
+#[test]
+fn migrate_backfills_ts_ms_for_range_queries() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let db_path = dir.path().join("ts_ms.sqlite");
+    {
+        let conn = Connection::open(&db_path).expect("open conn");
+        conn.execute_batch(include_str!("../migrations/0001_init.sql"))
+            .expect("migrate 0001");
+        for (id, ts, total) in [
+            ("e1", "2025-12-19T19:00:00Z", 12),
+            ("e2", "2025-12-19T19:00:00.250Z", 20),
+        ] {
+            conn.execute(
+                r#"
+                INSERT INTO usage_event (
+                  id, ts, model, input_tokens, cached_input_tokens, output_tokens,
+                  reasoning_output_tokens, total_tokens, context_used, context_window,
+                  cost_usd, source, request_id, raw_json
+                ) VALUES (?1, ?2, 'gpt-5.2', ?3, 0, 0, 0, ?3, 0, 0, NULL, 'source-a', NULL, NULL)
+                "#,
+                rusqlite::params![id, ts, total],
+            )
+            .expect("insert usage event");
+        }
+    }
+
+    let mut db = tracker_db::Db::open(&db_path).expect("open db");
+    db.migrate().expect("migrate db");
+
+    let conn = Connection::open(&db_path).expect("open conn");
+    let stored = conn
+        .prepare("SELECT id, ts_ms FROM usage_event ORDER BY id")
+        .expect("prepare")
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })
+        .expect("query")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("rows");
+    assert_eq!(
+        stored,
+        vec![
+            ("e1".to_string(), 1_766_170_800_000),
+            ("e2".to_string(), 1_766_170_800_250),
+        ]
+    );
+
+    let home_id = db
+        .get_active_home()
+        .expect("load home")
+        .expect("active home")
+        .id;
+    let range = tracker_core::TimeRange {
+        start: "2025-12-19T19:00:00Z".to_string(),
+        end: "2025-12-19T19:00:00.250Z".to_string(),
+    };
+    let summary = db.summary(&range, home_id).expect("summary");
+    assert_eq!(summary.total_tokens, 12);
 }
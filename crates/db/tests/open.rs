@@ -0,0 +1,74 @@
+mod support;
+
+use support::{insert_events, make_event, setup_db, setup_home};
+use tracker_core::{HomeScope, TimeRange, UsageTotals};
+use tracker_db::{Db, DbError, DbOptions};
+
+#[test]
+fn read_only_connection_reads_but_rejects_writes() {
+    let mut test_db = setup_db();
+    let home = setup_home(&mut test_db.db);
+
+    let mut reader = Db::open_read_only(&test_db.path).expect("open read only");
+    let active = reader
+        .get_active_home()
+        .expect("active home")
+        .expect("home");
+    assert_eq!(active.id, home.id);
+    assert!(reader.rename_home(home.id, "Renamed").is_err());
+    assert!(reader.ensure_active_home().is_ok());
+
+    let tuned = DbOptions {
+        busy_timeout_ms: 100,
+        mmap_size: 1 << 20,
+        cache_size: 500,
+        wal_autocheckpoint: 0,
+    };
+    let writer = Db::open_with(&test_db.path, &tuned).expect("open tuned");
+    writer.rename_home(home.id, "Renamed").expect("rename");
+    let renamed = reader
+        .get_active_home()
+        .expect("active home")
+        .expect("home");
+    assert_eq!(renamed.label, "Renamed");
+}
+
+#[test]
+fn snapshot_reads_ignore_commits_made_meanwhile() {
+    let mut test_db = setup_db();
+    let home = setup_home(&mut test_db.db);
+    let usage = UsageTotals {
+        input_tokens: 10,
+        cached_input_tokens: 0,
+        output_tokens: 0,
+        reasoning_output_tokens: 0,
+        total_tokens: 10,
+    };
+    let event = |id: &str, ts: &str| make_event(id, ts, "gpt-5.2", usage, id);
+    insert_events(
+        &mut test_db.db,
+        home.id,
+        vec![event("e1", "2025-12-19T10:00:00Z")],
+    );
+    let range = TimeRange {
+        start: "2025-12-19T00:00:00Z".to_string(),
+        end: "2025-12-20T00:00:00Z".to_string(),
+    };
+    let scope = HomeScope::Home(home.id);
+
+    let mut reader = Db::open_read_only(&test_db.path).expect("open read only");
+    let (before, after) = reader
+        .with_snapshot(|db| {
+            let before = db.summary(&range, scope)?.total_tokens;
+            insert_events(
+                &mut test_db.db,
+                home.id,
+                vec![event("e2", "2025-12-19T11:00:00Z")],
+            );
+            Ok::<_, DbError>((before, db.summary(&range, scope)?.total_tokens))
+        })
+        .expect("snapshot");
+    assert_eq!((before, after), (10, 10));
+    let latest = reader.summary(&range, scope).expect("summary");
+    assert_eq!(latest.total_tokens, 20);
+}
@@ -1,7 +1,10 @@
 mod support;
 
 use support::{insert_events, insert_rules, make_event, setup_db, setup_home};
-use tracker_core::{PricingRuleInput, TimeRange, UsageTotals};
+use tracker_core::{
+    CostRecomputeProgress, CostSource, ModelAlias, PricingRuleInput, TimeRange, UsageEvent,
+    UsageTotals,
+};
 
 #[test]
 fn breakdown_by_model_costs_uses_output_only() {
@@ -17,6 +20,8 @@ fn breakdown_by_model_costs_uses_output_only() {
             output_per_1m: 14000.0,
             effective_from: "2025-01-01T00:00:00Z".to_string(),
             effective_to: None,
+            provider: None,
+            fallback: false,
         }],
     );
     insert_events(
@@ -172,6 +177,8 @@ fn update_event_costs_sets_value_with_pricing() {
             output_per_1m: 14000.0,
             effective_from: "2025-01-01T00:00:00Z".to_string(),
             effective_to: None,
+            provider: None,
+            fallback: false,
         }],
     );
     insert_events(
@@ -208,3 +215,386 @@ fn update_event_costs_sets_value_with_pricing() {
     let expected_total = expected_input + expected_cached + expected_output;
     assert!((cost - expected_total).abs() < 1e-9);
 }
+
+#[test]
+fn unpriced_models_lists_events_without_cost() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    insert_rules(
+        db,
+        vec![PricingRuleInput {
+            model_pattern: "gpt-5.2".to_string(),
+            input_per_1m: 1.75,
+            cached_input_per_1m: 0.175,
+            output_per_1m: 14.0,
+            effective_from: "2025-01-01T00:00:00Z".to_string(),
+            effective_to: None,
+            provider: None,
+            fallback: false,
+        }],
+    );
+    let usage = UsageTotals {
+        input_tokens: 100,
+        cached_input_tokens: 0,
+        output_tokens: 50,
+        reasoning_output_tokens: 0,
+        total_tokens: 150,
+    };
+    insert_events(
+        db,
+        home.id,
+        vec![
+            make_event("e1", "2025-12-19T19:00:00Z", "gpt-5.2", usage, "source-a"),
+            make_event("e2", "2025-12-19T19:05:00Z", "mystery", usage, "source-b"),
+            make_event("e3", "2025-12-19T19:10:00Z", "mystery", usage, "source-b"),
+        ],
+    );
+    db.update_event_costs(home.id).expect("update costs");
+
+    let unpriced = db.unpriced_models(home.id).expect("unpriced");
+    assert_eq!(unpriced, vec![("mystery".to_string(), 2)]);
+}
+
+#[test]
+fn model_aliases_merge_models_in_breakdowns_and_pricing() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    insert_rules(
+        db,
+        vec![PricingRuleInput {
+            model_pattern: "gpt-5.2-codex".to_string(),
+            input_per_1m: 1000.0,
+            cached_input_per_1m: 0.0,
+            output_per_1m: 0.0,
+            effective_from: "2025-01-01T00:00:00Z".to_string(),
+            effective_to: None,
+            provider: None,
+            fallback: false,
+        }],
+    );
+    let input_only = |tokens| UsageTotals {
+        input_tokens: tokens,
+        cached_input_tokens: 0,
+        output_tokens: 0,
+        reasoning_output_tokens: 0,
+        total_tokens: tokens,
+    };
+    insert_events(
+        db,
+        home.id,
+        vec![
+            make_event(
+                "e1",
+                "2025-12-19T19:00:00Z",
+                "gpt-5.2-codex-preview",
+                input_only(1000),
+                "source-a",
+            ),
+            make_event(
+                "e2",
+                "2025-12-19T19:05:00Z",
+                "gpt-5.2-codex",
+                input_only(500),
+                "source-b",
+            ),
+        ],
+    );
+    db.replace_model_aliases(&[ModelAlias {
+        alias: "gpt-5.2-codex-preview".to_string(),
+        model: "gpt-5.2-codex".to_string(),
+    }])
+    .expect("replace aliases");
+    assert_eq!(db.list_model_aliases().expect("aliases").len(), 1);
+
+    let range = TimeRange {
+        start: "2025-12-19T00:00:00Z".to_string(),
+        end: "2025-12-20T00:00:00Z".to_string(),
+    };
+    let breakdown = db
+        .breakdown_by_model_costs(&range, home.id)
+        .expect("breakdown");
+    assert_eq!(breakdown.len(), 1);
+    assert_eq!(breakdown[0].model, "gpt-5.2-codex");
+    assert_eq!(breakdown[0].total_tokens, 1500);
+    assert!((breakdown[0].total_cost_usd.unwrap() - 1.5).abs() < 1e-9);
+    assert_eq!(
+        db.distinct_models(&range, home.id).expect("models"),
+        vec!["gpt-5.2-codex".to_string()]
+    );
+
+    // Stored costs are priced under the alias target too.
+    db.update_event_costs(home.id).expect("recompute");
+    assert!(db.unpriced_models(home.id).expect("unpriced").is_empty());
+    let events = db
+        .list_usage_events(&range, Some("gpt-5.2-codex"), 10, 0, home.id)
+        .expect("events");
+    assert_eq!(events.len(), 2);
+    let preview = events
+        .iter()
+        .find(|event| event.model == "gpt-5.2-codex-preview")
+        .expect("preview event keeps its logged model");
+    assert!((preview.cost_usd.unwrap() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn provider_rules_price_their_provider_and_split_breakdowns() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    let rule = |input_per_1m, provider: Option<&str>| PricingRuleInput {
+        model_pattern: "gpt-5.2".to_string(),
+        input_per_1m,
+        cached_input_per_1m: 0.0,
+        output_per_1m: 0.0,
+        effective_from: "2025-01-01T00:00:00Z".to_string(),
+        effective_to: None,
+        provider: provider.map(str::to_string),
+        fallback: false,
+    };
+    insert_rules(db, vec![rule(1000.0, None), rule(3000.0, Some("azure"))]);
+    let input_only = UsageTotals {
+        input_tokens: 1000,
+        cached_input_tokens: 0,
+        output_tokens: 0,
+        reasoning_output_tokens: 0,
+        total_tokens: 1000,
+    };
+    let on = |event: UsageEvent, provider: Option<&str>| UsageEvent {
+        provider: provider.map(str::to_string),
+        ..event
+    };
+    insert_events(
+        db,
+        home.id,
+        vec![
+            on(
+                make_event(
+                    "e1",
+                    "2025-12-19T19:00:00Z",
+                    "gpt-5.2",
+                    input_only,
+                    "source-a",
+                ),
+                Some("azure"),
+            ),
+            on(
+                make_event(
+                    "e2",
+                    "2025-12-19T19:05:00Z",
+                    "gpt-5.2",
+                    input_only,
+                    "source-b",
+                ),
+                None,
+            ),
+        ],
+    );
+    db.update_event_costs(home.id).expect("recompute");
+
+    let range = TimeRange {
+        start: "2025-12-19T00:00:00Z".to_string(),
+        end: "2025-12-20T00:00:00Z".to_string(),
+    };
+    let providers = db
+        .breakdown_by_provider(&range, home.id)
+        .expect("breakdown");
+    assert_eq!(providers.len(), 2);
+    let cost = |name: &str| {
+        providers
+            .iter()
+            .find(|item| item.provider == name)
+            .and_then(|item| item.total_cost_usd)
+            .expect("priced provider")
+    };
+    // Both rules match the Azure event; the provider-scoped one wins.
+    assert!((cost("openai") - 1.0).abs() < 1e-9);
+    assert!((cost("azure") - 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn fallback_rule_prices_unmatched_models_as_estimated() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    let rule = |model_pattern: &str, input_per_1m, fallback| PricingRuleInput {
+        model_pattern: model_pattern.to_string(),
+        input_per_1m,
+        cached_input_per_1m: 0.0,
+        output_per_1m: 0.0,
+        effective_from: "2025-01-01T00:00:00Z".to_string(),
+        effective_to: None,
+        provider: None,
+        fallback,
+    };
+    insert_rules(
+        db,
+        vec![rule("gpt-5.2", 1000.0, false), rule("default", 500.0, true)],
+    );
+    let input_only = UsageTotals {
+        input_tokens: 1000,
+        cached_input_tokens: 0,
+        output_tokens: 0,
+        reasoning_output_tokens: 0,
+        total_tokens: 1000,
+    };
+    insert_events(
+        db,
+        home.id,
+        vec![
+            make_event(
+                "e1",
+                "2025-12-19T19:00:00Z",
+                "gpt-5.2",
+                input_only,
+                "source-a",
+            ),
+            make_event(
+                "e2",
+                "2025-12-19T19:05:00Z",
+                "mystery-model",
+                input_only,
+                "source-b",
+            ),
+        ],
+    );
+    db.update_event_costs(home.id).expect("recompute");
+
+    let range = TimeRange {
+        start: "2025-12-19T00:00:00Z".to_string(),
+        end: "2025-12-20T00:00:00Z".to_string(),
+    };
+    let summary = db.summary(&range, home.id).expect("summary");
+    // The specific rule keeps priority for gpt-5.2; only the unknown model
+    // falls back.
+    assert!((summary.total_cost_usd.unwrap() - 1.5).abs() < 1e-9);
+    assert!((summary.estimated_cost_usd.unwrap() - 0.5).abs() < 1e-9);
+    assert_eq!(summary.cost_source, Some(CostSource::Estimated));
+    let events = db
+        .list_usage_events(&range, None, 10, 0, home.id)
+        .expect("events");
+    let source = |id: &str| {
+        events
+            .iter()
+            .find(|event| event.id == id)
+            .and_then(|event| event.cost_source)
+    };
+    assert_eq!(source("e1"), Some(CostSource::Computed));
+    assert_eq!(source("e2"), Some(CostSource::Estimated));
+    let totals = db
+        .usage_event_totals(&range, Some("gpt-5.2"), home.id)
+        .expect("totals");
+    assert_eq!(totals.cost_source, Some(CostSource::Computed));
+    assert!(db.unpriced_models(home.id).expect("unpriced").is_empty());
+    let rules = db.list_pricing_rules().expect("rules");
+    assert_eq!(rules.iter().filter(|rule| rule.fallback).count(), 1);
+}
+
+#[test]
+fn recompute_keeps_reported_costs() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    insert_rules(
+        db,
+        vec![PricingRuleInput {
+            model_pattern: "gpt-5.2".to_string(),
+            input_per_1m: 1000.0,
+            cached_input_per_1m: 0.0,
+            output_per_1m: 0.0,
+            effective_from: "2025-01-01T00:00:00Z".to_string(),
+            effective_to: None,
+            provider: None,
+            fallback: false,
+        }],
+    );
+    let usage = UsageTotals {
+        input_tokens: 1000,
+        cached_input_tokens: 0,
+        output_tokens: 0,
+        reasoning_output_tokens: 0,
+        total_tokens: 1000,
+    };
+    let reported = UsageEvent {
+        cost_usd: Some(7.0),
+        cost_source: Some(CostSource::Reported),
+        ..make_event(
+            "r1",
+            "2025-12-19T19:00:00Z",
+            "gpt-5.2",
+            usage,
+            "ccusage:daily:2025-12-19",
+        )
+    };
+    insert_events(db, home.id, vec![reported]);
+    db.update_event_costs(home.id).expect("recompute");
+
+    let event = db
+        .get_usage_event(home.id, "r1")
+        .expect("event")
+        .expect("stored");
+    assert_eq!(event.cost_usd, Some(7.0));
+    assert_eq!(event.cost_source, Some(CostSource::Reported));
+}
+
+#[test]
+fn resume_event_costs_continues_after_the_last_completed_source() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    let rule = PricingRuleInput {
+        model_pattern: "gpt-5.2".to_string(),
+        input_per_1m: 1750.0,
+        cached_input_per_1m: 175.0,
+        output_per_1m: 14000.0,
+        effective_from: "2025-01-01T00:00:00Z".to_string(),
+        effective_to: None,
+        provider: None,
+        fallback: false,
+    };
+    insert_rules(db, vec![rule.clone()]);
+    let usage = UsageTotals {
+        input_tokens: 100,
+        cached_input_tokens: 0,
+        output_tokens: 50,
+        reasoning_output_tokens: 0,
+        total_tokens: 150,
+    };
+    insert_events(
+        db,
+        home.id,
+        vec![
+            make_event("a1", "2025-12-19T19:00:00Z", "gpt-5.2", usage, "source-a"),
+            make_event("a2", "2025-12-19T19:05:00Z", "gpt-5.2", usage, "source-a"),
+            make_event("b1", "2025-12-19T19:10:00Z", "gpt-5.2", usage, "source-b"),
+        ],
+    );
+    assert_eq!(db.cost_recompute_progress().expect("progress"), None);
+
+    assert_eq!(db.update_event_costs(home.id).expect("recompute"), 3);
+    let done = CostRecomputeProgress {
+        codex_home_id: home.id,
+        processed: 3,
+        total: 3,
+        complete: true,
+    };
+    assert_eq!(db.cost_recompute_progress().expect("progress"), Some(done));
+
+    // A run that stopped after committing source-a resumes with source-b.
+    let interrupt = |db: &tracker_db::Db| {
+        db.set_setting("cost_recompute.processed", "2")
+            .expect("processed");
+        db.set_setting("cost_recompute.complete", "0")
+            .expect("complete");
+        db.set_setting("cost_recompute.cursor", "source-a")
+            .expect("cursor");
+    };
+    interrupt(db);
+    assert_eq!(db.resume_event_costs(home.id).expect("resume"), (1, done));
+
+    // New prices invalidate the resume point, so everything is re-priced.
+    interrupt(db);
+    insert_rules(db, vec![rule]);
+    assert_eq!(db.resume_event_costs(home.id).expect("resume"), (3, done));
+}
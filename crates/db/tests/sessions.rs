@@ -0,0 +1,163 @@
+mod support;
+
+use support::{insert_events, make_event, make_message_event, setup_db, setup_home};
+use tracker_core::{SessionSpan, SessionTimelineEntry, TimeRange, Timestamp, UsageTotals};
+
+fn usage(total_tokens: u64) -> UsageTotals {
+    UsageTotals {
+        input_tokens: total_tokens,
+        cached_input_tokens: 0,
+        output_tokens: 0,
+        reasoning_output_tokens: 0,
+        total_tokens,
+    }
+}
+
+#[test]
+fn session_timeline_interleaves_messages_tokens_and_effort() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    let mut first = make_event(
+        "e1",
+        "2025-12-19T10:00:05Z",
+        "gpt-5.2",
+        usage(100),
+        "source-a",
+    );
+    first.reasoning_effort = Some("medium".to_string());
+    let mut second = make_event(
+        "e2",
+        "2025-12-19T10:01:00Z",
+        "gpt-5.2",
+        usage(250),
+        "source-a",
+    );
+    second.reasoning_effort = Some("high".to_string());
+    let other = make_event(
+        "e3",
+        "2025-12-19T10:00:30Z",
+        "gpt-5.2",
+        usage(999),
+        "source-b",
+    );
+    insert_events(db, home.id, vec![first, second, other]);
+    db.insert_message_events(
+        home.id,
+        &[
+            make_message_event("m1", "2025-12-19T10:00:00Z", "source-a"),
+            make_message_event("m2", "2025-12-19T10:01:00Z", "source-a"),
+        ],
+    )
+    .expect("insert messages");
+
+    let timeline = db.session_timeline(home.id, "source-a").expect("timeline");
+    let kinds: Vec<&str> = timeline
+        .entries
+        .iter()
+        .map(|entry| match entry {
+            SessionTimelineEntry::Message { .. } => "message",
+            SessionTimelineEntry::TokenCount { .. } => "tokens",
+            SessionTimelineEntry::EffortChange { .. } => "effort",
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        vec!["message", "effort", "tokens", "message", "effort", "tokens"]
+    );
+    match &timeline.entries[4] {
+        SessionTimelineEntry::EffortChange { from, to, .. } => {
+            assert_eq!(from.as_deref(), Some("medium"));
+            assert_eq!(to.as_deref(), Some("high"));
+        }
+        entry => panic!("unexpected entry {entry:?}"),
+    }
+    match &timeline.entries[5] {
+        SessionTimelineEntry::TokenCount { usage, .. } => assert_eq!(usage.total_tokens, 150),
+        entry => panic!("unexpected entry {entry:?}"),
+    }
+}
+
+#[test]
+fn session_spans_follow_inserts_and_shutdowns() {
+    let mut test_db = setup_db();
+    let db = &mut test_db.db;
+    let home = setup_home(db);
+    insert_events(
+        db,
+        home.id,
+        vec![
+            make_event(
+                "a2",
+                "2025-12-19T10:20:00Z",
+                "gpt-5.2",
+                usage(200),
+                "source-a",
+            ),
+            make_event(
+                "b1",
+                "2025-12-19T11:00:00Z",
+                "gpt-5.2",
+                usage(100),
+                "source-b",
+            ),
+        ],
+    );
+    db.insert_message_events(
+        home.id,
+        &[make_message_event("m1", "2025-12-19T10:00:00Z", "source-a")],
+    )
+    .expect("insert messages");
+    let ts = |value: &str| value.parse::<Timestamp>().expect("timestamp");
+    db.record_session_ends(
+        home.id,
+        &[
+            ("source-a".to_string(), ts("2025-12-19T10:30:00Z")),
+            // Logged before the session's last event: it was resumed.
+            ("source-b".to_string(), ts("2025-12-19T10:59:00Z")),
+        ],
+    )
+    .expect("record ends");
+
+    let range = TimeRange {
+        start: "2025-12-19T00:00:00Z".to_string(),
+        end: "2025-12-20T00:00:00Z".to_string(),
+    };
+    let spans = db.session_spans(&range, home.id).expect("spans");
+    assert_eq!(
+        spans,
+        vec![
+            SessionSpan {
+                session_id: "source-a".to_string(),
+                started_at: ts("2025-12-19T10:00:00Z"),
+                last_event_at: ts("2025-12-19T10:20:00Z"),
+                ended_at: Some(ts("2025-12-19T10:30:00Z")),
+            },
+            SessionSpan {
+                session_id: "source-b".to_string(),
+                started_at: ts("2025-12-19T11:00:00Z"),
+                last_event_at: ts("2025-12-19T11:00:00Z"),
+                ended_at: None,
+            },
+        ]
+    );
+
+    // Activity after a shutdown reopens the session.
+    insert_events(
+        db,
+        home.id,
+        vec![make_event(
+            "a3",
+            "2025-12-19T12:00:00Z",
+            "gpt-5.2",
+            usage(300),
+            "source-a",
+        )],
+    );
+    let spans = db.session_spans(&range, home.id).expect("spans");
+    assert_eq!(spans[0].last_event_at, ts("2025-12-19T12:00:00Z"));
+    assert_eq!(spans[0].ended_at, None);
+
+    db.clear_home_data(home.id).expect("clear");
+    assert!(db.session_spans(&range, home.id).expect("spans").is_empty());
+}
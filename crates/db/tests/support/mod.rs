@@ -47,7 +47,7 @@ pub fn setup_home(db: &mut Db) -> CodexHome {
 pub fn make_event(id: &str, ts: &str, model: &str, usage: UsageTotals, source: &str) -> UsageEvent {
     UsageEvent {
         id: id.to_string(),
-        ts: ts.to_string(),
+        ts: ts.parse().expect("timestamp"),
         model: model.to_string(),
         usage,
         context: ContextStatus {
@@ -55,7 +55,9 @@ pub fn make_event(id: &str, ts: &str, model: &str, usage: UsageTotals, source: &
             context_window: 100_000,
         },
         cost_usd: None,
+        cost_source: None,
         reasoning_effort: None,
+        provider: None,
         source: source.to_string(),
         session_id: session_id_from_source(source),
         request_id: None,
@@ -73,8 +75,8 @@ pub fn make_limit_snapshot(
     UsageLimitSnapshot {
         limit_type: limit_type.to_string(),
         percent_left,
-        reset_at: reset_at.to_string(),
-        observed_at: observed_at.to_string(),
+        reset_at: reset_at.parse().expect("timestamp"),
+        observed_at: observed_at.parse().expect("timestamp"),
         source: source.to_string(),
         raw_line: None,
     }
@@ -83,7 +85,7 @@ pub fn make_limit_snapshot(
 pub fn make_message_event(id: &str, ts: &str, source: &str) -> MessageEvent {
     MessageEvent {
         id: id.to_string(),
-        ts: ts.to_string(),
+        ts: ts.parse().expect("timestamp"),
         role: "user".to_string(),
         source: source.to_string(),
         session_id: session_id_from_source(source),
@@ -0,0 +1,125 @@
+//! Error codes shared by the HTTP API, the desktop app, and the CLI, so
+//! clients can branch on a stable identifier instead of matching messages.
+//! Codes are part of the public API: add new ones, but never rename them.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum ErrorCode {
+    /// A request field is missing or malformed.
+    InvalidInput,
+    /// A named range, start, or end could not be turned into a time range.
+    BadRange,
+    /// The codex home asked for does not exist.
+    HomeNotFound,
+    /// Any other record (event, webhook, token, route) does not exist.
+    NotFound,
+    /// Another process holds the database lock; retrying usually works.
+    DbLocked,
+    /// A bearer token is required and was missing or wrong.
+    Unauthorized,
+    /// The request came from an origin the server does not serve.
+    InvalidOrigin,
+    /// The CSRF token header was missing or wrong.
+    CsrfInvalid,
+    /// The route exists, but not for this HTTP method.
+    MethodNotAllowed,
+    /// Everything else: database, ingest, and I/O failures.
+    Internal,
+}
+
+impl ErrorCode {
+    pub const ALL: [ErrorCode; 10] = [
+        ErrorCode::InvalidInput,
+        ErrorCode::BadRange,
+        ErrorCode::HomeNotFound,
+        ErrorCode::NotFound,
+        ErrorCode::DbLocked,
+        ErrorCode::Unauthorized,
+        ErrorCode::InvalidOrigin,
+        ErrorCode::CsrfInvalid,
+        ErrorCode::MethodNotAllowed,
+        ErrorCode::Internal,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::InvalidInput => "invalid_input",
+            ErrorCode::BadRange => "bad_range",
+            ErrorCode::HomeNotFound => "home_not_found",
+            ErrorCode::NotFound => "not_found",
+            ErrorCode::DbLocked => "db_locked",
+            ErrorCode::Unauthorized => "unauthorized",
+            ErrorCode::InvalidOrigin => "invalid_origin",
+            ErrorCode::CsrfInvalid => "csrf_invalid",
+            ErrorCode::MethodNotAllowed => "method_not_allowed",
+            ErrorCode::Internal => "internal",
+        }
+    }
+
+    /// The HTTP status the API answers with for this code.
+    pub fn http_status(self) -> u16 {
+        match self {
+            ErrorCode::InvalidInput | ErrorCode::BadRange => 400,
+            ErrorCode::Unauthorized => 401,
+            ErrorCode::InvalidOrigin | ErrorCode::CsrfInvalid => 403,
+            ErrorCode::HomeNotFound | ErrorCode::NotFound => 404,
+            ErrorCode::MethodNotAllowed => 405,
+            ErrorCode::DbLocked => 503,
+            ErrorCode::Internal => 500,
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An error as the desktop app and the CLI's `--json` output report it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TrackerError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl TrackerError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for TrackerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for TrackerError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_serialize_as_their_stable_names() {
+        for code in ErrorCode::ALL {
+            let json = serde_json::to_string(&code).expect("serialize");
+            assert_eq!(json, format!("\"{}\"", code.as_str()));
+        }
+        let error = TrackerError::new(ErrorCode::HomeNotFound, "home not found");
+        assert_eq!(
+            serde_json::to_value(&error).expect("serialize"),
+            serde_json::json!({ "code": "home_not_found", "message": "home not found" })
+        );
+    }
+}
@@ -0,0 +1,18 @@
+use app_api::AppContext;
+use tracker_app::{AppError, Result};
+
+/// Runs an `app_api` call on tokio's blocking pool.
+///
+/// Every call reads or writes SQLite synchronously; on a runtime worker a
+/// slow aggregation would stall every other request and timer scheduled on
+/// that thread.
+pub async fn run_blocking<T, F>(context: &AppContext, call: F) -> Result<T>
+where
+    F: FnOnce(&AppContext) -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    let context = context.clone();
+    tokio::task::spawn_blocking(move || call(&context))
+        .await
+        .map_err(|err| AppError::Message(err.to_string()))?
+}
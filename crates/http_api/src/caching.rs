@@ -0,0 +1,84 @@
+use axum::{
+    body::{Body, to_bytes},
+    extract::State,
+    http::{
+        HeaderValue, Request, StatusCode,
+        header::{CACHE_CONTROL, ETAG, IF_NONE_MATCH},
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use tracker_core::HomeScope;
+use tracker_error::ErrorCode;
+
+use crate::{blocking::run_blocking, errors::HttpError, state::HttpState};
+
+const MAX_REQUEST_BYTES: usize = 64 * 1024;
+
+/// ETag / If-None-Match support for aggregate endpoints.
+///
+/// The tag hashes the route, the JSON request body, the data fingerprint of
+/// the requested (or active) home, and the current minute. The minute keeps relative ranges
+/// such as `last7days` from being served stale forever while still letting
+/// dashboards that poll every few seconds get cheap `304`s.
+pub async fn etag(
+    State(state): State<HttpState>,
+    req: Request<Body>,
+    next: Next,
+) -> Result<Response, HttpError> {
+    let (parts, body) = req.into_parts();
+    let body = to_bytes(body, MAX_REQUEST_BYTES).await.map_err(|_| {
+        HttpError::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "request body too large",
+            ErrorCode::InvalidInput,
+        )
+    })?;
+    // Without a fingerprint (e.g. no active home yet) let the handler report
+    // whatever is wrong and skip caching.
+    let home = serde_json::from_slice::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|value| serde_json::from_value::<HomeScope>(value.get("home_id")?.clone()).ok());
+    let fingerprint = run_blocking(&state.context, move |context| {
+        app_api::usage_fingerprint(context, home)
+    })
+    .await;
+    let Ok(fingerprint) = fingerprint else {
+        return Ok(next.run(Request::from_parts(parts, Body::from(body))).await);
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.update(parts.uri.path().as_bytes());
+    hasher.update(b"\0");
+    hasher.update(&body);
+    hasher.update(b"\0");
+    hasher.update(fingerprint.as_bytes());
+    hasher.update(b"\0");
+    hasher.update((Utc::now().timestamp() / 60).to_string().as_bytes());
+    let digest = hasher.finalize();
+    let etag = format!("W/\"{}\"", hex::encode(&digest[..16]));
+
+    let matches = parts
+        .headers
+        .get(IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value
+                .split(',')
+                .any(|tag| tag.trim() == etag || tag.trim() == "*")
+        });
+    let etag_value = HeaderValue::from_str(&etag).expect("hex etag is a valid header");
+    if matches {
+        return Ok((StatusCode::NOT_MODIFIED, [(ETAG, etag_value)]).into_response());
+    }
+
+    let mut response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    if response.status().is_success() {
+        let headers = response.headers_mut();
+        headers.insert(ETAG, etag_value);
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("private, no-cache"));
+    }
+    Ok(response)
+}
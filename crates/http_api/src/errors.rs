@@ -4,6 +4,7 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use tracker_app::{ApiError, AppError};
+use tracker_error::ErrorCode;
 
 #[derive(Debug)]
 pub struct HttpError {
@@ -12,7 +13,7 @@ pub struct HttpError {
 }
 
 impl HttpError {
-    pub fn new(status: StatusCode, message: impl Into<String>, code: Option<String>) -> Self {
+    pub fn new(status: StatusCode, message: impl Into<String>, code: ErrorCode) -> Self {
         let body = ApiError {
             status: status.as_u16(),
             message: message.into(),
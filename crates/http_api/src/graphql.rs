@@ -0,0 +1,249 @@
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, InputObject, Object, Schema, SimpleObject,
+};
+use axum::extract::{Extension, Json, State};
+
+use app_api::{
+    AppContext, EventsRequest, LimitsSnapshotsRequest, LimitsWindowsRequest, RangeRequest,
+    SessionsRequest, TimeseriesRequest,
+};
+use tracker_core::{
+    HomeScope, ModelBreakdown, PricingRule, SessionBreakdown, TimeSeriesPoint, UsageEvent,
+    UsageLimitSnapshot, UsageLimitWindow, UsageSummary,
+};
+
+use crate::blocking::run_blocking;
+use crate::state::HttpState;
+
+/// Keeps a single request from fanning out into an expensive query.
+const MAX_DEPTH: usize = 8;
+const MAX_COMPLEXITY: usize = 500;
+
+pub type TrackerSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+pub fn schema() -> TrackerSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+/// Time window shared by the range-based fields; same values as the REST API
+/// (`today`, `last7days`, ... or explicit `start` / `end`).
+#[derive(InputObject, Default)]
+pub struct RangeInput {
+    pub range: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct Limits {
+    pub primary: Option<UsageLimitSnapshot>,
+    pub secondary: Option<UsageLimitSnapshot>,
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Token and cost totals. `homeId` defaults to the active home.
+    async fn summary(
+        &self,
+        ctx: &Context<'_>,
+        range: Option<RangeInput>,
+        home_id: Option<i64>,
+    ) -> async_graphql::Result<UsageSummary> {
+        let range = range.unwrap_or_default();
+        Ok(run_blocking(app_context(ctx), move |context| {
+            app_api::summary(
+                context,
+                RangeRequest {
+                    range: range.range,
+                    start: range.start,
+                    end: range.end,
+                    home_id: home_id.map(HomeScope::Home),
+                },
+            )
+        })
+        .await?)
+    }
+
+    /// `bucket` is `hour` or `day`; `metric` is `tokens` or `cost`.
+    async fn timeseries(
+        &self,
+        ctx: &Context<'_>,
+        range: Option<RangeInput>,
+        bucket: Option<String>,
+        metric: Option<String>,
+        home_id: Option<i64>,
+    ) -> async_graphql::Result<Vec<TimeSeriesPoint>> {
+        let range = range.unwrap_or_default();
+        Ok(run_blocking(app_context(ctx), move |context| {
+            app_api::timeseries(
+                context,
+                TimeseriesRequest {
+                    range: range.range,
+                    start: range.start,
+                    end: range.end,
+                    bucket,
+                    metric,
+                    home_id: home_id.map(HomeScope::Home),
+                    tz_offset_minutes: None,
+                },
+            )
+        })
+        .await?)
+    }
+
+    /// Per-model totals, largest first.
+    async fn models(
+        &self,
+        ctx: &Context<'_>,
+        range: Option<RangeInput>,
+        home_id: Option<i64>,
+    ) -> async_graphql::Result<Vec<ModelBreakdown>> {
+        let range = range.unwrap_or_default();
+        Ok(run_blocking(app_context(ctx), move |context| {
+            app_api::breakdown(
+                context,
+                RangeRequest {
+                    range: range.range,
+                    start: range.start,
+                    end: range.end,
+                    home_id: home_id.map(HomeScope::Home),
+                },
+            )
+        })
+        .await?)
+    }
+
+    /// Raw usage events, newest first (at most 1000 per page).
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        range: Option<RangeInput>,
+        model: Option<String>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+        home_id: Option<i64>,
+    ) -> async_graphql::Result<Vec<UsageEvent>> {
+        let range = range.unwrap_or_default();
+        Ok(run_blocking(app_context(ctx), move |context| {
+            app_api::events(
+                context,
+                EventsRequest {
+                    range: range.range,
+                    start: range.start,
+                    end: range.end,
+                    limit,
+                    offset,
+                    model,
+                    home_id,
+                },
+            )
+        })
+        .await?
+        .events)
+    }
+
+    /// Sessions active in the range, by total tokens.
+    async fn sessions(
+        &self,
+        ctx: &Context<'_>,
+        range: Option<RangeInput>,
+        limit: Option<u32>,
+        home_id: Option<i64>,
+    ) -> async_graphql::Result<Vec<SessionBreakdown>> {
+        let range = range.unwrap_or_default();
+        Ok(run_blocking(app_context(ctx), move |context| {
+            app_api::sessions(
+                context,
+                SessionsRequest {
+                    range: range.range,
+                    start: range.start,
+                    end: range.end,
+                    limit,
+                    home_id: home_id.map(HomeScope::Home),
+                },
+            )
+        })
+        .await?)
+    }
+
+    /// Latest 5h (primary) and 7d (secondary) limit snapshots.
+    async fn limits(&self, ctx: &Context<'_>) -> async_graphql::Result<Limits> {
+        let latest = run_blocking(app_context(ctx), app_api::limits_latest).await?;
+        Ok(Limits {
+            primary: latest.primary.map(|status| status.snapshot),
+            secondary: latest.secondary.map(|status| status.snapshot),
+        })
+    }
+
+    /// Raw limit snapshots, newest first; `limitType` is `5h` or `7d`.
+    async fn limit_snapshots(
+        &self,
+        ctx: &Context<'_>,
+        range: Option<RangeInput>,
+        limit_type: Option<String>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+        home_id: Option<i64>,
+    ) -> async_graphql::Result<Vec<UsageLimitSnapshot>> {
+        let range = range.unwrap_or_default();
+        Ok(run_blocking(app_context(ctx), move |context| {
+            app_api::limits_snapshots(
+                context,
+                LimitsSnapshotsRequest {
+                    range: range.range,
+                    start: range.start,
+                    end: range.end,
+                    limit_type,
+                    limit,
+                    offset,
+                    home_id,
+                },
+            )
+        })
+        .await?)
+    }
+
+    /// Completed and in-progress 7d windows, newest first.
+    async fn limit_windows(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<usize>,
+    ) -> async_graphql::Result<Vec<UsageLimitWindow>> {
+        Ok(run_blocking(app_context(ctx), move |context| {
+            app_api::limits_7d_windows(context, LimitsWindowsRequest { limit })
+        })
+        .await?)
+    }
+
+    /// Pricing rules used to compute costs.
+    async fn pricing(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<PricingRule>> {
+        Ok(run_blocking(app_context(ctx), |context| {
+            context.app_state.services.pricing.list_rules()
+        })
+        .await?)
+    }
+}
+
+fn app_context<'a>(ctx: &Context<'a>) -> &'a AppContext {
+    ctx.data_unchecked::<AppContext>()
+}
+
+/// Executes one GraphQL request. Errors are reported in the response body's
+/// `errors` array, so this always answers 200.
+pub async fn graphql(
+    State(state): State<HttpState>,
+    Extension(schema): Extension<TrackerSchema>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(schema.execute(request.data(state.context.clone())).await)
+}
+
+/// The schema in SDL, for codegen and editor tooling.
+pub async fn graphql_sdl(Extension(schema): Extension<TrackerSchema>) -> String {
+    schema.sdl()
+}
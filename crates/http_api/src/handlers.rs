@@ -2,233 +2,1730 @@ use std::process::Command;
 
 use axum::{
     body::Body,
-    extract::{Json, State},
+    extract::{Json, Path, Query, State},
     http::{Method, Request, StatusCode},
     response::{IntoResponse, Response},
 };
 
 use app_api::{
-    ContextSessionsRequest, EventsRequest, HomesClearDataRequest, HomesCreateRequest,
-    HomesDeleteRequest, HomesSetActiveRequest, LimitsWindowsRequest, PricingReplaceRequest,
-    RangeRequest, SettingsPutRequest, TimeseriesRequest,
+    ActivityByWeekdayRequest, ActivityRequest, ApiTokenCreatedResponse, ApiTokensCreateRequest,
+    ApiTokensRevokeRequest, CcusageRequest, ClearedResponse, CompactionsRequest,
+    ContextPeaksRequest, ContextSessionsRequest, CustomLimitsCreateRequest,
+    CustomLimitsDeleteRequest, DashboardRequest, DashboardResponse, DeletedResponse, EmptyRequest,
+    EventDetailResponse, EventRequest, EventsRequest, EventsResponse, HealthResponse,
+    HomeCandidateResponse, HomeRequest, HomesArchiveRequest, HomesClearDataRequest,
+    HomesCreateRequest, HomesDeleteRequest, HomesRenameRequest, HomesResponse,
+    HomesSetActiveRequest, HomesUpdateRequest, IngestCursorResponse, IngestCursorsResetRequest,
+    LimitsResponse, LimitsSnapshotsRequest, LimitsWindowsRequest, ModelAliasesReplaceRequest,
+    OkResponse, PricingRecomputeResponse, PricingReplaceRequest, PricingRuleResponse, RangeRequest,
+    RefreshRequest, RefreshResponse, ReportRequest, ResetResponse, RevokedResponse,
+    SchedulesCreateRequest, SchedulesDeleteRequest, SchedulesSendRequest, SentResponse,
+    SessionLifetimesRequest, SessionTimelineRequest, SettingsImportResponse, SettingsPutRequest,
+    SettingsResponse, TimeseriesGroupedRequest, TimeseriesRequest, TopRequestsRequest,
+    UpdatedResponse, WebhookDeliveriesRequest, WebhooksCreateRequest, WebhooksDeleteRequest,
 };
+use ingest::IngestStats;
+use tracker_app::ApiError;
+use tracker_app::bundle::SettingsBundle;
+use tracker_app::ccusage::CcusageReport;
+use tracker_app::version::VersionInfo;
+use tracker_core::{
+    ActiveSession, ActivityReport, ApiToken, CodexHome, CompactionReport, ContextPressureStats,
+    ContextStatus, CostRecomputeProgress, CustomLimit, GroupedTimeSeries, HomeBreakdown,
+    ModelAlias, ModelBreakdown, ModelCostBreakdown, ModelEffortCostBreakdown,
+    ModelEffortTokenBreakdown, ModelTokenBreakdown, ProviderBreakdown, RequestBreakdown, Schedule,
+    SessionContextPeak, SessionLifetimeReport, SessionTimeline, TimeSeriesPoint,
+    UsageLimitCurrentResponse, UsageLimitSnapshot, UsageLimitWindow, UsageSummary, UserBreakdown,
+    Webhook, WebhookDelivery, WeekdayHourAverages,
+};
+use tracker_error::ErrorCode;
 
-use crate::{assets, errors::HttpError, state::HttpState};
+use crate::{assets, blocking::run_blocking, errors::HttpError, middleware, state::HttpState};
 
+#[utoipa::path(
+    post,
+    path = "/api/summary",
+    tag = "analytics",
+    request_body = RangeRequest,
+    responses(
+        (status = 200, body = UsageSummary),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn summary(
     State(state): State<HttpState>,
     Json(req): Json<RangeRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::summary(&state.context, req)?;
+    let response = run_blocking(&state.context, move |context| {
+        app_api::summary(context, req)
+    })
+    .await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/context_latest",
+    tag = "analytics",
+    request_body = HomeRequest,
+    responses(
+        (status = 200, body = Option<ContextStatus>),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn context_latest(
     State(state): State<HttpState>,
-    Json(_): Json<app_api::EmptyRequest>,
+    Json(req): Json<HomeRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::context_latest(&state.context)?;
+    let response = run_blocking(&state.context, move |context| {
+        app_api::context_latest(context, req)
+    })
+    .await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/context_sessions",
+    tag = "analytics",
+    request_body = ContextSessionsRequest,
+    responses(
+        (status = 200, body = Vec<ActiveSession>),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn context_sessions(
     State(state): State<HttpState>,
     Json(req): Json<ContextSessionsRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::context_sessions(&state.context, req)?;
+    let response = run_blocking(&state.context, move |context| {
+        app_api::context_sessions(context, req)
+    })
+    .await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/context_stats",
+    tag = "analytics",
+    request_body = RangeRequest,
+    responses(
+        (status = 200, body = ContextPressureStats),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn context_stats(
     State(state): State<HttpState>,
     Json(req): Json<RangeRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::context_stats(&state.context, req)?;
+    let response = run_blocking(&state.context, move |context| {
+        app_api::context_stats(context, req)
+    })
+    .await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/timeseries",
+    tag = "analytics",
+    request_body = TimeseriesRequest,
+    responses(
+        (status = 200, body = Vec<TimeSeriesPoint>),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn timeseries(
     State(state): State<HttpState>,
     Json(req): Json<TimeseriesRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::timeseries(&state.context, req)?;
+    let response = run_blocking(&state.context, move |context| {
+        app_api::timeseries(context, req)
+    })
+    .await?;
     Ok(Json(response))
 }
 
+/// One timeseries per model or reasoning effort (`group_by`), e.g. to see
+/// which effort level drives output cost.
+#[utoipa::path(
+    post,
+    path = "/api/timeseries_grouped",
+    tag = "analytics",
+    request_body = TimeseriesGroupedRequest,
+    responses(
+        (status = 200, body = Vec<GroupedTimeSeries>),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn timeseries_grouped(
+    State(state): State<HttpState>,
+    Json(req): Json<TimeseriesGroupedRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::timeseries_grouped(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/breakdown",
+    tag = "analytics",
+    request_body = RangeRequest,
+    responses(
+        (status = 200, body = Vec<ModelBreakdown>),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn breakdown(
     State(state): State<HttpState>,
     Json(req): Json<RangeRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::breakdown(&state.context, req)?;
+    let response = run_blocking(&state.context, move |context| {
+        app_api::breakdown(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/breakdown_homes",
+    tag = "analytics",
+    request_body = RangeRequest,
+    responses(
+        (status = 200, body = Vec<HomeBreakdown>),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn breakdown_homes(
+    State(state): State<HttpState>,
+    Json(req): Json<RangeRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::breakdown_homes(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+/// Per-user totals for team mode, across every home unless `home_id` is
+/// given.
+#[utoipa::path(
+    post,
+    path = "/api/breakdown_users",
+    tag = "analytics",
+    request_body = RangeRequest,
+    responses(
+        (status = 200, body = Vec<UserBreakdown>),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn breakdown_users(
+    State(state): State<HttpState>,
+    Json(req): Json<RangeRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::breakdown_users(context, req)
+    })
+    .await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/breakdown_providers",
+    tag = "analytics",
+    request_body = RangeRequest,
+    responses(
+        (status = 200, body = Vec<ProviderBreakdown>),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn breakdown_providers(
+    State(state): State<HttpState>,
+    Json(req): Json<RangeRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::breakdown_providers(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/breakdown_tokens",
+    tag = "analytics",
+    request_body = RangeRequest,
+    responses(
+        (status = 200, body = Vec<ModelTokenBreakdown>),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn breakdown_tokens(
     State(state): State<HttpState>,
     Json(req): Json<RangeRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::breakdown_tokens(&state.context, req)?;
+    let response = run_blocking(&state.context, move |context| {
+        app_api::breakdown_tokens(context, req)
+    })
+    .await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/breakdown_costs",
+    tag = "analytics",
+    request_body = RangeRequest,
+    responses(
+        (status = 200, body = Vec<ModelCostBreakdown>),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn breakdown_costs(
     State(state): State<HttpState>,
     Json(req): Json<RangeRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::breakdown_costs(&state.context, req)?;
+    let response = run_blocking(&state.context, move |context| {
+        app_api::breakdown_costs(context, req)
+    })
+    .await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/breakdown_effort_tokens",
+    tag = "analytics",
+    request_body = RangeRequest,
+    responses(
+        (status = 200, body = Vec<ModelEffortTokenBreakdown>),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn breakdown_effort_tokens(
     State(state): State<HttpState>,
     Json(req): Json<RangeRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::breakdown_effort_tokens(&state.context, req)?;
+    let response = run_blocking(&state.context, move |context| {
+        app_api::breakdown_effort_tokens(context, req)
+    })
+    .await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/breakdown_effort_costs",
+    tag = "analytics",
+    request_body = RangeRequest,
+    responses(
+        (status = 200, body = Vec<ModelEffortCostBreakdown>),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn breakdown_effort_costs(
     State(state): State<HttpState>,
     Json(req): Json<RangeRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::breakdown_effort_costs(&state.context, req)?;
+    let response = run_blocking(&state.context, move |context| {
+        app_api::breakdown_effort_costs(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/models",
+    tag = "analytics",
+    request_body = RangeRequest,
+    responses(
+        (status = 200, body = Vec<String>),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn models(
+    State(state): State<HttpState>,
+    Json(req): Json<RangeRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response =
+        run_blocking(&state.context, move |context| app_api::models(context, req)).await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/efforts",
+    tag = "analytics",
+    request_body = RangeRequest,
+    responses(
+        (status = 200, body = Vec<String>),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn efforts(
+    State(state): State<HttpState>,
+    Json(req): Json<RangeRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::efforts(context, req)
+    })
+    .await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/sources",
+    tag = "analytics",
+    request_body = RangeRequest,
+    responses(
+        (status = 200, body = Vec<String>),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn sources(
+    State(state): State<HttpState>,
+    Json(req): Json<RangeRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::sources(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/events",
+    tag = "analytics",
+    request_body = EventsRequest,
+    responses(
+        (status = 200, body = EventsResponse),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn events(
     State(state): State<HttpState>,
     Json(req): Json<EventsRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::events(&state.context, req)?;
+    let response =
+        run_blocking(&state.context, move |context| app_api::events(context, req)).await?;
+    Ok(Json(response))
+}
+
+/// Every dashboard usage panel from one database snapshot, so the numbers
+/// agree even while an ingest is committing.
+#[utoipa::path(
+    post,
+    path = "/api/dashboard",
+    tag = "analytics",
+    request_body = DashboardRequest,
+    responses(
+        (status = 200, body = DashboardResponse),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn dashboard(
+    State(state): State<HttpState>,
+    Json(req): Json<DashboardRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::dashboard(context, req)
+    })
+    .await?;
     Ok(Json(response))
 }
 
+/// "Hours of Codex use": active time per session and day, not counting gaps
+/// longer than `idle_gap_minutes` between events.
+#[utoipa::path(
+    post,
+    path = "/api/activity",
+    tag = "analytics",
+    request_body = ActivityRequest,
+    responses(
+        (status = 200, body = ActivityReport),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn activity(
+    State(state): State<HttpState>,
+    Json(req): Json<ActivityRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::activity(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+/// Average tokens and cost per weekday and per hour of the day, for the bar
+/// charts next to the activity view.
+#[utoipa::path(
+    post,
+    path = "/api/activity/by-weekday",
+    tag = "analytics",
+    request_body = ActivityByWeekdayRequest,
+    responses(
+        (status = 200, body = WeekdayHourAverages),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn activity_by_weekday(
+    State(state): State<HttpState>,
+    Json(req): Json<ActivityByWeekdayRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::activity_by_weekday(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+/// How often Codex compacted its context (the context size dropping to half
+/// or less mid-session), per session and per day.
+#[utoipa::path(
+    post,
+    path = "/api/compactions",
+    tag = "analytics",
+    request_body = CompactionsRequest,
+    responses(
+        (status = 200, body = CompactionReport),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn compactions(
+    State(state): State<HttpState>,
+    Json(req): Json<CompactionsRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::compactions(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+/// How many sessions started in the range, how many ended with a shutdown
+/// or were abandoned, and how long they ran.
+#[utoipa::path(
+    post,
+    path = "/api/session_lifetimes",
+    tag = "analytics",
+    request_body = SessionLifetimesRequest,
+    responses(
+        (status = 200, body = SessionLifetimeReport),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn session_lifetimes(
+    State(state): State<HttpState>,
+    Json(req): Json<SessionLifetimesRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::session_lifetimes(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+/// Sessions whose context pressure reached `threshold_pct` (default 80%),
+/// with where they peaked: conversations that should have been restarted.
+#[utoipa::path(
+    post,
+    path = "/api/context_peaks",
+    tag = "analytics",
+    request_body = ContextPeaksRequest,
+    responses(
+        (status = 200, body = Vec<SessionContextPeak>),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn context_peaks(
+    State(state): State<HttpState>,
+    Json(req): Json<ContextPeaksRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::context_peaks(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+/// The largest single requests in the range, for finding the prompt that
+/// blew up the context.
+#[utoipa::path(
+    post,
+    path = "/api/top_requests",
+    tag = "analytics",
+    request_body = TopRequestsRequest,
+    responses(
+        (status = 200, body = Vec<RequestBreakdown>),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn top_requests(
+    State(state): State<HttpState>,
+    Json(req): Json<TopRequestsRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::top_requests(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/session_timeline",
+    tag = "analytics",
+    request_body = SessionTimelineRequest,
+    responses(
+        (status = 200, body = SessionTimeline),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn session_timeline(
+    State(state): State<HttpState>,
+    Json(req): Json<SessionTimelineRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::session_timeline(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+/// REST-style alias of `session_timeline` for scripts and deep links.
+#[utoipa::path(
+    get,
+    path = "/api/sessions/{id}/timeline",
+    tag = "analytics",
+    params(
+        ("id" = String, Path, description = "Session id"),
+        ("home_id" = Option<i64>, Query, description = "Home to query instead of the active one")
+    ),
+    responses(
+        (status = 200, body = SessionTimeline),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn session_timeline_by_path(
+    State(state): State<HttpState>,
+    Path(session_id): Path<String>,
+    Query(home): Query<HomeRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let req = SessionTimelineRequest {
+        session_id,
+        home_id: home.home_id,
+    };
+    let response = run_blocking(&state.context, move |context| {
+        app_api::session_timeline(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+/// A stored event with its raw log line parsed, for debugging ingest.
+#[utoipa::path(
+    get,
+    path = "/api/events/{id}",
+    tag = "analytics",
+    params(
+        ("id" = String, Path, description = "Event id"),
+        ("home_id" = Option<i64>, Query, description = "Home to query instead of the active one")
+    ),
+    responses(
+        (status = 200, body = EventDetailResponse),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn event(
+    State(state): State<HttpState>,
+    Path(id): Path<String>,
+    Query(home): Query<HomeRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let req = EventRequest {
+        id,
+        home_id: home.home_id,
+    };
+    let response =
+        run_blocking(&state.context, move |context| app_api::event(context, req)).await?;
+    Ok(Json(response))
+}
+
+/// Usage as `ccusage <report> --json` output, for tools built on ccusage.
+#[utoipa::path(
+    post,
+    path = "/api/ccusage",
+    tag = "export",
+    request_body = CcusageRequest,
+    responses(
+        (status = 200, body = CcusageReport),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn ccusage(
+    State(state): State<HttpState>,
+    Json(req): Json<CcusageRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::ccusage(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[cfg(feature = "parquet")]
+#[utoipa::path(
+    post,
+    path = "/api/export/events.parquet",
+    tag = "export",
+    request_body = RangeRequest,
+    responses(
+        (status = 200, content_type = "application/vnd.apache.parquet", body = [u8]),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn export_events_parquet(
+    State(state): State<HttpState>,
+    Json(req): Json<RangeRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let bytes = run_blocking(&state.context, move |context| {
+        app_api::export_events_parquet(context, req)
+    })
+    .await?;
+    Ok((
+        [
+            ("content-type", "application/vnd.apache.parquet"),
+            (
+                "content-disposition",
+                "attachment; filename=\"events.parquet\"",
+            ),
+        ],
+        bytes,
+    ))
+}
+
+/// Markdown, HTML, or JSON usage digest, e.g. for piping into email, or a
+/// standalone HTML snapshot with charts. Served over GET like the calendar feed.
+#[utoipa::path(
+    get,
+    path = "/api/report",
+    tag = "analytics",
+    params(ReportRequest),
+    security((), ("bearer_token" = [])),
+    responses(
+        (status = 200, content_type = "text/markdown", body = String),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn report(
+    State(state): State<HttpState>,
+    Query(req): Query<ReportRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let (format, body) =
+        run_blocking(&state.context, move |context| app_api::report(context, req)).await?;
+    Ok(([("content-type", format.content_type())], body))
+}
+
+/// Build identification for bug reports; served over GET without the CSRF
+/// header.
+#[utoipa::path(
+    get,
+    path = "/api/version",
+    tag = "system",
+    security((), ("bearer_token" = [])),
+    responses(
+        (status = 200, body = VersionInfo),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn version() -> Json<VersionInfo> {
+    Json(app_api::version())
+}
+
+/// Liveness probe that also reports the running build.
+#[utoipa::path(
+    get,
+    path = "/api/health",
+    tag = "system",
+    security((), ("bearer_token" = [])),
+    responses(
+        (status = 200, body = HealthResponse),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn health() -> Json<HealthResponse> {
+    Json(app_api::health())
+}
+
+/// Calendar feed for subscription from calendar apps. Served over GET without
+/// the CSRF header; with token auth enabled, append `?token=<token>`.
+#[utoipa::path(
+    get,
+    path = "/api/limits/resets.ics",
+    tag = "limits",
+    security((), ("bearer_token" = [])),
+    responses(
+        (status = 200, content_type = "text/calendar", body = String),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn limits_resets_ics(
+    State(state): State<HttpState>,
+) -> Result<impl IntoResponse, HttpError> {
+    let body = run_blocking(&state.context, app_api::limits_resets_ics).await?;
+    Ok((
+        [
+            ("content-type", "text/calendar; charset=utf-8"),
+            (
+                "content-disposition",
+                "inline; filename=\"codex-limit-resets.ics\"",
+            ),
+        ],
+        body,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/limits_latest",
+    tag = "limits",
+    request_body = EmptyRequest,
+    responses(
+        (status = 200, body = LimitsResponse),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn limits_latest(
     State(state): State<HttpState>,
-    Json(_): Json<app_api::EmptyRequest>,
+    Json(_): Json<EmptyRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::limits_latest(&state.context)?;
+    let response = run_blocking(&state.context, app_api::limits_latest).await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/limits_current",
+    tag = "limits",
+    request_body = EmptyRequest,
+    responses(
+        (status = 200, body = UsageLimitCurrentResponse),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn limits_current(
     State(state): State<HttpState>,
-    Json(_): Json<app_api::EmptyRequest>,
+    Json(_): Json<EmptyRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::limits_current(&state.context)?;
+    let response = run_blocking(&state.context, app_api::limits_current).await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/custom_limits_list",
+    tag = "limits",
+    request_body = EmptyRequest,
+    responses(
+        (status = 200, body = Vec<CustomLimit>),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn custom_limits_list(
+    State(state): State<HttpState>,
+    Json(_): Json<EmptyRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, app_api::custom_limits_list).await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/custom_limits_create",
+    tag = "limits",
+    request_body = CustomLimitsCreateRequest,
+    responses(
+        (status = 200, body = CustomLimit),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn custom_limits_create(
+    State(state): State<HttpState>,
+    Json(req): Json<CustomLimitsCreateRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::custom_limits_create(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/custom_limits_delete",
+    tag = "limits",
+    request_body = CustomLimitsDeleteRequest,
+    responses(
+        (status = 200, body = DeletedResponse),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn custom_limits_delete(
+    State(state): State<HttpState>,
+    Json(req): Json<CustomLimitsDeleteRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::custom_limits_delete(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/limits_7d_windows",
+    tag = "limits",
+    request_body = LimitsWindowsRequest,
+    responses(
+        (status = 200, body = Vec<UsageLimitWindow>),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn limits_7d_windows(
     State(state): State<HttpState>,
     Json(req): Json<LimitsWindowsRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::limits_7d_windows(&state.context, req)?;
+    let response = run_blocking(&state.context, move |context| {
+        app_api::limits_7d_windows(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/limits_snapshots",
+    tag = "limits",
+    request_body = LimitsSnapshotsRequest,
+    responses(
+        (status = 200, body = Vec<UsageLimitSnapshot>),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn limits_snapshots(
+    State(state): State<HttpState>,
+    Json(req): Json<LimitsSnapshotsRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::limits_snapshots(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/limits/snapshots",
+    tag = "limits",
+    params(LimitsSnapshotsRequest),
+    responses(
+        (status = 200, body = Vec<UsageLimitSnapshot>),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn limits_snapshots_query(
+    State(state): State<HttpState>,
+    Query(req): Query<LimitsSnapshotsRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::limits_snapshots(context, req)
+    })
+    .await?;
     Ok(Json(response))
 }
 
+/// Per-file ingest progress for one home.
+#[utoipa::path(
+    post,
+    path = "/api/ingest/cursors",
+    tag = "ingest",
+    request_body = HomeRequest,
+    responses(
+        (status = 200, body = Vec<IngestCursorResponse>),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn ingest_cursors(
+    State(state): State<HttpState>,
+    Json(req): Json<HomeRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::ingest_cursors(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+/// Makes the next ingest re-read one file, or the whole home, from the start.
+#[utoipa::path(
+    post,
+    path = "/api/ingest/cursors/reset",
+    tag = "ingest",
+    request_body = IngestCursorsResetRequest,
+    responses(
+        (status = 200, body = ResetResponse),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn ingest_cursors_reset(
+    State(state): State<HttpState>,
+    Json(req): Json<IngestCursorsResetRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::ingest_cursors_reset(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/ingest",
+    tag = "ingest",
+    request_body = EmptyRequest,
+    responses(
+        (status = 200, body = IngestStats),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn ingest(
     State(state): State<HttpState>,
-    Json(_): Json<app_api::EmptyRequest>,
+    Json(_): Json<EmptyRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let context = state.context.clone();
-    let stats = tokio::task::spawn_blocking(move || app_api::ingest(&context))
-        .await
-        .map_err(|err| {
-            HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string(), None)
-        })??;
+    let stats = run_blocking(&state.context, app_api::ingest).await?;
     Ok(Json(stats))
 }
 
+/// Ingests, then returns the summary and limits as of that ingest, so a
+/// client never re-fetches before its own ingest has landed.
+#[utoipa::path(
+    post,
+    path = "/api/refresh",
+    tag = "ingest",
+    request_body = RefreshRequest,
+    responses(
+        (status = 200, body = RefreshResponse),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn refresh(
+    State(state): State<HttpState>,
+    Json(req): Json<RefreshRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::refresh(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/open_logs_dir",
+    tag = "ingest",
+    request_body = EmptyRequest,
+    responses(
+        (status = 200, body = OkResponse),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn open_logs_dir(
     State(state): State<HttpState>,
-    Json(_): Json<app_api::EmptyRequest>,
+    Json(_): Json<EmptyRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let path = app_api::logs_dir(&state.context)?;
+    let path = run_blocking(&state.context, app_api::logs_dir).await?;
     open_path(&path)?;
     Ok(Json(app_api::ok()))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/pricing_list",
+    tag = "pricing",
+    request_body = EmptyRequest,
+    responses(
+        (status = 200, body = Vec<PricingRuleResponse>),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn pricing_list(
     State(state): State<HttpState>,
-    Json(_): Json<app_api::EmptyRequest>,
+    Json(_): Json<EmptyRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::pricing_list(&state.context)?;
+    let response = run_blocking(&state.context, app_api::pricing_list).await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/pricing_replace",
+    tag = "pricing",
+    request_body = PricingReplaceRequest,
+    responses(
+        (status = 200, body = UpdatedResponse),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn pricing_replace(
     State(state): State<HttpState>,
     Json(req): Json<PricingReplaceRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::pricing_replace(&state.context, req)?;
+    let response = run_blocking(&state.context, move |context| {
+        app_api::pricing_replace(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+/// Model names reported and priced under another name.
+#[utoipa::path(
+    post,
+    path = "/api/model_aliases_list",
+    tag = "pricing",
+    request_body = EmptyRequest,
+    responses(
+        (status = 200, body = Vec<ModelAlias>),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn model_aliases_list(
+    State(state): State<HttpState>,
+    Json(_): Json<EmptyRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, app_api::model_aliases_list).await?;
     Ok(Json(response))
 }
 
+/// Replaces every model alias and recomputes the active home's costs.
+#[utoipa::path(
+    post,
+    path = "/api/model_aliases_replace",
+    tag = "pricing",
+    request_body = ModelAliasesReplaceRequest,
+    responses(
+        (status = 200, body = UpdatedResponse),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn model_aliases_replace(
+    State(state): State<HttpState>,
+    Json(req): Json<ModelAliasesReplaceRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::model_aliases_replace(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/pricing_recompute",
+    tag = "pricing",
+    request_body = EmptyRequest,
+    responses(
+        (status = 200, body = PricingRecomputeResponse),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn pricing_recompute(
     State(state): State<HttpState>,
-    Json(_): Json<app_api::EmptyRequest>,
+    Json(_): Json<EmptyRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, app_api::pricing_recompute).await?;
+    Ok(Json(response))
+}
+
+/// Progress of the running or latest cost recompute; `null` if none ran.
+#[utoipa::path(
+    post,
+    path = "/api/pricing_recompute_status",
+    tag = "pricing",
+    request_body = EmptyRequest,
+    responses(
+        (status = 200, body = Option<CostRecomputeProgress>),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn pricing_recompute_status(
+    State(state): State<HttpState>,
+    Json(_): Json<EmptyRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::pricing_recompute(&state.context)?;
+    let response = run_blocking(&state.context, app_api::pricing_recompute_status).await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/settings_get",
+    tag = "settings",
+    request_body = EmptyRequest,
+    responses(
+        (status = 200, body = SettingsResponse),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn settings_get(
     State(state): State<HttpState>,
-    Json(_): Json<app_api::EmptyRequest>,
+    Json(_): Json<EmptyRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::settings_get(&state.context)?;
+    let response = run_blocking(&state.context, app_api::settings_get).await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/settings_put",
+    tag = "settings",
+    request_body = SettingsPutRequest,
+    responses(
+        (status = 200, body = SettingsResponse),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn settings_put(
     State(state): State<HttpState>,
     Json(req): Json<SettingsPutRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::settings_put(&state.context, req)?;
+    let response = run_blocking(&state.context, move |context| {
+        app_api::settings_put(context, req)
+    })
+    .await?;
     Ok(Json(response))
 }
 
+/// Everything configurable as one JSON bundle, secrets included, for
+/// `settings_import` on another machine.
+#[utoipa::path(
+    post,
+    path = "/api/settings_export",
+    tag = "settings",
+    request_body = EmptyRequest,
+    responses(
+        (status = 200, body = SettingsBundle),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn settings_export(
+    State(state): State<HttpState>,
+    Json(_): Json<EmptyRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, app_api::settings_export).await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/settings_import",
+    tag = "settings",
+    request_body = SettingsBundle,
+    responses(
+        (status = 200, body = SettingsImportResponse),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn settings_import(
+    State(state): State<HttpState>,
+    Json(req): Json<SettingsBundle>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::settings_import(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/notifications_test",
+    tag = "settings",
+    request_body = EmptyRequest,
+    responses(
+        (status = 200, body = SentResponse),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn notifications_test(
+    State(state): State<HttpState>,
+    Json(_): Json<EmptyRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, app_api::notifications_test).await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/homes_list",
+    tag = "homes",
+    request_body = EmptyRequest,
+    responses(
+        (status = 200, body = HomesResponse),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn homes_list(
     State(state): State<HttpState>,
-    Json(_): Json<app_api::EmptyRequest>,
+    Json(_): Json<EmptyRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::homes_list(&state.context)?;
+    let response = run_blocking(&state.context, app_api::homes_list).await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/homes_create",
+    tag = "homes",
+    request_body = HomesCreateRequest,
+    responses(
+        (status = 200, body = CodexHome),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn homes_create(
     State(state): State<HttpState>,
     Json(req): Json<HomesCreateRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::homes_create(&state.context, req)?;
+    let response = run_blocking(&state.context, move |context| {
+        app_api::homes_create(context, req)
+    })
+    .await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/homes_set_active",
+    tag = "homes",
+    request_body = HomesSetActiveRequest,
+    responses(
+        (status = 200, body = CodexHome),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn homes_set_active(
     State(state): State<HttpState>,
     Json(req): Json<HomesSetActiveRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::homes_set_active(&state.context, req)?;
+    let response = run_blocking(&state.context, move |context| {
+        app_api::homes_set_active(context, req)
+    })
+    .await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/homes_rename",
+    tag = "homes",
+    request_body = HomesRenameRequest,
+    responses(
+        (status = 200, body = CodexHome),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn homes_rename(
+    State(state): State<HttpState>,
+    Json(req): Json<HomesRenameRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::homes_rename(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/homes/{id}",
+    tag = "homes",
+    params(("id" = i64, Path, description = "Home id")),
+    request_body = HomesUpdateRequest,
+    responses(
+        (status = 200, body = CodexHome),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn homes_update(
+    State(state): State<HttpState>,
+    Path(id): Path<i64>,
+    Json(req): Json<HomesUpdateRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::homes_update(context, id, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+/// Directories with Codex session logs in the usual places (`$CODEX_HOME`,
+/// `~/.codex`, other user profiles, WSL mounts), for first-run setup.
+#[utoipa::path(
+    post,
+    path = "/api/homes/discover",
+    tag = "homes",
+    request_body = EmptyRequest,
+    responses(
+        (status = 200, body = Vec<HomeCandidateResponse>),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn homes_discover(
+    State(state): State<HttpState>,
+    Json(_): Json<EmptyRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, app_api::homes_discover).await?;
+    Ok(Json(response))
+}
+
+/// Hides a home from pickers and ingest; its usage stays in all-homes queries.
+#[utoipa::path(
+    post,
+    path = "/api/homes_archive",
+    tag = "homes",
+    request_body = HomesArchiveRequest,
+    responses(
+        (status = 200, body = CodexHome),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn homes_archive(
+    State(state): State<HttpState>,
+    Json(req): Json<HomesArchiveRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::homes_archive(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/homes_unarchive",
+    tag = "homes",
+    request_body = HomesArchiveRequest,
+    responses(
+        (status = 200, body = CodexHome),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn homes_unarchive(
+    State(state): State<HttpState>,
+    Json(req): Json<HomesArchiveRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::homes_unarchive(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/homes_delete",
+    tag = "homes",
+    request_body = HomesDeleteRequest,
+    responses(
+        (status = 200, body = DeletedResponse),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn homes_delete(
     State(state): State<HttpState>,
     Json(req): Json<HomesDeleteRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::homes_delete(&state.context, req)?;
+    let response = run_blocking(&state.context, move |context| {
+        app_api::homes_delete(context, req)
+    })
+    .await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/homes_clear_data",
+    tag = "homes",
+    request_body = HomesClearDataRequest,
+    responses(
+        (status = 200, body = ClearedResponse),
+        (status = "default", body = ApiError)
+    )
+)]
 pub async fn homes_clear_data(
     State(state): State<HttpState>,
     Json(req): Json<HomesClearDataRequest>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let response = app_api::homes_clear_data(&state.context, req)?;
+    let response = run_blocking(&state.context, move |context| {
+        app_api::homes_clear_data(context, req)
+    })
+    .await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/webhooks_list",
+    tag = "webhooks",
+    request_body = EmptyRequest,
+    responses(
+        (status = 200, body = Vec<Webhook>),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn webhooks_list(
+    State(state): State<HttpState>,
+    Json(_): Json<EmptyRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, app_api::webhooks_list).await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/webhooks_create",
+    tag = "webhooks",
+    request_body = WebhooksCreateRequest,
+    responses(
+        (status = 200, body = Webhook),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn webhooks_create(
+    State(state): State<HttpState>,
+    Json(req): Json<WebhooksCreateRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::webhooks_create(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/webhooks_delete",
+    tag = "webhooks",
+    request_body = WebhooksDeleteRequest,
+    responses(
+        (status = 200, body = DeletedResponse),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn webhooks_delete(
+    State(state): State<HttpState>,
+    Json(req): Json<WebhooksDeleteRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::webhooks_delete(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/webhook_deliveries",
+    tag = "webhooks",
+    request_body = WebhookDeliveriesRequest,
+    responses(
+        (status = 200, body = Vec<WebhookDelivery>),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn webhook_deliveries(
+    State(state): State<HttpState>,
+    Json(req): Json<WebhookDeliveriesRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::webhook_deliveries(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/schedules_list",
+    tag = "schedules",
+    request_body = EmptyRequest,
+    responses(
+        (status = 200, body = Vec<Schedule>),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn schedules_list(
+    State(state): State<HttpState>,
+    Json(_): Json<EmptyRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, app_api::schedules_list).await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/schedules_create",
+    tag = "schedules",
+    request_body = SchedulesCreateRequest,
+    responses(
+        (status = 200, body = Schedule),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn schedules_create(
+    State(state): State<HttpState>,
+    Json(req): Json<SchedulesCreateRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::schedules_create(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/schedules_delete",
+    tag = "schedules",
+    request_body = SchedulesDeleteRequest,
+    responses(
+        (status = 200, body = DeletedResponse),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn schedules_delete(
+    State(state): State<HttpState>,
+    Json(req): Json<SchedulesDeleteRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::schedules_delete(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/schedules_send",
+    tag = "schedules",
+    request_body = SchedulesSendRequest,
+    responses(
+        (status = 200, body = Schedule),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn schedules_send(
+    State(state): State<HttpState>,
+    Json(req): Json<SchedulesSendRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::schedules_send(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/api_tokens_list",
+    tag = "auth",
+    request_body = EmptyRequest,
+    responses(
+        (status = 200, body = Vec<ApiToken>),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn api_tokens_list(
+    State(state): State<HttpState>,
+    Json(_): Json<EmptyRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, app_api::api_tokens_list).await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/api_tokens_create",
+    tag = "auth",
+    request_body = ApiTokensCreateRequest,
+    responses(
+        (status = 200, body = ApiTokenCreatedResponse),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn api_tokens_create(
+    State(state): State<HttpState>,
+    Json(req): Json<ApiTokensCreateRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::api_tokens_create(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/api_tokens_revoke",
+    tag = "auth",
+    request_body = ApiTokensRevokeRequest,
+    responses(
+        (status = 200, body = RevokedResponse),
+        (status = "default", body = ApiError)
+    )
+)]
+pub async fn api_tokens_revoke(
+    State(state): State<HttpState>,
+    Json(req): Json<ApiTokensRevokeRequest>,
+) -> Result<impl IntoResponse, HttpError> {
+    let response = run_blocking(&state.context, move |context| {
+        app_api::api_tokens_revoke(context, req)
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+pub async fn badge(
+    State(state): State<HttpState>,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, HttpError> {
+    let svg = run_blocking(&state.context, move |context| {
+        app_api::badge_svg(context, &name)
+    })
+    .await?;
+    Ok((
+        [
+            ("content-type", "image/svg+xml; charset=utf-8"),
+            // Image proxies (e.g. GitHub's camo) honour this and refetch.
+            ("cache-control", "no-cache, max-age=0"),
+        ],
+        svg,
+    ))
+}
+
+/// Fallback in headless mode, where nothing outside `/api` is served.
+pub async fn not_found() -> HttpError {
+    HttpError::new(StatusCode::NOT_FOUND, "not found", ErrorCode::NotFound)
+}
+
 pub async fn ui_fallback(
     State(state): State<HttpState>,
     req: Request<Body>,
@@ -237,27 +1734,26 @@ pub async fn ui_fallback(
         return Err(HttpError::new(
             StatusCode::METHOD_NOT_ALLOWED,
             "method not allowed",
-            None,
+            ErrorCode::MethodNotAllowed,
         ));
     }
 
     let path = req.uri().path().trim_start_matches('/');
-    if path.is_empty() {
-        return render_index(&state.csrf_token);
-    }
-
     if let Some(asset) = assets::asset(path) {
         return Ok(asset_response(asset));
     }
 
-    if !path.contains('.') {
+    if path.is_empty() || !path.contains('.') {
+        // The index carries the CSRF token; keep it from DNS names rebound
+        // to this server.
+        middleware::check_host(&state, req.headers())?;
         return render_index(&state.csrf_token);
     }
 
     Err(HttpError::new(
         StatusCode::NOT_FOUND,
         "not found",
-        Some("not_found".to_string()),
+        ErrorCode::NotFound,
     ))
 }
 
@@ -266,14 +1762,14 @@ fn render_index(csrf_token: &str) -> Result<Response, HttpError> {
         HttpError::new(
             StatusCode::INTERNAL_SERVER_ERROR,
             "missing index.html",
-            None,
+            ErrorCode::Internal,
         )
     })?;
     let html = std::str::from_utf8(index.bytes).map_err(|_| {
         HttpError::new(
             StatusCode::INTERNAL_SERVER_ERROR,
             "invalid index.html encoding",
-            None,
+            ErrorCode::Internal,
         )
     })?;
     let injected = inject_csrf(html, csrf_token);
@@ -284,11 +1780,14 @@ fn render_index(csrf_token: &str) -> Result<Response, HttpError> {
     Ok(response)
 }
 
+/// Inline script that hands the CSRF token to the UI. The CSP allows exactly
+/// this script by hash.
+pub(crate) fn csrf_script(csrf_token: &str) -> String {
+    format!("window.__CODEX_TRACKER_CSRF__=\"{}\";", csrf_token)
+}
+
 fn inject_csrf(html: &str, csrf_token: &str) -> String {
-    let snippet = format!(
-        "<script>window.__CODEX_TRACKER_CSRF__=\"{}\";</script>",
-        csrf_token
-    );
+    let snippet = format!("<script>{}</script>", csrf_script(csrf_token));
     if html.contains("</head>") {
         html.replacen("</head>", &format!("{snippet}</head>"), 1)
     } else {
@@ -305,17 +1804,20 @@ fn asset_response(asset: &assets::EmbeddedAsset) -> Response {
 }
 
 fn open_path(path: &std::path::Path) -> Result<(), HttpError> {
-    let status = Command::new("open")
-        .arg(path)
-        .status()
-        .map_err(|err| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string(), None))?;
+    let status = Command::new("open").arg(path).status().map_err(|err| {
+        HttpError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            err.to_string(),
+            ErrorCode::Internal,
+        )
+    })?;
     if status.success() {
         Ok(())
     } else {
         Err(HttpError::new(
             StatusCode::INTERNAL_SERVER_ERROR,
             "failed to open path",
-            None,
+            ErrorCode::Internal,
         ))
     }
 }
@@ -1,21 +1,39 @@
 mod assets;
+mod blocking;
+mod caching;
 mod errors;
+#[cfg(feature = "graphql")]
+mod graphql;
 mod handlers;
 mod middleware;
+mod openapi;
 mod state;
 
-use axum::{Router, middleware as axum_middleware, routing::post};
+use axum::http::Request;
+use axum::{
+    Router, middleware as axum_middleware,
+    routing::{get, patch, post},
+};
+use tower_http::{
+    compression::CompressionLayer,
+    trace::{DefaultOnResponse, TraceLayer},
+};
+use tracing::Level;
 
+pub use openapi::ApiDoc;
 pub use state::{HttpState, generate_csrf_token};
 
 pub fn router(state: HttpState) -> Router<()> {
-    let api = Router::new()
+    // Aggregate endpoints polled by the dashboard; answered with 304 when the
+    // underlying data has not changed.
+    let cached_api = Router::new()
         .route("/summary", post(handlers::summary))
-        .route("/context_latest", post(handlers::context_latest))
-        .route("/context_sessions", post(handlers::context_sessions))
-        .route("/context_stats", post(handlers::context_stats))
         .route("/timeseries", post(handlers::timeseries))
+        .route("/timeseries_grouped", post(handlers::timeseries_grouped))
         .route("/breakdown", post(handlers::breakdown))
+        .route("/breakdown_homes", post(handlers::breakdown_homes))
+        .route("/breakdown_users", post(handlers::breakdown_users))
+        .route("/breakdown_providers", post(handlers::breakdown_providers))
         .route("/breakdown_tokens", post(handlers::breakdown_tokens))
         .route("/breakdown_costs", post(handlers::breakdown_costs))
         .route(
@@ -26,29 +44,162 @@ pub fn router(state: HttpState) -> Router<()> {
             "/breakdown_effort_costs",
             post(handlers::breakdown_effort_costs),
         )
+        .route("/models", post(handlers::models))
+        .route("/efforts", post(handlers::efforts))
+        .route("/sources", post(handlers::sources))
+        .route_layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            caching::etag,
+        ));
+
+    let api = Router::new()
+        .merge(cached_api)
+        .route("/context_latest", post(handlers::context_latest))
+        .route("/context_sessions", post(handlers::context_sessions))
+        .route("/context_stats", post(handlers::context_stats))
+        .route("/context_peaks", post(handlers::context_peaks))
+        .route("/dashboard", post(handlers::dashboard))
         .route("/events", post(handlers::events))
+        .route("/events/:id", get(handlers::event))
+        .route("/top_requests", post(handlers::top_requests))
+        .route("/activity", post(handlers::activity))
+        .route("/activity/by-weekday", post(handlers::activity_by_weekday))
+        .route("/compactions", post(handlers::compactions))
+        .route("/session_lifetimes", post(handlers::session_lifetimes))
+        .route("/session_timeline", post(handlers::session_timeline))
+        .route(
+            "/sessions/:id/timeline",
+            get(handlers::session_timeline_by_path),
+        )
         .route("/limits_latest", post(handlers::limits_latest))
         .route("/limits_current", post(handlers::limits_current))
         .route("/limits_7d_windows", post(handlers::limits_7d_windows))
+        .route("/custom_limits_list", post(handlers::custom_limits_list))
+        .route(
+            "/custom_limits_create",
+            post(handlers::custom_limits_create),
+        )
+        .route(
+            "/custom_limits_delete",
+            post(handlers::custom_limits_delete),
+        )
+        .route("/limits_snapshots", post(handlers::limits_snapshots))
+        .route("/limits/snapshots", get(handlers::limits_snapshots_query))
         .route("/ingest", post(handlers::ingest))
+        .route("/refresh", post(handlers::refresh))
+        .route("/ingest/cursors", post(handlers::ingest_cursors))
+        .route(
+            "/ingest/cursors/reset",
+            post(handlers::ingest_cursors_reset),
+        )
         .route("/open_logs_dir", post(handlers::open_logs_dir))
         .route("/pricing_list", post(handlers::pricing_list))
         .route("/pricing_replace", post(handlers::pricing_replace))
         .route("/pricing_recompute", post(handlers::pricing_recompute))
+        .route(
+            "/pricing_recompute_status",
+            post(handlers::pricing_recompute_status),
+        )
+        .route("/model_aliases_list", post(handlers::model_aliases_list))
+        .route(
+            "/model_aliases_replace",
+            post(handlers::model_aliases_replace),
+        )
         .route("/settings_get", post(handlers::settings_get))
         .route("/settings_put", post(handlers::settings_put))
+        .route("/settings_export", post(handlers::settings_export))
+        .route("/settings_import", post(handlers::settings_import))
+        .route("/notifications_test", post(handlers::notifications_test))
         .route("/homes_list", post(handlers::homes_list))
         .route("/homes_create", post(handlers::homes_create))
         .route("/homes_set_active", post(handlers::homes_set_active))
+        .route("/homes_rename", post(handlers::homes_rename))
+        .route("/homes/:id", patch(handlers::homes_update))
+        .route("/homes/discover", post(handlers::homes_discover))
+        .route("/homes_archive", post(handlers::homes_archive))
+        .route("/homes_unarchive", post(handlers::homes_unarchive))
         .route("/homes_delete", post(handlers::homes_delete))
         .route("/homes_clear_data", post(handlers::homes_clear_data))
-        .route_layer(axum_middleware::from_fn_with_state(
-            state.clone(),
-            middleware::require_csrf,
-        ));
+        .route("/webhooks_list", post(handlers::webhooks_list))
+        .route("/webhooks_create", post(handlers::webhooks_create))
+        .route("/webhooks_delete", post(handlers::webhooks_delete))
+        .route("/webhook_deliveries", post(handlers::webhook_deliveries))
+        .route("/schedules_list", post(handlers::schedules_list))
+        .route("/schedules_create", post(handlers::schedules_create))
+        .route("/schedules_delete", post(handlers::schedules_delete))
+        .route("/schedules_send", post(handlers::schedules_send))
+        .route("/api_tokens_list", post(handlers::api_tokens_list))
+        .route("/api_tokens_create", post(handlers::api_tokens_create))
+        .route("/api_tokens_revoke", post(handlers::api_tokens_revoke))
+        .route("/ccusage", post(handlers::ccusage));
+    #[cfg(feature = "parquet")]
+    let api = api.route(
+        "/export/events.parquet",
+        post(handlers::export_events_parquet),
+    );
+    #[cfg(feature = "graphql")]
+    let graphql_schema = graphql::schema();
+    #[cfg(feature = "graphql")]
+    let api = api.route(
+        "/graphql",
+        post(graphql::graphql).layer(axum::Extension(graphql_schema.clone())),
+    );
+    let api = api.route_layer(axum_middleware::from_fn_with_state(
+        state.clone(),
+        middleware::require_csrf,
+    ));
+
+    // Read-only GET endpoints that clients cannot attach the CSRF header to.
+    // They are only served under loopback or allowed Host names instead.
+    let public_api = Router::new()
+        .route("/openapi.json", get(openapi::openapi_json))
+        .route("/limits/resets.ics", get(handlers::limits_resets_ics))
+        .route("/report", get(handlers::report))
+        .route("/version", get(handlers::version))
+        .route("/health", get(handlers::health));
+    #[cfg(feature = "graphql")]
+    let public_api = public_api.route(
+        "/graphql/schema.graphql",
+        get(graphql::graphql_sdl).layer(axum::Extension(graphql_schema)),
+    );
+    let public_api = public_api.route_layer(axum_middleware::from_fn_with_state(
+        state.clone(),
+        middleware::require_allowed_host,
+    ));
 
-    Router::new()
-        .nest("/api", api)
-        .fallback(handlers::ui_fallback)
-        .with_state(state)
+    let app = Router::new().nest("/api", api.merge(public_api));
+    let app = if state.headless {
+        app.fallback(handlers::not_found)
+    } else {
+        // Badges load as images from any page, so they get the Host check too.
+        let badges = Router::new()
+            .route("/badge/:name", get(handlers::badge))
+            .route_layer(axum_middleware::from_fn_with_state(
+                state.clone(),
+                middleware::require_allowed_host,
+            ));
+        app.merge(badges).fallback(handlers::ui_fallback)
+    };
+    app.layer(axum_middleware::from_fn_with_state(
+        state.clone(),
+        middleware::require_api_token,
+    ))
+    .layer(axum_middleware::from_fn_with_state(
+        state.clone(),
+        middleware::security_headers,
+    ))
+    .layer(CompressionLayer::new())
+    .layer(
+        TraceLayer::new_for_http()
+            // Only the path: the query string may carry `?token=`.
+            .make_span_with(|req: &Request<_>| {
+                tracing::info_span!(
+                    "request",
+                    method = %req.method(),
+                    path = req.uri().path(),
+                )
+            })
+            .on_response(DefaultOnResponse::new().level(Level::INFO)),
+    )
+    .with_state(state)
 }
@@ -1,31 +1,60 @@
 use axum::{
     body::Body,
     extract::State,
-    http::{Request, StatusCode, header::ORIGIN},
+    http::{
+        HeaderMap, HeaderValue, Request, StatusCode,
+        header::{
+            AUTHORIZATION, CONTENT_SECURITY_POLICY, COOKIE, HOST, LOCATION, ORIGIN,
+            REFERRER_POLICY, SET_COOKIE, X_CONTENT_TYPE_OPTIONS, X_FRAME_OPTIONS,
+        },
+    },
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use sha2::{Digest, Sha256};
+use tracker_error::ErrorCode;
 
-use crate::{errors::HttpError, state::HttpState};
+use crate::{
+    blocking::run_blocking,
+    errors::HttpError,
+    handlers::csrf_script,
+    state::{HttpState, host_name},
+};
+
+const TOKEN_COOKIE: &str = "codex_tracker_token";
 
 pub async fn require_csrf(
     State(state): State<HttpState>,
     req: Request<Body>,
     next: Next,
 ) -> Result<Response, HttpError> {
+    // Browsers never attach an Authorization header on their own, so requests
+    // authenticated with a bearer token cannot be forged cross-site.
+    if let Some(token) = bearer_token(req.headers())
+        && verify_token(&state, token).await?
+    {
+        return Ok(next.run(req).await);
+    }
+
     if let Some(origin) = req.headers().get(ORIGIN) {
         let origin = origin.to_str().map_err(|_| {
             HttpError::new(
                 StatusCode::BAD_REQUEST,
                 "invalid Origin header",
-                Some("invalid_origin".to_string()),
+                ErrorCode::InvalidOrigin,
             )
         })?;
-        if !is_loopback_origin(origin) {
+        let host = req
+            .headers()
+            .get(HOST)
+            .and_then(|value| value.to_str().ok());
+        if !is_loopback_origin(origin) && !is_allowed_same_origin(&state, origin, host) {
             return Err(HttpError::new(
                 StatusCode::FORBIDDEN,
                 "invalid origin",
-                Some("invalid_origin".to_string()),
+                ErrorCode::InvalidOrigin,
             ));
         }
     }
@@ -38,13 +67,191 @@ pub async fn require_csrf(
         return Err(HttpError::new(
             StatusCode::UNAUTHORIZED,
             "missing or invalid CSRF token",
-            Some("csrf_invalid".to_string()),
+            ErrorCode::CsrfInvalid,
         ));
     }
 
     Ok(next.run(req).await)
 }
 
+/// Rejects requests under a Host name that is neither loopback nor allowed,
+/// so a page on a DNS name rebound to this server cannot read what the
+/// route serves. Covers the GET routes that skip [`require_csrf`].
+pub async fn require_allowed_host(
+    State(state): State<HttpState>,
+    req: Request<Body>,
+    next: Next,
+) -> Result<Response, HttpError> {
+    check_host(&state, req.headers())?;
+    Ok(next.run(req).await)
+}
+
+/// Errors unless the request's Host passes [`HttpState::allows_host`].
+pub(crate) fn check_host(state: &HttpState, headers: &HeaderMap) -> Result<(), HttpError> {
+    let host = headers.get(HOST).and_then(|value| value.to_str().ok());
+    if state.allows_host(host) {
+        Ok(())
+    } else {
+        Err(HttpError::new(
+            StatusCode::FORBIDDEN,
+            "host not allowed",
+            ErrorCode::InvalidOrigin,
+        ))
+    }
+}
+
+/// Enforces API token auth when the server was started with it enabled.
+///
+/// Accepts `Authorization: Bearer <token>` or the session cookie. Opening
+/// `/?token=<token>` in a browser sets the cookie and redirects to `/`;
+/// under `/api/` and `/badge/` a query token authenticates the request
+/// directly, since feed readers and embedded images neither send headers nor
+/// keep cookies. `/api/health` stays open so monitors and the CLI's
+/// single-instance check can reach it.
+pub async fn require_api_token(
+    State(state): State<HttpState>,
+    req: Request<Body>,
+    next: Next,
+) -> Result<Response, HttpError> {
+    if !state.require_api_token || req.uri().path() == "/api/health" {
+        return Ok(next.run(req).await);
+    }
+
+    if let Some(token) = bearer_token(req.headers()).or_else(|| cookie_token(req.headers()))
+        && verify_token(&state, token).await?
+    {
+        return Ok(next.run(req).await);
+    }
+
+    if let Some(token) = query_token(req.uri().query())
+        && verify_token(&state, token).await?
+    {
+        if req.uri().path().starts_with("/api/") || req.uri().path().starts_with("/badge/") {
+            return Ok(next.run(req).await);
+        }
+        let mut cookie = format!("{TOKEN_COOKIE}={token}; Path=/; HttpOnly; SameSite=Strict");
+        if state.tls {
+            cookie.push_str("; Secure");
+        }
+        return Ok((
+            StatusCode::SEE_OTHER,
+            [
+                (LOCATION, req.uri().path().to_string()),
+                (SET_COOKIE, cookie),
+            ],
+        )
+            .into_response());
+    }
+
+    Err(HttpError::new(
+        StatusCode::UNAUTHORIZED,
+        "missing or invalid API token",
+        ErrorCode::Unauthorized,
+    ))
+}
+
+/// Sets CSP, framing, referrer, and sniffing headers on every response,
+/// leaving any a handler already chose in place.
+///
+/// Without configured frame ancestors the UI cannot be framed at all. With
+/// them, `X-Frame-Options` is omitted since it cannot name other origins.
+/// `no-referrer` keeps `?token=` URLs out of outgoing `Referer` headers.
+pub async fn security_headers(
+    State(state): State<HttpState>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let mut response = next.run(req).await;
+    let csp = content_security_policy(&state.csrf_token, &state.frame_ancestors);
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(&csp) {
+        headers.entry(CONTENT_SECURITY_POLICY).or_insert(value);
+    }
+    if state.frame_ancestors.is_empty() {
+        headers
+            .entry(X_FRAME_OPTIONS)
+            .or_insert(HeaderValue::from_static("DENY"));
+    }
+    headers
+        .entry(REFERRER_POLICY)
+        .or_insert(HeaderValue::from_static("no-referrer"));
+    headers
+        .entry(X_CONTENT_TYPE_OPTIONS)
+        .or_insert(HeaderValue::from_static("nosniff"));
+    response
+}
+
+fn content_security_policy(csrf_token: &str, frame_ancestors: &[String]) -> String {
+    let script_hash = BASE64.encode(Sha256::digest(csrf_script(csrf_token).as_bytes()));
+    let frame_ancestors = if frame_ancestors.is_empty() {
+        "'none'".to_string()
+    } else {
+        std::iter::once("'self'")
+            .chain(frame_ancestors.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    format!(
+        "default-src 'self'; script-src 'self' 'sha256-{script_hash}'; \
+         style-src 'self' 'unsafe-inline'; img-src 'self' data:; connect-src 'self'; \
+         object-src 'none'; base-uri 'self'; form-action 'self'; \
+         frame-ancestors {frame_ancestors}"
+    )
+}
+
+async fn verify_token(state: &HttpState, token: &str) -> Result<bool, HttpError> {
+    let token = token.to_string();
+    Ok(run_blocking(&state.context, move |context| {
+        context.app_state.services.api_tokens.verify(&token)
+    })
+    .await?)
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+fn cookie_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .find_map(|pair| pair.trim().strip_prefix(TOKEN_COOKIE)?.strip_prefix('='))
+}
+
+fn query_token(query: Option<&str>) -> Option<&str> {
+    query?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+}
+
+/// The UI served to another device posts from its own `scheme://host:port`.
+/// Only configured hosts qualify: a DNS name an attacker rebinds to this
+/// server would otherwise match its own `Host` too.
+fn is_allowed_same_origin(state: &HttpState, origin: &str, host: Option<&str>) -> bool {
+    let Some(host) = host else {
+        return false;
+    };
+    let name = host_name(host);
+    if !state
+        .allowed_hosts
+        .iter()
+        .any(|allowed| allowed.eq_ignore_ascii_case(name))
+    {
+        return false;
+    }
+    origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"))
+        .is_some_and(|origin_host| origin_host.eq_ignore_ascii_case(host))
+}
+
 fn is_loopback_origin(origin: &str) -> bool {
     origin.starts_with("http://127.0.0.1:")
         || origin.starts_with("http://localhost:")
@@ -0,0 +1,139 @@
+use axum::Json;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, Http, HttpAuthScheme, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+use crate::handlers;
+
+/// OpenAPI description of the `/api/*` surface served by the local HTTP server.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Codex Tracker API",
+        description = "Local HTTP API for Codex CLI usage analytics. All endpoints accept a JSON body and require either the `x-codex-token` header injected into the served UI or an `Authorization: Bearer` API token."
+    ),
+    paths(
+        handlers::summary,
+        handlers::context_latest,
+        handlers::context_sessions,
+        handlers::context_stats,
+        handlers::timeseries,
+        handlers::timeseries_grouped,
+        handlers::breakdown,
+        handlers::breakdown_homes,
+        handlers::breakdown_users,
+        handlers::breakdown_providers,
+        handlers::breakdown_tokens,
+        handlers::breakdown_costs,
+        handlers::breakdown_effort_tokens,
+        handlers::breakdown_effort_costs,
+        handlers::models,
+        handlers::efforts,
+        handlers::sources,
+        handlers::dashboard,
+        handlers::events,
+        handlers::event,
+        handlers::context_peaks,
+        handlers::top_requests,
+        handlers::activity,
+        handlers::activity_by_weekday,
+        handlers::compactions,
+        handlers::session_lifetimes,
+        handlers::session_timeline,
+        handlers::session_timeline_by_path,
+        handlers::report,
+        handlers::limits_latest,
+        handlers::limits_current,
+        handlers::limits_7d_windows,
+        handlers::custom_limits_list,
+        handlers::custom_limits_create,
+        handlers::custom_limits_delete,
+        handlers::limits_snapshots,
+        handlers::limits_snapshots_query,
+        handlers::limits_resets_ics,
+        handlers::ingest,
+        handlers::refresh,
+        handlers::ingest_cursors,
+        handlers::ingest_cursors_reset,
+        handlers::open_logs_dir,
+        handlers::pricing_list,
+        handlers::pricing_replace,
+        handlers::pricing_recompute,
+        handlers::pricing_recompute_status,
+        handlers::model_aliases_list,
+        handlers::model_aliases_replace,
+        handlers::settings_get,
+        handlers::settings_put,
+        handlers::settings_export,
+        handlers::settings_import,
+        handlers::notifications_test,
+        handlers::homes_list,
+        handlers::homes_create,
+        handlers::homes_set_active,
+        handlers::homes_rename,
+        handlers::homes_update,
+        handlers::homes_discover,
+        handlers::homes_archive,
+        handlers::homes_unarchive,
+        handlers::homes_delete,
+        handlers::homes_clear_data,
+        handlers::webhooks_list,
+        handlers::webhooks_create,
+        handlers::webhooks_delete,
+        handlers::webhook_deliveries,
+        handlers::schedules_list,
+        handlers::schedules_create,
+        handlers::schedules_delete,
+        handlers::schedules_send,
+        handlers::api_tokens_list,
+        handlers::api_tokens_create,
+        handlers::api_tokens_revoke,
+        handlers::ccusage,
+        handlers::version,
+        handlers::health,
+    ),
+    modifiers(&SecuritySchemes),
+    security(("csrf_token" = []), ("bearer_token" = [])),
+    tags(
+        (name = "analytics", description = "Usage totals, breakdowns, and events"),
+        (name = "limits", description = "5h / 7d usage limit snapshots and windows"),
+        (name = "ingest", description = "Codex log ingestion"),
+        (name = "pricing", description = "Pricing rules and cost recomputation"),
+        (name = "settings", description = "Application settings"),
+        (name = "homes", description = "Codex home directories"),
+        (name = "webhooks", description = "Outbound webhooks and their delivery log"),
+        (name = "schedules", description = "Daily report and status deliveries"),
+        (name = "auth", description = "API bearer tokens"),
+        (name = "export", description = "Bulk data exports"),
+        (name = "system", description = "Build and health information"),
+    )
+)]
+pub struct ApiDoc;
+
+#[cfg(feature = "parquet")]
+#[derive(OpenApi)]
+#[openapi(paths(handlers::export_events_parquet))]
+struct ExportApiDoc;
+
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "csrf_token",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("x-codex-token"))),
+        );
+        components.add_security_scheme(
+            "bearer_token",
+            SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+        );
+    }
+}
+
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    #[allow(unused_mut)]
+    let mut doc = ApiDoc::openapi();
+    #[cfg(feature = "parquet")]
+    doc.merge(ExportApiDoc::openapi());
+    Json(doc)
+}
@@ -1,3 +1,5 @@
+use std::net::IpAddr;
+
 use rand::RngCore;
 
 use app_api::AppContext;
@@ -6,6 +8,20 @@ use app_api::AppContext;
 pub struct HttpState {
     pub context: AppContext,
     pub csrf_token: String,
+    /// When set, every request must carry a valid API token (bearer header or
+    /// session cookie), including the UI itself.
+    pub require_api_token: bool,
+    /// Origins allowed to embed the UI in a frame (CSP `frame-ancestors`).
+    /// Empty means the UI cannot be framed at all.
+    pub frame_ancestors: Vec<String>,
+    /// Serve only `/api/*`: the dashboard, its assets, and badges answer 404.
+    pub headless: bool,
+    /// Served over HTTPS, so the session cookie is marked `Secure`.
+    pub tls: bool,
+    /// Host names, besides loopback ones, the dashboard may be opened under
+    /// and whose same-host `Origin` passes the CSRF check. Empty on a
+    /// loopback bind, so a rebound DNS name never gets the CSRF token.
+    pub allowed_hosts: Vec<String>,
 }
 
 impl HttpState {
@@ -13,8 +29,86 @@ impl HttpState {
         Self {
             context,
             csrf_token,
+            require_api_token: false,
+            frame_ancestors: Vec::new(),
+            headless: false,
+            tls: false,
+            allowed_hosts: Vec::new(),
         }
     }
+
+    /// Names or IP addresses, without a port, e.g. `tracker.lan` or
+    /// `192.168.1.20`.
+    pub fn with_allowed_hosts(mut self, hosts: Vec<String>) -> Self {
+        self.allowed_hosts = hosts
+            .into_iter()
+            .map(|host| host_name(host.trim()).to_ascii_lowercase())
+            .filter(|host| !host.is_empty())
+            .collect();
+        self
+    }
+
+    /// Whether the UI may be served for a request with this `Host` header:
+    /// loopback names always, other names only when configured. Requests
+    /// without one did not come from a browser.
+    pub(crate) fn allows_host(&self, host: Option<&str>) -> bool {
+        let Some(host) = host else {
+            return true;
+        };
+        let name = host_name(host);
+        name.eq_ignore_ascii_case("localhost")
+            || name.parse::<IpAddr>().is_ok_and(|addr| addr.is_loopback())
+            || self
+                .allowed_hosts
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(name))
+    }
+
+    pub fn with_tls(mut self, tls: bool) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    pub fn with_headless(mut self, headless: bool) -> Self {
+        self.headless = headless;
+        self
+    }
+
+    pub fn with_api_token_required(mut self, required: bool) -> Self {
+        self.require_api_token = required;
+        self
+    }
+
+    /// Sources such as `https://grafana.example.com` or `'self'`. Entries that
+    /// would break out of the CSP directive are dropped.
+    pub fn with_frame_ancestors(mut self, origins: Vec<String>) -> Self {
+        self.frame_ancestors = origins
+            .into_iter()
+            .map(|origin| origin.trim().to_string())
+            .filter(|origin| {
+                let valid = !origin.is_empty()
+                    && !origin
+                        .chars()
+                        .any(|ch| ch.is_whitespace() || ch.is_control() || ch == ';' || ch == ',');
+                if !valid {
+                    tracing::warn!(origin = %origin, "ignoring invalid frame ancestor");
+                }
+                valid
+            })
+            .collect();
+        self
+    }
+}
+
+/// `host[:port]` or `[v6][:port]` without the port and brackets.
+pub(crate) fn host_name(host: &str) -> &str {
+    if let Some(rest) = host.strip_prefix('[') {
+        return rest.split_once(']').map_or(rest, |(name, _)| name);
+    }
+    match host.split_once(':') {
+        Some((name, port)) if !port.contains(':') => name,
+        _ => host,
+    }
 }
 
 pub fn generate_csrf_token() -> String {
@@ -14,9 +14,18 @@ const TEST_TOKEN: &str = "testtoken";
 struct TestApp {
     _temp_dir: tempfile::TempDir,
     router: axum::Router,
+    context: AppContext,
 }
 
 fn build_app() -> TestApp {
+    build_app_with_token_auth(false)
+}
+
+fn build_app_with_token_auth(require_api_token: bool) -> TestApp {
+    build_app_with(|state| state.with_api_token_required(require_api_token))
+}
+
+fn build_app_with(configure: impl FnOnce(HttpState) -> HttpState) -> TestApp {
     let temp_dir = tempfile::tempdir().expect("tempdir");
     let paths = AppPaths::new(temp_dir.path().to_path_buf());
     ensure_app_data_dir(&paths).expect("ensure app data dir");
@@ -28,12 +37,13 @@ fn build_app() -> TestApp {
         app_data_dir: paths.app_data_dir,
         legacy_backup_dir: None,
     };
-    let state = HttpState::new(context, TEST_TOKEN.to_string());
+    let state = configure(HttpState::new(context.clone(), TEST_TOKEN.to_string()));
     let router = http_api::router(state);
 
     TestApp {
         _temp_dir: temp_dir,
         router,
+        context,
     }
 }
 
@@ -128,3 +138,1434 @@ async fn api_allows_valid_csrf() {
     assert!(payload.get("db_path").is_some());
     assert!(payload.get("app_data_dir").is_some());
 }
+
+#[tokio::test]
+async fn openapi_document_is_public_and_lists_routes() {
+    let app = build_app();
+
+    let response = app
+        .router
+        .oneshot(
+            Request::builder()
+                .uri("/api/openapi.json")
+                .body(Body::empty())
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("body")
+        .to_bytes();
+    let payload: Value = serde_json::from_slice(&body).expect("json body");
+    assert!(payload["paths"]["/api/summary"]["post"].is_object());
+    assert!(payload["paths"]["/api/homes_create"]["post"].is_object());
+    assert!(payload["components"]["schemas"]["UsageSummary"].is_object());
+    assert!(payload["components"]["securitySchemes"]["csrf_token"].is_object());
+}
+
+#[tokio::test]
+async fn exports_events_as_parquet() {
+    let app = build_app();
+
+    let response = app
+        .router
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/export/events.parquet")
+                .header(header::CONTENT_TYPE, "application/json")
+                .header("x-codex-token", TEST_TOKEN)
+                .body(Body::from(r#"{"range":"alltime"}"#))
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+    assert_eq!(content_type, "application/vnd.apache.parquet");
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("body")
+        .to_bytes();
+    assert!(body.starts_with(b"PAR1"));
+    assert!(body.ends_with(b"PAR1"));
+}
+
+fn create_api_token(app: &TestApp) -> String {
+    app_api::api_tokens_create(
+        &app.context,
+        app_api::ApiTokensCreateRequest {
+            label: Some("test".to_string()),
+        },
+    )
+    .expect("create token")
+    .secret
+}
+
+#[tokio::test]
+async fn bearer_token_bypasses_csrf() {
+    let app = build_app();
+    let token = create_api_token(&app);
+
+    let response = app
+        .router
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/settings_get")
+                .header(header::CONTENT_TYPE, "application/json")
+                .header(header::AUTHORIZATION, format!("Bearer {token}"))
+                .body(Body::from("{}"))
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn required_token_guards_ui_and_api() {
+    let app = build_app_with_token_auth(true);
+    let token = create_api_token(&app);
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/")
+                .body(Body::empty())
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/health")
+                .body(Body::empty())
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/settings_get")
+                .header(header::CONTENT_TYPE, "application/json")
+                .header("x-codex-token", TEST_TOKEN)
+                .body(Body::from("{}"))
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/?token={token}"))
+                .body(Body::empty())
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let cookie = response
+        .headers()
+        .get(header::SET_COOKIE)
+        .and_then(|value| value.to_str().ok())
+        .expect("set-cookie")
+        .split(';')
+        .next()
+        .expect("cookie pair")
+        .to_string();
+
+    let response = app
+        .router
+        .oneshot(
+            Request::builder()
+                .uri("/")
+                .header(header::COOKIE, cookie)
+                .body(Body::empty())
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn session_cookie_is_secure_only_over_tls() {
+    for tls in [false, true] {
+        let app = build_app_with(|state| state.with_api_token_required(true).with_tls(tls));
+        let token = create_api_token(&app);
+        let response = app
+            .router
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/?token={token}"))
+                    .body(Body::empty())
+                    .expect("request"),
+            )
+            .await
+            .expect("response");
+        let cookie = response
+            .headers()
+            .get(header::SET_COOKIE)
+            .and_then(|value| value.to_str().ok())
+            .expect("set-cookie")
+            .to_string();
+        assert_eq!(cookie.ends_with("; Secure"), tls, "{cookie}");
+    }
+}
+
+#[tokio::test]
+async fn api_accepts_same_origin_from_lan_host() {
+    let app = build_app_with(|state| state.with_allowed_hosts(vec!["192.168.1.20".to_string()]));
+
+    let request = |origin: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/api/settings_get")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::HOST, "192.168.1.20:3845")
+            .header(header::ORIGIN, origin)
+            .header("x-codex-token", TEST_TOKEN)
+            .body(Body::from("{}"))
+            .expect("request")
+    };
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(request("http://192.168.1.20:3845"))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .router
+        .oneshot(request("http://evil.example:3845"))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn loopback_bind_rejects_rebound_host() {
+    let app = build_app();
+    let index = |host: &str| {
+        Request::builder()
+            .uri("/")
+            .header(header::HOST, host)
+            .body(Body::empty())
+            .expect("request")
+    };
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(index("127.0.0.1:3845"))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(index("rebind.example:3845"))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = app
+        .router
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/settings_put")
+                .header(header::CONTENT_TYPE, "application/json")
+                .header(header::HOST, "rebind.example:3845")
+                .header(header::ORIGIN, "http://rebind.example:3845")
+                .header("x-codex-token", TEST_TOKEN)
+                .body(Body::from("{}"))
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn public_routes_reject_rebound_host() {
+    let app = build_app();
+    for uri in [
+        "/api/report?period=day&format=json",
+        "/api/limits/resets.ics",
+        "/badge/today-cost.svg",
+    ] {
+        let get = |host: &str| {
+            Request::builder()
+                .uri(uri)
+                .header(header::HOST, host)
+                .body(Body::empty())
+                .expect("request")
+        };
+        let response = app
+            .router
+            .clone()
+            .oneshot(get("localhost:3845"))
+            .await
+            .expect("response");
+        assert_eq!(response.status(), StatusCode::OK, "{uri}");
+
+        let response = app
+            .router
+            .clone()
+            .oneshot(get("rebind.example:3845"))
+            .await
+            .expect("response");
+        assert_eq!(response.status(), StatusCode::FORBIDDEN, "{uri}");
+    }
+}
+
+fn summary_request(body: &'static str, if_none_match: Option<&str>) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method("POST")
+        .uri("/api/summary")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("x-codex-token", TEST_TOKEN);
+    if let Some(etag) = if_none_match {
+        builder = builder.header(header::IF_NONE_MATCH, etag);
+    }
+    builder.body(Body::from(body)).expect("request")
+}
+
+#[tokio::test]
+async fn summary_honours_if_none_match() {
+    let app = build_app();
+    let body = r#"{"range":"alltime"}"#;
+
+    // The tag rolls over every minute; retry once if the boundary is crossed
+    // between the two requests.
+    let mut revalidated = None;
+    for _ in 0..2 {
+        let response = app
+            .router
+            .clone()
+            .oneshot(summary_request(body, None))
+            .await
+            .expect("response");
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response
+            .headers()
+            .get(header::ETAG)
+            .and_then(|value| value.to_str().ok())
+            .expect("etag")
+            .to_string();
+        assert!(etag.starts_with("W/\""));
+
+        let response = app
+            .router
+            .clone()
+            .oneshot(summary_request(body, Some(&etag)))
+            .await
+            .expect("response");
+        if response.status() == StatusCode::NOT_MODIFIED {
+            revalidated = Some(etag);
+            break;
+        }
+    }
+    let etag = revalidated.expect("304 for unchanged data");
+
+    let response = app
+        .router
+        .oneshot(summary_request(r#"{"range":"today"}"#, Some(&etag)))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn unknown_range_reports_bad_range_code() {
+    let app = build_app();
+
+    let response = app
+        .router
+        .oneshot(summary_request(r#"{"range":"fortnight"}"#, None))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("body")
+        .to_bytes();
+    let payload: Value = serde_json::from_slice(&body).expect("json body");
+    assert_eq!(payload["code"], "bad_range");
+    assert_eq!(payload["message"], "unsupported range fortnight");
+}
+
+#[tokio::test]
+async fn compresses_responses_when_accepted() {
+    let app = build_app();
+
+    let response = app
+        .router
+        .oneshot(
+            Request::builder()
+                .uri("/api/openapi.json")
+                .header(header::ACCEPT_ENCODING, "gzip")
+                .body(Body::empty())
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response
+            .headers()
+            .get(header::CONTENT_ENCODING)
+            .and_then(|value| value.to_str().ok()),
+        Some("gzip")
+    );
+}
+
+#[tokio::test]
+async fn analytics_accept_all_homes() {
+    let app = build_app();
+
+    let request = |uri: &str, body: &'static str| {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header("x-codex-token", TEST_TOKEN)
+            .body(Body::from(body))
+            .expect("request")
+    };
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(request(
+            "/api/summary",
+            r#"{"range":"alltime","home_id":"all"}"#,
+        ))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(request("/api/breakdown_homes", r#"{"home_id":"all"}"#))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("body")
+        .to_bytes();
+    let payload: Value = serde_json::from_slice(&body).expect("json body");
+    assert!(payload.is_array());
+
+    let response = app
+        .router
+        .oneshot(request("/api/context_stats", r#"{"home_id":"all"}"#))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn lookup_endpoints_list_distinct_values() {
+    let app = build_app();
+
+    for uri in ["/api/models", "/api/efforts", "/api/sources"] {
+        let response = app
+            .router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(uri)
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .header("x-codex-token", TEST_TOKEN)
+                    .body(Body::from(r#"{"range":"alltime"}"#))
+                    .expect("request"),
+            )
+            .await
+            .expect("response");
+        assert_eq!(response.status(), StatusCode::OK, "{uri}");
+        assert!(response.headers().contains_key(header::ETAG), "{uri}");
+        let body = response
+            .into_body()
+            .collect()
+            .await
+            .expect("body")
+            .to_bytes();
+        let payload: Value = serde_json::from_slice(&body).expect("json body");
+        assert_eq!(payload, serde_json::json!([]), "{uri}");
+    }
+}
+
+#[tokio::test]
+async fn serves_limit_reset_calendar_feed() {
+    let app = build_app_with_token_auth(true);
+    let token = create_api_token(&app);
+
+    let get = |uri: String| {
+        Request::builder()
+            .uri(uri)
+            .body(Body::empty())
+            .expect("request")
+    };
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(get("/api/limits/resets.ics".to_string()))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Calendar apps cannot send headers, so the query token is accepted as-is.
+    let response = app
+        .router
+        .oneshot(get(format!("/api/limits/resets.ics?token={token}")))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+    assert!(content_type.starts_with("text/calendar"));
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("body")
+        .to_bytes();
+    let body_text = String::from_utf8_lossy(&body);
+    assert!(body_text.starts_with("BEGIN:VCALENDAR\r\n"));
+}
+
+#[tokio::test]
+async fn serves_svg_badges() {
+    let app = build_app();
+
+    let get = |uri: &str| {
+        Request::builder()
+            .uri(uri)
+            .body(Body::empty())
+            .expect("request")
+    };
+
+    for name in ["today-cost", "today-tokens", "limit-5h", "limit-7d"] {
+        let response = app
+            .router
+            .clone()
+            .oneshot(get(&format!("/badge/{name}.svg")))
+            .await
+            .expect("response");
+        assert_eq!(response.status(), StatusCode::OK, "{name}");
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("");
+        assert!(content_type.starts_with("image/svg+xml"));
+        let body = response
+            .into_body()
+            .collect()
+            .await
+            .expect("body")
+            .to_bytes();
+        assert!(body.starts_with(b"<svg"));
+    }
+
+    let response = app
+        .router
+        .oneshot(get("/badge/unknown.svg"))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn serves_usage_report() {
+    let app = build_app();
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/report?period=week&format=html")
+                .body(Body::empty())
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+    assert!(content_type.starts_with("text/html"));
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("body")
+        .to_bytes();
+    let body_text = String::from_utf8_lossy(&body);
+    assert!(body_text.contains("<h1>Codex usage report: last 7 days</h1>"));
+
+    let response = app
+        .router
+        .oneshot(
+            Request::builder()
+                .uri("/api/report?period=year")
+                .body(Body::empty())
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn serves_ccusage_daily_report() {
+    let app = build_app();
+
+    let request = |body: &'static str| {
+        Request::builder()
+            .method("POST")
+            .uri("/api/ccusage")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header("x-codex-token", TEST_TOKEN)
+            .body(Body::from(body))
+            .expect("request")
+    };
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(request(r#"{"report":"daily"}"#))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("body")
+        .to_bytes();
+    let payload: Value = serde_json::from_slice(&body).expect("json body");
+    assert!(payload["daily"].is_array());
+    assert_eq!(payload["totals"]["totalTokens"], 0);
+
+    let response = app
+        .router
+        .oneshot(request(r#"{"report":"monthly"}"#))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn dashboard_returns_every_panel_in_one_response() {
+    let app = build_app();
+    let mut db = app.context.app_state.open_db().expect("open db");
+    let home = db
+        .get_or_create_home("/tmp/codex-home", Some("Default"))
+        .expect("home");
+    db.set_active_home(home.id).expect("active home");
+    let event = tracker_core::UsageEvent {
+        id: "e1".to_string(),
+        ts: "2025-12-19T10:00:00Z".parse().expect("timestamp"),
+        model: "gpt-5.2".to_string(),
+        usage: tracker_core::UsageTotals {
+            input_tokens: 40,
+            cached_input_tokens: 0,
+            output_tokens: 2,
+            reasoning_output_tokens: 0,
+            total_tokens: 42,
+        },
+        context: tracker_core::ContextStatus::default(),
+        cost_usd: None,
+        cost_source: None,
+        reasoning_effort: None,
+        provider: None,
+        source: "source-a".to_string(),
+        session_id: "session-a".to_string(),
+        request_id: None,
+        raw_json: None,
+    };
+    db.insert_usage_events(home.id, &[event])
+        .expect("insert events");
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/dashboard")
+                .header(header::CONTENT_TYPE, "application/json")
+                .header("x-codex-token", TEST_TOKEN)
+                .body(Body::from(
+                    r#"{"start":"2025-12-19T00:00:00Z","end":"2025-12-20T00:00:00Z","bucket":"hour","tz_offset_minutes":0}"#,
+                ))
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("body")
+        .to_bytes();
+    let payload: Value = serde_json::from_slice(&body).expect("json body");
+    assert_eq!(payload["summary"]["total_tokens"], 42);
+    assert_eq!(payload["events"]["total_count"], 1);
+    assert_eq!(payload["events"]["events"][0]["id"], "e1");
+    assert_eq!(payload["models"], serde_json::json!(["gpt-5.2"]));
+    assert_eq!(payload["breakdown"][0]["model"], "gpt-5.2");
+    assert!(payload["tokens_series"].is_array() && payload["cost_series"].is_array());
+}
+
+#[tokio::test]
+async fn event_detail_parses_raw_json() {
+    let app = build_app();
+    let mut db = app.context.app_state.open_db().expect("open db");
+    let home = db
+        .get_or_create_home("/tmp/codex-home", Some("Default"))
+        .expect("home");
+    db.set_active_home(home.id).expect("active home");
+    let event = tracker_core::UsageEvent {
+        id: "e1".to_string(),
+        ts: "2025-12-19T10:00:00Z".parse().expect("timestamp"),
+        model: "gpt-5.2".to_string(),
+        usage: tracker_core::UsageTotals::default(),
+        context: tracker_core::ContextStatus {
+            context_used: 0,
+            context_window: 0,
+        },
+        cost_usd: None,
+        cost_source: None,
+        reasoning_effort: None,
+        provider: None,
+        source: "source-a".to_string(),
+        session_id: "session-a".to_string(),
+        request_id: None,
+        raw_json: Some(r#"{"type":"token_count","info":null}"#.to_string()),
+    };
+    db.insert_usage_events(home.id, &[event])
+        .expect("insert events");
+
+    let get = |uri: &str| {
+        Request::builder()
+            .uri(uri)
+            .header("x-codex-token", TEST_TOKEN)
+            .body(Body::empty())
+            .expect("request")
+    };
+    let response = app
+        .router
+        .clone()
+        .oneshot(get("/api/events/e1"))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("body")
+        .to_bytes();
+    let payload: Value = serde_json::from_slice(&body).expect("json body");
+    assert_eq!(payload["id"], "e1");
+    assert_eq!(payload["model"], "gpt-5.2");
+    assert_eq!(payload["raw"]["type"], "token_count");
+
+    let response = app
+        .router
+        .oneshot(get("/api/events/missing"))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn top_requests_groups_events_by_request_id() {
+    let app = build_app();
+    let mut db = app.context.app_state.open_db().expect("open db");
+    let home = db
+        .get_or_create_home("/tmp/codex-home", Some("Default"))
+        .expect("home");
+    db.set_active_home(home.id).expect("active home");
+    let event = |id: &str, total_tokens: u64| tracker_core::UsageEvent {
+        id: id.to_string(),
+        ts: format!("2025-12-19T10:00:0{}Z", total_tokens / 100)
+            .parse()
+            .expect("timestamp"),
+        model: "gpt-5.2".to_string(),
+        usage: tracker_core::UsageTotals {
+            total_tokens,
+            ..tracker_core::UsageTotals::default()
+        },
+        context: tracker_core::ContextStatus {
+            context_used: 0,
+            context_window: 0,
+        },
+        cost_usd: None,
+        cost_source: None,
+        reasoning_effort: None,
+        provider: None,
+        source: "source-a".to_string(),
+        session_id: "session-a".to_string(),
+        request_id: Some("req-1".to_string()),
+        raw_json: None,
+    };
+    db.insert_usage_events(home.id, &[event("e1", 100), event("e2", 300)])
+        .expect("insert events");
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/top_requests")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("x-codex-token", TEST_TOKEN)
+        .body(Body::from(r#"{"range":"alltime","limit":5}"#))
+        .expect("request");
+    let response = app.router.oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("body")
+        .to_bytes();
+    let payload: Value = serde_json::from_slice(&body).expect("json body");
+    assert_eq!(payload[0]["request_id"], "req-1");
+    assert_eq!(payload[0]["usage"]["total_tokens"], 300);
+    assert_eq!(payload[0]["event_count"], 2);
+}
+
+#[tokio::test]
+async fn activity_by_weekday_averages_over_calendar_days() {
+    let app = build_app();
+    let mut db = app.context.app_state.open_db().expect("open db");
+    let home = db
+        .get_or_create_home("/tmp/codex-home", Some("Default"))
+        .expect("home");
+    db.set_active_home(home.id).expect("active home");
+    let event = |id: &str, ts: &str, source: &str, total_tokens: u64| tracker_core::UsageEvent {
+        id: id.to_string(),
+        ts: ts.parse().expect("timestamp"),
+        model: "gpt-5.2".to_string(),
+        usage: tracker_core::UsageTotals {
+            total_tokens,
+            ..tracker_core::UsageTotals::default()
+        },
+        context: tracker_core::ContextStatus {
+            context_used: 0,
+            context_window: 0,
+        },
+        cost_usd: None,
+        cost_source: None,
+        reasoning_effort: None,
+        provider: None,
+        source: source.to_string(),
+        session_id: source.to_string(),
+        request_id: None,
+        raw_json: None,
+    };
+    db.insert_usage_events(
+        home.id,
+        &[
+            // A Friday and the Monday after, both at 10:00 UTC.
+            event("e1", "2025-12-19T10:00:00Z", "source-a", 100),
+            event("e2", "2025-12-22T10:30:00Z", "source-b", 200),
+        ],
+    )
+    .expect("insert events");
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/activity/by-weekday")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("x-codex-token", TEST_TOKEN)
+        .body(Body::from(
+            r#"{"start":"2025-12-19T00:00:00Z","end":"2025-12-26T00:00:00Z","tz_offset_minutes":0}"#,
+        ))
+        .expect("request");
+    let response = app.router.oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("body")
+        .to_bytes();
+    let payload: Value = serde_json::from_slice(&body).expect("json body");
+    assert_eq!(payload["days"], 7);
+    assert_eq!(payload["weekdays"][0]["label"], "Mon");
+    assert_eq!(payload["weekdays"][0]["avg_tokens"], 200.0);
+    assert_eq!(payload["weekdays"][4]["avg_tokens"], 100.0);
+    assert_eq!(payload["weekdays"][5]["avg_tokens"], 0.0);
+    assert_eq!(payload["hours"][10]["label"], "10:00");
+    assert_eq!(payload["hours"][10]["avg_tokens"], 300.0 / 7.0);
+}
+
+#[tokio::test]
+async fn ingest_cursor_endpoints_list_and_reset() {
+    let app = build_app();
+    let db = app.context.app_state.open_db().expect("open db");
+    let home = db
+        .get_or_create_home("/tmp/codex-home", Some("Default"))
+        .expect("home");
+    db.set_active_home(home.id).expect("active home");
+
+    let post = |uri: &str, body: &'static str| {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header("x-codex-token", TEST_TOKEN)
+            .body(Body::from(body))
+            .expect("request")
+    };
+    let json =
+        |body: axum::body::Bytes| -> Value { serde_json::from_slice(&body).expect("json body") };
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(post("/api/ingest/cursors", "{}"))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.expect("body");
+    assert_eq!(json(body.to_bytes()), serde_json::json!([]));
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(post("/api/ingest/cursors/reset", "{}"))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.expect("body");
+    assert_eq!(json(body.to_bytes()), serde_json::json!({"reset": 0}));
+
+    let response = app
+        .router
+        .oneshot(post(
+            "/api/ingest/cursors/reset",
+            r#"{"file_path":"/tmp/missing.jsonl"}"#,
+        ))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn patch_home_updates_label_and_path() {
+    let app = build_app();
+    let db = app.context.app_state.open_db().expect("open db");
+    let home = db
+        .get_or_create_home("/tmp/codex-home", Some("Default"))
+        .expect("home");
+    db.get_or_create_home("/tmp/other-home", Some("Other"))
+        .expect("other home");
+
+    let patch = |uri: &str, body: &'static str| {
+        Request::builder()
+            .method("PATCH")
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header("x-codex-token", TEST_TOKEN)
+            .body(Body::from(body))
+            .expect("request")
+    };
+    let uri = format!("/api/homes/{}", home.id);
+    let response = app
+        .router
+        .clone()
+        .oneshot(patch(
+            &uri,
+            r#"{"label":" Work ","path":"/tmp/moved-home"}"#,
+        ))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("body")
+        .to_bytes();
+    let payload: Value = serde_json::from_slice(&body).expect("json body");
+    assert_eq!(payload["label"], "Work");
+    assert_eq!(payload["path"], "/tmp/moved-home");
+    assert_eq!(
+        payload["last_seen_at"].as_str(),
+        home.last_seen_at.as_deref()
+    );
+
+    for (body, status) in [
+        (r#"{"path":"/tmp/other-home"}"#, StatusCode::BAD_REQUEST),
+        (r#"{"label":"  "}"#, StatusCode::BAD_REQUEST),
+    ] {
+        let response = app
+            .router
+            .clone()
+            .oneshot(patch(&uri, body))
+            .await
+            .expect("response");
+        assert_eq!(response.status(), status, "{body}");
+    }
+    let response = app
+        .router
+        .oneshot(patch("/api/homes/9999", r#"{"label":"Nope"}"#))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn homes_discover_lists_candidates() {
+    let app = build_app();
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/homes/discover")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("x-codex-token", TEST_TOKEN)
+        .body(Body::from("{}"))
+        .expect("request");
+    let response = app.router.oneshot(request).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("body")
+        .to_bytes();
+    let payload: Value = serde_json::from_slice(&body).expect("json body");
+    assert!(payload.is_array());
+}
+
+#[tokio::test]
+async fn session_timeline_is_not_found_for_unknown_session() {
+    let app = build_app();
+
+    let response = app
+        .router
+        .oneshot(
+            Request::builder()
+                .uri("/api/sessions/nope/timeline")
+                .header("x-codex-token", TEST_TOKEN)
+                .body(Body::empty())
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn limit_snapshots_validate_type_filter() {
+    let app = build_app();
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/limits/snapshots?type=7d&range=alltime&limit=10")
+                .header("x-codex-token", TEST_TOKEN)
+                .body(Body::empty())
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("body")
+        .to_bytes();
+    let snapshots: Value = serde_json::from_slice(&body).expect("json");
+    assert_eq!(snapshots, serde_json::json!([]));
+
+    let response = app
+        .router
+        .oneshot(
+            Request::builder()
+                .uri("/api/limits/snapshots?type=1d")
+                .header("x-codex-token", TEST_TOKEN)
+                .body(Body::empty())
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn limits_latest_reports_used_percent_and_status() {
+    let app = build_app();
+    let mut db = app.context.app_state.open_db().expect("open db");
+    let home = db
+        .get_or_create_home("/tmp/codex-home", Some("Default"))
+        .expect("home");
+    db.set_active_home(home.id).expect("active home");
+    let now = chrono::Utc::now();
+    let snapshot = tracker_core::UsageLimitSnapshot {
+        limit_type: "5h".to_string(),
+        percent_left: 20.0,
+        reset_at: (now + chrono::Duration::minutes(192)).into(),
+        observed_at: now.into(),
+        source: "source-a".to_string(),
+        raw_line: None,
+    };
+    db.insert_limit_snapshots(home.id, &[snapshot])
+        .expect("insert snapshot");
+
+    let post = |uri: &str, body: &'static str| {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header("x-codex-token", TEST_TOKEN)
+            .body(Body::from(body))
+            .expect("request")
+    };
+    let read = |response: axum::response::Response| async move {
+        let body = response
+            .into_body()
+            .collect()
+            .await
+            .expect("body")
+            .to_bytes();
+        serde_json::from_slice::<Value>(&body).expect("json")
+    };
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(post("/api/limits_latest", "{}"))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let payload = read(response).await;
+    let primary = &payload["primary"];
+    assert_eq!(primary["percent_left"], 20.0);
+    assert_eq!(primary["used_percent"], 80.0);
+    assert_eq!(primary["status"], "warn");
+    let resets_in_secs = primary["resets_in_secs"].as_i64().expect("seconds");
+    assert!((11_500..=11_520).contains(&resets_in_secs));
+    assert_eq!(primary["resets_in"], "3h 11m");
+    assert_eq!(payload["secondary"], Value::Null);
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(post(
+            "/api/settings_put",
+            r#"{"limit_warn_percent":50,"limit_critical_percent":80}"#,
+        ))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let settings = read(response).await;
+    assert_eq!(settings["limit_critical_percent"], 80);
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(post("/api/limits_latest", "{}"))
+        .await
+        .expect("response");
+    assert_eq!(read(response).await["primary"]["status"], "critical");
+
+    let response = app
+        .router
+        .oneshot(post("/api/settings_put", r#"{"limit_warn_percent":95}"#))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn health_reports_build_version() {
+    let app = build_app();
+
+    let response = app
+        .router
+        .oneshot(
+            Request::builder()
+                .uri("/api/health")
+                .body(Body::empty())
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("body")
+        .to_bytes();
+    let health: Value = serde_json::from_slice(&body).expect("json");
+    assert_eq!(health["status"], "ok");
+    assert_eq!(health["version"]["version"], env!("CARGO_PKG_VERSION"));
+    assert!(health["version"]["schema_version"].as_u64().unwrap_or(0) > 0);
+    assert!(health["version"]["git_sha"].is_string());
+}
+
+#[tokio::test]
+async fn sets_security_headers() {
+    let app = build_app();
+
+    for uri in ["/", "/api/version"] {
+        let response = app
+            .router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(uri)
+                    .body(Body::empty())
+                    .expect("request"),
+            )
+            .await
+            .expect("response");
+        let headers = response.headers();
+        let csp = headers
+            .get(header::CONTENT_SECURITY_POLICY)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("");
+        assert!(csp.contains("script-src 'self' 'sha256-"), "{uri}: {csp}");
+        assert!(csp.contains("frame-ancestors 'none'"), "{uri}: {csp}");
+        assert_eq!(headers[header::X_FRAME_OPTIONS], "DENY");
+        assert_eq!(headers[header::REFERRER_POLICY], "no-referrer");
+        assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+    }
+}
+
+#[tokio::test]
+async fn frame_ancestors_allow_embedding() {
+    let app = build_app();
+    let state =
+        HttpState::new(app.context.clone(), TEST_TOKEN.to_string()).with_frame_ancestors(vec![
+            "https://grafana.example.com".to_string(),
+            "https://evil.example.com; script-src *".to_string(),
+        ]);
+    let router = http_api::router(state);
+
+    let response = router
+        .oneshot(
+            Request::builder()
+                .uri("/")
+                .body(Body::empty())
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+    let headers = response.headers();
+    let csp = headers
+        .get(header::CONTENT_SECURITY_POLICY)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+    assert!(csp.ends_with("frame-ancestors 'self' https://grafana.example.com"));
+    assert!(headers.get(header::X_FRAME_OPTIONS).is_none());
+}
+
+#[tokio::test]
+async fn headless_mode_serves_only_the_api() {
+    let app = build_app();
+    let state = HttpState::new(app.context.clone(), TEST_TOKEN.to_string()).with_headless(true);
+    let router = http_api::router(state);
+
+    for uri in ["/", "/sessions", "/badge/today-cost.svg"] {
+        let response = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(uri)
+                    .body(Body::empty())
+                    .expect("request"),
+            )
+            .await
+            .expect("response");
+        assert_eq!(response.status(), StatusCode::NOT_FOUND, "{uri}");
+    }
+
+    let response = router
+        .oneshot(
+            Request::builder()
+                .uri("/api/health")
+                .body(Body::empty())
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn graphql_selects_requested_fields() {
+    let app = build_app();
+
+    let query = serde_json::json!({
+        "query": "{ summary(range: { range: \"alltime\" }) { totalTokens } limitSnapshots(limitType: \"7d\") { percentLeft } pricing { modelPattern } }"
+    });
+    let response = app
+        .router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/graphql")
+                .header("content-type", "application/json")
+                .header("x-codex-token", TEST_TOKEN)
+                .body(Body::from(query.to_string()))
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("body")
+        .to_bytes();
+    let payload: Value = serde_json::from_slice(&body).expect("json");
+    assert!(payload.get("errors").is_none(), "{payload}");
+    assert_eq!(
+        payload["data"]["summary"],
+        serde_json::json!({ "totalTokens": 0 })
+    );
+    assert_eq!(payload["data"]["limitSnapshots"], serde_json::json!([]));
+    assert!(payload["data"]["pricing"].is_array());
+
+    let response = app
+        .router
+        .oneshot(
+            Request::builder()
+                .uri("/api/graphql/schema.graphql")
+                .body(Body::empty())
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("body")
+        .to_bytes();
+    assert!(String::from_utf8_lossy(&body).contains("type QueryRoot"));
+}
+
+#[tokio::test]
+async fn refresh_returns_summary_including_its_own_ingest() {
+    let app = build_app();
+    let codex_home = app._temp_dir.path().join("codex");
+    let log_dir = codex_home.join("sessions/2025/12/19");
+    std::fs::create_dir_all(&log_dir).expect("create log dir");
+    let line = r#"{"timestamp":"2025-12-19T21:31:36.168Z","type":"event_msg","payload":{"type":"token_count","info":{"model":"gpt-test","total_token_usage":{"input_tokens":1,"cached_input_tokens":0,"output_tokens":1,"reasoning_output_tokens":0,"total_tokens":2},"model_context_window":100}}}"#;
+    std::fs::write(
+        log_dir.join("rollout-2025-12-19T21-31-36.jsonl"),
+        format!("{line}\n"),
+    )
+    .expect("write log");
+    let db = app.context.app_state.open_db().expect("open db");
+    let home = db
+        .get_or_create_home(&codex_home.to_string_lossy(), Some("Codex"))
+        .expect("home");
+    db.set_active_home(home.id).expect("active home");
+
+    let range = r#"{"start":"2025-12-19T00:00:00Z","end":"2025-12-20T00:00:00Z"}"#;
+    let post = |uri: &str| {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header("x-codex-token", TEST_TOKEN)
+            .body(Body::from(range))
+            .expect("request")
+    };
+    let read = |response: axum::response::Response| async move {
+        let body = response
+            .into_body()
+            .collect()
+            .await
+            .expect("body")
+            .to_bytes();
+        serde_json::from_slice::<Value>(&body).expect("json")
+    };
+
+    // Warms the summary cache with the pre-ingest totals.
+    let response = app
+        .router
+        .clone()
+        .oneshot(post("/api/summary"))
+        .await
+        .expect("response");
+    assert_eq!(read(response).await["total_tokens"], 0);
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(post("/api/refresh"))
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let payload = read(response).await;
+    assert_eq!(payload["ingest"]["events_inserted"], 1);
+    assert_eq!(payload["recompute"], Value::Null);
+    assert_eq!(payload["summary"]["total_tokens"], 2);
+    assert!(payload["limits"].get("primary").is_some());
+}
@@ -8,8 +8,8 @@ pub use parser::{
     extract_context_from_line, extract_token_totals_from_line, extract_usage_event_from_line,
     extract_usage_totals_from_line, usage_events_from_reader,
 };
-pub use paths::default_codex_home;
-pub use pipeline::ingest_codex_home;
+pub use paths::{DiscoveredHome, default_codex_home, discover_codex_homes};
+pub use pipeline::{ingest_codex_home, ingest_log_files, log_files};
 pub use totals::{
     latest_context_from_reader, total_from_reader, total_from_totals, totals_from_usage,
     usage_totals_from_reader,
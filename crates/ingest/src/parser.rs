@@ -1,11 +1,12 @@
+use std::collections::HashMap;
 use std::fmt::Write;
 
-use chrono::{DateTime, SecondsFormat, Timelike, Utc};
+use chrono::{DateTime, Timelike, Utc};
 use serde_json::Value;
 use sha2::{Digest, Sha256};
 use tracker_core::{
-    ContextStatus, MessageEvent, PricingRule, UsageEvent, UsageLimitSnapshot, UsageTotals,
-    compute_cost_breakdown, model_matches_pattern, session_id_from_source,
+    ContextStatus, CostSource, MessageEvent, PricingRule, Timestamp, UsageEvent,
+    UsageLimitSnapshot, UsageTotals, compute_cost_breakdown, session_id_from_source,
 };
 
 use crate::types::TokenTotals;
@@ -97,21 +98,17 @@ fn find_string<'a>(value: &'a Value, paths: &[&[&str]]) -> Option<&'a str> {
     None
 }
 
-fn normalize_timestamp(raw: &str) -> Option<String> {
+fn normalize_timestamp(raw: &str) -> Option<Timestamp> {
     if let Ok(parsed) = DateTime::parse_from_rfc3339(raw) {
-        return Some(
-            parsed
-                .with_timezone(&Utc)
-                .to_rfc3339_opts(SecondsFormat::Millis, true),
-        );
+        return Some(parsed.with_timezone(&Utc).into());
     }
     if let Ok(parsed) = chrono::NaiveDateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S") {
         let dt = DateTime::<Utc>::from_naive_utc_and_offset(parsed, Utc);
-        return Some(dt.to_rfc3339_opts(SecondsFormat::Millis, true));
+        return Some(dt.into());
     }
     if let Ok(parsed) = chrono::NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S") {
         let dt = DateTime::<Utc>::from_naive_utc_and_offset(parsed, Utc);
-        return Some(dt.to_rfc3339_opts(SecondsFormat::Millis, true));
+        return Some(dt.into());
     }
     if raw.chars().all(|ch| ch.is_ascii_digit())
         && let Ok(value) = raw.parse::<i64>()
@@ -125,13 +122,13 @@ fn normalize_timestamp(raw: &str) -> Option<String> {
             (value, 0)
         };
         if let Some(dt) = DateTime::<Utc>::from_timestamp(secs, nanos) {
-            return Some(dt.to_rfc3339_opts(SecondsFormat::Millis, true));
+            return Some(dt.into());
         }
     }
     None
 }
 
-fn extract_timestamp(value: &Value) -> Option<String> {
+fn extract_timestamp(value: &Value) -> Option<Timestamp> {
     find_string(value, &[&["timestamp"], &["ts"], &["time"]]).and_then(normalize_timestamp)
 }
 
@@ -175,31 +172,26 @@ pub(crate) fn delta_usage(prev: Option<&UsageTotals>, current: UsageTotals) -> U
     }
 }
 
-fn rule_matches_event(rule: &PricingRule, model: &str, ts: &str) -> bool {
-    if !model_matches_pattern(model, &rule.model_pattern) {
-        return false;
-    }
-    if rule.effective_from.as_str() > ts {
-        return false;
-    }
-    if let Some(ref end) = rule.effective_to
-        && ts >= end.as_str()
-    {
-        return false;
-    }
-    true
-}
-
+/// Prices `delta` with the rule for the event's model and provider, the
+/// model looked up under its alias target when `aliases` maps it, and says
+/// whether the rule was a fallback.
 pub(crate) fn compute_cost_for_event(
     pricing: &[PricingRule],
+    aliases: &HashMap<String, String>,
     event: &UsageEvent,
     delta: UsageTotals,
-) -> Option<f64> {
+) -> Option<(f64, CostSource)> {
+    let model = aliases.get(&event.model).unwrap_or(&event.model);
+    // Rule dates are compared as text, as the database does.
+    let ts = event.ts.to_string();
     let rule = pricing
         .iter()
-        .filter(|rule| rule_matches_event(rule, &event.model, &event.ts))
-        .max_by(|a, b| a.effective_from.cmp(&b.effective_from))?;
-    Some(compute_cost_breakdown(delta, rule).total_cost_usd)
+        .filter(|rule| rule.matches(model, event.provider.as_deref(), &ts))
+        .max_by(|a, b| a.precedence().cmp(&b.precedence()))?;
+    Some((
+        compute_cost_breakdown(delta, rule).total_cost_usd,
+        CostSource::for_rule(rule),
+    ))
 }
 
 pub(crate) fn extract_effort_if_turn_context(value: &Value) -> Option<String> {
@@ -214,6 +206,69 @@ pub(crate) fn extract_effort_if_turn_context(value: &Value) -> Option<String> {
     extract_effort(value)
 }
 
+/// The session id Codex records in a `session_meta` line, which survives the
+/// log file being renamed or copied.
+pub(crate) fn extract_session_meta_id(value: &Value) -> Option<String> {
+    if value.get("type")?.as_str()? != "session_meta" {
+        return None;
+    }
+    let id = value.get("payload")?.get("id")?.as_str()?.trim();
+    (!id.is_empty()).then(|| id.to_string())
+}
+
+/// When Codex logged a `shutdown_complete` event, which it does as a session
+/// exits normally.
+pub(crate) fn extract_shutdown_ts(value: &Value) -> Option<Timestamp> {
+    if value.get("type")?.as_str()? != "event_msg" {
+        return None;
+    }
+    if value.get("payload")?.get("type")?.as_str()? != "shutdown_complete" {
+        return None;
+    }
+    extract_timestamp(value)
+}
+
+/// The provider named in a `session_meta` line: its `model_provider`, or
+/// one recognised from a `base_url` when Codex only logged the endpoint.
+/// Lowercased, so `OpenAI` and `openai` count as one provider.
+pub(crate) fn extract_session_meta_provider(value: &Value) -> Option<String> {
+    if value.get("type")?.as_str()? != "session_meta" {
+        return None;
+    }
+    let payload = value.get("payload")?;
+    if let Some(provider) = find_string(payload, &[&["model_provider"], &["provider"]]) {
+        let provider = provider.trim();
+        if !provider.is_empty() {
+            return Some(provider.to_ascii_lowercase());
+        }
+    }
+    let base_url = find_string(payload, &[&["base_url"], &["model_provider_base_url"]])?;
+    provider_from_base_url(base_url)
+}
+
+fn provider_from_base_url(base_url: &str) -> Option<String> {
+    let host = base_url
+        .split("://")
+        .nth(1)
+        .unwrap_or(base_url)
+        .split(['/', ':'])
+        .next()?
+        .to_ascii_lowercase();
+    if host.is_empty() {
+        return None;
+    }
+    let known = [
+        ("azure.com", "azure"),
+        ("openrouter.ai", "openrouter"),
+        ("openai.com", "openai"),
+    ];
+    let provider = known
+        .iter()
+        .find(|(suffix, _)| host == *suffix || host.ends_with(&format!(".{suffix}")))
+        .map_or(host.as_str(), |(_, provider)| provider);
+    Some(provider.to_string())
+}
+
 fn extract_request_id(value: &Value) -> Option<String> {
     find_string(
         value,
@@ -307,30 +362,28 @@ fn normalize_reset_at(dt: DateTime<Utc>) -> DateTime<Utc> {
         .unwrap_or(dt)
 }
 
-fn parse_reset_at(raw: &Value, reference_ts: &str) -> Option<String> {
-    let reference = DateTime::parse_from_rfc3339(reference_ts)
-        .ok()
-        .map(|dt| dt.with_timezone(&Utc))?;
+fn parse_reset_at(raw: &Value, reference_ts: Timestamp) -> Option<Timestamp> {
+    let reference = reference_ts.as_datetime();
     if let Some(value) = raw.as_str() {
         if let Ok(parsed) = DateTime::parse_from_rfc3339(value) {
             let dt = normalize_reset_at(parsed.with_timezone(&Utc));
-            return Some(dt.to_rfc3339_opts(SecondsFormat::Millis, true));
+            return Some(dt.into());
         }
         if let Ok(parsed) = chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S") {
             let dt = normalize_reset_at(DateTime::<Utc>::from_naive_utc_and_offset(parsed, Utc));
-            return Some(dt.to_rfc3339_opts(SecondsFormat::Millis, true));
+            return Some(dt.into());
         }
         if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
             let time = chrono::NaiveTime::from_hms_opt(0, 0, 0)?;
             let parsed = chrono::NaiveDateTime::new(date, time);
             let dt = normalize_reset_at(DateTime::<Utc>::from_naive_utc_and_offset(parsed, Utc));
-            return Some(dt.to_rfc3339_opts(SecondsFormat::Millis, true));
+            return Some(dt.into());
         }
         if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y/%m/%d") {
             let time = chrono::NaiveTime::from_hms_opt(0, 0, 0)?;
             let parsed = chrono::NaiveDateTime::new(date, time);
             let dt = normalize_reset_at(DateTime::<Utc>::from_naive_utc_and_offset(parsed, Utc));
-            return Some(dt.to_rfc3339_opts(SecondsFormat::Millis, true));
+            return Some(dt.into());
         }
         if let Ok(time) = chrono::NaiveTime::parse_from_str(value, "%H:%M:%S") {
             let mut parsed = chrono::NaiveDateTime::new(reference.date_naive(), time);
@@ -340,7 +393,7 @@ fn parse_reset_at(raw: &Value, reference_ts: &str) -> Option<String> {
                 dt = DateTime::<Utc>::from_naive_utc_and_offset(parsed, Utc);
             }
             let dt = normalize_reset_at(dt);
-            return Some(dt.to_rfc3339_opts(SecondsFormat::Millis, true));
+            return Some(dt.into());
         }
         if let Ok(time) = chrono::NaiveTime::parse_from_str(value, "%H:%M") {
             let mut parsed = chrono::NaiveDateTime::new(reference.date_naive(), time);
@@ -350,7 +403,7 @@ fn parse_reset_at(raw: &Value, reference_ts: &str) -> Option<String> {
                 dt = DateTime::<Utc>::from_naive_utc_and_offset(parsed, Utc);
             }
             let dt = normalize_reset_at(dt);
-            return Some(dt.to_rfc3339_opts(SecondsFormat::Millis, true));
+            return Some(dt.into());
         }
         return None;
     }
@@ -364,7 +417,7 @@ fn parse_reset_at(raw: &Value, reference_ts: &str) -> Option<String> {
     let nanos = ((seconds - secs as f64) * 1_000_000_000.0) as u32;
     let parsed = DateTime::<Utc>::from_timestamp(secs, nanos)?;
     let dt = normalize_reset_at(parsed);
-    Some(dt.to_rfc3339_opts(SecondsFormat::Millis, true))
+    Some(dt.into())
 }
 
 fn extract_rate_limits(value: &Value) -> Option<&Value> {
@@ -401,7 +454,7 @@ fn extract_percent_left(limit: &Value) -> Option<f64> {
     None
 }
 
-fn extract_reset_at(limit: &Value, reference_ts: &str) -> Option<String> {
+fn extract_reset_at(limit: &Value, reference_ts: Timestamp) -> Option<Timestamp> {
     for key in [
         "reset_at",
         "resets_at",
@@ -461,7 +514,7 @@ pub(crate) fn extract_limit_snapshots_from_value(
                 Some(value) => value,
                 None => continue,
             };
-            let reset_at = match extract_reset_at(value, &observed_at) {
+            let reset_at = match extract_reset_at(value, observed_at) {
                 Some(value) => value,
                 None => continue,
             };
@@ -469,7 +522,7 @@ pub(crate) fn extract_limit_snapshots_from_value(
                 limit_type: limit_type.to_string(),
                 percent_left,
                 reset_at,
-                observed_at: observed_at.clone(),
+                observed_at,
                 source: source.to_string(),
                 raw_line: Some(line.to_string()),
             });
@@ -646,7 +699,9 @@ pub(crate) fn extract_usage_event_from_value(
         usage,
         context,
         cost_usd: None,
+        cost_source: None,
         reasoning_effort: effort,
+        provider: None,
         source: source.to_string(),
         session_id: session_id.to_string(),
         request_id,
@@ -657,26 +712,35 @@ pub(crate) fn extract_usage_event_from_value(
 pub fn usage_events_from_reader<R: std::io::BufRead>(reader: R, source: &str) -> Vec<UsageEvent> {
     let mut current_model: Option<String> = None;
     let mut current_effort: Option<String> = None;
-    let session_id = session_id_from_source(source);
+    let mut session_id = session_id_from_source(source);
+    let mut provider: Option<String> = None;
     reader
         .lines()
         .map_while(|line| line.ok())
         .filter_map(|line| {
             let obj = parse_json_line(&line)?;
+            if let Some(id) = extract_session_meta_id(&obj) {
+                session_id = id;
+            }
+            if let Some(name) = extract_session_meta_provider(&obj) {
+                provider = Some(name);
+            }
             if let Some(model) = extract_model(&obj) {
                 current_model = Some(model);
             }
             if let Some(effort) = extract_effort_if_turn_context(&obj) {
                 current_effort = Some(effort);
             }
-            extract_usage_event_from_value(
+            let mut event = extract_usage_event_from_value(
                 &obj,
                 &line,
                 source,
                 current_model.as_deref(),
                 &session_id,
                 current_effort.as_deref(),
-            )
+            )?;
+            event.provider = provider.clone();
+            Some(event)
         })
         .collect()
 }
@@ -698,6 +762,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn session_meta_provider_comes_from_name_or_base_url() {
+        let provider = |payload: &str| {
+            let line = format!(r#"{{"type":"session_meta","payload":{payload}}}"#);
+            extract_session_meta_provider(&parse_json_line(&line).expect("json"))
+        };
+        assert_eq!(
+            provider(r#"{"model_provider":"OpenRouter"}"#).as_deref(),
+            Some("openrouter")
+        );
+        assert_eq!(
+            provider(r#"{"base_url":"https://team.openai.azure.com/openai"}"#).as_deref(),
+            Some("azure")
+        );
+        assert_eq!(
+            provider(r#"{"base_url":"http://localhost:11434/v1"}"#).as_deref(),
+            Some("localhost")
+        );
+        assert_eq!(provider(r#"{"id":"abc"}"#), None);
+    }
+
+    #[test]
+    fn shutdown_events_carry_their_time() {
+        let shutdown = r#"{"timestamp":"2025-12-19T22:05:00.000Z","type":"event_msg","payload":{"type":"shutdown_complete"}}"#;
+        assert_eq!(
+            extract_shutdown_ts(&parse_json_line(shutdown).expect("json")),
+            Some("2025-12-19T22:05:00Z".parse().expect("timestamp"))
+        );
+        let message = r#"{"timestamp":"2025-12-19T22:05:00.000Z","type":"event_msg","payload":{"type":"user_message"}}"#;
+        assert_eq!(
+            extract_shutdown_ts(&parse_json_line(message).expect("json")),
+            None
+        );
+    }
+
     #[test]
     fn ignores_non_token_lines() {
         let line = r#"{"timestamp":"2025-12-19T21:31:32.694Z","type":"session_meta","payload":{"id":"abc"}}"#;
@@ -738,7 +837,7 @@ mod tests {
         let line = r#"{"timestamp":"2025-12-19T21:31:36.168Z","type":"event_msg","payload":{"type":"token_count","info":{"model":"gpt-test","total_token_usage":{"input_tokens":10,"cached_input_tokens":1,"output_tokens":2,"reasoning_output_tokens":0,"total_tokens":12},"model_context_window":100}}}"#;
         let event = extract_usage_event_from_line(line, "test.log", None, "session-1", None)
             .expect("event");
-        assert_eq!(event.ts, "2025-12-19T21:31:36.168Z");
+        assert_eq!(event.ts.to_string(), "2025-12-19T21:31:36.168Z");
         assert_eq!(event.model, "gpt-test");
         assert_eq!(event.context.context_used, 12);
         assert_eq!(event.context.context_window, 100);
@@ -753,7 +852,7 @@ mod tests {
         let line = r#"{"timestamp":"2025-12-19T21:31:36+02:00","type":"event_msg","payload":{"type":"token_count","info":{"model":"gpt-test","total_token_usage":{"input_tokens":10,"cached_input_tokens":1,"output_tokens":2,"reasoning_output_tokens":0,"total_tokens":12},"model_context_window":100}}}"#;
         let event = extract_usage_event_from_line(line, "test.log", None, "session-1", None)
             .expect("event");
-        assert_eq!(event.ts, "2025-12-19T19:31:36.000Z");
+        assert_eq!(event.ts.to_string(), "2025-12-19T19:31:36.000Z");
     }
 
     #[test]
@@ -811,7 +910,7 @@ mod tests {
         let line = r#"{"timestamp":"2025-01-01T00:00:00Z","type":"event_msg","payload":{"type":"user_message","info":{"role":"user","content":"Hello"}}}"#;
         let event =
             extract_message_event_from_line(line, "test.log", "session-1").expect("message event");
-        assert_eq!(event.ts, "2025-01-01T00:00:00.000Z");
+        assert_eq!(event.ts.to_string(), "2025-01-01T00:00:00.000Z");
         assert_eq!(event.role, "user");
         assert_eq!(event.source, "test.log");
         assert_eq!(event.session_id, "session-1");
@@ -841,13 +940,13 @@ mod tests {
             .find(|snap| snap.limit_type == "5h")
             .expect("primary");
         assert!((primary.percent_left - 75.0).abs() < 1e-6);
-        assert_eq!(primary.reset_at, "2025-01-01T05:00:00.000Z");
+        assert_eq!(primary.reset_at.to_string(), "2025-01-01T05:00:00.000Z");
         let secondary = snapshots
             .iter()
             .find(|snap| snap.limit_type == "7d")
             .expect("secondary");
         assert!((secondary.percent_left - 40.0).abs() < 1e-6);
-        assert_eq!(secondary.reset_at, "2025-01-08T00:00:00.000Z");
+        assert_eq!(secondary.reset_at.to_string(), "2025-01-08T00:00:00.000Z");
     }
 
     #[test]
@@ -855,7 +954,10 @@ mod tests {
         let line = r#"{"timestamp":"2025-01-01T04:00:00Z","type":"event_msg","payload":{"rate_limits":{"primary":{"remaining":0.5,"resets_at":"05:30"}}}}"#;
         let snapshots = extract_limit_snapshots_from_line(line, "test.log");
         assert_eq!(snapshots.len(), 1);
-        assert_eq!(snapshots[0].reset_at, "2025-01-01T05:30:00.000Z");
+        assert_eq!(
+            snapshots[0].reset_at.to_string(),
+            "2025-01-01T05:30:00.000Z"
+        );
         assert!((snapshots[0].percent_left - 50.0).abs() < 1e-6);
     }
 }
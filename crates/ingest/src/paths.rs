@@ -1,4 +1,8 @@
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::pipeline::log_files;
 
 pub fn default_codex_home() -> PathBuf {
     if let Ok(path) = std::env::var("CODEX_HOME") {
@@ -9,3 +13,116 @@ pub fn default_codex_home() -> PathBuf {
     }
     PathBuf::from(".codex")
 }
+
+/// A directory found by [`discover_codex_homes`] that holds session logs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredHome {
+    pub path: PathBuf,
+    pub log_files: usize,
+}
+
+/// Looks for codex homes where Codex usually writes them: `$CODEX_HOME`,
+/// `~/.codex`, the `.codex` of other user profiles next to ours, and Windows
+/// profiles mounted under `/mnt` in WSL. Only directories with session logs
+/// are returned, most logs first.
+pub fn discover_codex_homes() -> Vec<DiscoveredHome> {
+    let user_home = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from);
+    let candidates = candidate_dirs(
+        std::env::var_os("CODEX_HOME").map(PathBuf::from),
+        user_home,
+        Path::new("/mnt"),
+    );
+    scan(candidates)
+}
+
+fn candidate_dirs(
+    codex_home: Option<PathBuf>,
+    user_home: Option<PathBuf>,
+    wsl_mounts: &Path,
+) -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    candidates.extend(codex_home);
+    if let Some(user_home) = user_home {
+        candidates.push(user_home.join(".codex"));
+        if let Some(profiles) = user_home.parent() {
+            candidates.extend(subdirs(profiles).map(|profile| profile.join(".codex")));
+        }
+    }
+    // WSL mounts Windows drives as /mnt/<letter>, with profiles under Users.
+    for drive in subdirs(wsl_mounts) {
+        candidates.extend(subdirs(&drive.join("Users")).map(|profile| profile.join(".codex")));
+    }
+    candidates
+}
+
+fn scan(candidates: Vec<PathBuf>) -> Vec<DiscoveredHome> {
+    let mut seen = HashSet::new();
+    let mut found = Vec::new();
+    for candidate in candidates {
+        let Ok(path) = fs::canonicalize(&candidate) else {
+            continue;
+        };
+        if !seen.insert(path.clone()) {
+            continue;
+        }
+        let log_files = log_files(&path).len();
+        if log_files > 0 {
+            found.push(DiscoveredHome { path, log_files });
+        }
+    }
+    found.sort_by_key(|home| std::cmp::Reverse(home.log_files));
+    found
+}
+
+fn subdirs(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .map(|entry| entry.path())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_log(home: &Path, name: &str) {
+        let dir = home.join("sessions/2025/01/01");
+        fs::create_dir_all(&dir).expect("create sessions dir");
+        fs::write(dir.join(name), "{}\n").expect("write log");
+    }
+
+    #[test]
+    fn discovery_finds_profiles_and_wsl_mounts_with_logs() {
+        let root = tempfile::tempdir().expect("tempdir");
+        let profiles = root.path().join("home");
+        let me = profiles.join("me");
+        write_log(&me.join(".codex"), "rollout-a.jsonl");
+        write_log(&profiles.join("other/.codex"), "rollout-b.jsonl");
+        write_log(&profiles.join("other/.codex"), "rollout-c.jsonl");
+        // A .codex without session logs is not a candidate.
+        fs::create_dir_all(profiles.join("empty/.codex")).expect("create empty");
+        let mounts = root.path().join("mnt");
+        write_log(&mounts.join("c/Users/win/.codex"), "rollout-d.jsonl");
+
+        // $CODEX_HOME pointing at ~/.codex is reported once.
+        let found = scan(candidate_dirs(
+            Some(me.join(".codex")),
+            Some(me.clone()),
+            &mounts,
+        ));
+        let canonical = |path: PathBuf| fs::canonicalize(path).expect("canonicalize");
+        let paths = found
+            .iter()
+            .map(|home| home.path.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(found.len(), 3);
+        assert_eq!(paths[0], canonical(profiles.join("other/.codex")));
+        assert_eq!(found[0].log_files, 2);
+        assert!(paths.contains(&canonical(me.join(".codex"))));
+        assert!(paths.contains(&canonical(mounts.join("c/Users/win/.codex"))));
+    }
+}
@@ -1,18 +1,22 @@
+use std::collections::HashMap;
+use std::fs;
 use std::fs::File;
 use std::io::{self, BufRead, BufReader, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 use std::time::{Duration as StdDuration, Instant};
-use std::{env, fs};
 
 use chrono::{DateTime, Utc};
 use rayon::prelude::*;
-use tracker_core::{MessageEvent, PricingRule, UsageEvent, UsageLimitSnapshot, UsageTotals};
+use tracker_core::{
+    MessageEvent, PricingRule, Timestamp, UsageEvent, UsageLimitSnapshot, UsageTotals,
+};
 use tracker_db::{Db, IngestCursor};
 use walkdir::WalkDir;
 
 use crate::parser::{
     compute_cost_for_event, delta_usage, extract_effort_if_turn_context,
     extract_limit_snapshots_from_value, extract_message_event_from_value, extract_model,
+    extract_session_meta_id, extract_session_meta_provider, extract_shutdown_ts,
     extract_usage_event_from_value, parse_json_line,
 };
 use crate::types::{IngestIssue, IngestStats, Result};
@@ -24,6 +28,18 @@ fn is_log_path(path: &Path) -> bool {
     )
 }
 
+/// The log files ingest would read under `codex_home`, in walk order.
+/// Unreadable entries are left out.
+pub fn log_files(codex_home: &Path) -> Vec<PathBuf> {
+    WalkDir::new(codex_home.join("sessions"))
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file() && is_log_path(entry.path()))
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
 fn is_plain_log(path: &Path) -> bool {
     matches!(
         path.extension().and_then(|value| value.to_str()),
@@ -57,6 +73,8 @@ struct FileTask {
     start_offset: u64,
     seed_model: Option<String>,
     seed_effort: Option<String>,
+    seed_session_id: Option<String>,
+    seed_provider: Option<String>,
     prev_usage: Option<UsageTotals>,
 }
 
@@ -68,10 +86,15 @@ struct ParsedFile {
     bytes_read: u64,
     events: Vec<UsageEvent>,
     message_events: Vec<MessageEvent>,
+    /// `(session_id, ts)` of logged shutdowns.
+    session_ends: Vec<(String, Timestamp)>,
     limit_snapshots: Vec<UsageLimitSnapshot>,
     issues: Vec<IngestIssue>,
     last_model: Option<String>,
     last_effort: Option<String>,
+    /// From the file's `session_meta` line, if one has been read.
+    session_id: Option<String>,
+    provider: Option<String>,
     last_event_key: Option<String>,
     skipped: bool,
     parse_duration: StdDuration,
@@ -80,8 +103,8 @@ struct ParsedFile {
 fn parse_file(
     task: FileTask,
     pricing: &[PricingRule],
+    aliases: &HashMap<String, String>,
     has_pricing: bool,
-    timing_enabled: bool,
 ) -> ParsedFile {
     let file_start = Instant::now();
     let mut issues = Vec::new();
@@ -89,8 +112,11 @@ fn parse_file(
     let mut events = Vec::new();
     let mut limit_snapshots = Vec::new();
     let mut message_events = Vec::new();
+    let mut session_ends = Vec::new();
     let mut current_model = task.seed_model;
     let mut current_effort = task.seed_effort;
+    let mut meta_session_id = task.seed_session_id;
+    let mut provider = task.seed_provider;
     let mut prev_usage = task.prev_usage;
 
     let mut file = match File::open(&task.path) {
@@ -108,10 +134,13 @@ fn parse_file(
                 bytes_read,
                 events,
                 message_events,
+                session_ends,
                 limit_snapshots,
                 issues,
                 last_model: current_model,
                 last_effort: current_effort,
+                session_id: meta_session_id,
+                provider,
                 last_event_key: None,
                 skipped: true,
                 parse_duration: file_start.elapsed(),
@@ -131,10 +160,13 @@ fn parse_file(
                     bytes_read,
                     events,
                     message_events,
+                    session_ends,
                     limit_snapshots,
                     issues,
                     last_model: current_model,
                     last_effort: current_effort,
+                    session_id: meta_session_id,
+                    provider,
                     last_event_key: None,
                     skipped: true,
                     parse_duration: file_start.elapsed(),
@@ -153,10 +185,13 @@ fn parse_file(
                     bytes_read,
                     events,
                     message_events,
+                    session_ends,
                     limit_snapshots,
                     issues,
                     last_model: current_model,
                     last_effort: current_effort,
+                    session_id: meta_session_id,
+                    provider,
                     last_event_key: None,
                     skipped: true,
                     parse_duration: file_start.elapsed(),
@@ -178,10 +213,13 @@ fn parse_file(
             bytes_read,
             events,
             message_events,
+            session_ends,
             limit_snapshots,
             issues,
             last_model: current_model,
             last_effort: current_effort,
+            session_id: meta_session_id,
+            provider,
             last_event_key: None,
             skipped: true,
             parse_duration: file_start.elapsed(),
@@ -190,7 +228,9 @@ fn parse_file(
 
     let mut reader = BufReader::new(file);
     let mut buf = String::new();
-    let session_id = tracker_core::session_id_from_source(&task.file_path);
+    let mut session_id = meta_session_id
+        .clone()
+        .unwrap_or_else(|| tracker_core::session_id_from_source(&task.file_path));
 
     loop {
         match reader.read_line(&mut buf) {
@@ -202,6 +242,13 @@ fn parse_file(
                     buf.clear();
                     continue;
                 };
+                if let Some(id) = extract_session_meta_id(&obj) {
+                    session_id = id.clone();
+                    meta_session_id = Some(id);
+                }
+                if let Some(name) = extract_session_meta_provider(&obj) {
+                    provider = Some(name);
+                }
                 if let Some(model) = extract_model(&obj) {
                     current_model = Some(model);
                 }
@@ -216,11 +263,14 @@ fn parse_file(
                     &session_id,
                     current_effort.as_deref(),
                 ) {
+                    event.provider = provider.clone();
                     let delta = delta_usage(prev_usage.as_ref(), event.usage);
                     if has_pricing
-                        && let Some(cost) = compute_cost_for_event(pricing, &event, delta)
+                        && let Some((cost, source)) =
+                            compute_cost_for_event(pricing, aliases, &event, delta)
                     {
                         event.cost_usd = Some(cost);
+                        event.cost_source = Some(source);
                     }
                     prev_usage = Some(event.usage);
                     events.push(event);
@@ -230,6 +280,9 @@ fn parse_file(
                 {
                     message_events.push(event);
                 }
+                if let Some(ts) = extract_shutdown_ts(&obj) {
+                    session_ends.push((session_id.clone(), ts));
+                }
                 let mut snapshots = extract_limit_snapshots_from_value(&obj, line, &task.file_path);
                 if !snapshots.is_empty() {
                     limit_snapshots.append(&mut snapshots);
@@ -247,15 +300,13 @@ fn parse_file(
     }
 
     let parse_duration = file_start.elapsed();
-    if timing_enabled {
-        eprintln!(
-            "ingest file: {} read={}ms db=0ms events={} bytes={}",
-            task.file_path,
-            parse_duration.as_millis(),
-            events.len(),
-            bytes_read
-        );
-    }
+    tracing::debug!(
+        file = %task.file_path,
+        read_ms = parse_duration.as_millis() as u64,
+        events = events.len(),
+        bytes = bytes_read,
+        "parsed log file"
+    );
 
     let last_event_key = events.last().map(|event| event.id.clone());
     ParsedFile {
@@ -266,24 +317,23 @@ fn parse_file(
         bytes_read,
         events,
         message_events,
+        session_ends,
         limit_snapshots,
         issues,
         last_model: current_model,
         last_effort: current_effort,
+        session_id: meta_session_id,
+        provider,
         last_event_key,
         skipped: false,
         parse_duration,
     }
 }
 
+#[tracing::instrument(name = "ingest", skip(db), fields(codex_home = %codex_home.display()))]
 pub fn ingest_codex_home(db: &mut Db, codex_home: &Path) -> Result<IngestStats> {
     let mut stats = IngestStats::default();
-    let pricing = std::sync::Arc::new(db.list_pricing_rules()?);
-    let has_pricing = !pricing.is_empty();
-    let timing_enabled = env::var("CODEX_TRACKER_INGEST_TIMING").is_ok();
     let ingest_start = Instant::now();
-    let mut parse_total = StdDuration::ZERO;
-    let mut db_total = StdDuration::ZERO;
     let codex_home_str = codex_home.to_string_lossy().to_string();
     let home = db.get_or_create_home(&codex_home_str, Some("Default"))?;
     db.update_home_last_seen(home.id)?;
@@ -292,7 +342,7 @@ pub fn ingest_codex_home(db: &mut Db, codex_home: &Path) -> Result<IngestStats>
         return Ok(stats);
     }
 
-    let mut tasks = Vec::new();
+    let mut paths = Vec::new();
     for entry in WalkDir::new(&sessions_dir).follow_links(false).into_iter() {
         let entry = match entry {
             Ok(entry) => entry,
@@ -308,10 +358,63 @@ pub fn ingest_codex_home(db: &mut Db, codex_home: &Path) -> Result<IngestStats>
                 continue;
             }
         };
-        let path = entry.path();
-        if !entry.file_type().is_file() || !is_log_path(path) {
-            continue;
+        if entry.file_type().is_file() && is_log_path(entry.path()) {
+            paths.push(entry.into_path());
         }
+    }
+    ingest_paths(db, home.id, &codex_home_str, paths, stats, ingest_start)
+}
+
+/// Ingests log files from anywhere on disk into the codex home at
+/// `codex_home`, as if they had been found under its `sessions` directory.
+/// Files are resumed from their cursors like any other log, so ingesting the
+/// same file twice only reads what was appended since. Paths without a log
+/// extension are ignored.
+#[tracing::instrument(
+    name = "ingest_files",
+    skip(db, files),
+    fields(codex_home = %codex_home.display(), files = files.len())
+)]
+pub fn ingest_log_files(db: &mut Db, codex_home: &Path, files: &[PathBuf]) -> Result<IngestStats> {
+    let ingest_start = Instant::now();
+    let codex_home_str = codex_home.to_string_lossy().to_string();
+    let home = db.get_or_create_home(&codex_home_str, Some("Default"))?;
+    db.update_home_last_seen(home.id)?;
+    let paths = files
+        .iter()
+        .filter(|path| is_log_path(path))
+        .cloned()
+        .collect();
+    ingest_paths(
+        db,
+        home.id,
+        &codex_home_str,
+        paths,
+        IngestStats::default(),
+        ingest_start,
+    )
+}
+
+fn ingest_paths(
+    db: &mut Db,
+    home_id: i64,
+    codex_home_str: &str,
+    paths: Vec<PathBuf>,
+    mut stats: IngestStats,
+    ingest_start: Instant,
+) -> Result<IngestStats> {
+    let pricing = std::sync::Arc::new(db.list_pricing_rules()?);
+    let has_pricing = !pricing.is_empty();
+    let aliases = db
+        .list_model_aliases()?
+        .into_iter()
+        .map(|alias| (alias.alias, alias.model))
+        .collect::<HashMap<_, _>>();
+    let mut parse_total = StdDuration::ZERO;
+    let mut db_total = StdDuration::ZERO;
+    let mut tasks = Vec::new();
+    for path in paths {
+        let path = path.as_path();
         stats.files_scanned += 1;
         let file_path = path.to_string_lossy().to_string();
         let metadata = match fs::metadata(path) {
@@ -331,25 +434,28 @@ pub fn ingest_codex_home(db: &mut Db, codex_home: &Path) -> Result<IngestStats>
             .modified()
             .ok()
             .map(|time| DateTime::<Utc>::from(time).to_rfc3339());
-        let cursor = db.get_cursor(home.id, &file_path)?;
+        let cursor = db.get_cursor(home_id, &file_path)?;
         let can_resume = matches!(
             cursor.as_ref(),
             Some(cursor) if cursor.byte_offset <= file_len && inode == cursor.inode
         );
-        let (start_offset, seed_model, seed_effort) = match cursor.as_ref() {
-            Some(cursor) if can_resume => (
-                cursor.byte_offset,
-                cursor.last_model.clone(),
-                cursor.last_effort.clone(),
-            ),
-            _ => (0, None, None),
-        };
+        let (start_offset, seed_model, seed_effort, seed_session_id, seed_provider) =
+            match cursor.as_ref() {
+                Some(cursor) if can_resume => (
+                    cursor.byte_offset,
+                    cursor.last_model.clone(),
+                    cursor.last_effort.clone(),
+                    cursor.session_id.clone(),
+                    cursor.provider.clone(),
+                ),
+                _ => (0, None, None, None, None),
+            };
         if start_offset >= file_len {
             stats.files_skipped += 1;
             continue;
         }
         let prev_usage = if can_resume {
-            db.last_usage_totals_for_source(home.id, &file_path)?
+            db.last_usage_totals_for_source(home_id, &file_path)?
         } else {
             None
         };
@@ -361,17 +467,20 @@ pub fn ingest_codex_home(db: &mut Db, codex_home: &Path) -> Result<IngestStats>
             start_offset,
             seed_model,
             seed_effort,
+            seed_session_id,
+            seed_provider,
             prev_usage,
         });
     }
 
     let parsed_files = tasks
         .into_par_iter()
-        .map(|task| parse_file(task, &pricing, has_pricing, timing_enabled))
+        .map(|task| parse_file(task, &pricing, &aliases, has_pricing))
         .collect::<Vec<_>>();
 
     let mut all_events = Vec::new();
     let mut all_message_events = Vec::new();
+    let mut all_session_ends = Vec::new();
     let mut all_limit_snapshots = Vec::new();
     let mut cursors = Vec::new();
     for parsed in parsed_files {
@@ -384,10 +493,11 @@ pub fn ingest_codex_home(db: &mut Db, codex_home: &Path) -> Result<IngestStats>
         }
         all_events.extend(parsed.events);
         all_message_events.extend(parsed.message_events);
+        all_session_ends.extend(parsed.session_ends);
         all_limit_snapshots.extend(parsed.limit_snapshots);
         cursors.push(IngestCursor {
-            codex_home_id: home.id,
-            codex_home: codex_home_str.clone(),
+            codex_home_id: home_id,
+            codex_home: codex_home_str.to_string(),
             file_path: parsed.file_path,
             inode: parsed.inode,
             mtime: parsed.mtime,
@@ -396,36 +506,41 @@ pub fn ingest_codex_home(db: &mut Db, codex_home: &Path) -> Result<IngestStats>
             updated_at: Utc::now().to_rfc3339(),
             last_model: parsed.last_model,
             last_effort: parsed.last_effort,
+            session_id: parsed.session_id,
+            provider: parsed.provider,
         });
     }
 
     let db_start = Instant::now();
     if !all_events.is_empty() {
-        stats.events_inserted = db.insert_usage_events(home.id, &all_events)?;
+        stats.events_inserted = db.insert_usage_events(home_id, &all_events)?;
     }
     if !all_message_events.is_empty() {
-        let _ = db.insert_message_events(home.id, &all_message_events)?;
+        stats.messages_inserted = db.insert_message_events(home_id, &all_message_events)?;
+    }
+    if !all_session_ends.is_empty() {
+        db.record_session_ends(home_id, &all_session_ends)?;
     }
     if !all_limit_snapshots.is_empty() {
-        let _ = db.insert_limit_snapshots(home.id, &all_limit_snapshots)?;
+        stats.limit_snapshots_inserted =
+            db.insert_limit_snapshots(home_id, &all_limit_snapshots)?;
     }
     for cursor in cursors {
         db.upsert_cursor(&cursor)?;
     }
     db_total += db_start.elapsed();
 
-    if timing_enabled {
-        eprintln!(
-            "ingest total: files={} scanned={} skipped={} events={} read={}ms db={}ms total={}ms",
-            stats.files_scanned + stats.files_skipped,
-            stats.files_scanned,
-            stats.files_skipped,
-            stats.events_inserted,
-            parse_total.as_millis(),
-            db_total.as_millis(),
-            ingest_start.elapsed().as_millis()
-        );
-    }
+    tracing::info!(
+        files = stats.files_scanned + stats.files_skipped,
+        scanned = stats.files_scanned,
+        skipped = stats.files_skipped,
+        events = stats.events_inserted,
+        issues = stats.issues.len(),
+        read_ms = parse_total.as_millis() as u64,
+        db_ms = db_total.as_millis() as u64,
+        total_ms = ingest_start.elapsed().as_millis() as u64,
+        "ingest finished"
+    );
     Ok(stats)
 }
 
@@ -10,16 +10,27 @@ pub struct TokenTotals {
 
 /// Ingest summary returned after scanning Codex logs.
 #[derive(Debug, Clone, Default, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct IngestStats {
     pub files_scanned: usize,
     pub files_skipped: usize,
     pub events_inserted: usize,
+    pub messages_inserted: usize,
+    pub limit_snapshots_inserted: usize,
     pub bytes_read: u64,
     pub issues: Vec<IngestIssue>,
 }
 
+impl IngestStats {
+    /// Usage events, messages, and limit snapshots stored by the run.
+    pub fn rows_inserted(&self) -> usize {
+        self.events_inserted + self.messages_inserted + self.limit_snapshots_inserted
+    }
+}
+
 /// Non-fatal issues encountered during ingest.
 #[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct IngestIssue {
     pub file_path: String,
     pub message: String,
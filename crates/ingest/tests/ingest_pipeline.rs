@@ -1,9 +1,9 @@
 use std::fs::{self, OpenOptions};
 use std::io::Write;
 
-use ingest::ingest_codex_home;
+use ingest::{ingest_codex_home, ingest_log_files};
 use tempfile::tempdir;
-use tracker_core::TimeRange;
+use tracker_core::{HomeScope, TimeRange};
 use tracker_db::Db;
 
 #[test]
@@ -55,6 +55,63 @@ fn ingest_resume_seeds_model_and_effort_from_cursor() {
     assert_eq!(events[0].reasoning_effort.as_deref(), Some("high"));
 }
 
+#[test]
+fn ingest_takes_session_id_and_provider_from_session_meta() {
+    let dir = tempdir().expect("temp dir");
+    let mut db = Db::open(dir.path().join("ingest.sqlite")).expect("open db");
+    db.migrate().expect("migrate db");
+
+    let log_dir = dir.path().join("sessions/2025/01/01");
+    fs::create_dir_all(&log_dir).expect("create log dir");
+    // A renamed log: the file name no longer carries the session id.
+    let log_path = log_dir.join("copy-of-session.jsonl");
+    let initial = r#"
+{"timestamp":"2025-01-01T00:00:00Z","type":"session_meta","payload":{"id":"0199a1b2-c3d4-7e5f-8a9b-0c1d2e3f4a5b","model_provider":"azure"}}
+{"timestamp":"2025-01-01T00:00:10Z","type":"event_msg","payload":{"type":"token_count","info":{"total_token_usage":{"input_tokens":1,"cached_input_tokens":0,"output_tokens":1,"reasoning_output_tokens":0,"total_tokens":2},"model_context_window":100}}}
+"#;
+    fs::write(&log_path, initial.trim()).expect("write log");
+    ingest_codex_home(&mut db, dir.path()).expect("ingest");
+
+    // Appended lines are read without the session_meta line; the cursor
+    // carries the id and provider over.
+    let appended = r#"
+{"timestamp":"2025-01-01T00:00:20Z","type":"event_msg","payload":{"type":"token_count","info":{"total_token_usage":{"input_tokens":2,"cached_input_tokens":0,"output_tokens":1,"reasoning_output_tokens":0,"total_tokens":3},"model_context_window":100}}}
+"#;
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(&log_path)
+        .expect("open log");
+    writeln!(file, "\n{}", appended.trim()).expect("append log");
+    let stats = ingest_codex_home(&mut db, dir.path()).expect("ingest again");
+    assert_eq!(stats.events_inserted, 1);
+
+    let home = db
+        .get_home_by_path(&dir.path().to_string_lossy())
+        .expect("home lookup")
+        .expect("home");
+    let cursor = db
+        .get_cursor(home.id, &log_path.to_string_lossy())
+        .expect("cursor lookup")
+        .expect("cursor");
+    assert_eq!(
+        cursor.session_id.as_deref(),
+        Some("0199a1b2-c3d4-7e5f-8a9b-0c1d2e3f4a5b")
+    );
+    assert_eq!(cursor.provider.as_deref(), Some("azure"));
+    let range = TimeRange {
+        start: "0000-01-01T00:00:00Z".to_string(),
+        end: "9999-12-31T23:59:59Z".to_string(),
+    };
+    let events = db
+        .list_usage_events(&range, None, 10, 0, home.id)
+        .expect("events");
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(
+        |event| event.session_id == "0199a1b2-c3d4-7e5f-8a9b-0c1d2e3f4a5b"
+            && event.provider.as_deref() == Some("azure")
+    ));
+}
+
 #[test]
 fn ingest_does_not_advance_cursor_on_invalid_utf8() {
     let dir = tempdir().expect("temp dir");
@@ -88,6 +145,54 @@ fn ingest_does_not_advance_cursor_on_invalid_utf8() {
     assert_eq!(cursor.byte_offset, expected_offset);
 }
 
+#[test]
+fn reset_cursor_makes_ingest_reread_the_file() {
+    let dir = tempdir().expect("temp dir");
+    let db_path = dir.path().join("ingest.sqlite");
+    let mut db = Db::open(&db_path).expect("open db");
+    db.migrate().expect("migrate db");
+
+    let log_dir = dir.path().join("sessions/2025/01/01");
+    fs::create_dir_all(&log_dir).expect("create log dir");
+    let log_path = log_dir.join("rollout-2025-01-01T00-00-00-1234.jsonl");
+    let line = r#"{"timestamp":"2025-01-01T00:00:10Z","type":"event_msg","payload":{"type":"token_count","info":{"model":"gpt-test","total_token_usage":{"input_tokens":1,"cached_input_tokens":0,"output_tokens":1,"reasoning_output_tokens":0,"total_tokens":2},"model_context_window":100}}}"#;
+    fs::write(&log_path, line).expect("write log");
+    let file_path = log_path.to_string_lossy().to_string();
+
+    let stats = ingest_codex_home(&mut db, dir.path()).expect("ingest");
+    assert_eq!(stats.events_inserted, 1);
+    let home = db
+        .get_home_by_path(&dir.path().to_string_lossy())
+        .expect("home lookup")
+        .expect("home");
+    // Stand-in for a manual edit: the rows go, the cursor stays.
+    db.prune_before("9999-01-01T00:00:00Z", HomeScope::Home(home.id))
+        .expect("prune");
+    let stats = ingest_codex_home(&mut db, dir.path()).expect("ingest again");
+    assert_eq!(stats.events_inserted, 0);
+
+    assert_eq!(
+        db.reset_cursors(home.id, Some("missing.jsonl"))
+            .expect("reset missing"),
+        0
+    );
+    assert_eq!(
+        db.reset_cursors(home.id, Some(&file_path)).expect("reset"),
+        1
+    );
+    assert!(
+        db.get_cursor(home.id, &file_path)
+            .expect("cursor")
+            .is_none()
+    );
+    let stats = ingest_codex_home(&mut db, dir.path()).expect("ingest after reset");
+    assert_eq!(stats.events_inserted, 1);
+    assert_eq!(db.count_usage_events(home.id).expect("count"), 1);
+
+    assert_eq!(db.reset_cursors(home.id, None).expect("reset home"), 1);
+    assert!(db.list_cursors(home.id).expect("cursors").is_empty());
+}
+
 #[test]
 fn ingest_skips_plain_log_files() {
     let dir = tempdir().expect("tempdir");
@@ -109,6 +214,36 @@ fn ingest_skips_plain_log_files() {
     assert!(stats.files_skipped >= 1);
 }
 
+#[test]
+fn ingest_log_files_reads_files_outside_the_home() {
+    let dir = tempdir().expect("tempdir");
+    let home_dir = dir.path().join("codex");
+    fs::create_dir_all(&home_dir).expect("create home");
+    let json_path = dir.path().join("rollout-2025-12-19T21-31-36.jsonl");
+    let other_path = dir.path().join("notes.txt");
+    let mut db = Db::open(dir.path().join("ingest.sqlite")).expect("open db");
+    db.migrate().expect("migrate");
+    fs::write(
+        &json_path,
+        r#"{"timestamp":"2025-12-19T21:31:36.168Z","type":"event_msg","payload":{"type":"token_count","info":{"model":"gpt-test","total_token_usage":{"input_tokens":10,"cached_input_tokens":0,"output_tokens":2,"reasoning_output_tokens":0,"total_tokens":12},"model_context_window":100}}}"#,
+    )
+    .expect("write json");
+    fs::write(&other_path, "not a log\n").expect("write notes");
+
+    let files = [json_path, other_path];
+    let stats = ingest_log_files(&mut db, &home_dir, &files).expect("ingest files");
+    assert_eq!(stats.files_scanned, 1);
+    assert_eq!(stats.events_inserted, 1);
+    let stats = ingest_log_files(&mut db, &home_dir, &files).expect("ingest again");
+    assert_eq!(stats.events_inserted, 0);
+
+    let home = db
+        .get_home_by_path(&home_dir.to_string_lossy())
+        .expect("home lookup")
+        .expect("home");
+    assert_eq!(db.count_usage_events(home.id).expect("count"), 1);
+}
+
 #[test]
 fn ingest_sets_cost_on_insert() {
     let dir = tempdir().expect("tempdir");
@@ -122,6 +257,8 @@ fn ingest_sets_cost_on_insert() {
         output_per_1m: 14000.0,
         effective_from: "2025-01-01T00:00:00Z".to_string(),
         effective_to: None,
+        provider: None,
+        fallback: false,
     }])
     .expect("pricing");
 
@@ -154,3 +291,20 @@ fn ingest_sets_cost_on_insert() {
     let expected_total = expected_input + expected_cached + expected_output;
     assert!((cost - expected_total).abs() < 1e-9);
 }
+
+#[test]
+fn ingest_counts_limit_snapshots_without_usage() {
+    let dir = tempdir().expect("temp dir");
+    let mut db = Db::open(dir.path().join("ingest.sqlite")).expect("open db");
+    db.migrate().expect("migrate db");
+
+    let log_dir = dir.path().join("sessions/2025/01/01");
+    fs::create_dir_all(&log_dir).expect("create log dir");
+    let line = r#"{"timestamp":"2025-01-01T00:00:00Z","type":"event_msg","payload":{"rate_limits":{"primary":{"used_percent":25,"resets_at":"2025-01-01T05:00:00Z"}}}}"#;
+    fs::write(log_dir.join("rollout-2025-01-01T00-00-00-1234.jsonl"), line).expect("write log");
+
+    let stats = ingest_codex_home(&mut db, dir.path()).expect("ingest");
+    assert_eq!(stats.events_inserted, 0);
+    assert_eq!(stats.limit_snapshots_inserted, 1);
+    assert_eq!(stats.rows_inserted(), 1);
+}
@@ -8,5 +8,5 @@ npm install
 npm run build
 
 cd "${root_dir}"
-# run with CODEX_TRACKER_INGEST_TIMING=1 for ingestion time logs
+# run with CODEX_TRACKER_LOG=debug for ingestion timings and request logs
 cargo run -p codex_tracker